@@ -0,0 +1,113 @@
+use core::ptr;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use js_sys::{Array, Function, Promise};
+
+use liblumen_alloc::erts::process::Status;
+use liblumen_alloc::erts::term::Atom;
+
+use liblumen_eir_interpreter::call_result::{call_erlang, ProcessResult, ProcessResultReceiver};
+use liblumen_eir_interpreter::VM;
+
+use lumen_web::js_value::{js_value_to_term, term_to_js_value};
+
+/// Calls `module:function(arguments...)` in a new process, the same as `call_run_erlang`, but
+/// without blocking the page, as `lumen_web::start` runs the process in slices.
+///
+/// `arguments` are converted with `lumen_web::js_value::js_value_to_term`.  The returned promise
+/// resolves to the return value converted with `lumen_web::js_value::term_to_js_value`, or rejects
+/// with `[class, reason]` if the call raises.  It also rejects if an argument can't be converted
+/// to a term, or the return value, class or reason has no JavaScript equivalent.
+///
+/// ```javascript
+/// Interpreter.call("lists", "reverse", [[1, 2, 3]]).then((reversed) => ...);
+/// ```
+#[wasm_bindgen]
+pub fn call(module: &str, function: &str, arguments: Box<[JsValue]>) -> Result<Promise, JsValue> {
+    let module_atom = Atom::try_from_str(module).map_err(|_| "module is not an atom")?;
+    let function_atom = Atom::try_from_str(function).map_err(|_| "function is not an atom")?;
+
+    let init_arc_process = VM.init.clone();
+    let mut argument_terms = Vec::with_capacity(arguments.len());
+
+    for argument in arguments.into_vec() {
+        match js_value_to_term(&init_arc_process, argument) {
+            Ok(argument_term) => argument_terms.push(argument_term),
+            Err(_) => return Ok(Promise::reject(&"argument could not be converted".into())),
+        }
+    }
+
+    let mut option_receiver = Some(call_erlang(
+        init_arc_process,
+        module_atom,
+        function_atom,
+        &argument_terms,
+    ));
+
+    Ok(Promise::new(&mut |resolve, reject| {
+        settle_when_returned(option_receiver.take().unwrap(), resolve, reject)
+    }))
+}
+
+// Private
+
+/// Rejection reason when the return value, or the class or reason of a raise, has no JavaScript
+/// equivalent, such as a closure or reference
+const NOT_CONVERTIBLE: &str = "result could not be converted";
+
+fn settle(process_result: ProcessResult, resolve: &Function, reject: &Function) {
+    match process_result.result {
+        Ok(return_term) => match term_to_js_value(return_term) {
+            Ok(return_js_value) => {
+                drop(resolve.call1(&JsValue::undefined(), &return_js_value));
+            }
+            Err(_) => drop(reject.call1(&JsValue::undefined(), &NOT_CONVERTIBLE.into())),
+        },
+        Err((class, reason, _stacktrace)) => {
+            match (term_to_js_value(class), term_to_js_value(reason)) {
+                (Ok(class_js_value), Ok(reason_js_value)) => {
+                    let array = Array::new();
+                    array.push(&class_js_value);
+                    array.push(&reason_js_value);
+
+                    drop(reject.call1(&JsValue::undefined(), &array));
+                }
+                _ => drop(reject.call1(&JsValue::undefined(), &NOT_CONVERTIBLE.into())),
+            }
+        }
+    }
+
+    // the converted `JsValue`s are copies, so they outlive the terms
+    unsafe { ptr::drop_in_place(process_result.heap.as_ptr()) };
+}
+
+/// Settles the promise once the process returns, checking again after the scheduler has run
+/// another slice if it hasn't yet.
+fn settle_when_returned(receiver: ProcessResultReceiver, resolve: Function, reject: Function) {
+    match receiver.try_get() {
+        Some(process_result) => settle(process_result, &resolve, &reject),
+        None => {
+            if let Status::Exiting(_) = *receiver.process.status.read() {
+                // the result may have been sent between checking for it and checking the status
+                match receiver.try_get() {
+                    Some(process_result) => settle(process_result, &resolve, &reject),
+                    // exited without returning or raising, such as when killed by a link
+                    None => drop(reject.call1(&JsValue::undefined(), &"exited".into())),
+                }
+
+                return;
+            }
+
+            // freed by JavaScript once called, instead of being leaked
+            let retry =
+                Closure::once_into_js(move || settle_when_returned(receiver, resolve, reject));
+
+            web_sys::window()
+                .unwrap()
+                .set_timeout_with_callback_and_timeout_and_arguments_0(retry.unchecked_ref(), 0)
+                .unwrap();
+        }
+    }
+}
@@ -3,6 +3,7 @@
 #![feature(allocator_api)]
 #![feature(type_ascription)]
 
+mod call;
 mod heap;
 mod module;
 mod start;
@@ -52,10 +53,22 @@ pub fn start() {
         .write()
         .unwrap()
         .register_native_module(module::make_lumen_web_element());
+    VM.modules
+        .write()
+        .unwrap()
+        .register_native_module(module::make_lumen_web_event_target());
+    VM.modules
+        .write()
+        .unwrap()
+        .register_native_module(module::make_lumen_web_fetch());
     VM.modules
         .write()
         .unwrap()
         .register_native_module(module::make_lumen_web_node());
+    VM.modules
+        .write()
+        .unwrap()
+        .register_native_module(module::make_websocket());
 
     system::io::puts("initialized");
 }
@@ -33,5 +33,13 @@ pub fn make_lumen_web_document() -> NativeModule {
         },
     );
 
+    native.add_simple(
+        Atom::try_from_str("query_selector").unwrap(),
+        2,
+        |proc, args| {
+            Ok(lumen_web::document::query_selector_2::native(proc, args[0], args[1]).unwrap())
+        },
+    );
+
     native
 }
@@ -0,0 +1,20 @@
+use liblumen_alloc::erts::term::Atom;
+
+use liblumen_eir_interpreter::NativeModule;
+
+pub fn make_lumen_web_event_target() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("Elixir.Lumen.Web.EventTarget").unwrap());
+
+    native.add_simple(
+        Atom::try_from_str("add_event_listener").unwrap(),
+        3,
+        |_proc, args| {
+            Ok(
+                lumen_web::event_target::add_event_listener_3::native(args[0], args[1], args[2])
+                    .unwrap(),
+            )
+        },
+    );
+
+    native
+}
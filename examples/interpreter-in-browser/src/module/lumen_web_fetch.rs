@@ -0,0 +1,13 @@
+use liblumen_alloc::erts::term::Atom;
+
+use liblumen_eir_interpreter::NativeModule;
+
+pub fn make_lumen_web_fetch() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("Elixir.Lumen.Web.Fetch").unwrap());
+
+    native.add_simple(Atom::try_from_str("request").unwrap(), 4, |proc, args| {
+        Ok(lumen_web::fetch::request_4::native(proc, args[0], args[1], args[2], args[3]).unwrap())
+    });
+
+    native
+}
@@ -4,8 +4,17 @@ pub use lumen_web_window::make_lumen_web_window;
 mod lumen_web_document;
 pub use lumen_web_document::make_lumen_web_document;
 
+mod lumen_web_event_target;
+pub use lumen_web_event_target::make_lumen_web_event_target;
+
+mod lumen_web_fetch;
+pub use lumen_web_fetch::make_lumen_web_fetch;
+
 mod lumen_web_element;
 pub use lumen_web_element::make_lumen_web_element;
 
 mod lumen_web_node;
 pub use lumen_web_node::make_lumen_web_node;
+
+mod websocket;
+pub use websocket::make_websocket;
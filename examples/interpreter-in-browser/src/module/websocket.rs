@@ -0,0 +1,21 @@
+use liblumen_alloc::erts::term::Atom;
+
+use liblumen_eir_interpreter::NativeModule;
+
+pub fn make_websocket() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("websocket").unwrap());
+
+    native.add_simple(Atom::try_from_str("close").unwrap(), 1, |_proc, args| {
+        Ok(lumen_web::websocket::close_1::native(args[0]).unwrap())
+    });
+
+    native.add_simple(Atom::try_from_str("connect").unwrap(), 1, |proc, args| {
+        Ok(lumen_web::websocket::connect_1::native(proc, args[0]).unwrap())
+    });
+
+    native.add_simple(Atom::try_from_str("send").unwrap(), 2, |proc, args| {
+        Ok(lumen_web::websocket::send_2::native(proc, args[0], args[1]).unwrap())
+    });
+
+    native
+}
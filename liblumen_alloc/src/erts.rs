@@ -1,5 +1,6 @@
 pub mod exception;
 mod fragment;
+mod literal;
 pub mod message;
 mod module_function_arity;
 mod node;
@@ -8,6 +9,7 @@ pub mod scheduler;
 pub mod term;
 
 pub use fragment::{HeapFragment, HeapFragmentAdapter};
+pub use literal::LiteralArea;
 pub use message::Message;
 pub use module_function_arity::ModuleFunctionArity;
 pub use node::*;
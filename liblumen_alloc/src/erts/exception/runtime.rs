@@ -4,7 +4,7 @@ use core::result::Result;
 
 use crate::erts::exception::system::Alloc;
 use crate::erts::process::Process;
-use crate::erts::term::atom::{AtomError, EncodingError};
+use crate::erts::term::atom::{AtomError, AtomErrorKind, EncodingError};
 use crate::erts::term::list::ImproperList;
 use crate::erts::term::{
     atom_unchecked, index, BoolError, Term, TryIntoIntegerError, TypeError, TypedTerm,
@@ -35,12 +35,22 @@ impl TryFrom<Term> for Class {
     }
 }
 
+/// Which argument made a BIF raise and why, as OTP reports in the `error_info` of the
+/// stacktrace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ErrorInfo {
+    /// The one-based position of the argument
+    pub argument: usize,
+    pub cause: &'static str,
+}
+
 #[derive(Debug)]
 #[cfg_attr(test, derive(Clone))]
 pub struct Exception {
     pub class: Class,
     pub reason: Term,
     pub stacktrace: Option<Term>,
+    pub error_info: Option<ErrorInfo>,
     pub file: &'static str,
     pub line: u32,
     pub column: u32,
@@ -51,6 +61,18 @@ impl Exception {
         Self::error(Self::badarg_reason(), None, None, file, line, column)
     }
 
+    pub fn badarg_with_error_info(
+        error_info: ErrorInfo,
+        file: &'static str,
+        line: u32,
+        column: u32,
+    ) -> Self {
+        Exception {
+            error_info: Some(error_info),
+            ..Self::badarg(file, line, column)
+        }
+    }
+
     pub fn badarith(file: &'static str, line: u32, column: u32) -> Self {
         Self::error(Self::badarith_reason(), None, None, file, line, column)
     }
@@ -185,6 +207,7 @@ impl Exception {
             class,
             reason,
             stacktrace,
+            error_info: None,
             file,
             line,
             column,
@@ -217,8 +240,13 @@ impl Exception {
 impl Eq for Exception {}
 
 impl From<AtomError> for Exception {
-    fn from(_: AtomError) -> Self {
-        badarg!()
+    fn from(atom_error: AtomError) -> Self {
+        match atom_error.kind() {
+            AtomErrorKind::TooManyAtoms | AtomErrorKind::InvalidLength(_) => {
+                error!(atom_unchecked("system_limit"))
+            }
+            _ => badarg!(),
+        }
     }
 }
 
@@ -266,7 +294,8 @@ impl From<TypeError> for Exception {
 
 impl PartialEq for Exception {
     /// `file`, `line`, and `column` don't count for equality as they are for `Debug` only to help
-    /// track down exceptions.
+    /// track down exceptions.  `error_info` doesn't count either, so that a `badarg` is equal
+    /// whether or not the BIF that raised it reports which argument was bad.
     fn eq(&self, other: &Exception) -> bool {
         (self.class == other.class)
             & (self.reason == other.reason)
@@ -289,6 +318,28 @@ impl TryFrom<super::Exception> for Exception {
 mod tests {
     use super::*;
 
+    mod badarg {
+        use super::*;
+
+        #[test]
+        fn with_error_info_stores_error_info() {
+            let badarg = badarg!(2, "out of range");
+
+            assert_eq!(
+                badarg.error_info,
+                Some(ErrorInfo {
+                    argument: 2,
+                    cause: "out of range"
+                })
+            );
+        }
+
+        #[test]
+        fn with_error_info_is_equal_to_without() {
+            assert_eq!(badarg!(1, "not a binary"), badarg!());
+        }
+    }
+
     mod error {
         use super::Class::*;
         use super::*;
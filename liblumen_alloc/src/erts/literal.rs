@@ -0,0 +1,187 @@
+//! Literal areas hold the constants of a module, such as the tuples, lists and binaries in its
+//! functions, so that processes reference them instead of copying them to their heaps.
+//!
+//! A literal, and every term in it, is flagged as literal (see `Term::is_literal`), so collections
+//! neither move it nor look inside it, and copying a term to another heap, such as for a message,
+//! shares the literals in it.  Because of that, literals have to outlive any process that could
+//! still reference them, so the fragments of an area are never freed, not even when its module is
+//! purged.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use intrusive_collections::{LinkedList, UnsafeRef};
+
+use liblumen_core::locks::SpinLock;
+
+use crate::borrow::CloneToProcess;
+use crate::erts::exception::system::Alloc;
+use crate::erts::term::{Term, TypedTerm};
+use crate::erts::{HeapFragment, HeapFragmentAdapter};
+
+pub struct LiteralArea {
+    fragments: SpinLock<LinkedList<HeapFragmentAdapter>>,
+    /// The size, in bytes, of `fragments`
+    size: AtomicUsize,
+}
+
+impl LiteralArea {
+    pub fn new() -> Self {
+        Self {
+            fragments: SpinLock::new(LinkedList::new(HeapFragmentAdapter::new())),
+            size: AtomicUsize::new(0),
+        }
+    }
+
+    /// Copies `term` into the area, returning the literal, or `None` if `term` can't be a literal
+    /// because it contains maps, sub-binaries or terms that belong to a process, whose pointers
+    /// can't all be flagged.
+    pub fn insert(&self, term: Term) -> Result<Option<Term>, Alloc> {
+        if term.is_immediate() || term.is_literal() {
+            Ok(Some(term))
+        } else if can_be_literal(term) {
+            let (fragment_term, mut non_null_heap_fragment) = term.clone_to_fragment()?;
+            let heap_fragment = unsafe { non_null_heap_fragment.as_mut() };
+            let literal = unsafe { flag_literal(fragment_term) };
+
+            self.size.fetch_add(heap_fragment.size(), Ordering::AcqRel);
+            self.fragments
+                .lock()
+                .push_back(unsafe { UnsafeRef::from_raw(heap_fragment as *mut HeapFragment) });
+
+            Ok(Some(literal))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// The size, in bytes, of the literals in the area
+    pub fn size(&self) -> usize {
+        self.size.load(Ordering::Acquire)
+    }
+}
+
+unsafe impl Send for LiteralArea {}
+unsafe impl Sync for LiteralArea {}
+
+// Private
+
+fn can_be_literal(mut term: Term) -> bool {
+    // lists are walked by their tails, so that long ones don't overflow the stack
+    loop {
+        if term.is_immediate() || term.is_literal() {
+            return true;
+        }
+
+        match term.to_typed_term().unwrap() {
+            TypedTerm::List(cons) => {
+                if !can_be_literal(cons.head) {
+                    return false;
+                }
+
+                term = cons.tail;
+            }
+            TypedTerm::Boxed(boxed) => {
+                return match boxed.to_typed_term().unwrap() {
+                    TypedTerm::Tuple(tuple) => tuple.iter().all(can_be_literal),
+                    TypedTerm::BigInteger(_)
+                    | TypedTerm::Float(_)
+                    | TypedTerm::HeapBinary(_)
+                    | TypedTerm::ProcBin(_) => true,
+                    _ => false,
+                };
+            }
+            _ => return false,
+        }
+    }
+}
+
+/// Flags `term`, which is in a fragment of the area, and the terms in it as literal
+unsafe fn flag_literal(term: Term) -> Term {
+    if term.is_literal() {
+        term
+    } else if term.is_boxed() {
+        let ptr = term.boxed_val();
+        let header = *ptr;
+
+        if header.is_tuple_header() {
+            for index in 1..=header.arityval() {
+                let element_ptr = ptr.add(index);
+                *element_ptr = flag_literal(*element_ptr);
+            }
+        }
+
+        Term::make_boxed_literal(ptr)
+    } else if term.is_non_empty_list() {
+        let mut cons_ptr = term.list_val();
+
+        loop {
+            let cons = &mut *cons_ptr;
+            cons.head = flag_literal(cons.head);
+
+            if cons.tail.is_non_empty_list() && !cons.tail.is_literal() {
+                let tail_ptr = cons.tail.list_val();
+                cons.tail = Term::make_list_literal(tail_ptr);
+                cons_ptr = tail_ptr;
+            } else {
+                cons.tail = flag_literal(cons.tail);
+
+                break;
+            }
+        }
+
+        Term::make_list_literal(term.list_val())
+    } else {
+        term
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::erts::term::atom_unchecked;
+    use crate::erts::HeapAlloc;
+
+    #[test]
+    fn insert_flags_nested_terms_as_literal() {
+        let mut non_null_heap_fragment = unsafe { HeapFragment::new_from_word_size(32).unwrap() };
+        let heap_fragment = unsafe { non_null_heap_fragment.as_mut() };
+        let list = heap_fragment
+            .list_from_slice(&[atom_unchecked("a"), heap_fragment.float(1.0).unwrap()])
+            .unwrap();
+        let term = heap_fragment
+            .tuple_from_slice(&[list, heap_fragment.binary_from_str("bin").unwrap()])
+            .unwrap();
+
+        let literal_area = LiteralArea::new();
+        let literal = literal_area.insert(term).unwrap().unwrap();
+
+        assert!(literal.is_literal());
+        assert!(literal.is_tuple());
+        assert_eq!(literal, term);
+        assert!(literal_area.size() > 0);
+
+        let element = unsafe { *literal.boxed_val().add(1) };
+
+        assert!(element.is_literal());
+        assert!(element.is_non_empty_list());
+
+        // literals are shared instead of copied
+        assert_eq!(literal.clone_to_heap(heap_fragment), Ok(literal));
+    }
+
+    #[test]
+    fn insert_does_not_take_maps() {
+        let mut non_null_heap_fragment = unsafe { HeapFragment::new_from_word_size(32).unwrap() };
+        let heap_fragment = unsafe { non_null_heap_fragment.as_mut() };
+        let map = heap_fragment
+            .map_from_slice(&[(atom_unchecked("key"), atom_unchecked("value"))])
+            .unwrap();
+        let term = heap_fragment.tuple_from_slice(&[map]).unwrap();
+
+        let literal_area = LiteralArea::new();
+
+        assert_eq!(literal_area.insert(term), Ok(None));
+        assert_eq!(literal_area.size(), 0);
+    }
+}
@@ -5,10 +5,13 @@ use intrusive_collections::UnsafeRef;
 
 #[derive(Debug)]
 pub enum Message {
-    /// A message whose `data` is allocated inside the receiving process's heap.
+    /// A message whose `data` is allocated inside the receiving process's heap, or its off-heap,
+    /// so that it is collected with them.
     Process(Process),
-    /// A message whose `message` `Term` had to be allocated in `heap` outside of the receiving
-    /// `Process` because the `Process`'s `Heap` was locked.
+    /// A message whose `data` was copied to a heap fragment by the sending process, so that the
+    /// sender didn't need the receiving process's heap.  With the `on_heap` `message_queue_data`,
+    /// the fragment is part of the off-heap of the receiving process, and the next collection
+    /// merges the message into the heap.
     HeapFragment(HeapFragment),
 }
 
@@ -1,10 +1,103 @@
-#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+use core::cmp;
+use core::fmt::{self, Display};
+use core::hash::{Hash, Hasher};
+
+use alloc::format;
+
+use hashbrown::HashMap;
+use lazy_static::lazy_static;
+
+use liblumen_core::locks::RwLock;
+
+use crate::erts::term::Atom;
+
+/// The node that an external pid, port or reference came from.
+///
+/// Like the node table in ERTS, each name and creation is given an `id` the first time it is
+/// seen, which is only used to print external identifiers.  Nodes are equal, hash and order by
+/// their name and then their creation, so that an identifier from a node that restarted with the
+/// same name is not equal to one from before the restart.
+#[derive(Debug, Clone, Copy)]
 pub struct Node {
     id: usize,
+    name: Atom,
+    creation: u32,
 }
 
 impl Node {
-    pub(in crate::erts) fn new(id: usize) -> Self {
-        Self { id }
+    /// The node named `name` with `creation`, which is added to the node table if it is new.
+    pub fn new(name: Atom, creation: u32) -> Self {
+        if let Some(node) = RW_LOCK_NODES.read().get(&(name, creation)) {
+            return *node;
+        }
+
+        let mut writable_nodes = RW_LOCK_NODES.write();
+        let id = writable_nodes.len() + 1;
+
+        *writable_nodes
+            .entry((name, creation))
+            .or_insert_with(|| Node { id, name, creation })
+    }
+
+    /// A node named after `id`, for constructing external identifiers in tests without a real
+    /// node for them to come from.
+    pub(in crate::erts) fn with_id(id: usize) -> Self {
+        let name = Atom::try_from_str(format!("node{}@nohost", id)).unwrap();
+
+        Self::new(name, 0)
+    }
+
+    /// The index of the node in the node table.  `0` is the local node, so the first other node
+    /// is `1`.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn name(&self) -> Atom {
+        self.name
+    }
+
+    /// Distinguishes incarnations of a node with the same name
+    pub fn creation(&self) -> u32 {
+        self.creation
+    }
+}
+
+impl Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name.name())
+    }
+}
+
+impl Eq for Node {}
+
+impl Hash for Node {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.creation.hash(state);
+    }
+}
+
+impl Ord for Node {
+    fn cmp(&self, other: &Node) -> cmp::Ordering {
+        self.name
+            .cmp(&other.name)
+            .then_with(|| self.creation.cmp(&other.creation))
     }
 }
+
+impl PartialEq for Node {
+    fn eq(&self, other: &Node) -> bool {
+        self.name == other.name && self.creation == other.creation
+    }
+}
+
+impl PartialOrd for Node {
+    fn partial_cmp(&self, other: &Node) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+lazy_static! {
+    static ref RW_LOCK_NODES: RwLock<HashMap<(Atom, u32), Node>> = Default::default();
+}
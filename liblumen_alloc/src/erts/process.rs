@@ -1,11 +1,17 @@
 pub mod alloc;
 pub mod code;
 mod flags;
+pub mod flight_recorder;
 mod gc;
 mod heap;
+mod heap_sizing;
 mod mailbox;
+mod max_heap_size;
+mod message_queue_data;
 mod monitor;
 mod priority;
+pub mod signal;
+pub mod trace;
 
 use core::alloc::Layout;
 use core::any::Any;
@@ -29,7 +35,9 @@ use crate::borrow::CloneToProcess;
 use crate::erts::exception::runtime;
 use crate::erts::exception::system::Alloc;
 use crate::erts::process::alloc::layout_to_words;
-use crate::erts::term::{atom_unchecked, pid, reference, Atom, Integer, Pid, ProcBin, Reference};
+use crate::erts::term::{
+    atom_unchecked, pid, reference, Atom, Integer, MatchBuffer, Pid, ProcBin, Reference, Tuple,
+};
 
 use super::*;
 
@@ -41,11 +49,17 @@ use self::code::stack;
 use self::code::stack::frame::{Frame, Placement};
 pub use self::flags::*;
 pub use self::flags::*;
+use self::flight_recorder::{Direction, FlightRecorder};
 pub use self::gc::{GcError, RootSet};
 use self::heap::ProcessHeap;
+pub use self::heap_sizing::{heap_sizing, set_heap_sizing, HeapSizing};
 pub use self::mailbox::*;
-pub use self::monitor::Monitor;
+pub use self::max_heap_size::MaxHeapSize;
+pub use self::message_queue_data::MessageQueueData;
+pub use self::monitor::{Monitor, MonitorTable, Tag};
 pub use self::priority::Priority;
+use self::signal::Exit;
+use self::trace::{Event, Trace, Tracer};
 use crate::erts::process::alloc::heap_alloc::MakePidError;
 use crate::erts::process::code::Code;
 use crate::erts::term::BytesFromBinaryError;
@@ -75,12 +89,21 @@ pub struct Process {
     pub priority: Priority,
     /// Process flags, e.g. `Process.flag/1`
     flags: AtomicProcessFlags,
-    /// Minimum size of the heap that this process will start with
-    min_heap_size: usize,
-    /// The maximum size of the heap allowed for this process
-    max_heap_size: usize,
-    /// Minimum virtual heap size for this process
-    min_vheap_size: usize,
+    /// The size, in words, below which collections don't shrink the heap, which is the size the
+    /// process started with unless it is set with `process_flag(min_heap_size, MinHeapSize)`
+    min_heap_size: AtomicUsize,
+    /// The maximum size of the heap allowed for this process, and what happens when it is exceeded.
+    /// Set with `process_flag(max_heap_size, MaxHeapSize)`.
+    max_heap_size: Mutex<MaxHeapSize>,
+    /// The sizes the heap grows through and when collections grow or shrink it, which is the
+    /// VM's heap sizing unless it is set with `process_flag(heap_sizing, HeapSizing)`.
+    heap_sizing: Mutex<HeapSizing>,
+    /// Whether messages from other processes are copied to the heap or kept in heap fragments
+    /// until they are received.  Set with `process_flag(message_queue_data, MessageQueueData)`.
+    message_queue_data: Mutex<MessageQueueData>,
+    /// The size, in words, below which the virtual heap of binaries isn't shrunk.  Set with
+    /// `process_flag(min_bin_vheap_size, MinBinVHeapSize)`.
+    min_vheap_size: AtomicUsize,
     /// The percentage of used to unused space at which a collection is triggered
     gc_threshold: f64,
     /// The maximum number of minor collections before a full sweep occurs
@@ -90,15 +113,31 @@ pub struct Process {
     off_heap_size: AtomicUsize,
     /// Process dictionary
     dictionary: Mutex<HashMap<Term, Term>>,
+    /// The module whose `undefined_function/3` is called when this process calls a function that
+    /// is not loaded.  Set with `process_flag(error_handler, Module)`.
+    error_handler: Mutex<Atom>,
+    /// The version of each module that the process last executed, as numbered by the code loader,
+    /// so that the process can be found when it is still executing old code.
+    code_versions: Mutex<HashMap<Atom, usize>>,
     /// The `pid` of the process that `spawn`ed this process.
     parent_pid: Option<Pid>,
     pid: Pid,
+    /// The process that io requests to `standard_io` are sent to.  Inherited from the parent, and
+    /// the process itself for processes without one, such as `init`.
+    group_leader_pid: Mutex<Pid>,
     pub initial_module_function_arity: Arc<ModuleFunctionArity>,
+    /// The monotonic time, in milliseconds, that the process was spawned at, as set by the
+    /// runtime, which owns the clock
+    creation_monotonic_time_milliseconds: AtomicU64,
     /// The number of reductions in the current `run`.  `code` MUST return when `run_reductions`
     /// exceeds `MAX_REDUCTIONS_PER_RUN`.
     run_reductions: AtomicU16,
     pub total_reductions: AtomicU64,
     code_stack: Mutex<code::stack::Stack>,
+    /// When more than one is held, `status` is locked before `heap`, which is locked before
+    /// `mailbox`, as a collection of a process that isn't running holds all three.  Only the
+    /// running process itself, which nothing else collects, copies messages from its locked
+    /// mailbox to its heap.
     pub status: RwLock<Status>,
     pub registered_name: RwLock<Option<Atom>>,
     /// Pids of processes that are linked to this process and need to be exited when this process
@@ -106,10 +145,18 @@ pub struct Process {
     pub linked_pid_set: Mutex<HashSet<Pid>>,
     /// Maps monitor references to the PID of the process that is monitoring through that
     /// reference.
-    pub monitor_by_reference: Mutex<HashMap<Reference, Monitor>>,
+    pub monitor_by_reference: Mutex<MonitorTable<Monitor>>,
     /// Maps monitor references to the PID of the process being monitored by this process.
-    pub monitored_pid_by_reference: Mutex<HashMap<Reference, Pid>>,
+    pub monitored_pid_by_reference: Mutex<MonitorTable<Pid>>,
     pub mailbox: Mutex<RefCell<Mailbox>>,
+    /// The number of times each process has suspended this process with
+    /// `erlang:suspend_process/1,2` without resuming it with `erlang:resume_process/1`.  The
+    /// process isn't run while any count remains.
+    suspend_count_by_pid: Mutex<HashMap<Pid, usize>>,
+    /// The tracer and the events it is sent trace messages about.  Set with `erlang:trace/3`.
+    trace: Mutex<Option<Trace>>,
+    /// The last messages sent and received, when enabled with `set_flight_recorder_capacity`
+    flight_recorder: Mutex<Option<FlightRecorder>>,
     // process heap, cache line aligned to avoid false sharing with rest of struct
     heap: Mutex<ProcessHeap>,
 }
@@ -129,23 +176,32 @@ impl Process {
 
         Self {
             flags: AtomicProcessFlags::new(ProcessFlags::Default),
-            min_heap_size: heap_size,
-            max_heap_size: 0,
-            min_vheap_size: 0,
+            min_heap_size: AtomicUsize::new(heap_size),
+            max_heap_size: Default::default(),
+            heap_sizing: Mutex::new(heap_sizing()),
+            message_queue_data: Default::default(),
+            min_vheap_size: AtomicUsize::new(0),
             gc_threshold: 0.75,
             max_gen_gcs: 65535,
             off_heap,
             off_heap_size: AtomicUsize::new(0),
             dictionary: Default::default(),
+            error_handler: Mutex::new(Atom::try_from_str("error_handler").unwrap()),
+            code_versions: Default::default(),
             pid,
+            group_leader_pid: Mutex::new(pid),
             status: Default::default(),
             mailbox: Default::default(),
+            suspend_count_by_pid: Default::default(),
+            trace: Mutex::new(trace::new_process_trace()),
+            flight_recorder: Default::default(),
             heap: Mutex::new(heap),
             code_stack: Default::default(),
             scheduler_id: Mutex::new(None),
             priority,
             parent_pid,
             initial_module_function_arity,
+            creation_monotonic_time_milliseconds: Default::default(),
             run_reductions: Default::default(),
             total_reductions: Default::default(),
             registered_name: Default::default(),
@@ -199,6 +255,127 @@ impl Process {
         self.are_flags_set(ProcessFlags::TrapExit)
     }
 
+    pub fn error_handler(&self) -> Atom {
+        *self.error_handler.lock()
+    }
+
+    /// Sets the module that handles calls to undefined functions, returning the previous module.
+    pub fn set_error_handler(&self, module: Atom) -> Atom {
+        mem::replace(&mut *self.error_handler.lock(), module)
+    }
+
+    pub fn max_heap_size(&self) -> MaxHeapSize {
+        *self.max_heap_size.lock()
+    }
+
+    /// Sets the maximum size of the heap, which is checked when the process is collected,
+    /// returning the previous maximum.
+    pub fn set_max_heap_size(&self, max_heap_size: MaxHeapSize) -> MaxHeapSize {
+        mem::replace(&mut *self.max_heap_size.lock(), max_heap_size)
+    }
+
+    pub fn heap_sizing(&self) -> HeapSizing {
+        *self.heap_sizing.lock()
+    }
+
+    /// Sets how collections size the heap, returning the previous heap sizing.  The heap keeps its
+    /// size until the process is next collected.
+    pub fn set_heap_sizing(&self, heap_sizing: HeapSizing) -> HeapSizing {
+        mem::replace(&mut *self.heap_sizing.lock(), heap_sizing)
+    }
+
+    pub fn min_heap_size(&self) -> usize {
+        self.min_heap_size.load(Ordering::Acquire)
+    }
+
+    /// Sets the size, in words, below which collections don't shrink the heap, returning the
+    /// previous minimum.  The heap is only grown to it when the process is next collected.
+    pub fn set_min_heap_size(&self, min_heap_size: usize) -> usize {
+        self.min_heap_size.swap(min_heap_size, Ordering::AcqRel)
+    }
+
+    pub fn min_bin_vheap_size(&self) -> usize {
+        self.min_vheap_size.load(Ordering::Acquire)
+    }
+
+    /// Sets the size, in words, below which the virtual heap of binaries isn't shrunk, returning
+    /// the previous minimum.  The virtual heap is grown to it right away, so that a process that
+    /// references many large binaries isn't collected as often.
+    pub fn set_min_bin_vheap_size(&self, min_bin_vheap_size: usize) -> usize {
+        let old_min_bin_vheap_size = self
+            .min_vheap_size
+            .swap(min_bin_vheap_size, Ordering::AcqRel);
+        self.heap
+            .lock()
+            .young
+            .set_min_virtual_heap_size(min_bin_vheap_size);
+
+        old_min_bin_vheap_size
+    }
+
+    pub fn message_queue_data(&self) -> MessageQueueData {
+        *self.message_queue_data.lock()
+    }
+
+    /// Sets where messages from other processes are stored until they are received, returning the
+    /// previous setting.  Messages already in the mailbox stay where they are.
+    pub fn set_message_queue_data(&self, message_queue_data: MessageQueueData) -> MessageQueueData {
+        mem::replace(&mut *self.message_queue_data.lock(), message_queue_data)
+    }
+
+    pub fn group_leader_pid(&self) -> Pid {
+        *self.group_leader_pid.lock()
+    }
+
+    pub fn set_group_leader_pid(&self, group_leader_pid: Pid) {
+        *self.group_leader_pid.lock() = group_leader_pid;
+    }
+
+    // Flight Recorder
+
+    /// The number of messages the flight recorder keeps, or `0` when it is disabled.
+    pub fn flight_recorder_capacity(&self) -> usize {
+        self.flight_recorder
+            .lock()
+            .as_ref()
+            .map_or(0, |flight_recorder| flight_recorder.capacity())
+    }
+
+    /// Sets the number of messages the flight recorder keeps, disabling it when `0`, and returns
+    /// the previous capacity.
+    pub fn set_flight_recorder_capacity(&self, capacity: usize) -> usize {
+        let mut flight_recorder = self.flight_recorder.lock();
+
+        match (flight_recorder.as_mut(), capacity) {
+            (Some(_), 0) => flight_recorder.take().unwrap().capacity(),
+            (Some(enabled), _) => enabled.resize(capacity),
+            (None, 0) => 0,
+            (None, _) => {
+                *flight_recorder = Some(FlightRecorder::new(capacity));
+
+                0
+            }
+        }
+    }
+
+    /// The recorded messages, oldest first, one per line, if the flight recorder is enabled
+    pub fn flight_recorder_dump(&self) -> Option<String> {
+        self.flight_recorder
+            .lock()
+            .as_ref()
+            .map(|flight_recorder| flight_recorder.to_string())
+    }
+
+    /// Records that this process sent `message` to `destination`, if the flight recorder is
+    /// enabled.
+    pub fn record_sent(&self, destination: Term, message: Term) {
+        if let Some(flight_recorder) = self.flight_recorder.lock().as_mut() {
+            let destination = destination.to_string();
+
+            flight_recorder.record(Direction::Sent { destination }, message);
+        }
+    }
+
     // Alloc
 
     /// Acquires exclusive access to the process heap, blocking the current thread until it is able
@@ -328,20 +505,45 @@ impl Process {
 
     // Links
 
-    pub fn link(&self, other: &Process) {
+    /// Returns `false` without linking if either process is exiting, as its links may already have
+    /// been sent their exit signals.
+    pub fn link(&self, other: &Process) -> bool {
+        let linked = self.link_in_order(other);
+
+        if linked {
+            self.trace_event(Event::Link, &[other.pid_term()]);
+        }
+
+        linked
+    }
+
+    pub fn unlink(&self, other: &Process) {
+        self.unlink_in_order(other);
+        self.trace_event(Event::Unlink, &[other.pid_term()]);
+    }
+
+    fn link_in_order(&self, other: &Process) -> bool {
         // link in order so that locks are always taken in the same order to prevent deadlocks
         if self.pid < other.pid {
             let mut self_pid_set = self.linked_pid_set.lock();
             let mut other_pid_set = other.linked_pid_set.lock();
 
-            self_pid_set.insert(other.pid);
-            other_pid_set.insert(self.pid);
+            // checked while the sets are locked, so an exit is either propagated to this link or
+            // seen here
+            if self.is_exiting() || other.is_exiting() {
+                false
+            } else {
+                self_pid_set.insert(other.pid);
+                other_pid_set.insert(self.pid);
+
+                true
+            }
         } else {
-            other.link(self)
+            other.link_in_order(self)
         }
     }
 
-    pub fn unlink(&self, other: &Process) {
+    fn unlink_in_order(&self, other: &Process) {
         // unlink in order so that locks are always taken in the same order to prevent deadlocks
         if self.pid < other.pid {
             let mut self_pid_set = self.linked_pid_set.lock();
@@ -350,7 +552,7 @@ impl Process {
             self_pid_set.remove(&other.pid);
             other_pid_set.remove(&self.pid);
         } else {
-            other.unlink(self)
+            other.unlink_in_order(self)
         }
     }
 
@@ -366,8 +568,20 @@ impl Process {
         self.monitored_pid_by_reference.lock().remove(reference)
     }
 
-    pub fn monitored(&self, reference: Reference, monitor: Monitor) {
-        self.monitor_by_reference.lock().insert(reference, monitor);
+    /// Returns `false` without monitoring if this process is exiting, as its `DOWN` messages may
+    /// already have been sent.
+    pub fn monitored(&self, reference: Reference, monitor: Monitor) -> bool {
+        let mut monitor_by_reference = self.monitor_by_reference.lock();
+
+        // checked while the monitors are locked, so an exit is either propagated to this monitor
+        // or seen here
+        if self.is_exiting() {
+            false
+        } else {
+            monitor_by_reference.insert(reference, monitor);
+
+            true
+        }
     }
 
     pub fn demonitored(&self, reference: &Reference) -> Option<Pid> {
@@ -377,6 +591,41 @@ impl Process {
             .map(|monitor| *monitor.monitoring_pid())
     }
 
+    // Code versions
+
+    /// Records that the process is executing `version` of `module`.
+    pub fn execute_code_version(&self, module: Atom, version: usize) {
+        let mut code_versions = self.code_versions.lock();
+
+        if code_versions.get(&module) != Some(&version) {
+            code_versions.insert(module, version);
+        }
+    }
+
+    /// The version of `module` that the process last executed, unless it never executed `module`
+    /// or has exited.
+    pub fn code_version(&self, module: Atom) -> Option<usize> {
+        self.code_versions.lock().get(&module).cloned()
+    }
+
+    /// Forgets the versions of modules the process executed, as it no longer executes any code
+    /// once it exits.
+    pub fn clear_code_versions(&self) {
+        self.code_versions.lock().clear();
+    }
+
+    // Creation
+
+    pub fn creation_monotonic_time_milliseconds(&self) -> u64 {
+        self.creation_monotonic_time_milliseconds
+            .load(Ordering::Relaxed)
+    }
+
+    pub fn set_creation_monotonic_time_milliseconds(&self, milliseconds: u64) {
+        self.creation_monotonic_time_milliseconds
+            .store(milliseconds, Ordering::Relaxed);
+    }
+
     // Pid
 
     pub fn pid(&self) -> Pid {
@@ -389,64 +638,136 @@ impl Process {
 
     // Send
 
+    /// Sends `data`, which is in `heap_fragment`.
     pub fn send_heap_message(&self, heap_fragment: NonNull<HeapFragment>, data: Term) {
+        let message = self.heap_fragment_message(heap_fragment, data);
+
+        self.send_message(message);
+    }
+
+    /// Delivers `exit`, in order with the messages sent to this process (see the `signal` module).
+    ///
+    /// Nothing happens if the process is already exiting, so the reason it exits with is the first
+    /// one.  A process that traps exits gets `{'EXIT', From, Reason}`, unless `exit` is
+    /// untrappable, and any other process exits with the reason, unless it is `normal`.
+    ///
+    /// Returns `true` if the process should stop waiting and be rescheduled, to receive the message
+    /// or to exit.
+    pub fn send_exit(&self, exit: Exit) -> Result<bool, Alloc> {
+        if self.is_exiting() {
+            Ok(false)
+        } else if exit.trappable && self.traps_exit() {
+            let need_in_words = Tuple::need_in_words_from_len(3)
+                + exit.from.size_in_words()
+                + exit.reason.size_in_words();
+            let mut non_null_heap_fragment =
+                unsafe { HeapFragment::new_from_word_size(need_in_words)? };
+            let heap_fragment = unsafe { non_null_heap_fragment.as_mut() };
+
+            let from = exit.from.clone_to_heap(heap_fragment)?;
+            let reason = exit.reason.clone_to_heap(heap_fragment)?;
+            let data = heap_fragment.tuple_from_slice(&[atom_unchecked("EXIT"), from, reason])?;
+
+            self.send_heap_message(non_null_heap_fragment, data);
+
+            Ok(self.stop_waiting())
+        } else if exit.trappable && exit.reason == atom_unchecked("normal") {
+            Ok(false)
+        } else {
+            // the status stays locked, so that only the first of two exits at the same time sets
+            // the reason
+            let mut writable_status = self.status.write();
+
+            if let Status::Exiting(_) = *writable_status {
+                return Ok(false);
+            }
+
+            let reason = if exit.reason.is_boxed() || exit.reason.is_non_empty_list() {
+                let (heap_fragment_reason, mut non_null_heap_fragment) =
+                    exit.reason.clone_to_fragment()?;
+                self.attach_fragment(unsafe { non_null_heap_fragment.as_mut() });
+
+                heap_fragment_reason
+            } else {
+                exit.reason
+            };
+
+            *writable_status = Status::Exiting(exit!(reason));
+
+            Ok(true)
+        }
+    }
+
+    /// `data`, which is in `heap_fragment`, as a message.
+    ///
+    /// When the `message_queue_data` is `OnHeap`, `heap_fragment` is part of the off-heap of this
+    /// process, so the next collection merges the message into the heap if it is still queued and
+    /// frees the fragment; otherwise it is owned by the message until the message is removed from
+    /// the mailbox.
+    fn heap_fragment_message(&self, heap_fragment: NonNull<HeapFragment>, data: Term) -> Message {
         let heap_fragment_ptr = heap_fragment.as_ptr();
 
-        let off_heap_unsafe_ref_heap_fragment = unsafe { UnsafeRef::from_raw(heap_fragment_ptr) };
-        self.off_heap
-            .lock()
-            .push_back(off_heap_unsafe_ref_heap_fragment);
+        if self.message_queue_data() == MessageQueueData::OnHeap {
+            self.attach_fragment(unsafe { &mut *heap_fragment_ptr });
+        }
 
         let message_unsafe_ref_heap_fragment = unsafe { UnsafeRef::from_raw(heap_fragment_ptr) };
 
-        self.send_message(Message::HeapFragment(message::HeapFragment {
+        Message::HeapFragment(message::HeapFragment {
             unsafe_ref_heap_fragment: message_unsafe_ref_heap_fragment,
             data,
-        }));
+        })
     }
 
     pub fn send_from_self(&self, data: Term) {
         self.send_message(Message::Process(message::Process { data }));
     }
 
+    /// Sends `data` from another process, copying it to a heap fragment instead of the heap of
+    /// this process, so that the sender never has to lock the heap of a process that may be
+    /// running or collecting (see `heap_fragment_message`).
+    ///
     /// Returns `true` if the process should stop waiting and be rescheduled as runnable.
     pub fn send_from_other(&self, data: Term) -> Result<bool, Alloc> {
-        match self.heap.try_lock() {
-            Some(ref mut destination_heap) => match data.clone_to_heap(destination_heap) {
-                Ok(destination_data) => {
-                    self.send_message(Message::Process(message::Process {
-                        data: destination_data,
-                    }));
-                }
-                Err(_) => {
-                    let (heap_fragment_data, heap_fragment) = data.clone_to_fragment()?;
-
-                    self.send_heap_message(heap_fragment, heap_fragment_data);
-                }
-            },
-            None => {
-                let (heap_fragment_data, heap_fragment) = data.clone_to_fragment()?;
-
-                self.send_heap_message(heap_fragment, heap_fragment_data);
-            }
-        }
+        let (heap_fragment_data, heap_fragment) = data.clone_to_fragment()?;
 
-        // status.write() scope
-        {
-            let mut writable_status = self.status.write();
+        self.send_heap_message(heap_fragment, heap_fragment_data);
 
-            if *writable_status == Status::Waiting {
-                *writable_status = Status::Runnable;
+        Ok(self.stop_waiting())
+    }
 
-                Ok(true)
-            } else {
-                Ok(false)
-            }
+    /// Signals, both messages and trapped exits, are put in the mailbox while it is locked, so that
+    /// they are delivered in the order they are sent.  The status isn't locked until the mailbox is
+    /// unlocked (see `status`).
+    fn send_message(&self, message: Message) {
+        let data = *message.data();
+
+        self.mailbox.lock().borrow_mut().push(message);
+
+        self.received(data);
+    }
+
+    /// Records and traces `data` arriving in the mailbox.  Called after the mailbox is unlocked, so
+    /// that a tracer sending to this process doesn't deadlock.
+    fn received(&self, data: Term) {
+        if let Some(flight_recorder) = self.flight_recorder.lock().as_mut() {
+            flight_recorder.record(Direction::Received, data);
         }
+
+        self.trace_event(Event::Receive, &[data]);
     }
 
-    fn send_message(&self, message: Message) {
-        self.mailbox.lock().borrow_mut().push(message)
+    /// Makes the process runnable if it is waiting.  Returns `true` if it was waiting.
+    fn stop_waiting(&self) -> bool {
+        let mut writable_status = self.status.write();
+
+        if *writable_status == Status::Waiting {
+            *writable_status = Status::Runnable;
+
+            true
+        } else {
+            false
+        }
     }
 
     // Terms
@@ -491,6 +812,29 @@ impl Process {
         self.acquire_heap().cons(head, tail)
     }
 
+    pub fn external_pid(
+        &self,
+        node: Node,
+        number: usize,
+        serial: usize,
+    ) -> Result<Term, MakePidError> {
+        self.acquire_heap().external_pid(node, number, serial)
+    }
+
+    pub fn external_port(&self, node: Node, number: usize) -> Result<Term, Alloc> {
+        self.acquire_heap().external_port(node, number)
+    }
+
+    pub fn external_reference(
+        &self,
+        node: Node,
+        scheduler_id: scheduler::ID,
+        number: reference::Number,
+    ) -> Result<Term, Alloc> {
+        self.acquire_heap()
+            .external_reference(node, scheduler_id, number)
+    }
+
     pub fn external_pid_with_node_id(
         &self,
         node_id: usize,
@@ -543,6 +887,10 @@ impl Process {
         self.acquire_heap().map_from_slice(slice)
     }
 
+    pub fn match_context_from_buffer(&self, buffer: MatchBuffer) -> Result<Term, Alloc> {
+        self.acquire_heap().match_context_from_buffer(buffer)
+    }
+
     pub fn pid_with_node_id(
         &self,
         node_id: usize,
@@ -731,6 +1079,31 @@ impl Process {
         heap.garbage_collect(self, need, rootset)
     }
 
+    /// Shrinks the heap to the smallest size that fits the live data in the provided root set,
+    /// even if that is below the minimum heap size, as `erlang:hibernate/3` does before the
+    /// process waits for a message.
+    ///
+    /// A full sweep sizes its new heap for everything on the old one, garbage included, so the
+    /// first sweep discards the garbage, and the second copies only the live data to a heap sized
+    /// for it.
+    pub fn shrink_heap(&self, roots: &mut [Term]) -> Result<usize, GcError> {
+        self.set_flags(ProcessFlags::NeedFullSweep);
+        let reductions = self.garbage_collect(0, roots)?;
+
+        self.set_flags(ProcessFlags::NeedFullSweep | ProcessFlags::ShrinkHeap);
+        let shrink_reductions = self.garbage_collect(0, roots)?;
+
+        Ok(reductions + shrink_reductions)
+    }
+
+    /// The size, in words, of the heap, including the old generation and the stack, as compared to
+    /// the max heap size
+    pub fn heap_size(&self) -> usize {
+        let heap = self.heap.lock();
+
+        heap.young.size() + heap.old.size()
+    }
+
     /// Returns true if the given pointer belongs to memory owned by this process
     #[inline]
     pub fn is_owner<T>(&self, ptr: *const T) -> bool {
@@ -746,6 +1119,125 @@ impl Process {
         false
     }
 
+    // Suspending
+
+    /// Whether any process has suspended this process without resuming it, so that its scheduler
+    /// doesn't run it.
+    pub fn is_suspended(&self) -> bool {
+        !self.suspend_count_by_pid.lock().is_empty()
+    }
+
+    /// The number of times `suspender` has suspended this process without resuming it.
+    pub fn suspend_count(&self, suspender: Pid) -> usize {
+        self.suspend_count_by_pid
+            .lock()
+            .get(&suspender)
+            .cloned()
+            .unwrap_or(0)
+    }
+
+    /// Suspends this process on behalf of `suspender`, returning the number of times `suspender`
+    /// has now suspended it.  The scheduler stops running this process once its current run, if
+    /// any, ends.
+    pub fn suspend(&self, suspender: Pid) -> usize {
+        let mut suspend_count_by_pid = self.suspend_count_by_pid.lock();
+        let suspend_count = suspend_count_by_pid.entry(suspender).or_insert(0);
+        *suspend_count += 1;
+
+        *suspend_count
+    }
+
+    /// Undoes one `suspend` by `resumer`.  Returns `None` if `resumer` hasn't suspended this
+    /// process; otherwise, whether it is still suspended by any process.
+    pub fn resume(&self, resumer: Pid) -> Option<bool> {
+        let mut suspend_count_by_pid = self.suspend_count_by_pid.lock();
+        let suspend_count = suspend_count_by_pid.get_mut(&resumer)?;
+        *suspend_count -= 1;
+
+        if *suspend_count == 0 {
+            suspend_count_by_pid.remove(&resumer);
+        }
+
+        Some(!suspend_count_by_pid.is_empty())
+    }
+
+    // Tracing
+
+    pub fn trace(&self) -> Option<Trace> {
+        self.trace.lock().clone()
+    }
+
+    /// Sets the trace, returning the previous trace.
+    pub fn set_trace(&self, trace: Option<Trace>) -> Option<Trace> {
+        mem::replace(&mut *self.trace.lock(), trace)
+    }
+
+    /// Traces a call to `module:function(arguments...)`
+    pub fn trace_call(&self, module: Atom, function: Atom, arguments: &[Term]) {
+        if let Some(tracer) = self.tracer_for(Event::Call) {
+            trace::deliver_module_function_arguments(
+                &tracer,
+                self.pid,
+                Event::Call,
+                &[],
+                module,
+                function,
+                arguments,
+            );
+        }
+    }
+
+    /// Traces sending `message` to `destination`
+    pub fn trace_send(&self, message: Term, destination: Term) {
+        self.trace_event(Event::Send, &[message, destination]);
+    }
+
+    /// Traces spawning `child` to run `module:function(arguments...)`.  When this process has
+    /// `set_on_spawn`, `child` gets its trace, so that its events go to the same tracer.
+    pub fn trace_spawn(&self, child: &Process, module: Atom, function: Atom, arguments: &[Term]) {
+        if let Some(trace) = self.trace() {
+            if trace.flags.set_on_spawn {
+                child.set_trace(Some(trace.clone()));
+            }
+
+            if trace.flags.is_set(Event::Spawn) {
+                trace::deliver_module_function_arguments(
+                    &trace.tracer,
+                    self.pid,
+                    Event::Spawn,
+                    &[child.pid_term()],
+                    module,
+                    function,
+                    arguments,
+                );
+            }
+        }
+    }
+
+    /// Traces this process exiting with `reason`
+    pub fn trace_exit(&self, reason: Term) {
+        self.trace_event(Event::Exit, &[reason]);
+    }
+
+    fn trace_event(&self, event: Event, arguments: &[Term]) {
+        if let Some(tracer) = self.tracer_for(event) {
+            trace::deliver(&tracer, self.pid, event, arguments);
+        }
+    }
+
+    fn tracer_for(&self, event: Event) -> Option<Tracer> {
+        match self.trace() {
+            // a tracer tracing its own receives would receive a trace message for each trace
+            // message
+            Some(Trace {
+                tracer: Tracer::Process(tracer_pid),
+                ..
+            }) if event == Event::Receive && tracer_pid == self.pid => None,
+            Some(Trace { tracer, flags }) if flags.is_set(event) => Some(tracer),
+            _ => None,
+        }
+    }
+
     // Running
 
     pub fn reduce(&self) {
@@ -756,6 +1248,19 @@ impl Process {
         MAX_REDUCTIONS_PER_RUN <= self.run_reductions.load(Ordering::SeqCst)
     }
 
+    /// The reductions of all runs, including the current run
+    pub fn reductions(&self) -> u64 {
+        self.total_reductions.load(Ordering::SeqCst)
+            + (self.run_reductions.load(Ordering::SeqCst) as u64)
+    }
+
+    /// Uses up the reductions left in the current run, so that the code returns to the scheduler,
+    /// which puts the process at the back of its run queue, as `erlang:yield/0` does.
+    pub fn yield_run(&self) {
+        self.run_reductions
+            .store(MAX_REDUCTIONS_PER_RUN, Ordering::SeqCst);
+    }
+
     /// Run process until `reductions` exceeds `MAX_REDUCTIONS` or process exits
     pub fn run(arc_process: &Arc<Process>) -> code::Result {
         arc_process.start_running();
@@ -767,9 +1272,13 @@ impl Process {
             .get(0)
             .map(|frame| frame.code());
 
-        let code_result = match option_code {
-            Some(code) => code(arc_process),
-            None => Ok(arc_process.exit()),
+        let code_result = if arc_process.is_exiting() {
+            Ok(())
+        } else {
+            match option_code {
+                Some(code) => code(arc_process),
+                None => Ok(arc_process.exit()),
+            }
         };
 
         arc_process.stop_running();
@@ -778,7 +1287,13 @@ impl Process {
     }
 
     fn start_running(&self) {
-        *self.status.write() = Status::Running;
+        let mut writable_status = self.status.write();
+
+        // an exit signal delivered after the process was dequeued isn't lost
+        if let Status::Exiting(_) = *writable_status {
+        } else {
+            *writable_status = Status::Running;
+        }
     }
 
     fn stop_running(&self) {
@@ -797,10 +1312,41 @@ impl Process {
 
     /// Puts the process in the waiting status
     pub fn wait(&self) {
-        *self.status.write() = Status::Waiting;
+        let mut writable_status = self.status.write();
+
+        // a process that is exiting, such as one killed while it ran, doesn't wait
+        if let Status::Exiting(_) = *writable_status {
+        } else {
+            *writable_status = Status::Waiting;
+        }
+        drop(writable_status);
+
         self.run_reductions.fetch_add(1, Ordering::AcqRel);
     }
 
+    /// Puts the process in the waiting status, unless a message is already in its mailbox, in which
+    /// case it returns `false` and the process keeps running.
+    ///
+    /// The status is locked before the mailbox is checked, so a message sent after the check
+    /// wakes the process once it is waiting.
+    pub fn wait_for_message(&self) -> bool {
+        let mut writable_status = self.status.write();
+
+        if self.mailbox.lock().borrow().len() > 0 {
+            return false;
+        }
+
+        if let Status::Exiting(_) = *writable_status {
+        } else {
+            *writable_status = Status::Waiting;
+        }
+        drop(writable_status);
+
+        self.run_reductions.fetch_add(1, Ordering::AcqRel);
+
+        true
+    }
+
     pub fn exit(&self) {
         self.reduce();
         self.exception(exit!(atom_unchecked("normal")));
@@ -917,6 +1463,12 @@ impl Process {
         heap.young.heap_used()
     }
 
+    #[inline]
+    fn young_heap_size(&self) -> usize {
+        let heap = self.heap.lock();
+        heap.young.size()
+    }
+
     #[inline]
     fn old_heap_used(&self) -> usize {
         let heap = self.heap.lock();
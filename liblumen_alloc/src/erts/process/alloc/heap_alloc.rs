@@ -22,9 +22,11 @@ use crate::erts::term::binary::IterableBitstring;
 use crate::erts::term::reference::{self, Reference};
 use crate::erts::term::resource;
 use crate::erts::term::{
-    make_pid, pid, AsTerm, BinaryType, BytesFromBinaryError, Closure, Cons, ExternalPid, Float,
-    HeapBin, Integer, Map, ProcBin, StrFromBinaryError, SubBinary, Term, Tuple, TypedTerm,
+    make_pid, pid, AsTerm, BinaryType, BytesFromBinaryError, Closure, Cons, ExternalPid,
+    ExternalPort, ExternalReference, Float, HeapBin, Integer, Map, MatchBuffer, MatchContext,
+    ProcBin, StrFromBinaryError, SubBinary, Term, Tuple, TypedTerm,
 };
+use crate::erts::Node;
 use crate::{erts, ModuleFunctionArity};
 use crate::{scheduler, VirtualAlloc};
 
@@ -173,6 +175,44 @@ pub trait HeapAlloc {
         }
     }
 
+    /// Creates an `ExternalPid` for the pid with `number` and `serial` on `node`.
+    fn external_pid(
+        &mut self,
+        node: Node,
+        number: usize,
+        serial: usize,
+    ) -> Result<Term, MakePidError>
+    where
+        Self: core::marker::Sized,
+    {
+        let external_pid = ExternalPid::new(node, number, serial)?;
+        let heap_external_pid = external_pid.clone_to_heap(self)?;
+
+        Ok(heap_external_pid)
+    }
+
+    /// Creates an `ExternalPort` for the port with `number` on `node`.
+    fn external_port(&mut self, node: Node, number: usize) -> Result<Term, Alloc>
+    where
+        Self: core::marker::Sized,
+    {
+        ExternalPort::new(node, number).clone_to_heap(self)
+    }
+
+    /// Creates an `ExternalReference` for the reference with `scheduler_id` and `number` on
+    /// `node`.
+    fn external_reference(
+        &mut self,
+        node: Node,
+        scheduler_id: scheduler::ID,
+        number: reference::Number,
+    ) -> Result<Term, Alloc>
+    where
+        Self: core::marker::Sized,
+    {
+        ExternalReference::new(node, scheduler_id, number).clone_to_heap(self)
+    }
+
     fn external_pid_with_node_id(
         &mut self,
         node_id: usize,
@@ -336,6 +376,20 @@ pub trait HeapAlloc {
     }
 
     /// Creates a `Pid` or `ExternalPid` with the given `node`, `number` and `serial`.
+    /// Constructs a match context that continues the match in `buffer`
+    fn match_context_from_buffer(&mut self, buffer: MatchBuffer) -> Result<Term, Alloc> {
+        let match_context = MatchContext::from_buffer(buffer);
+
+        unsafe {
+            let ptr =
+                self.alloc_layout(Layout::new::<MatchContext>())?.as_ptr() as *mut MatchContext;
+            ptr::write(ptr, match_context);
+            let process_match_context = &*ptr;
+
+            Ok(process_match_context.as_term())
+        }
+    }
+
     fn pid_with_node_id(
         &mut self,
         node_id: usize,
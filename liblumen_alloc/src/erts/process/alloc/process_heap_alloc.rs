@@ -16,6 +16,7 @@ use liblumen_core::alloc::mmap;
 use liblumen_core::alloc::size_classes::SizeClass;
 
 use crate::erts::exception::system::Alloc;
+use crate::erts::process::heap_sizing::heap_sizing;
 use crate::erts::Term;
 use crate::SizeClassAlloc;
 
@@ -56,23 +57,17 @@ pub unsafe fn free(heap: *mut Term, size: usize) {
     PROC_ALLOC.dealloc(heap, size)
 }
 
-/// Calculates the next largest heap size equal to or greater than `size`
+/// Calculates the next largest heap size greater than `size`, using the heap sizing of the VM
+/// (see `heap_sizing`)
 #[inline]
 pub fn next_heap_size(size: usize) -> usize {
-    let mut next_size = 0;
-    for i in 0..ProcessHeapAlloc::HEAP_SIZES.len() {
-        next_size = ProcessHeapAlloc::HEAP_SIZES[i];
-        if next_size > size {
-            return next_size;
-        }
-    }
-    // If we reach this point, we just grow by 20% until
-    // we have a large enough region, these will be allocated
-    // using `mmap` directly
-    while size >= next_size {
-        next_size = next_size + next_size / 5;
-    }
-    next_size
+    heap_sizing().next_heap_size(size)
+}
+
+/// The Fibonacci-like heap sizes, from the default heap size, before heaps grow by a percentage at
+/// a time instead
+pub(crate) fn fibonacci_heap_sizes() -> &'static [usize] {
+    &ProcessHeapAlloc::HEAP_SIZES[..=ProcessHeapAlloc::MAX_FIBONACCI_HEAP_SIZE_INDEX]
 }
 
 /// This allocator is used to allocate process heaps globally.
@@ -97,9 +92,12 @@ impl ProcessHeapAlloc {
     /// Corresponds to the first heap size of 233 words
     const MIN_HEAP_SIZE_INDEX: usize = 0;
 
+    /// Corresponds to the last Fibonacci heap size, at ~1M words
+    const MAX_FIBONACCI_HEAP_SIZE_INDEX: usize = 18;
+
     /// Creates a new `ProcessAlloc` instance
     pub fn new() -> Self {
-        let size_classes = &Self::HEAP_SIZES[..19]
+        let size_classes = &Self::HEAP_SIZES[..=Self::MAX_FIBONACCI_HEAP_SIZE_INDEX]
             .iter()
             .map(|size| SizeClass::new(*size))
             .filter(|size_class| SizeClassAlloc::can_fit_multiple_blocks(size_class))
@@ -26,6 +26,9 @@ impl ProcessFlags {
     /// This flag indicates the processes linked to this process should send exit messages instead
     /// of causing this process to exit when they exit
     pub const TrapExit: Self = Self(1 << 6);
+    /// This flag indicates that the next full sweep should size the new heap for only the data it
+    /// copies, without growing it, as when the process hibernates
+    pub const ShrinkHeap: Self = Self(1 << 7);
 
     pub fn are_set(&self, flags: ProcessFlags) -> bool {
         (*self & flags) == flags
@@ -0,0 +1,119 @@
+//! A flight recorder: a fixed-size ring buffer of the last messages a process sent and received,
+//! for finding out what a process was doing when it crashed without the cost of full tracing.
+//!
+//! Messages are recorded as text when they are sent or arrive in the mailbox, because the terms
+//! themselves are garbage collected or freed with their heap fragment long before a crash.
+
+use core::fmt;
+use core::mem;
+
+use alloc::collections::VecDeque;
+use alloc::string::{String, ToString};
+
+use lazy_static::lazy_static;
+
+use liblumen_core::locks::RwLock;
+
+use crate::erts::term::Term;
+
+/// Milliseconds since some fixed point, used to timestamp entries
+pub type Clock = fn() -> u64;
+
+pub fn clock() -> Clock {
+    *RW_LOCK_CLOCK.read()
+}
+
+/// Sets the clock used to timestamp entries.  Until it is set, every entry is at `0`.
+pub fn set_clock(clock: Clock) {
+    *RW_LOCK_CLOCK.write() = clock;
+}
+
+pub struct FlightRecorder {
+    capacity: usize,
+    entries: VecDeque<Entry>,
+}
+
+impl FlightRecorder {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The recorded entries, oldest first
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.iter()
+    }
+
+    /// Records `message`, dropping the oldest entry if the recorder is full
+    pub fn record(&mut self, direction: Direction, message: Term) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+
+        self.entries.push_back(Entry {
+            time: clock()(),
+            direction,
+            message: message.to_string(),
+        });
+    }
+
+    /// Changes the capacity, keeping the newest entries that fit, and returns the old capacity.
+    pub fn resize(&mut self, capacity: usize) -> usize {
+        while capacity < self.entries.len() {
+            self.entries.pop_front();
+        }
+
+        mem::replace(&mut self.capacity, capacity)
+    }
+}
+
+impl fmt::Display for FlightRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for entry in &self.entries {
+            writeln!(f, "{}", entry)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct Entry {
+    pub time: u64,
+    pub direction: Direction,
+    pub message: String,
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.direction {
+            Direction::Sent { destination } => write!(
+                f,
+                "{}ms sent {} to {}",
+                self.time, self.message, destination
+            ),
+            Direction::Received => write!(f, "{}ms received {}", self.time, self.message),
+        }
+    }
+}
+
+pub enum Direction {
+    /// Sent to `destination`, as the pid or name was given to `send`
+    Sent {
+        destination: String,
+    },
+    Received,
+}
+
+fn no_clock() -> u64 {
+    0
+}
+
+lazy_static! {
+    static ref RW_LOCK_CLOCK: RwLock<Clock> = RwLock::new(no_clock);
+}
@@ -1,6 +1,8 @@
 mod collector;
 mod old_heap;
 mod rootset;
+#[cfg(feature = "gc_debug")]
+mod verify;
 mod virtual_heap;
 mod young_heap;
 
@@ -1,10 +1,13 @@
 use core::ptr;
+use core::sync::atomic::Ordering;
 
 use intrusive_collections::UnsafeRef;
 use log::trace;
 
 use liblumen_core::util::pointer::{distance_absolute, in_area};
 
+#[cfg(feature = "gc_debug")]
+use super::verify;
 use super::*;
 use crate::erts::exception::system::Alloc;
 use crate::erts::process::alloc;
@@ -60,6 +63,9 @@ pub struct GarbageCollector<'p, 'h> {
     process: &'p Process,
     heap: &'h mut ProcessHeap,
     roots: RootSet,
+    /// The heaps this collection freed, which no live term may point into
+    #[cfg(feature = "gc_debug")]
+    from_spaces: Vec<verify::Area>,
 }
 impl<'p, 'h> GarbageCollector<'p, 'h> {
     /// Initializes the collector with the given process and root set
@@ -74,6 +80,8 @@ impl<'p, 'h> GarbageCollector<'p, 'h> {
             process,
             heap,
             roots,
+            #[cfg(feature = "gc_debug")]
+            from_spaces: Vec::new(),
         }
     }
 
@@ -106,20 +114,33 @@ impl<'p, 'h> GarbageCollector<'p, 'h> {
         let stack_size = self.heap.young.stack_used();
         let off_heap_size = self.process.off_heap_size();
         let size_before = self.heap.young.heap_used() + old_heap_size + off_heap_size;
+        let heap_sizing = self.process.heap_sizing();
         // Conservatively pad out estimated size to include space for the requested `need`
-        let mut new_size = alloc::next_heap_size(stack_size + size_before);
+        let mut new_size = heap_sizing.next_heap_size(stack_size + size_before);
         while new_size < (need + stack_size + size_before) {
-            new_size = alloc::next_heap_size(new_size);
+            new_size = heap_sizing.next_heap_size(new_size);
         }
         // If we already have a large enough heap, we don't need to grow it, but if the GROW flag is
         // set, then we should do it anyway, since it will prevent us from doing another full
         // collection for awhile (assuming one is not forced)
-        if new_size == self.heap.young.size() && self.should_force_heap_growth() {
-            new_size = alloc::next_heap_size(new_size);
+        if new_size == self.heap.young.size()
+            && self.should_force_heap_growth()
+            && !self.process.flags.are_set(ProcessFlags::ShrinkHeap)
+        {
+            new_size = heap_sizing.next_heap_size(new_size);
+        }
+        // The heap isn't made smaller than the minimum heap size of the process, unless it is being
+        // shrunk to fit its live data, such as when it hibernates
+        if new_size < self.process.min_heap_size()
+            && !self.process.flags.are_set(ProcessFlags::ShrinkHeap)
+        {
+            new_size = self.process.min_heap_size();
         }
         // Verify that our projected heap size is not going to blow the max heap size, if set
-        // NOTE: When this happens, we will be left with no choice but to kill the process
-        if self.process.max_heap_size > 0 && self.process.max_heap_size < new_size {
+        // NOTE: When this happens, we will be left with no choice but to kill the process, so a
+        // process that isn't killed is collected anyway
+        let max_heap_size = self.process.max_heap_size();
+        if max_heap_size.kill && max_heap_size.is_exceeded_by(new_size) {
             return Err(GcError::MaxHeapSizeExceeded);
         }
         // Unset heap_grow and need_fullsweep flags, because we are doing both
@@ -129,6 +150,7 @@ impl<'p, 'h> GarbageCollector<'p, 'h> {
         // Allocate new heap
         let new_heap_start = alloc::heap(new_size).map_err(|alloc| GcError::Alloc(alloc))?;
         let mut new_heap = YoungHeap::new(new_heap_start, new_size);
+        new_heap.set_min_virtual_heap_size(self.process.min_bin_vheap_size());
         // Follow roots and copy values to appropriate heaps
         unsafe {
             for root in self.roots.iter() {
@@ -186,10 +208,11 @@ impl<'p, 'h> GarbageCollector<'p, 'h> {
         self.sweep_off_heap();
 
         // Free the old generation heap, as it is no longer used post-sweep
+        //
+        // NOTE: Because OldHeap implements Drop, replacing it frees it, after its virtual binary
+        // heap is cleared
         if self.heap.old.active() {
-            let old_heap_start = self.heap.old.heap_start();
-            let old_heap_size = self.heap.old.size();
-            unsafe { alloc::free(old_heap_start, old_heap_size) };
+            self.record_from_space(self.heap.old.heap_start(), self.heap.old.size());
             self.heap.old = OldHeap::empty();
         }
 
@@ -203,12 +226,11 @@ impl<'p, 'h> GarbageCollector<'p, 'h> {
             new_heap.set_stack_size(old_stack_slots);
         }
 
-        // Free the old young generation heap, as it is no longer used now that the stack is moved
-        let young_heap_start = self.heap.young.heap_start();
-        let young_heap_size = self.heap.young.size();
-        unsafe { alloc::free(young_heap_start, young_heap_size) };
-
         // Update the process to use the new heap
+        //
+        // NOTE: Because YoungHeap implements Drop, the old young heap, which is no longer used now
+        // that the stack is moved, will be freed correctly here
+        self.record_from_space(self.heap.young.heap_start(), self.heap.young.size());
         new_heap.set_high_water_mark();
         self.heap.young = new_heap;
         self.heap.gen_gc_count = 0;
@@ -241,16 +263,22 @@ impl<'p, 'h> GarbageCollector<'p, 'h> {
             // that was smaller than even our worst case estimate, which means
             // we almost certainly have a bug
             panic!("Full sweep finished, but the needed size exceeds even the most pessimistic estimate, this must be a bug");
-        } else if total_size * 3 < need_after * 4 {
-            // `need_after` requires more than 75% of the current size, schedule some growth
+        } else if self.process.flags.are_set(ProcessFlags::ShrinkHeap) {
+            // The new heap was sized for only the live data, so it is as small as it can be
+            self.process.flags.clear(ProcessFlags::ShrinkHeap);
+        } else if heap_sizing.should_grow(total_size, need_after) {
+            // `need_after` requires more than the grow threshold of the current size, schedule
+            // some growth
             self.process.flags.set(ProcessFlags::GrowHeap);
-        } else if total_size > need_after * 4 && self.process.min_heap_size < total_size {
-            // We need less than 25% of the current heap, shrink
+        } else if heap_sizing.should_shrink(total_size, need_after)
+            && self.process.min_heap_size() < total_size
+        {
+            // We need less than the shrink threshold of the current heap, shrink
             let wanted = need_after * 2;
-            let size = if wanted < self.process.min_heap_size {
-                self.process.min_heap_size
+            let size = if wanted < self.process.min_heap_size() {
+                self.process.min_heap_size()
             } else {
-                alloc::next_heap_size(wanted)
+                heap_sizing.next_heap_size(wanted)
             };
             if size < total_size {
                 self.shrink_young_heap(size);
@@ -290,21 +318,23 @@ impl<'p, 'h> GarbageCollector<'p, 'h> {
 
         let prev_old_top = self.heap.old.heap_pointer();
         let stack_size = self.heap.young.stack_used();
-        let mut new_size = alloc::next_heap_size(stack_size + size_before);
+        let heap_sizing = self.process.heap_sizing();
+        let mut new_size = heap_sizing.next_heap_size(stack_size + size_before);
         while new_size < (stack_size + size_before + need) {
             // While we expect that we will free memory during collection,
             // we want to avoid the case where we collect and then find that
             // the new heap is too small to meet the need that triggered the
             // collection in the first place. Better to shrink it post-collection
             // than to require growing it and re-updating all the roots again
-            new_size = alloc::next_heap_size(new_size);
+            new_size = heap_sizing.next_heap_size(new_size);
+        }
+        if new_size < self.process.min_heap_size() {
+            new_size = self.process.min_heap_size();
         }
 
         // Perform the "meat" of the minor collection
         unsafe { self.do_minor_sweep(self.heap.young.heap_start(), mature_size, new_size)? };
 
-        // TODO: if using on-heap messages, move messages in the queue to the heap
-
         let new_mature = distance_absolute(self.heap.old.heap_pointer(), prev_old_top);
         let heap_used = self.heap.young.heap_used();
         let size_after = new_mature + heap_used; // + process.mbuf_size
@@ -319,7 +349,7 @@ impl<'p, 'h> GarbageCollector<'p, 'h> {
         // The reason for this is that after tenuring, we often use a really small portion of the
         // new heap, therefore unless the heap size is substantial, we don't want to shrink
         let mut adjust_size = 0;
-        let oversized_heap = heap_used > need_after * 4;
+        let oversized_heap = heap_sizing.should_shrink(heap_used, need_after);
         let old_heap_size = self.heap.old.size();
         let shrink = oversized_heap && (heap_used > 8000 || heap_used > old_heap_size);
         if shrink {
@@ -333,10 +363,10 @@ impl<'p, 'h> GarbageCollector<'p, 'h> {
                 }
             }
 
-            wanted = if wanted < self.process.min_heap_size {
-                self.process.min_heap_size
+            wanted = if wanted < self.process.min_heap_size() {
+                self.process.min_heap_size()
             } else {
-                alloc::next_heap_size(wanted)
+                heap_sizing.next_heap_size(wanted)
             };
 
             if wanted < heap_used {
@@ -360,6 +390,7 @@ impl<'p, 'h> GarbageCollector<'p, 'h> {
         // Allocate new tospace (young generation)
         let new_young_start = alloc::heap(new_size).map_err(|alloc| GcError::Alloc(alloc))?;
         let mut new_young = YoungHeap::new(new_young_start, new_size);
+        new_young.set_min_virtual_heap_size(self.process.min_bin_vheap_size());
 
         // Follow roots and copy values to appropriate heaps
         for root in self.roots.iter() {
@@ -450,6 +481,7 @@ impl<'p, 'h> GarbageCollector<'p, 'h> {
 
         // Replace the now freed young heap with the one we've been building
         // NOTE: Because YoungHeap implements Drop, the old young heap will be freed correctly here
+        self.record_from_space(self.heap.young.heap_start(), self.heap.young.size());
         self.heap.young = new_young;
 
         Ok(())
@@ -472,6 +504,7 @@ impl<'p, 'h> GarbageCollector<'p, 'h> {
             let fragment_ptr = UnsafeRef::into_raw(fragment_ref);
             unsafe { ptr::drop_in_place(fragment_ptr) };
         }
+        self.process.off_heap_size.store(0, Ordering::Release);
     }
 
     /// In some cases, after a minor collection we may find that we have over-allocated for the
@@ -495,7 +528,7 @@ impl<'p, 'h> GarbageCollector<'p, 'h> {
     #[inline]
     fn ensure_old_heap(&mut self, size_before: usize, mature_size: usize) -> Result<(), Alloc> {
         if !self.heap.old.active() && mature_size > 0 {
-            let size = alloc::next_heap_size(size_before);
+            let size = self.process.heap_sizing().next_heap_size(size_before);
             let start = alloc::heap(size)?;
             self.heap.old = OldHeap::new(start, size);
         }
@@ -513,15 +546,18 @@ impl<'p, 'h> GarbageCollector<'p, 'h> {
         let young = &self.heap.young;
         let old = &self.heap.old;
 
-        // If a max heap size is set, make sure we're not going to exceed it
-        if self.process.max_heap_size > 0 {
+        // If a max heap size is set for a process that is killed when it exceeds it, make sure
+        // we're not going to exceed it
+        let max_heap_size = self.process.max_heap_size();
+        if max_heap_size.kill && max_heap_size.size > 0 {
+            let heap_sizing = self.process.heap_sizing();
             // First, check if we have exceeded the max heap size
             let mut heap_size = size_before;
             // Includes unused area between stack and heap
             let stack_size = young.stack_used() + young.unused();
             // Add potential old heap size
             if !old.active() && mature_size > 0 {
-                heap_size += alloc::next_heap_size(size_before);
+                heap_size += heap_sizing.next_heap_size(size_before);
             } else if old.active() {
                 heap_size += old.heap_used();
             }
@@ -529,14 +565,14 @@ impl<'p, 'h> GarbageCollector<'p, 'h> {
             // the worst case scenario where we free no memory and need to
             // reclaim `need` words. We grow the projected size until there
             // is at least enough memory for the current heap + `need`
-            let mut new_heap_size = alloc::next_heap_size(stack_size + size_before);
+            let mut new_heap_size = heap_sizing.next_heap_size(stack_size + size_before);
             while new_heap_size < (need + stack_size + size_before) {
-                new_heap_size = alloc::next_heap_size(new_heap_size);
+                new_heap_size = heap_sizing.next_heap_size(new_heap_size);
             }
             heap_size += new_heap_size;
 
             // When this error type is returned, a full sweep will be triggered
-            if heap_size > self.process.max_heap_size {
+            if max_heap_size.is_exceeded_by(heap_size) {
                 return Err(GcError::MaxHeapSizeExceeded);
             }
         }
@@ -570,7 +606,39 @@ impl<'p, 'h> GarbageCollector<'p, 'h> {
         }
     }
 
+    /// Records the heap at `start` of `size` words, which this collection is about to free, so that
+    /// `sanity_check` can verify that no live term points into it
+    #[cfg(feature = "gc_debug")]
+    #[inline]
+    fn record_from_space(&mut self, start: *mut Term, size: usize) {
+        self.from_spaces.push(verify::Area::new(start, size));
+    }
+
+    #[cfg(not(feature = "gc_debug"))]
+    #[inline]
+    fn record_from_space(&mut self, _start: *mut Term, _size: usize) {}
+
+    /// Runs verification of heap invariants
+    #[cfg(feature = "gc_debug")]
+    #[inline]
+    fn sanity_check(&self) {
+        self.heap.young.sanity_check();
+
+        let young = &self.heap.young;
+        let young_heap = verify::Area::new(young.heap_start(), young.heap_used());
+        let stack_used = young.stack_used();
+        let stack = verify::Area::new(
+            unsafe { young.heap_start().add(young.size() - stack_used) },
+            stack_used,
+        );
+        let old = &self.heap.old;
+        let old_heap = verify::Area::new(old.heap_start(), old.heap_used());
+
+        verify::verify(young_heap, stack, old_heap, &self.from_spaces);
+    }
+
     /// Runs verification of heap invariants
+    #[cfg(not(feature = "gc_debug"))]
     #[inline]
     fn sanity_check(&self) {
         self.heap.young.sanity_check()
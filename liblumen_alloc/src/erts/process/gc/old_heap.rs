@@ -378,6 +378,9 @@ impl Drop for OldHeap {
                 // Free virtual binary heap, we can't free the memory of this heap until we've done
                 // this
                 self.vheap.clear();
+                // Poison the memory region, so that any term still pointing into it reads garbage
+                #[cfg(feature = "gc_debug")]
+                super::verify::poison(super::verify::Area::new(self.start, self.size()));
                 // Free memory region managed by this heap instance
                 process::alloc::free(self.start, self.size());
             }
@@ -0,0 +1,217 @@
+//! Verification of the heaps after a collection, enabled by the `gc_debug` feature.
+//!
+//! The from-space of a collection is poisoned before it is freed, so that any term that still
+//! refers to it reads garbage instead of stale, plausible looking terms.  Once the collection is
+//! done, the young heap, stack and old heap are walked the same way the collector sweeps them, and
+//! every boxed and list pointer is checked:
+//!
+//! - it must not point into a from-space
+//! - unless it is a literal, it must point into the young heap, stack or old heap, and a pointer in
+//!   the old heap must not point into the young heap or stack
+//! - a boxed pointer must point at a header and a list pointer must point at a cons cell, neither of
+//!   which may be a move marker
+//!
+//! and each walk must end exactly at the top of the area it walks.
+
+use core::mem;
+use core::ptr;
+
+use liblumen_core::util::pointer::in_area;
+
+use crate::erts::term::{is_move_marker, Closure};
+use crate::erts::*;
+
+/// The byte every byte of a from-space is set to before it is freed
+const POISON_BYTE: u8 = 0xDB;
+
+/// A region of memory, from `start` up to, but not including, `end`
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Area {
+    start: *mut Term,
+    end: *mut Term,
+}
+impl Area {
+    #[inline]
+    pub fn new(start: *mut Term, size: usize) -> Self {
+        Self {
+            start,
+            end: unsafe { start.add(size) },
+        }
+    }
+
+    #[inline]
+    fn contains<T>(&self, ptr: *const T) -> bool {
+        in_area(ptr, self.start, self.end)
+    }
+}
+
+/// Overwrites `from_space`, which is about to be freed, with `POISON_BYTE`
+pub(super) unsafe fn poison(from_space: Area) {
+    let len = (from_space.end as usize) - (from_space.start as usize);
+    debug_assert_eq!(len % mem::size_of::<Term>(), 0);
+
+    ptr::write_bytes(from_space.start as *mut u8, POISON_BYTE, len);
+}
+
+/// Panics if any term in `young_heap`, `stack` or `old_heap` breaks an invariant of the heaps, such
+/// as pointing into one of `from_spaces`
+pub(super) fn verify(young_heap: Area, stack: Area, old_heap: Area, from_spaces: &[Area]) {
+    let young_targets = [young_heap, stack, old_heap];
+    let old_targets = [old_heap];
+
+    walk("young heap", young_heap, &young_targets, from_spaces);
+    walk("stack", stack, &young_targets, from_spaces);
+    walk("old heap", old_heap, &old_targets, from_spaces);
+}
+
+/// Walks `area` like the collector's sweeps, checking that every pointer points into one of
+/// `targets` and not into `from_spaces`
+fn walk(name: &str, area: Area, targets: &[Area], from_spaces: &[Area]) {
+    let mut pos = area.start;
+
+    while (pos as usize) < (area.end as usize) {
+        let term = unsafe { *pos };
+
+        pos = if term.is_boxed() {
+            let ptr = term.boxed_val();
+            check_pointer(name, pos, ptr, term.is_literal(), targets, from_spaces);
+
+            if !term.is_literal() {
+                let header = unsafe { *ptr };
+
+                assert!(
+                    !is_move_marker(header),
+                    "{} term at {:?} points at move marker at {:?}",
+                    name,
+                    pos,
+                    ptr
+                );
+                assert!(
+                    header.is_header(),
+                    "{} term at {:?} is boxed, but points at {:?}, which is not a header",
+                    name,
+                    pos,
+                    ptr
+                );
+            }
+
+            unsafe { pos.add(1) }
+        } else if term.is_non_empty_list() {
+            let ptr = term.list_val();
+            check_pointer(name, pos, ptr, term.is_literal(), targets, from_spaces);
+
+            if !term.is_literal() {
+                assert!(
+                    !unsafe { *ptr }.is_move_marker(),
+                    "{} term at {:?} points at moved cons cell at {:?}",
+                    name,
+                    pos,
+                    ptr
+                );
+            }
+
+            unsafe { pos.add(1) }
+        } else if term.is_header() {
+            if term.is_tuple_header() {
+                // Check the elements
+                unsafe { pos.add(1) }
+            } else if term.is_closure_header() {
+                // Check the terms in the env
+                unsafe { pos.add(Closure::base_size_words()) }
+            } else {
+                // Skip the non-term data
+                unsafe { pos.add(1 + term.arityval()) }
+            }
+        } else {
+            unsafe { pos.add(1) }
+        };
+    }
+
+    assert_eq!(
+        pos, area.end,
+        "walking {} from {:?} went past its end at {:?}",
+        name, area.start, area.end
+    );
+}
+
+fn check_pointer<T>(
+    name: &str,
+    pos: *mut Term,
+    ptr: *const T,
+    literal: bool,
+    targets: &[Area],
+    from_spaces: &[Area],
+) {
+    assert!(
+        !from_spaces
+            .iter()
+            .any(|from_space| from_space.contains(ptr)),
+        "{} term at {:?} points into from-space at {:?}",
+        name,
+        pos,
+        ptr
+    );
+
+    if !literal {
+        assert!(
+            targets.iter().any(|target| target.contains(ptr)),
+            "{} term at {:?} points outside of the heaps it may point into at {:?}",
+            name,
+            pos,
+            ptr
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::erts::process::alloc::{self, HeapAlloc};
+    use crate::erts::process::gc::YoungHeap;
+    use crate::erts::term::atom_unchecked;
+
+    #[test]
+    fn verify_with_pointers_into_young_heap() {
+        let (heap, heap_size) = alloc::default_heap().unwrap();
+        let mut young_heap = YoungHeap::new(heap, heap_size);
+
+        let inner = young_heap
+            .tuple_from_slice(&[atom_unchecked("inner")])
+            .unwrap();
+        let list = young_heap.cons(inner, Term::NIL).unwrap();
+        young_heap.tuple_from_slice(&[inner, list]).unwrap();
+
+        verify(
+            area(&young_heap),
+            Area::new(heap, 0),
+            Area::new(ptr::null_mut(), 0),
+            &[],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "points into from-space")]
+    fn verify_with_pointer_into_from_space() {
+        let (from_heap, from_heap_size) = alloc::default_heap().unwrap();
+        let mut from_young_heap = YoungHeap::new(from_heap, from_heap_size);
+        let (heap, heap_size) = alloc::default_heap().unwrap();
+        let mut young_heap = YoungHeap::new(heap, heap_size);
+
+        let moved = from_young_heap
+            .tuple_from_slice(&[atom_unchecked("moved")])
+            .unwrap();
+        young_heap.tuple_from_slice(&[moved]).unwrap();
+
+        verify(
+            area(&young_heap),
+            Area::new(heap, 0),
+            Area::new(ptr::null_mut(), 0),
+            &[area(&from_young_heap)],
+        );
+    }
+
+    fn area(young_heap: &YoungHeap) -> Area {
+        Area::new(young_heap.heap_start(), young_heap.heap_used())
+    }
+}
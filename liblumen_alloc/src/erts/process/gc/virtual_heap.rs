@@ -33,6 +33,17 @@ impl VirtualBinaryHeap {
         self.size
     }
 
+    /// Grows the virtual heap to at least `size` words, so that a process referencing many
+    /// binaries isn't collected as often
+    #[inline]
+    pub fn grow_to(&mut self, size: usize) {
+        let size_in_bytes = size * mem::size_of::<usize>();
+
+        if self.size < size_in_bytes {
+            self.size = size_in_bytes;
+        }
+    }
+
     /// Gets the current amount of virtual binary heap space used (in bytes)
     /// by binaries referenced from the current process
     #[inline]
@@ -248,6 +248,12 @@ impl YoungHeap {
         self.vheap.unused()
     }
 
+    /// Grows the virtual heap to at least `size` words, the `min_bin_vheap_size` of the process
+    #[inline]
+    pub fn set_min_virtual_heap_size(&mut self, size: usize) {
+        self.vheap.grow_to(size)
+    }
+
     /// Gets the current amount of space (in words) available for heap allocations
     #[inline]
     pub fn heap_available(&self) -> usize {
@@ -835,6 +841,9 @@ impl Drop for YoungHeap {
         unsafe {
             // Free virtual binary heap
             self.vheap.clear();
+            // Poison the memory region, so that any term still pointing into it reads garbage
+            #[cfg(feature = "gc_debug")]
+            super::verify::poison(super::verify::Area::new(self.start, self.size()));
             // Free memory region managed by this heap instance
             process::alloc::free(self.start, self.size());
         }
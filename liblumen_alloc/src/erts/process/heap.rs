@@ -5,7 +5,7 @@ use crate::erts::term::{ProcBin, Term};
 
 use super::alloc::{HeapAlloc, StackAlloc, StackPrimitives, VirtualAlloc};
 use super::gc::*;
-use super::Process;
+use super::{Process, ProcessFlags};
 
 #[derive(Debug)]
 #[repr(C)]
@@ -57,8 +57,19 @@ impl ProcessHeap {
         need: usize,
         mut rootset: RootSet,
     ) -> Result<usize, GcError> {
+        debug_assert!(
+            !process.are_flags_set(ProcessFlags::DisableGC),
+            "garbage collection is disabled, such as while a `BinaryView` is alive"
+        );
         // The primary source of roots we add is the process stack
         rootset.push_range(self.young.stack_pointer(), self.young.stack_size());
+        // Queued messages are roots too.  Those in heap fragments in the off-heap are merged into
+        // the heap, as the collection frees the fragments.  The mailbox stays locked until the
+        // collection is done, so that the roots in it don't move.
+        let mailbox_guard = process.mailbox.lock();
+        let mut mailbox = mailbox_guard.borrow_mut();
+        mailbox.merge_off_heap_messages();
+        unsafe { mailbox.add_to_root_set(&mut rootset) };
         // Initialize the collector
         let mut gc = GarbageCollector::new(self, process, rootset);
         // Run the collector
@@ -0,0 +1,243 @@
+//! How collections size the heap of a process: the sizes heaps grow through, and how full a heap
+//! has to be after a collection for the next one to grow it, or how empty for it to be shrunk.
+//!
+//! The VM's heap sizing is used by every process unless the process is spawned with, or sets,
+//! its own with `spawn_opt(..., [{heap_sizing, HeapSizing}])` or
+//! `process_flag(heap_sizing, HeapSizing)`.  Changing the VM's heap sizing only affects processes
+//! spawned after the change.
+
+use core::convert::{TryFrom, TryInto};
+
+use lazy_static::lazy_static;
+
+use liblumen_core::locks::RwLock;
+
+use crate::erts::exception::runtime;
+use crate::erts::exception::system::Alloc;
+use crate::erts::process::alloc::{default_heap_size, fibonacci_heap_sizes};
+use crate::erts::process::Process;
+use crate::erts::term::{atom_unchecked, Term, TypedTerm};
+
+/// The `heap_sizing` process flag and VM configuration
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HeapSizing {
+    /// The largest size, in words, that heaps grow to along the same Fibonacci-like sizes as the
+    /// BEAM, starting from 232 words.  Past it, heaps grow by `growth_percent` at a time.
+    pub fibonacci_max: usize,
+    /// The percentage that heaps past `fibonacci_max` grow by
+    pub growth_percent: usize,
+    /// The percentage of the heap that, when needed by live data after a full sweep, grows the
+    /// heap on the next full sweep
+    pub grow_threshold: usize,
+    /// The percentage of the heap that, when more than what is needed by live data after a sweep,
+    /// shrinks the heap
+    pub shrink_threshold: usize,
+}
+
+impl HeapSizing {
+    /// Whether heaps can be sized with these settings: `fibonacci_max` has to be at least the
+    /// default heap size, `growth_percent` positive, and `shrink_threshold` positive and less than
+    /// `grow_threshold`, which is at most 100.
+    pub fn is_valid(&self) -> bool {
+        default_heap_size() <= self.fibonacci_max
+            && 0 < self.growth_percent
+            && 0 < self.shrink_threshold
+            && self.shrink_threshold < self.grow_threshold
+            && self.grow_threshold <= 100
+    }
+
+    /// The next size, in words, greater than `size` that a heap can have
+    pub fn next_heap_size(&self, size: usize) -> usize {
+        let mut next_size = default_heap_size();
+
+        for &heap_size in fibonacci_heap_sizes() {
+            if self.fibonacci_max < heap_size {
+                break;
+            }
+
+            next_size = heap_size;
+
+            if size < next_size {
+                return next_size;
+            }
+        }
+
+        // past the Fibonacci-like sizes, large heaps are allocated using `mmap` directly
+        while next_size <= size {
+            next_size += (next_size * self.growth_percent / 100).max(1);
+        }
+
+        next_size
+    }
+
+    /// Whether the heap of `heap_size` words should grow on the next full sweep when `need` words
+    /// of it are still needed after a full sweep
+    pub fn should_grow(&self, heap_size: usize, need: usize) -> bool {
+        heap_size * self.grow_threshold < need * 100
+    }
+
+    /// Whether the heap of `heap_size` words should be shrunk when only `need` words of it are
+    /// needed after a sweep
+    pub fn should_shrink(&self, heap_size: usize, need: usize) -> bool {
+        need * 100 < heap_size * self.shrink_threshold
+    }
+
+    /// `#{fibonacci_max => FibonacciMax, growth_percent => GrowthPercent,
+    /// grow_threshold => GrowThreshold, shrink_threshold => ShrinkThreshold}`
+    pub fn to_map(&self, process: &Process) -> Result<Term, Alloc> {
+        process.map_from_slice(&[
+            (
+                atom_unchecked("fibonacci_max"),
+                process.integer(self.fibonacci_max)?,
+            ),
+            (
+                atom_unchecked("grow_threshold"),
+                process.integer(self.grow_threshold)?,
+            ),
+            (
+                atom_unchecked("growth_percent"),
+                process.integer(self.growth_percent)?,
+            ),
+            (
+                atom_unchecked("shrink_threshold"),
+                process.integer(self.shrink_threshold)?,
+            ),
+        ])
+    }
+}
+
+/// The same sizing as the BEAM: Fibonacci-like growth until about 1M words, then 20% at a time,
+/// growing when more than 75% of the heap is needed, and shrinking when less than 25% is.
+impl Default for HeapSizing {
+    fn default() -> Self {
+        Self {
+            fibonacci_max: *fibonacci_heap_sizes().last().unwrap(),
+            growth_percent: 20,
+            grow_threshold: 75,
+            shrink_threshold: 25,
+        }
+    }
+}
+
+/// A map with any of `fibonacci_max`, `growth_percent`, `grow_threshold` and `shrink_threshold`,
+/// keeping the VM's heap sizing for the rest
+impl TryFrom<Term> for HeapSizing {
+    type Error = runtime::Exception;
+
+    fn try_from(term: Term) -> Result<Self, Self::Error> {
+        match term.to_typed_term().unwrap() {
+            TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+                TypedTerm::Map(map) => {
+                    let mut heap_sizing = self::heap_sizing();
+
+                    if let Some(fibonacci_max) = map.get(atom_unchecked("fibonacci_max")) {
+                        heap_sizing.fibonacci_max = fibonacci_max.try_into()?;
+                    }
+
+                    if let Some(growth_percent) = map.get(atom_unchecked("growth_percent")) {
+                        heap_sizing.growth_percent = growth_percent.try_into()?;
+                    }
+
+                    if let Some(grow_threshold) = map.get(atom_unchecked("grow_threshold")) {
+                        heap_sizing.grow_threshold = grow_threshold.try_into()?;
+                    }
+
+                    if let Some(shrink_threshold) = map.get(atom_unchecked("shrink_threshold")) {
+                        heap_sizing.shrink_threshold = shrink_threshold.try_into()?;
+                    }
+
+                    if heap_sizing.is_valid() {
+                        Ok(heap_sizing)
+                    } else {
+                        Err(badarg!())
+                    }
+                }
+                _ => Err(badarg!()),
+            },
+            _ => Err(badarg!()),
+        }
+    }
+}
+
+/// The heap sizing of the VM, which processes are spawned with
+pub fn heap_sizing() -> HeapSizing {
+    *RW_LOCK_HEAP_SIZING.read()
+}
+
+/// Sets the heap sizing of the VM.  Processes that are already alive keep their heap sizing.
+pub fn set_heap_sizing(heap_sizing: HeapSizing) {
+    assert!(
+        heap_sizing.is_valid(),
+        "heap sizing ({:?}) is not valid",
+        heap_sizing
+    );
+
+    *RW_LOCK_HEAP_SIZING.write() = heap_sizing;
+}
+
+lazy_static! {
+    static ref RW_LOCK_HEAP_SIZING: RwLock<HeapSizing> = RwLock::new(Default::default());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_next_heap_size_is_beam_sizes() {
+        let heap_sizing: HeapSizing = Default::default();
+
+        assert_eq!(heap_sizing.next_heap_size(0), 232);
+        assert_eq!(heap_sizing.next_heap_size(232), 374);
+        assert_eq!(heap_sizing.next_heap_size(10_000), 10_870);
+        assert_eq!(heap_sizing.next_heap_size(1_336_920), 1_604_304);
+    }
+
+    #[test]
+    fn next_heap_size_grows_by_percent_past_fibonacci_max() {
+        let heap_sizing = HeapSizing {
+            fibonacci_max: 980,
+            growth_percent: 50,
+            ..Default::default()
+        };
+
+        assert_eq!(heap_sizing.next_heap_size(0), 232);
+        assert_eq!(heap_sizing.next_heap_size(606), 980);
+        assert_eq!(heap_sizing.next_heap_size(980), 1470);
+        assert_eq!(heap_sizing.next_heap_size(1470), 2205);
+    }
+
+    #[test]
+    fn should_grow_or_shrink_past_thresholds() {
+        let heap_sizing = HeapSizing {
+            grow_threshold: 50,
+            shrink_threshold: 10,
+            ..Default::default()
+        };
+
+        assert!(!heap_sizing.should_grow(1000, 500));
+        assert!(heap_sizing.should_grow(1000, 501));
+        assert!(!heap_sizing.should_shrink(1000, 100));
+        assert!(heap_sizing.should_shrink(1000, 99));
+    }
+
+    #[test]
+    fn is_valid_requires_shrink_threshold_below_grow_threshold() {
+        assert!(HeapSizing::default().is_valid());
+        assert!(!HeapSizing {
+            shrink_threshold: 75,
+            ..Default::default()
+        }
+        .is_valid());
+        assert!(!HeapSizing {
+            fibonacci_max: 100,
+            ..Default::default()
+        }
+        .is_valid());
+        assert!(!HeapSizing {
+            growth_percent: 0,
+            ..Default::default()
+        }
+        .is_valid());
+    }
+}
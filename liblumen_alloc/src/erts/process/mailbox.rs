@@ -1,13 +1,18 @@
 use core::default::Default;
+use core::ptr;
+use core::sync::atomic::Ordering;
 
 use alloc::collections::vec_deque::Iter;
 use alloc::collections::VecDeque;
 
+use intrusive_collections::UnsafeRef;
+
 use crate::borrow::CloneToProcess;
 use crate::erts::exception::system::Alloc;
 use crate::erts::message::{self, Message};
+use crate::erts::process::gc::RootSet;
 use crate::erts::process::Process;
-use crate::erts::term::Term;
+use crate::erts::term::{Reference, Term};
 
 #[derive(Debug)]
 pub struct Mailbox {
@@ -15,12 +20,53 @@ pub struct Mailbox {
     seen: isize,
 
     cursor: usize,
+    /// The current receive has `after 0`, so it polls the mailbox instead of waiting
+    polling: bool,
+    /// Set by `recv_mark` when a reference is made, so that a receive that only matches messages
+    /// containing that reference skips the messages that were queued before it existed.
+    marker: Option<Marker>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Marker {
+    reference: Reference,
+    /// The index of the first message queued after `reference` was made
+    index: usize,
 }
 
 impl Mailbox {
     // Start receive implementation for the eir interpreter
-    pub fn recv_start(&self) {
+    pub fn recv_start(&mut self, polling: bool) {
         debug_assert!(self.cursor == 0);
+        self.polling = polling;
+    }
+    /// Whether the receive should time out instead of waiting when no message matches
+    pub fn recv_polling(&self) -> bool {
+        self.polling
+    }
+    /// Marks the end of the queue when `reference` is made, as no message queued before then can
+    /// contain it.
+    pub fn recv_mark(&mut self, reference: Reference) {
+        self.marker = Some(Marker {
+            reference,
+            index: self.len(),
+        });
+    }
+    /// Starts the current receive, which only matches messages containing `reference`, at the
+    /// messages queued after `reference` was marked with `recv_mark`, instead of scanning the
+    /// whole queue.  Returns `false` if `reference` isn't marked, so the whole queue is scanned.
+    pub fn recv_set(&mut self, reference: &Reference) -> bool {
+        match self.marker {
+            Some(Marker {
+                reference: ref marked_reference,
+                index,
+            }) if marked_reference == reference => {
+                self.cursor = index;
+
+                true
+            }
+            _ => false,
+        }
     }
     /// Important to remember that this might return a term in a heap
     /// fragment, and that it needs to be copied over to the process
@@ -44,6 +90,12 @@ impl Mailbox {
     pub fn recv_finish(&mut self, proc: &Process) {
         self.remove(self.cursor - 1, proc);
         self.cursor = 0;
+        self.polling = false;
+    }
+    /// Ends the receive without removing a message, as when it times out
+    pub fn recv_timeout(&mut self) {
+        self.cursor = 0;
+        self.polling = false;
     }
     // End receive implementation for the eir interpreter
 
@@ -85,6 +137,33 @@ impl Mailbox {
         }
     }
 
+    /// Turns the messages whose heap fragment is in the off-heap of the process into
+    /// `Message::Process`, as the collection about to run moves their data to the heap and frees
+    /// the fragments.
+    pub(super) fn merge_off_heap_messages(&mut self) {
+        for message in self.messages.iter_mut() {
+            let merged_data = match message {
+                Message::HeapFragment(message::HeapFragment {
+                    unsafe_ref_heap_fragment,
+                    data,
+                }) if unsafe_ref_heap_fragment.link.is_linked() => *data,
+                _ => continue,
+            };
+
+            *message = Message::Process(message::Process { data: merged_data });
+        }
+    }
+
+    /// Adds the data of the messages of the process to `rootset`.  Messages that own their heap
+    /// fragment are left out, as collections don't touch them until they are received.
+    pub(super) unsafe fn add_to_root_set(&mut self, rootset: &mut RootSet) {
+        for message in self.messages.iter_mut() {
+            if let Message::Process(message::Process { data }) = message {
+                rootset.push(data as *mut Term);
+            }
+        }
+    }
+
     /// Puts `message` into mailbox at end of receive queue.
     pub fn push(&mut self, message: Message) {
         self.messages.push_back(message);
@@ -104,14 +183,28 @@ impl Mailbox {
                 data,
             }) => match data.clone_to_heap(&mut process.acquire_heap()) {
                 Ok(heap_data) => {
-                    let mut off_heap = process.off_heap.lock();
-
-                    unsafe {
-                        let mut cursor =
-                            off_heap.cursor_mut_from_ptr(unsafe_ref_heap_fragment.as_ref());
-                        cursor
-                            .remove()
-                            .expect("HeapFragment was not in process's off_heap");
+                    if unsafe_ref_heap_fragment.link.is_linked() {
+                        // `data` is now on the heap, so the fragment can be freed before the
+                        // next collection
+                        let mut off_heap = process.off_heap.lock();
+
+                        let heap_fragment_ref = unsafe {
+                            let mut cursor =
+                                off_heap.cursor_mut_from_ptr(unsafe_ref_heap_fragment.as_ref());
+                            cursor
+                                .remove()
+                                .expect("HeapFragment was not in process's off_heap")
+                        };
+                        process
+                            .off_heap_size
+                            .fetch_sub(heap_fragment_ref.size(), Ordering::AcqRel);
+                        let heap_fragment_ptr = UnsafeRef::into_raw(heap_fragment_ref);
+                        unsafe { ptr::drop_in_place(heap_fragment_ptr) };
+                    } else {
+                        // the message owned the fragment and `data` is now on the heap
+                        let heap_fragment_ptr =
+                            UnsafeRef::into_raw(unsafe_ref_heap_fragment.clone());
+                        unsafe { ptr::drop_in_place(heap_fragment_ptr) };
                     }
 
                     self.decrement_seen();
@@ -127,6 +220,11 @@ impl Mailbox {
         })
     }
 
+    /// Removes the message at `index`.
+    ///
+    /// A message that was kept in a heap fragment outside the off-heap of `process`, as when the
+    /// `message_queue_data` is `OffHeap`, has its fragment attached to the off-heap, so that the
+    /// terms taken from the message stay valid until the next collection copies them to the heap.
     pub fn remove(&mut self, index: usize, process: &Process) {
         let message = self.messages.remove(index).unwrap();
 
@@ -135,19 +233,18 @@ impl Mailbox {
             ..
         }) = message
         {
-            let mut off_heap = process.off_heap.lock();
-
-            unsafe {
-                let mut cursor = off_heap.cursor_mut_from_ptr(unsafe_ref_heap_fragment.as_ref());
-                cursor
-                    .remove()
-                    .expect("HeapFragment was not in process's off_heap");
+            // A fragment in the off-heap already stays until the next collection
+            if !unsafe_ref_heap_fragment.link.is_linked() {
+                let heap_fragment_ptr = UnsafeRef::into_raw(unsafe_ref_heap_fragment);
+                process.attach_fragment(unsafe { &mut *heap_fragment_ptr });
             }
         }
 
         if (index as isize) <= self.seen {
             self.seen -= 1;
         }
+
+        self.removed_before_marker(index);
     }
 
     pub fn seen(&self) -> isize {
@@ -164,6 +261,35 @@ impl Mailbox {
         if 0 <= self.seen {
             self.seen -= 1;
         }
+
+        self.removed_before_marker(0);
+    }
+
+    fn removed_before_marker(&mut self, index: usize) {
+        if let Some(ref mut marker) = self.marker {
+            if index < marker.index {
+                marker.index -= 1;
+            }
+        }
+    }
+}
+
+/// Frees the heap fragments owned by the messages still queued when the process is dropped, as
+/// when it exits without receiving them.  Fragments in the off-heap of the process are left to it.
+impl Drop for Mailbox {
+    fn drop(&mut self) {
+        for message in self.messages.drain(..) {
+            if let Message::HeapFragment(message::HeapFragment {
+                unsafe_ref_heap_fragment,
+                ..
+            }) = message
+            {
+                if !unsafe_ref_heap_fragment.link.is_linked() {
+                    let heap_fragment_ptr = UnsafeRef::into_raw(unsafe_ref_heap_fragment);
+                    unsafe { ptr::drop_in_place(heap_fragment_ptr) };
+                }
+            }
+        }
     }
 }
 
@@ -173,6 +299,8 @@ impl Default for Mailbox {
             messages: Default::default(),
             seen: -1,
             cursor: 0,
+            polling: false,
+            marker: None,
         }
     }
 }
@@ -0,0 +1,69 @@
+use core::convert::{TryFrom, TryInto};
+
+use crate::erts::exception::runtime;
+use crate::erts::term::{atom_unchecked, Term, TypedTerm};
+
+/// The `max_heap_size` process flag
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MaxHeapSize {
+    /// The size, in words, that the heap, including the old generation and the stack, may not
+    /// exceed when it is collected.  `0` is no limit.
+    pub size: usize,
+    /// Whether the process is killed when its heap exceeds `size`
+    pub kill: bool,
+    /// Whether the error logger is told when the heap of the process exceeds `size`
+    pub error_logger: bool,
+}
+
+impl MaxHeapSize {
+    /// Whether a heap of `heap_size` words exceeds the limit
+    pub fn is_exceeded_by(&self, heap_size: usize) -> bool {
+        0 < self.size && self.size < heap_size
+    }
+}
+
+impl Default for MaxHeapSize {
+    fn default() -> Self {
+        Self {
+            size: 0,
+            kill: true,
+            error_logger: true,
+        }
+    }
+}
+
+/// Either the `size` alone, keeping the defaults for `kill` and `error_logger`, or a map with any of
+/// `size`, `kill` and `error_logger`
+impl TryFrom<Term> for MaxHeapSize {
+    type Error = runtime::Exception;
+
+    fn try_from(term: Term) -> Result<Self, Self::Error> {
+        match term.to_typed_term().unwrap() {
+            TypedTerm::SmallInteger(_) => Ok(Self {
+                size: term.try_into()?,
+                ..Default::default()
+            }),
+            TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+                TypedTerm::Map(map) => {
+                    let mut max_heap_size: Self = Default::default();
+
+                    if let Some(size) = map.get(atom_unchecked("size")) {
+                        max_heap_size.size = size.try_into()?;
+                    }
+
+                    if let Some(kill) = map.get(atom_unchecked("kill")) {
+                        max_heap_size.kill = kill.try_into()?;
+                    }
+
+                    if let Some(error_logger) = map.get(atom_unchecked("error_logger")) {
+                        max_heap_size.error_logger = error_logger.try_into()?;
+                    }
+
+                    Ok(max_heap_size)
+                }
+                _ => Err(badarg!()),
+            },
+            _ => Err(badarg!()),
+        }
+    }
+}
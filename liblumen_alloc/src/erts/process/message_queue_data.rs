@@ -0,0 +1,63 @@
+use core::convert::{TryFrom, TryInto};
+
+use crate::erts::exception::runtime;
+use crate::erts::term::{atom_unchecked, Atom, Term, TypedTerm};
+
+/// The `message_queue_data` process flag
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MessageQueueData {
+    /// Messages are copied to the heap of the process when possible, so queued messages are
+    /// collected with the rest of the heap.
+    OnHeap,
+    /// Messages are always copied to heap fragments that the garbage collector doesn't scan until
+    /// they are received, so that the cost of a collection doesn't grow with the queue.
+    OffHeap,
+}
+
+impl Default for MessageQueueData {
+    fn default() -> Self {
+        MessageQueueData::OnHeap
+    }
+}
+
+impl From<MessageQueueData> for Term {
+    fn from(message_queue_data: MessageQueueData) -> Self {
+        let name = match message_queue_data {
+            MessageQueueData::OnHeap => "on_heap",
+            MessageQueueData::OffHeap => "off_heap",
+        };
+
+        atom_unchecked(name)
+    }
+}
+
+impl TryFrom<Atom> for MessageQueueData {
+    type Error = runtime::Exception;
+
+    fn try_from(atom: Atom) -> Result<Self, Self::Error> {
+        match atom.name() {
+            "off_heap" => Ok(MessageQueueData::OffHeap),
+            "on_heap" => Ok(MessageQueueData::OnHeap),
+            _ => Err(badarg!()),
+        }
+    }
+}
+
+impl TryFrom<Term> for MessageQueueData {
+    type Error = runtime::Exception;
+
+    fn try_from(term: Term) -> Result<Self, Self::Error> {
+        term.to_typed_term().unwrap().try_into()
+    }
+}
+
+impl TryFrom<TypedTerm> for MessageQueueData {
+    type Error = runtime::Exception;
+
+    fn try_from(typed_term: TypedTerm) -> Result<Self, Self::Error> {
+        match typed_term {
+            TypedTerm::Atom(atom) => atom.try_into(),
+            _ => Err(badarg!()),
+        }
+    }
+}
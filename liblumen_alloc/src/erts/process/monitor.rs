@@ -1,9 +1,18 @@
-use crate::erts::term::{Atom, Pid};
+use alloc::vec::Vec;
+use core::ptr::{self, NonNull};
+use core::slice;
+
+use hashbrown::HashMap;
+
+use crate::borrow::CloneToProcess;
+use crate::erts::exception::system::Alloc;
+use crate::erts::term::{atom_unchecked, Atom, Pid, Reference, Term};
+use crate::erts::HeapFragment;
 
 pub enum Monitor {
     /// The monitor was created using a `Pid`, so the monitor message object should be the
     /// monitored `Process`'s `pid_term`
-    Pid { monitoring_pid: Pid },
+    Pid { monitoring_pid: Pid, tag: Tag },
     /// When monitoring a name, it does not matter if the name change after the monitor, the name
     /// passed to monitor is always returned, but the node name reflects the current node name.
     ///
@@ -47,14 +56,208 @@ pub enum Monitor {
     Name {
         monitoring_pid: Pid,
         monitored_name: Atom,
+        tag: Tag,
     },
 }
 
 impl Monitor {
     pub fn monitoring_pid(&self) -> &Pid {
         match self {
-            Self::Pid { monitoring_pid } => monitoring_pid,
+            Self::Pid { monitoring_pid, .. } => monitoring_pid,
             Self::Name { monitoring_pid, .. } => monitoring_pid,
         }
     }
+
+    pub fn tag(&self) -> &Tag {
+        match self {
+            Self::Pid { tag, .. } => tag,
+            Self::Name { tag, .. } => tag,
+        }
+    }
+}
+
+/// The first element of the `DOWN` message of a monitor: `'DOWN'`, unless another was given with
+/// the `{tag, Tag}` option of `monitor/3`.
+///
+/// The `DOWN` message is made when the monitored process exits, after the heap of the monitoring
+/// process may have been collected, so a tag that isn't immediate is copied to a heap fragment
+/// that the tag owns.
+#[derive(Debug)]
+pub struct Tag {
+    term: Term,
+    heap_fragment: Option<NonNull<HeapFragment>>,
+}
+
+impl Tag {
+    pub fn new(term: Term) -> Result<Self, Alloc> {
+        if term.is_boxed() || term.is_non_empty_list() {
+            let (heap_fragment_term, heap_fragment) = term.clone_to_fragment()?;
+
+            Ok(Self {
+                term: heap_fragment_term,
+                heap_fragment: Some(heap_fragment),
+            })
+        } else {
+            Ok(Self {
+                term,
+                heap_fragment: None,
+            })
+        }
+    }
+
+    pub fn term(&self) -> Term {
+        self.term
+    }
+}
+
+impl Default for Tag {
+    fn default() -> Self {
+        Self {
+            term: atom_unchecked("DOWN"),
+            heap_fragment: None,
+        }
+    }
+}
+
+// the heap fragment is only reached through the tag that owns it
+unsafe impl Send for Tag {}
+
+impl Drop for Tag {
+    fn drop(&mut self) {
+        if let Some(heap_fragment) = self.heap_fragment {
+            unsafe { ptr::drop_in_place(heap_fragment.as_ptr()) };
+        }
+    }
+}
+
+/// The monitors of or by a process, keyed by their reference.
+///
+/// Entries are kept densely in slots, with an index from reference to slot, so inserting and
+/// removing, as `monitor/2` and `demonitor/1` do, take constant time, and iterating, as sending
+/// `DOWN` messages does, takes time proportional to the current number of monitors.  Iterating a
+/// `HashMap` instead takes time proportional to its capacity, which stays at the most monitors the
+/// process ever had.  Removing an entry moves the last entry into its slot, and the storage shrinks
+/// when most entries have been removed.
+pub struct MonitorTable<T> {
+    entries: Vec<(Reference, T)>,
+    slot_by_reference: HashMap<Reference, usize>,
+}
+
+impl<T> MonitorTable<T> {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            slot_by_reference: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, reference: &Reference) -> Option<&T> {
+        self.slot_by_reference
+            .get(reference)
+            .map(|slot| &self.entries[*slot].1)
+    }
+
+    /// Inserts `value` for `reference`, returning the value it replaces
+    pub fn insert(&mut self, reference: Reference, value: T) -> Option<T> {
+        match self.slot_by_reference.get(&reference) {
+            Some(slot) => Some(core::mem::replace(&mut self.entries[*slot].1, value)),
+            None => {
+                self.slot_by_reference.insert(reference, self.entries.len());
+                self.entries.push((reference, value));
+
+                None
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The entries in no particular order
+    pub fn iter(&self) -> slice::Iter<(Reference, T)> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn remove(&mut self, reference: &Reference) -> Option<T> {
+        let slot = self.slot_by_reference.remove(reference)?;
+        let (_, value) = self.entries.swap_remove(slot);
+
+        if let Some((moved_reference, _)) = self.entries.get(slot) {
+            self.slot_by_reference.insert(*moved_reference, slot);
+        }
+
+        if Self::MIN_SHRINK_CAPACITY < self.entries.capacity()
+            && self.entries.len() < self.entries.capacity() / 4
+        {
+            self.entries.shrink_to_fit();
+            self.slot_by_reference.shrink_to_fit();
+        }
+
+        Some(value)
+    }
+
+    const MIN_SHRINK_CAPACITY: usize = 64;
+}
+
+impl<T> Default for MonitorTable<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::erts::scheduler;
+
+    #[test]
+    fn remove_moves_last_entry_into_slot() {
+        let mut table = MonitorTable::new();
+        let references: Vec<Reference> = (0..3)
+            .map(|number| Reference::new(scheduler::ID::new(1), number))
+            .collect();
+
+        for (value, reference) in references.iter().enumerate() {
+            assert_eq!(table.insert(*reference, value), None);
+        }
+
+        assert_eq!(table.remove(&references[0]), Some(0));
+        assert_eq!(table.remove(&references[0]), None);
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.get(&references[1]), Some(&1));
+        assert_eq!(table.get(&references[2]), Some(&2));
+        assert_eq!(table.remove(&references[2]), Some(2));
+        assert_eq!(table.remove(&references[1]), Some(1));
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn shrinks_when_most_entries_are_removed() {
+        let mut table = MonitorTable::new();
+        let references: Vec<Reference> = (0..1000)
+            .map(|number| Reference::new(scheduler::ID::new(1), number))
+            .collect();
+
+        for reference in references.iter() {
+            table.insert(*reference, ());
+        }
+
+        for reference in references.iter().skip(10) {
+            assert_eq!(table.remove(reference), Some(()));
+        }
+
+        assert_eq!(table.len(), 10);
+        assert!(table.entries.capacity() < 1000);
+        assert_eq!(table.iter().count(), 10);
+
+        for reference in references.iter().take(10) {
+            assert_eq!(table.get(reference), Some(&()));
+        }
+    }
 }
@@ -0,0 +1,52 @@
+//! Signals sent to a process
+//!
+//! Messages and exit signals are both delivered while the mailbox of the receiving process is
+//! locked, so they form one queue: signals from one process to another are delivered in the order
+//! they were sent, and a message sent before an exit signal is in the mailbox before the receiver
+//! exits or gets `{'EXIT', From, Reason}`.  Whether an exit signal is trapped is decided when it is
+//! delivered, not when it is sent, so it follows the `trap_exit` flag of the receiver at that time.
+
+use crate::erts::term::{atom_unchecked, Term};
+
+/// An exit signal from a linked process, `exit/2` or the runtime
+#[derive(Clone, Copy, Debug)]
+pub struct Exit {
+    /// The pid, local or external, of the sender, which is the `From` of the `{'EXIT', From,
+    /// Reason}` message that a receiver trapping exits gets.  `from` only has to live until the
+    /// signal is delivered, as it is copied into the message.
+    pub from: Term,
+    /// Copied to the receiver when it is delivered, like `from`
+    pub reason: Term,
+    /// Whether the receiver can trap the signal.  Untrappable signals, such as `exit(Pid, kill)`,
+    /// always make the receiver exit with `reason`, even if it is `normal`.
+    pub trappable: bool,
+}
+
+impl Exit {
+    /// The signal a process sends to the processes linked to it when it exits with `reason`
+    pub fn linked(from: Term, reason: Term) -> Self {
+        Self {
+            from,
+            reason,
+            trappable: true,
+        }
+    }
+
+    /// The signal `exit(Pid, reason)` sends, which is untrappable if `reason` is `kill`, and makes
+    /// the receiver exit with `killed` instead
+    pub fn exit2(from: Term, reason: Term) -> Self {
+        if reason == atom_unchecked("kill") {
+            Self {
+                from,
+                reason: atom_unchecked("killed"),
+                trappable: false,
+            }
+        } else {
+            Self {
+                from,
+                reason,
+                trappable: true,
+            }
+        }
+    }
+}
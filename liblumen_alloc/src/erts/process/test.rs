@@ -48,6 +48,80 @@ fn gc_fullsweep_after_tenuring_test() {
     tenuring_gc_test(process, true);
 }
 
+// This test ensures that a process that hibernates gives back the memory of its heap, even when
+// the heap started larger than the default size
+#[test]
+fn gc_shrink_heap_test() {
+    let heap_size = alloc::next_heap_size(10_000);
+    let process = process_with_heap(alloc::heap(heap_size).unwrap(), heap_size);
+
+    for _ in 0..1_000 {
+        process.binary_from_str("garbage").unwrap();
+    }
+
+    let ok = unsafe { Atom::try_from_str("ok").unwrap().as_term() };
+    let greeting_term = process.binary_from_str("hello world").unwrap();
+    let tuple_term = process.tuple_from_slice(&[ok, greeting_term]).unwrap();
+    let tuple_ptr = tuple_term.boxed_val();
+
+    let mut roots = [tuple_term];
+    process.shrink_heap(&mut roots).unwrap();
+
+    assert_eq!(process.young_heap_size(), alloc::default_heap_size());
+    assert!(!process.are_flags_set(ProcessFlags::ShrinkHeap));
+    verify_tuple_root(roots[0], tuple_ptr);
+}
+
+// This test ensures that a full sweep doesn't make the heap smaller than `min_heap_size`, even when
+// the live data would fit in a smaller one, and keeps the virtual heap at `min_bin_vheap_size`
+#[test]
+fn gc_min_heap_size_test() {
+    let process = process();
+    let min_heap_size = alloc::next_heap_size(10_000);
+
+    assert_eq!(
+        process.set_min_heap_size(min_heap_size),
+        alloc::default_heap_size()
+    );
+    assert_eq!(process.set_min_bin_vheap_size(100_000), 0);
+
+    let greeting_term = process.binary_from_str("hello world").unwrap();
+    let mut roots = [greeting_term];
+
+    process.set_flags(ProcessFlags::NeedFullSweep);
+    process.garbage_collect(0, &mut roots).unwrap();
+
+    assert_eq!(process.young_heap_size(), min_heap_size);
+    assert_eq!(process.min_bin_vheap_size(), 100_000);
+    assert_eq!(
+        process.acquire_heap().young.virtual_heap_unused(),
+        100_000 * mem::size_of::<usize>()
+    );
+}
+
+// This test ensures that a full sweep grows the heap through the sizes of the heap sizing of the
+// process instead of the VM's
+#[test]
+fn gc_heap_sizing_test() {
+    let process = process();
+    let doubling_heap_sizing = HeapSizing {
+        fibonacci_max: alloc::default_heap_size(),
+        growth_percent: 100,
+        ..Default::default()
+    };
+
+    assert_eq!(process.set_heap_sizing(doubling_heap_sizing), heap_sizing());
+
+    let greeting_term = process.binary_from_str("hello world").unwrap();
+    let mut roots = [greeting_term];
+
+    process.set_flags(ProcessFlags::NeedFullSweep);
+    process.garbage_collect(1_000, &mut roots).unwrap();
+
+    // doubled from the default heap size until `need` fits
+    assert_eq!(process.young_heap_size(), 8 * alloc::default_heap_size());
+}
+
 mod are_flags_set {
     use super::*;
 
@@ -97,6 +171,64 @@ mod traps_exit {
     }
 }
 
+mod wait_for_message {
+    use super::*;
+
+    use crate::erts::term::atom_unchecked;
+
+    #[test]
+    fn without_message_waits() {
+        let process = process();
+
+        assert!(process.wait_for_message());
+        assert_eq!(*process.status.read(), Status::Waiting);
+    }
+
+    #[test]
+    fn with_message_does_not_wait() {
+        let process = process();
+        process.send_from_self(atom_unchecked("message"));
+
+        assert!(!process.wait_for_message());
+        assert_ne!(*process.status.read(), Status::Waiting);
+    }
+}
+
+mod mailbox {
+    use super::*;
+
+    use core::sync::atomic::Ordering;
+
+    use crate::erts::term::atom_unchecked;
+
+    #[test]
+    fn exiting_with_off_heap_messages_queued_frees_their_fragments() {
+        let process = process();
+        process.set_message_queue_data(MessageQueueData::OffHeap);
+
+        let data = process
+            .tuple_from_slice(&[atom_unchecked("off_heap"), process.integer(0).unwrap()])
+            .unwrap();
+        process.send_from_other(data).unwrap();
+        process.send_from_other(data).unwrap();
+
+        // the messages own their fragments, which aren't part of the off-heap
+        assert_eq!(process.off_heap_size.load(Ordering::SeqCst), 0);
+
+        process.set_message_queue_data(MessageQueueData::OnHeap);
+        process.send_from_other(data).unwrap();
+
+        assert_ne!(process.off_heap_size.load(Ordering::SeqCst), 0);
+        assert_eq!(process.mailbox.lock().borrow().len(), 3);
+
+        process.exit();
+        assert!(process.is_exiting());
+
+        // each fragment is freed once, whether owned by its message or by the off-heap
+        mem::drop(process);
+    }
+}
+
 mod integer {
     use super::*;
 
@@ -601,13 +733,18 @@ fn tenuring_gc_test(process: Process, _perform_fullsweep: bool) {
 }
 
 fn process() -> Process {
+    let (heap, heap_size) = alloc::default_heap().unwrap();
+
+    process_with_heap(heap, heap_size)
+}
+
+fn process_with_heap(heap: *mut Term, heap_size: usize) -> Process {
     let init = Atom::try_from_str("init").unwrap();
     let initial_module_function_arity = Arc::new(ModuleFunctionArity {
         module: init,
         function: init,
         arity: 0,
     });
-    let (heap, heap_size) = alloc::default_heap().unwrap();
 
     let process = Process::new(
         Priority::Normal,
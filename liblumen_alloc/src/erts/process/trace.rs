@@ -0,0 +1,341 @@
+//! Tracing with `erlang:trace/3`: a traced process sends `{trace, Pid, Tag, ...}` messages about
+//! the events its flags select to its tracer.
+//!
+//! Trace messages are built in heap fragments, so that tracing never allocates on the heap of the
+//! traced process.  Trace messages for a tracer process are delivered by the runtime, which owns
+//! the registry of processes, through the function passed to `set_deliver`; those for a `Backend`
+//! are given to it directly.
+
+use core::fmt::{self, Debug};
+use core::mem::size_of;
+use core::ptr::{self, NonNull};
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use hashbrown::HashMap;
+
+use lazy_static::lazy_static;
+
+use liblumen_core::locks::RwLock;
+
+use crate::borrow::CloneToProcess;
+use crate::erts::exception::system::Alloc;
+use crate::erts::fragment::HeapFragment;
+use crate::erts::process::alloc::heap_alloc::HeapAlloc;
+use crate::erts::term::{atom_unchecked, to_word_size, AsTerm, Atom, Cons, Pid, Term, Tuple};
+
+/// Delivers `message`, which is in `heap_fragment`, to the `tracer` process.  The fragment is
+/// owned by the function, which frees it if `tracer` no longer exists.
+pub type Deliver = fn(tracer: Pid, heap_fragment: NonNull<HeapFragment>, message: Term);
+
+/// Sets how trace messages are delivered.  Until it is set, trace messages are dropped.
+pub fn set_deliver(deliver: Deliver) {
+    *RW_LOCK_OPTION_DELIVER.write() = Some(deliver);
+}
+
+/// A tracer implemented in Rust, in the style of an `erl_tracer` module, so that trace messages
+/// can be forwarded to host tooling, such as a file, a socket or a logger, without a tracer process
+/// in between.
+pub trait Backend: Send + Sync {
+    /// Called with each trace message, `{trace, Pid, Tag, ...}`, on the thread running the traced
+    /// process.  `message` is freed when this returns, so anything kept has to be copied out of it,
+    /// such as with `to_string`.
+    fn trace(&self, event: Event, message: Term);
+}
+
+/// Registers `backend` as `name`, so that `erlang:trace/3` can select it with `{tracer, Name}`,
+/// returning the backend previously registered as `name`.
+pub fn register_backend(name: Atom, backend: Arc<dyn Backend>) -> Option<Arc<dyn Backend>> {
+    RW_LOCK_BACKEND_BY_NAME.write().insert(name, backend)
+}
+
+/// The backend registered as `name`
+pub fn backend(name: Atom) -> Option<Arc<dyn Backend>> {
+    RW_LOCK_BACKEND_BY_NAME.read().get(&name).cloned()
+}
+
+/// The trace that processes start with, as set for `new` processes by `erlang:trace/3`
+pub fn new_process_trace() -> Option<Trace> {
+    RW_LOCK_OPTION_NEW_PROCESS_TRACE.read().clone()
+}
+
+/// Sets the trace that processes spawned from now on start with, returning the previous one.
+pub fn set_new_process_trace(trace: Option<Trace>) -> Option<Trace> {
+    core::mem::replace(&mut *RW_LOCK_OPTION_NEW_PROCESS_TRACE.write(), trace)
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Event {
+    /// `{trace, Pid, call, {Module, Function, Arguments}}`
+    Call,
+    /// `{trace, Pid, send, Message, To}`
+    Send,
+    /// `{trace, Pid, 'receive', Message}`
+    Receive,
+    /// `{trace, Pid, spawn, Pid2, {Module, Function, Arguments}}`
+    Spawn,
+    /// `{trace, Pid, exit, Reason}`
+    Exit,
+    /// `{trace, Pid, link, Pid2}`
+    Link,
+    /// `{trace, Pid, unlink, Pid2}`
+    Unlink,
+}
+
+impl Event {
+    fn tag(&self) -> Term {
+        let name = match self {
+            Event::Call => "call",
+            Event::Send => "send",
+            Event::Receive => "receive",
+            Event::Spawn => "spawn",
+            Event::Exit => "exit",
+            Event::Link => "link",
+            Event::Unlink => "unlink",
+        };
+
+        atom_unchecked(name)
+    }
+}
+
+/// The trace flags of `erlang:trace/3` that select which events are traced
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Flags {
+    /// `call`: `Event::Call`
+    pub call: bool,
+    /// `send`: `Event::Send`
+    pub send: bool,
+    /// `'receive'`: `Event::Receive`
+    pub receive: bool,
+    /// `procs`: `Event::Spawn`, `Event::Exit`, `Event::Link` and `Event::Unlink`
+    pub procs: bool,
+    /// `set_on_spawn`: processes spawned by the traced process get its trace
+    pub set_on_spawn: bool,
+}
+
+impl Flags {
+    pub fn is_empty(&self) -> bool {
+        !(self.call || self.send || self.receive || self.procs || self.set_on_spawn)
+    }
+
+    pub fn is_set(&self, event: Event) -> bool {
+        match event {
+            Event::Call => self.call,
+            Event::Send => self.send,
+            Event::Receive => self.receive,
+            Event::Spawn | Event::Exit | Event::Link | Event::Unlink => self.procs,
+        }
+    }
+
+    /// Sets the flags that are set in `other`
+    pub fn set(&mut self, other: Flags) {
+        self.call |= other.call;
+        self.send |= other.send;
+        self.receive |= other.receive;
+        self.procs |= other.procs;
+        self.set_on_spawn |= other.set_on_spawn;
+    }
+
+    /// Clears the flags that are set in `other`
+    pub fn clear(&mut self, other: Flags) {
+        self.call &= !other.call;
+        self.send &= !other.send;
+        self.receive &= !other.receive;
+        self.procs &= !other.procs;
+        self.set_on_spawn &= !other.set_on_spawn;
+    }
+}
+
+/// Where trace messages go
+#[derive(Clone)]
+pub enum Tracer {
+    /// Sent to the process
+    Process(Pid),
+    /// Given to the backend
+    Backend(Arc<dyn Backend>),
+}
+
+impl Debug for Tracer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Tracer::Process(pid) => f.debug_tuple("Process").field(pid).finish(),
+            Tracer::Backend(_) => f.debug_tuple("Backend").finish(),
+        }
+    }
+}
+
+impl Eq for Tracer {}
+
+impl PartialEq for Tracer {
+    fn eq(&self, other: &Tracer) -> bool {
+        match (self, other) {
+            (Tracer::Process(self_pid), Tracer::Process(other_pid)) => self_pid == other_pid,
+            (Tracer::Backend(self_backend), Tracer::Backend(other_backend)) => {
+                Arc::ptr_eq(self_backend, other_backend)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The trace of a process: who its trace messages are sent to and for which events
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Trace {
+    pub tracer: Tracer,
+    pub flags: Flags,
+}
+
+impl Trace {
+    /// The trace after setting (`how` is `true`) or clearing (`how` is `false`) `flags`.  Setting
+    /// flags also changes the tracer to `tracer`.  `None` once no flags remain.
+    pub fn apply(
+        option_trace: Option<Trace>,
+        how: bool,
+        tracer: Tracer,
+        flags: Flags,
+    ) -> Option<Trace> {
+        let trace = match (option_trace, how) {
+            (Some(mut trace), true) => {
+                trace.tracer = tracer;
+                trace.flags.set(flags);
+
+                trace
+            }
+            (None, true) => Trace { tracer, flags },
+            (Some(mut trace), false) => {
+                trace.flags.clear(flags);
+
+                trace
+            }
+            (None, false) => return None,
+        };
+
+        if trace.flags.is_empty() {
+            None
+        } else {
+            Some(trace)
+        }
+    }
+}
+
+// Crate
+
+/// Sends `{trace, tracee, Tag, arguments...}` to `tracer`
+pub(in crate::erts::process) fn deliver(
+    tracer: &Tracer,
+    tracee: Pid,
+    event: Event,
+    arguments: &[Term],
+) {
+    let need_in_words = Tuple::need_in_words_from_len(3 + arguments.len())
+        + arguments
+            .iter()
+            .map(|argument| argument.size_in_words())
+            .sum::<usize>();
+
+    deliver_from_fragment(tracer, event, need_in_words, |heap_fragment| {
+        let mut elements = Vec::with_capacity(3 + arguments.len());
+        elements.push(atom_unchecked("trace"));
+        elements.push(unsafe { tracee.as_term() });
+        elements.push(event.tag());
+
+        for argument in arguments {
+            elements.push(argument.clone_to_heap(heap_fragment)?);
+        }
+
+        heap_fragment.tuple_from_slice(&elements)
+    });
+}
+
+/// Sends `{trace, tracee, Tag, prefix..., {module, function, arguments}}` to `tracer`, for the
+/// events about calls, whose arguments aren't a list yet.
+pub(in crate::erts::process) fn deliver_module_function_arguments(
+    tracer: &Tracer,
+    tracee: Pid,
+    event: Event,
+    prefix: &[Term],
+    module: Atom,
+    function: Atom,
+    arguments: &[Term],
+) {
+    let need_in_words = Tuple::need_in_words_from_len(4 + prefix.len())
+        + prefix
+            .iter()
+            .map(|term| term.size_in_words())
+            .sum::<usize>()
+        + Tuple::need_in_words_from_len(3)
+        + to_word_size(size_of::<Cons>()) * arguments.len()
+        + arguments
+            .iter()
+            .map(|argument| argument.size_in_words())
+            .sum::<usize>();
+
+    deliver_from_fragment(tracer, event, need_in_words, |heap_fragment| {
+        let mut heap_fragment_arguments = Vec::with_capacity(arguments.len());
+
+        for argument in arguments {
+            heap_fragment_arguments.push(argument.clone_to_heap(heap_fragment)?);
+        }
+
+        let argument_list = heap_fragment.list_from_slice(&heap_fragment_arguments)?;
+        let module_function_arguments = heap_fragment.tuple_from_slice(&[
+            unsafe { module.as_term() },
+            unsafe { function.as_term() },
+            argument_list,
+        ])?;
+
+        let mut elements = Vec::with_capacity(4 + prefix.len());
+        elements.push(atom_unchecked("trace"));
+        elements.push(unsafe { tracee.as_term() });
+        elements.push(event.tag());
+
+        for term in prefix {
+            elements.push(term.clone_to_heap(heap_fragment)?);
+        }
+
+        elements.push(module_function_arguments);
+
+        heap_fragment.tuple_from_slice(&elements)
+    });
+}
+
+// Private
+
+fn deliver_from_fragment<F>(tracer: &Tracer, event: Event, need_in_words: usize, build: F)
+where
+    F: FnOnce(&mut HeapFragment) -> Result<Term, Alloc>,
+{
+    let option_deliver = match tracer {
+        Tracer::Process(_) => match *RW_LOCK_OPTION_DELIVER.read() {
+            Some(deliver) => Some(deliver),
+            None => return,
+        },
+        Tracer::Backend(_) => None,
+    };
+
+    // a trace message that can't be allocated is dropped instead of failing the traced process
+    if let Ok(mut non_null_heap_fragment) =
+        unsafe { HeapFragment::new_from_word_size(need_in_words) }
+    {
+        let heap_fragment = unsafe { non_null_heap_fragment.as_mut() };
+        // the fragment is sized to fit the message
+        let message = build(heap_fragment).unwrap();
+
+        match tracer {
+            Tracer::Process(pid) => option_deliver.unwrap()(*pid, non_null_heap_fragment, message),
+            Tracer::Backend(backend) => {
+                backend.trace(event, message);
+
+                unsafe { ptr::drop_in_place(non_null_heap_fragment.as_ptr()) };
+            }
+        }
+    }
+}
+
+lazy_static! {
+    static ref RW_LOCK_OPTION_DELIVER: RwLock<Option<Deliver>> = RwLock::new(None);
+    static ref RW_LOCK_OPTION_NEW_PROCESS_TRACE: RwLock<Option<Trace>> = RwLock::new(None);
+    static ref RW_LOCK_BACKEND_BY_NAME: RwLock<HashMap<Atom, Arc<dyn Backend>>> =
+        Default::default();
+}
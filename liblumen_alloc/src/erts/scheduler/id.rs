@@ -13,6 +13,17 @@ pub fn next() -> ID {
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub struct ID(usize);
 
+impl ID {
+    /// An `ID` that was not generated with `next`, such as one in a reference from another node
+    pub fn new(raw: usize) -> Self {
+        ID(raw)
+    }
+
+    pub fn as_usize(&self) -> usize {
+        self.0
+    }
+}
+
 impl Display for ID {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.0)
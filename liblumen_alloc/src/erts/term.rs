@@ -14,6 +14,8 @@ pub mod resource;
 mod term;
 pub(in crate::erts) mod tuple;
 mod typed_term;
+#[cfg(feature = "term_debug")]
+pub mod verify;
 
 pub use atom::*;
 pub use binary::*;
@@ -1,20 +1,17 @@
+mod table;
+
 use core::cmp;
 use core::convert::{TryFrom, TryInto};
 use core::fmt::{self, Debug, Display, Write};
-use core::mem;
-use core::ptr;
-use core::slice;
 use core::str;
+use core::sync::atomic::{AtomicUsize, Ordering};
 
+use alloc::string::String;
 use alloc::vec::Vec;
 
-use hashbrown::HashMap;
 use lazy_static::lazy_static;
 
-use liblumen_arena::DroplessArena;
-
-use liblumen_core::locks::RwLock;
-
+use self::table::AtomTable;
 use super::{AsTerm, Term, TypeError, TypedTerm};
 
 /// The maximum number of atoms allowed
@@ -23,14 +20,37 @@ use super::{AsTerm, Term, TypeError, TypedTerm};
 /// tagged in their highest 6 bits, so they are unusable.
 pub const MAX_ATOMS: usize = usize::max_value() >> 6;
 
-/// The maximum length of an atom (255)
-pub const MAX_ATOM_LENGTH: usize = u16::max_value() as usize;
+/// The maximum number of atoms when it isn't set, the same as the BEAM
+pub const DEFAULT_MAX_ATOMS: usize = 1_048_576;
+
+/// The maximum length of an atom, in characters, as names can be any UTF-8
+pub const MAX_ATOM_LENGTH: usize = 255;
 
 lazy_static! {
     /// The atom table used by the runtime system
-    static ref ATOMS: RwLock<AtomTable> = Default::default();
+    static ref ATOMS: AtomTable = Default::default();
+}
+
+/// The maximum number of atoms that can be created, as set with `+t` on the BEAM.  Creating an
+/// atom past it fails with `system_limit`.
+pub fn max_atoms() -> usize {
+    MAX_ATOM_COUNT.load(Ordering::SeqCst)
+}
+
+/// Sets the maximum number of atoms.  Atoms that already exist past a lower maximum stay, but no
+/// more can be created.
+pub fn set_max_atoms(max: usize) {
+    assert!(
+        0 < max && max <= MAX_ATOMS,
+        "maximum number of atoms must be positive and at most {}",
+        MAX_ATOMS
+    );
+
+    MAX_ATOM_COUNT.store(max, Ordering::SeqCst);
 }
 
+static MAX_ATOM_COUNT: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_ATOMS);
+
 /// An interned string, represented in memory as a tagged integer id.
 ///
 /// This struct contains the untagged id
@@ -49,23 +69,60 @@ impl Atom {
     /// Returns the string representation of this atom
     #[inline]
     pub fn name(&self) -> &'static str {
-        ATOMS.read().get_name(self.0).unwrap()
+        ATOMS.get_name(self.0).unwrap()
     }
 
-    /// Creates a new atom from a slice of bytes interpreted as Latin-1.
+    /// Creates a new atom from a slice of bytes interpreted as Latin-1, where each byte is the
+    /// character with that code point.
     ///
     /// Returns `Err` if the atom name is invalid or the table overflows
     #[inline]
     pub fn try_from_latin1_bytes(name: &[u8]) -> Result<Self, AtomError> {
-        Self::try_from_str(str::from_utf8(name).unwrap())
+        if name.is_ascii() {
+            Self::try_from_str(unsafe { str::from_utf8_unchecked(name) })
+        } else {
+            Self::try_from_str(latin1_to_string(name))
+        }
     }
 
     /// Like `try_from_latin1_bytes`, but requires that the atom already exists
     ///
+    /// ASCII bytes are looked up in the atom table directly, without converting them to a `str`
+    /// first, as decoders call this for every key they map to an atom.
+    ///
     /// Returns `Err` if the atom does not exist
     #[inline]
     pub fn try_from_latin1_bytes_existing(name: &[u8]) -> Result<Self, AtomError> {
-        Self::try_from_str_existing(str::from_utf8(name).unwrap())
+        if name.is_ascii() {
+            if let Some(id) = ATOMS.get_id_from_bytes(name) {
+                return Ok(Atom(id));
+            }
+            Err(AtomError(AtomErrorKind::NonExistent))
+        } else {
+            Self::try_from_str_existing(latin1_to_string(name))
+        }
+    }
+
+    /// Creates a new atom from a slice of bytes that are UTF-8.
+    ///
+    /// Returns `Err` if the bytes are not UTF-8, the atom name is invalid or the table overflows
+    #[inline]
+    pub fn try_from_utf8_bytes(name: &[u8]) -> Result<Self, AtomError> {
+        let s = str::from_utf8(name).map_err(|_| AtomError(AtomErrorKind::InvalidUtf8))?;
+
+        Self::try_from_str(s)
+    }
+
+    /// Like `try_from_utf8_bytes`, but requires that the atom already exists
+    ///
+    /// Returns `Err` if the bytes are not UTF-8 or the atom does not exist
+    #[inline]
+    pub fn try_from_utf8_bytes_existing(name: &[u8]) -> Result<Self, AtomError> {
+        if let Some(id) = ATOMS.get_id_from_bytes(name) {
+            return Ok(Atom(id));
+        }
+        str::from_utf8(name).map_err(|_| AtomError(AtomErrorKind::InvalidUtf8))?;
+        Err(AtomError(AtomErrorKind::NonExistent))
     }
 
     /// Creates a new atom from a `str`.
@@ -75,10 +132,7 @@ impl Atom {
     pub fn try_from_str<S: AsRef<str>>(s: S) -> Result<Self, AtomError> {
         let name = s.as_ref();
         Self::validate(name)?;
-        if let Some(id) = ATOMS.read().get_id(name) {
-            return Ok(Atom(id));
-        }
-        let id = ATOMS.write().get_id_or_insert(name)?;
+        let id = ATOMS.get_id_or_insert(name)?;
         Ok(Atom(id))
     }
 
@@ -89,7 +143,7 @@ impl Atom {
     pub fn try_from_str_existing<S: AsRef<str>>(s: S) -> Result<Self, AtomError> {
         let name = s.as_ref();
         Self::validate(name)?;
-        if let Some(id) = ATOMS.read().get_id(name) {
+        if let Some(id) = ATOMS.get_id(name) {
             return Ok(Atom(id));
         }
         Err(AtomError(AtomErrorKind::NonExistent))
@@ -109,10 +163,25 @@ impl Atom {
         Self(id)
     }
 
+    /// Every atom, in the order they were created, so that dumps of the atom table are the same
+    /// for the same workload
+    pub fn all() -> Vec<Atom> {
+        (0..Self::count()).map(Atom).collect()
+    }
+
+    /// The number of atoms that have been created
+    pub fn count() -> usize {
+        ATOMS.len()
+    }
+
     fn validate(name: &str) -> Result<(), AtomError> {
-        let len = name.len();
-        if len > MAX_ATOM_LENGTH {
-            return Err(AtomError(AtomErrorKind::InvalidLength(len)));
+        // at most 4 bytes per character, so only long names have to be counted
+        if name.len() > MAX_ATOM_LENGTH {
+            let len = name.chars().count();
+
+            if len > MAX_ATOM_LENGTH {
+                return Err(AtomError(AtomErrorKind::InvalidLength(len)));
+            }
         }
         Ok(())
     }
@@ -131,7 +200,7 @@ impl Display for Atom {
 
 impl Debug for Atom {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if let Some(name) = ATOMS.read().get_name(self.0) {
+        if let Some(name) = ATOMS.get_name(self.0) {
             f.write_str(":\"")?;
             name.chars()
                 .flat_map(char::escape_default)
@@ -190,10 +259,18 @@ impl TryFrom<TypedTerm> for Atom {
 #[derive(Debug)]
 pub struct AtomError(AtomErrorKind);
 
+impl AtomError {
+    pub fn kind(&self) -> AtomErrorKind {
+        self.0
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AtomErrorKind {
     TooManyAtoms,
     InvalidLength(usize),
+    /// The name was given as bytes that are not UTF-8
+    InvalidUtf8,
     NonExistent,
 }
 
@@ -203,13 +280,14 @@ impl Display for AtomError {
             AtomErrorKind::TooManyAtoms => write!(
                 f,
                 "exceeded system limit: maximum number of atoms ({})",
-                MAX_ATOMS
+                max_atoms()
             ),
             AtomErrorKind::InvalidLength(len) => write!(
                 f,
                 "invalid atom, length is {}, maximum length is {}",
                 len, MAX_ATOM_LENGTH
             ),
+            AtomErrorKind::InvalidUtf8 => write!(f, "invalid atom, name is not UTF-8"),
             AtomErrorKind::NonExistent => {
                 write!(f, "tried to convert to an atom that doesn't exist")
             }
@@ -217,83 +295,10 @@ impl Display for AtomError {
     }
 }
 
-struct AtomTable {
-    ids: HashMap<&'static str, usize>,
-    names: Vec<&'static str>,
-    arena: DroplessArena,
+/// Each byte is the character with that code point, so characters over 127 become 2 bytes of UTF-8
+fn latin1_to_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|&byte| byte as char).collect()
 }
-impl AtomTable {
-    fn new(names: &[&'static str]) -> Self {
-        let len = names.len();
-        let mut table = Self {
-            ids: HashMap::with_capacity(len),
-            names: Vec::with_capacity(len),
-            arena: DroplessArena::default(),
-        };
-        let interned_names = &mut table.names;
-        for name in names {
-            table.ids.entry(name).or_insert_with(|| {
-                let id = interned_names.len();
-                interned_names.push(name);
-                id
-            });
-        }
-        table
-    }
-
-    fn get_id(&self, name: &str) -> Option<usize> {
-        self.ids.get(name).cloned()
-    }
-
-    fn get_name(&self, id: usize) -> Option<&'static str> {
-        self.names.get(id).cloned()
-    }
-
-    fn get_id_or_insert(&mut self, name: &str) -> Result<usize, AtomError> {
-        match self.get_id(name) {
-            Some(existing_id) => Ok(existing_id),
-            None => unsafe { self.insert(name) },
-        }
-    }
-
-    // Unsafe because `name` should already have been checked as not existing while holding a
-    // `mut reference`.
-    unsafe fn insert(&mut self, name: &str) -> Result<usize, AtomError> {
-        let id = self.names.len();
-        if id > MAX_ATOMS {
-            return Err(AtomError(AtomErrorKind::TooManyAtoms));
-        }
-
-        let size = name.len();
-
-        let s = if size > 0 {
-            // Copy string into arena
-            let ptr = self.arena.alloc_raw(size, mem::align_of::<u8>());
-            ptr::copy_nonoverlapping(name as *const _ as *const u8, ptr, size);
-            let bytes = slice::from_raw_parts(ptr, size);
-
-            str::from_utf8_unchecked(bytes)
-        } else {
-            ""
-        };
-
-        // Push into id map
-        self.ids.insert(s, id);
-        self.names.push(s);
-
-        Ok(id)
-    }
-}
-impl Default for AtomTable {
-    fn default() -> Self {
-        let atoms = &["true", "false", "undefined", "nil", "ok", "error"];
-        AtomTable::new(atoms)
-    }
-}
-
-/// This is safe to implement because the only usage is the ATOMS static, which is wrapped in an
-/// `RwLock`, but it is _not_ `Sync` in general, so don't try and use it as such in other situations
-unsafe impl Sync for AtomTable {}
 
 pub enum Encoding {
     Latin1,
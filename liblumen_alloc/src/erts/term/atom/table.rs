@@ -0,0 +1,300 @@
+//! The table of atoms, from their ids to their names and back.
+//!
+//! Atoms are read far more often than they are created, as `Atom::name` is called whenever an atom
+//! is compared, printed or converted, so reads don't lock.  Names are kept in segments that are
+//! never moved or freed, and the index from names to ids is an open-addressed table that is
+//! replaced by one twice the size, instead of being resized in place, when it is half full.
+//! Replaced indices are never freed either, as a reader may still be probing them, which at most
+//! doubles the memory of the index.  Only creating an atom locks, so that processes creating the
+//! same atom at once get the same id.
+
+use core::mem;
+use core::ptr;
+use core::slice;
+use core::str;
+use core::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use liblumen_arena::DroplessArena;
+
+use liblumen_core::locks::Mutex;
+
+use super::{max_atoms, AtomError, AtomErrorKind};
+
+pub struct AtomTable {
+    /// Pointers to the first name of each segment, or null for the segments not allocated yet
+    segments: Box<[AtomicPtr<&'static str>]>,
+    /// The number of atoms, and so the id of the next one
+    len: AtomicUsize,
+    index: AtomicPtr<Index>,
+    /// Holds the names, and is locked while an atom is created
+    arena: Mutex<DroplessArena>,
+}
+
+impl AtomTable {
+    pub fn new(names: &[&str]) -> Self {
+        let table = Self {
+            segments: (0..SEGMENTS)
+                .map(|_| AtomicPtr::new(ptr::null_mut()))
+                .collect::<Vec<_>>()
+                .into_boxed_slice(),
+            len: AtomicUsize::new(0),
+            index: AtomicPtr::new(Box::into_raw(Box::new(Index::with_capacity(
+                FIRST_SEGMENT_LEN,
+            )))),
+            arena: Default::default(),
+        };
+
+        for name in names {
+            table.get_id_or_insert(name).unwrap();
+        }
+
+        table
+    }
+
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Acquire)
+    }
+
+    pub fn get_id(&self, name: &str) -> Option<usize> {
+        self.get_id_from_bytes(name.as_bytes())
+    }
+
+    pub fn get_id_from_bytes(&self, bytes: &[u8]) -> Option<usize> {
+        let index = unsafe { &*self.index.load(Ordering::Acquire) };
+        let mask = index.mask();
+        let mut slot = hash(bytes) & mask;
+
+        loop {
+            match index.slots[slot].load(Ordering::Acquire) {
+                EMPTY => return None,
+                id_plus_one => {
+                    let id = id_plus_one - 1;
+
+                    if unsafe { self.get_name_unchecked(id) }.as_bytes() == bytes {
+                        return Some(id);
+                    }
+                }
+            }
+
+            slot = (slot + 1) & mask;
+        }
+    }
+
+    pub fn get_name(&self, id: usize) -> Option<&'static str> {
+        if id < self.len() {
+            Some(unsafe { self.get_name_unchecked(id) })
+        } else {
+            None
+        }
+    }
+
+    pub fn get_id_or_insert(&self, name: &str) -> Result<usize, AtomError> {
+        if let Some(id) = self.get_id(name) {
+            return Ok(id);
+        }
+
+        let arena = self.arena.lock();
+
+        // another thread may have created the atom while this one waited for the lock
+        if let Some(id) = self.get_id(name) {
+            return Ok(id);
+        }
+
+        let id = self.len.load(Ordering::Relaxed);
+
+        if max_atoms() <= id {
+            return Err(AtomError(AtomErrorKind::TooManyAtoms));
+        }
+
+        let interned_name = unsafe { intern(&arena, name) };
+
+        let (segment, offset) = location(id);
+        let mut segment_ptr = self.segments[segment].load(Ordering::Relaxed);
+
+        if segment_ptr.is_null() {
+            let names = vec![""; segment_len(segment)].into_boxed_slice();
+            segment_ptr = Box::into_raw(names) as *mut &'static str;
+            self.segments[segment].store(segment_ptr, Ordering::Release);
+        }
+
+        unsafe { segment_ptr.add(offset).write(interned_name) };
+        self.len.store(id + 1, Ordering::Release);
+        self.index_insert(id, interned_name);
+
+        Ok(id)
+    }
+
+    // Private
+
+    /// `id` has to be less than `len`, or have been read from the index
+    unsafe fn get_name_unchecked(&self, id: usize) -> &'static str {
+        let (segment, offset) = location(id);
+        let segment_ptr = self.segments[segment].load(Ordering::Acquire);
+
+        *segment_ptr.add(offset)
+    }
+
+    /// Only called while `arena` is locked
+    fn index_insert(&self, id: usize, name: &str) {
+        let index = unsafe { &*self.index.load(Ordering::Relaxed) };
+
+        if index.slots.len() < (id + 1) * 2 {
+            let grown_index = Index::with_capacity(index.slots.len() * 2);
+
+            for existing_id in 0..id {
+                let existing_name = unsafe { self.get_name_unchecked(existing_id) };
+                grown_index.insert(existing_id, existing_name);
+            }
+
+            grown_index.insert(id, name);
+
+            self.index
+                .store(Box::into_raw(Box::new(grown_index)), Ordering::Release);
+        } else {
+            index.insert(id, name);
+        }
+    }
+}
+
+impl Default for AtomTable {
+    fn default() -> Self {
+        AtomTable::new(&["true", "false", "undefined", "nil", "ok", "error"])
+    }
+}
+
+// `arena` is only used while it is locked, and everything else is atomic or never changes once it
+// is published
+unsafe impl Send for AtomTable {}
+unsafe impl Sync for AtomTable {}
+
+const EMPTY: usize = 0;
+
+/// The number of names in the first segment.  Each segment after it is twice the size of the one
+/// before it.
+const FIRST_SEGMENT_LEN: usize = 1024;
+
+/// Enough segments that the last one ends past `MAX_ATOMS`
+const SEGMENTS: usize = mem::size_of::<usize>() * 8 - 10;
+
+struct Index {
+    /// The id + 1 of the atom in each slot, so that `EMPTY` slots are 0
+    slots: Box<[AtomicUsize]>,
+}
+
+impl Index {
+    /// `capacity` has to be a power of 2
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: (0..capacity)
+                .map(|_| AtomicUsize::new(EMPTY))
+                .collect::<Vec<_>>()
+                .into_boxed_slice(),
+        }
+    }
+
+    fn insert(&self, id: usize, name: &str) {
+        let mask = self.mask();
+        let mut slot = hash(name.as_bytes()) & mask;
+
+        while self.slots[slot].load(Ordering::Relaxed) != EMPTY {
+            slot = (slot + 1) & mask;
+        }
+
+        self.slots[slot].store(id + 1, Ordering::Release);
+    }
+
+    fn mask(&self) -> usize {
+        self.slots.len() - 1
+    }
+}
+
+/// FNV-1a, which is quick for short keys like atom names
+fn hash(bytes: &[u8]) -> usize {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+
+    hash as usize
+}
+
+unsafe fn intern(arena: &DroplessArena, name: &str) -> &'static str {
+    let size = name.len();
+
+    if size > 0 {
+        let ptr = arena.alloc_raw(size, mem::align_of::<u8>());
+        ptr::copy_nonoverlapping(name.as_ptr(), ptr, size);
+        let bytes = slice::from_raw_parts(ptr, size);
+
+        str::from_utf8_unchecked(bytes)
+    } else {
+        ""
+    }
+}
+
+/// The segment and the offset in it of the name of the atom with `id`
+fn location(id: usize) -> (usize, usize) {
+    let n = id / FIRST_SEGMENT_LEN + 1;
+    let segment = (mem::size_of::<usize>() * 8 - 1) - (n.leading_zeros() as usize);
+    let offset = id - FIRST_SEGMENT_LEN * ((1 << segment) - 1);
+
+    (segment, offset)
+}
+
+fn segment_len(segment: usize) -> usize {
+    FIRST_SEGMENT_LEN << segment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn location_is_in_segment() {
+        assert_eq!(location(0), (0, 0));
+        assert_eq!(location(FIRST_SEGMENT_LEN - 1), (0, FIRST_SEGMENT_LEN - 1));
+        assert_eq!(location(FIRST_SEGMENT_LEN), (1, 0));
+        assert_eq!(
+            location(3 * FIRST_SEGMENT_LEN - 1),
+            (1, 2 * FIRST_SEGMENT_LEN - 1)
+        );
+        assert_eq!(location(3 * FIRST_SEGMENT_LEN), (2, 0));
+    }
+
+    #[test]
+    fn get_id_or_insert_is_found_after_index_grows() {
+        let table = AtomTable::new(&[]);
+        let names: Vec<String> = (0..(2 * FIRST_SEGMENT_LEN))
+            .map(|i| format!("atom{}", i))
+            .collect();
+
+        for (id, name) in names.iter().enumerate() {
+            assert_eq!(table.get_id_or_insert(name).unwrap(), id);
+        }
+
+        for (id, name) in names.iter().enumerate() {
+            assert_eq!(table.get_id(name), Some(id));
+            assert_eq!(table.get_name(id), Some(name.as_str()));
+        }
+
+        assert_eq!(table.get_id("atom_not_inserted"), None);
+        assert_eq!(table.get_name(names.len()), None);
+    }
+
+    #[test]
+    fn utf8_names_round_trip() {
+        let table = AtomTable::new(&[]);
+        let id = table.get_id_or_insert("héllo_wörld_λ").unwrap();
+
+        assert_eq!(table.get_name(id), Some("héllo_wörld_λ"));
+        assert_eq!(
+            table.get_id_from_bytes("héllo_wörld_λ".as_bytes()),
+            Some(id)
+        );
+    }
+}
@@ -4,6 +4,7 @@ mod match_context;
 pub mod maybe_aligned_maybe_binary;
 mod process;
 mod sub;
+mod view;
 
 use core::mem;
 use core::ptr;
@@ -17,9 +18,10 @@ use crate::mem::bit_size_of;
 use super::*;
 
 pub use heap::HeapBin;
-pub use match_context::MatchContext;
+pub use match_context::{MatchBuffer, MatchContext};
 pub use process::ProcBin;
 pub use sub::{Original, SubBinary};
+pub use view::BinaryView;
 
 struct PartialByteBitIter {
     byte: u8,
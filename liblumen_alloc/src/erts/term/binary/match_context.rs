@@ -73,17 +73,20 @@ impl MatchBuffer {
         let bin_ptr = original.boxed_val();
         let bin = unsafe { *bin_ptr };
 
-        let (base, full_byte_bit_len, byte_offset, bit_offset, partial_byte_bit_len) =
+        // A subbinary is matched in its original, between its offsets, so that the original is
+        // the binary that garbage collection moves the match buffer along with
+        let (original, base, full_byte_bit_len, byte_offset, bit_offset, partial_byte_bit_len) =
             if bin.is_procbin() {
                 let pb = unsafe { &*(bin_ptr as *mut ProcBin) };
-                (pb.bytes(), pb.full_byte_len() * 8, 0, 0, 0)
+                (original, pb.bytes(), pb.full_byte_len() * 8, 0, 0, 0)
             } else if bin.is_heapbin() {
                 let hb = unsafe { &*(bin_ptr as *mut HeapBin) };
-                (hb.bytes(), hb.full_byte_len() * 8, 0, 0, 0)
+                (original, hb.bytes(), hb.full_byte_len() * 8, 0, 0, 0)
             } else {
                 assert!(bin.is_subbinary_header());
                 let sb = unsafe { &*(bin_ptr as *mut SubBinary) };
                 (
+                    sb.original(),
                     sb.bytes(),
                     sb.full_byte_len() * 8,
                     sb.byte_offset(),
@@ -100,6 +103,38 @@ impl MatchBuffer {
             bit_len: full_byte_bit_len + improper_bit_offset + (partial_byte_bit_len as usize),
         }
     }
+
+    /// The heap binary or process binary being matched
+    #[inline]
+    pub fn original(&self) -> Term {
+        self.original
+    }
+
+    /// The bytes of `original` up to the end of the match
+    #[inline]
+    pub fn bytes(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.base, num_bytes(self.bit_len)) }
+    }
+
+    /// The offset in bits, from the start of `bytes`, of the next bit to match
+    #[inline]
+    pub fn bit_offset(&self) -> usize {
+        self.bit_offset
+    }
+
+    /// The number of bits left to match
+    #[inline]
+    pub fn remaining_bit_len(&self) -> usize {
+        self.bit_len - self.bit_offset
+    }
+
+    /// Moves past `bit_len` bits that were matched
+    #[inline]
+    pub fn skip(&mut self, bit_len: usize) {
+        assert!(bit_len <= self.remaining_bit_len());
+
+        self.bit_offset += bit_len;
+    }
 }
 
 /// Used in match contexts
@@ -134,11 +169,31 @@ impl MatchContext {
         }
     }
 
+    /// Create a new MatchContext that continues the match in `buffer`
+    #[inline]
+    pub fn from_buffer(buffer: MatchBuffer) -> Self {
+        Self {
+            header: Term::make_header(arity_of::<Self>(), Term::FLAG_MATCH_CTX),
+            buffer,
+            save_offset: None,
+        }
+    }
+
     #[inline]
     pub unsafe fn from_raw(ptr: *mut MatchContext) -> Self {
         *ptr
     }
 
+    #[inline]
+    pub fn buffer(&self) -> &MatchBuffer {
+        &self.buffer
+    }
+
+    #[inline]
+    pub fn buffer_mut(&mut self) -> &mut MatchBuffer {
+        &mut self.buffer
+    }
+
     /// Used by garbage collection to get a pointer to the original
     /// term in order to place/modify move markers
     #[inline]
@@ -0,0 +1,252 @@
+use core::slice;
+
+use alloc::borrow::Cow;
+
+use crate::erts::process::{Process, ProcessFlags};
+use crate::erts::term::binary::aligned_binary::AlignedBinary;
+use crate::erts::term::binary::maybe_aligned_maybe_binary::MaybeAlignedMaybeBinary;
+use crate::erts::term::binary::{IterableBitstring, MaybePartialByte, SubBinary};
+use crate::erts::term::{Term, TypeError, TypedTerm};
+
+/// A view of the bytes of a heap binary, reference-counted binary or sub-binary, so that native
+/// code can read them without copying them into a `Vec<u8>` first.
+///
+/// Garbage collection can move heap binaries, and the binaries that sub-binaries are slices of,
+/// so the view disables garbage collection of `process` until it is dropped.  The bytes borrowed
+/// from the view can't outlive it.  Views that are alive at the same time should be dropped in
+/// the reverse order that they were made, as they are when they are locals.
+pub struct BinaryView<'p> {
+    process: &'p Process,
+    binary: Term,
+    /// Whether the view disabled garbage collection, and so should enable it again when dropped
+    disabled_gc: bool,
+}
+
+impl<'p> BinaryView<'p> {
+    /// A view of `binary`, which is on the heap of `process`.  Match contexts and terms that
+    /// aren't bitstrings are a `TypeError`.
+    pub fn new(process: &'p Process, binary: Term) -> Result<Self, TypeError> {
+        match binary.to_typed_term().unwrap() {
+            TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+                TypedTerm::HeapBinary(_) | TypedTerm::ProcBin(_) | TypedTerm::SubBinary(_) => {
+                    let disabled_gc = !process.are_flags_set(ProcessFlags::DisableGC);
+
+                    if disabled_gc {
+                        process.set_flags(ProcessFlags::DisableGC);
+                    }
+
+                    Ok(Self {
+                        process,
+                        binary,
+                        disabled_gc,
+                    })
+                }
+                _ => Err(TypeError),
+            },
+            _ => Err(TypeError),
+        }
+    }
+
+    /// The full bytes, borrowed when they start on a byte boundary.  Only a sub-binary that
+    /// starts part way through a byte has its full bytes copied.  Any bits in a final partial
+    /// byte are not included; use `partial_byte` for them.
+    pub fn full_bytes(&self) -> Cow<[u8]> {
+        match self.as_bytes() {
+            Some(bytes) => Cow::Borrowed(bytes),
+            None => Cow::Owned(self.sub_binary().unwrap().full_byte_iter().collect()),
+        }
+    }
+
+    /// The full bytes borrowed without copying, or `None` if this is a sub-binary that starts
+    /// part way through a byte.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        let (ptr, len) = match self.typed_term() {
+            TypedTerm::HeapBinary(heap_binary) => {
+                let bytes = heap_binary.as_bytes();
+
+                (bytes.as_ptr(), bytes.len())
+            }
+            TypedTerm::ProcBin(process_binary) => {
+                let bytes = process_binary.as_bytes();
+
+                (bytes.as_ptr(), bytes.len())
+            }
+            TypedTerm::SubBinary(subbinary) => {
+                if subbinary.is_aligned() {
+                    let bytes = unsafe { subbinary.as_bytes() };
+
+                    (bytes.as_ptr(), bytes.len())
+                } else {
+                    return None;
+                }
+            }
+            _ => unreachable!(),
+        };
+
+        // The bytes are not in the typed term, which is a copy, but on the heap or in the
+        // reference-counted `ProcBinInner`, which the view keeps from moving or being freed
+        Some(unsafe { slice::from_raw_parts(ptr, len) })
+    }
+
+    /// Whether the bitstring is a binary, with no bits in a final partial byte
+    pub fn is_binary(&self) -> bool {
+        self.partial_byte().is_none()
+    }
+
+    /// The bits in the final partial byte, in the high bits of the byte as in the bitstring, and
+    /// the number of them, or `None` if the bitstring is a binary.
+    pub fn partial_byte(&self) -> Option<(u8, u8)> {
+        let subbinary = self.sub_binary()?;
+        let bit_len = subbinary.partial_byte_bit_len();
+
+        if bit_len == 0 {
+            None
+        } else {
+            let byte = subbinary
+                .partial_byte_bit_iter()
+                .enumerate()
+                .fold(0, |byte, (index, bit)| byte | (bit << (7 - index)));
+
+            Some((byte, bit_len))
+        }
+    }
+
+    fn sub_binary(&self) -> Option<SubBinary> {
+        match self.typed_term() {
+            TypedTerm::SubBinary(subbinary) => Some(subbinary),
+            _ => None,
+        }
+    }
+
+    fn typed_term(&self) -> TypedTerm {
+        match self.binary.to_typed_term().unwrap() {
+            TypedTerm::Boxed(boxed) => boxed.to_typed_term().unwrap(),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<'p> Drop for BinaryView<'p> {
+    fn drop(&mut self) {
+        if self.disabled_gc {
+            self.process.clear_flags(ProcessFlags::DisableGC);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::sync::Arc;
+
+    use crate::erts::process::{default_heap, Priority};
+    use crate::erts::scheduler;
+    use crate::erts::term::Atom;
+    use crate::erts::ModuleFunctionArity;
+
+    #[test]
+    fn heap_binary_is_borrowed() {
+        let process = process();
+        let binary = process.binary_from_bytes(&[1, 2, 3]).unwrap();
+        let view = BinaryView::new(&process, binary).unwrap();
+
+        assert_eq!(view.as_bytes(), Some(&[1, 2, 3][..]));
+        assert!(match view.full_bytes() {
+            Cow::Borrowed(bytes) => bytes == [1, 2, 3],
+            Cow::Owned(_) => false,
+        });
+        assert!(view.is_binary());
+    }
+
+    #[test]
+    fn reference_counted_binary_is_borrowed() {
+        let process = process();
+        // too big to be a heap binary
+        let bytes: Vec<u8> = (0..100).collect();
+        let binary = process.binary_from_bytes(&bytes).unwrap();
+        let view = BinaryView::new(&process, binary).unwrap();
+
+        assert_eq!(view.as_bytes(), Some(&bytes[..]));
+    }
+
+    #[test]
+    fn aligned_subbinary_is_borrowed_without_partial_byte() {
+        let process = process();
+        let original = process.binary_from_bytes(&[1, 2, 3, 0b1010_0000]).unwrap();
+        let binary = process
+            .subbinary_from_original(original, 1, 0, 2, 3)
+            .unwrap();
+        let view = BinaryView::new(&process, binary).unwrap();
+
+        assert_eq!(view.as_bytes(), Some(&[2, 3][..]));
+        assert!(!view.is_binary());
+        assert_eq!(view.partial_byte(), Some((0b1010_0000, 3)));
+    }
+
+    #[test]
+    fn unaligned_subbinary_is_copied() {
+        let process = process();
+        let original = process
+            .binary_from_bytes(&[0b0000_0001, 0b1000_0000])
+            .unwrap();
+        let binary = process
+            .subbinary_from_original(original, 0, 1, 1, 0)
+            .unwrap();
+        let view = BinaryView::new(&process, binary).unwrap();
+
+        assert_eq!(view.as_bytes(), None);
+        assert_eq!(view.full_bytes().into_owned(), vec![0b0000_0011]);
+        assert!(view.is_binary());
+    }
+
+    #[test]
+    fn non_binary_is_type_error() {
+        let process = process();
+
+        assert!(BinaryView::new(&process, process.integer(0).unwrap()).is_err());
+        assert!(BinaryView::new(&process, Term::NIL).is_err());
+    }
+
+    #[test]
+    fn garbage_collection_is_disabled_while_views_are_alive() {
+        let process = process();
+        let binary = process.binary_from_bytes(&[1]).unwrap();
+
+        {
+            let _view = BinaryView::new(&process, binary).unwrap();
+
+            assert!(process.are_flags_set(ProcessFlags::DisableGC));
+
+            {
+                let _nested_view = BinaryView::new(&process, binary).unwrap();
+            }
+
+            assert!(process.are_flags_set(ProcessFlags::DisableGC));
+        }
+
+        assert!(!process.are_flags_set(ProcessFlags::DisableGC));
+    }
+
+    fn process() -> Process {
+        let init = Atom::try_from_str("init").unwrap();
+        let initial_module_function_arity = Arc::new(ModuleFunctionArity {
+            module: init,
+            function: init,
+            arity: 0,
+        });
+        let (heap, heap_size) = default_heap().unwrap();
+
+        let process = Process::new(
+            Priority::Normal,
+            None,
+            initial_module_function_arity,
+            heap,
+            heap_size,
+        );
+
+        process.schedule_with(scheduler::id::next());
+
+        process
+    }
+}
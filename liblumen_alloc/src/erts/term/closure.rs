@@ -46,6 +46,11 @@ impl Closure {
         self.module_function_arity.arity
     }
 
+    /// The pid of the process that created the closure, either a `Pid` or an `ExternalPid`
+    pub fn creator(&self) -> Term {
+        self.creator
+    }
+
     pub fn frame(&self) -> Frame {
         Frame::new(Arc::clone(&self.module_function_arity), self.code)
     }
@@ -5,8 +5,6 @@ use core::hash::{self, Hash};
 use core::ops::*;
 use core::ptr;
 
-use num_bigint::{BigInt, Sign};
-
 use crate::borrow::CloneToProcess;
 use crate::erts::exception::system::Alloc;
 use crate::erts::term::{TypeError, TypedTerm};
@@ -160,63 +158,39 @@ impl PartialOrd<f32> for Float {
     }
 }
 impl PartialOrd<SmallInteger> for Float {
+    /// > A float is more precise than an integer until all significant figures of the float are to
+    /// > the left of the decimal point.
+    ///
+    /// Converting the integer to a float would round integers above `Float::INTEGRAL_MAX`, so the
+    /// integral part of the float is compared as an integer instead, with its fractional part
+    /// breaking ties.
     #[inline]
     fn partial_cmp(&self, other: &SmallInteger) -> Option<Ordering> {
-        use core::num::FpCategory;
+        // `isize::MIN` is a power of 2, so it and its negation are exact as floats
+        let min = std::isize::MIN as f64;
 
-        let is_negative = self.value.is_sign_negative();
-        match self.value.classify() {
-            FpCategory::Nan => None,
-            FpCategory::Subnormal => {
-                // The float is less precise, so convert to isize
-                let f = self.value as isize;
-                Some(f.cmp(&other.0))
-            }
-            FpCategory::Infinite if is_negative => Some(Ordering::Less),
-            FpCategory::Infinite => Some(Ordering::Greater),
-            FpCategory::Zero => Some(0.cmp(&other.0)),
-            FpCategory::Normal => {
-                // Float is higher precision
-                let i = other.0 as f64;
-                self.value.partial_cmp(&i)
-            }
+        if self.value.is_nan() {
+            None
+        } else if self.value < min {
+            Some(Ordering::Less)
+        } else if -min <= self.value {
+            Some(Ordering::Greater)
+        } else {
+            let integral = self.value.trunc();
+            let fract = self.value - integral;
+
+            Some(
+                (integral as isize)
+                    .cmp(&other.0)
+                    .then_with(|| fract.partial_cmp(&0.0).unwrap()),
+            )
         }
     }
 }
 impl PartialOrd<BigInteger> for Float {
     #[inline]
     fn partial_cmp(&self, other: &BigInteger) -> Option<Ordering> {
-        use core::num::FpCategory;
-        use num_traits::Zero;
-
-        let is_negative = self.value.is_sign_negative();
-        match self.value.classify() {
-            FpCategory::Nan => None,
-            FpCategory::Subnormal => {
-                // The float is less precise, so convert to isize
-                let f = BigInt::from(self.value as isize);
-                Some(f.cmp(&other.value))
-            }
-            FpCategory::Infinite if is_negative => Some(Ordering::Less),
-            FpCategory::Infinite => Some(Ordering::Greater),
-            FpCategory::Zero => {
-                let f: BigInt = Zero::zero();
-                Some(f.cmp(&other.value))
-            }
-            FpCategory::Normal => {
-                use num_traits::ToPrimitive;
-                // Float is higher precision, try and convert to it,
-                // if we fail, then the bigint is larger in either direction,
-                // which we must determine based on its sign
-                if let Some(i) = other.value.to_isize() {
-                    return self.value.partial_cmp(&(i as f64));
-                }
-                if let Sign::Minus = other.value.sign() {
-                    return Some(Ordering::Greater);
-                }
-                Some(Ordering::Less)
-            }
-        }
+        other.partial_cmp(self).map(Ordering::reverse)
     }
 }
 
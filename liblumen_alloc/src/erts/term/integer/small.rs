@@ -307,7 +307,7 @@ smallint_bitop_trait_impl!(BitXor, bitxor);
 impl PartialEq<Float> for SmallInteger {
     #[inline]
     fn eq(&self, other: &Float) -> bool {
-        (self.0 as f64).eq(&other.value)
+        other.eq(self)
     }
 }
 impl PartialEq<BigInteger> for SmallInteger {
@@ -334,7 +334,7 @@ impl PartialEq<isize> for SmallInteger {
 impl PartialOrd<Float> for SmallInteger {
     #[inline]
     fn partial_cmp(&self, other: &Float) -> Option<Ordering> {
-        (self.0 as f64).partial_cmp(&other.value)
+        other.partial_cmp(self).map(Ordering::reverse)
     }
 }
 impl PartialOrd<BigInteger> for SmallInteger {
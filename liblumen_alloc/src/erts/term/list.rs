@@ -300,6 +300,90 @@ impl Iterator for Iter {
     }
 }
 
+/// Iterates the elements of a list term that may be `[]`, so that callers need not match on
+/// `TypedTerm::Nil` and `TypedTerm::List` separately.  As with `Iter`, the tail of an improper
+/// list is returned as an `Err(ImproperList)`.
+pub struct ListIterator {
+    iter: Option<Iter>,
+}
+
+impl ListIterator {
+    pub fn new(list: Term) -> Result<Self, TypeError> {
+        let list: List = list.try_into()?;
+        let iter = match list {
+            List::Empty => None,
+            List::NonEmpty(cons) => Some(cons.into_iter()),
+        };
+
+        Ok(Self { iter })
+    }
+}
+
+impl FusedIterator for ListIterator {}
+
+impl Iterator for ListIterator {
+    type Item = Result<Term, ImproperList>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.as_mut().and_then(Iterator::next)
+    }
+}
+
+/// Iterates the elements of a list that was checked to be proper, ending in `[]`, when the
+/// iterator was made, so the elements are `Term`s instead of `Result`s and the length is known
+/// before the first one is used.  BIFs that only take proper lists use it so that they reject
+/// improper lists before doing any work, the same way `ImproperList` does for each of them.
+pub struct ProperListIterator {
+    tail: Term,
+    len: usize,
+}
+
+impl ProperListIterator {
+    /// Returns `Err(ImproperList)` with the tail that isn't `[]` if `list` is improper.  A term
+    /// that isn't a list at all is its own tail.
+    pub fn new(list: Term) -> Result<Self, ImproperList> {
+        let mut len = 0;
+        let mut tail = list;
+
+        loop {
+            match tail.to_typed_term().unwrap() {
+                TypedTerm::Nil => break,
+                TypedTerm::List(cons) => {
+                    len += 1;
+                    tail = cons.tail;
+                }
+                _ => return Err(ImproperList { tail }),
+            }
+        }
+
+        Ok(Self { tail: list, len })
+    }
+}
+
+impl ExactSizeIterator for ProperListIterator {}
+
+impl FusedIterator for ProperListIterator {}
+
+impl Iterator for ProperListIterator {
+    type Item = Term;
+
+    fn next(&mut self) -> Option<Term> {
+        if self.len == 0 {
+            None
+        } else {
+            let cons: Boxed<Cons> = self.tail.try_into().unwrap();
+            self.tail = cons.tail;
+            self.len -= 1;
+
+            Some(cons.head)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
 impl TryFrom<Term> for Boxed<Cons> {
     type Error = TypeError;
 
@@ -820,4 +904,119 @@ mod tests {
             assert_eq!(list_iter.next(), None);
         }
     }
+
+    mod list_iterator {
+        use super::*;
+
+        use ::alloc::sync::Arc;
+        use ::alloc::vec::Vec;
+
+        use crate::erts::process::{alloc, Priority, Process};
+        use crate::erts::scheduler;
+        use crate::erts::term::{atom_unchecked, Atom};
+        use crate::erts::ModuleFunctionArity;
+
+        #[test]
+        fn without_list_errors() {
+            assert!(atom_unchecked("list").list_iter().is_err());
+        }
+
+        #[test]
+        fn with_empty_list_is_empty() {
+            assert_eq!(Term::NIL.list_iter().unwrap().count(), 0);
+        }
+
+        #[test]
+        fn with_proper_list_returns_elements() {
+            let process = process();
+            let elements = [process.integer(0).unwrap(), atom_unchecked("one")];
+            let list = process.list_from_slice(&elements).unwrap();
+
+            let collected: Result<Vec<Term>, ImproperList> = list.list_iter().unwrap().collect();
+
+            assert_eq!(collected, Ok(elements.to_vec()));
+        }
+
+        #[test]
+        fn with_improper_list_returns_tail_as_error() {
+            let process = process();
+            let head = process.integer(0).unwrap();
+            let tail = atom_unchecked("tail");
+            let list = process.cons(head, tail).unwrap();
+
+            let mut list_iter = list.list_iter().unwrap();
+
+            assert_eq!(list_iter.next(), Some(Ok(head)));
+            assert_eq!(list_iter.next(), Some(Err(ImproperList { tail })));
+            assert_eq!(list_iter.next(), None);
+        }
+
+        pub(super) fn process() -> Process {
+            let init = Atom::try_from_str("init").unwrap();
+            let initial_module_function_arity = Arc::new(ModuleFunctionArity {
+                module: init,
+                function: init,
+                arity: 0,
+            });
+            let (heap, heap_size) = alloc::default_heap().unwrap();
+
+            let process = Process::new(
+                Priority::Normal,
+                None,
+                initial_module_function_arity,
+                heap,
+                heap_size,
+            );
+
+            process.schedule_with(scheduler::id::next());
+
+            process
+        }
+    }
+
+    mod proper_list_iterator {
+        use super::*;
+
+        use ::alloc::vec::Vec;
+
+        use crate::erts::term::atom_unchecked;
+
+        #[test]
+        fn without_list_errors_with_term_as_tail() {
+            let term = atom_unchecked("list");
+
+            assert_eq!(
+                term.proper_list_iter().err(),
+                Some(ImproperList { tail: term })
+            );
+        }
+
+        #[test]
+        fn with_empty_list_is_empty() {
+            assert_eq!(Term::NIL.proper_list_iter().unwrap().len(), 0);
+        }
+
+        #[test]
+        fn with_proper_list_returns_elements() {
+            let process = list_iterator::process();
+            let elements = [process.integer(0).unwrap(), atom_unchecked("one")];
+            let list = process.list_from_slice(&elements).unwrap();
+
+            let proper_list_iter = list.proper_list_iter().unwrap();
+
+            assert_eq!(proper_list_iter.len(), elements.len());
+            assert_eq!(proper_list_iter.collect::<Vec<Term>>(), elements.to_vec());
+        }
+
+        #[test]
+        fn with_improper_list_errors_with_tail() {
+            let process = list_iterator::process();
+            let tail = atom_unchecked("tail");
+            let list = process
+                .improper_list_from_slice(&[process.integer(0).unwrap()], tail)
+                .unwrap();
+
+            assert_eq!(list.proper_list_iter().err(), Some(ImproperList { tail }));
+        }
+    }
 }
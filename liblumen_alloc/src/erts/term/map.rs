@@ -2,10 +2,11 @@ use core::cmp;
 use core::convert::{TryFrom, TryInto};
 use core::fmt::{self, Debug, Display};
 use core::hash::{Hash, Hasher};
+use core::iter::FusedIterator;
 use core::mem;
 use core::ptr;
 
-use alloc::vec::Vec;
+use alloc::vec::{self, Vec};
 
 use hashbrown::HashMap;
 
@@ -133,12 +134,24 @@ impl Map {
         }
     }
 
+    /// The `(key, value)` entries in ascending key order
+    pub fn sorted_entries(&self) -> Vec<(Term, Term)> {
+        self.sorted_keys()
+            .into_iter()
+            .map(|key| (key, self.value[&key]))
+            .collect()
+    }
+
     // Private
 
+    /// In the key order of maps, an integer is less than a float that is equal to it
     fn sorted_keys(&self) -> Vec<Term> {
         let mut key_vec: Vec<Term> = Vec::new();
         key_vec.extend(self.value.keys());
-        key_vec.sort_unstable_by(|key1, key2| key1.cmp(&key2));
+        key_vec.sort_unstable_by(|key1, key2| {
+            key1.cmp(&key2)
+                .then_with(|| key1.is_float().cmp(&key2.is_float()))
+        });
 
         key_vec
     }
@@ -274,6 +287,44 @@ impl Ord for Map {
     }
 }
 
+/// Iterates the `(key, value)` entries of a map term in ascending key order, which is the order
+/// of `maps:to_list/1`.
+pub struct MapIterator {
+    entries: vec::IntoIter<(Term, Term)>,
+}
+
+impl MapIterator {
+    pub fn new(map: Term) -> Result<Self, TypeError> {
+        let boxed_map: Boxed<Map> = map.try_into()?;
+
+        Ok(Self {
+            entries: boxed_map.sorted_entries().into_iter(),
+        })
+    }
+}
+
+impl DoubleEndedIterator for MapIterator {
+    fn next_back(&mut self) -> Option<(Term, Term)> {
+        self.entries.next_back()
+    }
+}
+
+impl ExactSizeIterator for MapIterator {}
+
+impl FusedIterator for MapIterator {}
+
+impl Iterator for MapIterator {
+    type Item = (Term, Term);
+
+    fn next(&mut self) -> Option<(Term, Term)> {
+        self.entries.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
 impl TryFrom<Term> for Boxed<Map> {
     type Error = TypeError;
 
@@ -111,12 +111,12 @@ impl ExternalPid {
         number: usize,
         serial: usize,
     ) -> Result<Self, OutOfRange> {
-        let node = Node::new(node_id);
+        let node = Node::with_id(node_id);
 
         Self::new(node, number, serial)
     }
 
-    fn new(node: Node, number: usize, serial: usize) -> Result<Self, OutOfRange> {
+    pub fn new(node: Node, number: usize, serial: usize) -> Result<Self, OutOfRange> {
         let pid = Pid::new(number, serial)?;
         let header = Term::make_header(arity_of::<Self>(), Term::FLAG_EXTERN_PID);
 
@@ -127,6 +127,15 @@ impl ExternalPid {
             pid,
         })
     }
+
+    pub fn node(&self) -> Node {
+        self.node
+    }
+
+    /// The pid on `node`
+    pub fn pid(&self) -> Pid {
+        self.pid
+    }
 }
 
 unsafe impl AsTerm for ExternalPid {
@@ -148,8 +157,14 @@ impl CloneToProcess for ExternalPid {
 }
 
 impl Display for ExternalPid {
-    fn fmt(&self, _f: &mut fmt::Formatter) -> fmt::Result {
-        unimplemented!()
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "#PID<{}.{}.{}>",
+            self.node.id(),
+            self.pid.number(),
+            self.pid.serial()
+        )
     }
 }
 
@@ -1,12 +1,13 @@
 use core::cmp;
 use core::fmt::{self, Debug, Display};
 use core::hash::{Hash, Hasher};
+use core::ptr;
 
 use crate::borrow::CloneToProcess;
 use crate::erts::exception::system::Alloc;
 use crate::erts::{HeapAlloc, Node};
 
-use super::{AsTerm, Term};
+use super::{arity_of, AsTerm, Term};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
@@ -17,6 +18,10 @@ impl Port {
     pub unsafe fn from_raw(port: usize) -> Self {
         Self(port)
     }
+
+    pub fn number(&self) -> usize {
+        self.0
+    }
 }
 
 unsafe impl AsTerm for Port {
@@ -27,8 +32,8 @@ unsafe impl AsTerm for Port {
 }
 
 impl Display for Port {
-    fn fmt(&self, _f: &mut fmt::Formatter) -> fmt::Result {
-        unimplemented!()
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#Port<0.{}>", self.0)
     }
 }
 
@@ -52,6 +57,26 @@ pub struct ExternalPort {
     port: Port,
 }
 
+impl ExternalPort {
+    pub fn new(node: Node, number: usize) -> Self {
+        Self {
+            header: Term::make_header(arity_of::<Self>(), Term::FLAG_EXTERN_PORT),
+            node,
+            next: ptr::null_mut(),
+            port: unsafe { Port::from_raw(number) },
+        }
+    }
+
+    pub fn node(&self) -> Node {
+        self.node
+    }
+
+    /// The port on `node`
+    pub fn port(&self) -> Port {
+        self.port
+    }
+}
+
 unsafe impl AsTerm for ExternalPort {
     #[inline]
     unsafe fn as_term(&self) -> Term {
@@ -60,8 +85,13 @@ unsafe impl AsTerm for ExternalPort {
 }
 
 impl CloneToProcess for ExternalPort {
-    fn clone_to_heap<A: HeapAlloc>(&self, _heap: &mut A) -> Result<Term, Alloc> {
-        unimplemented!()
+    fn clone_to_heap<A: HeapAlloc>(&self, heap: &mut A) -> Result<Term, Alloc> {
+        unsafe {
+            let ptr = heap.alloc(self.size_in_words())?.as_ptr() as *mut Self;
+            ptr::copy_nonoverlapping(self as *const Self, ptr, 1);
+
+            Ok(Term::make_boxed(ptr))
+        }
     }
 }
 
@@ -77,11 +107,13 @@ impl Debug for ExternalPort {
 }
 
 impl Display for ExternalPort {
-    fn fmt(&self, _f: &mut fmt::Formatter) -> fmt::Result {
-        unimplemented!()
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#Port<{}.{}>", self.node.id(), self.port.number())
     }
 }
 
+impl Eq for ExternalPort {}
+
 impl Hash for ExternalPort {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.node.hash(state);
@@ -96,13 +128,17 @@ impl PartialEq<ExternalPort> for ExternalPort {
     }
 }
 
+impl Ord for ExternalPort {
+    fn cmp(&self, other: &ExternalPort) -> cmp::Ordering {
+        self.node
+            .cmp(&other.node)
+            .then_with(|| self.port.cmp(&other.port))
+    }
+}
+
 impl PartialOrd<ExternalPort> for ExternalPort {
     #[inline]
     fn partial_cmp(&self, other: &ExternalPort) -> Option<cmp::Ordering> {
-        use cmp::Ordering;
-        match self.node.partial_cmp(&other.node) {
-            Some(Ordering::Equal) => self.port.partial_cmp(&other.port),
-            result => result,
-        }
+        Some(self.cmp(other))
     }
 }
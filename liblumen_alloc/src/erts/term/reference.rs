@@ -142,6 +142,10 @@ impl TryFrom<TypedTerm> for Boxed<Reference> {
     }
 }
 
+/// The most 32-bit ids a reference from another node can have beyond the 3 that a `Reference`
+/// holds.  BEAM nodes send up to 5 ids once they know the other node accepts them (`V4_NC`).
+pub const MAX_EXTRA_IDS: usize = 2;
+
 #[derive(Clone)]
 #[repr(C)]
 pub struct ExternalReference {
@@ -149,6 +153,52 @@ pub struct ExternalReference {
     node: Node,
     next: *mut u8,
     reference: Reference,
+    extra_ids_len: usize,
+    extra_ids: [u32; MAX_EXTRA_IDS],
+}
+
+impl ExternalReference {
+    pub fn new(node: Node, scheduler_id: scheduler::ID, number: Number) -> Self {
+        Self::with_extra_ids(node, scheduler_id, number, &[])
+    }
+
+    /// A reference with more ids than `scheduler_id` and `number` hold.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more than `MAX_EXTRA_IDS` `extra_ids`.
+    pub fn with_extra_ids(
+        node: Node,
+        scheduler_id: scheduler::ID,
+        number: Number,
+        extra_ids: &[u32],
+    ) -> Self {
+        let mut ids = [0; MAX_EXTRA_IDS];
+        ids[..extra_ids.len()].copy_from_slice(extra_ids);
+
+        Self {
+            header: Term::make_header(arity_of::<Self>(), Term::FLAG_EXTERN_REF),
+            node,
+            next: ptr::null_mut(),
+            reference: Reference::new(scheduler_id, number),
+            extra_ids_len: extra_ids.len(),
+            extra_ids: ids,
+        }
+    }
+
+    pub fn node(&self) -> Node {
+        self.node
+    }
+
+    /// The reference on `node`
+    pub fn reference(&self) -> &Reference {
+        &self.reference
+    }
+
+    /// The ids after those in `reference`
+    pub fn extra_ids(&self) -> &[u32] {
+        &self.extra_ids[..self.extra_ids_len]
+    }
 }
 
 unsafe impl AsTerm for ExternalReference {
@@ -160,8 +210,13 @@ unsafe impl AsTerm for ExternalReference {
 
 impl CloneToProcess for ExternalReference {
     #[inline]
-    fn clone_to_heap<A: HeapAlloc>(&self, _heap: &mut A) -> Result<Term, Alloc> {
-        unimplemented!()
+    fn clone_to_heap<A: HeapAlloc>(&self, heap: &mut A) -> Result<Term, Alloc> {
+        unsafe {
+            let ptr = heap.alloc(self.size_in_words())?.as_ptr() as *mut Self;
+            ptr::copy_nonoverlapping(self as *const Self, ptr, 1);
+
+            Ok(Term::make_boxed(ptr))
+        }
     }
 }
 
@@ -172,20 +227,36 @@ impl Debug for ExternalReference {
             .field("node", &self.node)
             .field("next", &self.next)
             .field("reference", &self.reference)
+            .field("extra_ids", &self.extra_ids())
             .finish()
     }
 }
 
 impl Display for ExternalReference {
-    fn fmt(&self, _f: &mut fmt::Formatter) -> fmt::Result {
-        unimplemented!()
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "#Reference<{}.{}.{}",
+            self.node.id(),
+            self.reference.scheduler_id,
+            self.reference.number
+        )?;
+
+        for extra_id in self.extra_ids() {
+            write!(f, ".{}", extra_id)?;
+        }
+
+        write!(f, ">")
     }
 }
 
+impl Eq for ExternalReference {}
+
 impl Hash for ExternalReference {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.node.hash(state);
         self.reference.hash(state);
+        self.extra_ids().hash(state);
     }
 }
 
@@ -209,7 +280,9 @@ impl PartialEq<Boxed<Reference>> for Boxed<ExternalReference> {
 
 impl PartialEq<ExternalReference> for ExternalReference {
     fn eq(&self, other: &ExternalReference) -> bool {
-        self.node == other.node && self.reference == other.reference
+        self.node == other.node
+            && self.reference == other.reference
+            && self.extra_ids() == other.extra_ids()
     }
 }
 
@@ -232,12 +305,17 @@ impl PartialOrd<Boxed<Reference>> for Boxed<ExternalReference> {
     }
 }
 
+impl Ord for ExternalReference {
+    fn cmp(&self, other: &ExternalReference) -> cmp::Ordering {
+        self.node
+            .cmp(&other.node)
+            .then_with(|| self.reference.cmp(&other.reference))
+            .then_with(|| self.extra_ids().cmp(other.extra_ids()))
+    }
+}
+
 impl PartialOrd<ExternalReference> for ExternalReference {
     fn partial_cmp(&self, other: &ExternalReference) -> Option<cmp::Ordering> {
-        use cmp::Ordering;
-        match self.node.partial_cmp(&other.node) {
-            Some(Ordering::Equal) => self.reference.partial_cmp(&other.reference),
-            result => result,
-        }
+        Some(self.cmp(other))
     }
 }
@@ -2,6 +2,7 @@
 /// converted to a normal Term.
 use core::alloc::Layout;
 use core::any::{Any, TypeId};
+use core::cmp;
 use core::convert::{TryFrom, TryInto};
 use core::fmt::{self, Debug, Display};
 use core::hash::{Hash, Hasher};
@@ -187,6 +188,22 @@ impl PartialEq for Reference {
     }
 }
 
+impl Eq for Reference {}
+
+/// Resources have no value to order by, so they are ordered by where they were allocated, which is
+/// stable for as long as the resource is referenced.
+impl Ord for Reference {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.resource.cmp(&other.resource)
+    }
+}
+
+impl PartialOrd for Reference {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl TryFrom<Term> for Reference {
     type Error = TypeError;
 
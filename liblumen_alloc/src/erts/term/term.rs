@@ -758,6 +758,22 @@ impl Term {
         Self(constants::make_list(value))
     }
 
+    /// Creates a literal list term from a pointer to a cons cell
+    #[inline]
+    pub fn make_list_literal(value: *const Cons) -> Self {
+        let address = value as usize;
+
+        assert_eq!(
+            address & Self::FLAG_LITERAL,
+            0,
+            "Pointer bits ({:032b}) colliding with literal flag ({:032b})",
+            address,
+            Self::FLAG_LITERAL
+        );
+
+        Self(constants::make_list(value) | Self::FLAG_LITERAL)
+    }
+
     /// Creates a (local) pid value from a raw usize value
     #[inline]
     pub fn make_pid(serial_number: usize) -> Self {
@@ -861,32 +877,65 @@ impl Term {
         self.0
     }
 
-    /// Returns `true` if `self` and `other` are equal without converting integers and floats.
+    /// Returns `true` if `self` and `other` are equal without converting integers and floats,
+    /// including for the elements of tuples, lists and maps, so `{1}` is not exactly equal to
+    /// `{1.0}`.
     pub fn exactly_eq(self, other: &Term) -> bool {
-        let can_be_exactly_equal = match (
+        match (
             self.to_typed_term().unwrap(),
             other.to_typed_term().unwrap(),
         ) {
             (TypedTerm::SmallInteger(_), TypedTerm::Boxed(_)) => false,
             (TypedTerm::Boxed(_), TypedTerm::SmallInteger(_)) => false,
-            (TypedTerm::Boxed(self_unboxed), TypedTerm::Boxed(other_unboxed)) => {
+            (TypedTerm::Boxed(self_boxed), TypedTerm::Boxed(other_boxed)) => {
                 match (
-                    self_unboxed.to_typed_term().unwrap(),
-                    other_unboxed.to_typed_term().unwrap(),
+                    self_boxed.to_typed_term().unwrap(),
+                    other_boxed.to_typed_term().unwrap(),
                 ) {
                     (TypedTerm::BigInteger(_), TypedTerm::Float(_)) => false,
                     (TypedTerm::Float(_), TypedTerm::BigInteger(_)) => false,
-                    _ => true,
+                    (TypedTerm::Tuple(self_tuple), TypedTerm::Tuple(other_tuple)) => {
+                        self_tuple.len() == other_tuple.len()
+                            && self_tuple.iter().zip(other_tuple.iter()).all(
+                                |(self_element, other_element)| {
+                                    self_element.exactly_eq(&other_element)
+                                },
+                            )
+                    }
+                    // looking up the keys of one map in the other would convert them, so the
+                    // entries are compared in key order instead
+                    (TypedTerm::Map(self_map), TypedTerm::Map(other_map)) => {
+                        self_map.len() == other_map.len()
+                            && self_map
+                                .sorted_entries()
+                                .into_iter()
+                                .zip(other_map.sorted_entries())
+                                .all(|((self_key, self_value), (other_key, other_value))| {
+                                    self_key.exactly_eq(&other_key)
+                                        && self_value.exactly_eq(&other_value)
+                                })
+                    }
+                    (self_unboxed, other_unboxed) => self_unboxed.eq(&other_unboxed),
                 }
             }
-            _ => true,
-        };
+            (TypedTerm::List(mut self_cons), TypedTerm::List(mut other_cons)) => loop {
+                if !self_cons.head.exactly_eq(&other_cons.head) {
+                    break false;
+                }
 
-        can_be_exactly_equal
-            && (self
-                .to_typed_term()
-                .unwrap()
-                .eq(&other.to_typed_term().unwrap()))
+                match (
+                    self_cons.tail.to_typed_term().unwrap(),
+                    other_cons.tail.to_typed_term().unwrap(),
+                ) {
+                    (TypedTerm::List(self_tail_cons), TypedTerm::List(other_tail_cons)) => {
+                        self_cons = self_tail_cons;
+                        other_cons = other_tail_cons;
+                    }
+                    _ => break self_cons.tail.exactly_eq(&other_cons.tail),
+                }
+            },
+            (self_typed_term, other_typed_term) => self_typed_term.eq(&other_typed_term),
+        }
     }
 
     /// Returns `false` if `self` and `other` are equal without converting integers and floats.
@@ -951,6 +1000,26 @@ impl Term {
             })
     }
 
+    /// Iterates the elements of this list, which may be `[]`
+    pub fn list_iter(self) -> Result<ListIterator, TypeError> {
+        ListIterator::new(self)
+    }
+
+    /// Iterates the elements of this list, which has to be proper.  See `ProperListIterator`.
+    pub fn proper_list_iter(self) -> Result<ProperListIterator, ImproperList> {
+        ProperListIterator::new(self)
+    }
+
+    /// Iterates the `(key, value)` entries of this map in ascending key order
+    pub fn map_iter(self) -> Result<MapIterator, TypeError> {
+        MapIterator::new(self)
+    }
+
+    /// Iterates the elements of this tuple
+    pub fn tuple_elements(self) -> Result<TupleElements, TypeError> {
+        TupleElements::new(self)
+    }
+
     /// Returns true if this a term that the runtime should accept as an argument.
     pub fn is_runtime(&self) -> bool {
         self.is_immediate() || self.is_boxed() || self.is_non_empty_list()
@@ -1340,7 +1409,7 @@ impl Term {
             Self::FLAG_BOXED => {
                 let ptr = constants::boxed_value(val);
                 if constants::is_literal(val) {
-                    Ok(TypedTerm::Literal(unsafe { *ptr }))
+                    Ok(TypedTerm::Boxed(unsafe { Boxed::from_raw_literal(ptr) }))
                 } else {
                     Ok(TypedTerm::Boxed(unsafe { Boxed::from_raw(ptr) }))
                 }
@@ -1589,7 +1658,8 @@ impl Ord for Term {
 
 impl CloneToProcess for Term {
     fn clone_to_process(&self, process: &Process) -> Term {
-        if self.is_immediate() {
+        // Literals are never freed, so they are shared instead of copied
+        if self.is_immediate() || self.is_literal() {
             *self
         } else if self.is_boxed() || self.is_non_empty_list() {
             let tt = self.to_typed_term().unwrap();
@@ -1601,7 +1671,7 @@ impl CloneToProcess for Term {
 
     fn clone_to_heap<A: HeapAlloc>(&self, heap: &mut A) -> Result<Term, Alloc> {
         debug_assert!(self.is_runtime());
-        if self.is_immediate() {
+        if self.is_immediate() || self.is_literal() {
             Ok(*self)
         } else if self.is_boxed() || self.is_non_empty_list() {
             let tt = self.to_typed_term().unwrap();
@@ -1612,7 +1682,7 @@ impl CloneToProcess for Term {
     }
 
     fn size_in_words(&self) -> usize {
-        if self.is_immediate() {
+        if self.is_immediate() || self.is_literal() {
             return 1;
         } else if self.is_boxed() || self.is_non_empty_list() {
             let tt = self.to_typed_term().unwrap();
@@ -1794,6 +1864,7 @@ mod tests {
             constants::MAX_ALIGNED_ADDR | constants::FLAG_LIST | constants::FLAG_LITERAL
         ));
         assert!(Term::make_boxed_literal(constants::MAX_ALIGNED_ADDR as *mut Term).is_literal());
+        assert!(Term::make_list_literal(constants::MAX_ALIGNED_ADDR as *mut Cons).is_literal());
     }
 
     #[test]
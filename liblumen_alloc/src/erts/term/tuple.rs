@@ -341,6 +341,45 @@ impl Iterator for Iter {
 
 impl FusedIterator for Iter {}
 
+/// Iterates the elements of a tuple term
+pub struct TupleElements {
+    iter: Iter,
+}
+
+impl TupleElements {
+    pub fn new(tuple: Term) -> Result<Self, TypeError> {
+        let boxed_tuple: Boxed<Tuple> = tuple.try_into()?;
+
+        Ok(Self {
+            iter: boxed_tuple.iter(),
+        })
+    }
+}
+
+impl DoubleEndedIterator for TupleElements {
+    fn next_back(&mut self) -> Option<Term> {
+        self.iter.next_back()
+    }
+}
+
+impl ExactSizeIterator for TupleElements {}
+
+impl FusedIterator for TupleElements {}
+
+impl Iterator for TupleElements {
+    type Item = Term;
+
+    fn next(&mut self) -> Option<Term> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.iter.limit as usize - self.iter.pointer as usize) / mem::size_of::<Term>();
+
+        (len, Some(len))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -499,6 +538,38 @@ mod tests {
             .unwrap()
     }
 
+    mod tuple_elements {
+        use super::*;
+
+        use crate::erts::term::atom_unchecked;
+
+        #[test]
+        fn without_tuple_errors() {
+            assert!(atom_unchecked("tuple").tuple_elements().is_err());
+        }
+
+        #[test]
+        fn with_tuple_iterates_from_either_end() {
+            let process = process();
+            let elements = [
+                atom_unchecked("first"),
+                process.integer(1).unwrap(),
+                process.cons(atom_unchecked("last"), Term::NIL).unwrap(),
+            ];
+            let tuple = process.tuple_from_slice(&elements).unwrap();
+
+            let mut tuple_elements = tuple.tuple_elements().unwrap();
+
+            assert_eq!(tuple_elements.len(), 3);
+            assert_eq!(tuple_elements.next_back(), Some(elements[2]));
+            assert_eq!(tuple_elements.len(), 2);
+            assert_eq!(
+                tuple_elements.collect::<Vec<Term>>(),
+                vec![elements[0], elements[1]]
+            );
+        }
+    }
+
     fn process() -> Process {
         let init = Atom::try_from_str("init").unwrap();
         let initial_module_function_arity = Arc::new(ModuleFunctionArity {
@@ -31,7 +31,6 @@ pub enum TypedTerm {
     Tuple(Boxed<Tuple>),
     Map(Boxed<Map>),
     Boxed(Boxed<Term>),
-    Literal(Term),
     Pid(Pid),
     Port(Port),
     Reference(Boxed<Reference>),
@@ -152,6 +151,178 @@ impl TypedTerm {
             false
         }
     }
+
+    /// The position of the type of this unboxed term in the term order, so that terms of different
+    /// types compare by it alone.  Numbers, local and external identifiers, and bitstrings of
+    /// any representation share a position, so that they compare by value.
+    fn type_order(&self) -> u8 {
+        match self {
+            Self::SmallInteger(_) | Self::BigInteger(_) | Self::Float(_) => 0,
+            Self::Atom(_) => 1,
+            Self::Reference(_) | Self::ResourceReference(_) | Self::ExternalReference(_) => 2,
+            Self::Closure(_) => 3,
+            Self::Port(_) | Self::ExternalPort(_) => 4,
+            Self::Pid(_) | Self::ExternalPid(_) => 5,
+            Self::Tuple(_) => 6,
+            Self::Map(_) => 7,
+            Self::Nil => 8,
+            Self::List(_) => 9,
+            Self::HeapBinary(_) | Self::ProcBin(_) | Self::SubBinary(_) | Self::MatchContext(_) => {
+                10
+            }
+            _ => unreachable!("{:?} is not ordered", self),
+        }
+    }
+
+    /// Compares unboxed terms with the same `type_order`
+    fn cmp_same_type_order(&self, other: &Self) -> cmp::Ordering {
+        use cmp::Ordering::*;
+
+        match (self, other) {
+            // Numbers
+            //
+            // Flip order so that only type that will be conversion target needs to implement
+            // `PartialOrd` between types.
+            (Self::SmallInteger(self_small_integer), Self::SmallInteger(other_small_integer)) => {
+                self_small_integer.cmp(other_small_integer)
+            }
+            (Self::SmallInteger(self_small_integer), Self::BigInteger(other_big_integer)) => {
+                other_big_integer
+                    .partial_cmp(self_small_integer)
+                    .unwrap()
+                    .reverse()
+            }
+            (Self::SmallInteger(self_small_integer), Self::Float(other_float)) => other_float
+                .partial_cmp(self_small_integer)
+                .unwrap()
+                .reverse(),
+            (Self::BigInteger(self_big_integer), Self::SmallInteger(other_small_integer)) => {
+                self_big_integer.partial_cmp(other_small_integer).unwrap()
+            }
+            (Self::BigInteger(self_big_integer), Self::BigInteger(other_big_integer)) => {
+                self_big_integer.cmp(other_big_integer)
+            }
+            (Self::BigInteger(self_big_integer), Self::Float(other_float)) => {
+                self_big_integer.partial_cmp(other_float).unwrap()
+            }
+            (Self::Float(self_float), Self::SmallInteger(other_small_integer)) => {
+                self_float.partial_cmp(other_small_integer).unwrap()
+            }
+            (Self::Float(self_float), Self::BigInteger(other_big_integer)) => {
+                other_big_integer.partial_cmp(self_float).unwrap().reverse()
+            }
+            (Self::Float(self_float), Self::Float(other_float)) => self_float.cmp(other_float),
+            (Self::Atom(self_atom), Self::Atom(other_atom)) => self_atom.cmp(other_atom),
+            // References, local before resource before external
+            (Self::Reference(self_reference), Self::Reference(other_reference)) => {
+                self_reference.cmp(other_reference)
+            }
+            (Self::Reference(_), _) => Less,
+            (Self::ResourceReference(_), Self::Reference(_)) => Greater,
+            (
+                Self::ResourceReference(self_resource_reference),
+                Self::ResourceReference(other_resource_reference),
+            ) => self_resource_reference.cmp(other_resource_reference),
+            (Self::ResourceReference(_), _) => Less,
+            (
+                Self::ExternalReference(self_external_reference),
+                Self::ExternalReference(other_external_reference),
+            ) => self_external_reference.cmp(other_external_reference),
+            (Self::ExternalReference(_), _) => Greater,
+            (Self::Closure(self_closure), Self::Closure(other_closure)) => {
+                self_closure.cmp(other_closure)
+            }
+            // Ports and pids, local before external
+            (Self::Port(self_port), Self::Port(other_port)) => self_port.cmp(other_port),
+            (Self::Port(_), Self::ExternalPort(_)) => Less,
+            (Self::ExternalPort(_), Self::Port(_)) => Greater,
+            (Self::ExternalPort(self_external_port), Self::ExternalPort(other_external_port)) => {
+                self_external_port.cmp(other_external_port)
+            }
+            (Self::Pid(self_pid), Self::Pid(other_pid)) => self_pid.cmp(other_pid),
+            (Self::Pid(_), Self::ExternalPid(_)) => Less,
+            (Self::ExternalPid(_), Self::Pid(_)) => Greater,
+            (Self::ExternalPid(self_external_pid), Self::ExternalPid(other_external_pid)) => {
+                self_external_pid.cmp(other_external_pid)
+            }
+            (Self::Tuple(self_tuple), Self::Tuple(other_tuple)) => self_tuple.cmp(other_tuple),
+            (Self::Map(self_map), Self::Map(other_map)) => self_map.cmp(other_map),
+            (Self::Nil, Self::Nil) => Equal,
+            (Self::List(self_cons), Self::List(other_cons)) => self_cons.cmp(other_cons),
+            // Bitstrings
+            (Self::HeapBinary(self_heap_binary), Self::HeapBinary(other_heap_binary)) => {
+                self_heap_binary.as_ref().cmp(other_heap_binary.as_ref())
+            }
+            (Self::HeapBinary(self_heap_binary), Self::ProcBin(other_process_binary)) => {
+                other_process_binary
+                    .partial_cmp(self_heap_binary)
+                    .unwrap()
+                    .reverse()
+            }
+            (Self::HeapBinary(self_heap_binary), Self::SubBinary(other_subbinary)) => {
+                other_subbinary
+                    .partial_cmp(self_heap_binary.as_ref())
+                    .unwrap()
+                    .reverse()
+            }
+            (Self::HeapBinary(self_heap_binary), Self::MatchContext(other_match_context)) => {
+                other_match_context
+                    .partial_cmp(self_heap_binary.as_ref())
+                    .unwrap()
+                    .reverse()
+            }
+            (Self::ProcBin(self_process_binary), Self::HeapBinary(other_heap_binary)) => {
+                self_process_binary.partial_cmp(other_heap_binary).unwrap()
+            }
+            (Self::ProcBin(self_process_binary), Self::ProcBin(other_process_binary)) => {
+                self_process_binary
+                    .partial_cmp(other_process_binary)
+                    .unwrap()
+            }
+            (Self::ProcBin(self_process_binary), Self::SubBinary(other_subbinary)) => {
+                self_process_binary.partial_cmp(other_subbinary).unwrap()
+            }
+            (Self::ProcBin(self_process_binary), Self::MatchContext(other_match_context)) => {
+                self_process_binary
+                    .partial_cmp(other_match_context)
+                    .unwrap()
+            }
+            (Self::SubBinary(self_subbinary), Self::HeapBinary(other_heap_binary)) => {
+                self_subbinary
+                    .partial_cmp(other_heap_binary.as_ref())
+                    .unwrap()
+            }
+            (Self::SubBinary(self_subbinary), Self::ProcBin(other_process_binary)) => {
+                self_subbinary.partial_cmp(other_process_binary).unwrap()
+            }
+            (Self::SubBinary(self_subbinary), Self::SubBinary(other_subbinary)) => {
+                self_subbinary.cmp(other_subbinary)
+            }
+            (Self::SubBinary(self_subbinary), Self::MatchContext(other_match_context)) => {
+                self_subbinary.partial_cmp(other_match_context).unwrap()
+            }
+            (Self::MatchContext(self_match_context), Self::HeapBinary(other_heap_binary)) => {
+                self_match_context
+                    .partial_cmp(other_heap_binary.as_ref())
+                    .unwrap()
+            }
+            (Self::MatchContext(self_match_context), Self::ProcBin(other_process_binary)) => {
+                self_match_context
+                    .partial_cmp(other_process_binary)
+                    .unwrap()
+            }
+            (Self::MatchContext(self_match_context), Self::SubBinary(other_subbinary)) => {
+                other_subbinary
+                    .partial_cmp(self_match_context)
+                    .unwrap()
+                    .reverse()
+            }
+            (Self::MatchContext(self_match_context), Self::MatchContext(other_match_context)) => {
+                self_match_context.cmp(other_match_context)
+            }
+            _ => unreachable!("{:?} and {:?} have different type orders", self, other),
+        }
+    }
 }
 
 impl Display for TypedTerm {
@@ -170,7 +341,6 @@ impl Display for TypedTerm {
             Float(float) => write!(f, "{}", float),
             HeapBinary(heap_binary) => write!(f, "{}", heap_binary),
             List(cons) => write!(f, "{}", cons),
-            Literal(literal) => write!(f, "{}", literal),
             Map(map) => write!(f, "{}", map),
             MatchContext(match_context) => write!(f, "{}", match_context),
             Nil => write!(f, "[]"),
@@ -194,7 +364,6 @@ impl Hash for TypedTerm {
             Self::Tuple(tuple) => tuple.hash(state),
             Self::Map(map) => map.hash(state),
             Self::Boxed(boxed) => boxed.to_typed_term().unwrap().hash(state),
-            Self::Literal(literal) => literal.hash(state),
             Self::Pid(pid) => pid.hash(state),
             Self::Port(port) => port.hash(state),
             Self::Reference(reference) => reference.hash(state),
@@ -302,6 +471,24 @@ impl PartialEq<TypedTerm> for TypedTerm {
                     },
                     _ => false,
                 },
+                TypedTerm::ExternalPort(self_external_port) => match other {
+                    TypedTerm::Boxed(other_boxed) => match other_boxed.to_typed_term().unwrap() {
+                        TypedTerm::ExternalPort(other_external_port) => {
+                            self_external_port.eq(&other_external_port)
+                        }
+                        _ => false,
+                    },
+                    _ => false,
+                },
+                TypedTerm::ExternalReference(self_external_reference) => match other {
+                    TypedTerm::Boxed(other_boxed) => match other_boxed.to_typed_term().unwrap() {
+                        TypedTerm::ExternalReference(other_external_reference) => {
+                            self_external_reference.eq(&other_external_reference)
+                        }
+                        _ => false,
+                    },
+                    _ => false,
+                },
                 TypedTerm::Tuple(self_tuple) => match other {
                     TypedTerm::Boxed(other_boxed) => match other_boxed.to_typed_term().unwrap() {
                         TypedTerm::Tuple(other_tuple) => self_tuple.eq(&other_tuple),
@@ -371,6 +558,24 @@ impl PartialEq<TypedTerm> for TypedTerm {
                     },
                     _ => false,
                 },
+                TypedTerm::MatchContext(self_match_context) => match other {
+                    TypedTerm::Boxed(other_boxed) => match other_boxed.to_typed_term().unwrap() {
+                        TypedTerm::HeapBinary(other_heap_binary) => {
+                            self_match_context.eq(other_heap_binary.as_ref())
+                        }
+                        TypedTerm::ProcBin(other_process_binary) => {
+                            self_match_context.eq(&other_process_binary)
+                        }
+                        TypedTerm::SubBinary(other_subbinary) => {
+                            other_subbinary.eq(&self_match_context)
+                        }
+                        TypedTerm::MatchContext(other_match_context) => {
+                            self_match_context.eq(&other_match_context)
+                        }
+                        _ => false,
+                    },
+                    _ => false,
+                },
                 _ => unreachable!(),
             },
             TypedTerm::Atom(self_atom) => match other {
@@ -401,7 +606,7 @@ impl Eq for TypedTerm {}
 
 /// All terms in Erlang and Elixir are completely ordered.
 ///
-/// number < atom < reference < function < port < pid < tuple < map < list < bitstring
+/// number < atom < reference < function < port < pid < tuple < map < nil < list < bitstring
 ///
 /// > When comparing two numbers of different types (a number being either an integer or a float), a
 /// > conversion to the type with greater precision will always occur, unless the comparison
@@ -421,334 +626,20 @@ impl Eq for TypedTerm {}
 /// > -- https://hexdocs.pm/elixir/operators.html#term-ordering
 impl Ord for TypedTerm {
     fn cmp(&self, other: &Self) -> cmp::Ordering {
-        use cmp::Ordering::*;
-
-        match self {
-            // Numbers
-            // Order is SmallInteger, Float, BigInt because of the order of their ranges
-            TypedTerm::SmallInteger(self_small_integer) => match other {
-                TypedTerm::SmallInteger(other_small_integer) => {
-                    self_small_integer.cmp(other_small_integer)
-                }
-                TypedTerm::Boxed(other_boxed) => match other_boxed.to_typed_term().unwrap() {
-                    // Flip order so that only type that will be conversion target needs to
-                    // implement `PartialOrd` between types.
-                    TypedTerm::Float(other_float) => other_float
-                        .partial_cmp(self_small_integer)
-                        .unwrap()
-                        .reverse(),
-                    TypedTerm::BigInteger(other_big_integer) => other_big_integer
-                        .partial_cmp(self_small_integer)
-                        .unwrap()
-                        .reverse(),
-                    _ => Less,
-                },
-                _ => Less,
-            },
-            // In place of first boxed: Float.
-            TypedTerm::Boxed(self_boxed) => {
-                let self_unboxed = self_boxed.to_typed_term().unwrap();
-
-                match self_unboxed {
-                    TypedTerm::Float(self_float) => match other {
-                        TypedTerm::SmallInteger(other_small_integer) => {
-                            self_float.partial_cmp(other_small_integer).unwrap()
-                        }
-                        TypedTerm::Boxed(other_boxed) => match other_boxed.to_typed_term().unwrap()
-                        {
-                            TypedTerm::Float(other_float) => self_float.cmp(&other_float),
-                            TypedTerm::BigInteger(other_big_integer) => other_big_integer
-                                .partial_cmp(&self_float)
-                                .unwrap()
-                                .reverse(),
-                            _ => Less,
-                        },
-                        _ => Less,
-                    },
-                    TypedTerm::BigInteger(self_big_integer) => match other {
-                        TypedTerm::SmallInteger(other_small_integer) => {
-                            self_big_integer.partial_cmp(other_small_integer).unwrap()
-                        }
-                        TypedTerm::Boxed(other_boxed) => match other_boxed.to_typed_term().unwrap()
-                        {
-                            TypedTerm::Float(other_float) => {
-                                self_big_integer.partial_cmp(&other_float).unwrap()
-                            }
-                            TypedTerm::BigInteger(other_big_integer) => {
-                                self_big_integer.cmp(&other_big_integer)
-                            }
-                            _ => Less,
-                        },
-                        _ => Less,
-                    },
-                    TypedTerm::Reference(self_reference) => match other {
-                        TypedTerm::SmallInteger(_) => Greater,
-                        TypedTerm::Boxed(other_boxed) => match other_boxed.to_typed_term().unwrap()
-                        {
-                            TypedTerm::Float(_) | TypedTerm::BigInteger(_) => Greater,
-                            TypedTerm::Reference(other_reference) => {
-                                self_reference.cmp(&other_reference)
-                            }
-                            TypedTerm::ExternalReference(other_external_reference) => {
-                                other_external_reference
-                                    .partial_cmp(&self_reference)
-                                    .unwrap()
-                                    .reverse()
-                            }
-                            _ => Less,
-                        },
-                        TypedTerm::Atom(_) => Greater,
-                        _ => Less,
-                    },
-                    TypedTerm::Closure(self_closure) => match other {
-                        TypedTerm::SmallInteger(_) => Greater,
-                        TypedTerm::Boxed(other_boxed) => match other_boxed.to_typed_term().unwrap()
-                        {
-                            TypedTerm::Float(_)
-                            | TypedTerm::BigInteger(_)
-                            | TypedTerm::Reference(_)
-                            | TypedTerm::ExternalReference(_) => Greater,
-                            TypedTerm::Closure(other_closure) => {
-                                self_closure.cmp(other_closure.as_ref())
-                            }
-                            _ => Less,
-                        },
-                        TypedTerm::Atom(_) => Greater,
-                        _ => Less,
-                    },
-                    TypedTerm::ExternalPid(self_external_pid) => match other {
-                        TypedTerm::SmallInteger(_) => Greater,
-                        TypedTerm::Boxed(other_boxed) => match other_boxed.to_typed_term().unwrap()
-                        {
-                            TypedTerm::Float(_)
-                            | TypedTerm::BigInteger(_)
-                            | TypedTerm::Reference(_)
-                            | TypedTerm::ExternalReference(_)
-                            | TypedTerm::Closure(_)
-                            | TypedTerm::ExternalPort(_) => Greater,
-                            TypedTerm::ExternalPid(other_external_pid) => {
-                                self_external_pid.cmp(&other_external_pid)
-                            }
-                            _ => Less,
-                        },
-                        TypedTerm::Atom(_) | TypedTerm::Port(_) | TypedTerm::Pid(_) => Greater,
-                        _ => Less,
-                    },
-                    TypedTerm::Tuple(self_tuple) => match other {
-                        TypedTerm::SmallInteger(_) => Greater,
-                        TypedTerm::Boxed(other_boxed) => match other_boxed.to_typed_term().unwrap()
-                        {
-                            TypedTerm::Float(_)
-                            | TypedTerm::BigInteger(_)
-                            | TypedTerm::Reference(_)
-                            | TypedTerm::ExternalReference(_)
-                            | TypedTerm::Closure(_)
-                            | TypedTerm::ExternalPort(_)
-                            | TypedTerm::ExternalPid(_) => Greater,
-                            TypedTerm::Tuple(other_tuple) => self_tuple.cmp(other_tuple.as_ref()),
-                            _ => Less,
-                        },
-                        TypedTerm::Atom(_) | TypedTerm::Port(_) | TypedTerm::Pid(_) => Greater,
-                        _ => Less,
-                    },
-                    TypedTerm::Map(self_map) => match other {
-                        TypedTerm::SmallInteger(_) => Greater,
-                        TypedTerm::Boxed(other_boxed) => match other_boxed.to_typed_term().unwrap()
-                        {
-                            TypedTerm::Float(_)
-                            | TypedTerm::BigInteger(_)
-                            | TypedTerm::Reference(_)
-                            | TypedTerm::Closure(_)
-                            | TypedTerm::ExternalPort(_)
-                            | TypedTerm::ExternalPid(_)
-                            | TypedTerm::Tuple(_) => Greater,
-                            TypedTerm::Map(other_map) => self_map.cmp(other_map.as_ref()),
-                            _ => Less,
-                        },
-                        TypedTerm::Atom(_) | TypedTerm::Port(_) | TypedTerm::Pid(_) => Greater,
-                        _ => Less,
-                    },
-                    // Bitstrings in likely order
-                    TypedTerm::HeapBinary(self_heap_binary) => match other {
-                        TypedTerm::SmallInteger(_) => Greater,
-                        TypedTerm::Boxed(other_boxed) => match other_boxed.to_typed_term().unwrap()
-                        {
-                            TypedTerm::Float(_)
-                            | TypedTerm::BigInteger(_)
-                            | TypedTerm::Reference(_)
-                            | TypedTerm::ExternalReference(_)
-                            | TypedTerm::Closure(_)
-                            | TypedTerm::ExternalPort(_)
-                            | TypedTerm::ExternalPid(_)
-                            | TypedTerm::Tuple(_)
-                            | TypedTerm::Map(_)
-                            | TypedTerm::List(_) => Greater,
-                            TypedTerm::HeapBinary(other_heap_binary) => {
-                                self_heap_binary.as_ref().cmp(other_heap_binary.as_ref())
-                            }
-                            TypedTerm::ProcBin(other_process_binary) => other_process_binary
-                                .partial_cmp(&self_heap_binary)
-                                .unwrap()
-                                .reverse(),
-                            TypedTerm::SubBinary(other_subbinary) => other_subbinary
-                                .partial_cmp(self_heap_binary.as_ref())
-                                .unwrap()
-                                .reverse(),
-                            TypedTerm::MatchContext(other_match_context) => other_match_context
-                                .partial_cmp(self_heap_binary.as_ref())
-                                .unwrap()
-                                .reverse(),
-                            _ => unreachable!(),
-                        },
-                        TypedTerm::Atom(_) | TypedTerm::Pid(_) | TypedTerm::Nil => Greater,
-                        _ => unreachable!(),
-                    },
-                    TypedTerm::ProcBin(self_process_binary) => match other {
-                        TypedTerm::SmallInteger(_) => Greater,
-                        TypedTerm::Boxed(other_boxed) => match other_boxed.to_typed_term().unwrap()
-                        {
-                            TypedTerm::Float(_)
-                            | TypedTerm::BigInteger(_)
-                            | TypedTerm::Reference(_)
-                            | TypedTerm::ExternalReference(_)
-                            | TypedTerm::Closure(_)
-                            | TypedTerm::ExternalPort(_)
-                            | TypedTerm::ExternalPid(_)
-                            | TypedTerm::Tuple(_)
-                            | TypedTerm::Map(_)
-                            | TypedTerm::List(_) => Greater,
-                            TypedTerm::HeapBinary(other_heap_binary) => {
-                                self_process_binary.partial_cmp(&other_heap_binary).unwrap()
-                            }
-                            TypedTerm::ProcBin(other_process_binary) => self_process_binary
-                                .partial_cmp(&other_process_binary)
-                                .unwrap(),
-                            TypedTerm::SubBinary(other_subbinary) => other_subbinary
-                                .partial_cmp(&self_process_binary)
-                                .unwrap()
-                                .reverse(),
-                            TypedTerm::MatchContext(other_match_context) => other_match_context
-                                .partial_cmp(&self_process_binary)
-                                .unwrap()
-                                .reverse(),
-                            _ => unreachable!(),
-                        },
-                        TypedTerm::Atom(_) | TypedTerm::Pid(_) | TypedTerm::Nil => Greater,
-                        _ => unreachable!(),
-                    },
-                    TypedTerm::SubBinary(self_subbinary) => match other {
-                        TypedTerm::SmallInteger(_) => Greater,
-                        TypedTerm::Boxed(other_boxed) => match other_boxed.to_typed_term().unwrap()
-                        {
-                            TypedTerm::Float(_)
-                            | TypedTerm::BigInteger(_)
-                            | TypedTerm::Reference(_)
-                            | TypedTerm::ExternalReference(_)
-                            | TypedTerm::Closure(_)
-                            | TypedTerm::ExternalPort(_)
-                            | TypedTerm::ExternalPid(_)
-                            | TypedTerm::Tuple(_)
-                            | TypedTerm::Map(_)
-                            | TypedTerm::List(_) => Greater,
-                            TypedTerm::HeapBinary(other_heap_binary) => self_subbinary
-                                .partial_cmp(other_heap_binary.as_ref())
-                                .unwrap(),
-                            TypedTerm::ProcBin(other_process_binary) => {
-                                self_subbinary.partial_cmp(&other_process_binary).unwrap()
-                            }
-                            TypedTerm::SubBinary(other_subbinary) => {
-                                self_subbinary.cmp(&other_subbinary)
-                            }
-                            TypedTerm::MatchContext(other_match_context) => self_subbinary
-                                .partial_cmp(&other_match_context)
-                                .unwrap()
-                                .reverse(),
-                            _ => unreachable!(),
-                        },
-                        TypedTerm::Atom(_) | TypedTerm::Pid(_) | TypedTerm::Nil => Greater,
-                        _ => unreachable!(),
-                    },
-                    TypedTerm::MatchContext(self_match_context) => match other {
-                        _ => unimplemented!(
-                            "unboxed match_context ({:?}) cmp {:?}",
-                            self_match_context,
-                            other
-                        ),
-                    },
-                    _ => unimplemented!("unboxed {:?} cmp {:?}", self_unboxed, other),
-                }
-            }
-            TypedTerm::Atom(self_atom) => match other {
-                TypedTerm::SmallInteger(_) => Greater,
-                TypedTerm::Boxed(other_boxed) => match other_boxed.to_typed_term().unwrap() {
-                    TypedTerm::Float(_) | TypedTerm::BigInteger(_) => Greater,
-                    _ => Less,
-                },
-                TypedTerm::Atom(other_atom) => self_atom.cmp(other_atom),
-                _ => Less,
-            },
-            TypedTerm::Port(self_port) => match other {
-                _ => unimplemented!("Port {:?} cmp {:?}", self_port, other),
-            },
-            TypedTerm::Pid(self_pid) => match other {
-                TypedTerm::SmallInteger(_) => Greater,
-                TypedTerm::Boxed(other_boxed) => match other_boxed.to_typed_term().unwrap() {
-                    TypedTerm::Float(_)
-                    | TypedTerm::BigInteger(_)
-                    | TypedTerm::Reference(_)
-                    | TypedTerm::Closure(_)
-                    | TypedTerm::ExternalPort(_) => Greater,
-                    _ => Less,
-                },
-                TypedTerm::Atom(_) | TypedTerm::Port(_) => Greater,
-                TypedTerm::Pid(other_pid) => self_pid.cmp(other_pid),
-                _ => Less,
-            },
-            TypedTerm::Nil => match other {
-                TypedTerm::SmallInteger(_) => Greater,
-                TypedTerm::Boxed(other_boxed) => match other_boxed.to_typed_term().unwrap() {
-                    TypedTerm::Float(_)
-                    | TypedTerm::BigInteger(_)
-                    | TypedTerm::Reference(_)
-                    | TypedTerm::Closure(_)
-                    | TypedTerm::ExternalPort(_)
-                    | TypedTerm::ExternalPid(_)
-                    | TypedTerm::Tuple(_)
-                    | TypedTerm::Map(_) => Greater,
-                    _ => Less,
-                },
-                TypedTerm::Atom(_) | TypedTerm::Port(_) | TypedTerm::Pid(_) => Greater,
-                TypedTerm::Nil => Equal,
-                _ => Less,
-            },
-            TypedTerm::List(self_cons) => match other {
-                TypedTerm::SmallInteger(_) => Greater,
-                TypedTerm::Boxed(other_boxed) => match other_boxed.to_typed_term().unwrap() {
-                    TypedTerm::Float(_)
-                    | TypedTerm::BigInteger(_)
-                    | TypedTerm::Reference(_)
-                    | TypedTerm::Closure(_)
-                    | TypedTerm::ExternalPort(_)
-                    | TypedTerm::ExternalPid(_)
-                    | TypedTerm::Tuple(_)
-                    | TypedTerm::Map(_) => Greater,
-                    _ => Less,
-                },
-                TypedTerm::Atom(_) | TypedTerm::Port(_) | TypedTerm::Pid(_) | TypedTerm::Nil => {
-                    Greater
-                }
-                TypedTerm::List(other_cons) => self_cons.cmp(other_cons),
-                _ => Less,
-            },
-            // rest are boxed or GC-only
-            _ => unreachable!(),
+        match (self, other) {
+            (TypedTerm::Boxed(self_boxed), _) => self_boxed.to_typed_term().unwrap().cmp(other),
+            (_, TypedTerm::Boxed(other_boxed)) => self.cmp(&other_boxed.to_typed_term().unwrap()),
+            _ => self
+                .type_order()
+                .cmp(&other.type_order())
+                .then_with(|| self.cmp_same_type_order(other)),
         }
     }
 }
 
 /// All terms in Erlang and Elixir are completely ordered.
 ///
-/// number < atom < reference < function < port < pid < tuple < map < list < bitstring
+/// number < atom < reference < function < port < pid < tuple < map < nil < list < bitstring
 ///
 /// > When comparing two numbers of different types (a number being either an integer or a float), a
 /// > conversion to the type with greater precision will always occur, unless the comparison
@@ -778,8 +669,7 @@ unsafe impl AsTerm for TypedTerm {
             &Self::List(ref inner) => inner.as_term(),
             &Self::Tuple(ref inner) => inner.as_term(),
             &Self::Map(ref inner) => inner.as_term(),
-            &Self::Boxed(ref inner) => Term::make_boxed(inner),
-            &Self::Literal(ref inner) => Term::make_boxed_literal(inner),
+            &Self::Boxed(ref inner) => inner.as_term(),
             &Self::Pid(ref inner) => inner.as_term(),
             &Self::Port(ref inner) => inner.as_term(),
             &Self::Reference(ref inner) => inner.as_term(),
@@ -812,7 +702,6 @@ impl CloneToProcess for TypedTerm {
             &Self::Tuple(ref inner) => inner.clone_to_process(process),
             &Self::Map(ref inner) => inner.clone_to_process(process),
             &Self::Boxed(ref inner) => inner.clone_to_process(process),
-            &Self::Literal(inner) => inner,
             &Self::Pid(inner) => unsafe { inner.as_term() },
             &Self::Port(inner) => unsafe { inner.as_term() },
             &Self::Reference(ref inner) => inner.clone_to_process(process),
@@ -843,7 +732,6 @@ impl CloneToProcess for TypedTerm {
             &Self::Tuple(ref inner) => inner.clone_to_heap(heap),
             &Self::Map(ref inner) => inner.clone_to_heap(heap),
             &Self::Boxed(ref inner) => inner.to_typed_term().unwrap().clone_to_heap(heap),
-            &Self::Literal(inner) => Ok(inner),
             &Self::Pid(inner) => Ok(unsafe { inner.as_term() }),
             &Self::Port(inner) => Ok(unsafe { inner.as_term() }),
             &Self::Reference(ref inner) => inner.clone_to_heap(heap),
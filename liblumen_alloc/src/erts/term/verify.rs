@@ -0,0 +1,244 @@
+//! Verification of the terms passed to and returned from BIFs, enabled by the `term_debug` feature.
+//!
+//! A term that breaks an invariant usually isn't noticed until something far from where it was
+//! made reads it.  Verifying every term crossing a BIF boundary panics in the first BIF that is
+//! passed or returns the broken term instead.  A term, and every term it contains, is checked that:
+//!
+//! - immediates have a valid tag and headers aren't used as terms
+//! - unless they are literals, boxed and list pointers point into the heaps or heap fragments of
+//!   the process
+//! - a boxed pointer points at a valid header and a list pointer points at a cons cell, neither of
+//!   which may be a move marker
+//! - the original of a sub-binary is a binary that the sub-binary's range is within
+
+use core::fmt::Display;
+
+use hashbrown::HashSet;
+
+use crate::erts::process::Process;
+use crate::erts::term::{is_move_marker, Bitstring, MaybePartialByte, SubBinary, Term, TypedTerm};
+
+/// Panics if `term`, or any term it contains, breaks an invariant of terms.  `name` says which
+/// term it is, such as `"argument 1 of erlang:send/2"`.
+pub fn verify(process: &Process, term: Term, name: &dyn Display) {
+    let mut stack = vec![term];
+    // terms can share their contents, so each box or cons cell is only checked once
+    let mut visited = HashSet::new();
+
+    while let Some(top) = stack.pop() {
+        if top.is_boxed() {
+            let ptr = top.boxed_val();
+
+            if !visited.insert(ptr as usize) {
+                continue;
+            }
+
+            check_pointer(process, name, top, ptr);
+
+            match top.to_typed_term().unwrap() {
+                TypedTerm::Boxed(boxed) => match boxed.to_typed_term() {
+                    Ok(TypedTerm::Tuple(tuple)) => stack.extend(tuple.iter()),
+                    Ok(TypedTerm::Map(map)) => {
+                        for key in map.keys() {
+                            stack.push(key);
+                            stack.push(map.get(key).unwrap());
+                        }
+                    }
+                    Ok(TypedTerm::Closure(closure)) => stack.extend_from_slice(closure.env_slice()),
+                    Ok(TypedTerm::SubBinary(subbinary)) => {
+                        check_subbinary(process, name, top, &subbinary);
+                    }
+                    Ok(_) => (),
+                    Err(error) => panic!(
+                        "{} contains {:?}, which points at an invalid header at {:?}: {:?}",
+                        name, top, ptr, error
+                    ),
+                },
+                // literals are boxed, but only have their box checked
+                _ => (),
+            }
+        } else if top.is_non_empty_list() {
+            let ptr = top.list_val();
+
+            if !visited.insert(ptr as usize) {
+                continue;
+            }
+
+            check_pointer(process, name, top, ptr);
+
+            let cons = unsafe { &*ptr };
+
+            assert!(
+                !cons.is_move_marker(),
+                "{} contains {:?}, which points at moved cons cell at {:?}",
+                name,
+                top,
+                ptr
+            );
+
+            stack.push(cons.tail);
+            stack.push(cons.head);
+        } else {
+            assert!(
+                !top.is_header(),
+                "{} contains header {:?}, which is not a term",
+                name,
+                top
+            );
+
+            if let Err(error) = top.to_typed_term() {
+                panic!(
+                    "{} contains {:?}, which has an invalid tag: {:?}",
+                    name, top, error
+                );
+            }
+        }
+    }
+}
+
+// Private
+
+/// Checks that `ptr` of `term` is aligned and, unless `term` is a literal, owned by `process`
+fn check_pointer<T>(process: &Process, name: &dyn Display, term: Term, ptr: *const T) {
+    assert!(
+        !ptr.is_null() && (ptr as usize) % core::mem::align_of::<Term>() == 0,
+        "{} contains {:?}, which is not a valid pointer",
+        name,
+        term
+    );
+
+    if !term.is_literal() {
+        assert!(
+            process.is_owner(ptr),
+            "{} contains {:?}, which points at {:?}, outside the heaps of {:?}",
+            name,
+            term,
+            ptr,
+            process.pid()
+        );
+    }
+
+    if term.is_boxed() {
+        let header = unsafe { *(ptr as *const Term) };
+
+        assert!(
+            !is_move_marker(header),
+            "{} contains {:?}, which points at move marker at {:?}",
+            name,
+            term,
+            ptr
+        );
+        assert!(
+            header.is_header(),
+            "{} contains {:?}, which is boxed, but points at {:?}, which is not a header",
+            name,
+            term,
+            header
+        );
+    }
+}
+
+/// Checks that the original of `subbinary` is a binary owned by `process` that the range of
+/// `subbinary` is within
+fn check_subbinary(process: &Process, name: &dyn Display, term: Term, subbinary: &SubBinary) {
+    let original = subbinary.original();
+
+    assert!(
+        original.is_boxed(),
+        "{} contains sub-binary {:?}, whose original {:?} is not boxed",
+        name,
+        term,
+        original
+    );
+
+    check_pointer(process, name, original, original.boxed_val());
+
+    let original_byte_len = match original.to_typed_term().unwrap() {
+        TypedTerm::Boxed(boxed) => match boxed.to_typed_term() {
+            Ok(TypedTerm::ProcBin(procbin)) => procbin.full_byte_len(),
+            Ok(TypedTerm::HeapBinary(heap_binary)) => heap_binary.full_byte_len(),
+            _ => panic!(
+                "{} contains sub-binary {:?}, whose original {:?} is not a binary",
+                name, term, original
+            ),
+        },
+        _ => panic!(
+            "{} contains sub-binary {:?}, whose original {:?} is not a binary",
+            name, term, original
+        ),
+    };
+    let end_bit_offset = (subbinary.byte_offset() + subbinary.full_byte_len()) * 8
+        + (subbinary.bit_offset() as usize)
+        + (subbinary.partial_byte_bit_len() as usize);
+
+    assert!(
+        end_bit_offset <= original_byte_len * 8,
+        "{} contains sub-binary {:?}, which ends at bit {} of its {} byte original {:?}",
+        name,
+        term,
+        end_bit_offset,
+        original_byte_len,
+        original
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::sync::Arc;
+
+    use crate::erts::process::{default_heap, Priority};
+    use crate::erts::scheduler;
+    use crate::erts::term::{atom_unchecked, Atom};
+    use crate::erts::ModuleFunctionArity;
+
+    #[test]
+    fn verify_with_terms_on_heap() {
+        let process = process();
+        let inner = process
+            .tuple_from_slice(&[atom_unchecked("inner")])
+            .unwrap();
+        let list = process.cons(inner, Term::NIL).unwrap();
+        let binary = process.binary_from_bytes(&[1, 2, 3]).unwrap();
+        let subbinary = process.subbinary_from_original(binary, 1, 0, 2, 0).unwrap();
+        let term = process.tuple_from_slice(&[inner, list, subbinary]).unwrap();
+
+        verify(&process, term, &"term");
+    }
+
+    #[test]
+    #[should_panic(expected = "outside the heaps")]
+    fn verify_with_term_on_other_process_heap() {
+        let process = process();
+        let other_process = self::process();
+        let other = other_process
+            .tuple_from_slice(&[atom_unchecked("other")])
+            .unwrap();
+        let term = process.cons(other, Term::NIL).unwrap();
+
+        verify(&process, term, &"term");
+    }
+
+    fn process() -> Process {
+        let init = Atom::try_from_str("init").unwrap();
+        let initial_module_function_arity = Arc::new(ModuleFunctionArity {
+            module: init,
+            function: init,
+            arity: 0,
+        });
+        let (heap, heap_size) = default_heap().unwrap();
+
+        let process = Process::new(
+            Priority::Normal,
+            None,
+            initial_module_function_arity,
+            heap,
+            heap_size,
+        );
+
+        process.schedule_with(scheduler::id::next());
+
+        process
+    }
+}
@@ -3,6 +3,17 @@ macro_rules! badarg {
     () => {
         $crate::erts::exception::runtime::Exception::badarg(file!(), line!(), column!())
     };
+    ($argument:expr, $cause:expr) => {
+        $crate::erts::exception::runtime::Exception::badarg_with_error_info(
+            $crate::erts::exception::runtime::ErrorInfo {
+                argument: $argument,
+                cause: $cause,
+            },
+            file!(),
+            line!(),
+            column!(),
+        )
+    };
 }
 
 #[macro_export]
@@ -73,6 +84,7 @@ macro_rules! exception {
             class: $class,
             reason: $reason,
             stacktrace: $stacktrace,
+            error_info: None,
             file: file!(),
             line: line!(),
             column: column!(),
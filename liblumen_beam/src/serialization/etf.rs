@@ -33,10 +33,10 @@ mod test;
 
 use std::convert::From;
 
-use num::bigint::BigInt;
+use num::bigint::{BigInt, Sign};
 
 pub use self::codec::{DecodeError, DecodeResult};
-pub use self::codec::{EncodeError, EncodeResult};
+pub use self::codec::{EncodeError, EncodeOptions, EncodeResult};
 
 /// Term.
 #[derive(Debug, PartialEq, Clone)]
@@ -63,11 +63,27 @@ impl Term {
         codec::Decoder::new(reader).decode()
     }
 
+    /// Decodes the term at the start of `bytes`, returning it with the bytes after it.
+    ///
+    /// A compressed term takes the rest of `bytes`.
+    pub fn decode_prefix(bytes: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        codec::decode_prefix(bytes)
+    }
+
     /// Encodes the term.
     pub fn encode<W: std::io::Write>(&self, writer: W) -> EncodeResult {
         codec::Encoder::new(writer).encode(self)
     }
 
+    /// Encodes the term as `term_to_binary/2` does with `{minor_version, _}`.
+    pub fn encode_with_options<W: std::io::Write>(
+        &self,
+        writer: W,
+        options: EncodeOptions,
+    ) -> EncodeResult {
+        codec::Encoder::with_options(writer, options).encode(self)
+    }
+
     pub fn as_match<'a, P>(&'a self, pattern: P) -> pattern::Result<P::Output>
     where
         P: pattern::Pattern<'a>,
@@ -251,6 +267,24 @@ pub struct BigInteger {
     /// The value of the integer
     pub value: BigInt,
 }
+impl BigInteger {
+    /// The integer with the little-endian magnitude `bytes`, as the external term format stores
+    /// it.
+    pub fn from_bytes_le(negative: bool, bytes: &[u8]) -> Self {
+        let sign = if negative { Sign::Minus } else { Sign::Plus };
+
+        BigInteger {
+            value: BigInt::from_bytes_le(sign, bytes),
+        }
+    }
+
+    /// Whether the integer is negative and its little-endian magnitude.
+    pub fn to_bytes_le(&self) -> (bool, Vec<u8>) {
+        let (sign, bytes) = self.value.to_bytes_le();
+
+        (sign == Sign::Minus, bytes)
+    }
+}
 impl std::fmt::Display for BigInteger {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "{}", self.value)
@@ -364,10 +398,10 @@ pub struct Pid {
     pub node: Atom,
     pub id: u32,
     pub serial: u32,
-    pub creation: u8,
+    pub creation: u32,
 }
 impl Pid {
-    pub fn new<T>(node: T, id: u32, serial: u32, creation: u8) -> Self
+    pub fn new<T>(node: T, id: u32, serial: u32, creation: u32) -> Self
     where
         Atom: From<T>,
     {
@@ -400,8 +434,9 @@ impl<'a> From<(&'a str, u32, u32)> for Pid {
 #[derive(Debug, PartialEq, Clone)]
 pub struct Port {
     pub node: Atom,
-    pub id: u32,
-    pub creation: u8,
+    /// Only ids that don't fit in 32 bits need `V4_PORT_EXT`
+    pub id: u64,
+    pub creation: u32,
 }
 impl std::fmt::Display for Port {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -412,7 +447,7 @@ impl<'a> From<(&'a str, u32)> for Port {
     fn from((node, id): (&'a str, u32)) -> Self {
         Port {
             node: Atom::from(node),
-            id,
+            id: u64::from(id),
             creation: 0,
         }
     }
@@ -423,7 +458,7 @@ impl<'a> From<(&'a str, u32)> for Port {
 pub struct Reference {
     pub node: Atom,
     pub id: Vec<u32>,
-    pub creation: u8,
+    pub creation: u32,
 }
 impl std::fmt::Display for Reference {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -513,7 +548,6 @@ impl std::fmt::Display for InternalFun {
                 uniq,
                 ..
             } => {
-                use num::bigint::Sign;
                 let uniq = BigInt::from_bytes_be(Sign::Plus, &uniq);
                 write!(f, "#Fun<{}.{}.{}>", module, index, uniq)
             }
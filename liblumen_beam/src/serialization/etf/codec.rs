@@ -1,6 +1,6 @@
 mod aux;
 
-use std::io::Write;
+use std::io::{Read, Write};
 
 use byteorder::BigEndian;
 use byteorder::ReadBytesExt;
@@ -28,6 +28,9 @@ pub enum DecodeError {
     #[fail(display = "unknown tag: '{}'", tag)]
     UnknownTag { tag: u8 },
 
+    #[fail(display = "unsupported tag: '{}'", tag)]
+    UnsupportedTag { tag: u8 },
+
     #[fail(display = "unexpected type! {} is not a {}", value, expected)]
     UnexpectedType { value: Term, expected: String },
 
@@ -70,6 +73,33 @@ impl std::convert::From<std::io::Error> for EncodeError {
 pub type DecodeResult = Result<Term, DecodeError>;
 pub type EncodeResult = Result<(), EncodeError>;
 
+/// Options of the encoding, as `term_to_binary/2` takes them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EncodeOptions {
+    /// `0` encodes floats as text and `1` as their bits.  `2` also encodes atoms as UTF-8 when
+    /// they could be encoded as Latin-1.
+    pub minor_version: u8,
+}
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        EncodeOptions { minor_version: 1 }
+    }
+}
+
+/// Decodes the term at the start of `bytes`, returning it with the bytes after it.  A compressed
+/// term takes the rest of the bytes.
+pub fn decode_prefix(bytes: &[u8]) -> Result<(Term, &[u8]), DecodeError> {
+    let mut decoder = Decoder::new(bytes);
+    let term = decoder.decode_with_version()?;
+    let rest = if bytes.get(1) == Some(&COMPRESSED_TERM) {
+        &bytes[bytes.len()..]
+    } else {
+        decoder.reader
+    };
+
+    Ok((term, rest))
+}
+
 const VERSION: u8 = 131;
 
 const DISTRIBUTION_HEADER: u8 = 68;
@@ -77,9 +107,14 @@ const NEW_FLOAT_EXT: u8 = 70;
 const BIT_BINARY_EXT: u8 = 77;
 const COMPRESSED_TERM: u8 = 80;
 const ATOM_CACHE_REF: u8 = 82;
+const NEW_PID_EXT: u8 = 88;
+const NEW_PORT_EXT: u8 = 89;
+const NEWER_REFERENCE_EXT: u8 = 90;
 const SMALL_INTEGER_EXT: u8 = 97;
 const INTEGER_EXT: u8 = 98;
 const FLOAT_EXT: u8 = 99;
+/// The length of the text of a `FLOAT_EXT`
+const FLOAT_EXT_LEN: usize = 31;
 const ATOM_EXT: u8 = 100;
 const REFERENCE_EXT: u8 = 101;
 const PORT_EXT: u8 = 102;
@@ -100,6 +135,7 @@ const MAP_EXT: u8 = 116;
 const FUN_EXT: u8 = 117;
 const ATOM_UTF8_EXT: u8 = 118;
 const SMALL_ATOM_UTF8_EXT: u8 = 119;
+const V4_PORT_EXT: u8 = 120;
 
 pub struct Decoder<R> {
     reader: R,
@@ -113,6 +149,9 @@ impl<R: std::io::Read> Decoder<R> {
         }
     }
     pub fn decode(mut self) -> DecodeResult {
+        self.decode_with_version()
+    }
+    fn decode_with_version(&mut self) -> DecodeResult {
         let version = self.reader.read_u8()?;
         if version != VERSION {
             return Err(DecodeError::UnsupportedVersion { version });
@@ -120,7 +159,7 @@ impl<R: std::io::Read> Decoder<R> {
         let tag = self.reader.read_u8()?;
         match tag {
             COMPRESSED_TERM => self.decode_compressed_term(),
-            DISTRIBUTION_HEADER => unimplemented!(),
+            DISTRIBUTION_HEADER => Err(DecodeError::UnsupportedTag { tag }),
             _ => self.decode_term_with_tag(tag),
         }
     }
@@ -132,11 +171,15 @@ impl<R: std::io::Read> Decoder<R> {
         match tag {
             NEW_FLOAT_EXT => self.decode_new_float_ext(),
             BIT_BINARY_EXT => self.decode_bit_binary_ext(),
-            ATOM_CACHE_REF => unimplemented!(),
+            ATOM_CACHE_REF => Err(DecodeError::UnsupportedTag { tag }),
             SMALL_INTEGER_EXT => self.decode_small_integer_ext(),
             INTEGER_EXT => self.decode_integer_ext(),
             FLOAT_EXT => self.decode_float_ext(),
             ATOM_EXT => self.decode_atom_ext(),
+            NEW_PID_EXT => self.decode_new_pid_ext(),
+            NEW_PORT_EXT => self.decode_new_port_ext(),
+            NEWER_REFERENCE_EXT => self.decode_newer_reference_ext(),
+            V4_PORT_EXT => self.decode_v4_port_ext(),
             REFERENCE_EXT => self.decode_reference_ext(),
             PORT_EXT => self.decode_port_ext(),
             PID_EXT => self.decode_pid_ext(),
@@ -160,10 +203,42 @@ impl<R: std::io::Read> Decoder<R> {
         }
     }
     fn decode_compressed_term(&mut self) -> DecodeResult {
-        let _uncompressed_size = self.reader.read_u32::<BigEndian>()? as usize;
+        let uncompressed_size = self.reader.read_u32::<BigEndian>()? as usize;
         let zlib_decoder = zlib::Decoder::new(&mut self.reader)?;
-        let mut decoder = Decoder::new(zlib_decoder);
-        decoder.decode_term()
+        let mut uncompressed = Vec::new();
+        // one more byte than claimed, so that a wrong size is noticed without inflating all of it
+        zlib_decoder
+            .take(uncompressed_size as u64 + 1)
+            .read_to_end(&mut uncompressed)?;
+        if uncompressed.len() != uncompressed_size {
+            return aux::invalid_data_error(format!(
+                "uncompressed size is {}, not {}",
+                uncompressed.len(),
+                uncompressed_size
+            ))
+            .map_err(From::from);
+        }
+        let mut decoder = Decoder::new(uncompressed.as_slice());
+        let term = decoder.decode_term()?;
+        if !decoder.reader.is_empty() {
+            return aux::invalid_data_error(format!(
+                "{} bytes follow the compressed term",
+                decoder.reader.len()
+            ))
+            .map_err(From::from);
+        }
+        Ok(term)
+    }
+    /// Reads `len` bytes, which the encoding claims are there, without allocating them all before
+    /// they are.
+    fn read_bytes(&mut self, len: usize) -> std::io::Result<Vec<u8>> {
+        let mut buf = Vec::with_capacity(aux::preallocated_len(len));
+        (&mut self.reader).take(len as u64).read_to_end(&mut buf)?;
+        if buf.len() == len {
+            Ok(buf)
+        } else {
+            Err(std::io::ErrorKind::UnexpectedEof.into())
+        }
     }
     fn decode_nil_ext(&mut self) -> DecodeResult {
         Ok(Term::from(List::nil()))
@@ -178,7 +253,7 @@ impl<R: std::io::Read> Decoder<R> {
     }
     fn decode_list_ext(&mut self) -> DecodeResult {
         let count = self.reader.read_u32::<BigEndian>()? as usize;
-        let mut elements = Vec::with_capacity(count);
+        let mut elements = Vec::with_capacity(aux::preallocated_len(count));
         for _ in 0..count {
             elements.push(self.decode_term()?);
         }
@@ -195,7 +270,7 @@ impl<R: std::io::Read> Decoder<R> {
     }
     fn decode_small_tuple_ext(&mut self) -> DecodeResult {
         let count = self.reader.read_u8()? as usize;
-        let mut elements = Vec::with_capacity(count);
+        let mut elements = Vec::with_capacity(aux::preallocated_len(count));
         for _ in 0..count {
             elements.push(self.decode_term()?);
         }
@@ -203,7 +278,7 @@ impl<R: std::io::Read> Decoder<R> {
     }
     fn decode_large_tuple_ext(&mut self) -> DecodeResult {
         let count = self.reader.read_u32::<BigEndian>()? as usize;
-        let mut elements = Vec::with_capacity(count);
+        let mut elements = Vec::with_capacity(aux::preallocated_len(count));
         for _ in 0..count {
             elements.push(self.decode_term()?);
         }
@@ -211,7 +286,7 @@ impl<R: std::io::Read> Decoder<R> {
     }
     fn decode_map_ext(&mut self) -> DecodeResult {
         let count = self.reader.read_u32::<BigEndian>()? as usize;
-        let mut entries = Vec::with_capacity(count);
+        let mut entries = Vec::with_capacity(aux::preallocated_len(count));
         for _ in 0..count {
             let k = self.decode_term()?;
             let v = self.decode_term()?;
@@ -221,15 +296,13 @@ impl<R: std::io::Read> Decoder<R> {
     }
     fn decode_binary_ext(&mut self) -> DecodeResult {
         let size = self.reader.read_u32::<BigEndian>()? as usize;
-        let mut buf = vec![0; size];
-        self.reader.read_exact(&mut buf)?;
+        let buf = self.read_bytes(size)?;
         Ok(Term::from(Binary::from(buf)))
     }
     fn decode_bit_binary_ext(&mut self) -> DecodeResult {
         let size = self.reader.read_u32::<BigEndian>()? as usize;
         let tail_bits_size = self.reader.read_u8()?;
-        let mut buf = vec![0; size];
-        self.reader.read_exact(&mut buf)?;
+        let mut buf = self.read_bytes(size)?;
         if !buf.is_empty() {
             let last = buf[size - 1] >> (8 - tail_bits_size);
             buf[size - 1] = last;
@@ -242,7 +315,16 @@ impl<R: std::io::Read> Decoder<R> {
             node,
             id: self.reader.read_u32::<BigEndian>()?,
             serial: self.reader.read_u32::<BigEndian>()?,
-            creation: self.reader.read_u8()?,
+            creation: u32::from(self.reader.read_u8()?),
+        }))
+    }
+    fn decode_new_pid_ext(&mut self) -> DecodeResult {
+        let node = self.decode_term().and_then(aux::term_into_atom)?;
+        Ok(Term::from(Pid {
+            node,
+            id: self.reader.read_u32::<BigEndian>()?,
+            serial: self.reader.read_u32::<BigEndian>()?,
+            creation: self.reader.read_u32::<BigEndian>()?,
         }))
     }
     fn decode_port_ext(&mut self) -> DecodeResult {
@@ -254,8 +336,24 @@ impl<R: std::io::Read> Decoder<R> {
         })?;
         Ok(Term::from(Port {
             node,
-            id: self.reader.read_u32::<BigEndian>()?,
-            creation: self.reader.read_u8()?,
+            id: u64::from(self.reader.read_u32::<BigEndian>()?),
+            creation: u32::from(self.reader.read_u8()?),
+        }))
+    }
+    fn decode_new_port_ext(&mut self) -> DecodeResult {
+        let node = self.decode_term().and_then(aux::term_into_atom)?;
+        Ok(Term::from(Port {
+            node,
+            id: u64::from(self.reader.read_u32::<BigEndian>()?),
+            creation: self.reader.read_u32::<BigEndian>()?,
+        }))
+    }
+    fn decode_v4_port_ext(&mut self) -> DecodeResult {
+        let node = self.decode_term().and_then(aux::term_into_atom)?;
+        Ok(Term::from(Port {
+            node,
+            id: self.reader.read_u64::<BigEndian>()?,
+            creation: self.reader.read_u32::<BigEndian>()?,
         }))
     }
     fn decode_reference_ext(&mut self) -> DecodeResult {
@@ -263,14 +361,24 @@ impl<R: std::io::Read> Decoder<R> {
         Ok(Term::from(Reference {
             node,
             id: vec![self.reader.read_u32::<BigEndian>()?],
-            creation: self.reader.read_u8()?,
+            creation: u32::from(self.reader.read_u8()?),
         }))
     }
     fn decode_new_reference_ext(&mut self) -> DecodeResult {
         let id_count = self.reader.read_u16::<BigEndian>()? as usize;
         let node = self.decode_term().and_then(aux::term_into_atom)?;
-        let creation = self.reader.read_u8()?;
-        let mut id = Vec::with_capacity(id_count);
+        let creation = u32::from(self.reader.read_u8()?);
+        let mut id = Vec::with_capacity(aux::preallocated_len(id_count));
+        for _ in 0..id_count {
+            id.push(self.reader.read_u32::<BigEndian>()?);
+        }
+        Ok(Term::from(Reference { node, id, creation }))
+    }
+    fn decode_newer_reference_ext(&mut self) -> DecodeResult {
+        let id_count = self.reader.read_u16::<BigEndian>()? as usize;
+        let node = self.decode_term().and_then(aux::term_into_atom)?;
+        let creation = self.reader.read_u32::<BigEndian>()?;
+        let mut id = Vec::with_capacity(aux::preallocated_len(id_count));
         for _ in 0..id_count {
             id.push(self.reader.read_u32::<BigEndian>()?);
         }
@@ -294,7 +402,7 @@ impl<R: std::io::Read> Decoder<R> {
         let module = self.decode_term().and_then(aux::term_into_atom)?;
         let index = self.decode_term().and_then(aux::term_into_fix_integer)?;
         let uniq = self.decode_term().and_then(aux::term_into_fix_integer)?;
-        let mut vars = Vec::with_capacity(num_free as usize);
+        let mut vars = Vec::with_capacity(aux::preallocated_len(num_free as usize));
         for _ in 0..num_free {
             vars.push(self.decode_term()?);
         }
@@ -317,7 +425,7 @@ impl<R: std::io::Read> Decoder<R> {
         let old_index = self.decode_term().and_then(aux::term_into_fix_integer)?;
         let old_uniq = self.decode_term().and_then(aux::term_into_fix_integer)?;
         let pid = self.decode_term().and_then(aux::term_into_pid)?;
-        let mut vars = Vec::with_capacity(num_free as usize);
+        let mut vars = Vec::with_capacity(aux::preallocated_len(num_free as usize));
         for _ in 0..num_free {
             vars.push(self.decode_term()?);
         }
@@ -337,15 +445,15 @@ impl<R: std::io::Read> Decoder<R> {
         Ok(Term::from(Float::from(value)))
     }
     fn decode_float_ext(&mut self) -> DecodeResult {
-        let mut buf = [0; 31];
+        let mut buf = [0; FLOAT_EXT_LEN];
         self.reader.read_exact(&mut buf)?;
-        let float_str = std::str::from_utf8(&mut buf)
+        let float_str = std::str::from_utf8(&buf)
             .or_else(|e| aux::invalid_data_error(e.to_string()))?
             .trim_end_matches(0 as char);
         let value = float_str
-            .parse::<f32>()
+            .parse::<f64>()
             .or_else(|e| aux::invalid_data_error(e.to_string()))?;
-        Ok(Term::from(Float::from(value as f64)))
+        Ok(Term::from(Float::from(value)))
     }
     fn decode_small_integer_ext(&mut self) -> DecodeResult {
         let value = self.reader.read_u8()?;
@@ -366,23 +474,22 @@ impl<R: std::io::Read> Decoder<R> {
     fn decode_large_big_ext(&mut self) -> DecodeResult {
         let count = self.reader.read_u32::<BigEndian>()? as usize;
         let sign = self.reader.read_u8()?;
-        self.buf.resize(count, 0);
-        self.reader.read_exact(&mut self.buf)?;
-        let value = BigInt::from_bytes_le(aux::byte_to_sign(sign)?, &self.buf);
+        let buf = self.read_bytes(count)?;
+        let value = BigInt::from_bytes_le(aux::byte_to_sign(sign)?, &buf);
         Ok(Term::from(BigInteger { value }))
     }
     fn decode_atom_ext(&mut self) -> DecodeResult {
         let len = self.reader.read_u16::<BigEndian>()?;
         self.buf.resize(len as usize, 0);
         self.reader.read_exact(&mut self.buf)?;
-        let name = aux::latin1_bytes_to_string(&self.buf)?;
+        let name = aux::latin1_bytes_to_string(&self.buf);
         Ok(Term::from(Atom { name }))
     }
     fn decode_small_atom_ext(&mut self) -> DecodeResult {
         let len = self.reader.read_u8()?;
         self.buf.resize(len as usize, 0);
         self.reader.read_exact(&mut self.buf)?;
-        let name = aux::latin1_bytes_to_string(&self.buf)?;
+        let name = aux::latin1_bytes_to_string(&self.buf);
         Ok(Term::from(Atom { name }))
     }
     fn decode_atom_utf8_ext(&mut self) -> DecodeResult {
@@ -405,10 +512,14 @@ impl<R: std::io::Read> Decoder<R> {
 
 pub struct Encoder<W> {
     writer: W,
+    options: EncodeOptions,
 }
 impl<W: std::io::Write> Encoder<W> {
     pub fn new(writer: W) -> Self {
-        Encoder { writer }
+        Self::with_options(writer, Default::default())
+    }
+    pub fn with_options(writer: W, options: EncodeOptions) -> Self {
+        Encoder { writer, options }
     }
     pub fn encode(mut self, term: &Term) -> EncodeResult {
         self.writer.write_u8(VERSION)?;
@@ -515,24 +626,43 @@ impl<W: std::io::Write> Encoder<W> {
         }
         Ok(())
     }
+    /// Minor version 0 encodes floats as text, padded with zeros to `FLOAT_EXT_LEN`.
     fn encode_float(&mut self, x: &Float) -> EncodeResult {
-        self.writer.write_u8(NEW_FLOAT_EXT)?;
-        self.writer.write_f64::<BigEndian>(x.value)?;
+        if self.options.minor_version == 0 {
+            let text = aux::float_text(x.value);
+            let mut buf = [0; FLOAT_EXT_LEN];
+            buf[..text.len()].copy_from_slice(text.as_bytes());
+            self.writer.write_u8(FLOAT_EXT)?;
+            self.writer.write_all(&buf)?;
+        } else {
+            self.writer.write_u8(NEW_FLOAT_EXT)?;
+            self.writer.write_f64::<BigEndian>(x.value)?;
+        }
         Ok(())
     }
+    /// Before minor version 2, atoms that can be are encoded as Latin-1, as BEAM does.
     fn encode_atom(&mut self, x: &Atom) -> EncodeResult {
-        if x.name.len() > 0xFFFF {
-            return Err(EncodeError::TooLongAtomName(x.clone()));
-        }
-
-        let is_ascii = x.name.as_bytes().iter().all(|&c| c < 0x80);
-        if is_ascii {
+        if self.options.minor_version < 2 && aux::is_latin1(&x.name) {
+            let latin1: Vec<u8> = x.name.chars().map(|c| c as u8).collect();
+            if latin1.len() > 0xFFFF {
+                return Err(EncodeError::TooLongAtomName(x.clone()));
+            }
             self.writer.write_u8(ATOM_EXT)?;
+            self.writer.write_u16::<BigEndian>(latin1.len() as u16)?;
+            self.writer.write_all(&latin1)?;
         } else {
-            self.writer.write_u8(ATOM_UTF8_EXT)?;
+            let utf8 = x.name.as_bytes();
+            if utf8.len() <= 0xFF {
+                self.writer.write_u8(SMALL_ATOM_UTF8_EXT)?;
+                self.writer.write_u8(utf8.len() as u8)?;
+            } else if utf8.len() <= 0xFFFF {
+                self.writer.write_u8(ATOM_UTF8_EXT)?;
+                self.writer.write_u16::<BigEndian>(utf8.len() as u16)?;
+            } else {
+                return Err(EncodeError::TooLongAtomName(x.clone()));
+            }
+            self.writer.write_all(utf8)?;
         }
-        self.writer.write_u16::<BigEndian>(x.name.len() as u16)?;
-        self.writer.write_all(x.name.as_bytes())?;
         Ok(())
     }
     fn encode_fix_integer(&mut self, x: &FixInteger) -> EncodeResult {
@@ -561,33 +691,60 @@ impl<W: std::io::Write> Encoder<W> {
         Ok(())
     }
     fn encode_pid(&mut self, x: &Pid) -> EncodeResult {
-        self.writer.write_u8(PID_EXT)?;
+        if aux::is_small_creation(x.creation) {
+            self.writer.write_u8(PID_EXT)?;
+        } else {
+            self.writer.write_u8(NEW_PID_EXT)?;
+        }
         self.encode_atom(&x.node)?;
         self.writer.write_u32::<BigEndian>(x.id)?;
         self.writer.write_u32::<BigEndian>(x.serial)?;
-        self.writer.write_u8(x.creation)?;
+        self.encode_creation(x.creation)?;
         Ok(())
     }
     fn encode_port(&mut self, x: &Port) -> EncodeResult {
-        self.writer.write_u8(PORT_EXT)?;
+        if x.id > u64::from(std::u32::MAX) {
+            self.writer.write_u8(V4_PORT_EXT)?;
+            self.encode_atom(&x.node)?;
+            self.writer.write_u64::<BigEndian>(x.id)?;
+            self.writer.write_u32::<BigEndian>(x.creation)?;
+            return Ok(());
+        }
+        if aux::is_small_creation(x.creation) {
+            self.writer.write_u8(PORT_EXT)?;
+        } else {
+            self.writer.write_u8(NEW_PORT_EXT)?;
+        }
         self.encode_atom(&x.node)?;
-        self.writer.write_u32::<BigEndian>(x.id)?;
-        self.writer.write_u8(x.creation)?;
+        self.writer.write_u32::<BigEndian>(x.id as u32)?;
+        self.encode_creation(x.creation)?;
         Ok(())
     }
     fn encode_reference(&mut self, x: &Reference) -> EncodeResult {
-        self.writer.write_u8(NEW_REFERENCE_EXT)?;
+        if aux::is_small_creation(x.creation) {
+            self.writer.write_u8(NEW_REFERENCE_EXT)?;
+        } else {
+            self.writer.write_u8(NEWER_REFERENCE_EXT)?;
+        }
         if x.id.len() > std::u16::MAX as usize {
             return Err(EncodeError::TooLargeReferenceId(x.clone()));
         }
         self.writer.write_u16::<BigEndian>(x.id.len() as u16)?;
         self.encode_atom(&x.node)?;
-        self.writer.write_u8(x.creation)?;
+        self.encode_creation(x.creation)?;
         for n in &x.id {
             self.writer.write_u32::<BigEndian>(*n)?;
         }
         Ok(())
     }
+    fn encode_creation(&mut self, creation: u32) -> EncodeResult {
+        if aux::is_small_creation(creation) {
+            self.writer.write_u8(creation as u8)?;
+        } else {
+            self.writer.write_u32::<BigEndian>(creation)?;
+        }
+        Ok(())
+    }
     fn encode_external_fun(&mut self, x: &ExternalFun) -> EncodeResult {
         self.writer.write_u8(EXPORT_EXT)?;
         self.encode_atom(&x.module)?;
@@ -628,7 +785,7 @@ impl<W: std::io::Write> Encoder<W> {
 
                 let mut buf = Vec::new();
                 {
-                    let mut tmp = Encoder::new(&mut buf);
+                    let mut tmp = Encoder::with_options(&mut buf, self.options);
                     tmp.writer.write_u8(arity)?;
                     tmp.writer.write_all(uniq)?;
                     tmp.writer.write_u32::<BigEndian>(index)?;
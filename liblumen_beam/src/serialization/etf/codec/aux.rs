@@ -1,5 +1,7 @@
 use std::ops::Range;
 
+const MAX_PREALLOCATED_LEN: usize = 1024;
+
 use num::bigint::Sign;
 
 use self::convert::TryInto;
@@ -39,14 +41,33 @@ pub fn invalid_data_error<T>(message: String) -> std::io::Result<T> {
         message,
     ))
 }
-pub fn other_error<T>(message: String) -> std::io::Result<T> {
-    Err(std::io::Error::new(std::io::ErrorKind::Other, message))
+pub fn latin1_bytes_to_string(buf: &[u8]) -> String {
+    buf.iter().map(|&b| char::from(b)).collect()
+}
+/// Whether every character of `s` is in Latin-1, so that it can be encoded as a byte.
+pub fn is_latin1(s: &str) -> bool {
+    s.chars().all(|c| (c as u32) <= 0xFF)
+}
+/// `value` as C's `"%.20e"` writes it, with the exponent signed and at least 2 digits.
+pub fn float_text(value: f64) -> String {
+    let rust_text = format!("{:.20e}", value);
+
+    match rust_text.find('e') {
+        Some(index) => {
+            let (mantissa, exponent) = rust_text.split_at(index);
+            let exponent: i32 = exponent[1..].parse().unwrap();
+            let sign = if exponent < 0 { '-' } else { '+' };
+
+            format!("{}e{}{:02}", mantissa, sign, exponent.abs())
+        }
+        None => rust_text,
+    }
 }
-pub fn latin1_bytes_to_string(buf: &[u8]) -> std::io::Result<String> {
-    // FIXME: Supports Latin1 characters
-    std::str::from_utf8(buf)
-        .or_else(|e| other_error(e.to_string()))
-        .map(|s| s.to_string())
+/// How many elements to reserve room for when the encoding claims there are `len`.  The claim
+/// isn't trusted past `MAX_PREALLOCATED_LEN`, so that a few bytes can't make the decoder allocate
+/// gigabytes before it finds out the elements aren't there.
+pub fn preallocated_len(len: usize) -> usize {
+    len.min(MAX_PREALLOCATED_LEN)
 }
 pub fn byte_to_sign(b: u8) -> std::io::Result<Sign> {
     match b {
@@ -62,3 +83,8 @@ pub fn sign_to_byte(sign: Sign) -> u8 {
         0
     }
 }
+/// Creation values that fit in the 2 bits of the legacy `PID_EXT`, `PORT_EXT`, and
+/// `NEW_REFERENCE_EXT` formats.  Anything larger needs the 32-bit `NEW_*` formats.
+pub fn is_small_creation(creation: u32) -> bool {
+    creation <= 0b11
+}
@@ -28,11 +28,28 @@ fn atom_test() {
         decode(&[131, 119, 3, 102, 111, 111]).try_into()
     ); // SMALL_ATOM_UTF8_EXT
 
+    assert_eq!(
+        Ok(Atom::from("éo")),
+        decode(&[131, 100, 0, 2, 233, 111]).try_into()
+    ); // ATOM_EXT in Latin-1
+
     // Encode
     assert_eq!(
         vec![131, 100, 0, 3, 102, 111, 111],
         encode(Term::from(Atom::from("foo")))
     );
+    assert_eq!(
+        vec![131, 100, 0, 2, 233, 111],
+        encode(Term::from(Atom::from("éo")))
+    );
+    assert_eq!(
+        vec![131, 119, 3, 196, 137, 111],
+        encode(Term::from(Atom::from("ĉo")))
+    );
+    assert_eq!(
+        vec![131, 119, 3, 195, 169, 111],
+        encode_with_minor_version(Term::from(Atom::from("éo")), 2)
+    );
 }
 
 #[test]
@@ -103,7 +120,7 @@ fn float_test() {
 
     // Decode
     assert_eq!(
-        Ok(Float::from("1.23".parse::<f32>().unwrap() as f64)),
+        Ok(Float::from(1.23)),
         decode(&[
             131, 99, 49, 46, 50, 50, 57, 57, 57, 57, 57, 57, 57, 57, 57, 57, 57, 57, 57, 57, 56,
             50, 50, 52, 101, 43, 48, 48, 0, 0, 0, 0, 0
@@ -126,6 +143,13 @@ fn float_test() {
         vec![131, 70, 64, 94, 221, 47, 26, 159, 190, 119],
         encode(Term::from(Float::from(123.456)))
     );
+    assert_eq!(
+        vec![
+            131, 99, 49, 46, 50, 50, 57, 57, 57, 57, 57, 57, 57, 57, 57, 57, 57, 57, 57, 57, 56,
+            50, 50, 52, 101, 43, 48, 48, 0, 0, 0, 0, 0
+        ],
+        encode_with_minor_version(Term::from(Float::from(1.23)), 0)
+    ); // FLOAT_EXT
 }
 
 #[test]
@@ -145,6 +169,10 @@ fn pid_test() {
         ])
         .try_into()
     ); // PID_EXT
+    assert_eq!(
+        Ok(Pid::new("foo", 1, 2, 65536)),
+        decode(&[131, 88, 100, 0, 3, 102, 111, 111, 0, 0, 0, 1, 0, 0, 0, 2, 0, 1, 0, 0]).try_into()
+    ); // NEW_PID_EXT
 
     // Encode
     assert_eq!(
@@ -154,6 +182,10 @@ fn pid_test() {
         ],
         encode(Term::from(Pid::from(("nonode@nohost", 49, 0))))
     );
+    assert_eq!(
+        vec![131, 88, 100, 0, 3, 102, 111, 111, 0, 0, 0, 1, 0, 0, 0, 2, 0, 1, 0, 0],
+        encode(Term::from(Pid::new("foo", 1, 2, 65536)))
+    );
 }
 
 #[test]
@@ -173,6 +205,23 @@ fn port_test() {
         ])
         .try_into()
     ); // PORT_EXT
+    assert_eq!(
+        Ok(Port {
+            node: Atom::from("foo"),
+            id: 366,
+            creation: 65536
+        }),
+        decode(&[131, 89, 100, 0, 3, 102, 111, 111, 0, 0, 1, 110, 0, 1, 0, 0]).try_into()
+    ); // NEW_PORT_EXT
+    assert_eq!(
+        Ok(Port {
+            node: Atom::from("foo"),
+            id: 1 << 32,
+            creation: 2
+        }),
+        decode(&[131, 120, 100, 0, 3, 102, 111, 111, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2])
+            .try_into()
+    ); // V4_PORT_EXT
 
     // Encode
     assert_eq!(
@@ -182,6 +231,22 @@ fn port_test() {
         ],
         encode(Term::from(Port::from(("nonode@nohost", 366))))
     );
+    assert_eq!(
+        vec![131, 89, 100, 0, 3, 102, 111, 111, 0, 0, 1, 110, 0, 1, 0, 0],
+        encode(Term::from(Port {
+            node: Atom::from("foo"),
+            id: 366,
+            creation: 65536
+        }))
+    );
+    assert_eq!(
+        vec![131, 120, 100, 0, 3, 102, 111, 111, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2],
+        encode(Term::from(Port {
+            node: Atom::from("foo"),
+            id: 1 << 32,
+            creation: 2
+        }))
+    );
 }
 
 #[test]
@@ -206,12 +271,28 @@ fn reference_test() {
         // NEW_REFERENCE_EXT
         decode(&[131, 101, 115, 3, 102, 111, 111, 0, 0, 0, 2, 0]).try_into()
     );
+    assert_eq!(
+        Ok(Reference {
+            node: Atom::from("foo"),
+            id: vec![123],
+            creation: 65536
+        }),
+        decode(&[131, 90, 0, 1, 100, 0, 3, 102, 111, 111, 0, 1, 0, 0, 0, 0, 0, 123]).try_into()
+    ); // NEWER_REFERENCE_EXT
 
     // Encode
     assert_eq!(
         vec![131, 114, 0, 1, 100, 0, 3, 102, 111, 111, 0, 0, 0, 0, 123],
         encode(Term::from(Reference::from(("foo", 123))))
     );
+    assert_eq!(
+        vec![131, 90, 0, 1, 100, 0, 3, 102, 111, 111, 0, 1, 0, 0, 0, 0, 0, 123],
+        encode(Term::from(Reference {
+            node: Atom::from("foo"),
+            id: vec![123],
+            creation: 65536
+        }))
+    );
 }
 
 #[test]
@@ -471,12 +552,51 @@ fn compressed_term_test() {
     );
 }
 
+#[test]
+fn compressed_term_with_wrong_size_test() {
+    // term_to_binary(lists:duplicate(100, 0), [compressed])
+    let mut bytes = vec![
+        131, 80, 0, 0, 0, 103, 120, 156, 203, 102, 72, 97, 160, 3, 0, 0, 82, 232, 0, 208,
+    ];
+    assert_eq!(
+        Ok(List::from(vec![Term::from(FixInteger::from(0)); 100])),
+        decode(&bytes).try_into()
+    );
+
+    // claiming to be 1 byte longer
+    bytes[5] += 1;
+    assert!(Term::decode(Cursor::new(&bytes)).is_err());
+}
+
+#[test]
+fn decode_prefix_test() {
+    assert_eq!(
+        Ok((Term::from(Atom::from("a")), &[106][..])),
+        Term::decode_prefix(&[131, 119, 1, 97, 106]).map_err(|_| ())
+    );
+}
+
+#[test]
+fn claimed_length_past_the_end_test() {
+    // BINARY_EXT claiming 4 GiB of bytes, which aren't there
+    assert!(Term::decode(Cursor::new(&[131, 109, 255, 255, 255, 255, 0][..])).is_err());
+    // LIST_EXT claiming 4 billion elements
+    assert!(Term::decode(Cursor::new(&[131, 108, 255, 255, 255, 255, 106][..])).is_err());
+}
+
 fn encode(term: Term) -> Vec<u8> {
     let mut buf = Vec::new();
     term.encode(&mut buf).unwrap();
     buf
 }
 
+fn encode_with_minor_version(term: Term, minor_version: u8) -> Vec<u8> {
+    let mut buf = Vec::new();
+    term.encode_with_options(&mut buf, EncodeOptions { minor_version })
+        .unwrap();
+    buf
+}
+
 fn decode(bytes: &[u8]) -> Term {
     Term::decode(Cursor::new(bytes)).unwrap()
 }
@@ -1,3 +1,4 @@
 pub mod ast;
+pub mod core_erlang;
 
 pub use self::ast::AST;
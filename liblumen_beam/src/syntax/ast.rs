@@ -16,13 +16,16 @@
 pub mod ast;
 pub mod error;
 pub mod format;
+mod source;
 
 #[cfg(test)]
 mod test;
 
+use std::io::Read;
 use std::path::Path;
 
 use self::error::FromBeamError;
+use crate::serialization::etf;
 
 pub type FromBeamResult<T> = Result<T, error::FromBeamError>;
 
@@ -36,6 +39,25 @@ impl AST {
     pub fn from_beam_file<P: AsRef<Path>>(beam_file: P) -> FromBeamResult<Self> {
         use self::format::raw_abstract_v1::AbstractCode;
         let code = AbstractCode::from_beam_file(beam_file)?;
+        Self::from_abstract_code(code)
+    }
+
+    /// Builds AST from the contents of a BEAM file
+    pub fn from_beam_reader<R: Read>(reader: R) -> FromBeamResult<Self> {
+        use self::format::raw_abstract_v1::AbstractCode;
+        let code = AbstractCode::from_beam_reader(reader)?;
+        Self::from_abstract_code(code)
+    }
+
+    /// Builds AST from a list of abstract format forms
+    pub fn from_forms(forms: etf::Term) -> FromBeamResult<Self> {
+        use self::format::raw_abstract_v1::AbstractCode;
+        Self::from_abstract_code(AbstractCode::from_forms(forms))
+    }
+
+    fn from_abstract_code(
+        code: self::format::raw_abstract_v1::AbstractCode,
+    ) -> FromBeamResult<Self> {
         let forms = code.to_forms()?;
         Ok(AST {
             module: ast::ModuleDecl { forms },
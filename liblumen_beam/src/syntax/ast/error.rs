@@ -41,6 +41,12 @@ pub enum FromBeamError {
     #[fail(display = "debug info is required but not present")]
     NoDebugInfo,
 
+    #[fail(
+        display = "debug info from backend {} can't be read as abstract code",
+        _0
+    )]
+    UnsupportedDebugInfoBackend(String),
+
     #[fail(display = "missing module attribute")]
     NoModuleAttribute,
 
@@ -1,4 +1,5 @@
 use std::fmt::Debug;
+use std::io::Read;
 use std::marker::PhantomData;
 use std::path::Path;
 
@@ -42,13 +43,62 @@ pub struct AbstractCode {
 impl AbstractCode {
     pub fn from_beam_file<P: AsRef<Path>>(path: P) -> FromBeamResult<Self> {
         let beam = crate::beam::reader::RawBeamFile::from_file(path)?;
-        let chunk = beam
-            .chunks()
-            .into_iter()
-            .find(|c| c.id() == b"Abst")
-            .ok_or(FromBeamError::NoDebugInfo)?;
-        let code = etf::Term::decode(std::io::Cursor::new(&chunk.data))?;
-        Ok(AbstractCode { code })
+        Self::from_beam(beam)
+    }
+    pub fn from_beam_reader<R: Read>(reader: R) -> FromBeamResult<Self> {
+        let beam = crate::beam::reader::RawBeamFile::from_reader(reader)?;
+        Self::from_beam(beam)
+    }
+    /// Abstract code for a list of forms, such as those returned by `epp:parse_file/2` or passed
+    /// to parse transforms
+    pub fn from_forms(forms: etf::Term) -> Self {
+        let tag = etf::Term::from(etf::Atom::from("raw_abstract_v1"));
+        let code = etf::Term::from(etf::Tuple::from(vec![tag, forms]));
+        AbstractCode { code }
+    }
+    /// Reads the forms from the `Dbgi` chunk that `erlc +debug_info` writes since OTP 20, falling
+    /// back to the `Abst` chunk of older compilers.
+    fn from_beam(beam: crate::beam::reader::RawBeamFile) -> FromBeamResult<Self> {
+        let chunks = beam.chunks();
+
+        if let Some(chunk) = chunks.iter().find(|c| c.id() == b"Dbgi") {
+            let debug_info = etf::Term::decode(std::io::Cursor::new(&chunk.data))?;
+
+            if let Some(code) = Self::from_debug_info(&debug_info)? {
+                return Ok(code);
+            }
+        }
+
+        // without `+debug_info`, `erlc` still writes an empty `Abst` chunk
+        match chunks
+            .iter()
+            .find(|c| c.id() == b"Abst" && !c.data.is_empty())
+        {
+            Some(chunk) => {
+                let code = etf::Term::decode(std::io::Cursor::new(&chunk.data))?;
+                Ok(AbstractCode { code })
+            }
+            None => Err(FromBeamError::NoDebugInfo),
+        }
+    }
+    /// `{debug_info_v1, erl_abstract_code, {Forms, Options}}`, or `None` when compiled without
+    /// `+debug_info`, which leaves `Forms` as `none`.  Other backends, such as the `elixir_erl` of
+    /// Elixir, can only turn their data into forms by running the backend, so they aren't
+    /// supported.
+    fn from_debug_info(debug_info: &etf::Term) -> FromBeamResult<Option<Self>> {
+        let (_, backend, data) = debug_info.as_match(("debug_info_v1", atom(), any()))?;
+
+        if backend != "erl_abstract_code" {
+            return Err(FromBeamError::UnsupportedDebugInfoBackend(backend));
+        }
+
+        let (forms, _options) = data.as_match((any(), any()))?;
+
+        if forms.as_match("none").is_ok() {
+            Ok(None)
+        } else {
+            Ok(Some(Self::from_forms(forms.clone())))
+        }
     }
     pub fn to_forms(&self) -> FromBeamResult<Vec<form::Form>> {
         let (_, forms) = self
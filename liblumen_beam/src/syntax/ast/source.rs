@@ -0,0 +1,598 @@
+//! Prints an AST back out as Erlang source, so that modules loaded from BEAM files can be compiled
+//! by frontends that only accept source.
+//!
+//! Only the forms that affect the compiled code or are returned by `module_info(attributes)` are
+//! printed: behaviours and wild attributes are kept, while type declarations, specs and callbacks
+//! are dropped.  Operators and matches are always parenthesized, as the AST no longer records the
+//! original grouping.
+use std::fmt::{self, Display, Formatter, Write};
+
+use super::ast::clause::Clause;
+use super::ast::common;
+use super::ast::expr::{self, Expression};
+use super::ast::form::{self, Form};
+use super::ast::guard::{Guard, OrGuard};
+use super::ast::literal;
+use super::ast::pat::Pattern;
+use super::ast::ModuleDecl;
+
+impl Display for ModuleDecl {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for form in &self.forms {
+            write!(f, "{}", form)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Form {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            Form::Module(ref x) => {
+                f.write_str("-module(")?;
+                atom(f, &x.name)?;
+                f.write_str(").\n")
+            }
+            Form::Export(ref x) => {
+                f.write_str("-export([")?;
+                for (i, export) in x.funs.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    atom(f, &export.fun)?;
+                    write!(f, "/{}", export.arity)?;
+                }
+                f.write_str("]).\n")
+            }
+            Form::Import(ref x) => {
+                f.write_str("-import(")?;
+                atom(f, &x.module)?;
+                f.write_str(", [")?;
+                for (i, import) in x.funs.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    atom(f, &import.fun)?;
+                    write!(f, "/{}", import.arity)?;
+                }
+                f.write_str("]).\n")
+            }
+            Form::Behaviour(ref x) => {
+                f.write_str(if x.is_british {
+                    "-behaviour("
+                } else {
+                    "-behavior("
+                })?;
+                atom(f, &x.name)?;
+                f.write_str(").\n")
+            }
+            // names are left unquoted, as the frontend only reads attributes with unquoted names
+            Form::Attr(ref x) => write!(f, "-{}({}).\n", x.name, x.value),
+            Form::Record(ref x) => record_decl(f, x),
+            Form::Fun(ref x) => fun_decl(f, x),
+            Form::ExportType(_)
+            | Form::Compile(_)
+            | Form::File(_)
+            | Form::Type(_)
+            | Form::Spec(_)
+            | Form::Eof(_) => Ok(()),
+        }
+    }
+}
+
+fn record_decl(f: &mut Formatter, x: &form::RecordDecl) -> fmt::Result {
+    f.write_str("-record(")?;
+    atom(f, &x.name)?;
+    f.write_str(", {")?;
+    for (i, field) in x.fields.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        atom(f, &field.name)?;
+        write!(f, " = {}", field.default_value)?;
+    }
+    f.write_str("}).\n")
+}
+
+fn fun_decl(f: &mut Formatter, x: &form::FunDecl) -> fmt::Result {
+    for (i, clause) in x.clauses.iter().enumerate() {
+        if i > 0 {
+            f.write_str(";\n")?;
+        }
+        atom(f, &x.name)?;
+        function_clause(f, clause)?;
+    }
+    f.write_str(".\n")
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            Expression::Integer(ref x) => write!(f, "{}", x),
+            Expression::Float(ref x) => write!(f, "{}", x),
+            Expression::String(ref x) => write!(f, "{}", x),
+            Expression::Char(ref x) => write!(f, "{}", x),
+            Expression::Atom(ref x) => write!(f, "{}", x),
+            Expression::Match(ref x) => write!(f, "{}", x),
+            Expression::Var(ref x) => write!(f, "{}", x),
+            Expression::Tuple(ref x) => write!(f, "{}", x),
+            Expression::Nil(ref x) => write!(f, "{}", x),
+            Expression::Cons(ref x) => write!(f, "{}", x),
+            Expression::Binary(ref x) => write!(f, "{}", x),
+            Expression::UnaryOp(ref x) => write!(f, "{}", x),
+            Expression::BinaryOp(ref x) => write!(f, "{}", x),
+            Expression::Record(ref x) => write!(f, "{}", x),
+            Expression::RecordIndex(ref x) => write!(f, "{}", x),
+            Expression::Map(ref x) => write!(f, "{}", x),
+            Expression::Catch(ref x) => write!(f, "(catch {})", x.expr),
+            Expression::LocalCall(ref x) => write!(f, "{}", x),
+            Expression::RemoteCall(ref x) => write!(f, "{}", x),
+            Expression::Comprehension(ref x) => write!(f, "{}", x),
+            Expression::Block(ref x) => {
+                f.write_str("begin ")?;
+                body(f, &x.body)?;
+                f.write_str(" end")
+            }
+            Expression::If(ref x) => {
+                f.write_str("if ")?;
+                for (i, clause) in x.clauses.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str("; ")?;
+                    }
+                    or_guards(f, &clause.guards)?;
+                    f.write_str(" -> ")?;
+                    body(f, &clause.body)?;
+                }
+                f.write_str(" end")
+            }
+            Expression::Case(ref x) => {
+                write!(f, "case {} of ", x.expr)?;
+                case_clauses(f, &x.clauses)?;
+                f.write_str(" end")
+            }
+            Expression::Try(ref x) => write!(f, "{}", x),
+            Expression::Receive(ref x) => write!(f, "{}", x),
+            Expression::InternalFun(ref x) => {
+                f.write_str("fun ")?;
+                atom(f, &x.function)?;
+                write!(f, "/{}", x.arity)
+            }
+            Expression::ExternalFun(ref x) => {
+                f.write_str("fun ")?;
+                callee(f, &x.module)?;
+                f.write_str(":")?;
+                callee(f, &x.function)?;
+                f.write_str("/")?;
+                callee(f, &x.arity)
+            }
+            Expression::AnonymousFun(ref x) => write!(f, "{}", x),
+        }
+    }
+}
+
+impl Display for expr::Try {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("try ")?;
+        body(f, &self.body)?;
+        if !self.case_clauses.is_empty() {
+            f.write_str(" of ")?;
+            case_clauses(f, &self.case_clauses)?;
+        }
+        if !self.catch_clauses.is_empty() {
+            f.write_str(" catch ")?;
+            for (i, clause) in self.catch_clauses.iter().enumerate() {
+                if i > 0 {
+                    f.write_str("; ")?;
+                }
+                // The pattern of a catch clause is always a `{Class, Reason, Stacktrace}` tuple
+                match clause.patterns[0] {
+                    Pattern::Tuple(ref tuple) if tuple.elements.len() == 3 => write!(
+                        f,
+                        "{}:{}:{}",
+                        tuple.elements[0], tuple.elements[1], tuple.elements[2]
+                    )?,
+                    ref pattern => write!(f, "{}", pattern)?,
+                }
+                guards(f, &clause.guards)?;
+                f.write_str(" -> ")?;
+                body(f, &clause.body)?;
+            }
+        }
+        if !self.after.is_empty() {
+            f.write_str(" after ")?;
+            body(f, &self.after)?;
+        }
+        f.write_str(" end")
+    }
+}
+
+impl Display for expr::Receive {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("receive ")?;
+        case_clauses(f, &self.clauses)?;
+        if let Some(ref timeout) = self.timeout {
+            write!(f, " after {} -> ", timeout)?;
+            body(f, &self.after)?;
+        }
+        f.write_str(" end")
+    }
+}
+
+impl Display for expr::Comprehension {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let (open, close) = if self.is_list {
+            ("[", "]")
+        } else {
+            ("<<", ">>")
+        };
+        write!(f, "{}{} || ", open, self.expr)?;
+        for (i, qualifier) in self.qualifiers.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            match *qualifier {
+                expr::Qualifier::Generator(ref x) => write!(f, "{} <- {}", x.pattern, x.expr)?,
+                expr::Qualifier::BitStringGenerator(ref x) => {
+                    write!(f, "{} <= {}", x.pattern, x.expr)?
+                }
+                expr::Qualifier::Filter(ref x) => write!(f, "{}", x)?,
+            }
+        }
+        f.write_str(close)
+    }
+}
+
+impl Display for expr::AnonymousFun {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("fun ")?;
+        for (i, clause) in self.clauses.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            if let Some(ref name) = self.name {
+                f.write_str(name)?;
+            }
+            function_clause(f, clause)?;
+        }
+        f.write_str(" end")
+    }
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            Pattern::Integer(ref x) => write!(f, "{}", x),
+            Pattern::Float(ref x) => write!(f, "{}", x),
+            Pattern::String(ref x) => write!(f, "{}", x),
+            Pattern::Char(ref x) => write!(f, "{}", x),
+            Pattern::Atom(ref x) => write!(f, "{}", x),
+            Pattern::Var(ref x) => write!(f, "{}", x),
+            Pattern::Match(ref x) => write!(f, "{}", x),
+            Pattern::Tuple(ref x) => write!(f, "{}", x),
+            Pattern::Nil(ref x) => write!(f, "{}", x),
+            Pattern::Cons(ref x) => write!(f, "{}", x),
+            Pattern::Binary(ref x) => write!(f, "{}", x),
+            Pattern::UnaryOp(ref x) => write!(f, "{}", x),
+            Pattern::BinaryOp(ref x) => write!(f, "{}", x),
+            Pattern::Record(ref x) => write!(f, "{}", x),
+            Pattern::RecordIndex(ref x) => write!(f, "{}", x),
+            Pattern::Map(ref x) => write!(f, "{}", x),
+        }
+    }
+}
+
+impl Display for Guard {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            Guard::Integer(ref x) => write!(f, "{}", x),
+            Guard::Float(ref x) => write!(f, "{}", x),
+            Guard::String(ref x) => write!(f, "{}", x),
+            Guard::Char(ref x) => write!(f, "{}", x),
+            Guard::Atom(ref x) => write!(f, "{}", x),
+            Guard::Var(ref x) => write!(f, "{}", x),
+            Guard::Tuple(ref x) => write!(f, "{}", x),
+            Guard::Nil(ref x) => write!(f, "{}", x),
+            Guard::Cons(ref x) => write!(f, "{}", x),
+            Guard::Binary(ref x) => write!(f, "{}", x),
+            Guard::UnaryOp(ref x) => write!(f, "{}", x),
+            Guard::BinaryOp(ref x) => write!(f, "{}", x),
+            Guard::Record(ref x) => write!(f, "{}", x),
+            Guard::RecordIndex(ref x) => write!(f, "{}", x),
+            Guard::LocalCall(ref x) => write!(f, "{}", x),
+            Guard::RemoteCall(ref x) => write!(f, "{}", x),
+        }
+    }
+}
+
+impl Display for literal::Integer {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl Display for literal::Float {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        // Erlang requires a fraction before the exponent, which `{:e}` leaves out for whole numbers
+        let formatted = format!("{:e}", self.value);
+        match formatted.find('e') {
+            Some(index) if !formatted[..index].contains('.') => {
+                write!(f, "{}.0{}", &formatted[..index], &formatted[index..])
+            }
+            _ => f.write_str(&formatted),
+        }
+    }
+}
+
+impl Display for literal::Str {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        quoted(f, &self.value, '"')
+    }
+}
+
+impl Display for literal::Char {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.value as u32)
+    }
+}
+
+impl Display for literal::Atom {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        atom(f, &self.value)
+    }
+}
+
+impl Display for common::Var {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl Display for common::Nil {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("[]")
+    }
+}
+
+impl<L: Display, R: Display> Display for common::Match<L, R> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "({} = {})", self.left, self.right)
+    }
+}
+
+impl<T: Display> Display for common::Tuple<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("{")?;
+        comma_separated(f, &self.elements)?;
+        f.write_str("}")
+    }
+}
+
+impl<T: Display> Display for common::Cons<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "[{} | {}]", self.head, self.tail)
+    }
+}
+
+impl<T: Display> Display for common::Binary<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("<<")?;
+        for (i, element) in self.elements.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", element.element)?;
+            if let Some(ref size) = element.size {
+                write!(f, ":{}", size)?;
+            }
+            if let Some(ref tsl) = element.tsl {
+                for (i, spec) in tsl.iter().enumerate() {
+                    f.write_str(if i == 0 { "/" } else { "-" })?;
+                    f.write_str(&spec.name)?;
+                    if let Some(value) = spec.value {
+                        write!(f, ":{}", value)?;
+                    }
+                }
+            }
+        }
+        f.write_str(">>")
+    }
+}
+
+impl<T: Display> Display for common::UnaryOp<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "({} {})", self.operator, self.operand)
+    }
+}
+
+impl<T: Display> Display for common::BinaryOp<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "({} {} {})",
+            self.left_operand, self.operator, self.right_operand
+        )
+    }
+}
+
+impl<T: Display> Display for common::Record<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if let Some(ref base) = self.base {
+            write!(f, "({})", base)?;
+        }
+        f.write_str("#")?;
+        atom(f, &self.name)?;
+        f.write_str("{")?;
+        for (i, field) in self.fields.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            match field.name {
+                Some(ref name) => atom(f, name)?,
+                None => f.write_str("_")?,
+            }
+            write!(f, " = {}", field.value)?;
+        }
+        f.write_str("}")
+    }
+}
+
+impl<T: Display> Display for common::RecordIndex<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if let Some(ref base) = self.base {
+            write!(f, "({})", base)?;
+        }
+        f.write_str("#")?;
+        atom(f, &self.record)?;
+        f.write_str(".")?;
+        atom(f, &self.field)
+    }
+}
+
+impl<T: Display> Display for common::Map<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if let Some(ref base) = self.base {
+            write!(f, "({})", base)?;
+        }
+        f.write_str("#{")?;
+        for (i, pair) in self.pairs.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            let operator = if pair.is_assoc { "=>" } else { ":=" };
+            write!(f, "{} {} {}", pair.key, operator, pair.value)?;
+        }
+        f.write_str("}")
+    }
+}
+
+impl<T: Display + Callee> Display for common::LocalCall<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        callee(f, &self.function)?;
+        f.write_str("(")?;
+        comma_separated(f, &self.args)?;
+        f.write_str(")")
+    }
+}
+
+impl<T: Display + Callee> Display for common::RemoteCall<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        callee(f, &self.module)?;
+        f.write_str(":")?;
+        callee(f, &self.function)?;
+        f.write_str("(")?;
+        comma_separated(f, &self.args)?;
+        f.write_str(")")
+    }
+}
+
+/// The module, function and arity parts of calls and external funs.
+trait Callee {
+    /// Whether the part can be printed without parentheses, which keeps calls to literal
+    /// functions as local and remote calls instead of calls to funs.
+    fn is_bare(&self) -> bool;
+}
+
+impl Callee for Expression {
+    fn is_bare(&self) -> bool {
+        match *self {
+            Expression::Atom(_) | Expression::Var(_) | Expression::Integer(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Callee for Guard {
+    fn is_bare(&self) -> bool {
+        match *self {
+            Guard::Atom(_) | Guard::Var(_) | Guard::Integer(_) => true,
+            _ => false,
+        }
+    }
+}
+
+fn callee<T: Display + Callee>(f: &mut Formatter, part: &T) -> fmt::Result {
+    if part.is_bare() {
+        write!(f, "{}", part)
+    } else {
+        write!(f, "({})", part)
+    }
+}
+
+/// `(Patterns) when Guards -> Body` for function declarations and funs.
+fn function_clause(f: &mut Formatter, clause: &Clause) -> fmt::Result {
+    f.write_str("(")?;
+    comma_separated(f, &clause.patterns)?;
+    f.write_str(")")?;
+    guards(f, &clause.guards)?;
+    f.write_str(" -> ")?;
+    body(f, &clause.body)
+}
+
+/// `Pattern when Guards -> Body; ...` for `case`, `receive` and `try ... of`.
+fn case_clauses(f: &mut Formatter, clauses: &[Clause]) -> fmt::Result {
+    for (i, clause) in clauses.iter().enumerate() {
+        if i > 0 {
+            f.write_str("; ")?;
+        }
+        comma_separated(f, &clause.patterns)?;
+        guards(f, &clause.guards)?;
+        f.write_str(" -> ")?;
+        body(f, &clause.body)?;
+    }
+    Ok(())
+}
+
+fn guards(f: &mut Formatter, guards: &[OrGuard]) -> fmt::Result {
+    if guards.is_empty() {
+        Ok(())
+    } else {
+        f.write_str(" when ")?;
+        or_guards(f, guards)
+    }
+}
+
+fn or_guards(f: &mut Formatter, guards: &[OrGuard]) -> fmt::Result {
+    for (i, guard) in guards.iter().enumerate() {
+        if i > 0 {
+            f.write_str("; ")?;
+        }
+        comma_separated(f, &guard.and_guards)?;
+    }
+    Ok(())
+}
+
+fn body(f: &mut Formatter, exprs: &[Expression]) -> fmt::Result {
+    comma_separated(f, exprs)
+}
+
+fn comma_separated<T: Display>(f: &mut Formatter, items: &[T]) -> fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+fn atom(f: &mut Formatter, name: &str) -> fmt::Result {
+    quoted(f, name, '\'')
+}
+
+fn quoted(f: &mut Formatter, s: &str, quote: char) -> fmt::Result {
+    f.write_char(quote)?;
+    for c in s.chars() {
+        match c {
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\t' => f.write_str("\\t")?,
+            c if c == quote => {
+                f.write_char('\\')?;
+                f.write_char(c)?
+            }
+            c if c.is_control() => write!(f, "\\x{{{:X}}}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char(quote)
+}
@@ -9,3 +9,96 @@ fn it_works() {
         })
         .unwrap();
 }
+
+#[test]
+fn to_source() {
+    let ast = AST::from_beam_file("tests/testdata/ast/test.beam").unwrap();
+    let source = ast.module.to_string();
+
+    assert!(source.starts_with("-module('test').\n"));
+    assert!(source.contains("-export(['sum'/1, 'op'/1]).\n"));
+    assert!(source.contains("-foo_attribute('bar').\n"));
+    assert!(source.contains("-behaviour('test').\n"));
+    assert!(source.contains("-behavior('test2').\n"));
+    assert!(source.contains("'op'(Num) -> ((Num + 1) band 4294967295).\n"));
+    assert!(source.contains("'to_my_list'([]) -> 'nil';\n"));
+}
+
+#[test]
+fn from_forms() {
+    use crate::serialization::etf;
+    use crate::syntax::ast::format::raw_abstract_v1::AbstractCode;
+
+    let code = AbstractCode::from_beam_file("tests/testdata/ast/test.beam").unwrap();
+    let forms = match code.code {
+        etf::Term::Tuple(ref tuple) => tuple.elements[1].clone(),
+        ref other => panic!("{} is not {{raw_abstract_v1, Forms}}", other),
+    };
+
+    let ast = AST::from_forms(forms).unwrap();
+    let beam_ast = AST::from_beam_file("tests/testdata/ast/test.beam").unwrap();
+
+    assert_eq!(ast.module.to_string(), beam_ast.module.to_string());
+}
+
+#[test]
+fn from_beam_reads_dbgi_chunk() {
+    use crate::beam::reader::chunk::RawChunk;
+    use crate::beam::reader::RawBeamFile;
+    use crate::serialization::etf;
+
+    let beam_ast = AST::from_beam_file("tests/testdata/ast/test.beam").unwrap();
+
+    // `erlc +debug_info` since OTP 20 writes `{debug_info_v1, erl_abstract_code, {Forms, Options}}`
+    // to `Dbgi` and leaves `Abst` empty
+    let mut beam = RawBeamFile::from_file("tests/testdata/ast/test.beam").unwrap();
+    let abst = beam.get_chunk(b"Abst").unwrap();
+    let forms = match etf::Term::decode(std::io::Cursor::new(&abst.data)).unwrap() {
+        etf::Term::Tuple(tuple) => tuple.elements[1].clone(),
+        other => panic!("{} is not {{raw_abstract_v1, Forms}}", other),
+    };
+    let debug_info = etf::Term::from(etf::Tuple::from(vec![
+        etf::Term::from(etf::Atom::from("debug_info_v1")),
+        etf::Term::from(etf::Atom::from("erl_abstract_code")),
+        etf::Term::from(etf::Tuple::from(vec![
+            forms,
+            etf::Term::from(etf::List::nil()),
+        ])),
+    ]));
+    let mut data = Vec::new();
+    debug_info.encode(&mut data).unwrap();
+
+    beam.strip_with(|id, _| id == b"Abst");
+    beam.push_chunk(RawChunk {
+        id: *b"Abst",
+        data: Vec::new(),
+    });
+    beam.push_chunk(RawChunk { id: *b"Dbgi", data });
+
+    let mut bytes = Vec::new();
+    beam.to_writer(&mut bytes).unwrap();
+    let ast = AST::from_beam_reader(std::io::Cursor::new(bytes)).unwrap();
+
+    assert_eq!(ast.module.to_string(), beam_ast.module.to_string());
+}
+
+#[test]
+fn from_beam_without_debug_info_errors() {
+    // compiled by OTP 20+ `erlc` without `+debug_info`, so `Dbgi` has `none` for the forms
+    match AST::from_beam_file("tests/testdata/simple.beam") {
+        Err(FromBeamError::NoDebugInfo) => (),
+        other => panic!("expected NoDebugInfo, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn from_beam_with_elixir_debug_info_errors() {
+    match AST::from_beam_file("tests/testdata/reader/Elixir.Unicode.beam") {
+        Err(FromBeamError::UnsupportedDebugInfoBackend(ref backend)) if backend == "elixir_erl" => {
+        }
+        other => panic!(
+            "expected UnsupportedDebugInfoBackend, got {:?}",
+            other.map(|_| ())
+        ),
+    }
+}
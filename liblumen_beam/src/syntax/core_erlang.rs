@@ -0,0 +1,30 @@
+//! A Rust representation of Core Erlang modules.
+//!
+//! Core Erlang is the intermediate language of the Erlang compiler, which other languages (such as
+//! Elixir) also compile to.  Modules are parsed from their text form, as written by
+//! `erlc +to_core`, and can be translated back into Erlang source.
+//!
+//! # References
+//!
+//! * [Core Erlang 1.0.3 language specification](https://www.it.uu.se/research/group/hipe/cerl/doc/core_erlang-1.0.3.pdf)
+//!
+//! # Examples
+//!
+//!     use liblumen_beam::syntax::core_erlang;
+//!
+//!     let module = core_erlang::parse_module(
+//!         "module 'm' ['f'/0] attributes [] 'f'/0 = fun () -> 'ok' end",
+//!     ).unwrap();
+//!     println!("{}", module.to_erlang().unwrap());
+//!
+pub mod ast;
+mod erlang;
+pub mod error;
+mod lexer;
+mod parser;
+
+#[cfg(test)]
+mod test;
+
+pub use self::error::CoreError;
+pub use self::parser::parse_module;
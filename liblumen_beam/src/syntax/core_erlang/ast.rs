@@ -0,0 +1,134 @@
+//! Core Erlang syntax tree.
+//!
+//! Annotations are dropped by the parser, so none of the nodes carry them.
+use num::bigint::BigInt;
+
+#[derive(Debug, Clone)]
+pub struct Module {
+    pub name: String,
+    pub exports: Vec<FunName>,
+    /// The attributes, with values that are literals
+    pub attributes: Vec<(String, Expr)>,
+    pub definitions: Vec<FunDef>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FunName {
+    pub name: String,
+    pub arity: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct FunDef {
+    pub name: FunName,
+    pub fun: Fun,
+}
+
+#[derive(Debug, Clone)]
+pub struct Fun {
+    pub vars: Vec<String>,
+    pub body: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Atom(String),
+    Integer(BigInt),
+    Float(f64),
+    Char(char),
+    String(String),
+    Nil,
+}
+
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Var(String),
+    FunName(FunName),
+    Literal(Literal),
+    Cons(Box<Expr>, Box<Expr>),
+    Tuple(Vec<Expr>),
+    Values(Vec<Expr>),
+    Binary(Vec<Segment<Expr>>),
+    Map {
+        pairs: Vec<MapPair<Expr>>,
+        base: Option<Box<Expr>>,
+    },
+    Let {
+        vars: Vec<String>,
+        value: Box<Expr>,
+        body: Box<Expr>,
+    },
+    LetRec {
+        definitions: Vec<FunDef>,
+        body: Box<Expr>,
+    },
+    Case {
+        arg: Box<Expr>,
+        clauses: Vec<Clause>,
+    },
+    Fun(Box<Fun>),
+    Apply {
+        fun: Box<Expr>,
+        args: Vec<Expr>,
+    },
+    Call {
+        module: Box<Expr>,
+        function: Box<Expr>,
+        args: Vec<Expr>,
+    },
+    PrimOp {
+        name: String,
+        args: Vec<Expr>,
+    },
+    Try {
+        arg: Box<Expr>,
+        vars: Vec<String>,
+        body: Box<Expr>,
+        catch_vars: Vec<String>,
+        handler: Box<Expr>,
+    },
+    Receive {
+        clauses: Vec<Clause>,
+        timeout: Box<Expr>,
+        action: Box<Expr>,
+    },
+    Do(Box<Expr>, Box<Expr>),
+    Catch(Box<Expr>),
+}
+
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Var(String),
+    Literal(Literal),
+    Cons(Box<Pattern>, Box<Pattern>),
+    Tuple(Vec<Pattern>),
+    Binary(Vec<Segment<Pattern>>),
+    Map(Vec<MapPair<Pattern>>),
+    Alias(String, Box<Pattern>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Clause {
+    pub patterns: Vec<Pattern>,
+    pub guard: Expr,
+    pub body: Expr,
+}
+
+/// A `#<Value>(Size, Unit, Type, Flags)` bitstring segment
+#[derive(Debug, Clone)]
+pub struct Segment<T> {
+    pub value: T,
+    pub size: Expr,
+    pub unit: Expr,
+    pub ty: Expr,
+    pub flags: Expr,
+}
+
+/// `Key => Value` when `exact` is `false`, otherwise `Key := Value`.  Keys in patterns are
+/// expressions, as they must already be bound.
+#[derive(Debug, Clone)]
+pub struct MapPair<T> {
+    pub key: Expr,
+    pub exact: bool,
+    pub value: T,
+}
@@ -0,0 +1,599 @@
+//! Translates Core Erlang into Erlang source, so that the output of compilers that target Core
+//! Erlang can be compiled by frontends that only accept Erlang.
+//!
+//! Every Core variable is renamed to a fresh `V<n>`, as Core allows shadowing and Erlang does not.
+//! Value lists become tuples, `let` becomes a match in a `begin ... end` block and each `letrec`
+//! function becomes a named fun, so a `letrec` function can only call itself and the functions
+//! defined before it, and referring to one defined after it, as mutually recursive functions do,
+//! is unsupported.  `module_info/0,1` are left out, as the frontend generates them, and so are
+//! the attributes that only matter to compiling the module, such as `file` and `spec`.
+use crate::syntax::ast::ast::literal;
+
+use super::ast::*;
+use super::error::CoreError;
+
+type TranslateResult<T> = Result<T, CoreError>;
+
+impl Module {
+    /// Erlang source for this module
+    pub fn to_erlang(&self) -> TranslateResult<String> {
+        let mut translator = Translator { next_var: 0 };
+
+        let exports: Vec<String> = self
+            .exports
+            .iter()
+            .filter(|name| name.name != "module_info")
+            .map(fun_name)
+            .collect();
+        let mut source = format!(
+            "-module({}).\n-export([{}]).\n",
+            atom(&self.name),
+            exports.join(", ")
+        );
+
+        for (name, value) in &self.attributes {
+            source.push_str(&attributes(&mut translator, name, value)?);
+        }
+
+        for definition in &self.definitions {
+            if definition.name.name == "module_info" {
+                continue;
+            }
+
+            let mut env = Env::default();
+            let vars = translator.bind_all(&definition.fun.vars, &mut env);
+            let body = translator.expr(&definition.fun.body, &env)?;
+            source.push_str(&format!(
+                "{}({}) -> {}.\n",
+                atom(&definition.name.name),
+                vars.join(", "),
+                body
+            ));
+        }
+
+        Ok(source)
+    }
+}
+
+#[derive(Clone, Default)]
+struct Env {
+    /// The Erlang expression each Core variable in scope stands for
+    vars: std::collections::HashMap<String, String>,
+    /// The Erlang variable each `letrec` function in scope is bound to
+    funs: std::collections::HashMap<FunName, String>,
+    /// The `letrec` functions defined after the one being translated, which aren't bound yet
+    later_funs: std::collections::HashSet<FunName>,
+    /// Guards cannot match, so `let` bindings are substituted instead
+    guard: bool,
+}
+
+struct Translator {
+    next_var: usize,
+}
+impl Translator {
+    fn fresh(&mut self) -> String {
+        self.next_var += 1;
+        format!("V{}", self.next_var)
+    }
+
+    fn bind(&mut self, var: &str, env: &mut Env) -> String {
+        let fresh = self.fresh();
+        env.vars.insert(var.to_string(), fresh.clone());
+        fresh
+    }
+
+    fn bind_all(&mut self, vars: &[String], env: &mut Env) -> Vec<String> {
+        vars.iter().map(|var| self.bind(var, env)).collect()
+    }
+
+    fn exprs(&mut self, exprs: &[Expr], env: &Env) -> TranslateResult<String> {
+        let mut sources = Vec::with_capacity(exprs.len());
+        for expr in exprs {
+            sources.push(self.expr(expr, env)?);
+        }
+        Ok(sources.join(", "))
+    }
+
+    fn expr(&mut self, expr: &Expr, env: &Env) -> TranslateResult<String> {
+        let source = match *expr {
+            Expr::Var(ref name) => env
+                .vars
+                .get(name)
+                .cloned()
+                .ok_or_else(|| unsupported(format!("unbound variable {}", name)))?,
+            Expr::FunName(ref name) if env.later_funs.contains(name) => {
+                return Err(defined_later(name))
+            }
+            Expr::FunName(ref name) => match env.funs.get(name) {
+                Some(var) => var.clone(),
+                None => format!("fun {}", fun_name(name)),
+            },
+            Expr::Literal(ref literal) => literal_source(literal),
+            Expr::Cons(ref head, ref tail) => {
+                format!("[{} | {}]", self.expr(head, env)?, self.expr(tail, env)?)
+            }
+            Expr::Tuple(ref elements) => format!("{{{}}}", self.exprs(elements, env)?),
+            Expr::Values(ref values) if values.len() == 1 => self.expr(&values[0], env)?,
+            Expr::Values(ref values) => format!("{{{}}}", self.exprs(values, env)?),
+            Expr::Binary(ref segments) => {
+                let mut sources = Vec::with_capacity(segments.len());
+                for segment in segments {
+                    let value = format!("({})", self.expr(&segment.value, env)?);
+                    sources.push(self.segment(value, segment, env)?);
+                }
+                format!("<<{}>>", sources.join(", "))
+            }
+            Expr::Map {
+                ref pairs,
+                ref base,
+            } => {
+                let mut sources = Vec::with_capacity(pairs.len());
+                for pair in pairs {
+                    let value = self.expr(&pair.value, env)?;
+                    sources.push(self.map_pair(pair, value, env)?);
+                }
+                match *base {
+                    Some(ref base) => {
+                        format!("({})#{{{}}}", self.expr(base, env)?, sources.join(", "))
+                    }
+                    None => format!("#{{{}}}", sources.join(", ")),
+                }
+            }
+            Expr::Let {
+                ref vars,
+                ref value,
+                ref body,
+            } => {
+                let value = self.expr(value, env)?;
+                let mut env = env.clone();
+
+                if env.guard {
+                    if vars.len() != 1 {
+                        return Err(unsupported("multiple values in a guard"));
+                    }
+                    env.vars.insert(vars[0].clone(), value);
+                    return self.expr(body, &env);
+                }
+
+                let pattern = values_pattern(self.bind_all(vars, &mut env));
+                format!(
+                    "begin {} = {}, {} end",
+                    pattern,
+                    value,
+                    self.expr(body, &env)?
+                )
+            }
+            Expr::LetRec {
+                ref definitions,
+                ref body,
+            } => {
+                let mut env = env.clone();
+                for definition in definitions {
+                    env.later_funs.remove(&definition.name);
+                }
+                let vars: Vec<String> = definitions
+                    .iter()
+                    .map(|definition| {
+                        let var = self.fresh();
+                        env.funs.insert(definition.name.clone(), var.clone());
+                        var
+                    })
+                    .collect();
+
+                let mut bindings = Vec::with_capacity(definitions.len());
+                for (index, (definition, var)) in definitions.iter().zip(vars).enumerate() {
+                    let name = self.fresh();
+                    let mut fun_env = env.clone();
+                    fun_env.later_funs.extend(
+                        definitions[index + 1..]
+                            .iter()
+                            .map(|later| later.name.clone()),
+                    );
+                    fun_env.funs.insert(definition.name.clone(), name.clone());
+                    let fun = self.fun(&definition.fun, Some(&name), &fun_env)?;
+                    bindings.push(format!("{} = {}", var, fun));
+                }
+
+                format!(
+                    "begin {}, {} end",
+                    bindings.join(", "),
+                    self.expr(body, &env)?
+                )
+            }
+            Expr::Case {
+                ref arg,
+                ref clauses,
+            } => format!(
+                "case {} of {} end",
+                self.expr(arg, env)?,
+                self.clauses(clauses, env)?
+            ),
+            Expr::Fun(ref fun) => self.fun(fun, None, env)?,
+            Expr::Apply { ref fun, ref args } => {
+                let args = self.exprs(args, env)?;
+                match **fun {
+                    Expr::FunName(ref name) if env.later_funs.contains(name) => {
+                        return Err(defined_later(name))
+                    }
+                    Expr::FunName(ref name) if !env.funs.contains_key(name) => {
+                        format!("{}({})", atom(&name.name), args)
+                    }
+                    ref fun => format!("({})({})", self.expr(fun, env)?, args),
+                }
+            }
+            Expr::Call {
+                ref module,
+                ref function,
+                ref args,
+            } => self.call(module, function, args, env)?,
+            Expr::PrimOp { ref name, ref args } => match name.as_str() {
+                "match_fail" => match args.as_slice() {
+                    // BEAM raises plain `function_clause`, with the arguments in the stacktrace
+                    [Expr::Tuple(ref elements)] if is_function_clause(elements) => format!(
+                        "erlang:error('function_clause', [{}])",
+                        self.exprs(&elements[1..], env)?
+                    ),
+                    _ => format!("erlang:error({})", self.exprs(args, env)?),
+                },
+                "raw_raise" => format!("erlang:raise({})", self.exprs(args, env)?),
+                "build_stacktrace" => self.exprs(args, env)?,
+                _ => return Err(unsupported(format!("primop {}", name))),
+            },
+            // The `try` Core Erlang wraps around guard tests that may fail is implicit in Erlang
+            Expr::Try { ref arg, .. } if env.guard => self.expr(arg, env)?,
+            Expr::Try {
+                ref arg,
+                ref vars,
+                ref body,
+                ref catch_vars,
+                ref handler,
+            } => {
+                if catch_vars.len() != 3 {
+                    return Err(unsupported("try without class, reason and stacktrace"));
+                }
+
+                let arg = self.expr(arg, env)?;
+
+                let mut body_env = env.clone();
+                let pattern = values_pattern(self.bind_all(vars, &mut body_env));
+                let body = self.expr(body, &body_env)?;
+
+                let mut handler_env = env.clone();
+                let catch_vars = self.bind_all(catch_vars, &mut handler_env);
+                let handler = self.expr(handler, &handler_env)?;
+
+                format!(
+                    "try {} of {} -> {} catch {} -> {} end",
+                    arg,
+                    pattern,
+                    body,
+                    catch_vars.join(":"),
+                    handler
+                )
+            }
+            Expr::Receive {
+                ref clauses,
+                ref timeout,
+                ref action,
+            } => {
+                let clauses = if clauses.is_empty() {
+                    String::new()
+                } else {
+                    format!("{} ", self.clauses(clauses, env)?)
+                };
+                format!(
+                    "receive {}after {} -> {} end",
+                    clauses,
+                    self.expr(timeout, env)?,
+                    self.expr(action, env)?
+                )
+            }
+            Expr::Do(ref first, ref second) => format!(
+                "begin {}, {} end",
+                self.expr(first, env)?,
+                self.expr(second, env)?
+            ),
+            Expr::Catch(ref expr) => format!("(catch {})", self.expr(expr, env)?),
+        };
+
+        Ok(source)
+    }
+
+    fn call(
+        &mut self,
+        module: &Expr,
+        function: &Expr,
+        args: &[Expr],
+        env: &Env,
+    ) -> TranslateResult<String> {
+        match (module, function) {
+            (
+                Expr::Literal(Literal::Atom(ref module)),
+                Expr::Literal(Literal::Atom(ref function)),
+            ) => {
+                if module == "erlang" {
+                    match (args, is_operator(function, args.len())) {
+                        ([ref operand], true) => {
+                            return Ok(format!("({} {})", function, self.expr(operand, env)?));
+                        }
+                        ([ref left, ref right], true) => {
+                            return Ok(format!(
+                                "({} {} {})",
+                                self.expr(left, env)?,
+                                function,
+                                self.expr(right, env)?
+                            ));
+                        }
+                        _ => (),
+                    }
+                }
+
+                Ok(format!(
+                    "{}:{}({})",
+                    atom(module),
+                    atom(function),
+                    self.exprs(args, env)?
+                ))
+            }
+            _ => Ok(format!(
+                "({}):({})({})",
+                self.expr(module, env)?,
+                self.expr(function, env)?,
+                self.exprs(args, env)?
+            )),
+        }
+    }
+
+    fn fun(&mut self, fun: &Fun, name: Option<&str>, env: &Env) -> TranslateResult<String> {
+        let mut env = env.clone();
+        let vars = self.bind_all(&fun.vars, &mut env);
+        Ok(format!(
+            "fun {}({}) -> {} end",
+            name.unwrap_or(""),
+            vars.join(", "),
+            self.expr(&fun.body, &env)?
+        ))
+    }
+
+    fn clauses(&mut self, clauses: &[Clause], env: &Env) -> TranslateResult<String> {
+        let mut sources = Vec::with_capacity(clauses.len());
+        for clause in clauses {
+            let mut env = env.clone();
+            let mut patterns = Vec::with_capacity(clause.patterns.len());
+            for pattern in &clause.patterns {
+                patterns.push(self.pattern(pattern, &mut env)?);
+            }
+            let pattern = values_pattern(patterns);
+
+            let guard = match clause.guard {
+                Expr::Literal(Literal::Atom(ref guard)) if guard == "true" => None,
+                ref guard => {
+                    let mut guard_env = env.clone();
+                    guard_env.guard = true;
+                    Some(self.expr(guard, &guard_env)?)
+                }
+            };
+            let body = self.expr(&clause.body, &env)?;
+
+            sources.push(match guard {
+                Some(guard) => format!("{} when {} -> {}", pattern, guard, body),
+                None => format!("{} -> {}", pattern, body),
+            });
+        }
+        Ok(sources.join("; "))
+    }
+
+    fn pattern(&mut self, pattern: &Pattern, env: &mut Env) -> TranslateResult<String> {
+        let source = match *pattern {
+            Pattern::Var(ref name) => self.bind(name, env),
+            Pattern::Literal(ref literal) => literal_source(literal),
+            Pattern::Cons(ref head, ref tail) => {
+                let head = self.pattern(head, env)?;
+                format!("[{} | {}]", head, self.pattern(tail, env)?)
+            }
+            Pattern::Tuple(ref elements) => {
+                let mut sources = Vec::with_capacity(elements.len());
+                for element in elements {
+                    sources.push(self.pattern(element, env)?);
+                }
+                format!("{{{}}}", sources.join(", "))
+            }
+            Pattern::Binary(ref segments) => {
+                let mut sources = Vec::with_capacity(segments.len());
+                for segment in segments {
+                    let value = self.pattern(&segment.value, env)?;
+                    sources.push(self.segment(value, segment, env)?);
+                }
+                format!("<<{}>>", sources.join(", "))
+            }
+            Pattern::Map(ref pairs) => {
+                let mut sources = Vec::with_capacity(pairs.len());
+                for pair in pairs {
+                    let value = self.pattern(&pair.value, env)?;
+                    sources.push(self.map_pair(pair, value, env)?);
+                }
+                format!("#{{{}}}", sources.join(", "))
+            }
+            Pattern::Alias(ref name, ref pattern) => {
+                let pattern = self.pattern(pattern, env)?;
+                format!("({} = {})", self.bind(name, env), pattern)
+            }
+        };
+
+        Ok(source)
+    }
+
+    fn map_pair<T>(
+        &mut self,
+        pair: &MapPair<T>,
+        value: String,
+        env: &Env,
+    ) -> TranslateResult<String> {
+        Ok(format!(
+            "{} {} {}",
+            self.expr(&pair.key, env)?,
+            if pair.exact { ":=" } else { "=>" },
+            value
+        ))
+    }
+
+    /// `Value:Size/Type-Flags-unit:Unit`, leaving out the size and unit when Core Erlang leaves
+    /// them to the type
+    fn segment<T>(
+        &mut self,
+        value: String,
+        segment: &Segment<T>,
+        env: &Env,
+    ) -> TranslateResult<String> {
+        let mut source = value;
+
+        match segment.size {
+            Expr::Literal(Literal::Atom(ref size)) if size == "all" || size == "undefined" => (),
+            Expr::Literal(Literal::Integer(ref size)) => source.push_str(&format!(":{}", size)),
+            ref size => source.push_str(&format!(":({})", self.expr(size, env)?)),
+        }
+
+        let ty = match segment.ty {
+            Expr::Literal(Literal::Atom(ref ty)) => ty,
+            _ => return Err(unsupported("segment type that is not an atom")),
+        };
+        source.push_str(&format!("/{}", ty));
+
+        // Erlang rejects signedness and endianness on binaries, which Core Erlang still lists
+        if ty != "binary" && ty != "bitstring" {
+            let mut flags = &segment.flags;
+            while let Expr::Cons(ref head, ref tail) = *flags {
+                match **head {
+                    Expr::Literal(Literal::Atom(ref flag)) => {
+                        source.push_str(&format!("-{}", flag))
+                    }
+                    _ => return Err(unsupported("segment flag that is not an atom")),
+                }
+                flags = tail;
+            }
+        }
+
+        if let Expr::Literal(Literal::Integer(ref unit)) = segment.unit {
+            source.push_str(&format!("-unit:{}", unit));
+        }
+
+        Ok(source)
+    }
+}
+
+/// Operators that `erlang` functions of the same name and arity are written as
+fn is_operator(function: &str, arity: usize) -> bool {
+    match arity {
+        1 => ["-", "+", "not", "bnot"].contains(&function),
+        2 => [
+            "+", "-", "*", "/", "div", "rem", "band", "bor", "bxor", "bsl", "bsr", "and", "or",
+            "xor", "==", "/=", "=<", "<", ">=", ">", "=:=", "=/=", "++", "--", "!",
+        ]
+        .contains(&function),
+        _ => false,
+    }
+}
+
+/// A single value, or a tuple standing in for a value list
+fn values_pattern(mut sources: Vec<String>) -> String {
+    if sources.len() == 1 {
+        sources.remove(0)
+    } else {
+        format!("{{{}}}", sources.join(", "))
+    }
+}
+
+/// Whether the `elements` of the reason of a `match_fail` are `{function_clause, Arg1, ..., ArgN}`
+fn is_function_clause(elements: &[Expr]) -> bool {
+    match elements.first() {
+        Some(Expr::Literal(Literal::Atom(ref tag))) => tag == "function_clause",
+        _ => false,
+    }
+}
+
+fn literal_source(literal: &Literal) -> String {
+    match *literal {
+        Literal::Atom(ref name) => atom(name),
+        Literal::Integer(ref value) => value.to_string(),
+        Literal::Float(value) => literal::Float::new(0, value).to_string(),
+        Literal::Char(value) => (value as u32).to_string(),
+        Literal::String(ref value) => literal::Str::new(0, value.clone()).to_string(),
+        Literal::Nil => "[]".to_string(),
+    }
+}
+
+/// The attributes that are part of compiling a module, which are dropped, as they are not written
+/// the same in Erlang, or, like `file`, are not needed to compile it
+const COMPILE_ATTRIBUTES: &[&str] = &[
+    "callback",
+    "compile",
+    "deprecated",
+    "export",
+    "export_type",
+    "file",
+    "import",
+    "on_load",
+    "opaque",
+    "optional_callbacks",
+    "record",
+    "removed",
+    "spec",
+    "type",
+];
+
+/// The Erlang attributes for the Core Erlang attribute `name`, whose `value` is a list, as
+/// `module_info(attributes)` returns it.  Each behaviour gets its own `-behaviour`, as it only
+/// takes one.
+fn attributes(translator: &mut Translator, name: &str, value: &Expr) -> TranslateResult<String> {
+    if COMPILE_ATTRIBUTES.contains(&name) {
+        return Ok(String::new());
+    }
+
+    let name = attribute_name(name);
+    let env = Env::default();
+
+    match name.as_str() {
+        "behaviour" | "behavior" => {
+            let mut source = String::new();
+            let mut list = value;
+            while let Expr::Cons(ref head, ref tail) = *list {
+                source.push_str(&format!("-{}({}).\n", name, translator.expr(head, &env)?));
+                list = tail;
+            }
+            Ok(source)
+        }
+        _ => Ok(format!("-{}({}).\n", name, translator.expr(value, &env)?)),
+    }
+}
+
+/// `name` unquoted when it can be, as attribute names are written unquoted
+fn attribute_name(name: &str) -> String {
+    let mut chars = name.chars();
+    let unquoted = chars.next().map_or(false, |c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if unquoted {
+        name.to_string()
+    } else {
+        atom(name)
+    }
+}
+
+fn atom(name: &str) -> String {
+    literal::Atom::new(0, name.to_string()).to_string()
+}
+
+fn fun_name(name: &FunName) -> String {
+    format!("{}/{}", atom(&name.name), name.arity)
+}
+
+fn defined_later(name: &FunName) -> CoreError {
+    unsupported(format!(
+        "letrec function {} referred to before it is defined",
+        fun_name(name)
+    ))
+}
+
+fn unsupported<S: Into<String>>(what: S) -> CoreError {
+    CoreError::Unsupported(what.into())
+}
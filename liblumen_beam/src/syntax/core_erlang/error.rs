@@ -0,0 +1,18 @@
+use failure::Fail;
+
+#[derive(Fail, Debug)]
+pub enum CoreError {
+    #[fail(display = "syntax error on line {}: {}", line, message)]
+    Syntax { line: usize, message: String },
+
+    #[fail(display = "unsupported Core Erlang: {}", _0)]
+    Unsupported(String),
+}
+impl CoreError {
+    pub(super) fn syntax<S: Into<String>>(line: usize, message: S) -> Self {
+        CoreError::Syntax {
+            line,
+            message: message.into(),
+        }
+    }
+}
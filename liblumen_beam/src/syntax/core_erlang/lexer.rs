@@ -0,0 +1,285 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+use num::bigint::BigInt;
+use num::Num;
+
+use super::error::CoreError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Atom(String),
+    Var(String),
+    Integer(BigInt),
+    Float(f64),
+    Char(char),
+    String(String),
+    /// Bare words, which in Core Erlang are always keywords
+    Keyword(String),
+    Symbol(&'static str),
+}
+
+/// Longest symbols first, so that `->` is not read as `-`
+const SYMBOLS: &[&str] = &[
+    "->", "-|", "#{", "}#", "#<", "~{", "}~", "=>", ":=", "(", ")", "{", "}", "[", "]", "<", ">",
+    "|", ",", ":", "/", "=",
+];
+
+pub fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, CoreError> {
+    let mut lexer = Lexer {
+        chars: source.chars().peekable(),
+        rest: source,
+        line: 1,
+    };
+    let mut tokens = Vec::new();
+
+    while let Some(token) = lexer.next_token()? {
+        tokens.push((token, lexer.line));
+    }
+
+    Ok(tokens)
+}
+
+struct Lexer<'a> {
+    chars: Peekable<Chars<'a>>,
+    /// The unconsumed source, kept in step with `chars` for symbol matching
+    rest: &'a str,
+    line: usize,
+}
+impl<'a> Lexer<'a> {
+    fn next_token(&mut self) -> Result<Option<Token>, CoreError> {
+        self.skip_whitespace_and_comments();
+
+        let c = match self.chars.peek() {
+            Some(&c) => c,
+            None => return Ok(None),
+        };
+
+        let token = match c {
+            '\'' => {
+                self.bump();
+                Token::Atom(self.quoted('\'')?)
+            }
+            '"' => {
+                self.bump();
+                Token::String(self.quoted('"')?)
+            }
+            '$' => {
+                self.bump();
+                let c = self.character()?;
+                Token::Char(c)
+            }
+            c if c.is_ascii_digit() => self.number(false)?,
+            '-' | '+' if self.rest[1..].starts_with(|c: char| c.is_ascii_digit()) => {
+                self.bump();
+                self.number(c == '-')?
+            }
+            c if c.is_uppercase() || c == '_' => Token::Var(self.name()),
+            c if c.is_lowercase() => Token::Keyword(self.name()),
+            _ => match SYMBOLS.iter().find(|symbol| self.rest.starts_with(*symbol)) {
+                Some(symbol) => {
+                    for _ in 0..symbol.len() {
+                        self.bump();
+                    }
+                    Token::Symbol(symbol)
+                }
+                None => {
+                    return Err(CoreError::syntax(
+                        self.line,
+                        format!("unexpected character {:?}", c),
+                    ))
+                }
+            },
+        };
+
+        Ok(Some(token))
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        self.rest = &self.rest[c.len_utf8()..];
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_whitespace_and_comments(&mut self) {
+        while let Some(&c) = self.chars.peek() {
+            if c == '%' {
+                while let Some(c) = self.bump() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            } else if c.is_whitespace() {
+                self.bump();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn name(&mut self) -> String {
+        let mut name = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if c.is_alphanumeric() || c == '_' || c == '@' {
+                name.push(c);
+                self.bump();
+            } else {
+                break;
+            }
+        }
+        name
+    }
+
+    fn digits(&mut self, radix: u32) -> String {
+        let mut digits = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if c.is_digit(radix) {
+                digits.push(c);
+                self.bump();
+            } else {
+                break;
+            }
+        }
+        digits
+    }
+
+    fn number(&mut self, negative: bool) -> Result<Token, CoreError> {
+        let sign = if negative { "-" } else { "" };
+        let integer = self.digits(10);
+
+        if self.rest.starts_with('#') {
+            self.bump();
+            let radix: u32 = integer
+                .parse()
+                .ok()
+                .filter(|radix| (2..=36).contains(radix))
+                .ok_or_else(|| CoreError::syntax(self.line, "invalid radix"))?;
+            let digits = self.digits(radix);
+            return BigInt::from_str_radix(&format!("{}{}", sign, digits), radix)
+                .map(Token::Integer)
+                .map_err(|_| CoreError::syntax(self.line, "invalid integer"));
+        }
+
+        let is_fraction =
+            self.rest.starts_with('.') && self.rest[1..].starts_with(|c: char| c.is_ascii_digit());
+        if !is_fraction {
+            return BigInt::from_str_radix(&format!("{}{}", sign, integer), 10)
+                .map(Token::Integer)
+                .map_err(|_| CoreError::syntax(self.line, "invalid integer"));
+        }
+
+        self.bump();
+        let mut float = format!("{}{}.{}", sign, integer, self.digits(10));
+        if self.rest.starts_with(&['e', 'E'][..]) {
+            self.bump();
+            float.push('e');
+            if let Some(&c) = self.chars.peek() {
+                if c == '-' || c == '+' {
+                    float.push(c);
+                    self.bump();
+                }
+            }
+            float.push_str(&self.digits(10));
+        }
+
+        float
+            .parse()
+            .map(Token::Float)
+            .map_err(|_| CoreError::syntax(self.line, "invalid float"))
+    }
+
+    fn quoted(&mut self, quote: char) -> Result<String, CoreError> {
+        let mut s = String::new();
+        loop {
+            match self.chars.peek() {
+                Some(&c) if c == quote => {
+                    self.bump();
+                    return Ok(s);
+                }
+                Some(_) => s.push(self.character()?),
+                None => return Err(CoreError::syntax(self.line, "unterminated quote")),
+            }
+        }
+    }
+
+    /// A single, possibly escaped, character
+    fn character(&mut self) -> Result<char, CoreError> {
+        let c = self
+            .bump()
+            .ok_or_else(|| CoreError::syntax(self.line, "unexpected end of input"))?;
+        if c != '\\' {
+            return Ok(c);
+        }
+
+        let escaped = self
+            .bump()
+            .ok_or_else(|| CoreError::syntax(self.line, "unexpected end of input"))?;
+        let c = match escaped {
+            'b' => '\x08',
+            'd' => '\x7f',
+            'e' => '\x1b',
+            'f' => '\x0c',
+            'n' => '\n',
+            'r' => '\r',
+            's' => ' ',
+            't' => '\t',
+            'v' => '\x0b',
+            '^' => {
+                let c = self
+                    .bump()
+                    .ok_or_else(|| CoreError::syntax(self.line, "unexpected end of input"))?;
+                ((c as u32) & 0x1f) as u8 as char
+            }
+            'x' => {
+                let digits = if self.rest.starts_with('{') {
+                    self.bump();
+                    let digits = self.digits(16);
+                    if self.bump() != Some('}') {
+                        return Err(CoreError::syntax(self.line, "invalid hex escape"));
+                    }
+                    digits
+                } else {
+                    let mut digits = String::new();
+                    for _ in 0..2 {
+                        match self.chars.peek() {
+                            Some(&c) if c.is_ascii_hexdigit() => {
+                                digits.push(c);
+                                self.bump();
+                            }
+                            _ => break,
+                        }
+                    }
+                    digits
+                };
+                code_point(&digits, 16)
+                    .ok_or_else(|| CoreError::syntax(self.line, "invalid hex escape"))?
+            }
+            c if c.is_digit(8) => {
+                let mut digits = c.to_string();
+                for _ in 0..2 {
+                    match self.chars.peek() {
+                        Some(&c) if c.is_digit(8) => {
+                            digits.push(c);
+                            self.bump();
+                        }
+                        _ => break,
+                    }
+                }
+                code_point(&digits, 8)
+                    .ok_or_else(|| CoreError::syntax(self.line, "invalid octal escape"))?
+            }
+            c => c,
+        };
+
+        Ok(c)
+    }
+}
+
+fn code_point(digits: &str, radix: u32) -> Option<char> {
+    u32::from_str_radix(digits, radix)
+        .ok()
+        .and_then(std::char::from_u32)
+}
@@ -0,0 +1,526 @@
+use super::ast::*;
+use super::error::CoreError;
+use super::lexer::{tokenize, Token};
+
+pub type ParseResult<T> = Result<T, CoreError>;
+
+/// Parses a `module ... end` Core Erlang module, such as the output of `erlc +to_core`
+pub fn parse_module(source: &str) -> ParseResult<Module> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        position: 0,
+    };
+    let module = parser.annotated(Parser::module)?;
+
+    if parser.position < parser.tokens.len() {
+        return Err(parser.unexpected());
+    }
+
+    Ok(module)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+}
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> ParseResult<Token> {
+        let token = self
+            .peek()
+            .cloned()
+            .ok_or_else(|| CoreError::syntax(self.line(), "unexpected end of input"))?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .or_else(|| self.tokens.last())
+            .map_or(1, |(_, line)| *line)
+    }
+
+    fn unexpected(&self) -> CoreError {
+        match self.peek() {
+            Some(token) => CoreError::syntax(self.line(), format!("unexpected {:?}", token)),
+            None => CoreError::syntax(self.line(), "unexpected end of input"),
+        }
+    }
+
+    fn is_symbol(&self, symbol: &str) -> bool {
+        match self.peek() {
+            Some(Token::Symbol(s)) => *s == symbol,
+            _ => false,
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Keyword(word)) => word == keyword,
+            _ => false,
+        }
+    }
+
+    fn symbol(&mut self, symbol: &str) -> ParseResult<()> {
+        if self.is_symbol(symbol) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str) -> ParseResult<()> {
+        if self.is_keyword(keyword) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    /// Parses `item` or `( item -| Annotations )`, discarding the annotations
+    fn annotated<T>(&mut self, item: fn(&mut Self) -> ParseResult<T>) -> ParseResult<T> {
+        if self.is_symbol("(") {
+            let start = self.position;
+            self.position += 1;
+            if let Ok(value) = item(self) {
+                if self.is_symbol("-|") {
+                    self.position += 1;
+                    self.expr()?;
+                    self.symbol(")")?;
+                    return Ok(value);
+                }
+            }
+            self.position = start;
+        }
+        item(self)
+    }
+
+    /// Parses `open item, ... close`
+    fn sequence<T>(
+        &mut self,
+        open: &str,
+        close: &str,
+        item: fn(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<Vec<T>> {
+        self.symbol(open)?;
+        let mut items = Vec::new();
+        if !self.is_symbol(close) {
+            loop {
+                items.push(item(self)?);
+                if self.is_symbol(",") {
+                    self.position += 1;
+                } else {
+                    break;
+                }
+            }
+        }
+        self.symbol(close)?;
+        Ok(items)
+    }
+
+    fn module(&mut self) -> ParseResult<Module> {
+        self.keyword("module")?;
+        let name = self.atom()?;
+        let exports = self.sequence("[", "]", |p| p.annotated(Parser::fun_name))?;
+        self.keyword("attributes")?;
+        let attributes = self.sequence("[", "]", |p| {
+            let name = p.annotated(Parser::atom)?;
+            p.symbol("=")?;
+            Ok((name, p.expr()?))
+        })?;
+        let definitions = self.definitions("end")?;
+        self.keyword("end")?;
+
+        Ok(Module {
+            name,
+            exports,
+            attributes,
+            definitions,
+        })
+    }
+
+    /// Function definitions up to, but not including, the `terminator` keyword
+    fn definitions(&mut self, terminator: &str) -> ParseResult<Vec<FunDef>> {
+        let mut definitions = Vec::new();
+        while !self.is_keyword(terminator) {
+            let name = self.annotated(Parser::fun_name)?;
+            self.symbol("=")?;
+            let fun = self.annotated(Parser::fun)?;
+            definitions.push(FunDef { name, fun });
+        }
+        Ok(definitions)
+    }
+
+    fn atom(&mut self) -> ParseResult<String> {
+        match self.next()? {
+            Token::Atom(name) => Ok(name),
+            _ => {
+                self.position -= 1;
+                Err(self.unexpected())
+            }
+        }
+    }
+
+    fn var(&mut self) -> ParseResult<String> {
+        match self.next()? {
+            Token::Var(name) => Ok(name),
+            _ => {
+                self.position -= 1;
+                Err(self.unexpected())
+            }
+        }
+    }
+
+    fn fun_name(&mut self) -> ParseResult<FunName> {
+        let name = self.atom()?;
+        self.symbol("/")?;
+        match self.next()? {
+            Token::Integer(arity) => Ok(FunName {
+                name,
+                arity: arity
+                    .to_string()
+                    .parse()
+                    .map_err(|_| CoreError::syntax(self.line(), "invalid arity"))?,
+            }),
+            _ => {
+                self.position -= 1;
+                Err(self.unexpected())
+            }
+        }
+    }
+
+    fn fun(&mut self) -> ParseResult<Fun> {
+        self.keyword("fun")?;
+        let vars = self.sequence("(", ")", |p| p.annotated(Parser::var))?;
+        self.symbol("->")?;
+        let body = self.expr()?;
+        Ok(Fun { vars, body })
+    }
+
+    /// `Var` or `<Var, ...>`
+    fn vars(&mut self) -> ParseResult<Vec<String>> {
+        if self.is_symbol("<") {
+            self.sequence("<", ">", |p| p.annotated(Parser::var))
+        } else {
+            Ok(vec![self.annotated(Parser::var)?])
+        }
+    }
+
+    fn args(&mut self) -> ParseResult<Vec<Expr>> {
+        self.sequence("(", ")", Parser::expr)
+    }
+
+    fn expr(&mut self) -> ParseResult<Expr> {
+        self.annotated(Parser::single_expr)
+    }
+
+    fn single_expr(&mut self) -> ParseResult<Expr> {
+        let expr = match self.next()? {
+            Token::Var(name) => Expr::Var(name),
+            Token::Atom(name) => {
+                if self.is_symbol("/") {
+                    self.position -= 1;
+                    Expr::FunName(self.fun_name()?)
+                } else {
+                    Expr::Literal(Literal::Atom(name))
+                }
+            }
+            Token::Integer(value) => Expr::Literal(Literal::Integer(value)),
+            Token::Float(value) => Expr::Literal(Literal::Float(value)),
+            Token::Char(value) => Expr::Literal(Literal::Char(value)),
+            Token::String(value) => Expr::Literal(Literal::String(value)),
+            Token::Symbol("[") => {
+                self.position -= 1;
+                self.list(Parser::expr, Expr::Literal(Literal::Nil), |head, tail| {
+                    Expr::Cons(Box::new(head), Box::new(tail))
+                })?
+            }
+            Token::Symbol("{") => {
+                self.position -= 1;
+                Expr::Tuple(self.sequence("{", "}", Parser::expr)?)
+            }
+            Token::Symbol("<") => {
+                self.position -= 1;
+                Expr::Values(self.sequence("<", ">", Parser::expr)?)
+            }
+            Token::Symbol("#{") => {
+                self.position -= 1;
+                Expr::Binary(self.sequence("#{", "}#", |p| p.segment(Parser::expr))?)
+            }
+            Token::Symbol("~{") => {
+                let mut pairs = Vec::new();
+                let mut base = None;
+                if !self.is_symbol("}~") {
+                    loop {
+                        pairs.push(self.map_pair(Parser::expr)?);
+                        if self.is_symbol(",") {
+                            self.position += 1;
+                        } else {
+                            break;
+                        }
+                    }
+                    if self.is_symbol("|") {
+                        self.position += 1;
+                        base = Some(Box::new(self.expr()?));
+                    }
+                }
+                self.symbol("}~")?;
+                Expr::Map { pairs, base }
+            }
+            Token::Keyword(keyword) => return self.keyword_expr(&keyword),
+            _ => {
+                self.position -= 1;
+                return Err(self.unexpected());
+            }
+        };
+
+        Ok(expr)
+    }
+
+    fn keyword_expr(&mut self, keyword: &str) -> ParseResult<Expr> {
+        let expr = match keyword {
+            "fun" => {
+                self.position -= 1;
+                Expr::Fun(Box::new(self.fun()?))
+            }
+            "let" => {
+                let vars = self.vars()?;
+                self.symbol("=")?;
+                let value = Box::new(self.expr()?);
+                self.keyword("in")?;
+                let body = Box::new(self.expr()?);
+                Expr::Let { vars, value, body }
+            }
+            "letrec" => {
+                let definitions = self.definitions("in")?;
+                self.keyword("in")?;
+                let body = Box::new(self.expr()?);
+                Expr::LetRec { definitions, body }
+            }
+            "case" => {
+                let arg = Box::new(self.expr()?);
+                self.keyword("of")?;
+                let clauses = self.clauses("end")?;
+                self.keyword("end")?;
+                Expr::Case { arg, clauses }
+            }
+            "apply" => {
+                let fun = Box::new(self.expr()?);
+                let args = self.args()?;
+                Expr::Apply { fun, args }
+            }
+            "call" => {
+                let module = Box::new(self.expr()?);
+                self.symbol(":")?;
+                let function = Box::new(self.expr()?);
+                let args = self.args()?;
+                Expr::Call {
+                    module,
+                    function,
+                    args,
+                }
+            }
+            "primop" => {
+                let name = self.annotated(Parser::atom)?;
+                let args = self.args()?;
+                Expr::PrimOp { name, args }
+            }
+            "try" => {
+                let arg = Box::new(self.expr()?);
+                self.keyword("of")?;
+                let vars = self.vars()?;
+                self.symbol("->")?;
+                let body = Box::new(self.expr()?);
+                self.keyword("catch")?;
+                let catch_vars = self.vars()?;
+                self.symbol("->")?;
+                let handler = Box::new(self.expr()?);
+                Expr::Try {
+                    arg,
+                    vars,
+                    body,
+                    catch_vars,
+                    handler,
+                }
+            }
+            "receive" => {
+                let clauses = self.clauses("after")?;
+                self.keyword("after")?;
+                let timeout = Box::new(self.expr()?);
+                self.symbol("->")?;
+                let action = Box::new(self.expr()?);
+                Expr::Receive {
+                    clauses,
+                    timeout,
+                    action,
+                }
+            }
+            "do" => {
+                let first = Box::new(self.expr()?);
+                let second = Box::new(self.expr()?);
+                Expr::Do(first, second)
+            }
+            "catch" => Expr::Catch(Box::new(self.expr()?)),
+            _ => {
+                self.position -= 1;
+                return Err(self.unexpected());
+            }
+        };
+
+        Ok(expr)
+    }
+
+    /// Clauses up to, but not including, the `terminator` keyword
+    fn clauses(&mut self, terminator: &str) -> ParseResult<Vec<Clause>> {
+        let mut clauses = Vec::new();
+        while !self.is_keyword(terminator) {
+            clauses.push(self.annotated(Parser::clause)?);
+        }
+        Ok(clauses)
+    }
+
+    fn clause(&mut self) -> ParseResult<Clause> {
+        let patterns = if self.is_symbol("<") {
+            self.sequence("<", ">", Parser::pattern)?
+        } else {
+            vec![self.pattern()?]
+        };
+        self.keyword("when")?;
+        let guard = self.expr()?;
+        self.symbol("->")?;
+        let body = self.expr()?;
+
+        Ok(Clause {
+            patterns,
+            guard,
+            body,
+        })
+    }
+
+    fn pattern(&mut self) -> ParseResult<Pattern> {
+        self.annotated(Parser::single_pattern)
+    }
+
+    fn single_pattern(&mut self) -> ParseResult<Pattern> {
+        let pattern = match self.next()? {
+            Token::Var(name) => {
+                if self.is_symbol("=") {
+                    self.position += 1;
+                    Pattern::Alias(name, Box::new(self.pattern()?))
+                } else {
+                    Pattern::Var(name)
+                }
+            }
+            Token::Atom(name) => Pattern::Literal(Literal::Atom(name)),
+            Token::Integer(value) => Pattern::Literal(Literal::Integer(value)),
+            Token::Float(value) => Pattern::Literal(Literal::Float(value)),
+            Token::Char(value) => Pattern::Literal(Literal::Char(value)),
+            Token::String(value) => Pattern::Literal(Literal::String(value)),
+            Token::Symbol("[") => {
+                self.position -= 1;
+                self.list(
+                    Parser::pattern,
+                    Pattern::Literal(Literal::Nil),
+                    |head, tail| Pattern::Cons(Box::new(head), Box::new(tail)),
+                )?
+            }
+            Token::Symbol("{") => {
+                self.position -= 1;
+                Pattern::Tuple(self.sequence("{", "}", Parser::pattern)?)
+            }
+            Token::Symbol("#{") => {
+                self.position -= 1;
+                Pattern::Binary(self.sequence("#{", "}#", |p| p.segment(Parser::pattern))?)
+            }
+            Token::Symbol("~{") => {
+                self.position -= 1;
+                Pattern::Map(self.sequence("~{", "}~", |p| p.map_pair(Parser::pattern))?)
+            }
+            _ => {
+                self.position -= 1;
+                return Err(self.unexpected());
+            }
+        };
+
+        Ok(pattern)
+    }
+
+    /// `[E1, ..., En]` or `[E1, ..., En | Tail]`
+    fn list<T>(
+        &mut self,
+        item: fn(&mut Self) -> ParseResult<T>,
+        nil: T,
+        cons: fn(T, T) -> T,
+    ) -> ParseResult<T> {
+        self.symbol("[")?;
+        if self.is_symbol("]") {
+            self.position += 1;
+            return Ok(nil);
+        }
+
+        let mut heads = vec![item(self)?];
+        while self.is_symbol(",") {
+            self.position += 1;
+            heads.push(item(self)?);
+        }
+        let tail = if self.is_symbol("|") {
+            self.position += 1;
+            item(self)?
+        } else {
+            nil
+        };
+        self.symbol("]")?;
+
+        Ok(heads
+            .into_iter()
+            .rev()
+            .fold(tail, |tail, head| cons(head, tail)))
+    }
+
+    fn segment<T>(&mut self, value: fn(&mut Self) -> ParseResult<T>) -> ParseResult<Segment<T>> {
+        self.symbol("#<")?;
+        let value = value(self)?;
+        self.symbol(">")?;
+        let mut options = self.args()?.into_iter();
+        match (
+            options.next(),
+            options.next(),
+            options.next(),
+            options.next(),
+            options.next(),
+        ) {
+            (Some(size), Some(unit), Some(ty), Some(flags), None) => Ok(Segment {
+                value,
+                size,
+                unit,
+                ty,
+                flags,
+            }),
+            _ => Err(CoreError::syntax(
+                self.line(),
+                "expected size, unit, type and flags",
+            )),
+        }
+    }
+
+    fn map_pair<T>(&mut self, value: fn(&mut Self) -> ParseResult<T>) -> ParseResult<MapPair<T>> {
+        let key = self.expr()?;
+        let exact = if self.is_symbol(":=") {
+            true
+        } else if self.is_symbol("=>") {
+            false
+        } else {
+            return Err(self.unexpected());
+        };
+        self.position += 1;
+        let value = value(self)?;
+
+        Ok(MapPair { key, exact, value })
+    }
+}
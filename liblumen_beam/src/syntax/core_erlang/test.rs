@@ -0,0 +1,181 @@
+use crate::syntax::core_erlang::*;
+
+const FACT: &str = r#"
+module 'fact' ['fact'/1,
+               'module_info'/0,
+               'module_info'/1]
+    attributes [%% Line 1
+                'file' =
+                    %% Line 1
+                    [{[102|[97|[99|[116|[46|[101|[114|[108]]]]]]]],1}]]
+'fact'/1 =
+    %% Line 4
+    ( fun (_0) ->
+          case _0 of
+            <0> when 'true' ->
+                1
+            %% Line 5
+            <N> when call 'erlang':'>'(_0, 0) ->
+                let <_1> =
+                    call 'erlang':'-'(N, 1)
+                in  let <_2> =
+                        apply 'fact'/1(_1)
+                    in  call 'erlang':'*'(N, _2)
+            ( <_3> when 'true' ->
+                  primop 'match_fail'({'function_clause',_3})
+              -| [{'function_name',{'fact',1}}] )
+          end
+      -| [{'function',{'fact',1}}] )
+'module_info'/0 =
+    fun () ->
+        call 'erlang':'get_module_info'('fact')
+'module_info'/1 =
+    fun (_0) ->
+        call 'erlang':'get_module_info'('fact', _0)
+end
+"#;
+
+#[test]
+fn parse() {
+    let module = parse_module(FACT).unwrap();
+
+    assert_eq!(module.name, "fact");
+    assert_eq!(module.exports.len(), 3);
+    assert_eq!(module.definitions.len(), 3);
+    assert_eq!(module.definitions[0].name.name, "fact");
+    assert_eq!(module.definitions[0].fun.vars, vec!["_0".to_string()]);
+}
+
+#[test]
+fn syntax_error() {
+    match parse_module("module 'm' [] attributes [] 'f'/0 = fun () -> end") {
+        Err(CoreError::Syntax { line: 1, .. }) => (),
+        other => panic!("expected a syntax error, got {:?}", other),
+    }
+}
+
+#[test]
+fn to_erlang() {
+    let source = parse_module(FACT).unwrap().to_erlang().unwrap();
+
+    assert_eq!(
+        source,
+        "-module('fact').\n\
+         -export(['fact'/1]).\n\
+         'fact'(V1) -> case V1 of 0 -> 1; V2 when (V1 > 0) -> \
+         begin V3 = (V2 - 1), begin V4 = 'fact'(V3), (V2 * V4) end end; \
+         V5 -> erlang:error('function_clause', [V5]) end.\n"
+    );
+}
+
+#[test]
+fn to_erlang_attributes() {
+    let source = parse_module(
+        r#"module 'm' [] attributes ['file' = [{[109|[46|[101|[114|[108]]]]],1}],
+                                   'behaviour' = ['gen_server'|['gen_event']],
+                                   'answer' = [42]]
+        end"#,
+    )
+    .unwrap()
+    .to_erlang()
+    .unwrap();
+
+    assert_eq!(
+        source,
+        "-module('m').\n\
+         -export([]).\n\
+         -behaviour('gen_server').\n\
+         -behaviour('gen_event').\n\
+         -answer([42 | []]).\n"
+    );
+}
+
+#[test]
+fn to_erlang_letrec_and_guards() {
+    let source = parse_module(
+        r#"module 'm' ['f'/1] attributes []
+        'f'/1 = fun (L) ->
+            letrec 'lc$^0'/1 = fun (_1) ->
+                case _1 of
+                  <[H|T]> when try let <_2> = call 'erlang':'is_integer'(H) in _2
+                               of <Try> -> Try catch <_c,_r,_s> -> 'false' ->
+                      let <_3> = apply 'lc$^0'/1(T) in [H|_3]
+                  <[_|T]> when 'true' -> apply 'lc$^0'/1(T)
+                  <[]> when 'true' -> []
+                end
+            in apply 'lc$^0'/1(L)
+        end"#,
+    )
+    .unwrap()
+    .to_erlang()
+    .unwrap();
+
+    assert_eq!(
+        source,
+        "-module('m').\n\
+         -export(['f'/1]).\n\
+         'f'(V1) -> begin V2 = fun V3(V4) -> case V4 of \
+         [V5 | V6] when 'erlang':'is_integer'(V5) -> begin V7 = (V3)(V6), [V5 | V7] end; \
+         [V8 | V9] -> (V3)(V9); \
+         [] -> [] end end, (V2)(V1) end.\n"
+    );
+}
+
+#[test]
+fn to_erlang_mutually_recursive_letrec_is_unsupported() {
+    let result = parse_module(
+        r#"module 'm' ['f'/1] attributes []
+        'f'/1 = fun (N) ->
+            letrec 'even'/1 = fun (_1) ->
+                       case _1 of
+                         <0> when 'true' -> 'true'
+                         <_2> when 'true' -> apply 'odd'/1(call 'erlang':'-'(_2, 1))
+                       end
+                   'odd'/1 = fun (_3) ->
+                       case _3 of
+                         <0> when 'true' -> 'false'
+                         <_4> when 'true' -> apply 'even'/1(call 'erlang':'-'(_4, 1))
+                       end
+            in apply 'even'/1(N)
+        end"#,
+    )
+    .unwrap()
+    .to_erlang();
+
+    match result {
+        Err(CoreError::Unsupported(message)) => assert_eq!(
+            message,
+            "letrec function 'odd'/1 referred to before it is defined"
+        ),
+        other => panic!("expected unsupported, got {:?}", other),
+    }
+}
+
+#[test]
+fn to_erlang_binaries_and_maps() {
+    let source = parse_module(
+        r#"module 'm' ['f'/2] attributes []
+        'f'/2 = fun (Bin, Map) ->
+            case <Bin, Map> of
+              <#{#<Size>(8,1,'integer',['unsigned'|['big']]),
+                 #<Rest>('all',8,'binary',['unsigned'|['big']])}#, ~{'size' := 8}~> when 'true' ->
+                  ~{'rest' => #{#<Rest>('all',8,'binary',['unsigned'|['big']])}#|Map}~
+              ( <_2,_3> when 'true' -> primop 'match_fail'({'case_clause',{_2,_3}})
+                -| ['compiler_generated'] )
+            end
+        end"#,
+    )
+    .unwrap()
+    .to_erlang()
+    .unwrap();
+
+    assert_eq!(
+        source,
+        "-module('m').\n\
+         -export(['f'/2]).\n\
+         'f'(V1, V2) -> case {V1, V2} of \
+         {<<V3:8/integer-unsigned-big-unit:1, V4/binary-unit:8>>, #{'size' := 8}} -> \
+         (V2)#{'rest' => <<(V4)/binary-unit:8>>}; \
+         {V5, V6} -> erlang:error({'case_clause', {V5, V6}}) end.\n"
+    );
+}
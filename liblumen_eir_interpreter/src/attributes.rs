@@ -0,0 +1,331 @@
+//! Module attributes, such as `-vsn`, `-behaviour` and custom attributes, and exports, which
+//! lowering to EIR drops, so they are kept from the parsed module when it is compiled with
+//! `crate::compile::compile_str`, which modules compiled from BEAM files, abstract format forms
+//! and Core Erlang are also compiled with.
+//!
+//! The attributes of a loaded module are returned by `Module:module_info(attributes)` and by
+//! `ModuleRegistry::attributes`, and `ModuleRegistry::modules_with_behaviour` finds the loaded
+//! modules that implement a behaviour, as frameworks do for discovery.  As on the BEAM, an
+//! attribute whose value isn't a term, such as `-answer(6 * 7).`, fails compilation.
+
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::sync::{Arc, Mutex};
+
+use lazy_static::lazy_static;
+
+use num_bigint::BigInt;
+
+use libeir_syntax_erl::ast::{Expr, Literal, Module as ErlAstModule, UnaryOp};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::exception::Exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, AsTerm, Atom, Term};
+
+use crate::module::ModuleRegistry;
+
+#[derive(Clone, Debug)]
+pub struct Attribute {
+    pub name: Atom,
+    pub value: Value,
+}
+
+impl Attribute {
+    /// Whether this is a `-behaviour(behaviour)` or `-behavior(behaviour)` attribute
+    pub fn is_behaviour(&self, behaviour: Atom) -> bool {
+        match (self.name.name(), &self.value) {
+            ("behaviour", Value::Atom(atom)) | ("behavior", Value::Atom(atom)) => {
+                *atom == behaviour
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The value of an attribute, which is made on the heap of each process that asks for it
+#[derive(Clone, Debug)]
+pub enum Value {
+    Atom(Atom),
+    Integer(BigInt),
+    Float(f64),
+    /// A string literal, which is a list of its characters
+    String(String),
+    Nil,
+    Cons(Box<Value>, Box<Value>),
+    Tuple(Vec<Value>),
+}
+
+impl Value {
+    pub fn to_term(&self, process: &Process) -> Result<Term, Alloc> {
+        match self {
+            Value::Atom(atom) => Ok(unsafe { atom.as_term() }),
+            Value::Integer(integer) => process.integer(integer.clone()),
+            Value::Float(float) => process.float(*float),
+            Value::String(string) => process.charlist_from_str(string),
+            Value::Nil => Ok(Term::NIL),
+            Value::Cons(head, tail) => {
+                let head = head.to_term(process)?;
+                let tail = tail.to_term(process)?;
+
+                process.cons(head, tail)
+            }
+            Value::Tuple(elements) => {
+                let mut terms = Vec::with_capacity(elements.len());
+
+                for element in elements {
+                    terms.push(element.to_term(process)?);
+                }
+
+                process.tuple_from_slice(&terms)
+            }
+        }
+    }
+}
+
+/// What lowering drops from a module, which is kept from when it is compiled until it is loaded
+#[derive(Clone, Debug, Default)]
+pub struct Compiled {
+    /// The attributes, in the order they appear in the source
+    pub attributes: Vec<Attribute>,
+    /// The exported functions, sorted by name and arity, or `None` if all of the functions are
+    /// exported, as with `-compile(export_all)`
+    pub exports: Option<Vec<(Atom, usize)>>,
+}
+
+/// What lowering will drop from the `parsed` module, or the `-name(value)` of each attribute
+/// whose value isn't a term
+pub fn from_ast(parsed: &ErlAstModule) -> Result<Compiled, Vec<String>> {
+    let mut positioned = Vec::new();
+    let mut errors = Vec::new();
+
+    let mut push = |position, name: &str, expr: &Expr| match value(expr) {
+        Some(value) => positioned.push((
+            position,
+            Attribute {
+                name: Atom::try_from_str(name).unwrap(),
+                value,
+            },
+        )),
+        None => errors.push(format!("-{}(...)", name)),
+    };
+
+    if let Some(ref vsn) = parsed.vsn {
+        push(vsn.span().start(), "vsn", vsn);
+    }
+
+    if let Some(ref author) = parsed.author {
+        push(author.span().start(), "author", author);
+    }
+
+    for (name, attribute) in &parsed.attributes {
+        push(attribute.span.start(), &name.as_str(), &attribute.value);
+    }
+
+    for behaviour in &parsed.behaviours {
+        positioned.push((
+            behaviour.span.start(),
+            Attribute {
+                name: Atom::try_from_str("behaviour").unwrap(),
+                value: Value::Atom(Atom::try_from_str(behaviour.as_str()).unwrap()),
+            },
+        ));
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    positioned.sort_by_key(|(position, _)| *position);
+
+    let export_all = parsed
+        .compile
+        .as_ref()
+        .map_or(false, |options| options.export_all);
+    let exports = if export_all {
+        None
+    } else {
+        let mut exports: Vec<(Atom, usize)> = parsed
+            .exports
+            .iter()
+            .map(|export| {
+                (
+                    Atom::try_from_str(export.function.as_str()).unwrap(),
+                    export.arity as usize,
+                )
+            })
+            .collect();
+        sort_functions(&mut exports);
+
+        Some(exports)
+    };
+
+    Ok(Compiled {
+        attributes: positioned
+            .into_iter()
+            .map(|(_, attribute)| attribute)
+            .collect(),
+        exports,
+    })
+}
+
+/// Keeps what lowering dropped from the module named `module` until it is loaded
+pub fn put_compiled(module: Atom, compiled: Compiled) {
+    COMPILED.lock().unwrap().insert(module, compiled);
+}
+
+/// What lowering dropped from `module` when it was last compiled, which is no longer kept after
+/// this.  Modules that were lowered without `crate::compile::compile_str` have no attributes and
+/// export all of their functions.
+pub fn take_compiled(module: Atom) -> Compiled {
+    COMPILED.lock().unwrap().remove(&module).unwrap_or_default()
+}
+
+/// Sorts `functions` by name and arity, as `module_info` returns them
+pub fn sort_functions(functions: &mut Vec<(Atom, usize)>) {
+    functions.sort_by(|(left_name, left_arity), (right_name, right_arity)| {
+        left_name
+            .name()
+            .cmp(right_name.name())
+            .then(left_arity.cmp(right_arity))
+    });
+}
+
+/// `Module:module_info/0,1` for Erlang modules, which don't define it themselves, as it isn't part
+/// of their source.  Returns `None` if `module` isn't a loaded Erlang module.
+pub(crate) fn module_info(
+    process: &Arc<Process>,
+    registry: &ModuleRegistry,
+    module: Atom,
+    args: &[Term],
+) -> Option<Result<Term, Exception>> {
+    let info = Info {
+        module,
+        attributes: registry.attributes(module)?,
+        exports: registry.exports(module)?,
+        functions: registry.functions(module)?,
+    };
+
+    Some(match args {
+        [] => {
+            let mut entries = Vec::new();
+
+            for item in &["module", "exports", "attributes", "compile", "native"] {
+                let key = atom_unchecked(item);
+                let value = match info.item(process, key) {
+                    Ok(value) => value,
+                    Err(exception) => return Some(Err(exception)),
+                };
+
+                match process.tuple_from_slice(&[key, value]) {
+                    Ok(entry) => entries.push(entry),
+                    Err(alloc) => return Some(Err(alloc.into())),
+                }
+            }
+
+            process
+                .list_from_slice(&entries)
+                .map_err(|alloc| alloc.into())
+        }
+        [key] => info.item(process, *key),
+        _ => Err(badarg!().into()),
+    })
+}
+
+// Private
+
+struct Info<'a> {
+    module: Atom,
+    attributes: &'a [Attribute],
+    exports: Vec<(Atom, usize)>,
+    functions: Vec<(Atom, usize)>,
+}
+
+impl<'a> Info<'a> {
+    fn item(&self, process: &Process, key: Term) -> Result<Term, Exception> {
+        let key_atom: Atom = key.try_into()?;
+
+        match key_atom.name() {
+            "module" => Ok(unsafe { self.module.as_term() }),
+            "attributes" => {
+                let mut entries = Vec::with_capacity(self.attributes.len());
+
+                for attribute in self.attributes {
+                    let value = attribute.value.to_term(process)?;
+                    // values that aren't lists are wrapped in one, as on the BEAM
+                    let values = if value.is_list() {
+                        value
+                    } else {
+                        process.list_from_slice(&[value])?
+                    };
+
+                    entries.push(
+                        process.tuple_from_slice(&[unsafe { attribute.name.as_term() }, values])?,
+                    );
+                }
+
+                Ok(process.list_from_slice(&entries)?)
+            }
+            "exports" => functions_to_term(process, &self.exports),
+            "functions" => functions_to_term(process, &self.functions),
+            "compile" => Ok(Term::NIL),
+            "native" => Ok(atom_unchecked("false")),
+            _ => Err(badarg!().into()),
+        }
+    }
+}
+
+/// `[{Function, Arity}]` for `functions` and `module_info/0,1`, which every module exports
+fn functions_to_term(process: &Process, functions: &[(Atom, usize)]) -> Result<Term, Exception> {
+    let module_info = Atom::try_from_str("module_info").unwrap();
+    let mut all = functions.to_vec();
+    all.push((module_info, 0));
+    all.push((module_info, 1));
+    sort_functions(&mut all);
+
+    let mut entries = Vec::with_capacity(all.len());
+
+    for (function, arity) in all {
+        entries.push(
+            process.tuple_from_slice(&[unsafe { function.as_term() }, process.integer(arity)?])?,
+        );
+    }
+
+    Ok(process.list_from_slice(&entries)?)
+}
+
+/// The value of an attribute, or `None` if `expr` isn't a term
+fn value(expr: &Expr) -> Option<Value> {
+    match expr {
+        Expr::Literal(literal) => Some(match literal {
+            Literal::Atom(ident) => Value::Atom(Atom::try_from_str(ident.as_str()).ok()?),
+            Literal::String(ident) => Value::String(ident.as_str().to_string()),
+            Literal::Char(_, c) => Value::Integer((*c as u32).into()),
+            Literal::Integer(_, integer) => Value::Integer((*integer).into()),
+            Literal::BigInteger(_, integer) => Value::Integer(integer.clone()),
+            Literal::Float(_, float) => Value::Float(*float),
+        }),
+        Expr::Nil(_) => Some(Value::Nil),
+        Expr::Cons(cons) => Some(Value::Cons(
+            Box::new(value(&cons.head)?),
+            Box::new(value(&cons.tail)?),
+        )),
+        Expr::Tuple(tuple) => Some(Value::Tuple(
+            tuple.elements.iter().map(value).collect::<Option<_>>()?,
+        )),
+        // the sign of a negative number is an operator, but the number is still a term
+        Expr::UnaryExpr(unary) => match (&unary.op, value(&unary.operand)?) {
+            (UnaryOp::Minus, Value::Integer(integer)) => Some(Value::Integer(-integer)),
+            (UnaryOp::Minus, Value::Float(float)) => Some(Value::Float(-float)),
+            (UnaryOp::Plus, number @ Value::Integer(_))
+            | (UnaryOp::Plus, number @ Value::Float(_)) => Some(number),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+lazy_static! {
+    static ref COMPILED: Mutex<HashMap<Atom, Compiled>> = Default::default();
+}
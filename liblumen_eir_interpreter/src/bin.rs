@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use clap::{App, Arg};
 
@@ -17,6 +17,7 @@ use liblumen_eir_interpreter::VM;
 
 use liblumen_alloc::erts::term::Atom;
 
+use lumen_runtime::node::{self, NameKind};
 use lumen_runtime::scheduler::Scheduler;
 
 fn parse_file<T, P>(path: P, config: ParseConfig) -> (T, Parser)
@@ -65,10 +66,33 @@ fn main() {
             Arg::from_usage("<FUN_IDENT> -i,--ident <IDENT> 'select single function'")
                 .required(true),
         )
+        .arg(
+            Arg::from_usage(
+                "[CODE_PATH] -p,--path <DIR>... 'directories to load missing modules from'",
+            )
+            .required(false),
+        )
+        .arg(
+            Arg::from_usage("[NAME] --name <NAME> 'start the node with a long name'")
+                .conflicts_with("SNAME"),
+        )
+        .arg(Arg::from_usage(
+            "[SNAME] --sname <NAME> 'start the node with a short name'",
+        ))
         .get_matches();
 
     let ident = FunctionIdent::parse(matches.value_of("FUN_IDENT").unwrap()).unwrap();
 
+    let node_name = match (matches.value_of("NAME"), matches.value_of("SNAME")) {
+        (Some(name), _) => Some((name, NameKind::Long)),
+        (None, Some(name)) => Some((name, NameKind::Short)),
+        (None, None) => None,
+    };
+
+    if let Some((name, kind)) = node_name {
+        node::start(name, kind).expect("invalid node name");
+    }
+
     &*VM;
 
     let arc_scheduler = Scheduler::current();
@@ -78,6 +102,13 @@ fn main() {
     let function = Atom::try_from_str(&ident.name.as_str()).unwrap();
     assert!(ident.arity == 0);
 
+    if let Some(directories) = matches.values_of("CODE_PATH") {
+        VM.code_path
+            .write()
+            .unwrap()
+            .extend(directories.map(PathBuf::from));
+    }
+
     for file in matches.values_of("LOAD_ERL_FILES").unwrap() {
         let config = ParseConfig::default();
         let mut eir_mod = lower_file(file, config).unwrap();
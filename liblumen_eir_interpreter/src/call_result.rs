@@ -3,6 +3,8 @@ use core::ptr::NonNull;
 use std::convert::TryInto;
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
 use liblumen_alloc::borrow::clone_to_process::CloneToProcess;
 use liblumen_alloc::erts::exception::runtime;
@@ -38,6 +40,9 @@ struct ProcessResultSender {
     tx: Sender<ProcessResult>,
 }
 
+/// Calls `module:function(args)` and runs the current scheduler until the call returns, blocking the
+/// thread.  In the browser, where blocking would freeze the page, use `call_erlang` instead and
+/// check `ProcessResultReceiver::try_get` while `lumen_web::start` runs the scheduler in slices.
 pub fn call_run_erlang(
     proc: Arc<Process>,
     module: Atom,
@@ -60,6 +65,9 @@ pub fn call_run_erlang(
                         "WAITING Run queues len = {:?}",
                         Scheduler::current().run_queues_len()
                     ));
+                } else if is_waiting_for_dirty() {
+                    // a dirty native function will wake a process when it returns
+                    thread::sleep(Duration::from_millis(1));
                 } else {
                     panic!(
                         "{:?} did not run.  Deadlock likely in {:#?}",
@@ -84,6 +92,16 @@ pub fn call_run_erlang(
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+fn is_waiting_for_dirty() -> bool {
+    crate::dirty::is_waiting()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn is_waiting_for_dirty() -> bool {
+    false
+}
+
 pub fn call_erlang(
     proc: Arc<Process>,
     module: Atom,
@@ -217,6 +235,7 @@ fn return_throw(arc_process: &Arc<Process>) -> code::Result {
         class,
         reason: argument_vec[1],
         stacktrace: Some(argument_vec[2]),
+        error_info: None,
         file: "",
         line: 0,
         column: 0,
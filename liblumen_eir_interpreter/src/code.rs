@@ -68,6 +68,7 @@ pub fn return_throw(arc_process: &Arc<Process>) -> Result {
         class,
         reason: argument_vec[1],
         stacktrace: Some(argument_vec[2]),
+        error_info: None,
         file: "",
         line: 0,
         column: 0,
@@ -110,10 +111,52 @@ pub fn interpreter_mfa_code(arc_process: &Arc<Process>) -> Result {
     Ok(())
 }
 
+/// Expects the following on stack:
+/// * argument list
+/// * closure with the module version integer as its environment
+pub fn interpreter_local_mfa_code(arc_process: &Arc<Process>) -> Result {
+    let argument_list = arc_process.stack_pop().unwrap();
+    let closure_term = arc_process.stack_pop().unwrap();
+
+    let closure: Boxed<Closure> = closure_term.try_into().unwrap();
+
+    let mfa = arc_process.current_module_function_arity().unwrap();
+
+    let version: usize = closure.env_slice()[0].try_into().unwrap();
+
+    let mut argument_vec: Vec<Term> = Vec::new();
+    match argument_list.to_typed_term().unwrap() {
+        TypedTerm::Nil => (),
+        TypedTerm::List(argument_cons) => {
+            for result in argument_cons.into_iter() {
+                let element = result.unwrap();
+
+                argument_vec.push(element);
+            }
+        }
+        _ => panic!(),
+    }
+    assert!(mfa.arity as usize == argument_vec.len() - 2);
+
+    let mut exec = CallExecutor::new();
+    exec.call_local(
+        &crate::VM,
+        arc_process,
+        mfa.module,
+        mfa.function,
+        argument_vec.len() - 2,
+        version,
+        &mut argument_vec,
+    );
+
+    Ok(())
+}
+
 /// Expects the following on stack:
 /// * arity integer
 /// * argument list
 /// * block id integer
+/// * module version integer
 /// * environment list
 pub fn interpreter_closure_code(arc_process: &Arc<Process>) -> Result {
     let argument_list = arc_process.stack_pop().unwrap();
@@ -126,6 +169,7 @@ pub fn interpreter_closure_code(arc_process: &Arc<Process>) -> Result {
 
     let block_id: usize = closure.env_slice()[0].try_into().unwrap();
     let block = Block::new(block_id);
+    let version: usize = closure.env_slice()[1].try_into().unwrap();
 
     let mut argument_vec: Vec<Term> = Vec::new();
     match argument_list.to_typed_term().unwrap() {
@@ -140,7 +184,7 @@ pub fn interpreter_closure_code(arc_process: &Arc<Process>) -> Result {
         _ => panic!(),
     }
 
-    let mut environment_vec: Vec<Term> = closure.env_slice()[1..].to_owned();
+    let mut environment_vec: Vec<Term> = closure.env_slice()[2..].to_owned();
 
     let mut exec = CallExecutor::new();
     exec.call_block(
@@ -149,6 +193,7 @@ pub fn interpreter_closure_code(arc_process: &Arc<Process>) -> Result {
         mfa.module,
         mfa.function,
         arity as usize,
+        version,
         &mut argument_vec,
         block,
         &mut environment_vec,
@@ -185,3 +230,51 @@ pub fn apply(arc_process: &Arc<Process>) -> Result {
 
     Process::call_code(arc_process)
 }
+
+/// `erlang:hibernate/3`.  The continuations of the caller are dropped, so nothing it referenced
+/// stays live, and the heap is shrunk to what `module:function(arguments...)` needs before the
+/// process waits for a message.  Once there is one, the call is applied and, as in a spawned
+/// process, the process exits when it returns.
+pub fn hibernate(
+    arc_process: &Arc<Process>,
+    module: Term,
+    function: Term,
+    arguments: Term,
+) -> Result {
+    let ret = {
+        let mfa = ModuleFunctionArity {
+            module: Atom::try_from_str("lumen_eir_interpreter_intrinsics").unwrap(),
+            function: Atom::try_from_str("return_clean").unwrap(),
+            arity: 1,
+        };
+        arc_process.closure_with_env_from_slice(
+            mfa.into(),
+            return_clean,
+            arc_process.pid_term(),
+            &[],
+        )?
+    };
+
+    let mut roots = [ret, module, function, arguments];
+    // If the heap can't be collected, the process still hibernates, only with a larger heap
+    let _ = arc_process.shrink_heap(&mut roots);
+    let [ret, module, function, arguments] = roots;
+
+    let inner_args = arc_process.cons(ret, arc_process.cons(ret, arguments)?)?;
+    arc_process.stack_push(inner_args)?;
+    arc_process.stack_push(function)?;
+    arc_process.stack_push(module)?;
+
+    let module_function_arity = Arc::new(ModuleFunctionArity {
+        module: Atom::try_from_str("erlang").unwrap(),
+        function: Atom::try_from_str("hibernate").unwrap(),
+        arity: 3,
+    });
+    arc_process.replace_frame(Frame::new(module_function_arity, apply));
+
+    if arc_process.wait_for_message() {
+        Ok(())
+    } else {
+        apply(arc_process)
+    }
+}
@@ -0,0 +1,69 @@
+//! Resolves module names to BEAM, Erlang or Core Erlang source files on the code path and loads
+//! them into the `ModuleRegistry` on demand.
+
+use std::fs::File;
+use std::path::PathBuf;
+
+use liblumen_alloc::erts::term::Atom;
+
+use crate::compile::{compile_beam, compile_core, compile_str};
+use crate::module::LoadError;
+use crate::vm::VMState;
+
+/// Loads `module` from the first `<module>.beam`, `<module>.erl` or `<module>.core` file on the
+/// code path, unless it is already loaded.
+pub fn ensure_loaded(vm: &VMState, module: Atom) -> Result<(), LoadError> {
+    if vm.modules.read().unwrap().is_loaded(module) {
+        return Ok(());
+    }
+
+    let path = which(vm, module).ok_or(LoadError::NoFile)?;
+    let eir_module = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("beam") => {
+            let file = File::open(&path).map_err(|_| LoadError::NoFile)?;
+            compile_beam(file).map_err(|_| LoadError::BadFile)?
+        }
+        Some("core") => {
+            let source = std::fs::read_to_string(&path).map_err(|_| LoadError::NoFile)?;
+            compile_core(&source).map_err(|_| LoadError::BadFile)?
+        }
+        _ => {
+            let source = std::fs::read_to_string(&path).map_err(|_| LoadError::NoFile)?;
+            compile_str(&source).map_err(|_| LoadError::BadFile)?
+        }
+    };
+
+    if Atom::try_from_str(eir_module.name.as_str()).unwrap() != module {
+        return Err(LoadError::BadFile);
+    }
+
+    let mut modules = vm.modules.write().unwrap();
+
+    // Another process may have loaded the module while this one was compiling it
+    if modules.is_loaded(module) {
+        Ok(())
+    } else {
+        modules.load_erlang_module(eir_module).map(|_| ())
+    }
+}
+
+/// The first `<module>.beam`, `<module>.erl` or `<module>.core` file on the code path.  Within a
+/// directory, the BEAM file is preferred, then the Erlang source.
+pub fn which(vm: &VMState, module: Atom) -> Option<PathBuf> {
+    let beam_file_name = format!("{}.beam", module.name());
+    let erl_file_name = format!("{}.erl", module.name());
+    let core_file_name = format!("{}.core", module.name());
+
+    vm.code_path
+        .read()
+        .unwrap()
+        .iter()
+        .flat_map(|directory| {
+            vec![
+                directory.join(&beam_file_name),
+                directory.join(&erl_file_name),
+                directory.join(&core_file_name),
+            ]
+        })
+        .find(|path| path.is_file())
+}
@@ -0,0 +1,123 @@
+//! Compiles Erlang source, Core Erlang source, abstract format forms and BEAM files into EIR
+//! modules that can be loaded into the `ModuleRegistry`.
+
+use std::io::Read;
+
+use libeir_diagnostics::{ColorChoice, Emitter, StandardStreamEmitter};
+
+use libeir_ir::Module;
+
+use libeir_passes::PassManager;
+
+use libeir_syntax_erl::ast::Module as ErlAstModule;
+use libeir_syntax_erl::lower_module;
+use libeir_syntax_erl::{ParseConfig, Parser};
+
+use liblumen_alloc::erts::term::Atom;
+
+use liblumen_beam::serialization::etf;
+use liblumen_beam::syntax::ast::AST;
+use liblumen_beam::syntax::core_erlang::{self, CoreError};
+use liblumen_beam::FromBeamError;
+
+/// Why a BEAM file couldn't be compiled
+#[derive(Debug)]
+pub enum BeamCompileError {
+    /// The BEAM file couldn't be read or has no abstract code, such as when it was compiled
+    /// without `debug_info`
+    Beam(FromBeamError),
+    /// The Erlang source printed from the abstract code couldn't be compiled, as emitted in its
+    /// diagnostics
+    Erlang,
+}
+
+/// Why Core Erlang source couldn't be compiled
+#[derive(Debug)]
+pub enum CoreCompileError {
+    /// The Core Erlang couldn't be parsed or translated to Erlang source
+    Core(CoreError),
+    /// The translated Erlang source couldn't be compiled, as emitted in its diagnostics
+    Erlang,
+}
+
+/// Parses, lowers and runs the default passes on the Erlang `source` of a module.  Diagnostics
+/// are emitted to stderr.
+pub fn compile_str(source: &str) -> Result<Module, ()> {
+    let parser = Parser::new(ParseConfig::default());
+    let emitter =
+        StandardStreamEmitter::new(ColorChoice::Auto).set_codemap(parser.config.codemap.clone());
+
+    let parsed: ErlAstModule = match parser.parse_string::<&str, ErlAstModule>(source) {
+        Ok(ast) => ast,
+        Err(errs) => {
+            for err in errs.iter() {
+                emitter.diagnostic(&err.to_diagnostic()).unwrap();
+            }
+
+            return Err(());
+        }
+    };
+
+    // lowering drops attributes and exports, so they are kept until the module is loaded
+    let compiled = match crate::attributes::from_ast(&parsed) {
+        Ok(compiled) => compiled,
+        Err(attributes) => {
+            for attribute in attributes {
+                eprintln!(
+                    "error: bad attribute {}: its value is not a term",
+                    attribute
+                );
+            }
+
+            return Err(());
+        }
+    };
+
+    let (res, messages) = lower_module(&parsed);
+
+    for err in messages.iter() {
+        emitter.diagnostic(&err.to_diagnostic()).unwrap();
+    }
+
+    let mut eir_mod = res?;
+
+    crate::attributes::put_compiled(Atom::try_from_str(eir_mod.name.as_str()).unwrap(), compiled);
+
+    for fun in eir_mod.functions.values() {
+        fun.graph_validate_global();
+    }
+
+    let mut pass_manager = PassManager::default();
+    pass_manager.run(&mut eir_mod);
+
+    Ok(eir_mod)
+}
+
+/// Compiles a BEAM file from the abstract code in its `debug_info` chunk, not from its BEAM code:
+/// the abstract code is printed back out as Erlang source, which is compiled with `compile_str`.
+/// BEAM files compiled without `debug_info` can't be loaded, and type declarations and specs
+/// aren't kept.
+pub fn compile_beam<R: Read>(reader: R) -> Result<Module, BeamCompileError> {
+    let ast = AST::from_beam_reader(reader).map_err(BeamCompileError::Beam)?;
+
+    compile_str(&ast.module.to_string()).map_err(|()| BeamCompileError::Erlang)
+}
+
+/// Compiles a list of abstract format `forms`, such as those returned by `epp:parse_file/2` or
+/// generated by tools.  Like `compile_beam`, the forms are printed back out as Erlang source, which
+/// is compiled with `compile_str`.
+pub fn compile_forms(forms: etf::Term) -> Result<Module, ()> {
+    let ast = AST::from_forms(forms).map_err(|_| ())?;
+
+    compile_str(&ast.module.to_string())
+}
+
+/// Compiles the Core Erlang `source` of a module, such as the output of `erlc +to_core` or of
+/// other compilers that target Core Erlang, by translating it to Erlang source.
+pub fn compile_core(source: &str) -> Result<Module, CoreCompileError> {
+    let erlang_source = core_erlang::parse_module(source)
+        .and_then(|module| module.to_erlang())
+        .map_err(CoreCompileError::Core)?;
+
+    compile_str(&erlang_source).map_err(|()| CoreCompileError::Erlang)
+}
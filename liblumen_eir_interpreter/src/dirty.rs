@@ -0,0 +1,164 @@
+//! Running dirty native functions on the dirty CPU schedulers of
+//! `lumen_runtime::scheduler::dirty`.
+//!
+//! A dirty native function gets its arguments and allocates its result on the heap of the calling
+//! process, as any other native function does.  While it runs, the process waits with the
+//! arguments, including the continuations, on its stack and `code` as its frame.  If a message
+//! wakes the process before the function returns, `code` only waits again, so the heap is only
+//! used by the dirty scheduler.  Once the function returns, the process is woken and `code`
+//! continues the call with the result.
+//!
+//! Collections of the process are disabled until the function returns, as the arguments it was
+//! passed aren't roots, so they would be left pointing at the heap from before the collection.
+
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::sync::{Arc, Mutex};
+
+use lazy_static::lazy_static;
+
+use liblumen_alloc::erts::exception::Exception;
+use liblumen_alloc::erts::process::code::stack::frame::Frame;
+use liblumen_alloc::erts::process::code::Result;
+use liblumen_alloc::erts::process::{Process, ProcessFlags};
+use liblumen_alloc::erts::term::{Atom, Pid, Term, TypedTerm};
+use liblumen_alloc::erts::ModuleFunctionArity;
+
+use lumen_runtime::process::wake;
+use lumen_runtime::scheduler::dirty;
+
+use crate::module::DirtyFunction;
+
+/// Runs `fun` on a dirty scheduler with the arguments after the return and throw
+/// continuations in `args`.
+pub fn dispatch(
+    proc: &Arc<Process>,
+    module: Atom,
+    function: Atom,
+    fun: DirtyFunction,
+    args: &[Term],
+) -> Result {
+    let argument_list = proc.list_from_slice(args)?;
+    proc.stack_push(argument_list)?;
+
+    let module_function_arity = Arc::new(ModuleFunctionArity {
+        module,
+        function,
+        arity: (args.len() - 2).try_into().unwrap(),
+    });
+    proc.replace_frame(Frame::new(module_function_arity, code));
+
+    let disabled_gc = !proc.are_flags_set(ProcessFlags::DisableGC);
+
+    if disabled_gc {
+        proc.set_flags(ProcessFlags::DisableGC);
+    }
+
+    CALLS.lock().unwrap().insert(
+        proc.pid(),
+        Call {
+            fun,
+            disabled_gc,
+            option_result: None,
+        },
+    );
+    proc.wait();
+
+    let arc_process = Arc::clone(proc);
+    let arguments = args[2..].to_vec();
+
+    dirty::spawn(Box::new(move || {
+        let result = fun(&arc_process, &arguments);
+        let mut calls = CALLS.lock().unwrap();
+
+        if arc_process.is_exiting() {
+            calls.remove(&arc_process.pid());
+        } else if let Some(call) = calls.get_mut(&arc_process.pid()) {
+            call.option_result = Some(result);
+            std::mem::drop(calls);
+
+            wake(&arc_process);
+        }
+    }));
+
+    Ok(())
+}
+
+/// Whether any process is waiting for a dirty native function, and so will be woken when it
+/// returns
+pub fn is_waiting() -> bool {
+    !CALLS.lock().unwrap().is_empty()
+}
+
+// Private
+
+struct Call {
+    fun: DirtyFunction,
+    /// Whether `dispatch` disabled collections of the process, and so `code` should enable them
+    /// again once the function returns
+    disabled_gc: bool,
+    option_result: Option<std::result::Result<Term, Exception>>,
+}
+
+/// Expects the following on stack:
+/// * argument list, including the return and throw continuations
+fn code(arc_process: &Arc<Process>) -> Result {
+    let pid = arc_process.pid();
+    let option_call = {
+        let mut calls = CALLS.lock().unwrap();
+
+        let returned = calls
+            .get(&pid)
+            .map_or(false, |call| call.option_result.is_some());
+
+        if returned {
+            calls.remove(&pid)
+        } else {
+            // woken by a message before the function returned.  The process waits again while
+            // `CALLS` is still locked, so that it is already waiting when the function returns
+            // and wakes it.
+            arc_process.wait();
+
+            None
+        }
+    };
+
+    match option_call {
+        Some(Call {
+            fun,
+            disabled_gc,
+            option_result: Some(result),
+        }) => {
+            if disabled_gc {
+                arc_process.clear_flags(ProcessFlags::DisableGC);
+            }
+
+            let argument_list = arc_process.stack_pop().unwrap();
+            let mfa = arc_process.current_module_function_arity().unwrap();
+
+            let mut argument_vec: Vec<Term> = match argument_list.to_typed_term().unwrap() {
+                TypedTerm::List(argument_cons) => argument_cons
+                    .into_iter()
+                    .map(|result| result.unwrap())
+                    .collect(),
+                _ => unreachable!(),
+            };
+
+            crate::exec::resume_dirty(
+                arc_process,
+                mfa.module,
+                mfa.function,
+                fun,
+                result,
+                &mut argument_vec,
+            );
+
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+lazy_static! {
+    static ref CALLS: Mutex<HashMap<Pid, Call>> = Mutex::new(HashMap::new());
+}
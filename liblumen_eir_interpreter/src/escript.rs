@@ -0,0 +1,165 @@
+//! Runs escript-style scripts: a single file of Erlang source whose `main/1` is called with the
+//! command-line arguments as strings, as `escript` does.
+//!
+//! The first line may be a shebang, such as `#!/usr/bin/env run_escript`, so that the script can
+//! be run directly.  A script needs no `-module` or `-export` attributes: without `-module`, the
+//! module is named after the file and exports `main/1`.
+//!
+//! As with `escript`, the exit status is `0` when `main/1` returns and `127` when it raises.  There
+//! is no `halt/1` to choose another status, so `main/1` may instead return an integer from `0` to
+//! `255` to exit with.
+
+use std::fmt::{self, Display};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use liblumen_alloc::erts::process::{Process, Status};
+use liblumen_alloc::erts::term::{Atom, Term, TypedTerm};
+
+use lumen_runtime::scheduler::Scheduler;
+
+use crate::call_result::call_erlang;
+use crate::compile::compile_str;
+use crate::VM;
+
+/// The exit status when `main/1` raises or the script can't be run
+pub const FAILURE_STATUS: i32 = 127;
+
+#[derive(Debug)]
+pub enum ScriptError {
+    Io(io::Error),
+    /// The source did not compile.  The diagnostics have already been emitted to stderr.
+    Compile,
+}
+
+impl Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScriptError::Io(error) => write!(f, "{}", error),
+            ScriptError::Compile => write!(f, "there were compilation errors"),
+        }
+    }
+}
+
+impl From<io::Error> for ScriptError {
+    fn from(error: io::Error) -> Self {
+        ScriptError::Io(error)
+    }
+}
+
+/// Loads the script at `path` and calls its `main/1` with `arguments`, returning the exit status.
+pub fn run_script(
+    parent: &Arc<Process>,
+    path: &Path,
+    arguments: &[String],
+) -> Result<i32, ScriptError> {
+    let source = fs::read_to_string(path)?;
+    let default_module = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "escript".to_string());
+
+    run_source(parent, &default_module, &source, arguments)
+}
+
+/// Loads the script `source`, naming its module `default_module` if it has no `-module`
+/// attribute, and calls its `main/1` with `arguments`, returning the exit status.
+pub fn run_source(
+    parent: &Arc<Process>,
+    default_module: &str,
+    source: &str,
+    arguments: &[String],
+) -> Result<i32, ScriptError> {
+    let module =
+        compile_str(&module_source(default_module, source)).map_err(|_| ScriptError::Compile)?;
+    let module_atom = Atom::try_from_str(module.name.as_str()).unwrap();
+
+    VM.modules.write().unwrap().register_erlang_module(module);
+
+    let argument_vec: Vec<Term> = arguments
+        .iter()
+        .map(|argument| parent.charlist_from_str(argument).unwrap())
+        .collect();
+    let argument_list = parent.list_from_slice(&argument_vec).unwrap();
+
+    let receiver = call_erlang(
+        parent.clone(),
+        module_atom,
+        Atom::try_from_str("main").unwrap(),
+        &[argument_list],
+    );
+    let arc_process = receiver.process.clone();
+
+    // `main/1` may wait on other processes or on standard input, so unlike `call_run_erlang`
+    // waiting is not a deadlock
+    loop {
+        let ran = Scheduler::current().run_through(&arc_process);
+
+        if let Status::Exiting(ref exception) = *arc_process.status.read() {
+            return Ok(match receiver.try_get() {
+                Some(result) => match result.result {
+                    Ok(returned) => exit_status(returned),
+                    Err((class, reason, _)) => {
+                        eprintln!("escript: exception {}: {}", class, reason);
+
+                        FAILURE_STATUS
+                    }
+                },
+                None => {
+                    eprintln!("escript: exited with {}", exception.reason);
+
+                    FAILURE_STATUS
+                }
+            });
+        }
+
+        if !ran {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
+
+/// `source` without its shebang, and with `-module` and `-export` attributes if it has no
+/// `-module`.  The header replaces the shebang, so that line numbers are unchanged.
+fn module_source(default_module: &str, source: &str) -> String {
+    let body = if source.starts_with("#!") {
+        match source.find('\n') {
+            Some(index) => &source[index..],
+            None => "",
+        }
+    } else {
+        source
+    };
+
+    let has_module = body
+        .lines()
+        .any(|line| line.trim_start().starts_with("-module"));
+
+    if has_module {
+        body.to_string()
+    } else {
+        let quoted = default_module.replace('\\', "\\\\").replace('\'', "\\'");
+
+        format!("-module('{}'). -export([main/1]).{}", quoted, body)
+    }
+}
+
+/// The status `main/1` exits with when it returns `returned`
+fn exit_status(returned: Term) -> i32 {
+    match returned.to_typed_term().unwrap() {
+        TypedTerm::SmallInteger(small_integer) => {
+            let status: isize = small_integer.into();
+
+            if 0 <= status && status <= 255 {
+                status as i32
+            } else {
+                0
+            }
+        }
+        _ => 0,
+    }
+}
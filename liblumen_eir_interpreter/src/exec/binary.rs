@@ -0,0 +1,480 @@
+//! Binary matching
+//!
+//! As in BEAM, a binary being matched is held in a match context, which keeps the position of the
+//! next segment to match.  When the term that a segment is matched from is a match context that
+//! isn't needed once the segment matched, the context is moved past the segment in place and
+//! passed on as the rest of the binary, so a loop like `parse(<<X, Rest/binary>>) -> parse(Rest)`
+//! only allocates the context for its first iteration, instead of a subbinary for each.
+//!
+//! Match contexts are only passed as they are to blocks of the same function and to local calls,
+//! which can match them again.  Everywhere else, such as in natives, tuples, closures and
+//! messages, the rest of the binary is a subbinary (see `to_bitstring`).
+
+use std::convert::TryInto;
+
+use hashbrown::HashMap;
+use num_bigint::BigInt;
+
+use libeir_ir::{BinaryEntrySpecifier, Endianness, Value, ValueKind};
+
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{MatchBuffer, MatchContext, Term, TypedTerm};
+
+use crate::module::ErlangFunction;
+
+/// Matches the segment `specifier` of `size` at the start of `subject`, returning the value of the
+/// segment and the rest of `subject`, or `None` if `subject` is not a bitstring or doesn't start
+/// with such a segment.  `branch` is where the match continues if it succeeds.
+pub fn match_segment(
+    proc: &Process,
+    fun: &ErlangFunction,
+    binds: &HashMap<Value, Term>,
+    subject: Term,
+    specifier: &BinaryEntrySpecifier,
+    size: Option<Term>,
+    branch: Value,
+) -> Result<Option<(Term, Term)>, Alloc> {
+    let buffer = match self::buffer(subject) {
+        Some(buffer) => buffer,
+        None => return Ok(None),
+    };
+
+    let size: Option<usize> = match size {
+        Some(size_term) => match size_term.try_into() {
+            Ok(size) => Some(size),
+            Err(_) => return Ok(None),
+        },
+        None => None,
+    };
+
+    let (option_value, bit_len) = match specifier {
+        BinaryEntrySpecifier::Integer {
+            signed,
+            endianness,
+            unit,
+        } => {
+            let bit_len = size.unwrap_or(8) * (*unit as usize);
+
+            (
+                integer(proc, &buffer, bit_len, *signed, *endianness)?,
+                bit_len,
+            )
+        }
+        BinaryEntrySpecifier::Float { endianness, unit } => {
+            let bit_len = size.unwrap_or(64) * (*unit as usize);
+
+            (float(proc, &buffer, bit_len, *endianness)?, bit_len)
+        }
+        BinaryEntrySpecifier::Bytes { unit } | BinaryEntrySpecifier::Bits { unit } => {
+            let is_bytes = match specifier {
+                BinaryEntrySpecifier::Bytes { .. } => true,
+                _ => false,
+            };
+            let unit = *unit as usize;
+
+            match size {
+                Some(size) => {
+                    let bit_len = size * unit;
+
+                    if (is_bytes && bit_len % 8 != 0) || buffer.remaining_bit_len() < bit_len {
+                        return Ok(None);
+                    }
+
+                    (Some(subbinary(proc, &buffer, bit_len)?), bit_len)
+                }
+                // The rest of the binary, which isn't moved past, so that the rest of the match is
+                // empty, but the value can be matched further from the same position
+                None => {
+                    let bit_len = buffer.remaining_bit_len();
+
+                    if (is_bytes && bit_len % 8 != 0) || (unit > 0 && bit_len % unit != 0) {
+                        return Ok(None);
+                    }
+
+                    let value =
+                        if is_match_context(subject) && !is_needed(fun, binds, subject, branch) {
+                            subject
+                        } else {
+                            proc.match_context_from_buffer(buffer)?
+                        };
+                    let rest = proc.binary_from_bytes(&[])?;
+
+                    return Ok(Some((value, rest)));
+                }
+            }
+        }
+        BinaryEntrySpecifier::Utf8 => match utf8(&buffer) {
+            Some((code_point, bit_len)) => (Some(proc.integer(code_point as usize)?), bit_len),
+            None => (None, 0),
+        },
+        BinaryEntrySpecifier::Utf16 { endianness } => match utf16(&buffer, *endianness) {
+            Some((code_point, bit_len)) => (Some(proc.integer(code_point as usize)?), bit_len),
+            None => (None, 0),
+        },
+        BinaryEntrySpecifier::Utf32 { endianness } => match utf32(&buffer, *endianness) {
+            Some(code_point) => (Some(proc.integer(code_point as usize)?), 32),
+            None => (None, 0),
+        },
+    };
+
+    match option_value {
+        Some(value) => {
+            // Everything that can fail to allocate is done before the context is moved in place,
+            // as the match is run again from the start after garbage collection.
+            let rest = if is_match_context(subject) && !is_needed(fun, binds, subject, branch) {
+                match_context(subject).buffer_mut().skip(bit_len);
+
+                subject
+            } else {
+                let mut rest_buffer = buffer;
+                rest_buffer.skip(bit_len);
+
+                proc.match_context_from_buffer(rest_buffer)?
+            };
+
+            Ok(Some((value, rest)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Whether `subject`, which may be a match context, is exactly equal to `other`, which isn't.
+/// Comparing a match context doesn't allocate, so that checking for the end of a binary in a loop,
+/// as in `parse(<<>>) -> done`, keeps its context.
+pub fn exactly_eq(subject: Term, other: Term) -> bool {
+    if is_match_context(subject) {
+        let subject_buffer = match_context(subject).buffer();
+
+        match buffer(other) {
+            Some(other_buffer) => {
+                let bit_len = subject_buffer.remaining_bit_len();
+
+                bit_len == other_buffer.remaining_bit_len() && {
+                    let subject_bytes = subject_buffer.bytes();
+                    let other_bytes = other_buffer.bytes();
+
+                    (0..bit_len).all(|index| {
+                        bit(subject_bytes, subject_buffer.bit_offset() + index)
+                            == bit(other_bytes, other_buffer.bit_offset() + index)
+                    })
+                }
+            }
+            None => false,
+        }
+    } else {
+        subject.exactly_eq(&other)
+    }
+}
+
+pub fn is_match_context(term: Term) -> bool {
+    term.is_boxed() && unsafe { *term.boxed_val() }.is_match_context()
+}
+
+/// `term` as a subbinary if it is a match context, so that it can be used like any other term
+pub fn to_bitstring(proc: &Process, term: Term) -> Result<Term, Alloc> {
+    if is_match_context(term) {
+        let buffer = match_context(term).buffer();
+
+        subbinary(proc, buffer, buffer.remaining_bit_len())
+    } else {
+        Ok(term)
+    }
+}
+
+/// Replaces the match contexts in `terms` with subbinaries, before they are passed to code that
+/// isn't interpreted, such as natives and tracers
+pub fn to_bitstrings(proc: &Process, terms: &mut [Term]) -> Result<(), Alloc> {
+    for term in terms.iter_mut() {
+        *term = to_bitstring(proc, *term)?;
+    }
+
+    Ok(())
+}
+
+// Private
+
+fn bit(bytes: &[u8], bit_offset: usize) -> u8 {
+    (bytes[bit_offset >> 3] >> (7 - (bit_offset & 7))) & 1
+}
+
+/// The match buffer at the start of `term`, if it is a bitstring
+fn buffer(term: Term) -> Option<MatchBuffer> {
+    match term.to_typed_term().unwrap() {
+        TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+            TypedTerm::HeapBinary(_) | TypedTerm::ProcBin(_) | TypedTerm::SubBinary(_) => {
+                Some(MatchBuffer::start_match(term))
+            }
+            TypedTerm::MatchContext(match_context) => Some(*match_context.buffer()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn float(
+    proc: &Process,
+    buffer: &MatchBuffer,
+    bit_len: usize,
+    endianness: Endianness,
+) -> Result<Option<Term>, Alloc> {
+    if buffer.remaining_bit_len() < bit_len {
+        return Ok(None);
+    }
+
+    let bits = uint(buffer, bit_len, endianness);
+    let f = match bit_len {
+        32 => f32::from_bits(bits as u32) as f64,
+        64 => f64::from_bits(bits),
+        _ => return Ok(None),
+    };
+
+    if f.is_finite() {
+        proc.float(f).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+fn integer(
+    proc: &Process,
+    buffer: &MatchBuffer,
+    bit_len: usize,
+    signed: bool,
+    endianness: Endianness,
+) -> Result<Option<Term>, Alloc> {
+    if buffer.remaining_bit_len() < bit_len {
+        return Ok(None);
+    }
+
+    let term = if bit_len == 0 {
+        proc.integer(0)?
+    } else if bit_len <= 64 {
+        let unsigned = uint(buffer, bit_len, endianness);
+
+        if signed {
+            // Sign extends from the top bit of the segment
+            let shift = 64 - bit_len;
+
+            proc.integer(((unsigned << shift) as i64) >> shift)?
+        } else {
+            proc.integer(unsigned)?
+        }
+    } else {
+        let mut big_int = BigInt::from(0);
+
+        for (byte, byte_bit_len) in segment_bytes(buffer, bit_len, endianness) {
+            big_int = (big_int << byte_bit_len) | BigInt::from(byte);
+        }
+
+        if signed && (big_int.clone() >> (bit_len - 1)) == BigInt::from(1) {
+            big_int = big_int - (BigInt::from(1) << bit_len);
+        }
+
+        proc.integer(big_int)?
+    };
+
+    Ok(Some(term))
+}
+
+fn is_big_endian(endianness: Endianness) -> bool {
+    match endianness {
+        Endianness::Big => true,
+        Endianness::Little => false,
+        Endianness::Native => cfg!(target_endian = "big"),
+    }
+}
+
+/// Whether `subject` is bound to a value that is used after the match continues to `branch`, in
+/// which case the match context can't be moved in place
+fn is_needed(
+    fun: &ErlangFunction,
+    binds: &HashMap<Value, Term>,
+    subject: Term,
+    branch: Value,
+) -> bool {
+    match fun.fun.value_kind(branch) {
+        ValueKind::Block(block) => match fun.live.live.get(&block) {
+            Some(live) => live
+                .iter(&fun.live.pool)
+                .any(|value| match binds.get(&value) {
+                    Some(term) => term.as_usize() == subject.as_usize(),
+                    None => false,
+                }),
+            None => true,
+        },
+        _ => true,
+    }
+}
+
+/// The match context that `term` points to, which is changed in place
+fn match_context<'a>(term: Term) -> &'a mut MatchContext {
+    unsafe { &mut *(term.boxed_val() as *mut MatchContext) }
+}
+
+/// Reads `bit_len` bits from `bit_offset` in `bytes` as an unsigned big-endian integer
+fn read(bytes: &[u8], bit_offset: usize, bit_len: usize) -> u64 {
+    assert!(bit_len <= 64);
+
+    let mut value = 0;
+    let mut index = 0;
+
+    while index < bit_len {
+        let offset = bit_offset + index;
+
+        if offset & 7 == 0 && 8 <= bit_len - index {
+            value = (value << 8) | (bytes[offset >> 3] as u64);
+            index += 8;
+        } else {
+            value = (value << 1) | (bit(bytes, offset) as u64);
+            index += 1;
+        }
+    }
+
+    value
+}
+
+/// The bytes of the `bit_len` bits at the start of `buffer`, most significant first, each with its
+/// length in bits.  Only the last byte of the segment can be partial, which is the least
+/// significant byte of a big-endian segment and the most significant one of a little-endian
+/// segment.
+fn segment_bytes(buffer: &MatchBuffer, bit_len: usize, endianness: Endianness) -> Vec<(u8, usize)> {
+    let bytes = buffer.bytes();
+    let bit_offset = buffer.bit_offset();
+    let mut segment_bytes: Vec<(u8, usize)> = (0..bit_len)
+        .step_by(8)
+        .map(|index| {
+            let byte_bit_len = (bit_len - index).min(8);
+
+            (
+                read(bytes, bit_offset + index, byte_bit_len) as u8,
+                byte_bit_len,
+            )
+        })
+        .collect();
+
+    if !is_big_endian(endianness) {
+        segment_bytes.reverse();
+    }
+
+    segment_bytes
+}
+
+fn subbinary(proc: &Process, buffer: &MatchBuffer, bit_len: usize) -> Result<Term, Alloc> {
+    let bit_offset = buffer.bit_offset();
+
+    proc.subbinary_from_original(
+        buffer.original(),
+        bit_offset >> 3,
+        (bit_offset & 7) as u8,
+        bit_len >> 3,
+        (bit_len & 7) as u8,
+    )
+}
+
+/// The `bit_len`, at most 64, bits at the start of `buffer` as an unsigned integer
+fn uint(buffer: &MatchBuffer, bit_len: usize, endianness: Endianness) -> u64 {
+    if is_big_endian(endianness) {
+        read(buffer.bytes(), buffer.bit_offset(), bit_len)
+    } else {
+        segment_bytes(buffer, bit_len, endianness)
+            .into_iter()
+            .fold(0, |acc, (byte, byte_bit_len)| {
+                (acc << byte_bit_len) | (byte as u64)
+            })
+    }
+}
+
+/// The code point at the start of `buffer` and its length in bits
+fn utf8(buffer: &MatchBuffer) -> Option<(u32, usize)> {
+    let remaining_byte_len = buffer.remaining_bit_len() / 8;
+
+    if remaining_byte_len == 0 {
+        return None;
+    }
+
+    let byte = |index: usize| read(buffer.bytes(), buffer.bit_offset() + 8 * index, 8) as u32;
+    let first = byte(0);
+
+    let (byte_len, min_code_point, initial) = if first < 0x80 {
+        (1, 0, first)
+    } else if first & 0xE0 == 0xC0 {
+        (2, 0x80, first & 0x1F)
+    } else if first & 0xF0 == 0xE0 {
+        (3, 0x800, first & 0x0F)
+    } else if first & 0xF8 == 0xF0 {
+        (4, 0x1_0000, first & 0x07)
+    } else {
+        return None;
+    };
+
+    if remaining_byte_len < byte_len {
+        return None;
+    }
+
+    let mut code_point = initial;
+
+    for index in 1..byte_len {
+        let continuation = byte(index);
+
+        if continuation & 0xC0 != 0x80 {
+            return None;
+        }
+
+        code_point = (code_point << 6) | (continuation & 0x3F);
+    }
+
+    if min_code_point <= code_point && is_code_point(code_point) {
+        Some((code_point, 8 * byte_len))
+    } else {
+        None
+    }
+}
+
+fn utf16(buffer: &MatchBuffer, endianness: Endianness) -> Option<(u32, usize)> {
+    if buffer.remaining_bit_len() < 16 {
+        return None;
+    }
+
+    let first = uint(buffer, 16, endianness) as u32;
+
+    match first {
+        0xD800..=0xDBFF => {
+            if buffer.remaining_bit_len() < 32 {
+                return None;
+            }
+
+            let mut second_buffer = *buffer;
+            second_buffer.skip(16);
+            let second = uint(&second_buffer, 16, endianness) as u32;
+
+            match second {
+                0xDC00..=0xDFFF => Some((
+                    0x1_0000 + (((first - 0xD800) << 10) | (second - 0xDC00)),
+                    32,
+                )),
+                _ => None,
+            }
+        }
+        0xDC00..=0xDFFF => None,
+        _ => Some((first, 16)),
+    }
+}
+
+fn utf32(buffer: &MatchBuffer, endianness: Endianness) -> Option<u32> {
+    if buffer.remaining_bit_len() < 32 {
+        return None;
+    }
+
+    let code_point = uint(buffer, 32, endianness) as u32;
+
+    if is_code_point(code_point) {
+        Some(code_point)
+    } else {
+        None
+    }
+}
+
+fn is_code_point(code_point: u32) -> bool {
+    code_point <= 0x10_FFFF && !(0xD800 <= code_point && code_point <= 0xDFFF)
+}
@@ -6,7 +6,7 @@ use liblumen_alloc::erts::exception::system;
 use liblumen_alloc::erts::process::Process;
 use liblumen_alloc::erts::term::TypedTerm;
 
-use super::{CallExecutor, OpResult};
+use super::{binary, CallExecutor, OpResult};
 use crate::module::ErlangFunction;
 
 pub fn match_op(
@@ -22,7 +22,9 @@ pub fn match_op(
     assert!(fun.fun.primop_kind(branches_prim) == &PrimOpKind::ValueList);
     let branches_dests = fun.fun.primop_reads(branches_prim);
 
-    let unpack_term = exec.make_term(proc, fun, reads[1]).unwrap();
+    // A binary being matched is left as its match context, which only binary segments and values
+    // are matched against
+    let unpack_term = exec.make_match_term(proc, fun, reads[1])?;
 
     for (idx, (kind, branch)) in branches.iter().zip(branches_dests.iter()).enumerate() {
         let branch_arg_prim = fun.fun.value_primop(reads[idx + 2]).unwrap();
@@ -34,7 +36,7 @@ pub fn match_op(
                 assert!(branch_args.len() == 1);
                 let rhs = exec.make_term(proc, fun, branch_args[0]).unwrap();
 
-                if unpack_term.exactly_eq(&rhs) {
+                if binary::exactly_eq(unpack_term, rhs) {
                     return exec.val_call(proc, fun, *branch);
                 }
             }
@@ -89,6 +91,27 @@ pub fn match_op(
                     _ => (),
                 }
             }
+            MatchKind::Binary(specifier) => {
+                assert!(branch_args.len() <= 1);
+                let size = match branch_args.first() {
+                    Some(size) => Some(exec.make_term(proc, fun, *size)?),
+                    None => None,
+                };
+
+                if let Some((value, rest)) = binary::match_segment(
+                    proc,
+                    fun,
+                    &exec.binds,
+                    unpack_term,
+                    specifier,
+                    size,
+                    *branch,
+                )? {
+                    exec.next_args.push(value);
+                    exec.next_args.push(rest);
+                    return exec.val_call(proc, fun, *branch);
+                }
+            }
             MatchKind::Wildcard => {
                 assert!(branch_args.len() == 0);
                 return exec.val_call(proc, fun, *branch);
@@ -15,14 +15,20 @@ use liblumen_alloc::erts::exception::system;
 use liblumen_alloc::erts::exception::Exception;
 use liblumen_alloc::erts::process::code::Result;
 use liblumen_alloc::erts::process::RootSet;
-use liblumen_alloc::erts::process::{Process, ProcessFlags};
-use liblumen_alloc::erts::term::{atom_unchecked, Atom, Boxed, Map, Term, TypedTerm};
+use liblumen_alloc::erts::process::{GcError, Process, ProcessFlags};
+use liblumen_alloc::erts::term::{
+    atom_unchecked, AsTerm, Atom, Boxed, Map, Reference, Term, TypedTerm,
+};
 use liblumen_alloc::erts::ModuleFunctionArity;
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::module::DirtyFunction;
 use crate::module::{ErlangFunction, NativeFunctionKind, ResolvedFunction};
 use crate::vm::VMState;
 
+mod binary;
 mod r#match;
+mod receive;
 
 macro_rules! trace {
     ($($t:tt)*) => (lumen_runtime::system::io::puts(&format_args!($($t)*).to_string()))
@@ -97,36 +103,44 @@ where
         match fun(terms) {
             Ok(inner) => break inner,
             Err(system::Exception::Alloc(_)) => {
-                let mut heap = proc.acquire_heap();
+                let gc_result = {
+                    let mut heap = proc.acquire_heap();
 
-                let mut rootset = RootSet::new(&mut []);
-                // Process dictionary/other process related terms
-                proc.base_root_set(&mut rootset);
-                // Terms are in root set
-                unsafe { terms.add(&mut rootset) };
+                    let mut rootset = RootSet::new(&mut []);
+                    // Process dictionary/other process related terms
+                    proc.base_root_set(&mut rootset);
+                    // Terms are in root set
+                    unsafe { terms.add(&mut rootset) };
 
-                lumen_runtime::system::io::puts(
-                    "=================================================== GC",
-                );
-                match heap.garbage_collect(proc, 0, rootset) {
-                    Ok(_) => (),
-                    Err(_) => {
-                        proc.set_flags(ProcessFlags::NeedFullSweep);
-
-                        let mut rootset = RootSet::new(&mut []);
-                        // Process dictionary/other process related terms
-                        proc.base_root_set(&mut rootset);
-                        // Terms are in root set
-                        unsafe { terms.add(&mut rootset) };
-
-                        lumen_runtime::system::io::puts(
-                            "=================================================== FULL GC",
-                        );
-                        match heap.garbage_collect(proc, 0, rootset) {
-                            Ok(_) => (),
-                            Err(_) => panic!(),
+                    lumen_runtime::system::io::puts(
+                        "=================================================== GC",
+                    );
+                    match heap.garbage_collect(proc, 0, rootset) {
+                        Ok(reductions) => Ok(reductions),
+                        Err(_) => {
+                            proc.set_flags(ProcessFlags::NeedFullSweep);
+
+                            let mut rootset = RootSet::new(&mut []);
+                            // Process dictionary/other process related terms
+                            proc.base_root_set(&mut rootset);
+                            // Terms are in root set
+                            unsafe { terms.add(&mut rootset) };
+
+                            lumen_runtime::system::io::puts(
+                                "=================================================== FULL GC",
+                            );
+                            heap.garbage_collect(proc, 0, rootset)
                         }
                     }
+                };
+
+                // The heap has to be unlocked to be compared to the max heap size
+                match lumen_runtime::process::max_heap_size::enforce(proc, gc_result) {
+                    Ok(_) => (),
+                    // The process was killed, so the next try collects it without a max heap size
+                    // and it runs until it gets back to the scheduler, which won't run it again
+                    Err(GcError::MaxHeapSizeExceeded) => (),
+                    Err(_) => panic!(),
                 }
             }
         }
@@ -134,6 +148,165 @@ where
 }
 
 /// Sets up the current stack frame of `proc` to call `closure` with `args`.
+/// Verifies the `kind` terms, such as arguments, passed to or from the native
+/// `module:function/arity`
+#[cfg(feature = "term_debug")]
+fn verify_native_terms(
+    proc: &Process,
+    module: Atom,
+    function: Atom,
+    arity: usize,
+    kind: &str,
+    terms: &[Term],
+) {
+    for (index, term) in terms.iter().enumerate() {
+        liblumen_alloc::erts::term::verify::verify(
+            proc,
+            *term,
+            &format_args!(
+                "{} {} of {}:{}/{}",
+                kind,
+                index + 1,
+                module.name(),
+                function.name(),
+                arity
+            ),
+        );
+    }
+}
+
+#[cfg(not(feature = "term_debug"))]
+#[inline]
+fn verify_native_terms(
+    _proc: &Process,
+    _module: Atom,
+    _function: Atom,
+    _arity: usize,
+    _kind: &str,
+    _terms: &[Term],
+) {
+}
+
+/// Continues a call to a dirty native function with the `result` it returned on a dirty scheduler.
+/// If the result couldn't be allocated, the function is run again after garbage collection.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn resume_dirty(
+    proc: &Arc<Process>,
+    module: Atom,
+    function: Atom,
+    fun: DirtyFunction,
+    result: std::result::Result<Term, Exception>,
+    mut args: &mut [Term],
+) {
+    let arity = args.len() - 2;
+    let mut option_result = Some(result);
+
+    try_gc(proc, &mut args, &mut |args| match option_result.take() {
+        Some(result) => return_native(proc, module, function, arity, result, args),
+        None => crate::dirty::dispatch(proc, module, function, fun, args),
+    })
+}
+
+/// Continues the call of a yielding native function with what it returned, or the exception it
+/// raised, once it no longer waits.
+pub fn resume_yielded(
+    proc: &Arc<Process>,
+    module: Atom,
+    function: Atom,
+    result: std::result::Result<Term, Exception>,
+    args: &mut [Term],
+) -> Result {
+    let arity = args.len() - 2;
+
+    return_native(proc, module, function, arity, result, args)
+}
+
+/// Continues the call of a trapping native function with what it returned, or the exception it
+/// raised, once it no longer traps.
+pub fn resume_trapped(
+    proc: &Arc<Process>,
+    module: Atom,
+    function: Atom,
+    result: std::result::Result<Term, Exception>,
+    args: &mut [Term],
+) -> Result {
+    let arity = args.len() - 2;
+
+    return_native(proc, module, function, arity, result, args)
+}
+
+/// Calls the return continuation in `args` with what a native function returned, or the throw
+/// continuation with the exception it raised.
+fn return_native(
+    proc: &Arc<Process>,
+    module: Atom,
+    function: Atom,
+    arity: usize,
+    result: std::result::Result<Term, Exception>,
+    args: &mut [Term],
+) -> Result {
+    match result {
+        Ok(ret) => {
+            verify_native_terms(proc, module, function, arity, "return", &[ret]);
+
+            Ok(call_closure(proc, args[0], &mut [ret]))
+        }
+        Err(err) => {
+            if let Exception::Runtime(exception) = &err {
+                verify_native_terms(
+                    proc,
+                    module,
+                    function,
+                    arity,
+                    "exception reason",
+                    &[exception.reason],
+                );
+            }
+
+            match err {
+                Exception::System(err) => Err(err),
+                Exception::Runtime(runtime::Exception {
+                    class: runtime::Class::Throw,
+                    reason,
+                    ..
+                }) => Ok(call_closure(
+                    proc,
+                    args[1],
+                    &mut [atom_unchecked("throw"), reason, atom_unchecked("trace")],
+                )),
+                Exception::Runtime(runtime::Exception {
+                    class: runtime::Class::Exit,
+                    reason,
+                    ..
+                }) => Ok(call_closure(
+                    proc,
+                    args[1],
+                    &mut [atom_unchecked("EXIT"), reason, atom_unchecked("trace")],
+                )),
+                Exception::Runtime(runtime::Exception {
+                    class: runtime::Class::Error { .. },
+                    reason,
+                    ..
+                }) => Ok(call_closure(
+                    proc,
+                    args[1],
+                    &mut [atom_unchecked("error"), reason, atom_unchecked("trace")],
+                )),
+            }
+        }
+    }
+}
+
+/// Replaces the match contexts in `args` with subbinaries, before they are passed to code that
+/// isn't interpreted (see `binary`)
+fn to_bitstrings(proc: &Arc<Process>, mut args: &mut [Term]) {
+    try_gc(proc, &mut args, &mut |args| {
+        binary::to_bitstrings(proc, args)?;
+
+        Ok(())
+    })
+}
+
 fn call_closure(proc: &Arc<Process>, mut closure: Term, args: &mut [Term]) {
     try_gc(proc, &mut (&mut closure, args), &mut |(
         closure_term,
@@ -194,7 +367,6 @@ impl CallExecutor {
         args: &mut [Term],
     ) {
         trace!("======== RUN {} ========", proc.pid());
-        let modules = vm.modules.read().unwrap();
 
         // Make sure no non-heap terms make it into the process
         {
@@ -210,23 +382,95 @@ impl CallExecutor {
             }
         }
 
-        match modules.lookup_function(module, function, arity) {
-            None => {
-                self.fun_not_found(proc, args[1], module, function, arity)
-                    .unwrap();
+        // The registry lock is only held for the lookup, so that natives are free to load code
+        let option_resolved = vm
+            .modules
+            .read()
+            .unwrap()
+            .lookup_function(module, function, arity);
+
+        // The first call to a module that is not loaded yet loads it from the code path
+        let option_resolved = match option_resolved {
+            None if crate::code_server::ensure_loaded(vm, module).is_ok() => vm
+                .modules
+                .read()
+                .unwrap()
+                .lookup_function(module, function, arity),
+            option_resolved => option_resolved,
+        };
+
+        // Match contexts are only passed as they are to interpreted functions
+        let is_erlang = match option_resolved {
+            Some(ResolvedFunction::Erlang(_)) => true,
+            _ => false,
+        };
+
+        if !is_erlang || proc.trace().is_some() {
+            to_bitstrings(proc, args);
+        }
+
+        // the first two arguments are the return and throw continuations
+        proc.trace_call(module, function, &args[2..]);
+        crate::profile::call(proc, module, function, arity);
+
+        match option_resolved {
+            // Erlang modules don't define `module_info/0,1` in their source, so it is answered from
+            // the registry, unless the module or a native overlay defines it
+            None if function.name() == "module_info"
+                && arity <= 1
+                && vm.modules.read().unwrap().attributes(module).is_some() =>
+            {
+                self.module_info(vm, proc, module, function, args)
             }
+            None => self.fun_not_found(vm, proc, module, function, args),
             Some(ResolvedFunction::Native(native)) => {
                 assert!(arity + 2 == args.len());
-                self.run_native(vm, proc, native, args);
+                self.run_native(vm, proc, module, function, native, args);
             }
             Some(ResolvedFunction::Erlang(fun)) => {
                 let entry = fun.fun.block_entry();
-                self.run_erlang(vm, proc, fun, entry, args);
+                self.run_erlang(vm, proc, &fun, entry, args);
+            }
+        }
+    }
+
+    /// Calls the given MFA with args in `version` of the module, so that local calls stay on the
+    /// version of the module that the caller is executing when a new version is loaded.  Falls
+    /// back to the current version if `version` is no longer loaded.
+    pub fn call_local(
+        &mut self,
+        vm: &VMState,
+        proc: &Arc<Process>,
+        module: Atom,
+        function: Atom,
+        arity: usize,
+        version: usize,
+        args: &mut [Term],
+    ) {
+        let option_fun = vm
+            .modules
+            .read()
+            .unwrap()
+            .lookup_function_version(module, function, arity, version);
+
+        match option_fun {
+            Some(fun) => {
+                trace!("======== RUN {} ========", proc.pid());
+
+                if proc.trace().is_some() {
+                    to_bitstrings(proc, args);
+                }
+
+                proc.trace_call(module, function, &args[2..]);
+                crate::profile::call(proc, module, function, arity);
+                let entry = fun.fun.block_entry();
+                self.run_erlang(vm, proc, &fun, entry, args);
             }
+            None => self.call(vm, proc, module, function, arity, args),
         }
     }
 
-    /// Calls a block in the given MFA with an environment.
+    /// Calls a block in `version` of the given MFA with an environment.
     pub fn call_block(
         &mut self,
         vm: &VMState,
@@ -234,18 +478,21 @@ impl CallExecutor {
         module: Atom,
         function: Atom,
         arity: usize,
+        version: usize,
         args: &mut [Term],
         block: Block,
         env: &mut [Term],
     ) {
         trace!("======== RUN {} ========", proc.pid());
-        let modules = vm.modules.read().unwrap();
-        match modules.lookup_function(module, function, arity) {
-            None => self
-                .fun_not_found(proc, args[1], module, function, arity)
-                .unwrap(),
-            Some(ResolvedFunction::Native(_ptr)) => unreachable!(),
-            Some(ResolvedFunction::Erlang(fun)) => {
+        let option_fun = vm
+            .modules
+            .read()
+            .unwrap()
+            .lookup_function_version(module, function, arity, version);
+
+        match option_fun {
+            None => self.undef(proc, module, function, args),
+            Some(fun) => {
                 let live = &fun.live.live[&block];
                 assert!(live.size(&fun.live.pool) == env.len());
 
@@ -253,67 +500,146 @@ impl CallExecutor {
                     self.binds.insert(v, *t);
                 }
 
-                self.run_erlang(vm, proc, fun, block, args);
+                self.run_erlang(vm, proc, &fun, block, args);
             }
         }
     }
 
+    /// Calls `undefined_function(Module, Function, Arguments)` in the process's `error_handler`
+    /// module in place of the undefined function, so that the error handler can load the module or
+    /// stub the function.  If the error handler does not define `undefined_function/3`, `undef` is
+    /// raised.
     fn fun_not_found(
-        &self,
-        _proc: &Arc<Process>,
-        _throw_cont: Term,
+        &mut self,
+        vm: &VMState,
+        proc: &Arc<Process>,
         module: Atom,
         function: Atom,
-        arity: usize,
-    ) -> Result {
-        panic!("Undef: {} {} {}", module, function, arity);
-        //let exit_atom = atom_unchecked("EXIT");
-        //let undef_atom = atom_unchecked("undef");
-        //let trace_atom = atom_unchecked("trace");
-        //self.call_closure(proc, throw_cont, &[exit_atom, undef_atom, trace_atom])
+        mut args: &mut [Term],
+    ) {
+        let error_handler = proc.error_handler();
+        let undefined_function = Atom::try_from_str("undefined_function").unwrap();
+        let option_resolved =
+            vm.modules
+                .read()
+                .unwrap()
+                .lookup_function(error_handler, undefined_function, 3);
+
+        match option_resolved {
+            None => self.undef(proc, module, function, args),
+            Some(resolved) => {
+                let mut error_handler_args = try_gc(proc, &mut args, &mut |args| {
+                    let module_term = unsafe { module.as_term() };
+                    let function_term = unsafe { function.as_term() };
+                    let argument_list = proc.list_from_slice(&args[2..])?;
+
+                    Ok(vec![
+                        args[0],
+                        args[1],
+                        module_term,
+                        function_term,
+                        argument_list,
+                    ])
+                });
+
+                match resolved {
+                    ResolvedFunction::Native(native) => self.run_native(
+                        vm,
+                        proc,
+                        error_handler,
+                        undefined_function,
+                        native,
+                        &mut error_handler_args,
+                    ),
+                    ResolvedFunction::Erlang(fun) => {
+                        let entry = fun.fun.block_entry();
+                        self.run_erlang(vm, proc, &fun, entry, &mut error_handler_args);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Raises `error:undef` through the throw continuation with `{Module, Function, Arguments, []}`
+    /// as the top of the stacktrace.
+    fn undef(&mut self, proc: &Arc<Process>, module: Atom, function: Atom, mut args: &mut [Term]) {
+        try_gc(proc, &mut args, &mut |args| {
+            let module_term = unsafe { module.as_term() };
+            let function_term = unsafe { function.as_term() };
+            let argument_list = proc.list_from_slice(&args[2..])?;
+            let location = Term::NIL;
+            let stacktrace_entry =
+                proc.tuple_from_slice(&[module_term, function_term, argument_list, location])?;
+            let stacktrace = proc.list_from_slice(&[stacktrace_entry])?;
+
+            Ok(call_closure(
+                proc,
+                args[1],
+                &mut [atom_unchecked("error"), atom_unchecked("undef"), stacktrace],
+            ))
+        })
+    }
+
+    fn module_info(
+        &mut self,
+        vm: &VMState,
+        proc: &Arc<Process>,
+        module: Atom,
+        function: Atom,
+        mut args: &mut [Term],
+    ) {
+        let arity = args.len() - 2;
+
+        try_gc(proc, &mut args, &mut |args| {
+            let result = crate::attributes::module_info(
+                proc,
+                &vm.modules.read().unwrap(),
+                module,
+                &args[2..],
+            )
+            .unwrap();
+
+            return_native(proc, module, function, arity, result, args)
+        })
     }
 
     fn run_native(
         &mut self,
         _vm: &VMState,
         proc: &Arc<Process>,
+        module: Atom,
+        function: Atom,
         native: NativeFunctionKind,
         mut args: &mut [Term],
     ) {
+        let arity = args.len() - 2;
+        verify_native_terms(proc, module, function, arity, "continuation", &args[..2]);
+        verify_native_terms(proc, module, function, arity, "argument", &args[2..]);
+
         try_gc(proc, &mut args, &mut |args| match native {
-            NativeFunctionKind::Simple(ptr) => match ptr(proc, &args[2..]) {
-                Ok(ret) => Ok(call_closure(proc, args[0], &mut [ret])),
-                Err(err) => match err {
-                    Exception::System(err) => return Err(err),
-                    Exception::Runtime(runtime::Exception {
-                        class: runtime::Class::Throw,
-                        reason,
-                        ..
-                    }) => Ok(call_closure(
-                        proc,
-                        args[1],
-                        &mut [atom_unchecked("throw"), reason, atom_unchecked("trace")],
-                    )),
-                    Exception::Runtime(runtime::Exception {
-                        class: runtime::Class::Exit,
-                        reason,
-                        ..
-                    }) => Ok(call_closure(
-                        proc,
-                        args[1],
-                        &mut [atom_unchecked("EXIT"), reason, atom_unchecked("trace")],
-                    )),
-                    Exception::Runtime(runtime::Exception {
-                        class: runtime::Class::Error { .. },
-                        reason,
-                        ..
-                    }) => Ok(call_closure(
-                        proc,
-                        args[1],
-                        &mut [atom_unchecked("error"), reason, atom_unchecked("trace")],
-                    )),
-                },
-            },
+            NativeFunctionKind::Simple(ptr) => {
+                let result = ptr(proc, &args[2..]);
+
+                return_native(proc, module, function, arity, result, args)
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            NativeFunctionKind::Dirty(ptr) => {
+                crate::dirty::dispatch(proc, module, function, ptr, args)
+            }
+            #[cfg(target_arch = "wasm32")]
+            NativeFunctionKind::Dirty(ptr) => {
+                let result = ptr(proc, &args[2..]);
+
+                return_native(proc, module, function, arity, result, args)
+            }
+            NativeFunctionKind::Trapping(trap) => {
+                let mut state = (trap.start)(&args[2..]);
+
+                match trap.run(proc, &mut state) {
+                    Some(result) => return_native(proc, module, function, arity, result, args),
+                    None => crate::trap::trap(proc, module, function, &state, args),
+                }
+            }
             NativeFunctionKind::Yielding(ptr) => ptr(proc, args),
         })
     }
@@ -326,6 +652,8 @@ impl CallExecutor {
         mut block: Block,
         args: &mut [Term],
     ) {
+        proc.execute_code_version(fun.module, fun.version);
+
         self.next_args.extend(args.iter().cloned());
 
         let mut exec = self;
@@ -358,11 +686,43 @@ impl CallExecutor {
         }
     }
 
+    /// Constants are made on the heap the first time, and then copied to the literal area of the
+    /// module, so that the next time, the literal is referenced instead.
     fn make_const_term(
         &self,
         proc: &Arc<Process>,
         fun: &ErlangFunction,
         const_val: Const,
+    ) -> std::result::Result<Term, system::Exception> {
+        let made = fun
+            .literal_by_const
+            .lock()
+            .unwrap()
+            .get(&const_val)
+            .cloned();
+
+        match made {
+            Some(Some(literal)) => Ok(literal),
+            Some(None) => self.make_heap_const_term(proc, fun, const_val),
+            None => {
+                let term = self.make_heap_const_term(proc, fun, const_val)?;
+                let option_literal = fun.literal_area.insert(term)?;
+
+                fun.literal_by_const
+                    .lock()
+                    .unwrap()
+                    .insert(const_val, option_literal);
+
+                Ok(option_literal.unwrap_or(term))
+            }
+        }
+    }
+
+    fn make_heap_const_term(
+        &self,
+        proc: &Arc<Process>,
+        fun: &ErlangFunction,
+        const_val: Const,
     ) -> std::result::Result<Term, system::Exception> {
         let res = match fun.fun.cons().const_kind(const_val) {
             ConstKind::Atomic(AtomicTerm::Atom(atom)) => Ok(atom_unchecked(&atom.0.as_str())),
@@ -417,6 +777,7 @@ impl CallExecutor {
         // FIXME vec alloc
         let mut env = Vec::new();
         env.push(proc.integer(block.index())?);
+        env.push(proc.integer(fun.version)?);
         for v in live.iter(&fun.live.pool) {
             assert!(fun.fun.value_argument(v).is_some());
             env.push(self.make_term(proc, fun, v)?);
@@ -438,6 +799,45 @@ impl CallExecutor {
         Ok(closure)
     }
 
+    /// Captures `module:function/arity`.  Captures of functions in the same module as `fun` are
+    /// local calls, so they stay on the version of the module that `fun` is part of.
+    fn make_function_capture(
+        &self,
+        proc: &Arc<Process>,
+        fun: &ErlangFunction,
+        module: Atom,
+        function: Atom,
+        arity: usize,
+    ) -> std::result::Result<Term, system::Exception> {
+        let mfa = ModuleFunctionArity {
+            module,
+            function,
+            arity: arity as u8,
+        };
+
+        let fun_module = Atom::try_from_str(fun.fun.ident().module.as_str()).unwrap();
+
+        let closure = if module == fun_module {
+            let version = proc.integer(fun.version)?;
+
+            proc.closure_with_env_from_slice(
+                mfa.into(),
+                crate::code::interpreter_local_mfa_code,
+                proc.pid_term(),
+                &[version],
+            )?
+        } else {
+            proc.closure_with_env_from_slice(
+                mfa.into(),
+                crate::code::interpreter_mfa_code,
+                proc.pid_term(),
+                &[],
+            )?
+        };
+
+        Ok(closure)
+    }
+
     fn make_term(
         &self,
         proc: &Arc<Process>,
@@ -446,7 +846,7 @@ impl CallExecutor {
     ) -> std::result::Result<Term, system::Exception> {
         match fun.fun.value_kind(value) {
             ValueKind::Block(block) => self.make_closure(proc, fun, block),
-            ValueKind::Argument(_, _) => Ok(self.binds[&value]),
+            ValueKind::Argument(_, _) => Ok(binary::to_bitstring(proc, self.binds[&value])?),
             ValueKind::Const(cons) => self.make_const_term(proc, fun, cons),
             ValueKind::PrimOp(prim) => {
                 let reads = fun.fun.primop_reads(prim);
@@ -505,18 +905,7 @@ impl CallExecutor {
                             self.make_term(proc, fun, reads[1])?.try_into().unwrap();
                         let arity: usize = self.make_term(proc, fun, reads[2])?.try_into().unwrap();
 
-                        let mfa = ModuleFunctionArity {
-                            module,
-                            function,
-                            arity: arity as u8,
-                        };
-
-                        Ok(proc.closure_with_env_from_slice(
-                            mfa.into(),
-                            crate::code::interpreter_mfa_code,
-                            proc.pid_term(),
-                            &[],
-                        )?)
+                        self.make_function_capture(proc, fun, module, function, arity)
                     }
                     kind => unimplemented!("{:?}", kind),
                 }
@@ -524,6 +913,20 @@ impl CallExecutor {
         }
     }
 
+    /// Like `make_term`, but a binary being matched is left as its match context, so that it can
+    /// be matched further (see `binary`)
+    fn make_match_term(
+        &self,
+        proc: &Arc<Process>,
+        fun: &ErlangFunction,
+        value: Value,
+    ) -> std::result::Result<Term, system::Exception> {
+        match fun.fun.value_kind(value) {
+            ValueKind::Argument(_, _) => Ok(self.binds[&value]),
+            _ => self.make_term(proc, fun, value),
+        }
+    }
+
     fn val_call(
         &mut self,
         proc: &Arc<Process>,
@@ -553,8 +956,22 @@ impl CallExecutor {
 
         match kind {
             OpKind::Call => {
+                // Blocks of this function and captured functions are interpreted, so they can be
+                // passed match contexts
+                let is_interpreted = match fun.fun.value_kind(reads[0]) {
+                    ValueKind::Block(_) => true,
+                    ValueKind::PrimOp(prim) => {
+                        fun.fun.primop_kind(prim) == &PrimOpKind::CaptureFunction
+                    }
+                    _ => false,
+                };
+
                 for read in reads.iter().skip(1) {
-                    let term = self.make_term(proc, fun, *read)?;
+                    let term = if is_interpreted {
+                        self.make_match_term(proc, fun, *read)?
+                    } else {
+                        self.make_term(proc, fun, *read)?
+                    };
                     self.next_args.push(term);
                 }
                 self.val_call(proc, fun, reads[0])
@@ -593,18 +1010,7 @@ impl CallExecutor {
                 let function: Atom = self.make_term(proc, fun, reads[2])?.try_into().unwrap();
                 let arity: usize = self.make_term(proc, fun, reads[3])?.try_into().unwrap();
 
-                let mfa = ModuleFunctionArity {
-                    module,
-                    function,
-                    arity: arity as u8,
-                };
-
-                let closure = proc.closure_with_env_from_slice(
-                    mfa.into(),
-                    crate::code::interpreter_mfa_code,
-                    proc.pid_term(),
-                    &[],
-                )?;
+                let closure = self.make_function_capture(proc, fun, module, function, arity)?;
 
                 self.next_args.push(closure);
                 self.val_call(proc, fun, reads[0])
@@ -686,10 +1092,33 @@ impl CallExecutor {
                 assert!(reads.len() == 2);
 
                 let timeout = self.make_term(proc, fun, reads[1])?;
-                // Only infinity supported
-                assert!(timeout == atom_unchecked("infinity"));
+                // Only infinity and 0 supported.  `after 0` polls the mailbox: when no message
+                // matches, `receive_wait` takes the timeout branch instead of suspending.
+                let polling = timeout == proc.integer(0)?;
+                assert!(polling || timeout == atom_unchecked("infinity"));
+
+                // A receive whose every clause matches a reference made by `make_ref/0` only
+                // scans the messages queued after it was made.
+                let option_reference = match self::receive::guard_value(fun, block) {
+                    Some(guard_value) => {
+                        let guard: std::result::Result<Boxed<Reference>, _> =
+                            self.make_term(proc, fun, guard_value)?.try_into();
+
+                        guard.ok()
+                    }
+                    None => None,
+                };
 
-                proc.mailbox.lock().borrow_mut().recv_start();
+                let mailbox_lock = proc.mailbox.lock();
+                let mut mailbox = mailbox_lock.borrow_mut();
+                mailbox.recv_start(polling);
+
+                if let Some(reference) = option_reference {
+                    mailbox.recv_set(&reference);
+                }
+
+                std::mem::drop(mailbox);
+                std::mem::drop(mailbox_lock);
 
                 self.next_args.push(Term::NIL);
                 self.val_call(proc, fun, reads[0])
@@ -697,8 +1126,6 @@ impl CallExecutor {
             OpKind::Intrinsic(name) if *name == Symbol::intern("receive_wait") => {
                 assert!(reads.len() == 2);
 
-                let curr_cont = self.make_closure(proc, fun, block)?;
-
                 let mailbox_lock = proc.mailbox.lock();
                 let mut mailbox = mailbox_lock.borrow_mut();
                 if let Some(msg_term) = mailbox.recv_peek() {
@@ -709,9 +1136,20 @@ impl CallExecutor {
 
                     self.next_args.push(msg_term);
                     self.val_call(proc, fun, reads[1])
+                } else if mailbox.recv_polling() {
+                    mailbox.recv_timeout();
+
+                    std::mem::drop(mailbox);
+                    std::mem::drop(mailbox_lock);
+
+                    self.val_call(proc, fun, reads[0])
                 } else {
+                    std::mem::drop(mailbox);
+                    std::mem::drop(mailbox_lock);
+
                     // If there are no messages, schedule a call
                     // to the current block for later.
+                    let curr_cont = self.make_closure(proc, fun, block)?;
                     self.next_args.push(Term::NIL);
                     proc.wait();
                     Ok(OpResult::TermYield(curr_cont))
@@ -723,14 +1161,12 @@ impl CallExecutor {
                 let mailbox_lock = proc.mailbox.lock();
                 let mut mailbox = mailbox_lock.borrow_mut();
 
-                if mailbox.recv_last_off_heap() {
-                    // Copy to process heap
-                    unimplemented!()
-                } else {
-                    for n in 0..(reads.len() - 1) {
-                        let term = self.make_term(proc, fun, reads[n + 1]).unwrap();
-                        self.next_args.push(term);
-                    }
+                // Terms taken from a message in a heap fragment stay valid once it is removed, as
+                // the fragment is then part of the off-heap of the process until the next
+                // collection copies what is still live to the heap.
+                for n in 0..(reads.len() - 1) {
+                    let term = self.make_term(proc, fun, reads[n + 1]).unwrap();
+                    self.next_args.push(term);
                 }
 
                 mailbox.recv_finish(proc);
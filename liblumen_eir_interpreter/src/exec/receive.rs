@@ -0,0 +1,107 @@
+//! Finding the value that every clause of a receive matches a part of the message against, so that
+//! when that value is a reference marked by `make_ref/0`, the receive can skip the messages queued
+//! before the reference was made, as BEAM does for call and reply patterns.
+
+use hashbrown::HashSet;
+
+use libeir_intern::Symbol;
+use libeir_ir::{Block, MatchKind, OpKind, PrimOpKind, Value, ValueKind};
+
+use crate::module::ErlangFunction;
+
+/// Returns the value bound before the receive started at `receive_start` that every clause matches
+/// a part of the message against, or `None` if any clause could match a message without it.
+pub fn guard_value(fun: &ErlangFunction, receive_start: Block) -> Option<Value> {
+    let receive_start_reads = fun.fun.block_reads(receive_start);
+    let receive_wait = block(fun, receive_start_reads[0])?;
+
+    match fun.fun.block_kind(receive_wait) {
+        Some(OpKind::Intrinsic(name)) if *name == Symbol::intern("receive_wait") => (),
+        _ => return None,
+    }
+
+    let receive_wait_reads = fun.fun.block_reads(receive_wait);
+    let receive_match = block(fun, receive_wait_reads[1])?;
+    let bound: Vec<Value> = fun.live.live[&receive_start].iter(&fun.live.pool).collect();
+
+    let mut guard: Option<Value> = None;
+    let mut visited = HashSet::new();
+    let mut stack = vec![receive_match];
+
+    while let Some(current) = stack.pop() {
+        if !visited.insert(current) {
+            continue;
+        }
+
+        let reads = fun.fun.block_reads(current);
+
+        match fun.fun.block_kind(current) {
+            // a clause matched without comparing against the guard
+            Some(OpKind::Intrinsic(name)) if *name == Symbol::intern("receive_done") => {
+                return None
+            }
+            // no clause matched, so the next message is tried
+            Some(OpKind::Intrinsic(name)) if *name == Symbol::intern("receive_wait") => (),
+            Some(OpKind::Match { branches }) => {
+                let branches_dests = value_list(fun, reads[0])?;
+                let unpacked_is_bound = bound.contains(&reads[1]);
+
+                for (index, (kind, dest)) in branches.iter().zip(branches_dests.iter()).enumerate()
+                {
+                    let branch_args = value_list(fun, reads[index + 2])?;
+
+                    match kind {
+                        MatchKind::Value
+                            if !unpacked_is_bound && bound.contains(&branch_args[0]) =>
+                        {
+                            match guard {
+                                Some(guard_value) if guard_value != branch_args[0] => return None,
+                                _ => guard = Some(branch_args[0]),
+                            }
+                        }
+                        _ => stack.push(block(fun, *dest)?),
+                    }
+                }
+            }
+            _ => {
+                for read in reads {
+                    match fun.fun.value_kind(*read) {
+                        ValueKind::Block(successor) => stack.push(successor),
+                        _ => {
+                            if let Some(primop) = fun.fun.value_primop(*read) {
+                                for primop_read in fun.fun.primop_reads(primop) {
+                                    if let ValueKind::Block(successor) =
+                                        fun.fun.value_kind(*primop_read)
+                                    {
+                                        stack.push(successor);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    guard
+}
+
+// Private
+
+fn block(fun: &ErlangFunction, value: Value) -> Option<Block> {
+    match fun.fun.value_kind(value) {
+        ValueKind::Block(block) => Some(block),
+        _ => None,
+    }
+}
+
+fn value_list(fun: &ErlangFunction, value: Value) -> Option<&[Value]> {
+    let primop = fun.fun.value_primop(value)?;
+
+    if fun.fun.primop_kind(primop) == &PrimOpKind::ValueList {
+        Some(fun.fun.primop_reads(primop))
+    } else {
+        None
+    }
+}
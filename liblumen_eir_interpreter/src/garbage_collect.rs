@@ -0,0 +1,95 @@
+//! Running `garbage_collect/0,1,2` as yielding native functions, so that a synchronous request
+//! returns only once the process is collected.
+//!
+//! A process that is running, including the calling process, is collected by its scheduler once
+//! it stops, so the calling process waits with the arguments, including the continuations, on its
+//! stack and `code` as its frame.  Once it is woken, `code` continues the call with whether the
+//! process was collected, or waits again if it was woken by a message first.
+
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception::Exception;
+use liblumen_alloc::erts::process::code::stack::frame::Frame;
+use liblumen_alloc::erts::process::code::Result;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{Atom, Term, TypedTerm};
+use liblumen_alloc::erts::ModuleFunctionArity;
+
+use lumen_runtime::otp::erlang;
+use lumen_runtime::process::garbage_collect::wait_for_reply;
+
+/// `garbage_collect/0,1,2` with the arguments after the return and throw continuations in `args`
+pub fn collect(proc: &Arc<Process>, args: &[Term]) -> Result {
+    let result = match args[2..] {
+        [] => erlang::garbage_collect_0::native(proc),
+        [pid] => erlang::garbage_collect_1::native(proc, pid),
+        [pid, options] => erlang::garbage_collect_2::native(proc, pid, options),
+        _ => unreachable!(),
+    };
+
+    match result.transpose() {
+        Some(result) => resume(proc, result, args),
+        None => suspend(proc, args),
+    }
+}
+
+// Private
+
+fn resume(
+    proc: &Arc<Process>,
+    result: std::result::Result<Term, Exception>,
+    args: &[Term],
+) -> Result {
+    crate::exec::resume_yielded(
+        proc,
+        Atom::try_from_str("erlang").unwrap(),
+        function(),
+        result,
+        &mut args.to_vec(),
+    )
+}
+
+/// Saves the arguments in `args`, including the return and throw continuations, so that `code`
+/// continues the call once the process is woken
+fn suspend(proc: &Arc<Process>, args: &[Term]) -> Result {
+    let argument_list = proc.list_from_slice(args)?;
+    proc.stack_push(argument_list)?;
+
+    let module_function_arity = Arc::new(ModuleFunctionArity {
+        module: Atom::try_from_str("erlang").unwrap(),
+        function: function(),
+        arity: (args.len() - 2).try_into().unwrap(),
+    });
+    proc.replace_frame(Frame::new(module_function_arity, code));
+
+    Ok(())
+}
+
+/// Expects the following on stack:
+/// * argument list, including the return and throw continuations
+fn code(arc_process: &Arc<Process>) -> Result {
+    let argument_list = arc_process.stack_pop().unwrap();
+
+    let argument_vec: Vec<Term> = match argument_list.to_typed_term().unwrap() {
+        TypedTerm::List(argument_cons) => argument_cons
+            .into_iter()
+            .map(|result| result.unwrap())
+            .collect(),
+        _ => unreachable!(),
+    };
+
+    match wait_for_reply(arc_process) {
+        Some(collected) => resume(arc_process, Ok(collected.into()), &argument_vec),
+        // the process was woken by something else, such as a message, so it waits again
+        None => {
+            arc_process.stack_push(argument_list)?;
+
+            Ok(())
+        }
+    }
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("garbage_collect").unwrap()
+}
@@ -1,11 +1,24 @@
 #![deny(warnings)]
 
+pub mod attributes;
 pub mod code;
+pub mod code_server;
+pub mod compile;
+#[cfg(not(target_arch = "wasm32"))]
+mod dirty;
+pub mod escript;
 mod exec;
+mod garbage_collect;
 mod module;
-pub use module::NativeModule;
+pub use module::{LoadError, NativeModule};
 pub mod call_result;
 mod native;
+pub mod nif;
+#[cfg(not(target_arch = "wasm32"))]
+mod port;
+pub mod profile;
+pub mod suite;
+mod trap;
 mod vm;
 
 #[cfg(test)]
@@ -1,12 +1,18 @@
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
+use libeir_ir::constant::Const;
 use libeir_ir::{Function, LiveValues, Module};
 
 use liblumen_alloc::erts::exception::Exception;
 use liblumen_alloc::erts::process::code::Result;
 use liblumen_alloc::erts::process::Process;
-use liblumen_alloc::erts::term::{Atom, Term};
+use liblumen_alloc::erts::term::{Atom, Pid, Term};
+use liblumen_alloc::erts::LiteralArea;
+
+use lumen_runtime::trap::Trap;
+
+use crate::attributes::{self, Attribute};
 
 macro_rules! trace {
     ($($t:tt)*) => (lumen_runtime::system::io::puts(&format_args!($($t)*).to_string()))
@@ -15,45 +21,191 @@ macro_rules! trace {
 //    ($($t:tt)*) => ()
 //}
 
-pub enum ResolvedFunction<'a> {
+pub enum ResolvedFunction {
     Native(NativeFunctionKind),
-    Erlang(&'a ErlangFunction),
+    Erlang(Arc<ErlangFunction>),
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    /// No file for the module was found on the code path.
+    NoFile,
+    /// The file could not be compiled or does not define the requested module.
+    BadFile,
+    /// The module still has old code, which has to be purged before another version can be
+    /// loaded.
+    NotPurged,
 }
 
 pub struct ModuleRegistry {
     map: HashMap<Atom, ModuleType>,
+    /// The previous version of Erlang modules that have been reloaded.  Processes that were
+    /// executing the previous version when the new version was loaded keep executing it.  A
+    /// process is executing old code if the version of a module it last executed, as recorded on
+    /// the process itself, is the old version.
+    old: HashMap<Atom, ErlangModule>,
+    next_version: usize,
 }
 
 impl ModuleRegistry {
     pub fn new() -> Self {
         ModuleRegistry {
             map: HashMap::new(),
+            old: HashMap::new(),
+            next_version: 0,
         }
     }
 
     pub fn register_erlang_module(&mut self, module: Module) {
-        let erl_module = ErlangModule::from_eir(module);
-        match self.map.remove(&erl_module.name) {
-            None => self
-                .map
-                .insert(erl_module.name, ModuleType::Erlang(erl_module)),
-            Some(ModuleType::Native(native)) => self
-                .map
-                .insert(erl_module.name, ModuleType::Overlayed(erl_module, native)),
-            _ => panic!(),
+        self.load_erlang_module(module).unwrap();
+    }
+
+    /// Loads `module` as the current version of the module.  If there is already a current
+    /// version, it becomes the old version: fully-qualified calls go to the new current version,
+    /// while local calls in processes executing the old version stay on the old version.
+    pub fn load_erlang_module(&mut self, module: Module) -> std::result::Result<Atom, LoadError> {
+        let name = Atom::try_from_str(module.name.as_str()).unwrap();
+
+        if self.old.contains_key(&name) {
+            return Err(LoadError::NotPurged);
+        }
+
+        let erl_module = ErlangModule::from_eir(module, self.next_version);
+        self.next_version += 1;
+
+        let module_type = match self.map.remove(&name) {
+            None => ModuleType::Erlang(erl_module),
+            Some(ModuleType::Erlang(current)) => {
+                self.old.insert(name, current);
+
+                ModuleType::Erlang(erl_module)
+            }
+            Some(ModuleType::Overlayed(current, native)) => {
+                self.old.insert(name, current);
+
+                ModuleType::Overlayed(erl_module, native)
+            }
+            Some(ModuleType::Native(native)) => ModuleType::Overlayed(erl_module, native),
         };
+        self.map.insert(name, module_type);
+
+        Ok(name)
     }
 
+    /// Registers the functions of `native`, which take the place of Erlang functions of the same
+    /// name and arity.  If native functions are already registered for the module, the functions
+    /// of `native` are added to them, replacing those of the same name and arity.
     pub fn register_native_module(&mut self, native: NativeModule) {
-        match self.map.remove(&native.name) {
-            None => self.map.insert(native.name, ModuleType::Native(native)),
-            Some(ModuleType::Erlang(erl)) => self
-                .map
-                .insert(native.name, ModuleType::Overlayed(erl, native)),
-            _ => panic!(),
+        let name = native.name;
+
+        let module_type = match self.map.remove(&name) {
+            None => ModuleType::Native(native),
+            Some(ModuleType::Erlang(erl)) => ModuleType::Overlayed(erl, native),
+            Some(ModuleType::Native(mut current)) => {
+                current.functions.extend(native.functions);
+
+                ModuleType::Native(current)
+            }
+            Some(ModuleType::Overlayed(erl, mut current)) => {
+                current.functions.extend(native.functions);
+
+                ModuleType::Overlayed(erl, current)
+            }
         };
+        self.map.insert(name, module_type);
+    }
+
+    pub fn is_loaded(&self, module: Atom) -> bool {
+        self.map.contains_key(&module)
+    }
+
+    pub fn has_old_code(&self, module: Atom) -> bool {
+        self.old.contains_key(&module)
+    }
+
+    /// Whether `pid` is still executing the old version of `module`.
+    pub fn check_process_code(&self, pid: Pid, module: Atom) -> bool {
+        match lumen_runtime::registry::pid_to_process(&pid) {
+            Some(arc_process) => self.is_executing_old_code(&arc_process, module),
+            None => false,
+        }
+    }
+
+    /// The live processes that are still executing the old version of `module`.
+    pub fn processes_on_old_code(&self, module: Atom) -> Vec<Pid> {
+        if !self.old.contains_key(&module) {
+            return Vec::new();
+        }
+
+        lumen_runtime::registry::processes()
+            .filter(|arc_process| self.is_executing_old_code(arc_process, module))
+            .map(|arc_process| arc_process.pid())
+            .collect()
+    }
+
+    /// Removes the old version of `module`, so that a new version can be loaded.  Returns `false`
+    /// if there was no old code to purge.
+    ///
+    /// Processes may still have the version of the old code recorded, but as versions aren't
+    /// reused, they are no longer executing old code once it is purged.
+    pub fn purge(&mut self, module: Atom) -> bool {
+        self.old.remove(&module).is_some()
+    }
+
+    fn is_executing_old_code(&self, process: &Process, module: Atom) -> bool {
+        match self.old.get(&module) {
+            Some(old) => process.code_version(module) == Some(old.version),
+            None => false,
+        }
     }
 
+    /// The attributes of the current version of `module`, or `None` if it isn't a loaded Erlang
+    /// module.
+    pub fn attributes(&self, module: Atom) -> Option<&[Attribute]> {
+        self.erlang_module(module)
+            .map(|erl| erl.attributes.as_slice())
+    }
+
+    /// The functions of the current version of `module`, sorted by name and arity, or `None` if it
+    /// isn't a loaded Erlang module.
+    pub fn functions(&self, module: Atom) -> Option<Vec<(Atom, usize)>> {
+        self.erlang_module(module).map(|erl| {
+            let mut functions: Vec<(Atom, usize)> = erl.functions.keys().cloned().collect();
+            attributes::sort_functions(&mut functions);
+
+            functions
+        })
+    }
+
+    /// The exported functions of the current version of `module`, sorted by name and arity, or
+    /// `None` if it isn't a loaded Erlang module.
+    pub fn exports(&self, module: Atom) -> Option<Vec<(Atom, usize)>> {
+        self.erlang_module(module).map(|erl| match erl.exports {
+            Some(ref exports) => exports.clone(),
+            None => self.functions(module).unwrap(),
+        })
+    }
+
+    /// The loaded Erlang modules with a `-behaviour(behaviour)` attribute, sorted by name.
+    pub fn modules_with_behaviour(&self, behaviour: Atom) -> Vec<Atom> {
+        let mut modules: Vec<Atom> = self
+            .map
+            .keys()
+            .filter(|module| {
+                self.attributes(**module).map_or(false, |attributes| {
+                    attributes
+                        .iter()
+                        .any(|attribute| attribute.is_behaviour(behaviour))
+                })
+            })
+            .cloned()
+            .collect();
+        modules.sort_by(|left, right| left.name().cmp(right.name()));
+
+        modules
+    }
+
+    /// Looks up `function/arity` in the current version of `module`.
     pub fn lookup_function(
         &self,
         module: Atom,
@@ -66,6 +218,7 @@ impl ModuleRegistry {
             Some(ModuleType::Erlang(erl)) => erl
                 .functions
                 .get(&(function, arity))
+                .cloned()
                 .map(ResolvedFunction::Erlang),
             Some(ModuleType::Native(nat)) => nat
                 .functions
@@ -78,17 +231,58 @@ impl ModuleRegistry {
                 } else {
                     erl.functions
                         .get(&(function, arity))
+                        .cloned()
                         .map(ResolvedFunction::Erlang)
                 }
             }
         }
     }
+
+    /// Looks up `function/arity` in `version` of `module`, which may be either the current or the
+    /// old version.
+    pub fn lookup_function_version(
+        &self,
+        module: Atom,
+        function: Atom,
+        arity: usize,
+        version: usize,
+    ) -> Option<Arc<ErlangFunction>> {
+        trace!(
+            "LOOKUP {}:{}/{} (version {})",
+            module,
+            function,
+            arity,
+            version
+        );
+        self.erlang_module(module)
+            .into_iter()
+            .chain(self.old.get(&module))
+            .find(|erl| erl.version == version)
+            .and_then(|erl| erl.functions.get(&(function, arity)).cloned())
+    }
+
+    fn erlang_module(&self, module: Atom) -> Option<&ErlangModule> {
+        match self.map.get(&module) {
+            Some(ModuleType::Erlang(erl)) | Some(ModuleType::Overlayed(erl, _)) => Some(erl),
+            _ => None,
+        }
+    }
 }
 
+pub type DirtyFunction = fn(&Arc<Process>, &[Term]) -> std::result::Result<Term, Exception>;
+
 #[derive(Copy, Clone)]
 pub enum NativeFunctionKind {
     Simple(fn(&Arc<Process>, &[Term]) -> std::result::Result<Term, Exception>),
     Yielding(fn(&Arc<Process>, &[Term]) -> Result),
+    /// Runs on a dirty scheduler while the calling process waits (see `crate::dirty`), so that
+    /// functions that take a long time don't keep other processes from running.  On wasm32, where
+    /// there are no dirty schedulers, it runs as a `Simple` function.
+    Dirty(DirtyFunction),
+    /// Runs in steps that use reductions, trapping when the reductions of the run are used up (see
+    /// `crate::trap`), so that functions whose work grows with their arguments don't keep other
+    /// processes from running until they return.
+    Trapping(Trap),
 }
 
 pub struct NativeModule {
@@ -103,6 +297,16 @@ impl NativeModule {
         }
     }
 
+    /// Adds `name/arity`, with `name` as a string instead of an `Atom`
+    pub fn add_function(
+        &mut self,
+        name: &str,
+        arity: usize,
+        fun: fn(&Arc<Process>, &[Term]) -> std::result::Result<Term, Exception>,
+    ) {
+        self.add_simple(Atom::try_from_str(name).unwrap(), arity, fun);
+    }
+
     pub fn add_simple(
         &mut self,
         name: Atom,
@@ -113,6 +317,16 @@ impl NativeModule {
             .insert((name, arity), NativeFunctionKind::Simple(fun));
     }
 
+    pub fn add_dirty(&mut self, name: Atom, arity: usize, fun: DirtyFunction) {
+        self.functions
+            .insert((name, arity), NativeFunctionKind::Dirty(fun));
+    }
+
+    pub fn add_trapping(&mut self, name: Atom, arity: usize, trap: Trap) {
+        self.functions
+            .insert((name, arity), NativeFunctionKind::Trapping(trap));
+    }
+
     pub fn add_yielding(
         &mut self,
         name: Atom,
@@ -125,33 +339,59 @@ impl NativeModule {
 }
 
 pub struct ErlangFunction {
+    pub module: Atom,
     pub fun: Function,
     pub live: LiveValues,
+    /// The version of the module this function was loaded as part of.
+    pub version: usize,
+    /// The literal area of the module this function was loaded as part of.
+    pub literal_area: Arc<LiteralArea>,
+    /// The constants of this function that have been made, as literals in `literal_area`, or
+    /// `None` for those that can't be literals, such as maps, and are made on the heap each time.
+    pub literal_by_const: Mutex<HashMap<Const, Option<Term>>>,
 }
 
 pub struct ErlangModule {
     pub name: Atom,
-    pub functions: HashMap<(Atom, usize), ErlangFunction>,
+    pub version: usize,
+    pub functions: HashMap<(Atom, usize), Arc<ErlangFunction>>,
+    /// Holds the constants of the functions, which processes reference instead of copying.
+    pub literal_area: Arc<LiteralArea>,
+    /// The attributes from the source the module was compiled from, such as `-vsn` and
+    /// `-behaviour`, which aren't part of the EIR module (see `crate::attributes`).
+    pub attributes: Vec<Attribute>,
+    /// The exported functions, or `None` if all of the functions are exported
+    pub exports: Option<Vec<(Atom, usize)>>,
 }
 
 impl ErlangModule {
-    pub fn from_eir(module: Module) -> Self {
+    pub fn from_eir(module: Module, version: usize) -> Self {
         let name_atom = Atom::try_from_str(module.name.as_str()).unwrap();
+        let literal_area = Arc::new(LiteralArea::new());
         let functions = module
             .functions
             .values()
             .map(|fun| {
                 let nfun = ErlangFunction {
+                    module: name_atom,
                     live: fun.live_values(),
                     fun: fun.clone(),
+                    version,
+                    literal_area: literal_area.clone(),
+                    literal_by_const: Default::default(),
                 };
                 let name = Atom::try_from_str(fun.ident().name.as_str()).unwrap();
-                ((name, fun.ident().arity), nfun)
+                ((name, fun.ident().arity), Arc::new(nfun))
             })
             .collect();
+        let compiled = attributes::take_compiled(name_atom);
         ErlangModule {
             name: name_atom,
+            version,
             functions,
+            literal_area,
+            attributes: compiled.attributes,
+            exports: compiled.exports,
         }
     }
 }
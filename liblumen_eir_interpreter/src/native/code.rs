@@ -0,0 +1,235 @@
+use std::convert::TryInto;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception::{self, Exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, ImproperList, Pid, Term, TypedTerm};
+use liblumen_alloc::badarg;
+
+use lumen_runtime::process::exit_and_wake;
+use lumen_runtime::registry::pid_to_process;
+
+use crate::code_server;
+use crate::compile::{compile_beam, compile_str};
+use crate::module::{LoadError, NativeModule};
+
+pub fn make_code() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("code").unwrap());
+
+    native.add_simple(
+        Atom::try_from_str("load_binary").unwrap(),
+        3,
+        |proc, args| load_binary(proc, args[0], args[1], args[2]),
+    );
+    native.add_simple(
+        Atom::try_from_str("ensure_loaded").unwrap(),
+        1,
+        |proc, args| ensure_loaded(proc, args[0]),
+    );
+    native.add_simple(Atom::try_from_str("which").unwrap(), 1, |proc, args| {
+        which(proc, args[0])
+    });
+    native.add_simple(Atom::try_from_str("get_path").unwrap(), 0, |proc, _args| {
+        get_path(proc)
+    });
+    native.add_simple(Atom::try_from_str("add_patha").unwrap(), 1, |proc, args| {
+        add_path(proc, args[0], Placement::Front)
+    });
+    native.add_simple(Atom::try_from_str("add_pathz").unwrap(), 1, |proc, args| {
+        add_path(proc, args[0], Placement::Back)
+    });
+    native.add_simple(Atom::try_from_str("purge").unwrap(), 1, |_proc, args| {
+        purge(args[0])
+    });
+    native.add_simple(
+        Atom::try_from_str("soft_purge").unwrap(),
+        1,
+        |_proc, args| soft_purge(args[0]),
+    );
+
+    native
+}
+
+/// `code:load_binary/3`.  `binary` is either a BEAM file compiled with `debug_info`, which is
+/// loaded from its abstract code, or, as an extension, the Erlang source of the module.
+fn load_binary(
+    process: &Arc<Process>,
+    module: Term,
+    _filename: Term,
+    binary: Term,
+) -> exception::Result {
+    let module_atom: Atom = module.try_into()?;
+    let bytes = process.bytes_from_binary(binary)?;
+
+    let compiled = if bytes.starts_with(b"FOR1") {
+        compile_beam(bytes).map_err(|_| ())
+    } else {
+        let source = std::str::from_utf8(bytes).map_err(|_| badarg!())?;
+        compile_str(source)
+    };
+
+    let result = match compiled {
+        Ok(eir_module) if Atom::try_from_str(eir_module.name.as_str()).unwrap() == module_atom => {
+            crate::VM
+                .modules
+                .write()
+                .unwrap()
+                .load_erlang_module(eir_module)
+        }
+        _ => Err(LoadError::BadFile),
+    };
+
+    match result {
+        Ok(_) => loaded(process, module),
+        Err(err) => load_error(process, err),
+    }
+}
+
+/// `code:ensure_loaded/1`.  Loads `module` from the code path if it is not already loaded.
+fn ensure_loaded(process: &Process, module: Term) -> exception::Result {
+    let module_atom: Atom = module.try_into()?;
+
+    match code_server::ensure_loaded(&crate::VM, module_atom) {
+        Ok(()) => loaded(process, module),
+        Err(err) => load_error(process, err),
+    }
+}
+
+/// `code:which/1`
+fn which(process: &Process, module: Term) -> exception::Result {
+    let module_atom: Atom = module.try_into()?;
+
+    match code_server::which(&crate::VM, module_atom) {
+        Some(path) => Ok(process.charlist_from_str(&path.to_string_lossy())?),
+        None => Ok(atom_unchecked("non_existing")),
+    }
+}
+
+/// `code:get_path/0`
+fn get_path(process: &Process) -> exception::Result {
+    let code_path = crate::VM.code_path.read().unwrap();
+    let mut directories = Vec::with_capacity(code_path.len());
+
+    for directory in code_path.iter() {
+        directories.push(process.charlist_from_str(&directory.to_string_lossy())?);
+    }
+
+    Ok(process.list_from_slice(&directories)?)
+}
+
+enum Placement {
+    Front,
+    Back,
+}
+
+/// `code:add_patha/1` and `code:add_pathz/1`
+fn add_path(process: &Process, directory: Term, placement: Placement) -> exception::Result {
+    let directory_path = PathBuf::from(string(directory)?);
+
+    if !directory_path.is_dir() {
+        return error(process, "bad_directory");
+    }
+
+    let mut code_path = crate::VM.code_path.write().unwrap();
+    code_path.retain(|path| path != &directory_path);
+
+    match placement {
+        Placement::Front => code_path.insert(0, directory_path),
+        Placement::Back => code_path.push(directory_path),
+    }
+
+    Ok(true.into())
+}
+
+/// `erlang:check_process_code/2`
+pub fn check_process_code(pid: Term, module: Term) -> exception::Result {
+    let pid_pid: Pid = pid.try_into()?;
+    let module_atom: Atom = module.try_into()?;
+
+    Ok(crate::VM
+        .modules
+        .read()
+        .unwrap()
+        .check_process_code(pid_pid, module_atom)
+        .into())
+}
+
+/// `code:purge/1`.  Kills the processes still executing the old version of `module` and then
+/// removes the old version.  Returns `true` if any processes were killed.
+fn purge(module: Term) -> exception::Result {
+    let module_atom: Atom = module.try_into()?;
+
+    let pids = {
+        let mut modules = crate::VM.modules.write().unwrap();
+        let pids = modules.processes_on_old_code(module_atom);
+        modules.purge(module_atom);
+
+        pids
+    };
+
+    for pid in &pids {
+        if let Some(arc_process) = pid_to_process(pid) {
+            exit_and_wake(&arc_process, atom_unchecked("killed"))?;
+        }
+    }
+
+    Ok((!pids.is_empty()).into())
+}
+
+/// `code:soft_purge/1`.  Removes the old version of `module` only if no processes are still
+/// executing it.  Returns `false` if processes are still executing the old version.
+fn soft_purge(module: Term) -> exception::Result {
+    let module_atom: Atom = module.try_into()?;
+
+    let mut modules = crate::VM.modules.write().unwrap();
+
+    if modules.processes_on_old_code(module_atom).is_empty() {
+        modules.purge(module_atom);
+
+        Ok(true.into())
+    } else {
+        Ok(false.into())
+    }
+}
+
+fn loaded(process: &Process, module: Term) -> exception::Result {
+    let tag = atom_unchecked("module");
+
+    Ok(process.tuple_from_slice(&[tag, module])?)
+}
+
+fn load_error(process: &Process, err: LoadError) -> exception::Result {
+    let reason = match err {
+        LoadError::NoFile => "nofile",
+        LoadError::BadFile => "badfile",
+        LoadError::NotPurged => "not_purged",
+    };
+
+    error(process, reason)
+}
+
+fn string(list: Term) -> Result<String, Exception> {
+    match list.to_typed_term().unwrap() {
+        TypedTerm::Nil => Ok("".to_owned()),
+        TypedTerm::List(cons) => cons
+            .into_iter()
+            .map(|result| match result {
+                Ok(term) => {
+                    let c: char = term.try_into()?;
+
+                    Ok(c)
+                }
+                Err(ImproperList { .. }) => Err(badarg!().into()),
+            })
+            .collect(),
+        _ => Err(badarg!().into()),
+    }
+}
+
+fn error(process: &Process, reason: &str) -> exception::Result {
+    let tag = atom_unchecked("error");
+    let reason = atom_unchecked(reason);
+
+    Ok(process.tuple_from_slice(&[tag, reason])?)
+}
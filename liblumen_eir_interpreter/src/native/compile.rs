@@ -0,0 +1,65 @@
+//! `compile:forms/1,2` for interpreted code that generates modules as abstract format forms.
+//!
+//! The forms are lowered to EIR to check that they compile, and returned as a BEAM file holding
+//! only an `Abst` chunk, which `code:load_binary/3` loads as it would a BEAM file compiled with
+//! `debug_info`.
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception::{self, Exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Term};
+
+use liblumen_beam::beam::reader::chunk::RawChunk;
+use liblumen_beam::beam::reader::RawBeamFile;
+use liblumen_beam::syntax::ast::format::raw_abstract_v1::AbstractCode;
+
+use lumen_runtime::external_term_format;
+
+use crate::compile::compile_forms;
+use crate::module::NativeModule;
+
+pub fn make_compile() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("compile").unwrap());
+
+    native.add_simple(Atom::try_from_str("forms").unwrap(), 1, |proc, args| {
+        forms(proc, args[0])
+    });
+    // Options only affect code generation by `erlc`, so they are ignored
+    native.add_simple(Atom::try_from_str("forms").unwrap(), 2, |proc, args| {
+        forms(proc, args[0])
+    });
+
+    native
+}
+
+/// Returns `{ok, Module, Binary}`, or `error` if the forms do not compile.
+fn forms(process: &Process, forms: Term) -> exception::Result {
+    let forms = external_term_format::to_etf(forms).map_err(|error| -> Exception {
+        match error {
+            external_term_format::Error::Alloc(alloc) => alloc.into(),
+            _ => badarg!().into(),
+        }
+    })?;
+
+    let module = match compile_forms(forms.clone()) {
+        Ok(module) => module,
+        Err(()) => return Ok(atom_unchecked("error")),
+    };
+
+    let mut data = Vec::new();
+    AbstractCode::from_forms(forms)
+        .code
+        .encode(&mut data)
+        .map_err(|_| badarg!())?;
+
+    let mut beam = RawBeamFile::new();
+    beam.push_chunk(RawChunk { id: *b"Abst", data });
+    let mut bytes = Vec::new();
+    beam.to_writer(&mut bytes).map_err(|_| badarg!())?;
+
+    Ok(process.tuple_from_slice(&[
+        atom_unchecked("ok"),
+        atom_unchecked(module.name.as_str()),
+        process.binary_from_bytes(&bytes)?,
+    ])?)
+}
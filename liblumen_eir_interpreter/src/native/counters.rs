@@ -0,0 +1,163 @@
+//! A native `counters` with the semantics of the erts `counters` module, for cheap metrics shared
+//! between processes.
+//!
+//! A counter array is a resource, so it lives off the process heaps and every copy of the reference
+//! refers to the same counters.  Counters are 64-bit signed integers that wrap on overflow.
+//!
+//! With `atomics`, each counter is a single atomic.  With `write_concurrency`, each counter is
+//! striped over several atomics, picked by the calling thread, so concurrent `add`s and `sub`s
+//! from different threads mostly update different atomics; `get` sums the stripes and `put` is not
+//! atomic with respect to concurrent updates.
+
+use std::collections::hash_map::DefaultHasher;
+use std::convert::TryInto;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception::{self, Exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::resource;
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Term};
+
+use crate::module::NativeModule;
+
+/// Stripes of each counter with `write_concurrency`
+const STRIPES: usize = 16;
+
+pub fn make_counters() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("counters").unwrap());
+
+    native.add_simple(Atom::try_from_str("new").unwrap(), 1, |proc, args| {
+        new(proc, args[0], Term::NIL)
+    });
+    native.add_simple(Atom::try_from_str("new").unwrap(), 2, |proc, args| {
+        new(proc, args[0], args[1])
+    });
+    native.add_simple(Atom::try_from_str("add").unwrap(), 3, |_proc, args| {
+        let increment = integer(args[2])?;
+        counters(args[0])?.add(args[1], increment)
+    });
+    native.add_simple(Atom::try_from_str("sub").unwrap(), 3, |_proc, args| {
+        let decrement = integer(args[2])?;
+        counters(args[0])?.add(args[1], decrement.wrapping_neg())
+    });
+    native.add_simple(Atom::try_from_str("get").unwrap(), 2, |proc, args| {
+        let value = counters(args[0])?.get(args[1])?;
+        Ok(proc.integer(value)?)
+    });
+    native.add_simple(Atom::try_from_str("put").unwrap(), 3, |_proc, args| {
+        let value = integer(args[2])?;
+        counters(args[0])?.put(args[1], value)
+    });
+
+    native
+}
+
+fn new(process: &Process, size: Term, options: Term) -> exception::Result {
+    let size: usize = size.try_into()?;
+
+    if size == 0 {
+        return Err(badarg!().into());
+    }
+
+    let mut stripes = 1;
+
+    for result in options.list_iter()? {
+        let option: Atom = result?.try_into()?;
+
+        match option.name() {
+            "atomics" => stripes = 1,
+            "write_concurrency" => stripes = STRIPES,
+            _ => return Err(badarg!().into()),
+        }
+    }
+
+    let cells = (0..(size * stripes)).map(|_| AtomicI64::new(0)).collect();
+    let counters = Counters {
+        cells: Arc::new(cells),
+        stripes,
+    };
+
+    Ok(process.resource(Box::new(counters))?)
+}
+
+fn counters(term: Term) -> Result<Counters, Exception> {
+    let reference: resource::Reference = term.try_into().map_err(|_| badarg!())?;
+
+    reference
+        .downcast_ref::<Counters>()
+        .cloned()
+        .ok_or_else(|| badarg!().into())
+}
+
+fn integer(term: Term) -> Result<i64, Exception> {
+    let i: isize = term.try_into()?;
+
+    Ok(i as i64)
+}
+
+#[derive(Clone)]
+struct Counters {
+    /// The stripes of each counter, one after another
+    cells: Arc<Box<[AtomicI64]>>,
+    stripes: usize,
+}
+impl Counters {
+    fn len(&self) -> usize {
+        self.cells.len() / self.stripes
+    }
+
+    /// The stripes of the counter at the one-based `index`
+    fn stripes(&self, index: Term) -> Result<&[AtomicI64], Exception> {
+        let index: usize = index.try_into()?;
+
+        if 1 <= index && index <= self.len() {
+            let start = (index - 1) * self.stripes;
+
+            Ok(&self.cells[start..(start + self.stripes)])
+        } else {
+            Err(badarg!().into())
+        }
+    }
+
+    /// The stripe that the current thread updates
+    fn stripe(&self) -> usize {
+        if self.stripes == 1 {
+            0
+        } else {
+            let mut hasher = DefaultHasher::new();
+            thread::current().id().hash(&mut hasher);
+
+            (hasher.finish() as usize) % self.stripes
+        }
+    }
+
+    fn add(&self, index: Term, increment: i64) -> exception::Result {
+        let stripe = self.stripe();
+        self.stripes(index)?[stripe].fetch_add(increment, Ordering::Relaxed);
+
+        Ok(atom_unchecked("ok"))
+    }
+
+    fn get(&self, index: Term) -> Result<i64, Exception> {
+        let value = self.stripes(index)?.iter().fold(0_i64, |sum, cell| {
+            sum.wrapping_add(cell.load(Ordering::Relaxed))
+        });
+
+        Ok(value)
+    }
+
+    fn put(&self, index: Term, value: i64) -> exception::Result {
+        let stripes = self.stripes(index)?;
+        stripes[0].store(value, Ordering::Relaxed);
+
+        for cell in &stripes[1..] {
+            cell.store(0, Ordering::Relaxed);
+        }
+
+        Ok(atom_unchecked("ok"))
+    }
+}
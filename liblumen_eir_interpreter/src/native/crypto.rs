@@ -0,0 +1,17 @@
+use liblumen_alloc::erts::term::Atom;
+
+use lumen_runtime::otp::crypto;
+
+use crate::module::NativeModule;
+
+pub fn make_crypto() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("crypto").unwrap());
+
+    native.add_simple(
+        Atom::try_from_str("strong_rand_bytes").unwrap(),
+        1,
+        |proc, args| crypto::strong_rand_bytes_1(args[0], proc),
+    );
+
+    native
+}
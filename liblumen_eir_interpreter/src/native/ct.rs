@@ -0,0 +1,159 @@
+//! The parts of Common Test's `ct` module that test cases call, for suites run with
+//! `crate::suite`.
+//!
+//! `ct:log` and `ct:pal` messages are kept until the runner takes them with `take_log`, so that
+//! they can be reported with the case that logged them.  `ct:get_config` looks keys up in the
+//! config given to `set_config`.  Formats are formatted with `io_lib:format/2` (see
+//! `lumen_runtime::otp::io_lib`).
+
+use std::convert::TryInto;
+use std::sync::{Mutex, RwLock};
+
+use lazy_static::lazy_static;
+
+use liblumen_alloc::erts::exception::{self, Exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Term, TypedTerm};
+use liblumen_alloc::{badarg, exit, CloneToProcess};
+
+use lumen_runtime::otp::io_lib::format;
+
+use crate::module::NativeModule;
+
+lazy_static! {
+    static ref LOG: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    /// A list of `{Key, Value}`, in a heap that outlives every process
+    static ref CONFIG: RwLock<Term> = RwLock::new(Term::NIL);
+}
+
+pub fn make_ct() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("ct").unwrap());
+
+    native.add_simple(Atom::try_from_str("fail").unwrap(), 1, |proc, args| {
+        fail(proc, args[0])
+    });
+    native.add_simple(Atom::try_from_str("fail").unwrap(), 2, |proc, args| {
+        let reason = format(args[0], args[1])?;
+        fail(proc, proc.charlist_from_str(&reason)?)
+    });
+    native.add_simple(Atom::try_from_str("log").unwrap(), 1, |_proc, args| {
+        log(format(args[0], Term::NIL)?, false)
+    });
+    native.add_simple(Atom::try_from_str("log").unwrap(), 2, |_proc, args| {
+        log(format(args[0], args[1])?, false)
+    });
+    native.add_simple(Atom::try_from_str("pal").unwrap(), 1, |_proc, args| {
+        log(format(args[0], Term::NIL)?, true)
+    });
+    native.add_simple(Atom::try_from_str("pal").unwrap(), 2, |_proc, args| {
+        log(format(args[0], args[1])?, true)
+    });
+    native.add_simple(Atom::try_from_str("comment").unwrap(), 1, |_proc, args| {
+        log(
+            format!(
+                "comment: {}",
+                text(args[0]).unwrap_or_else(|_| args[0].to_string())
+            ),
+            false,
+        )
+    });
+    native.add_simple(
+        Atom::try_from_str("get_config").unwrap(),
+        1,
+        |proc, args| get_config(proc, args[0], atom_unchecked("undefined")),
+    );
+    native.add_simple(
+        Atom::try_from_str("get_config").unwrap(),
+        2,
+        |proc, args| get_config(proc, args[0], args[1]),
+    );
+    // Cases run until the runner's timetrap, whatever they ask for
+    native.add_simple(
+        Atom::try_from_str("timetrap").unwrap(),
+        1,
+        |_proc, _args| Ok(atom_unchecked("ok")),
+    );
+
+    native
+}
+
+/// Sets the config for `ct:get_config`.  `config` must be a list of `{Key, Value}` that is never
+/// garbage collected, such as the result of a call in `crate::call_result`.
+pub fn set_config(config: Term) {
+    *CONFIG.write().unwrap() = config;
+}
+
+/// Takes the messages logged since the last call.
+pub fn take_log() -> Vec<String> {
+    std::mem::replace(&mut *LOG.lock().unwrap(), Vec::new())
+}
+
+fn fail(process: &Process, reason: Term) -> exception::Result {
+    let tag = atom_unchecked("test_case_failed");
+    let reason = process.tuple_from_slice(&[tag, reason])?;
+
+    Err(exit!(reason).into())
+}
+
+fn log(message: String, print: bool) -> exception::Result {
+    if print {
+        lumen_runtime::system::io::puts(&message);
+    }
+
+    LOG.lock().unwrap().push(message);
+
+    Ok(atom_unchecked("ok"))
+}
+
+/// The value of `key`, or of `{Key, SubKey}` in the value of `Key`, or `default`
+fn get_config(process: &Process, key: Term, default: Term) -> exception::Result {
+    let config = *CONFIG.read().unwrap();
+
+    let value = match key.tuple_elements() {
+        Ok(elements) => {
+            let elements: Vec<Term> = elements.collect();
+
+            if elements.len() != 2 {
+                return Err(badarg!().into());
+            }
+
+            keyfind(config, elements[0]).and_then(|value| keyfind(value, elements[1]))
+        }
+        Err(_) => keyfind(config, key),
+    };
+
+    match value {
+        Some(value) => Ok(value.clone_to_process(process)),
+        None => Ok(default),
+    }
+}
+
+fn keyfind(list: Term, key: Term) -> Option<Term> {
+    list.list_iter().ok()?.find_map(|result| {
+        let elements: Vec<Term> = result.ok()?.tuple_elements().ok()?.collect();
+
+        if elements.len() == 2 && elements[0] == key {
+            Some(elements[1])
+        } else {
+            None
+        }
+    })
+}
+
+/// The text of a string, binary or atom
+fn text(term: Term) -> Result<String, Exception> {
+    match term.to_typed_term().unwrap() {
+        TypedTerm::Atom(atom) => Ok(atom.name().to_string()),
+        TypedTerm::Nil | TypedTerm::List(_) => {
+            let mut string = String::new();
+
+            for result in term.list_iter()? {
+                let c: char = result?.try_into()?;
+                string.push(c);
+            }
+
+            Ok(string)
+        }
+        _ => Ok(term.try_into()?),
+    }
+}
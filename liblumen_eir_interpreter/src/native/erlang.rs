@@ -12,8 +12,53 @@ pub fn make_erlang() -> NativeModule {
     });
 
     native.add_simple(Atom::try_from_str("/").unwrap(), 2, |proc, args| {
+        erlang::divide_2(args[0], args[1], proc)
+    });
+    native.add_simple(Atom::try_from_str("div").unwrap(), 2, |proc, args| {
         erlang::div_2(args[0], args[1], proc)
     });
+    native.add_simple(Atom::try_from_str("rem").unwrap(), 2, |proc, args| {
+        erlang::rem_2(args[0], args[1], proc)
+    });
+
+    native.add_simple(Atom::try_from_str("++").unwrap(), 2, |proc, args| {
+        erlang::concatenate_2(args[0], args[1], proc)
+    });
+    native.add_simple(Atom::try_from_str("--").unwrap(), 2, |proc, args| {
+        erlang::subtract_list_2(args[0], args[1], proc)
+    });
+
+    native.add_simple(Atom::try_from_str("band").unwrap(), 2, |proc, args| {
+        erlang::band_2(args[0], args[1], proc)
+    });
+    native.add_simple(Atom::try_from_str("bor").unwrap(), 2, |proc, args| {
+        erlang::bor_2(args[0], args[1], proc)
+    });
+    native.add_simple(Atom::try_from_str("bxor").unwrap(), 2, |proc, args| {
+        erlang::bxor_2(args[0], args[1], proc)
+    });
+    native.add_simple(Atom::try_from_str("bnot").unwrap(), 1, |proc, args| {
+        erlang::bnot_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("bsl").unwrap(), 2, |proc, args| {
+        erlang::bsl_2(args[0], args[1], proc)
+    });
+    native.add_simple(Atom::try_from_str("bsr").unwrap(), 2, |proc, args| {
+        erlang::bsr_2(args[0], args[1], proc)
+    });
+
+    native.add_simple(Atom::try_from_str("ceil").unwrap(), 1, |proc, args| {
+        erlang::ceil_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("floor").unwrap(), 1, |proc, args| {
+        erlang::floor_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("round").unwrap(), 1, |proc, args| {
+        erlang::round_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("trunc").unwrap(), 1, |proc, args| {
+        erlang::trunc_1(args[0], proc)
+    });
 
     native.add_simple(Atom::try_from_str("<").unwrap(), 2, |_proc, args| {
         Ok(erlang::is_less_than_2(args[0], args[1]))
@@ -33,6 +78,12 @@ pub fn make_erlang() -> NativeModule {
     native.add_simple(Atom::try_from_str("=:=").unwrap(), 2, |_proc, args| {
         Ok(erlang::are_exactly_equal_2(args[0], args[1]))
     });
+    native.add_simple(Atom::try_from_str("/=").unwrap(), 2, |_proc, args| {
+        Ok(erlang::are_not_equal_after_conversion_2(args[0], args[1]))
+    });
+    native.add_simple(Atom::try_from_str("=/=").unwrap(), 2, |_proc, args| {
+        Ok(erlang::are_exactly_not_equal_2(args[0], args[1]))
+    });
 
     native.add_simple(Atom::try_from_str("spawn_opt").unwrap(), 4, |proc, args| {
         match args[3].to_typed_term().unwrap() {
@@ -106,6 +157,35 @@ pub fn make_erlang() -> NativeModule {
         },
     );
 
+    // other nodes are only connected to off the web
+    #[cfg(not(target_arch = "wasm32"))]
+    native.add_yielding(Atom::try_from_str("spawn").unwrap(), 4, |proc, args| {
+        crate::native::lumen_distribution::apply_spawn(proc, "spawn", args)
+    });
+    #[cfg(not(target_arch = "wasm32"))]
+    native.add_yielding(
+        Atom::try_from_str("spawn_link").unwrap(),
+        4,
+        |proc, args| crate::native::lumen_distribution::apply_spawn(proc, "spawn_link", args),
+    );
+    #[cfg(not(target_arch = "wasm32"))]
+    native.add_simple(
+        Atom::try_from_str("monitor_node").unwrap(),
+        2,
+        |proc, args| crate::native::lumen_distribution::monitor_node(proc, args[0], args[1]),
+    );
+
+    native.add_simple(
+        Atom::try_from_str("send_after").unwrap(),
+        3,
+        |proc, args| erlang::send_after_3(args[0], args[1], args[2], proc.clone()),
+    );
+    native.add_simple(
+        Atom::try_from_str("cancel_timer").unwrap(),
+        1,
+        |proc, args| erlang::cancel_timer_1(args[0], proc),
+    );
+
     native.add_simple(Atom::try_from_str("exit").unwrap(), 1, |_proc, args| {
         panic!("{:?}", args[0]);
         //Ok(erlang::exit_1::native(args[0]).unwrap())
@@ -114,9 +194,40 @@ pub fn make_erlang() -> NativeModule {
     native.add_simple(Atom::try_from_str("monitor").unwrap(), 2, |proc, args| {
         erlang::monitor_2::native(proc, args[0], args[1])
     });
+    native.add_simple(Atom::try_from_str("monitor").unwrap(), 3, |proc, args| {
+        erlang::monitor_3::native(proc, args[0], args[1], args[2])
+    });
     native.add_simple(Atom::try_from_str("demonitor").unwrap(), 2, |proc, args| {
         erlang::demonitor_2::native(proc, args[0], args[1])
     });
+    native.add_simple(Atom::try_from_str("unalias").unwrap(), 1, |proc, args| {
+        erlang::unalias_1(args[0], proc)
+    });
+
+    // ports to external programs are only opened off the web
+    #[cfg(not(target_arch = "wasm32"))]
+    native.add_simple(Atom::try_from_str("open_port").unwrap(), 2, |proc, args| {
+        erlang::open_port_2(args[0], args[1], proc.clone())
+    });
+    // a command to a busy port suspends the calling process
+    #[cfg(not(target_arch = "wasm32"))]
+    native.add_yielding(
+        Atom::try_from_str("port_command").unwrap(),
+        2,
+        crate::port::command,
+    );
+    #[cfg(not(target_arch = "wasm32"))]
+    native.add_yielding(
+        Atom::try_from_str("port_command").unwrap(),
+        3,
+        crate::port::command,
+    );
+    #[cfg(not(target_arch = "wasm32"))]
+    native.add_simple(
+        Atom::try_from_str("port_close").unwrap(),
+        1,
+        |_proc, args| erlang::port_close_1(args[0]),
+    );
 
     native.add_simple(Atom::try_from_str("register").unwrap(), 2, |proc, args| {
         erlang::register_2(args[0], args[1], proc.clone())
@@ -148,6 +259,19 @@ pub fn make_erlang() -> NativeModule {
     native.add_simple(Atom::try_from_str("self").unwrap(), 0, |proc, _args| {
         Ok(proc.pid_term())
     });
+    native.add_simple(
+        Atom::try_from_str("group_leader").unwrap(),
+        0,
+        |proc, _args| erlang::group_leader_0(proc),
+    );
+    native.add_simple(
+        Atom::try_from_str("group_leader").unwrap(),
+        2,
+        |proc, args| erlang::group_leader_2(args[0], args[1], proc.clone()),
+    );
+    native.add_simple(Atom::try_from_str("make_ref").unwrap(), 0, |proc, _args| {
+        erlang::make_ref_0(proc)
+    });
 
     native.add_simple(
         Atom::try_from_str("is_integer").unwrap(),
@@ -196,6 +320,17 @@ pub fn make_erlang() -> NativeModule {
         Ok(erlang::is_bitstring_1(args[0]))
     });
 
+    native.add_trapping(
+        Atom::try_from_str("length").unwrap(),
+        1,
+        erlang::length_1::TRAP,
+    );
+    native.add_trapping(
+        Atom::try_from_str("binary_to_list").unwrap(),
+        1,
+        erlang::binary_to_list_1::TRAP,
+    );
+
     native.add_simple(
         Atom::try_from_str("monotonic_time").unwrap(),
         0,
@@ -212,12 +347,54 @@ pub fn make_erlang() -> NativeModule {
         crate::code::apply(proc)
     });
 
+    // a synchronous collection returns once the process is collected
+    for arity in 0..=2 {
+        native.add_yielding(
+            Atom::try_from_str("garbage_collect").unwrap(),
+            arity,
+            crate::garbage_collect::collect,
+        );
+    }
+
+    native.add_simple(
+        Atom::try_from_str("suspend_process").unwrap(),
+        1,
+        |proc, args| erlang::suspend_process_1::native(proc, args[0]),
+    );
+    native.add_simple(
+        Atom::try_from_str("suspend_process").unwrap(),
+        2,
+        |proc, args| erlang::suspend_process_2::native(proc, args[0], args[1]),
+    );
+    native.add_simple(
+        Atom::try_from_str("resume_process").unwrap(),
+        1,
+        |proc, args| erlang::resume_process_1::native(proc, args[0]),
+    );
+
+    native.add_simple(Atom::try_from_str("trace").unwrap(), 3, |proc, args| {
+        erlang::trace_3::native(proc, args[0], args[1], args[2])
+    });
+
+    native.add_simple(Atom::try_from_str("yield").unwrap(), 0, |proc, _args| {
+        Ok(erlang::yield_0::native(proc))
+    });
+
+    native.add_yielding(Atom::try_from_str("hibernate").unwrap(), 3, |proc, args| {
+        crate::code::hibernate(proc, args[2], args[3], args[4])
+    });
+
     native.add_simple(Atom::try_from_str("node").unwrap(), 0, |_proc, _args| {
         Ok(erlang::node_0())
     });
-    native.add_simple(Atom::try_from_str("node").unwrap(), 1, |_proc, _args| {
-        Ok(atom_unchecked("nonode@nohost"))
+    native.add_simple(Atom::try_from_str("node").unwrap(), 1, |_proc, args| {
+        erlang::node_1(args[0])
     });
+    native.add_simple(
+        Atom::try_from_str("is_alive").unwrap(),
+        0,
+        |_proc, _args| Ok(erlang::is_alive_0()),
+    );
     native.add_simple(Atom::try_from_str("whereis").unwrap(), 1, |_proc, args| {
         erlang::whereis_1(args[0])
     });
@@ -227,6 +404,11 @@ pub fn make_erlang() -> NativeModule {
         2,
         |proc, args| erlang::process_info_2::native(proc, args[0], args[1]),
     );
+    native.add_simple(
+        Atom::try_from_str("processes").unwrap(),
+        0,
+        |proc, _args| erlang::processes_0(proc),
+    );
 
     native.add_simple(Atom::try_from_str("get").unwrap(), 1, |proc, args| {
         Ok(proc.get(args[0]))
@@ -241,9 +423,99 @@ pub fn make_erlang() -> NativeModule {
         |proc, args| erlang::convert_time_unit_3::native(proc, args[0], args[1], args[2]),
     );
 
+    native.add_simple(
+        Atom::try_from_str("check_process_code").unwrap(),
+        2,
+        |_proc, args| crate::native::check_process_code(args[0], args[1]),
+    );
+
     native.add_simple(Atom::try_from_str("element").unwrap(), 2, |_proc, args| {
         erlang::element_2(args[0], args[1])
     });
+    native.add_simple(
+        Atom::try_from_str("tuple_to_list").unwrap(),
+        1,
+        |proc, args| erlang::tuple_to_list_1(args[0], proc),
+    );
+    native.add_simple(
+        Atom::try_from_str("list_to_tuple").unwrap(),
+        1,
+        |proc, args| erlang::list_to_tuple_1(args[0], proc),
+    );
+    native.add_simple(
+        Atom::try_from_str("make_tuple").unwrap(),
+        2,
+        |proc, args| erlang::make_tuple_2(args[0], args[1], proc),
+    );
+    native.add_simple(
+        Atom::try_from_str("make_tuple").unwrap(),
+        3,
+        |proc, args| erlang::make_tuple_3(args[0], args[1], args[2], proc),
+    );
+    native.add_simple(Atom::try_from_str("adler32").unwrap(), 1, |proc, args| {
+        erlang::adler32_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("adler32").unwrap(), 2, |proc, args| {
+        erlang::adler32_2(args[0], args[1], proc)
+    });
+    native.add_simple(Atom::try_from_str("crc32").unwrap(), 1, |proc, args| {
+        erlang::crc32_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("crc32").unwrap(), 2, |proc, args| {
+        erlang::crc32_2(args[0], args[1], proc)
+    });
+    native.add_simple(Atom::try_from_str("display").unwrap(), 1, |_proc, args| {
+        erlang::display_1(args[0])
+    });
+    native.add_simple(
+        Atom::try_from_str("display_string").unwrap(),
+        1,
+        |_proc, args| erlang::display_string_1(args[0]),
+    );
+    native.add_simple(Atom::try_from_str("md5").unwrap(), 1, |proc, args| {
+        erlang::md5_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("md5_init").unwrap(), 0, |proc, _args| {
+        erlang::md5_init_0(proc)
+    });
+    native.add_simple(
+        Atom::try_from_str("md5_update").unwrap(),
+        2,
+        |proc, args| erlang::md5_update_2(args[0], args[1], proc),
+    );
+    native.add_simple(Atom::try_from_str("md5_final").unwrap(), 1, |proc, args| {
+        erlang::md5_final_1(args[0], proc)
+    });
+    native.add_simple(
+        Atom::try_from_str("atom_to_binary").unwrap(),
+        1,
+        |proc, args| erlang::atom_to_binary_1(args[0], proc),
+    );
+    native.add_simple(
+        Atom::try_from_str("atom_to_binary").unwrap(),
+        2,
+        |proc, args| erlang::atom_to_binary_2(args[0], args[1], proc),
+    );
+    native.add_simple(
+        Atom::try_from_str("binary_to_atom").unwrap(),
+        2,
+        |proc, args| erlang::binary_to_atom_2(args[0], args[1], proc),
+    );
+    native.add_simple(
+        Atom::try_from_str("binary_to_existing_atom").unwrap(),
+        2,
+        |proc, args| erlang::binary_to_existing_atom_2(args[0], args[1], proc),
+    );
+    native.add_simple(
+        Atom::try_from_str("term_to_binary").unwrap(),
+        1,
+        |proc, args| erlang::term_to_binary_1(args[0], proc),
+    );
+    native.add_simple(
+        Atom::try_from_str("term_to_binary").unwrap(),
+        2,
+        |proc, args| erlang::term_to_binary_2(args[0], args[1], proc),
+    );
 
     native
 }
@@ -0,0 +1,54 @@
+use liblumen_alloc::erts::term::Atom;
+use lumen_runtime::otp::error_logger;
+
+use crate::module::NativeModule;
+
+pub fn make_error_logger() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("error_logger").unwrap());
+
+    native.add_simple(
+        Atom::try_from_str("error_msg").unwrap(),
+        1,
+        |_proc, args| error_logger::error_msg_1(args[0]),
+    );
+    native.add_simple(
+        Atom::try_from_str("error_msg").unwrap(),
+        2,
+        |_proc, args| error_logger::error_msg_2(args[0], args[1]),
+    );
+    native.add_simple(
+        Atom::try_from_str("error_report").unwrap(),
+        1,
+        |_proc, args| error_logger::error_report_1(args[0]),
+    );
+
+    native.add_simple(Atom::try_from_str("info_msg").unwrap(), 1, |_proc, args| {
+        error_logger::info_msg_1(args[0])
+    });
+    native.add_simple(Atom::try_from_str("info_msg").unwrap(), 2, |_proc, args| {
+        error_logger::info_msg_2(args[0], args[1])
+    });
+    native.add_simple(
+        Atom::try_from_str("info_report").unwrap(),
+        1,
+        |_proc, args| error_logger::info_report_1(args[0]),
+    );
+
+    native.add_simple(
+        Atom::try_from_str("warning_msg").unwrap(),
+        1,
+        |_proc, args| error_logger::warning_msg_1(args[0]),
+    );
+    native.add_simple(
+        Atom::try_from_str("warning_msg").unwrap(),
+        2,
+        |_proc, args| error_logger::warning_msg_2(args[0], args[1]),
+    );
+    native.add_simple(
+        Atom::try_from_str("warning_report").unwrap(),
+        1,
+        |_proc, args| error_logger::warning_report_1(args[0]),
+    );
+
+    native
+}
@@ -0,0 +1,26 @@
+use liblumen_alloc::erts::term::Atom;
+use lumen_runtime::otp::file;
+
+use crate::module::NativeModule;
+
+pub fn make_file() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("file").unwrap());
+
+    native.add_simple(Atom::try_from_str("read_file").unwrap(), 1, |proc, args| {
+        file::read_file_1::native(proc, args[0])
+    });
+
+    native.add_simple(
+        Atom::try_from_str("write_file").unwrap(),
+        2,
+        |proc, args| file::write_file_2::native(proc, args[0], args[1]),
+    );
+
+    native.add_simple(
+        Atom::try_from_str("write_file").unwrap(),
+        3,
+        |proc, args| file::write_file_3::native(proc, args[0], args[1], args[2]),
+    );
+
+    native
+}
@@ -0,0 +1,62 @@
+//! The subset of `global` that registers names across all connected nodes, backed by
+//! `lumen_runtime::distribution::global`.
+
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Term};
+use liblumen_alloc::{badarg, exit};
+
+use lumen_runtime::distribution::{self, global};
+
+use crate::module::NativeModule;
+
+pub fn make_global() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("global").unwrap());
+
+    native.add_simple(
+        Atom::try_from_str("register_name").unwrap(),
+        2,
+        |proc, args| register_name(proc, args[0], args[1]),
+    );
+    native.add_simple(
+        Atom::try_from_str("whereis_name").unwrap(),
+        1,
+        |proc, args| whereis_name(proc, args[0]),
+    );
+    native.add_simple(Atom::try_from_str("send").unwrap(), 2, |proc, args| {
+        send(proc, args[0], args[1])
+    });
+
+    native
+}
+
+fn register_name(process: &Arc<Process>, name: Term, pid: Term) -> exception::Result {
+    match global::register_name(process, name, pid) {
+        Ok(true) => Ok(atom_unchecked("yes")),
+        Ok(false) => Ok(atom_unchecked("no")),
+        Err(distribution::Error::Alloc(alloc)) => Err(alloc.into()),
+        // names that can't be sent to other nodes, such as funs
+        Err(_) => Err(badarg!().into()),
+    }
+}
+
+fn whereis_name(process: &Arc<Process>, name: Term) -> exception::Result {
+    global::whereis_name(process, name).map_err(From::from)
+}
+
+fn send(process: &Arc<Process>, name: Term, message: Term) -> exception::Result {
+    match global::send(process, name, message) {
+        Ok(Some(pid)) => Ok(pid),
+        Ok(None) => {
+            let name_message = process.tuple_from_slice(&[name, message])?;
+            let reason = process.tuple_from_slice(&[atom_unchecked("badarg"), name_message])?;
+
+            Err(exit!(reason).into())
+        }
+        Err(distribution::Error::Alloc(alloc)) => Err(alloc.into()),
+        // messages that can't be sent to another node, such as closures
+        Err(_) => Err(badarg!().into()),
+    }
+}
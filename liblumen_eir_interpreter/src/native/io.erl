@@ -0,0 +1,70 @@
+-module(io).
+
+-export([format/1, format/2, format/3, fwrite/1, fwrite/2, fwrite/3, get_line/1, get_line/2,
+         nl/0, nl/1, put_chars/1, put_chars/2, read/1, read/2]).
+
+format(Format) ->
+    format(Format, []).
+
+format(Format, Args) ->
+    format(standard_io, Format, Args).
+
+format(Device, Format, Args) ->
+    put_chars(Device, io_lib:format(Format, Args)).
+
+fwrite(Format) ->
+    format(Format).
+
+fwrite(Format, Args) ->
+    format(Format, Args).
+
+fwrite(Device, Format, Args) ->
+    format(Device, Format, Args).
+
+get_line(Prompt) ->
+    get_line(standard_io, Prompt).
+
+get_line(Device, Prompt) ->
+    request(Device, {get_line, unicode, Prompt}).
+
+nl() ->
+    nl(standard_io).
+
+nl(Device) ->
+    put_chars(Device, "\n").
+
+put_chars(Chars) ->
+    put_chars(standard_io, Chars).
+
+put_chars(Device, Chars) ->
+    request(Device, {put_chars, unicode, Chars}).
+
+read(Prompt) ->
+    read(standard_io, Prompt).
+
+read(Device, Prompt) ->
+    read(Device, Prompt, []).
+
+read(Device, Prompt, Lines) ->
+    case get_line(Device, Prompt) of
+        eof when Lines =:= [] ->
+            eof;
+        eof ->
+            {error, {1, erl_parse, "premature end"}};
+        {error, _} = Error ->
+            Error;
+        Line ->
+            case lumen_io:parse_term([Line | Lines]) of
+                more -> read(Device, Prompt, [Line | Lines]);
+                Result -> Result
+            end
+    end.
+
+request(standard_io, Request) ->
+    request(group_leader(), Request);
+request(Device, Request) ->
+    ReplyAs = make_ref(),
+    Device ! {io_request, self(), ReplyAs, Request},
+    receive
+        {io_reply, ReplyAs, Reply} -> Reply
+    end.
@@ -0,0 +1,307 @@
+//! The `io` module: `io:format/1,2,3`, `io:put_chars/1,2`, `io:get_line/1,2` and `io:read/1,2`.
+//!
+//! `io` is Erlang (`io.erl`), because a process making an io request has to wait in `receive` for
+//! the reply.  Requests to `standard_io` go to the group leader of the process, which is the `init`
+//! process unless it is changed with `group_leader/2` (see `lumen_runtime::system::io::server`).
+//! `io.erl` formats with the `io_lib:format/2` native (see `lumen_runtime::otp::io_lib`) and calls
+//! the `lumen_io:parse_term/1` native to parse the lines read so far.  Only terms that can be
+//! written as literals of atoms, integers that fit in 64 bits, floats, strings, lists and tuples
+//! can be read.
+
+use std::cell::Cell;
+use std::convert::TryInto;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use libeir_ir::Module;
+
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::exception::{self, Exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Term};
+
+use crate::compile::compile_str;
+use crate::module::NativeModule;
+
+/// The Erlang half of `io`, compiled from `io.erl`
+pub fn make_io() -> Module {
+    compile_str(include_str!("io.erl")).unwrap()
+}
+
+pub fn make_lumen_io() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("lumen_io").unwrap());
+
+    native.add_simple(
+        Atom::try_from_str("parse_term").unwrap(),
+        1,
+        |proc, args| parse_term(proc, args[0]),
+    );
+
+    native
+}
+
+/// Reads `text`, without a full stop, as a term on the heap of `process`.  Returns `Err` if the term
+/// didn't fit on the heap, so that the caller can garbage collect and try again, or `Ok(Err)` with
+/// the message if `text` isn't a term that can be read.
+pub(crate) fn read_term(process: &Process, text: &str) -> Result<Result<Term, String>, Alloc> {
+    let mut parser = Parser {
+        process,
+        chars: text.chars().peekable(),
+        alloc_error: Cell::new(None),
+    };
+
+    let result = parser.term().and_then(|term| {
+        parser.skip_whitespace();
+
+        match parser.chars.next() {
+            None => Ok(term),
+            Some(c) => Err(format!("syntax error before: {}", c)),
+        }
+    });
+
+    match parser.alloc_error.take() {
+        Some(alloc_error) => Err(alloc_error),
+        None => Ok(result),
+    }
+}
+
+// Private
+
+/// `more` until the lines end with a full stop, then `{ok, Term}` or `{error, ErrorInfo}`
+fn parse_term(process: &Process, lines: Term) -> exception::Result {
+    let mut text = String::new();
+
+    // `lines` are newest first
+    let mut lines_vec = Vec::new();
+
+    for result in lines.list_iter()? {
+        lines_vec.push(text_of_string(result?)?);
+    }
+
+    for line in lines_vec.iter().rev() {
+        text.push_str(line);
+    }
+
+    let text = text.trim_end();
+
+    if !text.ends_with('.') {
+        return Ok(atom_unchecked("more"));
+    }
+
+    match read_term(process, &text[..(text.len() - 1)])? {
+        Ok(term) => Ok(process.tuple_from_slice(&[atom_unchecked("ok"), term])?),
+        Err(message) => {
+            let message = process.charlist_from_str(&message)?;
+            let error_info = process.tuple_from_slice(&[
+                process.integer(1)?,
+                atom_unchecked("erl_parse"),
+                message,
+            ])?;
+
+            Ok(process.tuple_from_slice(&[atom_unchecked("error"), error_info])?)
+        }
+    }
+}
+
+fn text_of_string(term: Term) -> Result<String, Exception> {
+    let mut string = String::new();
+
+    for result in term.list_iter()? {
+        let c: char = result?.try_into()?;
+        string.push(c);
+    }
+
+    Ok(string)
+}
+
+struct Parser<'a> {
+    process: &'a Process,
+    chars: Peekable<Chars<'a>>,
+    alloc_error: Cell<Option<Alloc>>,
+}
+
+impl<'a> Parser<'a> {
+    fn term(&mut self) -> Result<Term, String> {
+        self.skip_whitespace();
+
+        match self.chars.peek().cloned() {
+            Some('{') => {
+                self.chars.next();
+                let elements = self.elements('}')?;
+
+                self.alloc(self.process.tuple_from_slice(&elements))
+            }
+            Some('[') => {
+                self.chars.next();
+                self.list()
+            }
+            Some('"') => {
+                self.chars.next();
+                let string = self.quoted('"')?;
+
+                self.alloc(self.process.charlist_from_str(&string))
+            }
+            Some('\'') => {
+                self.chars.next();
+                let name = self.quoted('\'')?;
+
+                Ok(atom_unchecked(&name))
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            Some(c) if c.is_lowercase() => {
+                let name = self.take_while(|c| c.is_alphanumeric() || c == '_' || c == '@');
+
+                Ok(atom_unchecked(&name))
+            }
+            Some(c) => Err(format!("syntax error before: {}", c)),
+            None => Err("premature end".to_string()),
+        }
+    }
+
+    /// The elements up to `close`, after the opening bracket
+    fn elements(&mut self, close: char) -> Result<Vec<Term>, String> {
+        let mut elements = Vec::new();
+
+        self.skip_whitespace();
+
+        if self.chars.peek() == Some(&close) {
+            self.chars.next();
+
+            return Ok(elements);
+        }
+
+        loop {
+            elements.push(self.term()?);
+            self.skip_whitespace();
+
+            match self.chars.next() {
+                Some(',') => (),
+                Some(c) if c == close => return Ok(elements),
+                Some(c) => return Err(format!("syntax error before: {}", c)),
+                None => return Err("premature end".to_string()),
+            }
+        }
+    }
+
+    /// A list after the `[`, which may be improper
+    fn list(&mut self) -> Result<Term, String> {
+        let mut elements = Vec::new();
+
+        self.skip_whitespace();
+
+        if self.chars.peek() == Some(&']') {
+            self.chars.next();
+
+            return Ok(Term::NIL);
+        }
+
+        loop {
+            elements.push(self.term()?);
+            self.skip_whitespace();
+
+            match self.chars.next() {
+                Some(',') => (),
+                Some('|') => {
+                    let tail = self.term()?;
+                    self.skip_whitespace();
+
+                    return match self.chars.next() {
+                        Some(']') => {
+                            self.alloc(self.process.improper_list_from_slice(&elements, tail))
+                        }
+                        Some(c) => Err(format!("syntax error before: {}", c)),
+                        None => Err("premature end".to_string()),
+                    };
+                }
+                Some(']') => return self.alloc(self.process.list_from_slice(&elements)),
+                Some(c) => return Err(format!("syntax error before: {}", c)),
+                None => return Err("premature end".to_string()),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Term, String> {
+        let mut number = String::new();
+
+        if self.chars.peek() == Some(&'-') {
+            self.chars.next();
+            number.push('-');
+        }
+
+        number.push_str(&self.take_while(|c| c.is_ascii_digit()));
+
+        if self.chars.peek() == Some(&'.') {
+            self.chars.next();
+            number.push('.');
+            number.push_str(&self.take_while(|c| c.is_ascii_digit()));
+
+            if let Some('e') | Some('E') = self.chars.peek() {
+                number.push(self.chars.next().unwrap());
+
+                if let Some('-') | Some('+') = self.chars.peek() {
+                    number.push(self.chars.next().unwrap());
+                }
+
+                number.push_str(&self.take_while(|c| c.is_ascii_digit()));
+            }
+
+            match number.parse::<f64>() {
+                Ok(float) => self.alloc(self.process.float(float)),
+                Err(_) => Err(format!("syntax error before: {}", number)),
+            }
+        } else {
+            match number.parse::<i64>() {
+                Ok(integer) => self.alloc(self.process.integer(integer)),
+                Err(_) => Err(format!("integer {} can't be read", number)),
+            }
+        }
+    }
+
+    /// The text up to the unescaped `quote`, after the opening quote
+    fn quoted(&mut self, quote: char) -> Result<String, String> {
+        let mut string = String::new();
+
+        loop {
+            match self.chars.next() {
+                Some('\\') => match self.chars.next() {
+                    Some('n') => string.push('\n'),
+                    Some('t') => string.push('\t'),
+                    Some('r') => string.push('\r'),
+                    Some('s') => string.push(' '),
+                    Some(c) => string.push(c),
+                    None => return Err("premature end".to_string()),
+                },
+                Some(c) if c == quote => return Ok(string),
+                Some(c) => string.push(c),
+                None => return Err("premature end".to_string()),
+            }
+        }
+    }
+
+    fn take_while<P: Fn(char) -> bool>(&mut self, predicate: P) -> String {
+        let mut taken = String::new();
+
+        while let Some(&c) = self.chars.peek() {
+            if !predicate(c) {
+                break;
+            }
+
+            taken.push(c);
+            self.chars.next();
+        }
+
+        taken
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.take_while(char::is_whitespace);
+    }
+
+    fn alloc(&self, result: Result<Term, Alloc>) -> Result<Term, String> {
+        result.map_err(|alloc_error| {
+            self.alloc_error.set(Some(alloc_error));
+
+            "not enough memory to read term".to_string()
+        })
+    }
+}
@@ -0,0 +1,14 @@
+use liblumen_alloc::erts::term::Atom;
+use lumen_runtime::otp::io_lib;
+
+use crate::module::NativeModule;
+
+pub fn make_io_lib() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("io_lib").unwrap());
+
+    native.add_simple(Atom::try_from_str("format").unwrap(), 2, |proc, args| {
+        io_lib::format_2::native(proc, args[0], args[1])
+    });
+
+    native
+}
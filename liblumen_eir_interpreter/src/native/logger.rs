@@ -1,25 +1,39 @@
 use liblumen_alloc::erts::term::Atom;
+use lumen_runtime::otp::logger;
 
 use crate::module::NativeModule;
 
-macro_rules! trace {
-    ($($t:tt)*) => (lumen_runtime::system::io::puts(&format_args!($($t)*).to_string()))
-}
-
 pub fn make_logger() -> NativeModule {
     let mut native = NativeModule::new(Atom::try_from_str("logger").unwrap());
 
-    native.add_simple(Atom::try_from_str("allow").unwrap(), 2, |_proc, _args| {
-        Ok(true.into())
+    native.add_simple(Atom::try_from_str("allow").unwrap(), 2, |_proc, args| {
+        logger::allow_2(args[0], args[1])
+    });
+
+    native.add_simple(Atom::try_from_str("log").unwrap(), 2, |_proc, args| {
+        logger::log_2(args[0], args[1])
+    });
+    native.add_simple(Atom::try_from_str("log").unwrap(), 3, |_proc, args| {
+        logger::log_3(args[0], args[1], args[2])
+    });
+    native.add_simple(Atom::try_from_str("log").unwrap(), 4, |_proc, args| {
+        logger::log_4(args[0], args[1], args[2], args[3])
     });
 
+    native.add_simple(
+        Atom::try_from_str("macro_log").unwrap(),
+        3,
+        |_proc, args| logger::macro_log_3(args[0], args[1], args[2]),
+    );
     native.add_simple(
         Atom::try_from_str("macro_log").unwrap(),
         4,
-        |_proc, args| {
-            trace!("{} {} {} {}", args[0], args[1], args[2], args[3]);
-            Ok(true.into())
-        },
+        |_proc, args| logger::macro_log_4(args[0], args[1], args[2], args[3]),
+    );
+    native.add_simple(
+        Atom::try_from_str("macro_log").unwrap(),
+        5,
+        |_proc, args| logger::macro_log_5(args[0], args[1], args[2], args[3], args[4]),
     );
 
     native
@@ -0,0 +1,78 @@
+//! `lumen:config/0,1`, which return the configuration of the runtime (see
+//! `lumen_runtime::config::runtime`), and `lumen:profile_start/0`, `lumen:profile_stop/0` and
+//! `lumen:profile_analyse/0`, which profile interpreted code (see `crate::profile`).
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, AsTerm, Atom};
+
+use lumen_runtime::config::runtime::RuntimeConfig;
+
+use crate::module::NativeModule;
+use crate::profile;
+
+pub fn make_lumen() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("lumen").unwrap());
+
+    native.add_simple(Atom::try_from_str("config").unwrap(), 0, |proc, _args| {
+        Ok(RuntimeConfig::current().to_map(proc)?)
+    });
+
+    native.add_simple(Atom::try_from_str("config").unwrap(), 1, |proc, args| {
+        match RuntimeConfig::current().get(proc, args[0])? {
+            Some(value) => Ok(value),
+            None => Err(badarg!().into()),
+        }
+    });
+
+    native.add_simple(
+        Atom::try_from_str("profile_start").unwrap(),
+        0,
+        |_proc, _args| {
+            profile::start();
+
+            Ok(atom_unchecked("ok"))
+        },
+    );
+
+    native.add_simple(
+        Atom::try_from_str("profile_stop").unwrap(),
+        0,
+        |_proc, _args| {
+            profile::stop();
+
+            Ok(atom_unchecked("ok"))
+        },
+    );
+
+    native.add_simple(
+        Atom::try_from_str("profile_analyse").unwrap(),
+        0,
+        |proc, _args| profile_analyse(proc),
+    );
+
+    native
+}
+
+/// `[{{Module, Function, Arity}, Calls, Reductions, Milliseconds}]`, most reductions first
+fn profile_analyse(process: &Process) -> exception::Result {
+    let mut entries = Vec::new();
+
+    for (module_function_arity, stats) in profile::analyse() {
+        let module_function_arity_term = process.tuple_from_slice(&[
+            unsafe { module_function_arity.module.as_term() },
+            unsafe { module_function_arity.function.as_term() },
+            process.integer(module_function_arity.arity)?,
+        ])?;
+
+        entries.push(process.tuple_from_slice(&[
+            module_function_arity_term,
+            process.integer(stats.calls)?,
+            process.integer(stats.reductions)?,
+            process.integer(stats.milliseconds)?,
+        ])?);
+    }
+
+    Ok(process.list_from_slice(&entries)?)
+}
@@ -0,0 +1,54 @@
+-module(lumen_distribution).
+
+-export([spawn/4, spawn_link/4]).
+
+spawn(Node, Module, Function, Arguments) ->
+    case node() of
+        Node ->
+            erlang:spawn(Module, Function, Arguments);
+        _ ->
+            spawn_on(Node, Module, Function, Arguments, false)
+    end.
+
+spawn_link(Node, Module, Function, Arguments) ->
+    case node() of
+        Node ->
+            erlang:spawn_link(Module, Function, Arguments);
+        _ ->
+            spawn_on(Node, Module, Function, Arguments, true)
+    end.
+
+%% Waits for `Node` to reply with the pid for as long as a connection may go without a tick
+%% (60 seconds), or until `Node` goes down.
+spawn_on(Node, Module, Function, Arguments, Link) ->
+    true = erlang:monitor_node(Node, true),
+    Reference = lumen_distribution:spawn_request(Node, Module, Function, Arguments, Link),
+    Timer = erlang:send_after(60000, self(), {Reference, timeout}),
+    receive
+        {Reference, timeout} ->
+            demonitor_node(Node),
+            lumen_distribution:noconnection(Node, Link);
+        {Reference, Pid} ->
+            cancel_timer(Timer, Reference),
+            demonitor_node(Node),
+            Pid;
+        {nodedown, Node} ->
+            cancel_timer(Timer, Reference),
+            lumen_distribution:noconnection(Node, Link)
+    end.
+
+%% The timeout may have been sent already, so it is flushed.
+cancel_timer(Timer, Reference) ->
+    erlang:cancel_timer(Timer),
+    receive
+        {Reference, timeout} -> ok
+    after 0 -> ok
+    end.
+
+%% `{nodedown, Node}` may have been sent already, so it is flushed.
+demonitor_node(Node) ->
+    true = erlang:monitor_node(Node, false),
+    receive
+        {nodedown, Node} -> ok
+    after 0 -> ok
+    end.
@@ -0,0 +1,130 @@
+//! `spawn/4` and `spawn_link/4` on other nodes, backed by `lumen_runtime::distribution::spawn`.
+//!
+//! The spawning process has to wait in `receive` for the other node to reply with the pid, so
+//! `erlang:spawn/4` and `erlang:spawn_link/4` call the Erlang half of `lumen_distribution`
+//! (`lumen_distribution.erl`), which spawns locally when the node is this node, and otherwise
+//! sends the request with the `lumen_distribution:spawn_request/5` native and waits for the reply.
+//!
+//! The spawning process monitors the node while it waits, and gives up on a node that goes down
+//! or doesn't reply in time.  As with BEAM, it then gets a pid on the node that no process has,
+//! and, for `spawn_link/4`, an exit signal with `noconnection` from that pid.
+
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use libeir_ir::Module;
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception::{self, Exception};
+use liblumen_alloc::erts::process::alloc::heap_alloc::MakePidError;
+use liblumen_alloc::erts::process::signal::Exit;
+use liblumen_alloc::erts::process::{code, Process};
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Term};
+use liblumen_alloc::erts::Node;
+
+use lumen_runtime::distribution::{self, monitor_node, spawn};
+use lumen_runtime::process::send_exit_and_wake;
+
+use crate::compile::compile_str;
+use crate::module::NativeModule;
+
+/// The Erlang half of `lumen_distribution`, compiled from `lumen_distribution.erl`
+pub fn make_lumen_distribution() -> Module {
+    compile_str(include_str!("lumen_distribution.erl")).unwrap()
+}
+
+pub fn make_lumen_distribution_natives() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("lumen_distribution").unwrap());
+
+    native.add_simple(
+        Atom::try_from_str("spawn_request").unwrap(),
+        5,
+        |proc, args| spawn_request(proc, args[0], args[1], args[2], args[3], args[4]),
+    );
+    native.add_simple(
+        Atom::try_from_str("noconnection").unwrap(),
+        2,
+        |proc, args| noconnection(proc, args[0], args[1]),
+    );
+
+    native
+}
+
+/// Calls `lumen_distribution:spawn/4` or `lumen_distribution:spawn_link/4` with the arguments of
+/// `erlang:spawn/4` or `erlang:spawn_link/4`, which are after the return and throw continuations
+/// in `args`
+pub fn apply_spawn(proc: &Arc<Process>, function: &str, args: &[Term]) -> code::Result {
+    let inner_args = proc.list_from_slice(args)?;
+    proc.stack_push(inner_args)?;
+
+    proc.stack_push(atom_unchecked(function))?;
+    proc.stack_push(atom_unchecked("lumen_distribution"))?;
+
+    crate::code::apply(proc)
+}
+
+/// `erlang:monitor_node/2`
+pub fn monitor_node(process: &Arc<Process>, node: Term, flag: Term) -> exception::Result {
+    let node: Atom = node.try_into().map_err(|_| badarg!())?;
+    let flag: bool = flag.try_into()?;
+
+    monitor_node::monitor_node(process, node, flag);
+
+    Ok(true.into())
+}
+
+// Private
+
+/// The pid that spawning on `node` returns when `node` can't be reached: a pid on `node` that no
+/// process has.  When spawning with `link`, `process` is also sent an exit signal with
+/// `noconnection` from the pid, as for a link that broke.
+fn noconnection(process: &Arc<Process>, node: Term, link: Term) -> exception::Result {
+    let node: Atom = node.try_into().map_err(|_| badarg!())?;
+    let link: bool = link.try_into()?;
+
+    let unreachable_node = Node::new(node, 0);
+    let pid = process
+        .external_pid(unreachable_node, 0, 0)
+        .map_err(|make_pid_error| -> Exception {
+            match make_pid_error {
+                MakePidError::Alloc(alloc) => alloc.into(),
+                // 0 is a valid number and serial
+                MakePidError::Number | MakePidError::Serial => unreachable!(),
+            }
+        })?;
+
+    if link {
+        send_exit_and_wake(process, Exit::linked(pid, atom_unchecked("noconnection")))?;
+    }
+
+    Ok(pid)
+}
+
+/// Asks `node` to spawn a process, returning the reference it will reply with
+fn spawn_request(
+    process: &Arc<Process>,
+    node: Term,
+    module: Term,
+    function: Term,
+    arguments: Term,
+    link: Term,
+) -> exception::Result {
+    let node: Atom = node.try_into().map_err(|_| badarg!())?;
+    let _: Atom = module.try_into().map_err(|_| badarg!())?;
+    let _: Atom = function.try_into().map_err(|_| badarg!())?;
+    let link: bool = link.try_into()?;
+
+    if !arguments.is_list() {
+        return Err(badarg!().into());
+    }
+
+    let reference = process.next_reference()?;
+
+    match spawn::request(process, node, module, function, arguments, link, reference) {
+        Ok(()) => Ok(reference),
+        Err(distribution::Error::Alloc(alloc)) => Err(alloc.into()),
+        // arguments that can't be sent to another node, such as closures.  Connecting happens
+        // after the request is made, so no other error is returned.
+        Err(_) => Err(badarg!().into()),
+    }
+}
@@ -0,0 +1,81 @@
+use liblumen_alloc::erts::term::Atom;
+
+use lumen_runtime::otp::math;
+
+use crate::module::NativeModule;
+
+pub fn make_math() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("math").unwrap());
+
+    native.add_simple(Atom::try_from_str("acos").unwrap(), 1, |proc, args| {
+        math::acos_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("acosh").unwrap(), 1, |proc, args| {
+        math::acosh_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("asin").unwrap(), 1, |proc, args| {
+        math::asin_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("asinh").unwrap(), 1, |proc, args| {
+        math::asinh_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("atan").unwrap(), 1, |proc, args| {
+        math::atan_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("atan2").unwrap(), 2, |proc, args| {
+        math::atan2_2(args[0], args[1], proc)
+    });
+    native.add_simple(Atom::try_from_str("atanh").unwrap(), 1, |proc, args| {
+        math::atanh_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("ceil").unwrap(), 1, |proc, args| {
+        math::ceil_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("cos").unwrap(), 1, |proc, args| {
+        math::cos_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("cosh").unwrap(), 1, |proc, args| {
+        math::cosh_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("exp").unwrap(), 1, |proc, args| {
+        math::exp_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("floor").unwrap(), 1, |proc, args| {
+        math::floor_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("fmod").unwrap(), 2, |proc, args| {
+        math::fmod_2(args[0], args[1], proc)
+    });
+    native.add_simple(Atom::try_from_str("log").unwrap(), 1, |proc, args| {
+        math::log_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("log10").unwrap(), 1, |proc, args| {
+        math::log10_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("log2").unwrap(), 1, |proc, args| {
+        math::log2_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("pi").unwrap(), 0, |proc, _args| {
+        math::pi_0(proc)
+    });
+    native.add_simple(Atom::try_from_str("pow").unwrap(), 2, |proc, args| {
+        math::pow_2(args[0], args[1], proc)
+    });
+    native.add_simple(Atom::try_from_str("sin").unwrap(), 1, |proc, args| {
+        math::sin_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("sinh").unwrap(), 1, |proc, args| {
+        math::sinh_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("sqrt").unwrap(), 1, |proc, args| {
+        math::sqrt_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("tan").unwrap(), 1, |proc, args| {
+        math::tan_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("tanh").unwrap(), 1, |proc, args| {
+        math::tanh_1(args[0], proc)
+    });
+
+    native
+}
@@ -1,14 +1,76 @@
+mod code;
+pub use code::{check_process_code, make_code};
+
+mod compile;
+pub use compile::make_compile;
+
+mod counters;
+pub use counters::make_counters;
+
+mod crypto;
+pub use crypto::make_crypto;
+
+mod ct;
+pub use ct::{make_ct, set_config, take_log};
+
 mod erlang;
 pub use erlang::make_erlang;
 
+mod error_logger;
+pub use error_logger::make_error_logger;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod file;
+#[cfg(not(target_arch = "wasm32"))]
+pub use file::make_file;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod global;
+#[cfg(not(target_arch = "wasm32"))]
+pub use global::make_global;
+
+mod io;
+pub use io::{make_io, make_lumen_io};
+
+mod io_lib;
+pub use io_lib::make_io_lib;
+
 mod lists;
 pub use lists::make_lists;
 
 mod maps;
 pub use maps::make_maps;
 
+mod lumen;
+pub use lumen::make_lumen;
+
+mod math;
+pub use math::make_math;
+
+mod rand;
+pub use rand::make_rand;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod lumen_distribution;
+#[cfg(not(target_arch = "wasm32"))]
+pub use lumen_distribution::{make_lumen_distribution, make_lumen_distribution_natives};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod net_kernel;
+#[cfg(not(target_arch = "wasm32"))]
+pub use net_kernel::make_net_kernel;
+
+mod proplists;
+pub use proplists::make_proplists;
+
+mod queue;
+pub use queue::make_queue;
+
 mod logger;
 pub use logger::make_logger;
 
 mod lumen_intrinsics;
 pub use lumen_intrinsics::make_lumen_intrinsics;
+
+mod zlib;
+pub use zlib::make_zlib;
@@ -0,0 +1,39 @@
+//! The part of `net_kernel` that subscribes processes to nodes connecting and disconnecting,
+//! backed by `lumen_runtime::distribution::monitor_nodes`.
+
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Term};
+
+use lumen_runtime::distribution::monitor_nodes::{self, Options};
+
+use crate::module::NativeModule;
+
+pub fn make_net_kernel() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("net_kernel").unwrap());
+
+    native.add_simple(
+        Atom::try_from_str("monitor_nodes").unwrap(),
+        1,
+        |proc, args| monitor_nodes(proc, args[0], Term::NIL),
+    );
+    native.add_simple(
+        Atom::try_from_str("monitor_nodes").unwrap(),
+        2,
+        |proc, args| monitor_nodes(proc, args[0], args[1]),
+    );
+
+    native
+}
+
+fn monitor_nodes(process: &Arc<Process>, flag: Term, options: Term) -> exception::Result {
+    let flag: bool = flag.try_into()?;
+    let options: Options = options.try_into()?;
+
+    monitor_nodes::monitor_nodes(process, flag, options);
+
+    Ok(atom_unchecked("ok"))
+}
@@ -0,0 +1,38 @@
+use liblumen_alloc::erts::term::Atom;
+use lumen_runtime::otp::proplists;
+
+use crate::module::NativeModule;
+
+pub fn make_proplists() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("proplists").unwrap());
+
+    native.add_simple(
+        Atom::try_from_str("get_all_values").unwrap(),
+        2,
+        |proc, args| proplists::get_all_values_2::native(proc, args[0], args[1]),
+    );
+
+    native.add_simple(
+        Atom::try_from_str("get_value").unwrap(),
+        2,
+        |_proc, args| proplists::get_value_2::native(args[0], args[1]),
+    );
+
+    native.add_simple(
+        Atom::try_from_str("get_value").unwrap(),
+        3,
+        |_proc, args| proplists::get_value_3::native(args[0], args[1], args[2]),
+    );
+
+    native.add_simple(
+        Atom::try_from_str("is_defined").unwrap(),
+        2,
+        |_proc, args| proplists::is_defined_2::native(args[0], args[1]),
+    );
+
+    native.add_simple(Atom::try_from_str("lookup").unwrap(), 2, |proc, args| {
+        proplists::lookup_2::native(proc, args[0], args[1])
+    });
+
+    native
+}
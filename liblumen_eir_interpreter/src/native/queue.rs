@@ -0,0 +1,283 @@
+//! A native `queue` with the semantics and the representation of the stdlib `queue`, for
+//! interpreted code that queues on hot paths.
+//!
+//! A queue is `{In, Out}`, the same term as the stdlib makes, so queues compare, match and encode
+//! with `term_to_binary` as on the BEAM, and can be passed to code that uses the stdlib `queue`.
+//! Items are added to the front of `In` and taken from the front of `Out`, and when the end an
+//! item is taken from is empty, half of the other end is reversed onto it, so that `in`, `in_r`,
+//! `out` and `out_r` are amortized O(1).  Each function returns the same queue as the stdlib's.
+
+use std::convert::TryInto;
+
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::exception::{self, Exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Boxed, Cons, Term, Tuple};
+use liblumen_alloc::{badarg, error};
+
+use lumen_runtime::otp::lists;
+
+use crate::module::NativeModule;
+
+pub fn make_queue() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("queue").unwrap());
+
+    native.add_simple(Atom::try_from_str("new").unwrap(), 0, |proc, _args| {
+        Ok(make(proc, Term::NIL, Term::NIL, End::Front)?)
+    });
+    native.add_simple(Atom::try_from_str("is_queue").unwrap(), 1, |_proc, args| {
+        Ok(sides(args[0], End::Front).is_ok().into())
+    });
+    native.add_simple(Atom::try_from_str("is_empty").unwrap(), 1, |_proc, args| {
+        let (far, near) = sides(args[0], End::Front)?;
+        Ok((far.is_nil() && near.is_nil()).into())
+    });
+    native.add_simple(Atom::try_from_str("len").unwrap(), 1, |proc, args| {
+        let (far, near) = sides(args[0], End::Front)?;
+        Ok(proc.integer(elements(far)?.len() + elements(near)?.len())?)
+    });
+    native.add_simple(Atom::try_from_str("in").unwrap(), 2, |proc, args| {
+        add(proc, args[0], args[1], End::Back)
+    });
+    native.add_simple(Atom::try_from_str("in_r").unwrap(), 2, |proc, args| {
+        add(proc, args[0], args[1], End::Front)
+    });
+    native.add_simple(Atom::try_from_str("out").unwrap(), 1, |proc, args| {
+        out(proc, args[0], End::Front)
+    });
+    native.add_simple(Atom::try_from_str("out_r").unwrap(), 1, |proc, args| {
+        out(proc, args[0], End::Back)
+    });
+    native.add_simple(Atom::try_from_str("peek").unwrap(), 1, |proc, args| {
+        peek(proc, args[0], End::Front)
+    });
+    native.add_simple(Atom::try_from_str("peek_r").unwrap(), 1, |proc, args| {
+        peek(proc, args[0], End::Back)
+    });
+    native.add_simple(Atom::try_from_str("get").unwrap(), 1, |_proc, args| {
+        get(args[0], End::Front)
+    });
+    native.add_simple(Atom::try_from_str("get_r").unwrap(), 1, |_proc, args| {
+        get(args[0], End::Back)
+    });
+    native.add_simple(Atom::try_from_str("drop").unwrap(), 1, |proc, args| {
+        discard(proc, args[0], End::Front)
+    });
+    native.add_simple(Atom::try_from_str("drop_r").unwrap(), 1, |proc, args| {
+        discard(proc, args[0], End::Back)
+    });
+    native.add_simple(Atom::try_from_str("from_list").unwrap(), 1, |proc, args| {
+        // the stdlib moves half of the list to the back, as if the list was the front of a queue
+        // whose back is being taken from
+        let (far, near) = halve(proc, &elements(args[0])?)?;
+        Ok(make(proc, far, near, End::Back)?)
+    });
+    native.add_simple(Atom::try_from_str("to_list").unwrap(), 1, |proc, args| {
+        let (rear, front) = sides(args[0], End::Front)?;
+        let mut items = elements(front)?;
+        items.extend(elements(rear)?.into_iter().rev());
+        Ok(proc.list_from_slice(&items)?)
+    });
+    native.add_simple(Atom::try_from_str("member").unwrap(), 2, |_proc, args| {
+        let (rear, front) = sides(args[1], End::Front)?;
+        if lists::member_2::native(args[0], rear)? == true.into() {
+            Ok(true.into())
+        } else {
+            lists::member_2::native(args[0], front)
+        }
+    });
+    native.add_simple(Atom::try_from_str("reverse").unwrap(), 1, |proc, args| {
+        let (rear, front) = sides(args[0], End::Front)?;
+        Ok(make(proc, rear, front, End::Back)?)
+    });
+    native.add_simple(Atom::try_from_str("join").unwrap(), 2, |proc, args| {
+        join(proc, args[0], args[1])
+    });
+
+    native
+}
+
+#[derive(Clone, Copy)]
+enum End {
+    Front,
+    Back,
+}
+
+/// `{empty, Queue}` or `{{value, Item}, Rest}`
+fn out(process: &Process, queue: Term, end: End) -> exception::Result {
+    match remove(process, queue, end)? {
+        Some((item, rest)) => {
+            let value = process.tuple_from_slice(&[atom_unchecked("value"), item])?;
+
+            Ok(process.tuple_from_slice(&[value, rest])?)
+        }
+        None => Ok(process.tuple_from_slice(&[atom_unchecked("empty"), queue])?),
+    }
+}
+
+fn peek(process: &Process, queue: Term, end: End) -> exception::Result {
+    match item(queue, end)? {
+        Some(item) => Ok(process.tuple_from_slice(&[atom_unchecked("value"), item])?),
+        None => Ok(atom_unchecked("empty")),
+    }
+}
+
+fn get(queue: Term, end: End) -> exception::Result {
+    item(queue, end)?.ok_or_else(|| error!(atom_unchecked("empty")).into())
+}
+
+fn discard(process: &Process, queue: Term, end: End) -> exception::Result {
+    match remove(process, queue, end)? {
+        Some((_, rest)) => Ok(rest),
+        None => Err(error!(atom_unchecked("empty")).into()),
+    }
+}
+
+/// Adds `item` to `end` of `queue`
+fn add(process: &Process, item: Term, queue: Term, end: End) -> exception::Result {
+    let (far, near) = sides(queue, end)?;
+
+    // when this end holds the only item, it is moved to the other end, so that either end can be
+    // taken from without reversing
+    if far.is_nil() && is_singleton(near) {
+        Ok(make(process, near, process.list_from_slice(&[item])?, end)?)
+    } else {
+        Ok(make(process, far, process.cons(item, near)?, end)?)
+    }
+}
+
+/// The item at `end` of `queue`, unless it is empty
+fn item(queue: Term, end: End) -> Result<Option<Term>, Exception> {
+    let (far, near) = sides(queue, end)?;
+
+    match split(near) {
+        Some((item, _)) => Ok(Some(item)),
+        None => Ok(elements(far)?.last().cloned()),
+    }
+}
+
+/// The item at `end` of `queue` and the queue without it, unless it is empty
+fn remove(process: &Process, queue: Term, end: End) -> Result<Option<(Term, Term)>, Exception> {
+    let (far, near) = sides(queue, end)?;
+
+    match split(near) {
+        // the other end is halved when the last item at this end is taken
+        Some((item, rest)) if rest.is_nil() => {
+            let (far, near) = halve(process, &elements(far)?)?;
+
+            Ok(Some((item, make(process, far, near, end)?)))
+        }
+        Some((item, rest)) => Ok(Some((item, make(process, far, rest, end)?))),
+        // all but the last item at the other end are reversed onto this end
+        None => match split(far) {
+            Some((far_item, others)) => {
+                let mut others = elements(others)?;
+
+                match others.pop() {
+                    Some(item) => {
+                        others.reverse();
+                        let near = process.list_from_slice(&others)?;
+                        let far = process.list_from_slice(&[far_item])?;
+
+                        Ok(Some((item, make(process, far, near, end)?)))
+                    }
+                    None => Ok(Some((far_item, make(process, Term::NIL, Term::NIL, end)?))),
+                }
+            }
+            None => Ok(None),
+        },
+    }
+}
+
+/// `{R1, F1}` followed by `{R2, F2}`, which is `{R2, F1 ++ lists:reverse(R1, F2)}`
+fn join(process: &Process, front: Term, back: Term) -> exception::Result {
+    let (front_rear, front_front) = sides(front, End::Front)?;
+    let (back_rear, back_front) = sides(back, End::Front)?;
+
+    if back_rear.is_nil() && back_front.is_nil() {
+        Ok(front)
+    } else if front_rear.is_nil() && front_front.is_nil() {
+        Ok(back)
+    } else {
+        let mut items = elements(front_front)?;
+        items.extend(elements(front_rear)?.into_iter().rev());
+        let joined_front = process.improper_list_from_slice(&items, back_front)?;
+
+        Ok(make(process, back_rear, joined_front, End::Front)?)
+    }
+}
+
+/// `{far, near}` of `queue`, where items at `end` are at the front of `near`, and items at the
+/// other end are at the front of `far`
+fn sides(queue: Term, end: End) -> Result<(Term, Term), Exception> {
+    let tuple: Boxed<Tuple> = queue.try_into().map_err(|_| badarg!())?;
+
+    if tuple.len() != 2 {
+        return Err(badarg!().into());
+    }
+
+    let mut iter = tuple.iter();
+    let rear = iter.next().unwrap();
+    let front = iter.next().unwrap();
+
+    if !(rear.is_list() && front.is_list()) {
+        return Err(badarg!().into());
+    }
+
+    Ok(match end {
+        End::Front => (rear, front),
+        End::Back => (front, rear),
+    })
+}
+
+/// The queue with `far` and `near` as returned by `sides` for `end`
+fn make(process: &Process, far: Term, near: Term, end: End) -> Result<Term, Alloc> {
+    match end {
+        End::Front => process.tuple_from_slice(&[far, near]),
+        End::Back => process.tuple_from_slice(&[near, far]),
+    }
+}
+
+/// `{far, near}` with the first half of `items` at `far` and the rest reversed onto `near`, as
+/// the stdlib moves items from one end to the other
+fn halve(process: &Process, items: &[Term]) -> Result<(Term, Term), Alloc> {
+    match items.len() {
+        0 => Ok((Term::NIL, Term::NIL)),
+        1 => Ok((Term::NIL, process.list_from_slice(items)?)),
+        len => {
+            let (far, moved) = items.split_at(len / 2);
+            let near: Vec<Term> = moved.iter().rev().cloned().collect();
+
+            Ok((
+                process.list_from_slice(far)?,
+                process.list_from_slice(&near)?,
+            ))
+        }
+    }
+}
+
+fn split(list: Term) -> Option<(Term, Term)> {
+    let cons: Boxed<Cons> = list.try_into().ok()?;
+
+    Some((cons.head, cons.tail))
+}
+
+fn is_singleton(list: Term) -> bool {
+    split(list).map_or(false, |(_, tail)| tail.is_nil())
+}
+
+fn elements(list: Term) -> Result<Vec<Term>, Exception> {
+    let mut elements = Vec::new();
+    let mut rest = list;
+
+    while let Some((head, tail)) = split(rest) {
+        elements.push(head);
+        rest = tail;
+    }
+
+    if rest.is_nil() {
+        Ok(elements)
+    } else {
+        Err(badarg!().into())
+    }
+}
@@ -0,0 +1,35 @@
+use liblumen_alloc::erts::term::Atom;
+
+use lumen_runtime::otp::rand;
+
+use crate::module::NativeModule;
+
+pub fn make_rand() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("rand").unwrap());
+
+    native.add_simple(
+        Atom::try_from_str("export_seed").unwrap(),
+        0,
+        |proc, _args| rand::export_seed_0(proc),
+    );
+    native.add_simple(Atom::try_from_str("normal").unwrap(), 0, |proc, _args| {
+        rand::normal_0(proc)
+    });
+    native.add_simple(Atom::try_from_str("normal").unwrap(), 2, |proc, args| {
+        rand::normal_2(args[0], args[1], proc)
+    });
+    native.add_simple(Atom::try_from_str("seed").unwrap(), 1, |proc, args| {
+        rand::seed_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("seed").unwrap(), 2, |proc, args| {
+        rand::seed_2(args[0], args[1], proc)
+    });
+    native.add_simple(Atom::try_from_str("uniform").unwrap(), 0, |proc, _args| {
+        rand::uniform_0(proc)
+    });
+    native.add_simple(Atom::try_from_str("uniform").unwrap(), 1, |proc, args| {
+        rand::uniform_1(args[0], proc)
+    });
+
+    native
+}
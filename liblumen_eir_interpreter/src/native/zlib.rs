@@ -0,0 +1,72 @@
+use liblumen_alloc::erts::term::Atom;
+
+use lumen_runtime::otp::zlib;
+
+use crate::module::NativeModule;
+
+pub fn make_zlib() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("zlib").unwrap());
+
+    native.add_simple(Atom::try_from_str("close").unwrap(), 1, |_proc, args| {
+        zlib::close_1(args[0])
+    });
+    native.add_simple(Atom::try_from_str("compress").unwrap(), 1, |proc, args| {
+        zlib::compress_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("deflate").unwrap(), 2, |proc, args| {
+        zlib::deflate_2(args[0], args[1], proc)
+    });
+    native.add_simple(Atom::try_from_str("deflate").unwrap(), 3, |proc, args| {
+        zlib::deflate_3(args[0], args[1], args[2], proc)
+    });
+    native.add_simple(
+        Atom::try_from_str("deflateEnd").unwrap(),
+        1,
+        |_proc, args| zlib::deflate_end_1(args[0]),
+    );
+    native.add_simple(
+        Atom::try_from_str("deflateInit").unwrap(),
+        1,
+        |_proc, args| zlib::deflate_init_1(args[0]),
+    );
+    native.add_simple(
+        Atom::try_from_str("deflateInit").unwrap(),
+        2,
+        |_proc, args| zlib::deflate_init_2(args[0], args[1]),
+    );
+    native.add_simple(Atom::try_from_str("gunzip").unwrap(), 1, |proc, args| {
+        zlib::gunzip_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("gzip").unwrap(), 1, |proc, args| {
+        zlib::gzip_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("inflate").unwrap(), 2, |proc, args| {
+        zlib::inflate_2(args[0], args[1], proc)
+    });
+    native.add_simple(
+        Atom::try_from_str("inflateEnd").unwrap(),
+        1,
+        |_proc, args| zlib::inflate_end_1(args[0]),
+    );
+    native.add_simple(
+        Atom::try_from_str("inflateInit").unwrap(),
+        1,
+        |_proc, args| zlib::inflate_init_1(args[0]),
+    );
+    native.add_simple(Atom::try_from_str("open").unwrap(), 0, |proc, _args| {
+        zlib::open_0(proc)
+    });
+    native.add_simple(
+        Atom::try_from_str("uncompress").unwrap(),
+        1,
+        |proc, args| zlib::uncompress_1(args[0], proc),
+    );
+    native.add_simple(Atom::try_from_str("unzip").unwrap(), 1, |proc, args| {
+        zlib::unzip_1(args[0], proc)
+    });
+    native.add_simple(Atom::try_from_str("zip").unwrap(), 1, |proc, args| {
+        zlib::zip_1(args[0], proc)
+    });
+
+    native
+}
@@ -0,0 +1,102 @@
+//! Implementing Erlang modules in Rust, for embedders of the interpreter.
+//!
+//! Implement `NifModule` for a module and register it with `VM.register_nif_module`.  Calls to
+//! the module are then dispatched by name and arity to the functions it defines, the same as for
+//! the modules the interpreter itself implements in Rust.  The functions get their arguments as
+//! terms on the heap of the calling process: the `*_arg` helpers convert them to Rust values,
+//! failing with `badarg` as NIFs do on the BEAM, while results are allocated with the `Process`
+//! functions, such as `Process::integer` or `Process::binary_from_bytes`.
+//!
+//! ```ignore
+//! struct Math;
+//!
+//! impl NifModule for Math {
+//!     fn name(&self) -> &str {
+//!         "math_nif"
+//!     }
+//!
+//!     fn define(&self, native: &mut NativeModule) {
+//!         native.add_function("add", 2, |proc, args| {
+//!             let sum = nif::isize_arg(args[0])? + nif::isize_arg(args[1])?;
+//!
+//!             Ok(proc.integer(sum)?)
+//!         });
+//!     }
+//! }
+//!
+//! VM.register_nif_module(&Math);
+//! ```
+
+use std::convert::TryInto;
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception::Exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{Atom, BinaryView, Term};
+
+pub use crate::module::{DirtyFunction, NativeFunctionKind, NativeModule};
+
+/// An Erlang module implemented in Rust
+pub trait NifModule {
+    /// The name of the Erlang module
+    fn name(&self) -> &str;
+
+    /// Adds the functions of the module to `native` with `NativeModule::add_function`, with
+    /// `NativeModule::add_dirty` for functions that take too long to run on a normal scheduler,
+    /// or with `NativeModule::add_yielding` for functions that have to return to the scheduler.
+    fn define(&self, native: &mut NativeModule);
+
+    fn to_native_module(&self) -> NativeModule {
+        let mut native = NativeModule::new(Atom::try_from_str(self.name()).unwrap());
+        self.define(&mut native);
+
+        native
+    }
+}
+
+pub fn atom_arg(term: Term) -> Result<Atom, Exception> {
+    Ok(term.try_into()?)
+}
+
+pub fn bool_arg(term: Term) -> Result<bool, Exception> {
+    Ok(term.try_into()?)
+}
+
+pub fn f64_arg(term: Term) -> Result<f64, Exception> {
+    Ok(term.try_into()?)
+}
+
+pub fn isize_arg(term: Term) -> Result<isize, Exception> {
+    Ok(term.try_into()?)
+}
+
+pub fn usize_arg(term: Term) -> Result<usize, Exception> {
+    Ok(term.try_into()?)
+}
+
+/// The bytes of a binary, borrowed without copying for as long as the view is alive.  Bitstrings
+/// with bits in a final partial byte are a `badarg`.
+pub fn binary_arg(process: &Process, term: Term) -> Result<BinaryView, Exception> {
+    let view = BinaryView::new(process, term)?;
+
+    if view.is_binary() {
+        Ok(view)
+    } else {
+        Err(badarg!().into())
+    }
+}
+
+/// A UTF-8 binary or a list of characters, as a `String`
+pub fn string_arg(term: Term) -> Result<String, Exception> {
+    if term.is_list() {
+        term.list_iter()?
+            .map(|result| -> Result<char, Exception> {
+                let c: char = result?.try_into()?;
+
+                Ok(c)
+            })
+            .collect()
+    } else {
+        Ok(term.try_into()?)
+    }
+}
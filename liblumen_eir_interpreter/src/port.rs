@@ -0,0 +1,71 @@
+//! Running `port_command/2,3` as yielding native functions, so that a command to a busy port
+//! suspends only the calling process instead of the scheduler running it.
+//!
+//! The command is first tried when the function is called.  If the port is busy, the process
+//! waits with the arguments, including the continuations, on its stack and `code` as its frame.
+//! Once the port isn't busy, or if a message wakes the process first, `code` tries the command
+//! again, and continues the call with the result once the command isn't suspended.
+
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use liblumen_alloc::erts::process::code::stack::frame::Frame;
+use liblumen_alloc::erts::process::code::Result;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{Atom, Term, TypedTerm};
+use liblumen_alloc::erts::ModuleFunctionArity;
+
+use lumen_runtime::otp::erlang;
+
+/// `port_command/2,3` with the arguments after the return and throw continuations in `args`
+pub fn command(proc: &Arc<Process>, args: &[Term]) -> Result {
+    let module = Atom::try_from_str("erlang").unwrap();
+    let function = Atom::try_from_str("port_command").unwrap();
+
+    let result = match args[2..] {
+        [port, data] => erlang::port_command_2(port, data, proc),
+        [port, data, options] => erlang::port_command_3(port, data, options, proc),
+        _ => unreachable!(),
+    };
+
+    match result.transpose() {
+        Some(result) => {
+            crate::exec::resume_yielded(proc, module, function, result, &mut args.to_vec())
+        }
+        None => suspend(proc, module, function, args),
+    }
+}
+
+// Private
+
+/// Saves the arguments in `args`, including the return and throw continuations, so that `code`
+/// commands the port again once the process is woken
+fn suspend(proc: &Arc<Process>, module: Atom, function: Atom, args: &[Term]) -> Result {
+    let argument_list = proc.list_from_slice(args)?;
+    proc.stack_push(argument_list)?;
+
+    let module_function_arity = Arc::new(ModuleFunctionArity {
+        module,
+        function,
+        arity: (args.len() - 2).try_into().unwrap(),
+    });
+    proc.replace_frame(Frame::new(module_function_arity, code));
+
+    Ok(())
+}
+
+/// Expects the following on stack:
+/// * argument list, including the return and throw continuations
+fn code(arc_process: &Arc<Process>) -> Result {
+    let argument_list = arc_process.stack_pop().unwrap();
+
+    let argument_vec: Vec<Term> = match argument_list.to_typed_term().unwrap() {
+        TypedTerm::List(argument_cons) => argument_cons
+            .into_iter()
+            .map(|result| result.unwrap())
+            .collect(),
+        _ => unreachable!(),
+    };
+
+    command(arc_process, &argument_vec)
+}
@@ -0,0 +1,119 @@
+//! Profiling interpreted code, like `cprof` and `eprof`: while profiling is on, every call through
+//! the interpreter is counted per function, and the reductions and time a process spends between
+//! one call and its next call are charged to the function it called first, so that hotspots are
+//! the functions with the most calls, reductions or time.
+//!
+//! Time is in milliseconds of the monotonic clock and includes any time the process spent waiting
+//! or descheduled, so reductions are the better measure of work for functions that `receive`.
+//!
+//! The profile is read from Rust with `analyse` and from Erlang with `lumen:profile_analyse/0`.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{Atom, Pid};
+use liblumen_alloc::ModuleFunctionArity;
+
+use lumen_runtime::time::monotonic;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Stats {
+    pub calls: u64,
+    pub reductions: u64,
+    pub milliseconds: u64,
+}
+
+/// Starts profiling, clearing any previous profile.
+pub fn start() {
+    let mut profile = PROFILE.lock().unwrap();
+    profile.stats_by_module_function_arity.clear();
+    profile.current_by_pid.clear();
+    ENABLED.store(true, Ordering::SeqCst);
+}
+
+/// Stops profiling, keeping the profile for `analyse`.  The functions that processes were in when
+/// profiling stopped aren't charged for the time since their last call.
+pub fn stop() {
+    ENABLED.store(false, Ordering::SeqCst);
+    PROFILE.lock().unwrap().current_by_pid.clear();
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// The stats of each function called since profiling started, most reductions first
+pub fn analyse() -> Vec<(ModuleFunctionArity, Stats)> {
+    let mut analysis: Vec<(ModuleFunctionArity, Stats)> = PROFILE
+        .lock()
+        .unwrap()
+        .stats_by_module_function_arity
+        .iter()
+        .map(|(module_function_arity, stats)| (*module_function_arity, *stats))
+        .collect();
+    analysis.sort_by(|(_, left), (_, right)| right.reductions.cmp(&left.reductions));
+
+    analysis
+}
+
+// Crate
+
+/// Counts a call by `process` to `module:function/arity`, charging the function `process` called
+/// before it.
+pub(crate) fn call(process: &Process, module: Atom, function: Atom, arity: usize) {
+    if is_enabled() {
+        let module_function_arity = ModuleFunctionArity {
+            module,
+            function,
+            arity: arity as u8,
+        };
+        let now = Current {
+            module_function_arity,
+            reductions: process.reductions(),
+            milliseconds: monotonic::time_in_milliseconds(),
+        };
+
+        let mut profile = PROFILE.lock().unwrap();
+
+        if let Some(previous) = profile.current_by_pid.insert(process.pid(), now) {
+            let stats = profile
+                .stats_by_module_function_arity
+                .entry(previous.module_function_arity)
+                .or_default();
+            stats.reductions += now.reductions.saturating_sub(previous.reductions);
+            stats.milliseconds += now.milliseconds.saturating_sub(previous.milliseconds);
+        }
+
+        profile
+            .stats_by_module_function_arity
+            .entry(module_function_arity)
+            .or_default()
+            .calls += 1;
+    }
+}
+
+// Private
+
+/// The function a process last called and when it called it
+#[derive(Clone, Copy)]
+struct Current {
+    module_function_arity: ModuleFunctionArity,
+    reductions: u64,
+    milliseconds: u64,
+}
+
+#[derive(Default)]
+struct Profile {
+    stats_by_module_function_arity: HashMap<ModuleFunctionArity, Stats>,
+    current_by_pid: HashMap<Pid, Current>,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    static ref PROFILE: Mutex<Profile> = Default::default();
+}
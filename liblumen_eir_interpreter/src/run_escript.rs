@@ -0,0 +1,54 @@
+use std::path::{Path, PathBuf};
+use std::process;
+
+use clap::{App, AppSettings, Arg};
+
+use liblumen_eir_interpreter::escript::{run_script, FAILURE_STATUS};
+use liblumen_eir_interpreter::VM;
+
+use lumen_runtime::scheduler::Scheduler;
+
+fn main() {
+    let matches = App::new("Lumen Eir Interpreter escript runner")
+        .version("alpha")
+        .setting(AppSettings::TrailingVarArg)
+        .arg(
+            Arg::from_usage(
+                "[CODE_PATH] -p,--path <DIR>... 'directories to load missing modules from'",
+            )
+            .number_of_values(1)
+            .required(false),
+        )
+        .arg(Arg::from_usage("<SCRIPT> 'the script to run'"))
+        .arg(Arg::from_usage("[ARGS]... 'arguments passed to main/1'"))
+        .get_matches();
+
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    if let Some(directories) = matches.values_of("CODE_PATH") {
+        VM.code_path
+            .write()
+            .unwrap()
+            .extend(directories.map(PathBuf::from));
+    }
+
+    let script = matches.value_of("SCRIPT").unwrap();
+    let arguments: Vec<String> = match matches.values_of("ARGS") {
+        Some(values) => values.map(String::from).collect(),
+        None => Vec::new(),
+    };
+
+    let status = match run_script(&init_arc_process, Path::new(script), &arguments) {
+        Ok(status) => status,
+        Err(error) => {
+            eprintln!("escript: {}: {}", script, error);
+
+            FAILURE_STATUS
+        }
+    };
+
+    process::exit(status);
+}
@@ -0,0 +1,66 @@
+use std::path::{Path, PathBuf};
+use std::process;
+
+use clap::{App, Arg};
+
+use liblumen_eir_interpreter::suite::{load_config, run_suite};
+use liblumen_eir_interpreter::VM;
+
+use liblumen_alloc::erts::term::Atom;
+
+use lumen_runtime::scheduler::Scheduler;
+
+fn main() {
+    let matches = App::new("Lumen Eir Interpreter Common Test runner")
+        .version("alpha")
+        .arg(Arg::from_usage("<SUITES> 'suite modules to run'").multiple(true))
+        .arg(
+            Arg::from_usage(
+                "[CODE_PATH] -p,--path <DIR>... 'directories to load suites and other modules from'",
+            )
+            .required(false),
+        )
+        .arg(
+            Arg::from_usage("[CONFIG] -c,--config <FILE> 'terms for ct:get_config'")
+                .required(false),
+        )
+        .get_matches();
+
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    if let Some(directories) = matches.values_of("CODE_PATH") {
+        VM.code_path
+            .write()
+            .unwrap()
+            .extend(directories.map(PathBuf::from));
+    }
+
+    if let Some(config) = matches.value_of("CONFIG") {
+        if let Err(error) = load_config(&init_arc_process, Path::new(config)) {
+            println!("{}: could not be loaded ({})", config, error);
+            process::exit(1);
+        }
+    }
+
+    let mut failed = 0;
+
+    for suite in matches.values_of("SUITES").unwrap() {
+        match run_suite(&init_arc_process, Atom::try_from_str(suite).unwrap()) {
+            Ok(report) => {
+                println!("{}", report);
+                failed += report.failed();
+            }
+            Err(error) => {
+                println!("{}: could not be loaded ({:?})", suite, error);
+                failed += 1;
+            }
+        }
+    }
+
+    if 0 < failed {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,421 @@
+//! Runs Common Test suites, such as those of the OTP stdlib, under the interpreter to measure how
+//! much real-world code works.
+//!
+//! This is only the part of Common Test that most suites need: the cases are taken from `all/0`,
+//! expanding `{group, Name}` from `groups/0`, and each case runs as its own process with the
+//! `Config` from `init_per_suite/1` and `init_per_testcase/2`, if the suite exports them.  Group
+//! properties and `init_per_group/2` are ignored.
+//!
+//! Cases can use the `ct` natives for `ct:fail`, `ct:log` and `ct:get_config`.  What a case logs
+//! is kept in its report, and the config comes from `load_config`.
+
+use std::fmt::{self, Display};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use liblumen_alloc::erts::process::{Process, Status};
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Term, TypedTerm};
+
+use lumen_runtime::scheduler::Scheduler;
+
+use crate::call_result::{call_erlang, call_run_erlang};
+use crate::code_server;
+use crate::compile::compile_str;
+use crate::module::LoadError;
+use crate::native;
+use crate::VM;
+
+/// How long a case or callback may run before it fails
+const TIMETRAP: Duration = Duration::from_secs(30);
+
+pub enum Outcome {
+    Passed,
+    Failed(String),
+    Skipped(String),
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Passed => write!(f, "ok"),
+            Outcome::Failed(reason) => write!(f, "FAILED {}", reason),
+            Outcome::Skipped(reason) => write!(f, "SKIPPED {}", reason),
+        }
+    }
+}
+
+pub struct CaseReport {
+    pub case: Atom,
+    pub outcome: Outcome,
+    /// What the case logged with `ct:log`, `ct:pal` and `ct:comment`
+    pub log: Vec<String>,
+}
+
+pub struct SuiteReport {
+    pub suite: Atom,
+    pub cases: Vec<CaseReport>,
+}
+
+impl SuiteReport {
+    pub fn passed(&self) -> usize {
+        self.count(|outcome| match outcome {
+            Outcome::Passed => true,
+            _ => false,
+        })
+    }
+
+    pub fn failed(&self) -> usize {
+        self.count(|outcome| match outcome {
+            Outcome::Failed(_) => true,
+            _ => false,
+        })
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.count(|outcome| match outcome {
+            Outcome::Skipped(_) => true,
+            _ => false,
+        })
+    }
+
+    fn count<F: Fn(&Outcome) -> bool>(&self, predicate: F) -> usize {
+        self.cases
+            .iter()
+            .filter(|report| predicate(&report.outcome))
+            .count()
+    }
+}
+
+impl Display for SuiteReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for report in &self.cases {
+            writeln!(
+                f,
+                "{}:{} {}",
+                self.suite.name(),
+                report.case.name(),
+                report.outcome
+            )?;
+
+            if let Outcome::Failed(_) = report.outcome {
+                for line in &report.log {
+                    writeln!(f, "    {}", line)?;
+                }
+            }
+        }
+
+        write!(
+            f,
+            "{}: {} passed, {} failed, {} skipped",
+            self.suite.name(),
+            self.passed(),
+            self.failed(),
+            self.skipped()
+        )
+    }
+}
+
+/// Loads `suite` from the code path, if it is not loaded already, and runs all its cases.
+pub fn run_suite(parent: &Arc<Process>, suite: Atom) -> Result<SuiteReport, LoadError> {
+    code_server::ensure_loaded(&VM, suite)?;
+
+    let runner = Runner {
+        parent: parent.clone(),
+        suite,
+    };
+
+    let cases = match runner.call("all", &[]) {
+        Ok(all) => runner.expand(all),
+        Err(reason) => {
+            return Ok(SuiteReport {
+                suite,
+                cases: vec![CaseReport {
+                    case: Atom::try_from_str("all").unwrap(),
+                    outcome: Outcome::Failed(reason),
+                    log: native::take_log(),
+                }],
+            })
+        }
+    };
+
+    let suite_config = if runner.exports("init_per_suite", 1) {
+        runner.call("init_per_suite", &[Term::NIL])
+    } else {
+        Ok(Term::NIL)
+    };
+
+    let reports = cases
+        .into_iter()
+        .map(|case| {
+            // Drop anything logged by the callbacks, so each report only has its case's log
+            native::take_log();
+
+            let outcome = match &suite_config {
+                Ok(config) => match skip_reason(*config) {
+                    Some(reason) => Outcome::Skipped(reason),
+                    None => runner.run_case(case, *config),
+                },
+                Err(reason) => Outcome::Failed(format!("init_per_suite failed: {}", reason)),
+            };
+
+            CaseReport {
+                case,
+                outcome,
+                log: native::take_log(),
+            }
+        })
+        .collect();
+
+    if let Ok(config) = suite_config {
+        if skip_reason(config).is_none() && runner.exports("end_per_suite", 1) {
+            let _ = runner.call("end_per_suite", &[config]);
+        }
+    }
+
+    Ok(SuiteReport {
+        suite,
+        cases: reports,
+    })
+}
+
+/// Sets the config for `ct:get_config` to the `{Key, Value}.` terms in the file at `path`, as
+/// `ct_run -config` does.
+pub fn load_config(parent: &Arc<Process>, path: &Path) -> Result<(), String> {
+    let source = fs::read_to_string(path).map_err(|error| error.to_string())?;
+    let terms = split_terms(&source);
+    let module = format!(
+        "-module(lumen_ct_config).\n-export([terms/0]).\nterms() -> [{}].\n",
+        terms.join(", ")
+    );
+    let module = compile_str(&module).map_err(|_| format!("{} is not valid", path.display()))?;
+
+    VM.modules.write().unwrap().register_erlang_module(module);
+
+    // The result's heap is never freed, so it can be kept as the config
+    let result = call_run_erlang(
+        parent.clone(),
+        Atom::try_from_str("lumen_ct_config").unwrap(),
+        Atom::try_from_str("terms").unwrap(),
+        &[],
+    );
+
+    match result.result {
+        Ok(config) => {
+            native::set_config(config);
+
+            Ok(())
+        }
+        Err((class, reason, _)) => Err(format!("{}:{}", class, reason)),
+    }
+}
+
+/// The terms of a file of `Term.`, without the full stops or comments
+fn split_terms(source: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    let mut term = String::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '%' => {
+                while let Some(&c) = chars.peek() {
+                    if c == '\n' {
+                        break;
+                    }
+
+                    chars.next();
+                }
+            }
+            '"' | '\'' => {
+                term.push(c);
+
+                while let Some(quoted) = chars.next() {
+                    term.push(quoted);
+
+                    if quoted == '\\' {
+                        if let Some(escaped) = chars.next() {
+                            term.push(escaped);
+                        }
+                    } else if quoted == c {
+                        break;
+                    }
+                }
+            }
+            '$' => {
+                term.push(c);
+
+                if let Some(character) = chars.next() {
+                    term.push(character);
+
+                    if character == '\\' {
+                        if let Some(escaped) = chars.next() {
+                            term.push(escaped);
+                        }
+                    }
+                }
+            }
+            // A full stop is followed by whitespace, unlike the point of a float
+            '.' if chars
+                .peek()
+                .map_or(true, |c| c.is_whitespace() || *c == '%') =>
+            {
+                if !term.trim().is_empty() {
+                    terms.push(term.trim().to_string());
+                }
+
+                term.clear();
+            }
+            _ => term.push(c),
+        }
+    }
+
+    terms
+}
+
+struct Runner {
+    parent: Arc<Process>,
+    suite: Atom,
+}
+
+impl Runner {
+    fn exports(&self, function: &str, arity: usize) -> bool {
+        VM.modules
+            .read()
+            .unwrap()
+            .lookup_function(self.suite, Atom::try_from_str(function).unwrap(), arity)
+            .is_some()
+    }
+
+    /// Case names from `all/0`, with `{group, Name}` replaced by the cases of the group
+    fn expand(&self, cases: Term) -> Vec<Atom> {
+        let mut expanded = Vec::new();
+
+        for case in list_elements(cases) {
+            match case.to_typed_term().unwrap() {
+                TypedTerm::Atom(atom) => expanded.push(atom),
+                _ => {
+                    let elements = tuple_elements(case);
+
+                    if elements.len() == 2 && elements[0] == atom_unchecked("group") {
+                        expanded.extend(self.expand(self.group(elements[1])));
+                    }
+                }
+            }
+        }
+
+        expanded
+    }
+
+    /// The cases of the group `name` in `groups/0`
+    fn group(&self, name: Term) -> Term {
+        let groups = match self.call("groups", &[]) {
+            Ok(groups) => groups,
+            Err(_) => return Term::NIL,
+        };
+
+        list_elements(groups)
+            .into_iter()
+            .map(tuple_elements)
+            .find(|group| group.len() == 3 && group[0] == name)
+            .map(|group| group[2])
+            .unwrap_or(Term::NIL)
+    }
+
+    fn run_case(&self, case: Atom, suite_config: Term) -> Outcome {
+        let case_term = atom_unchecked(case.name());
+
+        let config = if self.exports("init_per_testcase", 2) {
+            match self.call("init_per_testcase", &[case_term, suite_config]) {
+                Ok(config) => config,
+                Err(reason) => {
+                    return Outcome::Failed(format!("init_per_testcase failed: {}", reason))
+                }
+            }
+        } else {
+            suite_config
+        };
+
+        if let Some(reason) = skip_reason(config) {
+            return Outcome::Skipped(reason);
+        }
+
+        let outcome = match self.call(case.name(), &[config]) {
+            Ok(result) => {
+                let elements = tuple_elements(result);
+
+                if let Some(reason) = skip_reason(result) {
+                    Outcome::Skipped(reason)
+                } else if elements.len() == 2 && elements[0] == atom_unchecked("fail") {
+                    Outcome::Failed(elements[1].to_string())
+                } else {
+                    Outcome::Passed
+                }
+            }
+            Err(reason) => Outcome::Failed(reason),
+        };
+
+        if self.exports("end_per_testcase", 2) {
+            let _ = self.call("end_per_testcase", &[case_term, config]);
+        }
+
+        outcome
+    }
+
+    /// Calls `function` of the suite in a new process, returning its result or describing how it
+    /// failed.
+    fn call(&self, function: &str, arguments: &[Term]) -> Result<Term, String> {
+        let function = Atom::try_from_str(function).unwrap();
+        let receiver = call_erlang(self.parent.clone(), self.suite, function, arguments);
+        let arc_process = receiver.process.clone();
+        let deadline = Instant::now() + TIMETRAP;
+
+        loop {
+            let ran = Scheduler::current().run_through(&arc_process);
+
+            if let Status::Exiting(ref exception) = *arc_process.status.read() {
+                return match receiver.try_get() {
+                    Some(result) => result
+                        .result
+                        .map_err(|(class, reason, _)| format!("{}:{}", class, reason)),
+                    None => Err(format!("exited with {}", exception.reason)),
+                };
+            }
+
+            if Instant::now() > deadline {
+                return Err("timetrap timeout".to_string());
+            }
+
+            if !ran {
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+    }
+}
+
+/// The reason of `{skip, Reason}`
+fn skip_reason(term: Term) -> Option<String> {
+    let elements = tuple_elements(term);
+
+    if elements.len() == 2 && elements[0] == atom_unchecked("skip") {
+        Some(elements[1].to_string())
+    } else {
+        None
+    }
+}
+
+fn list_elements(list: Term) -> Vec<Term> {
+    match list.list_iter() {
+        Ok(iter) => iter.filter_map(Result::ok).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn tuple_elements(tuple: Term) -> Vec<Term> {
+    match tuple.tuple_elements() {
+        Ok(elements) => elements.collect(),
+        Err(_) => Vec::new(),
+    }
+}
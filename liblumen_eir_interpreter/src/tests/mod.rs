@@ -1,3 +1,5 @@
+use std::convert::TryInto;
+
 use super::VM;
 
 use libeir_diagnostics::{ColorChoice, Emitter, StandardStreamEmitter};
@@ -10,7 +12,9 @@ use libeir_syntax_erl::ast::Module as ErlAstModule;
 use libeir_syntax_erl::lower_module;
 use libeir_syntax_erl::{Parse, ParseConfig, Parser};
 
-use liblumen_alloc::erts::term::{atom_unchecked, Atom};
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Boxed, Cons, Term};
+
+use liblumen_beam::syntax::core_erlang;
 
 use lumen_runtime::scheduler::Scheduler;
 
@@ -58,6 +62,15 @@ pub fn compile(input: &str) -> Module {
     eir_mod
 }
 
+pub fn compile_core(input: &str) -> Module {
+    let source = core_erlang::parse_module(input)
+        .unwrap()
+        .to_erlang()
+        .unwrap();
+
+    compile(&source)
+}
+
 #[test]
 fn simple_function() {
     &*VM;
@@ -142,70 +155,61 @@ a() -> 1 + a.
 }
 
 #[test]
-fn fib_gc() {
+fn undefined_function_test() {
     &*VM;
 
     let arc_scheduler = Scheduler::current();
     let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
 
-    let module = Atom::try_from_str("fib2").unwrap();
-    let function = Atom::try_from_str("fib").unwrap();
+    let module = Atom::try_from_str("undefined_function_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
 
     let eir_mod = compile(
         "
--module(fib2).
+-module(undefined_function_test).
 
-fib(0) -> 0;
-fib(1) -> 1;
-fib(X) -> fib(X - 1) + fib(X - 2).
+run() -> undefined_function_test_missing:missing(1).
 ",
     );
 
     VM.modules.write().unwrap().register_erlang_module(eir_mod);
 
-    let int = init_arc_process.integer(14).unwrap();
-    let res =
-        crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[int]);
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
 
-    let int = init_arc_process.integer(377).unwrap();
-    assert!(res.result == Ok(int));
+    assert!(res.result.is_err());
+    if let Err((typ, reason, _trace)) = res.result {
+        assert!(typ == atom_unchecked("error"));
+        assert!(reason == atom_unchecked("undef"));
+    }
 }
 
 #[test]
-fn ping_pong() {
+fn error_handler_test() {
     &*VM;
 
     let arc_scheduler = Scheduler::current();
     let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
 
-    let module = Atom::try_from_str("ping_pong").unwrap();
+    let module = Atom::try_from_str("error_handler_test").unwrap();
     let function = Atom::try_from_str("run").unwrap();
 
     let eir_mod = compile(
         "
--module(ping_pong).
+-module(error_handler_test).
 
-proc_a(A) ->
-    receive
-        {b, R} -> R ! c
-    end.
+run() ->
+    process_flag(error_handler, error_handler_test_stub),
+    error_handler_test_missing:missing(1).
+",
+    );
 
-proc_b(A, B) ->
-    receive
-        a ->
-            B ! {b, self()},
-            proc_b(A, B);
-        c ->
-            A ! d
-    end.
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
 
-run() ->
-    P1 = spawn(ping_pong, proc_a, [self()]),
-    P2 = spawn(ping_pong, proc_b, [self(), P1]),
-    P2 ! a,
-    receive
-        Res -> Res
-    end.
+    let eir_mod = compile(
+        "
+-module(error_handler_test_stub).
+
+undefined_function(error_handler_test_missing, missing, [1]) -> stubbed.
 ",
     );
 
@@ -213,82 +217,1631 @@ run() ->
 
     let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
 
-    assert!(res.result == Ok(atom_unchecked("d")));
+    assert!(res.result == Ok(atom_unchecked("stubbed")));
 }
 
 #[test]
-fn ping_pong_count() {
+fn hot_code_loading_test() {
     &*VM;
 
     let arc_scheduler = Scheduler::current();
     let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
 
-    let module = Atom::try_from_str("ping_pong_count").unwrap();
+    let module = Atom::try_from_str("hot_code_loading_test").unwrap();
     let function = Atom::try_from_str("run").unwrap();
 
     let eir_mod = compile(
         "
--module(ping_pong_count).
+-module(hot_code_loading_test).
 
-other_proc({add, A, B}, Ret) -> Ret ! {result, A + B}.
+version() -> one.
 
-this_proc(0, Acc) ->
-    Acc;
-this_proc(N, Acc) ->
-    spawn(ping_pong_count, other_proc, [{add, 1, Acc}, self()]),
+loop() ->
     receive
-        {result, Res} -> this_proc(N - 1, Res)
+        {From, local} ->
+            From ! version(),
+            loop();
+        {From, remote} ->
+            From ! hot_code_loading_test:version(),
+            loop()
     end.
 
-run(N) -> this_proc(N, 0).
+run(Source) ->
+    Pid = spawn(hot_code_loading_test, loop, []),
+    Pid ! {self(), local},
+    receive
+        one -> ok
+    end,
+    {module, hot_code_loading_test} =
+        code:load_binary(hot_code_loading_test, \"hot_code_loading_test.erl\", Source),
+    Pid ! {self(), local},
+    Local = receive
+        LocalVersion -> LocalVersion
+    end,
+    Pid ! {self(), remote},
+    Remote = receive
+        RemoteVersion -> RemoteVersion
+    end,
+    {Local, Remote}.
 ",
     );
 
     VM.modules.write().unwrap().register_erlang_module(eir_mod);
 
-    let int = init_arc_process.integer(10).unwrap();
+    let source = init_arc_process
+        .binary_from_str(
+            "
+-module(hot_code_loading_test).
+
+version() -> two.
+",
+        )
+        .unwrap();
+
     let res =
-        crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[int]);
+        crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[source]);
 
-    println!("{:?}", res.result);
-    //assert!(res.result == Ok(100));
+    let expected = init_arc_process
+        .tuple_from_slice(&[atom_unchecked("one"), atom_unchecked("two")])
+        .unwrap();
+    assert!(res.result == Ok(expected));
+
+    assert!(VM.modules.read().unwrap().has_old_code(module));
+
+    let eir_mod = compile(
+        "
+-module(hot_code_loading_test).
+
+version() -> three.
+",
+    );
+
+    assert!(VM
+        .modules
+        .write()
+        .unwrap()
+        .load_erlang_module(eir_mod)
+        .is_err());
 }
 
-#[ignore]
 #[test]
-fn ping_pong_count_large() {
+fn purge_test() {
     &*VM;
 
     let arc_scheduler = Scheduler::current();
     let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
 
-    let module = Atom::try_from_str("ping_pong_count_large").unwrap();
+    let module = Atom::try_from_str("purge_test_driver").unwrap();
     let function = Atom::try_from_str("run").unwrap();
 
     let eir_mod = compile(
         "
--module(ping_pong_count_large).
-
-other_proc({add, A, B}, Ret) -> Ret ! {result, A + B}.
+-module(purge_test).
 
-this_proc(0, Acc) ->
-    Acc;
-this_proc(N, Acc) ->
-    spawn(ping_pong_count, other_proc, [{add, 1, Acc}, self()]),
+loop() ->
     receive
-        {result, Res} -> this_proc(N - 1, Res)
+        {From, ping} ->
+            From ! pong,
+            loop()
     end.
+",
+    );
 
-run(N) -> this_proc(N, 0).
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let eir_mod = compile(
+        "
+-module(purge_test_driver).
+
+run(Source) ->
+    Pid = spawn(purge_test, loop, []),
+    Pid ! {self(), ping},
+    receive
+        pong -> ok
+    end,
+    {module, purge_test} = code:load_binary(purge_test, \"purge_test.erl\", Source),
+    true = erlang:check_process_code(Pid, purge_test),
+    false = code:soft_purge(purge_test),
+    true = code:purge(purge_test),
+    false = erlang:check_process_code(Pid, purge_test),
+    false = code:purge(purge_test),
+    {module, purge_test} = code:load_binary(purge_test, \"purge_test.erl\", Source),
+    true = code:soft_purge(purge_test),
+    ok.
 ",
     );
 
     VM.modules.write().unwrap().register_erlang_module(eir_mod);
 
-    let int = init_arc_process.integer(100).unwrap();
+    let source = init_arc_process
+        .binary_from_str(
+            "
+-module(purge_test).
+
+loop() -> ok.
+",
+        )
+        .unwrap();
+
     let res =
-        crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[int]);
+        crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[source]);
+
+    assert!(res.result == Ok(atom_unchecked("ok")));
+    assert!(!VM
+        .modules
+        .read()
+        .unwrap()
+        .has_old_code(Atom::try_from_str("purge_test").unwrap()));
+}
 
-    println!("{:?}", res.result);
-    //assert!(res.result == Ok(100));
+#[test]
+fn code_path_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("code_path_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let directory = std::env::temp_dir().join("lumen_code_path_test");
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::write(
+        directory.join("code_path_test_autoloaded.erl"),
+        "
+-module(code_path_test_autoloaded).
+
+hello() -> world.
+",
+    )
+    .unwrap();
+
+    let eir_mod = compile(
+        "
+-module(code_path_test).
+
+run(Directory) ->
+    {error, nofile} = code:ensure_loaded(code_path_test_missing),
+    true = code:add_patha(Directory),
+    {error, bad_directory} = code:add_pathz(\"code_path_test_missing\"),
+    world = code_path_test_autoloaded:hello(),
+    {module, code_path_test_autoloaded} = code:ensure_loaded(code_path_test_autoloaded),
+    ok.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let directory_term = init_arc_process
+        .charlist_from_str(directory.to_str().unwrap())
+        .unwrap();
+
+    let res = crate::call_result::call_run_erlang(
+        init_arc_process.clone(),
+        module,
+        function,
+        &[directory_term],
+    );
+
+    assert!(res.result == Ok(atom_unchecked("ok")));
+    assert!(VM.code_path.read().unwrap()[0] == directory);
+}
+
+#[test]
+fn beam_loading_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("beam_loading_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(beam_loading_test).
+
+run(Beam) ->
+    {module, test} = code:load_binary(test, \"test.beam\", Beam),
+    {test:op(1), test:sum([1, 2, 3])}.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let bytes = std::fs::read("../liblumen_beam/tests/testdata/ast/test.beam").unwrap();
+    let beam = init_arc_process.binary_from_bytes(&bytes).unwrap();
+
+    let res =
+        crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[beam]);
+
+    let expected = init_arc_process
+        .tuple_from_slice(&[
+            init_arc_process.integer(2).unwrap(),
+            init_arc_process.integer(6).unwrap(),
+        ])
+        .unwrap();
+    assert!(res.result == Ok(expected));
+}
+
+#[test]
+fn core_erlang_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("core_erlang_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile_core(
+        "
+module 'core_erlang_test' ['run'/0, 'fact'/1]
+    attributes []
+'run'/0 =
+    fun () ->
+        let <Fact> = apply 'fact'/1(5)
+        in  letrec 'lc$^0'/1 =
+                fun (_4) ->
+                    case _4 of
+                      <[X|Xs]> when 'true' ->
+                          let <_5> = call 'erlang':'*'(X, 2)
+                          in  let <_6> = apply 'lc$^0'/1(Xs)
+                              in  [_5|_6]
+                      <[]> when 'true' -> []
+                    end
+            in  let <Doubled> = apply 'lc$^0'/1([1|[2|[3]]])
+                in  {Fact, Doubled}
+'fact'/1 =
+    fun (_0) ->
+        case _0 of
+          <0> when 'true' -> 1
+          <N> when call 'erlang':'>'(_0, 0) ->
+              let <_1> = call 'erlang':'-'(N, 1)
+              in  let <_2> = apply 'fact'/1(_1)
+                  in  call 'erlang':'*'(N, _2)
+          ( <_3> when 'true' ->
+                primop 'match_fail'({'function_clause', _3})
+            -| ['compiler_generated'] )
+        end
+end
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    let expected = init_arc_process
+        .tuple_from_slice(&[
+            init_arc_process.integer(120).unwrap(),
+            init_arc_process
+                .list_from_slice(&[
+                    init_arc_process.integer(2).unwrap(),
+                    init_arc_process.integer(4).unwrap(),
+                    init_arc_process.integer(6).unwrap(),
+                ])
+                .unwrap(),
+        ])
+        .unwrap();
+    assert!(res.result == Ok(expected));
+}
+
+#[test]
+fn bitwise_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("bitwise_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(bitwise_test).
+
+run() ->
+    2 = 6 band 3,
+    7 = 6 bor 3,
+    5 = 6 bxor 3,
+    -7 = bnot 6,
+    1024 = 1 bsl 10,
+    -1 = -7 bsr 3,
+    Big = 1 bsl 65,
+    36893488147419103232 = Big,
+    1 = Big bsr 65,
+    0 = Big band 1,
+    ok.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(atom_unchecked("ok")));
+}
+
+#[test]
+fn list_operators_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("list_operators_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(list_operators_test).
+
+run() ->
+    Improper = [2 | 3],
+    [1, 2, 3] = [1] ++ [2, 3],
+    [1, 2 | 3] = [1] ++ Improper,
+    [1 | three] = [1] ++ three,
+    badarg = try Improper ++ [4] catch error:Reason1 -> Reason1 end,
+    [1, 3, 1] = [1, 2, 1, 3, 1] -- [1, 2],
+    badarg = try Improper -- [2] catch error:Reason2 -> Reason2 end,
+    badarg = try [2] -- Improper catch error:Reason3 -> Reason3 end,
+    ok.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(atom_unchecked("ok")));
+}
+
+#[test]
+fn queue_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("queue_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(queue_test).
+
+run() ->
+    Q0 = queue:from_list([2, 3]),
+    Q1 = queue:in({four}, queue:in_r(1, Q0)),
+    {{value, 1}, Q2} = queue:out(Q1),
+    {{value, {four}}, Q3} = queue:out_r(Q2),
+    Q4 = queue:in(5, Q2),
+    Q5 = queue:in(6, Q3),
+    [1, 2, 3, {four}] = queue:to_list(Q1),
+    [2, 3, {four}, 5] = queue:to_list(Q4),
+    [2, 3, 6] = queue:to_list(Q5),
+    4 = queue:len(Q4),
+    true = queue:member({four}, Q4),
+    false = queue:member({four}, Q5),
+    {value, 6} = queue:peek_r(Q5),
+    {empty, Q6} = queue:out(queue:new()),
+    true = queue:is_empty(Q6),
+    [6, 3, 2, 2, 3] = queue:to_list(queue:join(queue:reverse(Q5), Q0)),
+    % queues are the same terms as the stdlib makes
+    {[3], [2]} = Q0,
+    true = queue:from_list([1, 2]) =:= queue:in(2, queue:in(1, queue:new())),
+    true = term_to_binary(Q0) =:= term_to_binary({[3], [2]}),
+    ok.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(atom_unchecked("ok")));
+}
+
+#[test]
+fn suite_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let suite = Atom::try_from_str("runner_SUITE").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(runner_SUITE).
+
+-export([all/0, groups/0, init_per_suite/1, init_per_testcase/2]).
+-export([passes/1, fails/1, skips/1, grouped/1]).
+
+all() -> [passes, fails, skips, {group, more}].
+
+groups() -> [{more, [], [grouped]}].
+
+init_per_suite(Config) -> [{suite, true} | Config].
+
+init_per_testcase(_Case, Config) -> [{testcase, true} | Config].
+
+passes(Config) ->
+    {suite, true} = lists:keyfind(suite, 1, Config),
+    ok.
+
+fails(_Config) ->
+    1 = 2.
+
+skips(_Config) ->
+    {skip, not_supported}.
+
+grouped(Config) ->
+    {testcase, true} = lists:keyfind(testcase, 1, Config).
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let report = crate::suite::run_suite(&init_arc_process, suite).unwrap();
+
+    assert_eq!(report.cases.len(), 4);
+    assert_eq!(report.passed(), 2);
+    assert_eq!(report.failed(), 1);
+    assert_eq!(report.skipped(), 1);
+}
+
+#[test]
+fn ct_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let suite = Atom::try_from_str("ct_SUITE").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(ct_SUITE).
+
+-export([all/0, logs/1, fails/1]).
+
+all() -> [logs, fails].
+
+logs(_Config) ->
+    ok = ct:log(\"~p and ~s~n\", [logs, \"text\"]),
+    ok = ct:pal(<<\"printed\">>),
+    default = ct:get_config(missing, default),
+    undefined = ct:get_config({missing, key}),
+    ok.
+
+fails(_Config) ->
+    ct:fail(\"~w went wrong\", [it]).
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let report = crate::suite::run_suite(&init_arc_process, suite).unwrap();
+
+    assert_eq!(report.passed(), 1);
+    assert_eq!(report.failed(), 1);
+
+    match &report.cases[1].outcome {
+        crate::suite::Outcome::Failed(reason) => assert!(reason.contains("test_case_failed")),
+        _ => panic!("fails/1 did not fail"),
+    }
+}
+
+#[test]
+fn counters_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("counters_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(counters_test).
+
+run() ->
+    Atomics = counters:new(2, [atomics]),
+    ok = counters:add(Atomics, 1, 5),
+    ok = counters:sub(Atomics, 1, 2),
+    ok = counters:put(Atomics, 2, -7),
+    3 = counters:get(Atomics, 1),
+    -7 = counters:get(Atomics, 2),
+    Striped = counters:new(1, [write_concurrency]),
+    ok = counters:add(Striped, 1, 10),
+    ok = counters:sub(Striped, 1, 1),
+    9 = counters:get(Striped, 1),
+    ok = counters:put(Striped, 1, 100),
+    100 = counters:get(Striped, 1),
+    Default = counters:new(1),
+    0 = counters:get(Default, 1),
+    ok.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(atom_unchecked("ok")));
+}
+
+#[test]
+fn net_kernel_monitor_nodes_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("net_kernel_monitor_nodes_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(net_kernel_monitor_nodes_test).
+
+run() ->
+    ok = net_kernel:monitor_nodes(true),
+    ok = net_kernel:monitor_nodes(true, [nodedown_reason, {node_type, visible}]),
+    ok = net_kernel:monitor_nodes(false, [nodedown_reason, {node_type, visible}]),
+    net_kernel:monitor_nodes(false).
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(atom_unchecked("ok")));
+}
+
+#[test]
+fn global_register_name_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("global_register_name_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(global_register_name_test).
+
+run() ->
+    undefined = global:whereis_name(global_register_name_test),
+    yes = global:register_name(global_register_name_test, self()),
+    no = global:register_name(global_register_name_test, self()),
+    Self = self(),
+    Self = global:whereis_name(global_register_name_test),
+    Self = global:send(global_register_name_test, hello),
+    receive
+        hello -> ok
+    end,
+    try global:send(global_register_name_test_unregistered, hello) of
+        _ -> registered
+    catch
+        exit:{badarg, {global_register_name_test_unregistered, hello}} -> ok
+    end.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(atom_unchecked("ok")));
+}
+
+#[test]
+fn global_register_name_with_term_name_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("global_register_name_with_term_name_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(global_register_name_with_term_name_test).
+
+run() ->
+    Name = {global_register_name_with_term_name_test, #{1 => <<\"one\">>}},
+    EqualName = {global_register_name_with_term_name_test, #{1 => <<\"one\">>}},
+    FloatKeyName = {global_register_name_with_term_name_test, #{1.0 => <<\"one\">>}},
+    undefined = global:whereis_name(Name),
+    yes = global:register_name(Name, self()),
+    no = global:register_name(EqualName, self()),
+    undefined = global:whereis_name(FloatKeyName),
+    Self = self(),
+    Self = global:whereis_name(Name),
+    Self = global:send(Name, hello),
+    receive
+        hello -> ok
+    end,
+    try global:register_name(fun() -> ok end, self()) of
+        _ -> registered
+    catch
+        error:badarg -> ok
+    end.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(atom_unchecked("ok")));
+}
+
+#[test]
+fn open_port_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("open_port_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(open_port_test).
+
+run() ->
+    Port = open_port({spawn, \"head -n 1\"}, [binary, exit_status, {line, 80}]),
+    true = port_command(Port, [<<\"hel\">>, \"lo\\n\"]),
+    receive
+        {Port, {data, {eol, <<\"hello\">>}}} -> ok
+    end,
+    receive
+        {Port, {exit_status, 0}} -> ok
+    end.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(atom_unchecked("ok")));
+}
+
+#[test]
+fn port_close_message_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("port_close_message_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(port_close_message_test).
+
+run() ->
+    Port = open_port({spawn, \"cat > /dev/null\"}, []),
+    true = port_command(Port, \"queued\", [nosuspend]),
+    Port ! {self(), {command, <<\"sent\">>}},
+    Port ! {self(), close},
+    receive
+        {Port, closed} -> ok
+    end.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(atom_unchecked("ok")));
+}
+
+#[test]
+fn port_command_to_busy_port_suspends_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("port_command_busy_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(port_command_busy_test).
+
+run() ->
+    Port = open_port({spawn, \"sleep 0.2; exec cat > /dev/null\"}, []),
+    Data = kilobyte(1024, []),
+    ok = fill(Port, Data),
+    true = port_command(Port, Data),
+    true = port_close(Port),
+    ok.
+
+kilobyte(0, Acc) -> Acc;
+kilobyte(N, Acc) -> kilobyte(N - 1, [0 | Acc]).
+
+fill(Port, Data) ->
+    case port_command(Port, Data, [nosuspend]) of
+        true -> fill(Port, Data);
+        false -> ok
+    end.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(atom_unchecked("ok")));
+}
+
+#[test]
+fn io_parse_term_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("io_parse_term_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(io_parse_term_test).
+
+run() ->
+    more = lumen_io:parse_term([\"{a,\\n\"]),
+    {ok, {a, [1, -2.5 | \"t\"], 'B c'}} =
+        lumen_io:parse_term([\" 'B c'}.\\n\", \"{a, [1, -2.5 | \\\"t\\\"],\\n\"]),
+    {error, {1, erl_parse, _}} = lumen_io:parse_term([\"{a b}.\\n\"]),
+    ok.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(atom_unchecked("ok")));
+}
+
+#[test]
+fn io_group_leader_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+    let (server_arc_process, captured) =
+        lumen_runtime::system::io::server::spawn_capturing(&init_arc_process).unwrap();
+    init_arc_process.set_group_leader_pid(server_arc_process.pid());
+
+    let module = Atom::try_from_str("io_group_leader_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(io_group_leader_test).
+
+run() ->
+    ok = io:format(\"~p and ~s~n\", [a, \"b\"]),
+    ok = io:put_chars(<<\"x\">>),
+    ok = io:nl(),
+    group_leader().
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(server_arc_process.pid_term()));
+    assert_eq!(captured.take(), "a and b\nx\n");
+}
+
+#[test]
+fn lumen_config_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("lumen_config_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(lumen_config_test).
+
+run() ->
+    Config = lumen:config(),
+    WordSize = maps:get(word_size, Config),
+    WordSize = lumen:config(word_size),
+    badarg = try lumen:config(unknown) catch error:Reason -> Reason end,
+    WordSize.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(
+        res.result
+            == Ok(init_arc_process
+                .integer(std::mem::size_of::<usize>())
+                .unwrap())
+    );
+}
+
+#[test]
+fn profile_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("profile_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(profile_test).
+
+count(0) -> done;
+count(N) -> count(N - 1).
+
+run() ->
+    ok = lumen:profile_start(),
+    done = count(10),
+    ok = lumen:profile_stop(),
+    Analysis = lumen:profile_analyse(),
+    {_, Calls, _, _} = lists:keyfind({profile_test, count, 1}, 1, Analysis),
+    Calls.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(init_arc_process.integer(11).unwrap()));
+}
+
+#[test]
+fn nif_module_test() {
+    use crate::nif::{self, NativeModule, NifModule};
+
+    struct NifTest;
+
+    impl NifModule for NifTest {
+        fn name(&self) -> &str {
+            "nif_test_natives"
+        }
+
+        fn define(&self, native: &mut NativeModule) {
+            native.add_function("add", 2, |proc, args| {
+                let sum = nif::isize_arg(args[0])? + nif::isize_arg(args[1])?;
+
+                Ok(proc.integer(sum)?)
+            });
+            native.add_function("byte_size", 1, |proc, args| {
+                let view = nif::binary_arg(proc, args[0])?;
+
+                Ok(proc.integer(view.full_bytes().len())?)
+            });
+            native.add_function("greet", 1, |proc, args| {
+                let name = nif::string_arg(args[0])?;
+
+                Ok(proc.binary_from_str(&format!("Hello, {}!", name))?)
+            });
+        }
+    }
+
+    &*VM;
+    VM.register_nif_module(&NifTest);
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("nif_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(nif_test).
+
+run() ->
+    5 = nif_test_natives:add(2, 3),
+    badarg = try nif_test_natives:add(2, three) catch error:Reason -> Reason end,
+    3 = nif_test_natives:byte_size(<<1, 2, 3>>),
+    <<\"Hello, Lumen!\">> = nif_test_natives:greet(\"Lumen\"),
+    nif_test_natives:greet(<<\"Erlang\">>).
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(init_arc_process.binary_from_str("Hello, Erlang!").unwrap()));
+}
+
+#[test]
+fn dirty_native_test() {
+    use crate::module::NativeModule;
+
+    &*VM;
+
+    let mut native = NativeModule::new(Atom::try_from_str("dirty_test_natives").unwrap());
+    native.add_dirty(Atom::try_from_str("double").unwrap(), 1, |proc, args| {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+
+        let n = crate::nif::isize_arg(args[0])?;
+
+        Ok(proc.integer(n * 2)?)
+    });
+    native.add_dirty(Atom::try_from_str("fail").unwrap(), 0, |_proc, _args| {
+        Err(liblumen_alloc::badarg!().into())
+    });
+    VM.register_native_module(native);
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("dirty_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(dirty_test).
+
+run() ->
+    Self = self(),
+    spawn(fun() -> Self ! during end),
+    42 = dirty_test_natives:double(21),
+    badarg = try dirty_test_natives:fail() catch error:Reason -> Reason end,
+    receive during -> dirty_test_natives:double(4) end.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(init_arc_process.integer(8).unwrap()));
+}
+
+#[test]
+fn escript_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let arguments = vec!["a".to_string(), "-b".to_string()];
+    let run = |name: &str, source: &str| {
+        crate::escript::run_source(&init_arc_process, name, source, &arguments).unwrap()
+    };
+
+    assert_eq!(
+        run(
+            "escript_arguments_test",
+            "#!/usr/bin/env run_escript
+main([\"a\", \"-b\"]) -> 3.
+",
+        ),
+        3
+    );
+    assert_eq!(
+        run(
+            "escript_module_test",
+            "#!/usr/bin/env run_escript
+-module(escript_named_test).
+-export([main/1]).
+
+main(_Args) -> ok.
+",
+        ),
+        0
+    );
+    assert_eq!(
+        run("escript_exception_test", "main(_Args) -> 1 = 2.\n"),
+        crate::escript::FAILURE_STATUS
+    );
+}
+
+#[test]
+fn compile_forms_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("compile_forms_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(compile_forms_test).
+
+run() ->
+    Forms = [{attribute, 1, module, compile_forms_generated},
+             {attribute, 2, export, [{double, 1}]},
+             {function, 3, double, 1,
+              [{clause, 3, [{var, 3, 'X'}], [],
+                [{op, 3, '*', {var, 3, 'X'}, {integer, 3, 2}}]}]}],
+    {ok, compile_forms_generated, Beam} = compile:forms(Forms),
+    {module, compile_forms_generated} =
+        code:load_binary(compile_forms_generated, \"compile_forms_generated.beam\", Beam),
+    error = compile:forms([{attribute, 1, module}]),
+    compile_forms_generated:double(21).
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(init_arc_process.integer(42).unwrap()));
+}
+
+#[test]
+fn module_info_attributes_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("module_info_attributes_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = crate::compile::compile_str(
+        "
+-module(module_info_attributes_test).
+-behaviour(gen_server).
+-vsn(\"1.0\").
+-answer(42). % a custom attribute
+-export([run/0]).
+
+run() -> module_info_attributes_test:module_info(attributes).
+",
+    )
+    .unwrap();
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    let behaviour = init_arc_process
+        .tuple_from_slice(&[
+            atom_unchecked("behaviour"),
+            init_arc_process
+                .list_from_slice(&[atom_unchecked("gen_server")])
+                .unwrap(),
+        ])
+        .unwrap();
+    let vsn = init_arc_process
+        .tuple_from_slice(&[
+            atom_unchecked("vsn"),
+            init_arc_process.charlist_from_str("1.0").unwrap(),
+        ])
+        .unwrap();
+    let answer = init_arc_process
+        .tuple_from_slice(&[
+            atom_unchecked("answer"),
+            init_arc_process
+                .list_from_slice(&[init_arc_process.integer(42).unwrap()])
+                .unwrap(),
+        ])
+        .unwrap();
+
+    assert!(
+        res.result
+            == Ok(init_arc_process
+                .list_from_slice(&[behaviour, vsn, answer])
+                .unwrap())
+    );
+    assert_eq!(
+        VM.modules
+            .read()
+            .unwrap()
+            .modules_with_behaviour(Atom::try_from_str("gen_server").unwrap()),
+        vec![module]
+    );
+}
+
+#[test]
+fn module_info_exports_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("module_info_exports_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = crate::compile::compile_str(
+        "
+-module(module_info_exports_test).
+-export([run/0]).
+
+run() -> {module_info_exports_test:module_info(exports), helper()}.
+
+helper() -> ok.
+",
+    )
+    .unwrap();
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    let function_entry = |name: &str, arity: usize| {
+        init_arc_process
+            .tuple_from_slice(&[
+                atom_unchecked(name),
+                init_arc_process.integer(arity).unwrap(),
+            ])
+            .unwrap()
+    };
+    let exports = init_arc_process
+        .list_from_slice(&[
+            function_entry("module_info", 0),
+            function_entry("module_info", 1),
+            function_entry("run", 0),
+        ])
+        .unwrap();
+
+    assert!(
+        res.result
+            == Ok(init_arc_process
+                .tuple_from_slice(&[exports, atom_unchecked("ok")])
+                .unwrap())
+    );
+}
+
+#[test]
+fn attribute_that_is_not_a_term_does_not_compile_test() {
+    assert!(crate::compile::compile_str(
+        "
+-module(attribute_that_is_not_a_term_does_not_compile_test).
+-answer(6 * 7).
+"
+    )
+    .is_err());
+}
+
+#[test]
+fn fib_gc() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("fib2").unwrap();
+    let function = Atom::try_from_str("fib").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(fib2).
+
+fib(0) -> 0;
+fib(1) -> 1;
+fib(X) -> fib(X - 1) + fib(X - 2).
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let int = init_arc_process.integer(14).unwrap();
+    let res =
+        crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[int]);
+
+    let int = init_arc_process.integer(377).unwrap();
+    assert!(res.result == Ok(int));
+}
+
+#[test]
+fn binary_match_loop_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("binary_match_loop_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(binary_match_loop_test).
+
+run() ->
+    Binary = <<1, 2, 3, 4, 5, 6, 7, 8, 9, 10>>,
+    {sum(Binary, 0), words(Binary, []), Binary}.
+
+sum(<<>>, Acc) -> Acc;
+sum(<<Byte, Rest/binary>>, Acc) -> sum(Rest, Acc + Byte).
+
+words(<<Word:16/little, Rest/binary>>, Acc) -> words(Rest, [Word | Acc]);
+words(<<>>, Acc) -> Acc.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    let words: Vec<Term> = [0x0A09, 0x0807, 0x0605, 0x0403, 0x0201]
+        .iter()
+        .map(|word: &isize| init_arc_process.integer(*word).unwrap())
+        .collect();
+    let expected = init_arc_process
+        .tuple_from_slice(&[
+            init_arc_process.integer(55).unwrap(),
+            init_arc_process.list_from_slice(&words).unwrap(),
+            init_arc_process
+                .binary_from_bytes(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10])
+                .unwrap(),
+        ])
+        .unwrap();
+    assert!(res.result == Ok(expected));
+}
+
+#[test]
+fn literal_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("literal_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(literal_test).
+
+run() -> [constant(), constant()].
+
+constant() -> {ok, [1, 2, 3], <<\"bin\">>}.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    let expected_constant = init_arc_process
+        .tuple_from_slice(&[
+            atom_unchecked("ok"),
+            init_arc_process
+                .list_from_slice(&[
+                    init_arc_process.integer(1).unwrap(),
+                    init_arc_process.integer(2).unwrap(),
+                    init_arc_process.integer(3).unwrap(),
+                ])
+                .unwrap(),
+            init_arc_process.binary_from_str("bin").unwrap(),
+        ])
+        .unwrap();
+    let expected = init_arc_process
+        .list_from_slice(&[expected_constant, expected_constant])
+        .unwrap();
+    assert!(res.result == Ok(expected));
+
+    // Both calls reference the same literal instead of making the constant on the heap
+    let list: Boxed<Cons> = res.result.unwrap().try_into().unwrap();
+    let tail: Boxed<Cons> = list.tail.try_into().unwrap();
+    assert!(list.head.is_literal());
+    assert_eq!(list.head.as_usize(), tail.head.as_usize());
+}
+
+#[test]
+fn ping_pong() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("ping_pong").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(ping_pong).
+
+proc_a(A) ->
+    receive
+        {b, R} -> R ! c
+    end.
+
+proc_b(A, B) ->
+    receive
+        a ->
+            B ! {b, self()},
+            proc_b(A, B);
+        c ->
+            A ! d
+    end.
+
+run() ->
+    P1 = spawn(ping_pong, proc_a, [self()]),
+    P2 = spawn(ping_pong, proc_b, [self(), P1]),
+    P2 ! a,
+    receive
+        Res -> Res
+    end.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(atom_unchecked("d")));
+}
+
+#[test]
+fn ping_pong_count() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("ping_pong_count").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(ping_pong_count).
+
+other_proc({add, A, B}, Ret) -> Ret ! {result, A + B}.
+
+this_proc(0, Acc) ->
+    Acc;
+this_proc(N, Acc) ->
+    spawn(ping_pong_count, other_proc, [{add, 1, Acc}, self()]),
+    receive
+        {result, Res} -> this_proc(N - 1, Res)
+    end.
+
+run(N) -> this_proc(N, 0).
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let int = init_arc_process.integer(10).unwrap();
+    let res =
+        crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[int]);
+
+    println!("{:?}", res.result);
+    //assert!(res.result == Ok(100));
+}
+
+#[ignore]
+#[test]
+fn ping_pong_count_large() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("ping_pong_count_large").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(ping_pong_count_large).
+
+other_proc({add, A, B}, Ret) -> Ret ! {result, A + B}.
+
+this_proc(0, Acc) ->
+    Acc;
+this_proc(N, Acc) ->
+    spawn(ping_pong_count, other_proc, [{add, 1, Acc}, self()]),
+    receive
+        {result, Res} -> this_proc(N - 1, Res)
+    end.
+
+run(N) -> this_proc(N, 0).
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let int = init_arc_process.integer(100).unwrap();
+    let res =
+        crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[int]);
+
+    println!("{:?}", res.result);
+    //assert!(res.result == Ok(100));
+}
+
+#[test]
+fn receive_after_zero() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("receive_after_zero").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(receive_after_zero).
+
+flush(Count) ->
+    receive
+        _ -> flush(Count + 1)
+    after 0 ->
+        Count
+    end.
+
+run() ->
+    self() ! a,
+    self() ! b,
+    2 = flush(0),
+    timeout = receive _ -> message after 0 -> timeout end,
+    self() ! c,
+    timeout = receive d -> d after 0 -> timeout end,
+    receive
+        Res -> Res
+    end.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(atom_unchecked("c")));
+}
+
+#[test]
+fn hibernate_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("hibernate_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(hibernate_test).
+
+wake(Parent) ->
+    receive
+        Message -> Parent ! {woke, self(), Message}
+    end.
+
+sleep(Parent) ->
+    erlang:hibernate(hibernate_test, wake, [Parent]),
+    Parent ! not_discarded.
+
+sleep_with_message(Parent) ->
+    self() ! early,
+    erlang:hibernate(hibernate_test, wake, [Parent]).
+
+run() ->
+    Sleeping = spawn(hibernate_test, sleep, [self()]),
+    Sleeping ! late,
+    late = receive
+        {woke, Sleeping, Late} -> Late
+    end,
+    Woken = spawn(hibernate_test, sleep_with_message, [self()]),
+    receive
+        {woke, Woken, Early} -> Early;
+        not_discarded -> not_discarded
+    end.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(atom_unchecked("early")));
+}
+
+#[test]
+fn selective_receive_reference_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("selective_receive_reference_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(selective_receive_reference_test).
+
+server() ->
+    receive
+        {call, From, Ref} -> From ! {Ref, reply}
+    end.
+
+call(Server) ->
+    Ref = make_ref(),
+    Server ! {call, self(), Ref},
+    receive
+        {Ref, Reply} -> Reply
+    end.
+
+run() ->
+    self() ! before,
+    Server = spawn(selective_receive_reference_test, server, []),
+    reply = call(Server),
+    receive
+        Before -> Before
+    end.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(atom_unchecked("before")));
+}
+
+#[test]
+fn spawn_on_node_test() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    let module = Atom::try_from_str("spawn_on_node_test").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(spawn_on_node_test).
+
+reply(Pid) ->
+    Pid ! {self(), node()}.
+
+run() ->
+    Node = node(),
+    Pid = spawn(Node, spawn_on_node_test, reply, [self()]),
+    receive
+        {Pid, Node} -> ok
+    end,
+    Linked = spawn_link(Node, spawn_on_node_test, reply, [self()]),
+    receive
+        {Linked, Node} -> ok
+    end,
+    Unreachable = 'spawn_on_node_test@nohost',
+    Unspawned = spawn(Unreachable, spawn_on_node_test, reply, [self()]),
+    Unreachable = node(Unspawned),
+    noconnection.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(atom_unchecked("noconnection")));
 }
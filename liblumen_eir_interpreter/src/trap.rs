@@ -0,0 +1,91 @@
+//! Running the trapping BIFs of `lumen_runtime::trap` as native functions.
+//!
+//! A trapping native function starts stepping as soon as it is called, and only traps if the
+//! reductions of the run are used up before it returns.  Then, the process returns to the
+//! scheduler with the arguments, including the continuations, and the state of the function on its
+//! stack and `code` as its frame.  `code` continues the steps on the next run, and once the
+//! function returns, continues the call with the result as for any other native function.
+
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use liblumen_alloc::erts::process::code::stack::frame::Frame;
+use liblumen_alloc::erts::process::code::Result;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{Atom, Term, TypedTerm};
+use liblumen_alloc::erts::ModuleFunctionArity;
+
+use lumen_runtime::trap::{self, Trap};
+
+use crate::module::{NativeFunctionKind, ResolvedFunction};
+
+/// Saves `state`, from which the steps of `module:function` continue on the next run, and the
+/// arguments in `args`, including the return and throw continuations.
+pub fn trap(
+    proc: &Arc<Process>,
+    module: Atom,
+    function: Atom,
+    state: &[Term],
+    args: &[Term],
+) -> Result {
+    let argument_list = proc.list_from_slice(args)?;
+    proc.stack_push(argument_list)?;
+    trap::push_state(proc, state)?;
+
+    let module_function_arity = Arc::new(ModuleFunctionArity {
+        module,
+        function,
+        arity: (args.len() - 2).try_into().unwrap(),
+    });
+    proc.replace_frame(Frame::new(module_function_arity, code));
+
+    Ok(())
+}
+
+// Private
+
+/// Expects the following on stack:
+/// * state of the function
+/// * argument list, including the return and throw continuations
+fn code(arc_process: &Arc<Process>) -> Result {
+    let mfa = arc_process.current_module_function_arity().unwrap();
+    let trap = lookup_trap(&mfa);
+
+    match trap.resume(arc_process)? {
+        // trapped again
+        None => Ok(()),
+        Some(result) => {
+            let argument_list = arc_process.stack_pop().unwrap();
+
+            let mut argument_vec: Vec<Term> = match argument_list.to_typed_term().unwrap() {
+                TypedTerm::List(argument_cons) => argument_cons
+                    .into_iter()
+                    .map(|result| result.unwrap())
+                    .collect(),
+                _ => unreachable!(),
+            };
+
+            crate::exec::resume_trapped(
+                arc_process,
+                mfa.module,
+                mfa.function,
+                result,
+                &mut argument_vec,
+            )
+        }
+    }
+}
+
+/// Native modules are never unloaded, so the function that trapped is still registered
+fn lookup_trap(mfa: &ModuleFunctionArity) -> Trap {
+    let option_resolved = crate::VM.modules.read().unwrap().lookup_function(
+        mfa.module,
+        mfa.function,
+        mfa.arity as usize,
+    );
+
+    match option_resolved {
+        Some(ResolvedFunction::Native(NativeFunctionKind::Trapping(trap))) => trap,
+        _ => unreachable!("{} is not a trapping native function", mfa),
+    }
+}
@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::{Arc, RwLock};
 
@@ -11,10 +12,13 @@ use lumen_runtime::process::spawn::options::Options;
 use lumen_runtime::scheduler::Scheduler;
 use lumen_runtime::system;
 
-use super::module::ModuleRegistry;
+use super::module::{ModuleRegistry, NativeModule};
+use super::nif::NifModule;
 
 pub struct VMState {
     pub modules: RwLock<ModuleRegistry>,
+    /// Directories searched in order for `<module>.erl` when a module is not loaded.
+    pub code_path: RwLock<Vec<PathBuf>>,
     pub closure_hack: RwLock<Vec<Vec<Term>>>,
     pub init: Arc<Process>,
 }
@@ -24,22 +28,59 @@ impl VMState {
         lumen_runtime::otp::erlang::apply_3::set_code(crate::code::apply);
 
         let mut modules = ModuleRegistry::new();
+        modules.register_native_module(crate::native::make_code());
+        modules.register_native_module(crate::native::make_compile());
+        modules.register_native_module(crate::native::make_counters());
+        modules.register_native_module(crate::native::make_crypto());
+        modules.register_native_module(crate::native::make_ct());
         modules.register_native_module(crate::native::make_erlang());
+        #[cfg(not(target_arch = "wasm32"))]
+        modules.register_native_module(crate::native::make_file());
+        modules.register_native_module(crate::native::make_io_lib());
         modules.register_native_module(crate::native::make_lists());
         modules.register_native_module(crate::native::make_maps());
+        modules.register_native_module(crate::native::make_math());
+        modules.register_native_module(crate::native::make_proplists());
+        modules.register_native_module(crate::native::make_rand());
+        #[cfg(not(target_arch = "wasm32"))]
+        modules.register_native_module(crate::native::make_global());
+        #[cfg(not(target_arch = "wasm32"))]
+        modules.register_native_module(crate::native::make_net_kernel());
+        modules.register_native_module(crate::native::make_queue());
         modules.register_native_module(crate::native::make_logger());
+        modules.register_native_module(crate::native::make_error_logger());
+        modules.register_native_module(crate::native::make_lumen());
         modules.register_native_module(crate::native::make_lumen_intrinsics());
+        modules.register_native_module(crate::native::make_zlib());
+        modules.register_native_module(crate::native::make_lumen_io());
+        modules.register_erlang_module(crate::native::make_io());
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            modules.register_native_module(crate::native::make_lumen_distribution_natives());
+            modules.register_erlang_module(crate::native::make_lumen_distribution());
+        }
 
         let arc_scheduler = Scheduler::current();
         let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
 
         VMState {
             modules: RwLock::new(modules),
+            code_path: RwLock::new(vec![PathBuf::from(".")]),
             closure_hack: RwLock::new(Vec::new()),
             init: init_arc_process,
         }
     }
 
+    /// Registers the functions of `native`, so that Erlang code can call them (see
+    /// `ModuleRegistry::register_native_module`).
+    pub fn register_native_module(&self, native: NativeModule) {
+        self.modules.write().unwrap().register_native_module(native);
+    }
+
+    pub fn register_nif_module<M: NifModule>(&self, nif_module: &M) {
+        self.register_native_module(nif_module.to_native_module());
+    }
+
     pub fn call(
         &mut self,
         fun: &FunctionIdent,
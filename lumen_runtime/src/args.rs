@@ -0,0 +1,138 @@
+//! Specs for the arguments of BIFs, checked with `args!`.
+//!
+//! Each spec checks the type (and for some, the value) of one argument and converts it to the Rust
+//! type the BIF works with.  When an argument does not match, the BIF raises `badarg` with an
+//! `ErrorInfo` naming the argument and the cause, so that BIFs don't each repeat the same tag checks
+//! and report the same problem the same way.
+
+use core::convert::TryInto;
+use core::ops::RangeBounds;
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::exception::Exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{Atom, Boxed, BytesFromBinaryError, Encoding, Term, Tuple};
+
+pub trait Spec<'process> {
+    type Output;
+
+    fn check(&self, term: Term, process: &'process Process) -> Result<Self::Output, Invalid>;
+}
+
+pub enum Invalid {
+    /// The argument does not match the spec for the reason given
+    Cause(&'static str),
+    /// The argument matches, but could not be converted
+    Alloc(Alloc),
+}
+
+/// Checks `term`, the one-based `argument` of a BIF, against `spec`.
+pub fn check<'process, S: Spec<'process>>(
+    spec: S,
+    argument: usize,
+    term: Term,
+    process: &'process Process,
+) -> Result<S::Output, Exception> {
+    spec.check(term, process).map_err(|invalid| match invalid {
+        Invalid::Cause(cause) => badarg!(argument, cause).into(),
+        Invalid::Alloc(alloc) => alloc.into(),
+    })
+}
+
+pub struct AtomSpec;
+
+/// An atom
+pub fn atom() -> AtomSpec {
+    AtomSpec
+}
+
+impl<'process> Spec<'process> for AtomSpec {
+    type Output = Atom;
+
+    fn check(&self, term: Term, _: &'process Process) -> Result<Atom, Invalid> {
+        term.try_into().map_err(|_| Invalid::Cause("not an atom"))
+    }
+}
+
+pub struct BinarySpec;
+
+/// A binary, as its bytes.  Bitstrings that are not a whole number of bytes do not match.
+pub fn binary() -> BinarySpec {
+    BinarySpec
+}
+
+impl<'process> Spec<'process> for BinarySpec {
+    type Output = &'process [u8];
+
+    fn check(&self, term: Term, process: &'process Process) -> Result<&'process [u8], Invalid> {
+        process
+            .bytes_from_binary(term)
+            .map_err(|error| match error {
+                BytesFromBinaryError::NotABinary | BytesFromBinaryError::Type => {
+                    Invalid::Cause("not a binary")
+                }
+                BytesFromBinaryError::Alloc(alloc) => Invalid::Alloc(alloc),
+            })
+    }
+}
+
+pub struct EncodingSpec;
+
+/// One of the atoms `latin1`, `unicode` or `utf8`
+pub fn encoding() -> EncodingSpec {
+    EncodingSpec
+}
+
+impl<'process> Spec<'process> for EncodingSpec {
+    type Output = Encoding;
+
+    fn check(&self, term: Term, _: &'process Process) -> Result<Encoding, Invalid> {
+        term.try_into()
+            .map_err(|_| Invalid::Cause("not a valid encoding"))
+    }
+}
+
+pub struct IntegerSpec<R> {
+    range: R,
+}
+
+/// An integer in `range`, such as `2..=36` or `0..` (`..` for any integer that fits in an `isize`)
+pub fn integer<R: RangeBounds<isize>>(range: R) -> IntegerSpec<R> {
+    IntegerSpec { range }
+}
+
+impl<'process, R: RangeBounds<isize>> Spec<'process> for IntegerSpec<R> {
+    type Output = isize;
+
+    fn check(&self, term: Term, _: &'process Process) -> Result<isize, Invalid> {
+        if !term.is_integer() {
+            return Err(Invalid::Cause("not an integer"));
+        }
+
+        let i: isize = term
+            .try_into()
+            .map_err(|_| Invalid::Cause("out of range"))?;
+
+        if self.range.contains(&i) {
+            Ok(i)
+        } else {
+            Err(Invalid::Cause("out of range"))
+        }
+    }
+}
+
+pub struct TupleSpec;
+
+/// A tuple of any size
+pub fn tuple() -> TupleSpec {
+    TupleSpec
+}
+
+impl<'process> Spec<'process> for TupleSpec {
+    type Output = Boxed<Tuple>;
+
+    fn check(&self, term: Term, _: &'process Process) -> Result<Boxed<Tuple>, Invalid> {
+        term.try_into().map_err(|_| Invalid::Cause("not a tuple"))
+    }
+}
@@ -2,10 +2,11 @@ use alloc::sync::Arc;
 
 use liblumen_alloc::erts::process::{code, Process};
 
-/// A stub that just puts the init process into `Status::Waiting`, so it remains alive without
-/// wasting CPU cycles
-pub fn init(arc_process: &Arc<Process>) -> code::Result {
-    Arc::clone(arc_process).wait();
+use crate::system::io::server;
 
-    Ok(())
+/// The init process is the group leader of the processes it spawns, so it serves their io
+/// requests, waiting in `Status::Waiting` between them so that it remains alive without wasting
+/// CPU cycles
+pub fn init(arc_process: &Arc<Process>) -> code::Result {
+    server::serve(arc_process)
 }
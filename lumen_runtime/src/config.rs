@@ -1,3 +1,5 @@
+pub mod runtime;
+
 use std::collections::HashMap;
 use std::error::Error;
 use std::ffi::{OsStr, OsString};
@@ -0,0 +1,172 @@
+//! The configuration of the running runtime, so that Erlang code can adapt to the host it is
+//! running on with `lumen:config/0,1`.
+
+use std::env::consts;
+use std::mem;
+
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::alloc::default_heap_size;
+use liblumen_alloc::erts::process::{heap_sizing, HeapSizing, Process};
+use liblumen_alloc::erts::term::{atom_unchecked, max_atoms, Term};
+
+use crate::process::limit;
+use crate::scheduler::Scheduler;
+use crate::system::host::cpus;
+
+pub struct RuntimeConfig {
+    /// The number of schedulers, one for each thread that has run processes
+    pub schedulers: usize,
+    /// The number of logical processors of the host
+    pub logical_processors: usize,
+    /// The maximum number of atoms that can be created (see `liblumen_alloc::erts::term::atom`)
+    pub max_atoms: usize,
+    /// The size of a process's heap, in words, when it isn't spawned with `min_heap_size`
+    pub min_heap_size: usize,
+    /// How the heaps of processes that don't set their own heap sizing grow and shrink (see
+    /// `liblumen_alloc::erts::process::HeapSizing`)
+    pub heap_sizing: HeapSizing,
+    /// The maximum number of processes that can be alive at once (see `process::limit`)
+    pub max_processes: usize,
+    /// The size of a word, in bytes
+    pub word_size: usize,
+    /// The architecture the runtime was compiled for, such as `x86_64` or `wasm32`
+    pub target_arch: &'static str,
+    /// The operating system the runtime was compiled for, such as `linux`, or `unknown` when
+    /// there isn't one, as in a browser
+    pub target_os: &'static str,
+}
+
+impl RuntimeConfig {
+    /// The configuration as it is now.  The maximum numbers of atoms and processes, the heap
+    /// sizing and the number of schedulers can change while the runtime runs.
+    pub fn current() -> Self {
+        Self {
+            schedulers: Scheduler::count(),
+            logical_processors: cpus::num_logical(),
+            max_atoms: max_atoms(),
+            min_heap_size: default_heap_size(),
+            heap_sizing: heap_sizing(),
+            max_processes: limit::max(),
+            word_size: mem::size_of::<usize>(),
+            target_arch: consts::ARCH,
+            target_os: consts::OS,
+        }
+    }
+
+    /// `#{Key => Value}` of every key
+    pub fn to_map(&self, process: &Process) -> Result<Term, Alloc> {
+        process.map_from_slice(&self.entries(process)?)
+    }
+
+    /// The value of `key`, or `None` if `key` isn't a key of the configuration
+    pub fn get(&self, process: &Process, key: Term) -> Result<Option<Term>, Alloc> {
+        let option_value = self
+            .entries(process)?
+            .into_iter()
+            .find(|(entry_key, _)| *entry_key == key)
+            .map(|(_, value)| value);
+
+        Ok(option_value)
+    }
+
+    fn entries(&self, process: &Process) -> Result<Vec<(Term, Term)>, Alloc> {
+        Ok(vec![
+            (
+                atom_unchecked("heap_sizing"),
+                self.heap_sizing.to_map(process)?,
+            ),
+            (
+                atom_unchecked("logical_processors"),
+                process.integer(self.logical_processors)?,
+            ),
+            (
+                atom_unchecked("max_atoms"),
+                process.integer(self.max_atoms)?,
+            ),
+            (
+                atom_unchecked("max_processes"),
+                process.integer(self.max_processes)?,
+            ),
+            (
+                atom_unchecked("min_heap_size"),
+                process.integer(self.min_heap_size)?,
+            ),
+            (
+                atom_unchecked("schedulers"),
+                process.integer(self.schedulers)?,
+            ),
+            (
+                atom_unchecked("target_arch"),
+                atom_unchecked(self.target_arch),
+            ),
+            (atom_unchecked("target_os"), atom_unchecked(self.target_os)),
+            (
+                atom_unchecked("word_size"),
+                process.integer(self.word_size)?,
+            ),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::scheduler::with_process;
+
+    #[test]
+    fn get_returns_value_of_key() {
+        with_process(|process| {
+            let runtime_config = RuntimeConfig::current();
+
+            assert_eq!(
+                runtime_config.get(process, atom_unchecked("word_size")),
+                Ok(Some(process.integer(mem::size_of::<usize>()).unwrap()))
+            );
+            assert_eq!(
+                runtime_config.get(process, atom_unchecked("target_arch")),
+                Ok(Some(atom_unchecked(consts::ARCH)))
+            );
+        });
+    }
+
+    #[test]
+    fn get_without_key_returns_none() {
+        with_process(|process| {
+            assert_eq!(
+                RuntimeConfig::current().get(process, atom_unchecked("unknown")),
+                Ok(None)
+            );
+        });
+    }
+
+    #[test]
+    fn current_counts_scheduler_of_this_thread() {
+        with_process(|_| {
+            assert!(1 <= RuntimeConfig::current().schedulers);
+        });
+    }
+
+    #[test]
+    fn current_uses_atom_limit() {
+        assert_eq!(RuntimeConfig::current().max_atoms, max_atoms());
+    }
+
+    #[test]
+    fn current_uses_heap_sizing() {
+        with_process(|process| {
+            let runtime_config = RuntimeConfig::current();
+
+            assert_eq!(runtime_config.heap_sizing, heap_sizing());
+            assert_eq!(
+                runtime_config.get(process, atom_unchecked("heap_sizing")),
+                Ok(Some(heap_sizing().to_map(process).unwrap()))
+            );
+        });
+    }
+
+    #[test]
+    fn current_uses_process_limit() {
+        assert_eq!(RuntimeConfig::current().max_processes, limit::max());
+    }
+}
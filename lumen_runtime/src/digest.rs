@@ -0,0 +1,238 @@
+//! Message digests and checksums of bytes
+//!
+//! The state of a digest in progress can be saved to and restored from bytes, so that BIFs can
+//! return it as a binary term and continue the digest when it is passed back.  Checksums are
+//! continued from their previous value instead.
+
+use core::convert::TryInto;
+
+/// The [Adler-32](https://tools.ietf.org/html/rfc1950#section-8) checksum of `bytes`, continuing
+/// from `adler`, which is `1` for no bytes.
+pub fn adler32(adler: u32, bytes: &[u8]) -> u32 {
+    let mut a = (adler & 0xFFFF) % ADLER32_MODULUS;
+    let mut b = (adler >> 16) % ADLER32_MODULUS;
+
+    // the most bytes that can be summed before `b` could overflow a `u32`
+    for chunk in bytes.chunks(5552) {
+        for byte in chunk {
+            a += *byte as u32;
+            b += a;
+        }
+
+        a %= ADLER32_MODULUS;
+        b %= ADLER32_MODULUS;
+    }
+
+    (b << 16) | a
+}
+
+/// The CRC-32 checksum of `bytes`, as used by zlib, continuing from `crc`, which is `0` for no
+/// bytes.
+pub fn crc32(crc: u32, bytes: &[u8]) -> u32 {
+    let mut crc = !crc;
+
+    for byte in bytes {
+        crc = CRC32_TABLE[((crc ^ (*byte as u32)) & 0xFF) as usize] ^ (crc >> 8);
+    }
+
+    !crc
+}
+
+/// [MD5](https://tools.ietf.org/html/rfc1321)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Md5 {
+    state: [u32; 4],
+    /// The number of bytes consumed
+    len: u64,
+    /// The bytes consumed that don't fill a block yet
+    buffer: Vec<u8>,
+}
+
+impl Md5 {
+    pub fn new() -> Self {
+        Md5 {
+            state: [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476],
+            len: 0,
+            buffer: Vec::with_capacity(BLOCK_LEN),
+        }
+    }
+
+    /// The digest of `bytes`
+    pub fn digest(bytes: &[u8]) -> [u8; 16] {
+        let mut md5 = Self::new();
+        md5.update(bytes);
+
+        md5.finalize()
+    }
+
+    /// Restores the state saved with `to_bytes`.  `None` if `bytes` is not a saved state.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < SAVED_LEN {
+            return None;
+        }
+
+        let (saved, buffer) = bytes.split_at(SAVED_LEN);
+        let len = u64::from_le_bytes(saved[16..24].try_into().unwrap());
+
+        if (buffer.len() as u64) == len % (BLOCK_LEN as u64) {
+            let mut state = [0; 4];
+
+            for (word, word_bytes) in state.iter_mut().zip(saved[0..16].chunks_exact(4)) {
+                *word = u32::from_le_bytes(word_bytes.try_into().unwrap());
+            }
+
+            let mut md5 = Md5 {
+                state,
+                len,
+                buffer: Vec::with_capacity(BLOCK_LEN),
+            };
+            md5.buffer.extend_from_slice(buffer);
+
+            Some(md5)
+        } else {
+            None
+        }
+    }
+
+    /// The state words, then the number of bytes consumed, all little endian, then the bytes that
+    /// don't fill a block yet.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(SAVED_LEN + self.buffer.len());
+
+        for word in self.state.iter() {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+
+        bytes.extend_from_slice(&self.len.to_le_bytes());
+        bytes.extend_from_slice(&self.buffer);
+
+        bytes
+    }
+
+    pub fn update(&mut self, mut bytes: &[u8]) {
+        self.len = self.len.wrapping_add(bytes.len() as u64);
+
+        if !self.buffer.is_empty() {
+            let take = (BLOCK_LEN - self.buffer.len()).min(bytes.len());
+            self.buffer.extend_from_slice(&bytes[..take]);
+            bytes = &bytes[take..];
+
+            if self.buffer.len() < BLOCK_LEN {
+                return;
+            }
+
+            let block = core::mem::replace(&mut self.buffer, Vec::with_capacity(BLOCK_LEN));
+            self.compress(&block);
+        }
+
+        let mut blocks = bytes.chunks_exact(BLOCK_LEN);
+
+        for block in &mut blocks {
+            self.compress(block);
+        }
+
+        self.buffer.extend_from_slice(blocks.remainder());
+    }
+
+    pub fn finalize(mut self) -> [u8; 16] {
+        let bit_len = self.len.wrapping_mul(8);
+        let padding_len = if self.buffer.len() < 56 {
+            56 - self.buffer.len()
+        } else {
+            120 - self.buffer.len()
+        };
+        let mut padding = vec![0; padding_len];
+        padding[0] = 0x80;
+        padding.extend_from_slice(&bit_len.to_le_bytes());
+        self.update(&padding);
+
+        let mut digest = [0; 16];
+
+        for (digest_bytes, word) in digest.chunks_exact_mut(4).zip(self.state.iter()) {
+            digest_bytes.copy_from_slice(&word.to_le_bytes());
+        }
+
+        digest
+    }
+
+    fn compress(&mut self, block: &[u8]) {
+        let mut m = [0u32; 16];
+
+        for (word, word_bytes) in m.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_le_bytes(word_bytes.try_into().unwrap());
+        }
+
+        let [mut a, mut b, mut c, mut d] = self.state;
+
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+
+            let rotated = a
+                .wrapping_add(f)
+                .wrapping_add(K[i])
+                .wrapping_add(m[g])
+                .rotate_left(S[i]);
+
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(rotated);
+        }
+
+        self.state[0] = self.state[0].wrapping_add(a);
+        self.state[1] = self.state[1].wrapping_add(b);
+        self.state[2] = self.state[2].wrapping_add(c);
+        self.state[3] = self.state[3].wrapping_add(d);
+    }
+}
+
+const ADLER32_MODULUS: u32 = 65521;
+
+const BLOCK_LEN: usize = 64;
+/// The state words and the number of bytes consumed
+const SAVED_LEN: usize = 24;
+
+const S: [u32; 64] = [
+    7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9,
+    14, 20, 5, 9, 14, 20, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 6, 10, 15,
+    21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+];
+
+const K: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+lazy_static! {
+    /// The CRC-32 of each byte for the reversed polynomial `0xEDB88320`
+    static ref CRC32_TABLE: [u32; 256] = {
+        let mut table = [0; 256];
+
+        for (n, entry) in table.iter_mut().enumerate() {
+            let mut c = n as u32;
+
+            for _ in 0..8 {
+                c = if c & 1 == 1 {
+                    0xEDB88320 ^ (c >> 1)
+                } else {
+                    c >> 1
+                };
+            }
+
+            *entry = c;
+        }
+
+        table
+    };
+}
@@ -0,0 +1,345 @@
+//! Erlang distribution over TCP, so that a Lumen node can connect to and accept connections from
+//! BEAM nodes.
+//!
+//! A node is started with `start`, which listens for connections, registers the listening port
+//! with EPMD and then names the node (see `crate::node`).  `connect` resolves the host and port of
+//! another node through EPMD, and both directions authenticate with the shared cookie in the
+//! version 6 handshake, so only BEAM nodes from OTP 23 on can connect.  Once connected, each
+//! `Connection` ticks so that the other node knows it is alive, and closes itself when the other
+//! node stops ticking.  Processes subscribed with `monitor_nodes` are told when connections open
+//! and close, and processes monitoring a node with `monitor_node` when its connection closes.
+//!
+//! Messages between processes are sent over connections as `control` messages, and terms in them
+//! are converted to and from the external term format by `crate::external_term_format`.
+//! Processes are spawned on other nodes with `spawn`, and linked to and monitor processes on other
+//! nodes with `link` and `monitor`, which turn a closed connection into `noconnection` exits and
+//! `DOWN`s.
+//! Names are registered across nodes with `global`.
+//!
+//! Tests connect to stand-in nodes over in-memory streams with `bridge`, which needs neither EPMD
+//! nor a started node.
+
+pub mod bridge;
+pub mod connection;
+pub mod control;
+pub mod epmd;
+pub mod global;
+pub mod handshake;
+pub mod link;
+pub mod monitor;
+pub mod monitor_node;
+pub mod monitor_nodes;
+pub mod spawn;
+
+use std::env;
+use std::fmt::{self, Display};
+use std::fs;
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use hashbrown::HashMap;
+
+use liblumen_alloc::erts::exception::runtime;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::Atom;
+
+use crate::external_term_format;
+use crate::node::{self, NameKind, StartError};
+
+pub use self::connection::Connection;
+
+/// The capabilities this node tells other nodes it has in the handshake
+pub mod flags {
+    pub const PUBLISHED: u64 = 0x1;
+    pub const EXTENDED_REFERENCES: u64 = 0x4;
+    pub const DIST_MONITOR: u64 = 0x8;
+    pub const FUN_TAGS: u64 = 0x10;
+    pub const NEW_FUN_TAGS: u64 = 0x80;
+    pub const EXTENDED_PIDS_PORTS: u64 = 0x100;
+    pub const EXPORT_PTR_TAG: u64 = 0x200;
+    pub const BIT_BINARIES: u64 = 0x400;
+    pub const NEW_FLOATS: u64 = 0x800;
+    pub const UTF8_ATOMS: u64 = 0x10000;
+    pub const MAP_TAG: u64 = 0x20000;
+    pub const BIG_CREATION: u64 = 0x40000;
+    pub const HANDSHAKE_23: u64 = 0x1000000;
+    pub const UNLINK_ID: u64 = 0x2000000;
+    pub const V4_NC: u64 = 0x4 << 32;
+
+    pub const THIS_NODE: u64 = PUBLISHED
+        | EXTENDED_REFERENCES
+        | DIST_MONITOR
+        | FUN_TAGS
+        | NEW_FUN_TAGS
+        | EXTENDED_PIDS_PORTS
+        | EXPORT_PTR_TAG
+        | BIT_BINARIES
+        | NEW_FLOATS
+        | UTF8_ATOMS
+        | MAP_TAG
+        | BIG_CREATION
+        | HANDSHAKE_23
+        | UNLINK_ID
+        | V4_NC;
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Start(StartError),
+    /// The node was not started with `start`, so it can't connect to other nodes
+    NotAlive,
+    /// The node name does not have an `@` separating the name from the host
+    InvalidNodeName,
+    /// EPMD on the other node's host does not know the node
+    NotRegistered,
+    /// EPMD refused to register this node, usually because the name is taken
+    RegistrationRefused,
+    /// The other node sent something other than the next step of the handshake
+    Protocol(&'static str),
+    /// The other node refused the connection with this status
+    Status(String),
+    /// The other node did not prove it has the same cookie
+    Cookie,
+    /// A term in a control message could not be encoded
+    Term(external_term_format::Error),
+    Alloc(Alloc),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "{}", error),
+            Error::Start(error) => write!(f, "could not name node ({:?})", error),
+            Error::NotAlive => write!(f, "node is not alive"),
+            Error::InvalidNodeName => write!(f, "node name must be name@host"),
+            Error::NotRegistered => write!(f, "node is not registered with EPMD"),
+            Error::RegistrationRefused => write!(f, "EPMD refused to register node"),
+            Error::Protocol(expected) => write!(f, "expected {} in handshake", expected),
+            Error::Status(status) => write!(f, "connection refused ({})", status),
+            Error::Cookie => write!(f, "cookies do not match"),
+            Error::Term(error) => write!(f, "could not encode term ({:?})", error),
+            Error::Alloc(alloc) => write!(f, "{:?}", alloc),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<Alloc> for Error {
+    fn from(alloc: Alloc) -> Self {
+        Error::Alloc(alloc)
+    }
+}
+
+impl From<external_term_format::Error> for Error {
+    fn from(error: external_term_format::Error) -> Self {
+        Error::Term(error)
+    }
+}
+
+impl From<StartError> for Error {
+    fn from(error: StartError) -> Self {
+        Error::Start(error)
+    }
+}
+
+/// Listens for connections from other nodes, registers with EPMD and, once registered, names this
+/// node.  Without a `cookie`, the cookie is read from `~/.erlang.cookie` as `erl` does.
+pub fn start(name: &str, kind: NameKind, cookie: Option<String>) -> Result<Atom, Error> {
+    let cookie = match cookie {
+        Some(cookie) => cookie,
+        None => read_cookie_file()?,
+    };
+    let listener = TcpListener::bind(("0.0.0.0", 0))?;
+    let port = listener.local_addr()?.port();
+    let checked_name = node::checked_name(name, kind)?;
+    let alive_name = split_node_name(checked_name.name())?.0.to_string();
+
+    // The node is only named once EPMD has registered it, so that a refused registration does
+    // not leave the node alive without distribution.  EPMD forgets the node when this connection
+    // closes, so it is kept open for as long as the node is alive, and is dropped, unregistering
+    // the node, when naming the node fails.
+    let (registration, creation) = epmd::register(&alive_name, port)?;
+    let node = node::start_with_creation(name, kind, creation)?;
+
+    set_cookie(cookie);
+    *EPMD_REGISTRATION.lock().unwrap() = Some(registration);
+
+    thread::spawn(move || {
+        for result in listener.incoming() {
+            if let Ok(stream) = result {
+                thread::spawn(move || {
+                    let _ = accept(stream);
+                });
+            }
+        }
+    });
+
+    Ok(node)
+}
+
+/// The connection to `node`, connecting if there isn't one already.
+pub fn connect(node: Atom) -> Result<Arc<Connection>, Error> {
+    if let Some(connection) = connection(node) {
+        return Ok(connection);
+    }
+
+    if !node::is_alive() {
+        return Err(Error::NotAlive);
+    }
+
+    let (host, port) = epmd::resolve(node.name())?;
+    let mut stream = TcpStream::connect((host.as_str(), port))?;
+    let peer = handshake::initiate(
+        &mut stream,
+        node::name().name(),
+        node::creation(),
+        &cookie(),
+    )?;
+    let connection = Connection::start(node, peer.flags, stream)?;
+
+    insert(connection.clone());
+
+    Ok(connection)
+}
+
+/// Calls `connected` with the connection to `node`, connecting on another thread if there isn't
+/// one already, so that a scheduler isn't blocked while EPMD and the other node answer.
+pub fn connect_in_background<F>(node: Atom, connected: F)
+where
+    F: FnOnce(Result<Arc<Connection>, Error>) + Send + 'static,
+{
+    match connection(node) {
+        Some(connection) => connected(Ok(connection)),
+        None => {
+            thread::spawn(move || connected(connect(node)));
+        }
+    }
+}
+
+/// The open connection to `node`, if any.
+pub fn connection(node: Atom) -> Option<Arc<Connection>> {
+    CONNECTIONS
+        .lock()
+        .unwrap()
+        .get(&node)
+        .filter(|connection| connection.is_open())
+        .cloned()
+}
+
+/// The nodes with open connections, as `erlang:nodes/0` returns.
+pub fn nodes() -> Vec<Atom> {
+    CONNECTIONS
+        .lock()
+        .unwrap()
+        .values()
+        .filter(|connection| connection.is_open())
+        .map(|connection| connection.node)
+        .collect()
+}
+
+pub fn cookie() -> String {
+    COOKIE.lock().unwrap().clone()
+}
+
+pub fn set_cookie(cookie: String) {
+    *COOKIE.lock().unwrap() = cookie;
+}
+
+// Private
+
+fn accept(mut stream: TcpStream) -> Result<(), Error> {
+    let peer = handshake::accept(
+        &mut stream,
+        node::name().name(),
+        node::creation(),
+        &cookie(),
+        |name| Atom::try_from_str(name).ok().and_then(connection).is_some(),
+    )?;
+    let node = Atom::try_from_str(&peer.name).map_err(|_| Error::InvalidNodeName)?;
+    let connection = Connection::start(node, peer.flags, stream)?;
+
+    insert(connection);
+
+    Ok(())
+}
+
+/// Adds `connection`, telling `monitor_nodes` subscribers that its node is up unless it was
+/// already connected
+fn insert(connection: Arc<Connection>) {
+    let node = connection.node;
+    let previous = CONNECTIONS.lock().unwrap().insert(node, connection);
+
+    if previous.map_or(true, |previous| !previous.is_open()) {
+        monitor_nodes::node_up(node);
+        global::node_up(node);
+    }
+}
+
+/// Tells the processes on other nodes that are linked to or monitor `process` that it exited with
+/// `exception`
+pub fn propagate_exit(process: &Process, exception: &runtime::Exception) {
+    link::propagate_exit(process, exception.reason);
+    monitor::propagate_exit(process, exception.reason);
+    global::propagate_exit(process);
+}
+
+/// Removes `connection` when it closes for `reason`, unless it was already replaced by a new
+/// connection
+fn remove(connection: &Connection, reason: &str) {
+    let removed = {
+        let mut connections = CONNECTIONS.lock().unwrap();
+
+        match connections.get(&connection.node) {
+            Some(current) if std::ptr::eq(current.as_ref(), connection) => {
+                connections.remove(&connection.node);
+
+                true
+            }
+            _ => false,
+        }
+    };
+
+    if removed {
+        monitor_nodes::node_down(connection.node, reason);
+        monitor_node::node_down(connection.node);
+        link::node_down(connection.node);
+        monitor::node_down(connection.node);
+        global::node_down(connection.node);
+    }
+}
+
+fn read_cookie_file() -> Result<String, Error> {
+    let home = env::var_os("HOME").ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "HOME is not set to find .erlang.cookie",
+        )
+    })?;
+    let path: PathBuf = [home, ".erlang.cookie".into()].iter().collect();
+    let cookie = fs::read_to_string(path)?;
+
+    Ok(cookie.trim().to_string())
+}
+
+fn split_node_name(name: &str) -> Result<(&str, &str), Error> {
+    match name.find('@') {
+        Some(index) => Ok((&name[..index], &name[(index + 1)..])),
+        None => Err(Error::InvalidNodeName),
+    }
+}
+
+lazy_static! {
+    static ref CONNECTIONS: Mutex<HashMap<Atom, Arc<Connection>>> = Default::default();
+    static ref COOKIE: Mutex<String> = Default::default();
+    static ref EPMD_REGISTRATION: Mutex<Option<TcpStream>> = Default::default();
+}
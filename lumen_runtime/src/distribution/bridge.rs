@@ -0,0 +1,473 @@
+//! In-memory connections to stand-in nodes, so that tests can exercise links, monitors and messages
+//! between nodes without sockets, EPMD or the handshake.
+//!
+//! `connect` opens a `Connection` to a node named `name` over an in-memory stream, exactly as if
+//! the node had connected over TCP, and returns the `Peer` at the other end of the stream.  The
+//! test plays the other node through the `Peer`: it reads the control messages this node sends it
+//! with `receive` and sends control messages with `send`.  Dropping or disconnecting the `Peer`
+//! closes the connection as the other node going down would.
+//!
+//! This node is the only node in the OS process, so the `Peer` doesn't run any processes of its
+//! own.  Its processes are only the pids and references that the test makes up for it.
+
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{Atom, Term, TypedTerm};
+
+use super::connection::{self, Connection};
+use super::control::PASS_THROUGH;
+use super::{flags, insert, split_node_name, Error};
+use crate::external_term_format::{self, decode_prefix, encode, encode_tuple};
+
+/// The other end of a connection to a stand-in node
+pub struct Peer {
+    node: Atom,
+    end: Mutex<End>,
+}
+
+impl Peer {
+    /// The name of the stand-in node
+    pub fn node(&self) -> Atom {
+        self.node
+    }
+
+    /// Sends the control message `control`, followed by `message` if there is one, to this node as
+    /// the stand-in node
+    pub fn send(&self, control: &[Term], message: Option<Term>) -> Result<(), Error> {
+        let mut packet = vec![PASS_THROUGH];
+        packet.extend_from_slice(&encode_tuple(control)?);
+
+        if let Some(message) = message {
+            packet.extend_from_slice(&encode(message)?);
+        }
+
+        connection::write_packet(&mut *self.end.lock().unwrap(), &packet).map_err(From::from)
+    }
+
+    /// The elements of the next control message that this node sends the stand-in node, and the
+    /// message that follows it, if any, decoded onto the heap of `process`.  Ticks are skipped.
+    /// Fails with `WouldBlock` or `TimedOut` if nothing is sent within `timeout`.
+    pub fn receive(
+        &self,
+        process: &Process,
+        timeout: Duration,
+    ) -> Result<(Vec<Term>, Option<Term>), Error> {
+        let mut end = self.end.lock().unwrap();
+        let deadline = Instant::now() + timeout;
+
+        let packet = loop {
+            end.set_read_timeout(Some(remaining(deadline)));
+
+            let packet = connection::read_packet(&mut *end)?;
+
+            if !packet.is_empty() {
+                break packet;
+            }
+        };
+
+        let bytes = match packet.split_first() {
+            Some((&PASS_THROUGH, bytes)) => bytes,
+            _ => return Err(external_term_format::Error::Invalid.into()),
+        };
+        let mut heap = process.acquire_heap();
+        let (control, message_bytes) = decode_prefix(bytes, &mut *heap)?;
+        let control_vec: Vec<Term> = match control.to_typed_term().unwrap() {
+            TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+                TypedTerm::Tuple(tuple) => tuple.iter().collect(),
+                _ => return Err(external_term_format::Error::Invalid.into()),
+            },
+            _ => return Err(external_term_format::Error::Invalid.into()),
+        };
+        let option_message = if message_bytes.is_empty() {
+            None
+        } else {
+            Some(decode_prefix(message_bytes, &mut *heap)?.0)
+        };
+
+        Ok((control_vec, option_message))
+    }
+
+    /// Closes the connection, as the stand-in node going down would
+    pub fn disconnect(&self) {
+        self.end.lock().unwrap().shutdown();
+    }
+}
+
+impl Drop for Peer {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
+
+/// Connects this node to a stand-in node named `name`, which must be `name@host`
+pub fn connect(name: &str) -> Result<Peer, Error> {
+    split_node_name(name)?;
+
+    let node = Atom::try_from_str(name).map_err(|_| Error::InvalidNodeName)?;
+    let (local, remote) = pair();
+    let connection = Connection::start(node, flags::THIS_NODE, local)?;
+
+    insert(connection);
+
+    Ok(Peer {
+        node,
+        end: Mutex::new(remote),
+    })
+}
+
+// Private
+
+/// One end of an in-memory stream, which reads what the other end writes
+struct End {
+    incoming: Arc<Pipe>,
+    outgoing: Arc<Pipe>,
+}
+
+impl End {
+    fn set_read_timeout(&self, timeout: Option<Duration>) {
+        self.incoming.state.lock().unwrap().read_timeout = timeout;
+    }
+
+    fn shutdown(&self) {
+        self.incoming.close();
+        self.outgoing.close();
+    }
+}
+
+impl Read for End {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut state = self.incoming.state.lock().unwrap();
+        let deadline = state.read_timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            if !state.bytes.is_empty() {
+                let len = buf.len().min(state.bytes.len());
+
+                for (byte, read) in buf.iter_mut().zip(state.bytes.drain(..len)) {
+                    *byte = read;
+                }
+
+                return Ok(len);
+            }
+
+            if state.closed {
+                return Ok(0);
+            }
+
+            state = match deadline {
+                Some(deadline) if deadline <= Instant::now() => {
+                    return Err(io::ErrorKind::WouldBlock.into())
+                }
+                Some(deadline) => {
+                    self.incoming
+                        .condvar
+                        .wait_timeout(state, remaining(deadline))
+                        .unwrap()
+                        .0
+                }
+                None => self.incoming.condvar.wait(state).unwrap(),
+            };
+        }
+    }
+}
+
+impl Write for End {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.outgoing.state.lock().unwrap();
+
+        if state.closed {
+            return Err(io::ErrorKind::BrokenPipe.into());
+        }
+
+        state.bytes.extend(buf);
+        self.outgoing.condvar.notify_all();
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl connection::Stream for End {
+    fn try_clone_stream(&self) -> io::Result<Box<dyn connection::Stream>> {
+        Ok(Box::new(End {
+            incoming: self.incoming.clone(),
+            outgoing: self.outgoing.clone(),
+        }))
+    }
+
+    fn set_stream_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.set_read_timeout(timeout);
+
+        Ok(())
+    }
+
+    fn shutdown_stream(&self) -> io::Result<()> {
+        self.shutdown();
+
+        Ok(())
+    }
+}
+
+/// The bytes written to one end of a stream that the other end hasn't read yet
+#[derive(Default)]
+struct Pipe {
+    state: Mutex<PipeState>,
+    condvar: Condvar,
+}
+
+impl Pipe {
+    fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.condvar.notify_all();
+    }
+}
+
+#[derive(Default)]
+struct PipeState {
+    bytes: VecDeque<u8>,
+    closed: bool,
+    read_timeout: Option<Duration>,
+}
+
+fn remaining(deadline: Instant) -> Duration {
+    let now = Instant::now();
+
+    if now < deadline {
+        deadline - now
+    } else {
+        Duration::from_secs(0)
+    }
+}
+
+fn pair() -> (End, End) {
+    let left_to_right: Arc<Pipe> = Default::default();
+    let right_to_left: Arc<Pipe> = Default::default();
+
+    (
+        End {
+            incoming: right_to_left.clone(),
+            outgoing: left_to_right.clone(),
+        },
+        End {
+            incoming: left_to_right,
+            outgoing: right_to_left,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use liblumen_alloc::erts::term::{atom_unchecked, Pid};
+    use liblumen_alloc::erts::Node;
+
+    use crate::distribution::control::{RemotePid, MONITOR_P, MONITOR_P_EXIT, SEND};
+    use crate::distribution::monitor::{self, Monitored};
+    use crate::distribution::{self, link, monitor_node};
+    use crate::scheduler::with_process_arc;
+    use crate::test::receive_message;
+
+    #[test]
+    fn sends_from_peer_are_received_in_order() {
+        with_process_arc(|arc_process| {
+            let peer = connect("bridge_order@peer").unwrap();
+            let messages: Vec<Term> = (0..3).map(|i| arc_process.integer(i).unwrap()).collect();
+
+            for message in &messages {
+                peer.send(
+                    &[
+                        Term::make_smallint(SEND),
+                        atom_unchecked(""),
+                        arc_process.pid_term(),
+                    ],
+                    Some(*message),
+                )
+                .unwrap();
+            }
+
+            let received: Vec<Term> = (0..3)
+                .map(|_| receive_message_within(&arc_process, TIMEOUT).unwrap())
+                .collect();
+
+            assert_eq!(received, messages);
+        });
+    }
+
+    #[test]
+    fn monitor_of_peer_process_is_sent_and_exit_sends_down() {
+        with_process_arc(|arc_process| {
+            let peer = connect("bridge_monitor@peer").unwrap();
+            let remote = remote_pid(&peer);
+            let remote_term = remote.to_term(&mut *arc_process.acquire_heap()).unwrap();
+
+            let reference =
+                monitor::monitor(&arc_process, Monitored::Pid(remote), Default::default()).unwrap();
+
+            // names registered with `global` by other tests are also sent to new nodes
+            let (control, message) = loop {
+                let (control, message) = peer.receive(&arc_process, TIMEOUT).unwrap();
+
+                if control[0] == Term::make_smallint(MONITOR_P) {
+                    break (control, message);
+                }
+            };
+
+            assert_eq!(
+                control,
+                vec![
+                    Term::make_smallint(MONITOR_P),
+                    arc_process.pid_term(),
+                    remote_term,
+                    reference
+                ]
+            );
+            assert_eq!(message, None);
+
+            let reason = atom_unchecked("gone");
+
+            peer.send(
+                &[
+                    Term::make_smallint(MONITOR_P_EXIT),
+                    remote_term,
+                    arc_process.pid_term(),
+                    reference,
+                    reason,
+                ],
+                None,
+            )
+            .unwrap();
+
+            assert_eq!(
+                receive_message_within(&arc_process, TIMEOUT),
+                Some(
+                    arc_process
+                        .tuple_from_slice(&[
+                            atom_unchecked("DOWN"),
+                            reference,
+                            atom_unchecked("process"),
+                            remote_term,
+                            reason
+                        ])
+                        .unwrap()
+                )
+            );
+        });
+    }
+
+    #[test]
+    fn disconnect_breaks_link_to_peer_process_with_noconnection() {
+        with_process_arc(|arc_process| {
+            arc_process.trap_exit(true);
+
+            let peer = connect("bridge_link@peer").unwrap();
+            let remote = remote_pid(&peer);
+            let remote_term = remote.to_term(&mut *arc_process.acquire_heap()).unwrap();
+
+            link::link(&arc_process, remote).unwrap();
+            peer.disconnect();
+
+            assert_eq!(
+                receive_message_within(&arc_process, TIMEOUT),
+                Some(
+                    arc_process
+                        .tuple_from_slice(&[
+                            atom_unchecked("EXIT"),
+                            remote_term,
+                            atom_unchecked("noconnection")
+                        ])
+                        .unwrap()
+                )
+            );
+            assert!(distribution::connection(peer.node()).is_none());
+        });
+    }
+
+    #[test]
+    fn disconnect_sends_nodedown_to_node_monitors() {
+        with_process_arc(|arc_process| {
+            let peer = connect("bridge_monitor_node@peer").unwrap();
+
+            monitor_node::monitor_node(&arc_process, peer.node(), true);
+            peer.disconnect();
+
+            assert_eq!(
+                receive_message_within(&arc_process, TIMEOUT),
+                Some(
+                    arc_process
+                        .tuple_from_slice(&[
+                            atom_unchecked("nodedown"),
+                            atom_unchecked("bridge_monitor_node@peer")
+                        ])
+                        .unwrap()
+                )
+            );
+        });
+    }
+
+    #[test]
+    fn monitor_node_that_can_not_be_connected_to_sends_nodedown() {
+        with_process_arc(|arc_process| {
+            let node = Atom::try_from_str("bridge_unreachable@peer").unwrap();
+
+            // this node is not alive, so it can't connect to other nodes
+            monitor_node::monitor_node(&arc_process, node, true);
+
+            assert_eq!(
+                receive_message_within(&arc_process, TIMEOUT),
+                Some(
+                    arc_process
+                        .tuple_from_slice(&[
+                            atom_unchecked("nodedown"),
+                            atom_unchecked("bridge_unreachable@peer")
+                        ])
+                        .unwrap()
+                )
+            );
+        });
+    }
+
+    #[test]
+    fn receive_without_packet_times_out() {
+        with_process_arc(|arc_process| {
+            let peer = connect("bridge_timeout@peer").unwrap();
+
+            match peer.receive(&arc_process, Duration::from_millis(10)) {
+                Err(Error::Io(ref error)) if error.kind() == io::ErrorKind::WouldBlock => (),
+                Err(error) => panic!("{} is not a timeout", error),
+                Ok(received) => panic!("{:?} was received", received),
+            }
+
+            assert!(distribution::connection(peer.node()).is_some());
+        });
+    }
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn remote_pid(peer: &Peer) -> RemotePid {
+        RemotePid {
+            node: Node::new(peer.node(), 1),
+            pid: Pid::new(1, 0).unwrap(),
+        }
+    }
+
+    /// Waits up to `timeout` for a message from the thread reading the connection
+    fn receive_message_within(process: &Process, timeout: Duration) -> Option<Term> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            match receive_message(process) {
+                Some(message) => return Some(message),
+                None if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(10)),
+                None => return None,
+            }
+        }
+    }
+}
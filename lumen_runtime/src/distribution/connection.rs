@@ -0,0 +1,225 @@
+//! A connection to another node after the handshake.
+//!
+//! Every packet has a 4-byte length, and an empty packet is a tick.  A connection ticks whenever
+//! it has not written anything for a quarter of `NET_TICKTIME`, and closes when it has not read
+//! anything, not even a tick, for `NET_TICKTIME`, as `net_kernel` does.  Every other packet is
+//! handled by `control` as it is read.
+//!
+//! Connections are over TCP, except in tests, which connect over the in-memory streams of
+//! `bridge`.
+
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use liblumen_alloc::erts::term::Atom;
+
+/// What a connection reads packets from and writes packets to
+pub trait Stream: Read + Write + Send {
+    /// Another handle to the same stream, so that it can be read and written on different threads
+    fn try_clone_stream(&self) -> io::Result<Box<dyn Stream>>;
+
+    /// Makes reads fail with `WouldBlock` or `TimedOut` when nothing is read for `timeout`
+    fn set_stream_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()>;
+
+    /// Closes both directions of the stream
+    fn shutdown_stream(&self) -> io::Result<()>;
+}
+
+impl Stream for TcpStream {
+    fn try_clone_stream(&self) -> io::Result<Box<dyn Stream>> {
+        self.try_clone()
+            .map(|stream| Box::new(stream) as Box<dyn Stream>)
+    }
+
+    fn set_stream_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.set_read_timeout(timeout)
+    }
+
+    fn shutdown_stream(&self) -> io::Result<()> {
+        self.shutdown(Shutdown::Both)
+    }
+}
+
+/// How long a node may go without hearing from the other before it considers it dead
+pub const NET_TICKTIME: Duration = Duration::from_secs(60);
+
+const TICK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// The longest packet that is read.  The length of a packet comes from the other node, so it is
+/// checked before anything is allocated for the packet.
+pub const MAX_PACKET_LEN: usize = 64 * 1024 * 1024;
+
+pub struct Connection {
+    pub node: Atom,
+    /// The capabilities the other node sent in the handshake
+    pub flags: u64,
+    writer: Mutex<Writer>,
+    open: AtomicBool,
+}
+
+impl Connection {
+    /// Starts reading from and ticking on `stream`, which has completed the handshake with `node`.
+    pub fn start<S: Stream + 'static>(
+        node: Atom,
+        flags: u64,
+        stream: S,
+    ) -> io::Result<Arc<Connection>> {
+        stream.set_stream_read_timeout(Some(NET_TICKTIME))?;
+
+        let reader = stream.try_clone_stream()?;
+        let connection = Arc::new(Connection {
+            node,
+            flags,
+            writer: Mutex::new(Writer {
+                stream: Box::new(stream),
+                last_write: Instant::now(),
+            }),
+            open: AtomicBool::new(true),
+        });
+
+        let reading_connection = connection.clone();
+        thread::spawn(move || reading_connection.read(reader));
+
+        let ticking_connection = connection.clone();
+        thread::spawn(move || ticking_connection.tick());
+
+        Ok(connection)
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open.load(Ordering::SeqCst)
+    }
+
+    /// Sends `packet`, a distribution header or control message, to the other node.
+    pub fn send(&self, packet: &[u8]) -> io::Result<()> {
+        let result = self.writer.lock().unwrap().write_packet(packet);
+
+        if result.is_err() {
+            self.close_because("connection_closed");
+        }
+
+        result
+    }
+
+    pub fn close(&self) {
+        self.close_because("disconnect");
+    }
+
+    // Private
+
+    /// Closes the connection, telling `net_kernel:monitor_nodes` subscribers that the node went
+    /// down for `reason`
+    fn close_because(&self, reason: &str) {
+        if self.open.swap(false, Ordering::SeqCst) {
+            let _ = self.writer.lock().unwrap().stream.shutdown_stream();
+
+            super::remove(self, reason);
+        }
+    }
+
+    fn read(&self, mut stream: Box<dyn Stream>) {
+        let mut reason = "connection_closed";
+
+        while self.is_open() {
+            match read_packet(&mut stream) {
+                // tick
+                Ok(ref packet) if packet.is_empty() => (),
+                // packets that can't be decoded are dropped
+                Ok(packet) => {
+                    let _ = super::control::receive(&packet);
+                }
+                // the read timeout is `NET_TICKTIME`, so nothing, not even a tick, was read
+                Err(ref error)
+                    if error.kind() == io::ErrorKind::WouldBlock
+                        || error.kind() == io::ErrorKind::TimedOut =>
+                {
+                    reason = "net_tick_timeout";
+
+                    break;
+                }
+                Err(_) => break,
+            }
+        }
+
+        self.close_because(reason);
+    }
+
+    fn tick(&self) {
+        while self.is_open() {
+            thread::sleep(TICK_INTERVAL);
+
+            let mut writer = self.writer.lock().unwrap();
+
+            if TICK_INTERVAL <= writer.last_write.elapsed() && writer.write_packet(&[]).is_err() {
+                drop(writer);
+                self.close_because("connection_closed");
+            }
+        }
+    }
+}
+
+struct Writer {
+    stream: Box<dyn Stream>,
+    last_write: Instant,
+}
+
+impl Writer {
+    fn write_packet(&mut self, packet: &[u8]) -> io::Result<()> {
+        write_packet(&mut self.stream, packet)?;
+        self.last_write = Instant::now();
+
+        Ok(())
+    }
+}
+
+pub(super) fn write_packet<W: Write>(stream: &mut W, packet: &[u8]) -> io::Result<()> {
+    stream.write_all(&(packet.len() as u32).to_be_bytes())?;
+    stream.write_all(packet)
+}
+
+pub(super) fn read_packet<R: Read>(stream: &mut R) -> io::Result<Vec<u8>> {
+    let mut len = [0; 4];
+    stream.read_exact(&mut len)?;
+
+    let len = u32::from_be_bytes(len) as usize;
+
+    if MAX_PACKET_LEN < len {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "packet is longer than MAX_PACKET_LEN",
+        ));
+    }
+
+    let mut packet = vec![0; len];
+    stream.read_exact(&mut packet)?;
+
+    Ok(packet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Cursor;
+
+    #[test]
+    fn read_packet_reads_length_then_packet() {
+        let mut stream = Cursor::new(vec![0, 0, 0, 2, 1, 2, 3]);
+
+        assert_eq!(read_packet(&mut stream).unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn read_packet_longer_than_max_errors_without_reading_it() {
+        let mut stream = Cursor::new(((MAX_PACKET_LEN + 1) as u32).to_be_bytes().to_vec());
+
+        assert_eq!(
+            read_packet(&mut stream).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+}
@@ -0,0 +1,502 @@
+//! Control messages, which are the packets other than ticks sent over a `Connection`.
+//!
+//! Connections don't use the atom cache, so every packet is `PASS_THROUGH` followed by the
+//! control message tuple and, for the control messages that carry one, the message, each in the
+//! external term format.  When received, the messages of `SEND` and `REG_SEND` are delivered to
+//! local processes, and dropped if there is no such process, except that `REG_SEND`s to
+//! `global::NAME` are handled by `global`.  Links, monitors and exits are handled by `link` and
+//! `monitor`.  Other control messages are ignored.
+//!
+//! Pids and references of other nodes are kept as `RemotePid`s and `RemoteReference`s, which,
+//! unlike `ExternalPid`s and `ExternalReference`s, aren't on a heap, so they can outlive the control
+//! message they were in.
+
+use core::convert::TryInto;
+use core::ptr;
+
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::alloc::heap_alloc::MakePidError;
+use liblumen_alloc::erts::process::HeapAlloc;
+use liblumen_alloc::erts::scheduler::ID;
+use liblumen_alloc::erts::term::{
+    atom_unchecked, reference, AsTerm, Atom, Boxed, ExternalReference, Pid, Reference, Term,
+    TypedTerm, MAX_EXTRA_IDS,
+};
+use liblumen_alloc::erts::{HeapFragment, Node};
+use liblumen_alloc::CloneToProcess;
+
+use crate::process::send_heap_message_and_wake;
+use crate::registry::{atom_to_process, pid_to_process};
+
+use super::{global, link, monitor, Connection};
+use crate::external_term_format::{self, decode_prefix_to_heap_fragment, encode, encode_tuple};
+
+pub const LINK: isize = 1;
+pub const SEND: isize = 2;
+pub const EXIT: isize = 3;
+pub const UNLINK: isize = 4;
+pub const REG_SEND: isize = 6;
+pub const GROUP_LEADER: isize = 7;
+pub const EXIT2: isize = 8;
+pub const MONITOR_P: isize = 19;
+pub const DEMONITOR_P: isize = 20;
+pub const MONITOR_P_EXIT: isize = 21;
+pub const UNLINK_ID: isize = 35;
+pub const UNLINK_ID_ACK: isize = 36;
+
+pub(super) const PASS_THROUGH: u8 = 112;
+
+/// The words that are enough for the tuples, pids and references in any control message that is
+/// made by `send_made`
+const MADE_NEED_IN_WORDS: usize = 64;
+
+/// A pid of a process on another node
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RemotePid {
+    pub node: Node,
+    pub pid: Pid,
+}
+
+impl RemotePid {
+    /// The `RemotePid` of `term` if it is an `ExternalPid`
+    pub fn from_term(term: Term) -> Option<RemotePid> {
+        match term.to_typed_term().unwrap() {
+            TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+                TypedTerm::ExternalPid(external_pid) => Some(RemotePid {
+                    node: external_pid.node(),
+                    pid: external_pid.pid(),
+                }),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// An `ExternalPid` for this pid on `heap`
+    pub fn to_term<H: HeapAlloc>(&self, heap: &mut H) -> Result<Term, Alloc> {
+        heap.external_pid(self.node, self.pid.number(), self.pid.serial())
+            .map_err(|make_pid_error| match make_pid_error {
+                MakePidError::Alloc(alloc) => alloc,
+                // the number and serial are from a valid `Pid`
+                MakePidError::Number | MakePidError::Serial => unreachable!(),
+            })
+    }
+}
+
+/// A reference made on another node
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RemoteReference {
+    pub node: Node,
+    pub scheduler_id: ID,
+    pub number: reference::Number,
+    extra_ids_len: usize,
+    extra_ids: [u32; MAX_EXTRA_IDS],
+}
+
+impl RemoteReference {
+    /// The `RemoteReference` of `term` if it is an `ExternalReference`
+    pub fn from_term(term: Term) -> Option<RemoteReference> {
+        match term.to_typed_term().unwrap() {
+            TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+                TypedTerm::ExternalReference(external_reference) => {
+                    let reference = external_reference.reference();
+                    let extra_ids = external_reference.extra_ids();
+                    let mut remote_reference = RemoteReference {
+                        node: external_reference.node(),
+                        scheduler_id: reference.scheduler_id(),
+                        number: reference.number(),
+                        extra_ids_len: extra_ids.len(),
+                        extra_ids: [0; MAX_EXTRA_IDS],
+                    };
+                    remote_reference.extra_ids[..extra_ids.len()].copy_from_slice(extra_ids);
+
+                    Some(remote_reference)
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// An `ExternalReference` for this reference on `heap`
+    pub fn to_term<H: HeapAlloc>(&self, heap: &mut H) -> Result<Term, Alloc> {
+        ExternalReference::with_extra_ids(
+            self.node,
+            self.scheduler_id,
+            self.number,
+            &self.extra_ids[..self.extra_ids_len],
+        )
+        .clone_to_heap(heap)
+    }
+}
+
+/// Sends `message` to the process with the pid `to` on the other node
+pub fn send(connection: &Connection, to: Term, message: Term) -> Result<(), super::Error> {
+    send_control(
+        connection,
+        &[Term::make_smallint(SEND), atom_unchecked(""), to],
+        Some(message),
+    )
+}
+
+/// Sends `message` from the process with the pid `from` to the process registered as `to_name` on
+/// the other node
+pub fn reg_send(
+    connection: &Connection,
+    from: Term,
+    to_name: Atom,
+    message: Term,
+) -> Result<(), super::Error> {
+    let packet = reg_send_packet(from, to_name, message)?;
+
+    connection.send(&packet).map_err(From::from)
+}
+
+/// The packet `reg_send` sends, so that it can be made from terms on a process's heap and sent
+/// later, such as once there is a connection
+pub fn reg_send_packet(from: Term, to_name: Atom, message: Term) -> Result<Vec<u8>, super::Error> {
+    control_packet(
+        &[
+            Term::make_smallint(REG_SEND),
+            from,
+            atom_unchecked(""),
+            unsafe { to_name.as_term() },
+        ],
+        Some(message),
+    )
+}
+
+/// Sends the control message with the elements that `make` makes in a temporary heap fragment, for
+/// control messages with pids and references of other nodes, which aren't on any process's heap
+pub fn send_made<F>(connection: &Connection, make: F) -> Result<(), super::Error>
+where
+    F: FnOnce(&mut HeapFragment) -> Result<Vec<Term>, Alloc>,
+{
+    let mut non_null_heap_fragment =
+        unsafe { HeapFragment::new_from_word_size(MADE_NEED_IN_WORDS)? };
+    let heap_fragment = unsafe { non_null_heap_fragment.as_mut() };
+
+    let result = make(heap_fragment)
+        .map_err(From::from)
+        .and_then(|control| send_control(connection, &control, None));
+
+    unsafe { ptr::drop_in_place(non_null_heap_fragment.as_ptr()) };
+
+    result
+}
+
+/// Handles a `packet` received over a connection
+pub fn receive(packet: &[u8]) -> Result<(), external_term_format::Error> {
+    let bytes = match packet.split_first() {
+        Some((&PASS_THROUGH, bytes)) => bytes,
+        _ => return Err(external_term_format::Error::Invalid),
+    };
+    let (control, control_heap_fragment, message_bytes) = decode_prefix_to_heap_fragment(bytes)?;
+
+    let result = deliver(control, message_bytes);
+
+    unsafe { ptr::drop_in_place(control_heap_fragment.as_ptr()) };
+
+    result
+}
+
+// Private
+
+fn send_control(
+    connection: &Connection,
+    control: &[Term],
+    message: Option<Term>,
+) -> Result<(), super::Error> {
+    let packet = control_packet(control, message)?;
+
+    connection.send(&packet).map_err(From::from)
+}
+
+fn control_packet(control: &[Term], message: Option<Term>) -> Result<Vec<u8>, super::Error> {
+    let mut packet = vec![PASS_THROUGH];
+    packet.extend_from_slice(&encode_tuple(control)?);
+
+    if let Some(message) = message {
+        packet.extend_from_slice(&encode(message)?);
+    }
+
+    Ok(packet)
+}
+
+fn deliver(control: Term, message_bytes: &[u8]) -> Result<(), external_term_format::Error> {
+    let control_vec: Vec<Term> = match control.to_typed_term().unwrap() {
+        TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+            TypedTerm::Tuple(tuple) if 0 < tuple.len() => tuple.iter().collect(),
+            _ => return Err(external_term_format::Error::Invalid),
+        },
+        _ => return Err(external_term_format::Error::Invalid),
+    };
+    let tag: isize = match control_vec[0].to_typed_term().unwrap() {
+        TypedTerm::SmallInteger(small_integer) => small_integer.into(),
+        _ => return Err(external_term_format::Error::Invalid),
+    };
+
+    let option_process = match (tag, control_vec.len()) {
+        (SEND, 3) => {
+            // pids of other nodes and of other incarnations of this node aren't `Pid`s
+            let to: Result<Pid, _> = control_vec[2].try_into();
+
+            to.ok().and_then(|pid| pid_to_process(&pid))
+        }
+        (REG_SEND, 4) => {
+            let to_name: Atom = control_vec[3]
+                .try_into()
+                .map_err(|_| external_term_format::Error::Invalid)?;
+
+            if to_name.name() == global::NAME {
+                return global::receive(message_bytes);
+            }
+
+            atom_to_process(&to_name)
+        }
+        (LINK, 3) => {
+            link::linked(remote_pid(control_vec[1])?, local_pid(control_vec[2])?);
+
+            None
+        }
+        (UNLINK, 3) => {
+            link::unlinked(remote_pid(control_vec[1])?, local_pid(control_vec[2])?);
+
+            None
+        }
+        (UNLINK_ID, 4) => {
+            link::unlinked_with_id(
+                control_vec[1],
+                remote_pid(control_vec[2])?,
+                local_pid(control_vec[3])?,
+            );
+
+            None
+        }
+        (EXIT, 4) => {
+            link::exited(
+                remote_pid(control_vec[1])?,
+                local_pid(control_vec[2])?,
+                control_vec[3],
+            );
+
+            None
+        }
+        (EXIT2, 4) => {
+            link::exit2(
+                remote_pid(control_vec[1])?,
+                local_pid(control_vec[2])?,
+                control_vec[3],
+            );
+
+            None
+        }
+        (MONITOR_P, 4) => {
+            monitor::monitored(
+                remote_pid(control_vec[1])?,
+                control_vec[2],
+                remote_reference(control_vec[3])?,
+            );
+
+            None
+        }
+        (DEMONITOR_P, 4) => {
+            monitor::demonitored(control_vec[2], remote_reference(control_vec[3])?);
+
+            None
+        }
+        (MONITOR_P_EXIT, 5) => {
+            let reference: Boxed<Reference> = control_vec[3]
+                .try_into()
+                .map_err(|_| external_term_format::Error::Invalid)?;
+
+            monitor::exited(local_pid(control_vec[2])?, &reference, control_vec[4]);
+
+            None
+        }
+        _ => None,
+    };
+
+    if let Some(process) = option_process {
+        let (message, message_heap_fragment, rest) = decode_prefix_to_heap_fragment(message_bytes)?;
+
+        if rest.is_empty() {
+            send_heap_message_and_wake(&process, message_heap_fragment, message);
+        } else {
+            unsafe { ptr::drop_in_place(message_heap_fragment.as_ptr()) };
+
+            return Err(external_term_format::Error::Invalid);
+        }
+    }
+
+    Ok(())
+}
+
+fn local_pid(term: Term) -> Result<Pid, external_term_format::Error> {
+    term.try_into()
+        .map_err(|_| external_term_format::Error::Invalid)
+}
+
+fn remote_pid(term: Term) -> Result<RemotePid, external_term_format::Error> {
+    RemotePid::from_term(term).ok_or(external_term_format::Error::Invalid)
+}
+
+fn remote_reference(term: Term) -> Result<RemoteReference, external_term_format::Error> {
+    RemoteReference::from_term(term).ok_or(external_term_format::Error::Invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::registry;
+    use crate::scheduler::with_process_arc;
+    use crate::test::receive_message;
+
+    #[test]
+    fn receive_delivers_send_to_local_pid() {
+        with_process_arc(|arc_process| {
+            let message = arc_process
+                .tuple_from_slice(&[atom_unchecked("hello"), arc_process.integer(1).unwrap()])
+                .unwrap();
+            let packet = packet(
+                &[
+                    Term::make_smallint(SEND),
+                    atom_unchecked(""),
+                    arc_process.pid_term(),
+                ],
+                message,
+            );
+
+            receive(&packet).unwrap();
+
+            assert_eq!(receive_message(&arc_process), Some(message));
+        });
+    }
+
+    #[test]
+    fn receive_delivers_reg_send_to_registered_name() {
+        with_process_arc(|arc_process| {
+            let name = Atom::try_from_str("control_receive_reg_send").unwrap();
+            let message = atom_unchecked("hello");
+            let packet = packet(
+                &[
+                    Term::make_smallint(REG_SEND),
+                    arc_process.pid_term(),
+                    atom_unchecked(""),
+                    unsafe { name.as_term() },
+                ],
+                message,
+            );
+
+            assert!(registry::put_atom_to_process(name, arc_process.clone()));
+
+            receive(&packet).unwrap();
+
+            assert!(registry::unregister(&name));
+            assert_eq!(receive_message(&arc_process), Some(message));
+        });
+    }
+
+    #[test]
+    fn receive_exit_after_link_sends_exit_message_to_trapping_process() {
+        with_process_arc(|arc_process| {
+            arc_process.trap_exit(true);
+
+            let remote = RemotePid {
+                node: Node::new(
+                    Atom::try_from_str("control_receive_exit@remote").unwrap(),
+                    1,
+                ),
+                pid: Pid::new(0, 1).unwrap(),
+            };
+            let from = remote.to_term(&mut *arc_process.acquire_heap()).unwrap();
+            let reason = atom_unchecked("remote_reason");
+
+            receive(&packet_without_message(&[
+                Term::make_smallint(LINK),
+                from,
+                arc_process.pid_term(),
+            ]))
+            .unwrap();
+            receive(&packet_without_message(&[
+                Term::make_smallint(EXIT),
+                from,
+                arc_process.pid_term(),
+                reason,
+            ]))
+            .unwrap();
+
+            assert_eq!(
+                receive_message(&arc_process),
+                Some(
+                    arc_process
+                        .tuple_from_slice(&[atom_unchecked("EXIT"), from, reason])
+                        .unwrap()
+                )
+            );
+        });
+    }
+
+    #[test]
+    fn receive_exit_after_unlink_id_is_ignored() {
+        with_process_arc(|arc_process| {
+            arc_process.trap_exit(true);
+
+            let remote = RemotePid {
+                node: Node::new(
+                    Atom::try_from_str("control_receive_unlink_id@remote").unwrap(),
+                    1,
+                ),
+                pid: Pid::new(0, 1).unwrap(),
+            };
+            let from = remote.to_term(&mut *arc_process.acquire_heap()).unwrap();
+
+            receive(&packet_without_message(&[
+                Term::make_smallint(LINK),
+                from,
+                arc_process.pid_term(),
+            ]))
+            .unwrap();
+            receive(&packet_without_message(&[
+                Term::make_smallint(UNLINK_ID),
+                Term::make_smallint(1),
+                from,
+                arc_process.pid_term(),
+            ]))
+            .unwrap();
+            receive(&packet_without_message(&[
+                Term::make_smallint(EXIT),
+                from,
+                arc_process.pid_term(),
+                atom_unchecked("remote_reason"),
+            ]))
+            .unwrap();
+
+            assert_eq!(receive_message(&arc_process), None);
+        });
+    }
+
+    #[test]
+    fn receive_without_pass_through_is_invalid() {
+        match receive(&[131, 106]) {
+            Err(external_term_format::Error::Invalid) => (),
+            result => panic!("{:?} is not invalid", result),
+        }
+    }
+
+    fn packet(control: &[Term], message: Term) -> Vec<u8> {
+        let mut packet = vec![PASS_THROUGH];
+        packet.extend_from_slice(&encode_tuple(control).unwrap());
+        packet.extend_from_slice(&encode(message).unwrap());
+
+        packet
+    }
+
+    fn packet_without_message(control: &[Term]) -> Vec<u8> {
+        let mut packet = vec![PASS_THROUGH];
+        packet.extend_from_slice(&encode_tuple(control).unwrap());
+
+        packet
+    }
+}
@@ -0,0 +1,274 @@
+//! A client for EPMD, the Erlang Port Mapper Daemon, which maps the names of the nodes on a host
+//! to the ports they listen on.
+//!
+//! EPMD listens on `DEFAULT_PORT` unless `ERL_EPMD_PORT` is set, as with `erl`.
+
+use std::env;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+
+use super::handshake::VERSION;
+use super::{split_node_name, Error};
+
+pub const DEFAULT_PORT: u16 = 4369;
+
+const ALIVE2_REQ: u8 = 120;
+/// The reply to `ALIVE2_REQ` with a 4-byte creation, from EPMDs of OTP 23 on
+const ALIVE2_X_RESP: u8 = 118;
+/// The reply to `ALIVE2_REQ` with a 2-byte creation, from older EPMDs
+const ALIVE2_RESP: u8 = 121;
+const PORT_PLEASE2_REQ: u8 = 122;
+const PORT2_RESP: u8 = 119;
+const NAMES_REQ: u8 = 110;
+
+/// A normal node, as opposed to a hidden one
+const NODE_TYPE_NORMAL: u8 = 77;
+/// TCP/IPv4
+const PROTOCOL_TCP_IPV4: u8 = 0;
+
+/// The port EPMD listens on
+pub fn port() -> u16 {
+    env::var("ERL_EPMD_PORT")
+        .ok()
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(DEFAULT_PORT)
+}
+
+/// Registers `alive_name` as listening on `port` with the EPMD on this host.  EPMD keeps the
+/// registration for as long as the returned stream is open.  The creation EPMD assigns is
+/// returned with the stream, so that identifiers from this incarnation of the node can be told
+/// apart from those of earlier ones with the same name.
+pub fn register(alive_name: &str, port: u16) -> Result<(TcpStream, u32), Error> {
+    register_at(("localhost", self::port()), alive_name, port)
+}
+
+/// The host and port that the node named `node_name` (`name@host`) listens on
+pub fn resolve(node_name: &str) -> Result<(String, u16), Error> {
+    let (alive_name, host) = split_node_name(node_name)?;
+    let port = port_please(host, alive_name)?;
+
+    Ok((host.to_string(), port))
+}
+
+/// The port that the node `alive_name` on `host` listens on
+pub fn port_please(host: &str, alive_name: &str) -> Result<u16, Error> {
+    port_please_at((host, port()), alive_name)
+}
+
+/// The names and ports of the nodes registered with the EPMD on `host`, as `net_adm:names/1`
+/// returns.
+pub fn names(host: &str) -> Result<Vec<(String, u16)>, Error> {
+    names_at((host, port()))
+}
+
+// Private
+
+type Address<'a> = (&'a str, u16);
+
+fn register_at(epmd: Address, alive_name: &str, port: u16) -> Result<(TcpStream, u32), Error> {
+    let mut request = vec![ALIVE2_REQ];
+    request.extend_from_slice(&port.to_be_bytes());
+    request.push(NODE_TYPE_NORMAL);
+    request.push(PROTOCOL_TCP_IPV4);
+    // highest and lowest version
+    request.extend_from_slice(&VERSION.to_be_bytes());
+    request.extend_from_slice(&VERSION.to_be_bytes());
+    request.extend_from_slice(&(alive_name.len() as u16).to_be_bytes());
+    request.extend_from_slice(alive_name.as_bytes());
+    // no extra
+    request.extend_from_slice(&0_u16.to_be_bytes());
+
+    let mut stream = request_stream(epmd, &request)?;
+
+    // tag and result, followed by the creation
+    let mut response = [0; 2];
+    stream.read_exact(&mut response)?;
+
+    match response {
+        [ALIVE2_X_RESP, 0] => {
+            let mut creation = [0; 4];
+            stream.read_exact(&mut creation)?;
+
+            Ok((stream, u32::from_be_bytes(creation)))
+        }
+        [ALIVE2_RESP, 0] => {
+            let mut creation = [0; 2];
+            stream.read_exact(&mut creation)?;
+
+            Ok((stream, u16::from_be_bytes(creation) as u32))
+        }
+        [ALIVE2_X_RESP, _] | [ALIVE2_RESP, _] => Err(Error::RegistrationRefused),
+        _ => Err(Error::Protocol("ALIVE2_RESP")),
+    }
+}
+
+fn port_please_at(epmd: Address, alive_name: &str) -> Result<u16, Error> {
+    let mut request = vec![PORT_PLEASE2_REQ];
+    request.extend_from_slice(alive_name.as_bytes());
+
+    let mut stream = request_stream(epmd, &request)?;
+
+    // tag and result, followed by the port and the rest of the node's registration when found
+    let mut response = [0; 2];
+    stream.read_exact(&mut response)?;
+
+    match response {
+        [PORT2_RESP, 0] => {
+            let mut port = [0; 2];
+            stream.read_exact(&mut port)?;
+
+            Ok(u16::from_be_bytes(port))
+        }
+        [PORT2_RESP, _] => Err(Error::NotRegistered),
+        _ => Err(Error::Protocol("PORT2_RESP")),
+    }
+}
+
+fn names_at(epmd: Address) -> Result<Vec<(String, u16)>, Error> {
+    let mut stream = request_stream(epmd, &[NAMES_REQ])?;
+
+    // EPMD's own port, then a `name NAME at port PORT` line for each node until EPMD closes
+    let mut epmd_port = [0; 4];
+    stream.read_exact(&mut epmd_port)?;
+
+    let mut text = String::new();
+    stream.read_to_string(&mut text)?;
+
+    text.lines()
+        .map(|line| {
+            let words: Vec<&str> = line.split_whitespace().collect();
+
+            match words[..] {
+                ["name", name, "at", "port", port] => port
+                    .parse()
+                    .map(|port| (name.to_string(), port))
+                    .map_err(|_| Error::Protocol("NAMES_RESP")),
+                _ => Err(Error::Protocol("NAMES_RESP")),
+            }
+        })
+        .collect()
+}
+
+/// Connects to EPMD and sends `request` with its 2-byte length
+fn request_stream(epmd: Address, request: &[u8]) -> Result<TcpStream, Error> {
+    let mut stream = TcpStream::connect(epmd)?;
+    stream.write_all(&(request.len() as u16).to_be_bytes())?;
+    stream.write_all(request)?;
+
+    Ok(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    #[test]
+    fn register_sends_alive2_request() {
+        let (port, epmd) = fake_epmd(vec![ALIVE2_X_RESP, 0, 0, 1, 0, 2]);
+
+        assert_eq!(
+            register_at(("127.0.0.1", port), "lumen", 4370).unwrap().1,
+            0x10002
+        );
+        assert_eq!(
+            epmd.join().unwrap(),
+            [
+                &[
+                    0,
+                    18,
+                    ALIVE2_REQ,
+                    17,
+                    18,
+                    NODE_TYPE_NORMAL,
+                    PROTOCOL_TCP_IPV4,
+                    0,
+                    6,
+                    0,
+                    6,
+                    0,
+                    5
+                ][..],
+                b"lumen",
+                &[0, 0]
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn register_with_old_epmd_reads_2_byte_creation() {
+        let (port, _) = fake_epmd(vec![ALIVE2_RESP, 0, 0, 3]);
+
+        assert_eq!(
+            register_at(("127.0.0.1", port), "lumen", 4370).unwrap().1,
+            3
+        );
+    }
+
+    #[test]
+    fn register_with_taken_name_is_refused() {
+        let (port, _) = fake_epmd(vec![ALIVE2_X_RESP, 1]);
+
+        match register_at(("127.0.0.1", port), "lumen", 4370) {
+            Err(Error::RegistrationRefused) => (),
+            _ => panic!("registration was not refused"),
+        }
+    }
+
+    #[test]
+    fn port_please_returns_port_of_registered_node() {
+        let (port, epmd) = fake_epmd(vec![PORT2_RESP, 0, 17, 18, NODE_TYPE_NORMAL]);
+
+        assert_eq!(port_please_at(("127.0.0.1", port), "beam").unwrap(), 4370);
+        assert_eq!(
+            epmd.join().unwrap(),
+            [&[0, 5, PORT_PLEASE2_REQ][..], b"beam"].concat()
+        );
+    }
+
+    #[test]
+    fn port_please_with_unregistered_node_errors() {
+        let (port, _) = fake_epmd(vec![PORT2_RESP, 1]);
+
+        match port_please_at(("127.0.0.1", port), "beam") {
+            Err(Error::NotRegistered) => (),
+            _ => panic!("unregistered node has a port"),
+        }
+    }
+
+    #[test]
+    fn names_returns_name_and_port_of_each_node() {
+        let mut response = vec![0, 0, 17, 17];
+        response.extend_from_slice(b"name beam at port 4370\nname lumen at port 4371\n");
+        let (port, _) = fake_epmd(response);
+
+        assert_eq!(
+            names_at(("127.0.0.1", port)).unwrap(),
+            vec![("beam".to_string(), 4370), ("lumen".to_string(), 4371)]
+        );
+    }
+
+    /// An EPMD that replies to one request with `response` and returns the request it received
+    fn fake_epmd(response: Vec<u8>) -> (u16, JoinHandle<Vec<u8>>) {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let epmd = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            let mut len = [0; 2];
+            stream.read_exact(&mut len).unwrap();
+
+            let mut request = vec![0; u16::from_be_bytes(len) as usize];
+            stream.read_exact(&mut request).unwrap();
+            stream.write_all(&response).unwrap();
+
+            [&len[..], &request[..]].concat()
+        });
+
+        (port, epmd)
+    }
+}
@@ -0,0 +1,376 @@
+//! A subset of `global`: names registered across all connected nodes, as
+//! `global:register_name/2`, `global:whereis_name/1` and `global:send/2` use.
+//!
+//! Each node keeps its own copy of every registration.  A node that registers a name tells every
+//! connected node with a `{register, Name, Pid}` message to the name `lumen_global`, and tells them
+//! `{unregister, Name, Pid}` when the registered process exits.  When a node connects, each side
+//! tells the other its registrations of local processes, and when it disconnects, the names of its
+//! processes are dropped.
+//!
+//! As with `global`, a name can be any term, and names are the same when they are exactly equal.
+//! The copies key each name by its external term format, which is the same for exactly equal
+//! terms, so a name must be a term this node can decode as well as encode, which rules out funs.
+//!
+//! Unlike `global`, names aren't locked across the nodes while they are registered, so two nodes
+//! can register the same name to different processes at the same time.  When a node is told of a
+//! registration that conflicts with its own copy, the process of the lowest node name, then pid,
+//! keeps the name and the other process is killed, as `global:random_exit_name/3` does.  Every node
+//! picks the same process, so the copies agree once they have been told of both registrations.
+//!
+//! This doesn't interoperate with `global` on BEAM nodes: they have no `lumen_global`, so they drop
+//! the messages and never see names registered on Lumen nodes, and Lumen nodes don't take part in
+//! `global`'s own protocol, so they never see names registered on BEAM nodes.
+
+use core::convert::TryInto;
+use core::ptr;
+
+use std::sync::Mutex;
+
+use hashbrown::HashMap;
+
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::{HeapAlloc, Process};
+use liblumen_alloc::erts::term::{atom_unchecked, AsTerm, Atom, Pid, Term, Tuple, TypedTerm};
+use liblumen_alloc::erts::{HeapFragment, Node};
+
+use crate::node;
+use crate::process::exit_and_wake;
+use crate::registry::pid_to_process;
+use crate::send::Sent;
+
+use super::control::{self, RemotePid};
+use super::{connection, nodes, Error};
+use crate::external_term_format::{self, decode_prefix_to_heap_fragment};
+
+/// The name that nodes send registrations to
+pub const NAME: &str = "lumen_global";
+
+/// Registers `name` to `pid` on every connected node, returning `false` if `name` is already
+/// registered or `pid` is a local process that isn't alive
+///
+/// Returns `Error::Term` if `name` can't be sent to other nodes, such as a fun.
+pub fn register_name(process: &Process, name: Term, pid: Term) -> Result<bool, Error> {
+    let name = Name::registrable(name)?;
+    let registered = match Registered::from_term(pid) {
+        Some(registered) => registered,
+        None => return Ok(false),
+    };
+
+    if registered.is_local() && pid_to_process(&registered.pid).is_none() {
+        return Ok(false);
+    }
+
+    {
+        let mut names = NAMES.lock().unwrap();
+
+        if names.contains_key(&name) {
+            return Ok(false);
+        }
+
+        names.insert(name.clone(), registered);
+    }
+
+    for node in nodes() {
+        tell(node, process.pid_term(), "register", &name, registered)?;
+    }
+
+    Ok(true)
+}
+
+/// The pid that `name` is registered to, or `undefined`
+pub fn whereis_name(process: &Process, name: Term) -> Result<Term, Alloc> {
+    let option_registered = registered(name);
+
+    match option_registered {
+        Some(registered) => registered.to_term(&mut *process.acquire_heap()),
+        None => Ok(atom_unchecked("undefined")),
+    }
+}
+
+/// Sends `message` to the process `name` is registered to, returning its pid, or `None` if `name`
+/// isn't registered
+pub fn send(process: &Process, name: Term, message: Term) -> Result<Option<Term>, Error> {
+    let option_registered = registered(name);
+
+    match option_registered {
+        Some(registered) => {
+            let pid = registered.to_term(&mut *process.acquire_heap())?;
+
+            if registered.is_local() {
+                match crate::send::send(pid, message, Default::default(), process) {
+                    Ok(Sent::Sent) => (),
+                    // sends to local pids are always sent
+                    _ => unreachable!(),
+                }
+            } else if let Some(connection) = connection(registered.node.name()) {
+                control::send(&connection, pid, message)?;
+            }
+
+            Ok(Some(pid))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Unregisters the names registered to `process`, which has exited, on every connected node
+pub fn propagate_exit(process: &Process) {
+    let registered = Registered::local(process.pid());
+    let mut unregistered = Vec::new();
+
+    NAMES.lock().unwrap().retain(|name, name_registered| {
+        if *name_registered == registered {
+            unregistered.push(name.clone());
+
+            false
+        } else {
+            true
+        }
+    });
+
+    for name in unregistered {
+        for node in nodes() {
+            let _ = tell(node, process.pid_term(), "unregister", &name, registered);
+        }
+    }
+}
+
+/// Tells `node`, which connected, the names registered to local processes
+pub fn node_up(node: Atom) {
+    let local: Vec<(Name, Registered)> = NAMES
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(_, registered)| registered.is_local())
+        .map(|(name, registered)| (name.clone(), *registered))
+        .collect();
+
+    for (name, registered) in local {
+        let from = unsafe { registered.pid.as_term() };
+        let _ = tell(node, from, "register", &name, registered);
+    }
+}
+
+/// Unregisters the names registered to processes on `node`, which disconnected
+pub fn node_down(node: Atom) {
+    NAMES
+        .lock()
+        .unwrap()
+        .retain(|_, registered| registered.node.name() != node);
+}
+
+/// Handles the message in `message_bytes` sent to `NAME` by another node
+pub(super) fn receive(message_bytes: &[u8]) -> Result<(), external_term_format::Error> {
+    let (message, message_heap_fragment, rest) = decode_prefix_to_heap_fragment(message_bytes)?;
+
+    let result = if rest.is_empty() {
+        told(message)
+    } else {
+        Err(external_term_format::Error::Invalid)
+    };
+
+    unsafe { ptr::drop_in_place(message_heap_fragment.as_ptr()) };
+
+    result
+}
+
+// Private
+
+/// A registered name, as the external term format of the term, so that it is kept without a heap
+/// and exactly equal terms are the same name
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct Name(Vec<u8>);
+
+impl Name {
+    fn from_term(term: Term) -> Result<Self, external_term_format::Error> {
+        external_term_format::encode(term).map(Name)
+    }
+
+    /// The name of `term`, if it can also be decoded, as other nodes are told it
+    fn registrable(term: Term) -> Result<Self, external_term_format::Error> {
+        let name = Self::from_term(term)?;
+        let (_, heap_fragment, _) = decode_prefix_to_heap_fragment(&name.0)?;
+        unsafe { ptr::drop_in_place(heap_fragment.as_ptr()) };
+
+        Ok(name)
+    }
+}
+
+/// A process that a name is registered to, which can be on this node or another node
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Registered {
+    node: Node,
+    pid: Pid,
+}
+
+impl Registered {
+    fn local(pid: Pid) -> Self {
+        Self {
+            node: local_node(),
+            pid,
+        }
+    }
+
+    fn from_term(term: Term) -> Option<Self> {
+        match term.to_typed_term().unwrap() {
+            TypedTerm::Pid(pid) => Some(Self::local(pid)),
+            _ => RemotePid::from_term(term).map(|remote| Self {
+                node: remote.node,
+                pid: remote.pid,
+            }),
+        }
+    }
+
+    fn is_local(&self) -> bool {
+        self.node == local_node()
+    }
+
+    fn to_term<H: HeapAlloc>(&self, heap: &mut H) -> Result<Term, Alloc> {
+        if self.is_local() {
+            Ok(unsafe { self.pid.as_term() })
+        } else {
+            RemotePid {
+                node: self.node,
+                pid: self.pid,
+            }
+            .to_term(heap)
+        }
+    }
+
+    /// Whether this process keeps a name that `other` is also registered to
+    fn wins_against(&self, other: &Self) -> bool {
+        let key = |registered: &Self| {
+            (
+                registered.node.name().name().to_string(),
+                registered.pid.number(),
+                registered.pid.serial(),
+            )
+        };
+
+        key(self) < key(other)
+    }
+}
+
+fn local_node() -> Node {
+    Node::new(node::name(), node::creation())
+}
+
+/// The process `name` is registered to, if any.  Names that can't be encoded can't be registered.
+fn registered(name: Term) -> Option<Registered> {
+    let name = Name::from_term(name).ok()?;
+
+    NAMES.lock().unwrap().get(&name).copied()
+}
+
+/// Sends `{tag, name, registered}` from `from` to `NAME` on `node`
+fn tell(
+    node: Atom,
+    from: Term,
+    tag: &str,
+    name: &Name,
+    registered: Registered,
+) -> Result<(), Alloc> {
+    let connection = match connection(node) {
+        Some(connection) => connection,
+        None => return Ok(()),
+    };
+    // registered names are checked to decode
+    let (name, name_heap_fragment, _) = match decode_prefix_to_heap_fragment(&name.0) {
+        Ok(decoded) => decoded,
+        Err(external_term_format::Error::Alloc(alloc)) => return Err(alloc),
+        Err(_) => unreachable!(),
+    };
+    let need_in_words = Tuple::need_in_words_from_len(3) + MAX_PID_NEED_IN_WORDS;
+    let mut non_null_heap_fragment = unsafe { HeapFragment::new_from_word_size(need_in_words)? };
+    let heap_fragment = unsafe { non_null_heap_fragment.as_mut() };
+
+    let result = registered
+        .to_term(heap_fragment)
+        .and_then(|pid| heap_fragment.tuple_from_slice(&[atom_unchecked(tag), name, pid]))
+        .map(|message| {
+            // the node disconnecting is handled when its connection closes
+            let _ = control::reg_send(
+                &connection,
+                from,
+                Atom::try_from_str(NAME).unwrap(),
+                message,
+            );
+        });
+
+    unsafe {
+        ptr::drop_in_place(non_null_heap_fragment.as_ptr());
+        ptr::drop_in_place(name_heap_fragment.as_ptr());
+    }
+
+    result
+}
+
+/// Handles `{register, Name, Pid}` or `{unregister, Name, Pid}` from another node
+fn told(message: Term) -> Result<(), external_term_format::Error> {
+    let elements: Vec<Term> = match message.to_typed_term().unwrap() {
+        TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+            TypedTerm::Tuple(tuple) if tuple.len() == 3 => tuple.iter().collect(),
+            _ => return Err(external_term_format::Error::Invalid),
+        },
+        _ => return Err(external_term_format::Error::Invalid),
+    };
+    let tag: Atom = elements[0]
+        .try_into()
+        .map_err(|_| external_term_format::Error::Invalid)?;
+    let name = Name::from_term(elements[1])?;
+    let registered =
+        Registered::from_term(elements[2]).ok_or(external_term_format::Error::Invalid)?;
+
+    match tag.name() {
+        "register" => registered_by_other_node(name, registered),
+        "unregister" => {
+            let mut names = NAMES.lock().unwrap();
+
+            if names.get(&name) == Some(&registered) {
+                names.remove(&name);
+            }
+        }
+        _ => return Err(external_term_format::Error::Invalid),
+    }
+
+    Ok(())
+}
+
+fn registered_by_other_node(name: Name, registered: Registered) {
+    let loser = {
+        let mut names = NAMES.lock().unwrap();
+
+        match names.get(&name).copied() {
+            None => {
+                names.insert(name, registered);
+
+                None
+            }
+            Some(current) if current == registered => None,
+            Some(current) => {
+                if registered.wins_against(&current) {
+                    names.insert(name, registered);
+
+                    Some(current)
+                } else {
+                    Some(registered)
+                }
+            }
+        }
+    };
+
+    if let Some(loser) = loser {
+        if loser.is_local() {
+            if let Some(loser_arc_process) = pid_to_process(&loser.pid) {
+                let _ = exit_and_wake(&loser_arc_process, atom_unchecked("killed"));
+            }
+        }
+    }
+}
+
+/// More than the words an `ExternalPid` takes on a heap
+const MAX_PID_NEED_IN_WORDS: usize = 16;
+
+lazy_static! {
+    /// The process each name is registered to
+    static ref NAMES: Mutex<HashMap<Name, Registered>> = Default::default();
+}
@@ -0,0 +1,477 @@
+//! The version 6 distribution handshake from the "Distribution Protocol" chapter of the ERTS
+//! User's Guide, which nodes speak from OTP 23 on.  Older nodes are not supported.
+//!
+//! The initiating node sends its name, the accepting node replies with a status and a challenge,
+//! the initiating node answers the challenge with the MD5 digest of the cookie and the challenge
+//! and sends its own challenge, and the accepting node answers that in turn.  Each handshake
+//! message is a packet with a 2-byte length.
+//!
+//! Names and challenges are sent in the `'N'` messages of version 6, which have 64-bit flags and
+//! 32-bit creations.  An initiating node that only knows the accepting node from its
+//! version 5 `'n'` name, with `flags::HANDSHAKE_23` set, is answered with `'N'`, and completes its
+//! flags and creation with a `'c'` complement.
+
+use std::io::{Read, Write};
+
+use super::{flags, Error};
+
+pub const VERSION: u16 = 6;
+
+/// The version of the `'n'` name sent by initiating nodes that don't know the accepting node's
+/// version
+const OLD_VERSION: u16 = 5;
+
+/// The other node of a completed handshake
+pub struct Peer {
+    pub name: String,
+    pub flags: u64,
+    /// Which incarnation of the node with `name` this is
+    pub creation: u32,
+}
+
+/// Connects to a node as `name` with `creation`, returning the other node once both have proven
+/// they have `cookie`.
+pub fn initiate<S: Read + Write>(
+    stream: &mut S,
+    name: &str,
+    creation: u32,
+    cookie: &str,
+) -> Result<Peer, Error> {
+    write_name(stream, name, creation)?;
+
+    let status = read_packet(stream, b's', "status")?;
+    let status = String::from_utf8_lossy(&status).into_owned();
+
+    match status.as_str() {
+        "ok" | "ok_simultaneous" => (),
+        _ => return Err(Error::Status(status)),
+    }
+
+    let (peer, challenge) = read_challenge(stream)?;
+    let own_challenge = rand::random::<u32>();
+
+    let mut reply = vec![b'r'];
+    reply.extend_from_slice(&own_challenge.to_be_bytes());
+    reply.extend_from_slice(&digest(challenge, cookie));
+    write_packet(stream, &reply)?;
+
+    let ack = read_packet(stream, b'a', "challenge ack")?;
+
+    if ack[..] == digest(own_challenge, cookie)[..] {
+        Ok(peer)
+    } else {
+        Err(Error::Cookie)
+    }
+}
+
+/// Accepts a connection from a node as `name` with `creation`, returning the other node once both
+/// have proven they have `cookie`.  Nodes for which `is_connected` is true are refused, so that
+/// there is only one connection between any two nodes.
+pub fn accept<S: Read + Write, F: Fn(&str) -> bool>(
+    stream: &mut S,
+    name: &str,
+    creation: u32,
+    cookie: &str,
+    is_connected: F,
+) -> Result<Peer, Error> {
+    let (mut peer, needs_complement) = read_name(stream)?;
+
+    // nodes older than OTP 23 can't use the version 6 handshake
+    if (peer.flags & flags::HANDSHAKE_23) != flags::HANDSHAKE_23 {
+        write_packet(stream, b"snot_allowed")?;
+
+        return Err(Error::Status("not_allowed".to_string()));
+    }
+
+    if is_connected(&peer.name) {
+        write_packet(stream, b"snok")?;
+
+        return Err(Error::Status("nok".to_string()));
+    }
+
+    write_packet(stream, b"sok")?;
+
+    let challenge = rand::random::<u32>();
+    write_challenge(stream, name, creation, challenge)?;
+
+    if needs_complement {
+        let complement = read_packet(stream, b'c', "complement")?;
+
+        if complement.len() != 4 + 4 {
+            return Err(Error::Protocol("complement"));
+        }
+
+        let flags_high =
+            u32::from_be_bytes([complement[0], complement[1], complement[2], complement[3]]);
+        peer.flags |= (flags_high as u64) << 32;
+        peer.creation =
+            u32::from_be_bytes([complement[4], complement[5], complement[6], complement[7]]);
+    }
+
+    let reply = read_packet(stream, b'r', "challenge reply")?;
+
+    if reply.len() != 4 + 16 {
+        return Err(Error::Protocol("challenge reply"));
+    }
+
+    if reply[4..] != digest(challenge, cookie)[..] {
+        return Err(Error::Cookie);
+    }
+
+    let peer_challenge = u32::from_be_bytes([reply[0], reply[1], reply[2], reply[3]]);
+    let mut ack = vec![b'a'];
+    ack.extend_from_slice(&digest(peer_challenge, cookie));
+    write_packet(stream, &ack)?;
+
+    Ok(peer)
+}
+
+/// The answer to `challenge`: the MD5 digest of the cookie followed by the challenge in decimal
+pub fn digest(challenge: u32, cookie: &str) -> [u8; 16] {
+    md5::compute(format!("{}{}", cookie, challenge)).0
+}
+
+// Private
+
+/// Writes the version 6 `send_name` message
+fn write_name<W: Write>(stream: &mut W, name: &str, creation: u32) -> Result<(), Error> {
+    let mut message = vec![b'N'];
+    message.extend_from_slice(&flags::THIS_NODE.to_be_bytes());
+    message.extend_from_slice(&creation.to_be_bytes());
+    extend_with_name(&mut message, name);
+
+    write_packet(stream, &message)
+}
+
+/// Writes the version 6 `send_challenge` message
+fn write_challenge<W: Write>(
+    stream: &mut W,
+    name: &str,
+    creation: u32,
+    challenge: u32,
+) -> Result<(), Error> {
+    let mut message = vec![b'N'];
+    message.extend_from_slice(&flags::THIS_NODE.to_be_bytes());
+    message.extend_from_slice(&challenge.to_be_bytes());
+    message.extend_from_slice(&creation.to_be_bytes());
+    extend_with_name(&mut message, name);
+
+    write_packet(stream, &message)
+}
+
+fn extend_with_name(message: &mut Vec<u8>, name: &str) {
+    message.extend_from_slice(&(name.len() as u16).to_be_bytes());
+    message.extend_from_slice(name.as_bytes());
+}
+
+/// Reads the `send_name` message, either version 6 (`'N'`) or version 5 (`'n'`).  Version 5 only
+/// has the low 32 bits of the flags and no creation, so whether the initiating node has to send
+/// the rest in a complement is returned with the other node.
+fn read_name<R: Read>(stream: &mut R) -> Result<(Peer, bool), Error> {
+    let message = read_packet_with_any_tag(stream)?;
+
+    match message.first() {
+        Some(b'N') => {
+            let mut fields = Fields::new(&message[1..], "name");
+            let flags = fields.u64()?;
+            let creation = fields.u32()?;
+            let name = fields.name()?;
+
+            Ok((
+                Peer {
+                    name,
+                    flags,
+                    creation,
+                },
+                false,
+            ))
+        }
+        Some(b'n') => {
+            let mut fields = Fields::new(&message[1..], "name");
+
+            if fields.u16()? != OLD_VERSION {
+                return Err(Error::Protocol("name"));
+            }
+
+            let flags = fields.u32()? as u64;
+            let name = fields.rest()?;
+
+            Ok((
+                Peer {
+                    name,
+                    flags,
+                    creation: 0,
+                },
+                true,
+            ))
+        }
+        _ => Err(Error::Protocol("name")),
+    }
+}
+
+/// Reads the version 6 `send_challenge` message
+fn read_challenge<R: Read>(stream: &mut R) -> Result<(Peer, u32), Error> {
+    let message = read_packet(stream, b'N', "challenge")?;
+    let mut fields = Fields::new(&message, "challenge");
+    let flags = fields.u64()?;
+    let challenge = fields.u32()?;
+    let creation = fields.u32()?;
+    let name = fields.name()?;
+
+    Ok((
+        Peer {
+            name,
+            flags,
+            creation,
+        },
+        challenge,
+    ))
+}
+
+/// The big-endian fields of a handshake message, which are a `Protocol` error naming the
+/// `expected` message when the message is too short
+struct Fields<'a> {
+    bytes: &'a [u8],
+    expected: &'static str,
+}
+
+impl<'a> Fields<'a> {
+    fn new(bytes: &'a [u8], expected: &'static str) -> Self {
+        Fields { bytes, expected }
+    }
+
+    fn u16(&mut self) -> Result<u16, Error> {
+        let bytes = self.take(2)?;
+
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        let bytes = self.take(4)?;
+
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn u64(&mut self) -> Result<u64, Error> {
+        let high = self.u32()? as u64;
+        let low = self.u32()? as u64;
+
+        Ok((high << 32) | low)
+    }
+
+    /// A name with a 2-byte length, which has to end the message
+    fn name(&mut self) -> Result<String, Error> {
+        let len = self.u16()? as usize;
+
+        if self.bytes.len() != len {
+            return Err(Error::Protocol(self.expected));
+        }
+
+        self.rest()
+    }
+
+    /// The non-empty rest of the message as a name
+    fn rest(&mut self) -> Result<String, Error> {
+        if self.bytes.is_empty() {
+            return Err(Error::Protocol(self.expected));
+        }
+
+        let name = String::from_utf8_lossy(self.bytes).into_owned();
+        self.bytes = &[];
+
+        Ok(name)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.bytes.len() < len {
+            return Err(Error::Protocol(self.expected));
+        }
+
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+
+        Ok(taken)
+    }
+}
+
+/// Reads a packet, checking that it starts with `tag`, and returns the rest of it
+fn read_packet<R: Read>(stream: &mut R, tag: u8, expected: &'static str) -> Result<Vec<u8>, Error> {
+    let mut packet = read_packet_with_any_tag(stream)?;
+
+    if packet.first() == Some(&tag) {
+        packet.remove(0);
+
+        Ok(packet)
+    } else {
+        Err(Error::Protocol(expected))
+    }
+}
+
+fn read_packet_with_any_tag<R: Read>(stream: &mut R) -> Result<Vec<u8>, Error> {
+    let mut len = [0; 2];
+    stream.read_exact(&mut len)?;
+
+    let mut packet = vec![0; u16::from_be_bytes(len) as usize];
+    stream.read_exact(&mut packet)?;
+
+    Ok(packet)
+}
+
+fn write_packet<W: Write>(stream: &mut W, packet: &[u8]) -> Result<(), Error> {
+    stream.write_all(&(packet.len() as u16).to_be_bytes())?;
+    stream.write_all(packet)?;
+    stream.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::net::{TcpListener, TcpStream};
+    use std::thread::{self, JoinHandle};
+
+    #[test]
+    fn digest_is_md5_of_cookie_and_decimal_challenge() {
+        assert_eq!(
+            digest(3141592653, "lumencookie"),
+            [199, 13, 221, 13, 124, 63, 95, 186, 191, 131, 238, 80, 25, 18, 35, 98]
+        );
+    }
+
+    #[test]
+    fn with_same_cookie_both_nodes_learn_the_other() {
+        let (initiated, accepted) = handshake("cookie", "cookie", false);
+        let initiated = initiated.unwrap();
+        let accepted = accepted.unwrap();
+
+        assert_eq!(initiated.name, "acceptor@localhost");
+        assert_eq!(initiated.flags, flags::THIS_NODE);
+        assert_eq!(initiated.creation, 2);
+        assert_eq!(accepted.name, "initiator@localhost");
+        assert_eq!(accepted.flags, flags::THIS_NODE);
+        assert_eq!(accepted.creation, 1);
+    }
+
+    #[test]
+    fn with_version_5_name_the_initiator_completes_flags_and_creation() {
+        let (mut stream, acceptor) = acceptor("cookie");
+
+        let mut name = vec![b'n'];
+        name.extend_from_slice(&OLD_VERSION.to_be_bytes());
+        name.extend_from_slice(&(flags::THIS_NODE as u32).to_be_bytes());
+        name.extend_from_slice(b"initiator@localhost");
+        write_packet(&mut stream, &name).unwrap();
+
+        assert_eq!(read_packet(&mut stream, b's', "status").unwrap(), b"ok");
+
+        let (_, challenge) = read_challenge(&mut stream).unwrap();
+
+        let mut complement = vec![b'c'];
+        complement.extend_from_slice(&((flags::THIS_NODE >> 32) as u32).to_be_bytes());
+        complement.extend_from_slice(&3_u32.to_be_bytes());
+        write_packet(&mut stream, &complement).unwrap();
+
+        let mut reply = vec![b'r'];
+        reply.extend_from_slice(&0_u32.to_be_bytes());
+        reply.extend_from_slice(&digest(challenge, "cookie"));
+        write_packet(&mut stream, &reply).unwrap();
+
+        assert_eq!(
+            read_packet(&mut stream, b'a', "challenge ack").unwrap(),
+            digest(0, "cookie")
+        );
+
+        let accepted = acceptor.join().unwrap().unwrap();
+
+        assert_eq!(accepted.name, "initiator@localhost");
+        assert_eq!(accepted.flags, flags::THIS_NODE);
+        assert_eq!(accepted.creation, 3);
+    }
+
+    #[test]
+    fn without_handshake_23_refuses() {
+        let (mut stream, acceptor) = acceptor("cookie");
+
+        let mut name = vec![b'n'];
+        name.extend_from_slice(&OLD_VERSION.to_be_bytes());
+        name.extend_from_slice(&0_u32.to_be_bytes());
+        name.extend_from_slice(b"initiator@localhost");
+        write_packet(&mut stream, &name).unwrap();
+
+        assert_eq!(
+            read_packet(&mut stream, b's', "status").unwrap(),
+            b"not_allowed"
+        );
+
+        match acceptor.join().unwrap() {
+            Err(Error::Status(status)) => assert_eq!(status, "not_allowed"),
+            _ => panic!("connection was not refused"),
+        }
+    }
+
+    #[test]
+    fn with_different_cookie_errors() {
+        let (initiated, accepted) = handshake("cookie", "other cookie", false);
+
+        assert!(initiated.is_err());
+
+        match accepted {
+            Err(Error::Cookie) => (),
+            _ => panic!("cookie was accepted"),
+        }
+    }
+
+    #[test]
+    fn when_already_connected_refuses() {
+        let (initiated, _) = handshake("cookie", "cookie", true);
+
+        match initiated {
+            Err(Error::Status(status)) => assert_eq!(status, "nok"),
+            _ => panic!("connection was not refused"),
+        }
+    }
+
+    fn handshake(
+        initiator_cookie: &'static str,
+        acceptor_cookie: &'static str,
+        is_connected: bool,
+    ) -> (Result<Peer, Error>, Result<Peer, Error>) {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let acceptor = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            accept(
+                &mut stream,
+                "acceptor@localhost",
+                2,
+                acceptor_cookie,
+                |_| is_connected,
+            )
+        });
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let initiated = initiate(&mut stream, "initiator@localhost", 1, initiator_cookie);
+        // unblock the acceptor if the initiator gave up first
+        drop(stream);
+
+        (initiated, acceptor.join().unwrap())
+    }
+
+    /// An accepting node to hand-write the initiating node's messages to
+    fn acceptor(cookie: &'static str) -> (TcpStream, JoinHandle<Result<Peer, Error>>) {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let acceptor = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            accept(&mut stream, "acceptor@localhost", 2, cookie, |_| false)
+        });
+
+        (TcpStream::connect(addr).unwrap(), acceptor)
+    }
+}
@@ -0,0 +1,250 @@
+//! Links between local processes and processes on other nodes.
+//!
+//! Each node keeps its own half of a link: the other node is sent `LINK` and `UNLINK` to keep its
+//! half, and `EXIT` when the local process exits.  When the other node sends `EXIT`, or the
+//! connection to it is lost, the linked local process is sent an exit signal from the remote
+//! process, with the reason `noconnection` for a lost connection.
+//!
+//! Nodes that support it (`flags::UNLINK_ID`) are sent `UNLINK_ID` instead of `UNLINK`, and each
+//! `UNLINK_ID` they send is acknowledged with `UNLINK_ID_ACK`.  The local half of a link is removed
+//! as soon as the process unlinks, so the acknowledgements the other node sends back change
+//! nothing and are ignored.
+
+use core::ptr;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use hashbrown::{HashMap, HashSet};
+
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::signal::Exit;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, AsTerm, Atom, Pid, Term};
+use liblumen_alloc::erts::HeapFragment;
+
+use crate::process::send_exit_and_wake;
+use crate::registry::pid_to_process;
+
+use super::control::{self, RemotePid, EXIT, LINK, UNLINK, UNLINK_ID, UNLINK_ID_ACK};
+use super::{connect, connection, flags, Error};
+
+/// Links `process` to `remote`, failing if `remote`'s node can't be connected to
+pub fn link(process: &Process, remote: RemotePid) -> Result<(), Error> {
+    let connection = connect(remote.node.name())?;
+    let pid = process.pid();
+
+    if !insert(pid, remote) {
+        return Ok(());
+    }
+
+    let result = control::send_made(&connection, |heap| {
+        Ok(vec![
+            Term::make_smallint(LINK),
+            unsafe { pid.as_term() },
+            remote.to_term(heap)?,
+        ])
+    });
+
+    if result.is_err() {
+        remove(pid, remote);
+    }
+
+    result
+}
+
+/// Removes the link between `process` and `remote`, if any
+pub fn unlink(process: &Process, remote: RemotePid) {
+    let pid = process.pid();
+
+    if remove(pid, remote) {
+        if let Some(connection) = connection(remote.node.name()) {
+            let _ = control::send_made(&connection, |heap| {
+                if (connection.flags & flags::UNLINK_ID) == flags::UNLINK_ID {
+                    let id = UNLINK_ID_COUNTER.fetch_add(1, Ordering::SeqCst)
+                        & (Term::MAX_SMALLINT_VALUE as usize);
+
+                    Ok(vec![
+                        Term::make_smallint(UNLINK_ID),
+                        Term::make_smallint(id as isize),
+                        unsafe { pid.as_term() },
+                        remote.to_term(heap)?,
+                    ])
+                } else {
+                    Ok(vec![
+                        Term::make_smallint(UNLINK),
+                        unsafe { pid.as_term() },
+                        remote.to_term(heap)?,
+                    ])
+                }
+            });
+        }
+    }
+}
+
+/// Sends `EXIT` with `reason` to the remote processes linked to `process`, which has exited
+pub fn propagate_exit(process: &Process, reason: Term) {
+    let pid = process.pid();
+    let option_remotes = LINKS.lock().unwrap().remove(&pid);
+
+    if let Some(remotes) = option_remotes {
+        for remote in remotes {
+            if let Some(connection) = connection(remote.node.name()) {
+                let _ = control::send_made(&connection, |heap| {
+                    Ok(vec![
+                        Term::make_smallint(EXIT),
+                        unsafe { pid.as_term() },
+                        remote.to_term(heap)?,
+                        reason,
+                    ])
+                });
+            }
+        }
+    }
+}
+
+/// Sends an exit signal with `noconnection` from every remote process on `node` to the local
+/// processes linked to it
+pub fn node_down(node: Atom) {
+    let mut broken = Vec::new();
+
+    {
+        let mut links = LINKS.lock().unwrap();
+
+        for (pid, remotes) in links.iter_mut() {
+            remotes.retain(|remote| {
+                if remote.node.name() == node {
+                    broken.push((*pid, *remote));
+
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+
+        links.retain(|_, remotes| !remotes.is_empty());
+    }
+
+    for (pid, remote) in broken {
+        if let Some(process) = pid_to_process(&pid) {
+            let _ = signal(&process, remote, |from| {
+                Exit::linked(from, atom_unchecked("noconnection"))
+            });
+        }
+    }
+}
+
+/// `remote` linked to the local process with `pid`.  If there is no such process, `remote` is sent
+/// `EXIT` with `noproc`.
+pub(super) fn linked(remote: RemotePid, pid: Pid) {
+    if pid_to_process(&pid).is_some() {
+        insert(pid, remote);
+    } else if let Some(connection) = connection(remote.node.name()) {
+        let _ = control::send_made(&connection, |heap| {
+            Ok(vec![
+                Term::make_smallint(EXIT),
+                unsafe { pid.as_term() },
+                remote.to_term(heap)?,
+                atom_unchecked("noproc"),
+            ])
+        });
+    }
+}
+
+/// `remote` unlinked from the local process with `pid`
+pub(super) fn unlinked(remote: RemotePid, pid: Pid) {
+    remove(pid, remote);
+}
+
+/// `remote` unlinked from the local process with `pid` with `UNLINK_ID`, which is acknowledged
+/// with the same `id`
+pub(super) fn unlinked_with_id(id: Term, remote: RemotePid, pid: Pid) {
+    remove(pid, remote);
+
+    if let Some(connection) = connection(remote.node.name()) {
+        let _ = control::send_made(&connection, |heap| {
+            Ok(vec![
+                Term::make_smallint(UNLINK_ID_ACK),
+                id,
+                unsafe { pid.as_term() },
+                remote.to_term(heap)?,
+            ])
+        });
+    }
+}
+
+/// `remote`, which is linked to the local process with `pid`, exited with `reason`
+pub(super) fn exited(remote: RemotePid, pid: Pid, reason: Term) {
+    if remove(pid, remote) {
+        if let Some(process) = pid_to_process(&pid) {
+            let _ = signal(&process, remote, |from| Exit::linked(from, reason));
+        }
+    }
+}
+
+/// `remote` called `exit(Pid, reason)` for the local process with `pid`
+pub(super) fn exit2(remote: RemotePid, pid: Pid, reason: Term) {
+    if let Some(process) = pid_to_process(&pid) {
+        let _ = signal(&process, remote, |from| Exit::exit2(from, reason));
+    }
+}
+
+// Private
+
+fn insert(pid: Pid, remote: RemotePid) -> bool {
+    LINKS
+        .lock()
+        .unwrap()
+        .entry(pid)
+        .or_insert_with(Default::default)
+        .insert(remote)
+}
+
+fn remove(pid: Pid, remote: RemotePid) -> bool {
+    let mut links = LINKS.lock().unwrap();
+
+    match links.get_mut(&pid) {
+        Some(remotes) => {
+            let removed = remotes.remove(&remote);
+
+            if remotes.is_empty() {
+                links.remove(&pid);
+            }
+
+            removed
+        }
+        None => false,
+    }
+}
+
+/// Sends the exit signal `exit` makes with `remote` as its sender to `process`
+fn signal<E>(process: &Process, remote: RemotePid, exit: E) -> Result<(), Alloc>
+where
+    E: FnOnce(Term) -> Exit,
+{
+    let mut non_null_heap_fragment =
+        unsafe { HeapFragment::new_from_word_size(MAX_EXTERNAL_PID_NEED_IN_WORDS)? };
+    let heap_fragment = unsafe { non_null_heap_fragment.as_mut() };
+
+    // `send_exit_and_wake` copies `from`, so the heap fragment is only needed until it returns
+    let result = remote
+        .to_term(heap_fragment)
+        .and_then(|from| send_exit_and_wake(process, exit(from)));
+
+    unsafe { ptr::drop_in_place(non_null_heap_fragment.as_ptr()) };
+
+    result
+}
+
+/// More than the words an `ExternalPid` takes on a heap
+const MAX_EXTERNAL_PID_NEED_IN_WORDS: usize = 16;
+
+/// The id of the next `UNLINK_ID`, which only has to differ from those of the unlinks the other
+/// node hasn't acknowledged yet
+static UNLINK_ID_COUNTER: AtomicUsize = AtomicUsize::new(1);
+
+lazy_static! {
+    /// The remote processes linked to each local process
+    static ref LINKS: Mutex<HashMap<Pid, HashSet<RemotePid>>> = Default::default();
+}
@@ -0,0 +1,364 @@
+//! Monitors between local processes and processes on other nodes.
+//!
+//! A local process monitoring a remote process is kept by the monitor's reference, and the other
+//! node is sent `MONITOR_P` and `DEMONITOR_P`.  When the remote process exits, the other node sends
+//! `MONITOR_P_EXIT`, which is delivered as a `DOWN` message.  A remote process monitoring a local
+//! process is kept by the local pid, and the other node is sent `MONITOR_P_EXIT` when the local
+//! process exits.
+//!
+//! When the connection to a node is lost, local processes monitoring processes on that node are
+//! sent `DOWN` with `noconnection`, and the monitors by processes on that node are dropped.
+
+use std::convert::TryInto;
+use std::sync::Mutex;
+
+use hashbrown::HashMap;
+
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::{HeapAlloc, MonitorTable, Process, Tag};
+use liblumen_alloc::erts::term::{
+    atom_unchecked, AsTerm, Atom, Boxed, Pid, Reference, Term, Tuple, TypedTerm,
+};
+use liblumen_alloc::erts::HeapFragment;
+use liblumen_alloc::CloneToProcess;
+
+use crate::process::{alias, send_heap_message_and_wake};
+use crate::registry::{atom_to_process, pid_to_process};
+
+use super::control::{self, RemotePid, RemoteReference, DEMONITOR_P, MONITOR_P, MONITOR_P_EXIT};
+use super::{connect, connection, Error};
+
+/// A process on another node that a local process monitors
+#[derive(Clone, Copy, Debug)]
+pub enum Monitored {
+    Pid(RemotePid),
+    /// The process registered as `name` on `node`, whatever process that is when it is monitored
+    Name {
+        name: Atom,
+        node: Atom,
+    },
+}
+
+impl Monitored {
+    fn node(&self) -> Atom {
+        match self {
+            Monitored::Pid(remote) => remote.node.name(),
+            Monitored::Name { node, .. } => *node,
+        }
+    }
+
+    /// The process as it is in `MONITOR_P` and `DEMONITOR_P`
+    fn to_term<H: HeapAlloc>(&self, heap: &mut H) -> Result<Term, Alloc> {
+        match self {
+            Monitored::Pid(remote) => remote.to_term(heap),
+            Monitored::Name { name, .. } => Ok(unsafe { name.as_term() }),
+        }
+    }
+
+    /// The process as it is in `DOWN` messages
+    fn identifier<H: HeapAlloc>(&self, heap: &mut H) -> Result<Term, Alloc> {
+        match self {
+            Monitored::Pid(remote) => remote.to_term(heap),
+            Monitored::Name { name, node } => {
+                heap.tuple_from_slice(&[unsafe { name.as_term() }, unsafe { node.as_term() }])
+            }
+        }
+    }
+}
+
+/// Monitors `monitored` by `process`, returning the monitor's reference, or failing if the node of
+/// `monitored` can't be connected to.  `tag` is the first element of the `DOWN` message.
+pub fn monitor(process: &Process, monitored: Monitored, tag: Tag) -> Result<Term, Error> {
+    let connection = connect(monitored.node())?;
+    let pid = process.pid();
+    let reference_term = process.next_reference()?;
+    let reference: Boxed<Reference> = reference_term.try_into().unwrap();
+
+    insert_outgoing(pid, *reference, Outgoing { monitored, tag });
+
+    let result = control::send_made(&connection, |heap| {
+        Ok(vec![
+            Term::make_smallint(MONITOR_P),
+            unsafe { pid.as_term() },
+            monitored.to_term(heap)?,
+            reference_term,
+        ])
+    });
+
+    match result {
+        Ok(()) => Ok(reference_term),
+        Err(error) => {
+            remove_outgoing(pid, &reference);
+
+            Err(error)
+        }
+    }
+}
+
+/// Removes the monitor of a remote process by `process` with `reference`, returning whether there
+/// was one
+pub fn demonitor(process: &Process, reference: &Reference) -> bool {
+    let pid = process.pid();
+
+    match remove_outgoing(pid, reference) {
+        Some(Outgoing { monitored, .. }) => {
+            if let Some(connection) = connection(monitored.node()) {
+                let reference = *reference;
+
+                let _ = control::send_made(&connection, |heap| {
+                    Ok(vec![
+                        Term::make_smallint(DEMONITOR_P),
+                        unsafe { pid.as_term() },
+                        monitored.to_term(heap)?,
+                        reference.clone_to_heap(heap)?,
+                    ])
+                });
+            }
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Sends `MONITOR_P_EXIT` with `reason` for the remote monitors of `process`, which has exited, and
+/// removes the monitors of remote processes by `process`
+pub fn propagate_exit(process: &Process, reason: Term) {
+    let pid = process.pid();
+    let option_incoming = INCOMING.lock().unwrap().remove(&pid);
+
+    if let Some(incoming) = option_incoming {
+        for monitor in incoming {
+            let monitored = match monitor.monitored {
+                LocalMonitored::Pid => unsafe { pid.as_term() },
+                LocalMonitored::Name(name) => unsafe { name.as_term() },
+            };
+
+            send_exit(&monitor, monitored, reason);
+        }
+    }
+
+    let option_outgoing = OUTGOING.lock().unwrap().remove(&pid);
+
+    if let Some(outgoing) = option_outgoing {
+        for (reference, Outgoing { monitored, .. }) in outgoing.iter() {
+            if let Some(connection) = connection(monitored.node()) {
+                let _ = control::send_made(&connection, |heap| {
+                    Ok(vec![
+                        Term::make_smallint(DEMONITOR_P),
+                        unsafe { pid.as_term() },
+                        monitored.to_term(heap)?,
+                        reference.clone_to_heap(heap)?,
+                    ])
+                });
+            }
+        }
+    }
+}
+
+/// Sends `DOWN` with `noconnection` to the local processes monitoring processes on `node`, and
+/// drops the monitors by processes on `node`
+pub fn node_down(node: Atom) {
+    let mut down = Vec::new();
+
+    {
+        let mut outgoing = OUTGOING.lock().unwrap();
+
+        for (pid, monitors) in outgoing.iter_mut() {
+            let references: Vec<Reference> = monitors
+                .iter()
+                .filter(|(_, outgoing)| outgoing.monitored.node() == node)
+                .map(|(reference, _)| *reference)
+                .collect();
+
+            for reference in references {
+                let outgoing = monitors.remove(&reference).unwrap();
+
+                down.push((*pid, reference, outgoing));
+            }
+        }
+
+        outgoing.retain(|_, monitors| !monitors.is_empty());
+    }
+
+    for (pid, reference, outgoing) in down {
+        if let Some(process) = pid_to_process(&pid) {
+            let _ = send_down(
+                &process,
+                &reference,
+                &outgoing,
+                atom_unchecked("noconnection"),
+            );
+        }
+    }
+
+    let mut incoming = INCOMING.lock().unwrap();
+
+    for monitors in incoming.values_mut() {
+        monitors.retain(|monitor| monitor.monitoring.node.name() != node);
+    }
+
+    incoming.retain(|_, monitors| !monitors.is_empty());
+}
+
+/// `monitoring` monitored the local process `monitored`, which is a pid or registered name, with
+/// `reference`.  If there is no such process, `monitoring` is sent `MONITOR_P_EXIT` with `noproc`.
+pub(super) fn monitored(monitoring: RemotePid, monitored: Term, reference: RemoteReference) {
+    let (option_process, local_monitored) = match monitored.to_typed_term().unwrap() {
+        TypedTerm::Pid(pid) => (pid_to_process(&pid), LocalMonitored::Pid),
+        TypedTerm::Atom(name) => (atom_to_process(&name), LocalMonitored::Name(name)),
+        _ => return,
+    };
+    let monitor = Incoming {
+        monitoring,
+        monitored: local_monitored,
+        reference,
+    };
+
+    match option_process {
+        Some(process) => INCOMING
+            .lock()
+            .unwrap()
+            .entry(process.pid())
+            .or_insert_with(Default::default)
+            .push(monitor),
+        None => send_exit(&monitor, monitored, atom_unchecked("noproc")),
+    }
+}
+
+/// The monitor of the local process `monitored`, which is a pid or registered name, with
+/// `reference` was removed
+pub(super) fn demonitored(monitored: Term, reference: RemoteReference) {
+    let option_pid = match monitored.to_typed_term().unwrap() {
+        TypedTerm::Pid(pid) => Some(pid),
+        TypedTerm::Atom(name) => atom_to_process(&name).map(|process| process.pid()),
+        _ => None,
+    };
+
+    if let Some(pid) = option_pid {
+        let mut incoming = INCOMING.lock().unwrap();
+
+        if let Some(monitors) = incoming.get_mut(&pid) {
+            monitors.retain(|monitor| monitor.reference != reference);
+
+            if monitors.is_empty() {
+                incoming.remove(&pid);
+            }
+        }
+    }
+}
+
+/// The remote process monitored by the local process with `pid` with `reference` exited with
+/// `reason`
+pub(super) fn exited(pid: Pid, reference: &Reference, reason: Term) {
+    if let Some(outgoing) = remove_outgoing(pid, reference) {
+        if let Some(process) = pid_to_process(&pid) {
+            let _ = send_down(&process, reference, &outgoing, reason);
+        }
+    }
+}
+
+// Private
+
+/// A monitor of a remote process by a local process
+struct Outgoing {
+    monitored: Monitored,
+    tag: Tag,
+}
+
+/// A monitor of a local process by a remote process
+struct Incoming {
+    monitoring: RemotePid,
+    monitored: LocalMonitored,
+    reference: RemoteReference,
+}
+
+/// How a remote process monitors a local process, which is how the local process is sent in
+/// `MONITOR_P_EXIT`
+enum LocalMonitored {
+    Pid,
+    Name(Atom),
+}
+
+fn insert_outgoing(pid: Pid, reference: Reference, outgoing: Outgoing) {
+    OUTGOING
+        .lock()
+        .unwrap()
+        .entry(pid)
+        .or_insert_with(Default::default)
+        .insert(reference, outgoing);
+}
+
+fn remove_outgoing(pid: Pid, reference: &Reference) -> Option<Outgoing> {
+    let mut outgoing = OUTGOING.lock().unwrap();
+    let monitors = outgoing.get_mut(&pid)?;
+    let option_outgoing = monitors.remove(reference);
+
+    if monitors.is_empty() {
+        outgoing.remove(&pid);
+    }
+
+    option_outgoing
+}
+
+/// Sends `MONITOR_P_EXIT` with `reason` for `monitor` of the local process `monitored`, which is
+/// sent as the remote process monitored it, by pid or registered name
+fn send_exit(monitor: &Incoming, monitored: Term, reason: Term) {
+    if let Some(connection) = connection(monitor.monitoring.node.name()) {
+        let _ = control::send_made(&connection, |heap| {
+            Ok(vec![
+                Term::make_smallint(MONITOR_P_EXIT),
+                monitored,
+                monitor.monitoring.to_term(heap)?,
+                monitor.reference.to_term(heap)?,
+                reason,
+            ])
+        });
+    }
+}
+
+/// Sends `{Tag, reference, process, Identifier, reason}` for `outgoing` to `process`, which
+/// deactivates the alias of the monitor unless it is `explicit_unalias`
+fn send_down(
+    process: &Process,
+    reference: &Reference,
+    outgoing: &Outgoing,
+    reason: Term,
+) -> Result<(), Alloc> {
+    alias::demonitored(process.pid(), reference);
+
+    let tag = outgoing.tag.term();
+    let need_in_words = Tuple::need_in_words_from_len(5)
+        + tag.size_in_words()
+        + Reference::need_in_words()
+        + MAX_IDENTIFIER_NEED_IN_WORDS
+        + reason.size_in_words();
+    let mut non_null_heap_fragment = unsafe { HeapFragment::new_from_word_size(need_in_words)? };
+    let heap_fragment = unsafe { non_null_heap_fragment.as_mut() };
+
+    let heap_fragment_tag = tag.clone_to_heap(heap_fragment)?;
+    let reference_term = reference.clone_to_heap(heap_fragment)?;
+    let identifier = outgoing.monitored.identifier(heap_fragment)?;
+    let heap_fragment_reason = reason.clone_to_heap(heap_fragment)?;
+    let message = heap_fragment.tuple_from_slice(&[
+        heap_fragment_tag,
+        reference_term,
+        atom_unchecked("process"),
+        identifier,
+        heap_fragment_reason,
+    ])?;
+
+    send_heap_message_and_wake(process, non_null_heap_fragment, message);
+
+    Ok(())
+}
+
+/// More than the words an `ExternalPid` or `{Name, Node}` takes on a heap
+const MAX_IDENTIFIER_NEED_IN_WORDS: usize = 16;
+
+lazy_static! {
+    /// The monitors of remote processes by each local process
+    static ref OUTGOING: Mutex<HashMap<Pid, MonitorTable<Outgoing>>> = Default::default();
+    /// The monitors of each local process by remote processes
+    static ref INCOMING: Mutex<HashMap<Pid, Vec<Incoming>>> = Default::default();
+}
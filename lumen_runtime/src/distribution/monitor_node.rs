@@ -0,0 +1,94 @@
+//! Monitors of whole nodes, as `erlang:monitor_node/2` makes.
+//!
+//! A process monitoring a node is sent `{nodedown, Node}` when the connection to the node closes,
+//! or, when there was no connection yet, when connecting to it fails.  As with BEAM, monitoring a
+//! node connects to it, in the background.  Each monitor is separate, so a process monitoring a
+//! node twice is sent `{nodedown, Node}` twice, unless it removes one monitor with
+//! `monitor_node(Node, false)`.
+
+use std::sync::{Arc, Mutex, Weak};
+
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::{HeapAlloc, Process};
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Tuple};
+use liblumen_alloc::erts::HeapFragment;
+
+use crate::process::send_heap_message_and_wake;
+
+use super::connect_in_background;
+
+/// Monitors `node` for `process` when `flag` is `true`, and otherwise removes one of its monitors
+/// of `node`.
+pub fn monitor_node(process: &Arc<Process>, node: Atom, flag: bool) {
+    let mut monitors = MONITORS.lock().unwrap();
+
+    if flag {
+        monitors.push(Monitor {
+            process: Arc::downgrade(process),
+            node,
+        });
+        drop(monitors);
+
+        connect_in_background(node, move |result| {
+            if result.is_err() {
+                node_down(node);
+            }
+        });
+    } else if let Some(index) = monitors.iter().position(|monitor| {
+        monitor.node == node
+            && monitor
+                .process
+                .upgrade()
+                .map_or(false, |monitoring| Arc::ptr_eq(&monitoring, process))
+    }) {
+        monitors.remove(index);
+    }
+}
+
+/// Sends `{nodedown, Node}` to the processes monitoring `node`, whose monitors are removed
+pub fn node_down(node: Atom) {
+    let mut down = Vec::new();
+
+    {
+        let mut monitors = MONITORS.lock().unwrap();
+
+        // the monitors of processes that have exited are dropped
+        monitors.retain(|monitor| match monitor.process.upgrade() {
+            Some(process) if monitor.node == node => {
+                down.push(process);
+
+                false
+            }
+            Some(_) => true,
+            None => false,
+        });
+    }
+
+    for process in down {
+        // the message is dropped if there isn't memory for it
+        let _ = send_nodedown(&process, node);
+    }
+}
+
+// Private
+
+struct Monitor {
+    process: Weak<Process>,
+    node: Atom,
+}
+
+fn send_nodedown(process: &Process, node: Atom) -> Result<(), Alloc> {
+    let mut non_null_heap_fragment =
+        unsafe { HeapFragment::new_from_word_size(Tuple::need_in_words_from_len(2))? };
+    let heap_fragment = unsafe { non_null_heap_fragment.as_mut() };
+    let data = heap_fragment
+        .tuple_from_slice(&[atom_unchecked("nodedown"), atom_unchecked(node.name())])?;
+
+    send_heap_message_and_wake(process, non_null_heap_fragment, data);
+
+    Ok(())
+}
+
+lazy_static! {
+    static ref MONITORS: Mutex<Vec<Monitor>> = Default::default();
+}
@@ -0,0 +1,295 @@
+//! Subscriptions of processes to other nodes connecting and disconnecting, as
+//! `net_kernel:monitor_nodes/1,2` makes.
+//!
+//! A process subscribed with no options is sent `{nodeup, Node}` and `{nodedown, Node}`.  With
+//! options, it is sent `{nodeup, Node, InfoList}` and `{nodedown, Node, InfoList}`, where
+//! `InfoList` has `{nodedown_reason, Reason}` for `nodedown_reason` and `{node_type, visible}` for
+//! `{node_type, Type}`.  Each subscription is separate, so a process subscribed twice is sent
+//! every message twice, until it unsubscribes with the same options.
+//!
+//! All connections are to visible nodes, so subscriptions with `{node_type, hidden}` are never
+//! sent anything.
+
+use core::convert::{TryFrom, TryInto};
+use core::mem;
+
+use std::sync::{Arc, Mutex, Weak};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception::runtime;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::{HeapAlloc, Process};
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Cons, Term, Tuple, TypedTerm};
+use liblumen_alloc::erts::HeapFragment;
+
+use crate::process::send_heap_message_and_wake;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NodeType {
+    Visible,
+    Hidden,
+    All,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Options {
+    pub nodedown_reason: bool,
+    pub node_type: Option<NodeType>,
+}
+
+impl Options {
+    fn put_option_term(&mut self, option: Term) -> Result<&Options, runtime::Exception> {
+        match option.to_typed_term().unwrap() {
+            TypedTerm::Atom(atom) if atom.name() == "nodedown_reason" => {
+                self.nodedown_reason = true;
+
+                Ok(self)
+            }
+            TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+                TypedTerm::Tuple(tuple) if tuple.len() == 2 => {
+                    let tag: Atom = tuple[0].try_into().map_err(|_| badarg!())?;
+                    let value: Atom = tuple[1].try_into().map_err(|_| badarg!())?;
+
+                    let node_type = match (tag.name(), value.name()) {
+                        ("node_type", "visible") => NodeType::Visible,
+                        ("node_type", "hidden") => NodeType::Hidden,
+                        ("node_type", "all") => NodeType::All,
+                        _ => return Err(badarg!()),
+                    };
+                    self.node_type = Some(node_type);
+
+                    Ok(self)
+                }
+                _ => Err(badarg!()),
+            },
+            _ => Err(badarg!()),
+        }
+    }
+
+    fn includes_visible(&self) -> bool {
+        self.node_type != Some(NodeType::Hidden)
+    }
+}
+
+impl TryFrom<Term> for Options {
+    type Error = runtime::Exception;
+
+    fn try_from(term: Term) -> Result<Options, Self::Error> {
+        let mut options: Options = Default::default();
+        let mut options_term = term;
+
+        loop {
+            match options_term.to_typed_term().unwrap() {
+                TypedTerm::Nil => return Ok(options),
+                TypedTerm::List(cons) => {
+                    options.put_option_term(cons.head)?;
+                    options_term = cons.tail;
+
+                    continue;
+                }
+                _ => return Err(badarg!()),
+            }
+        }
+    }
+}
+
+/// Subscribes `process` when `flag` is `true`, and otherwise removes one of its subscriptions with
+/// the same `options`.
+pub fn monitor_nodes(process: &Arc<Process>, flag: bool, options: Options) {
+    let mut subscriptions = SUBSCRIPTIONS.lock().unwrap();
+
+    if flag {
+        subscriptions.push(Subscription {
+            process: Arc::downgrade(process),
+            options,
+        });
+    } else if let Some(index) = subscriptions.iter().position(|subscription| {
+        subscription.options == options
+            && subscription
+                .process
+                .upgrade()
+                .map_or(false, |subscribed| Arc::ptr_eq(&subscribed, process))
+    }) {
+        subscriptions.remove(index);
+    }
+}
+
+/// Tells subscribers that `node` connected
+pub fn node_up(node: Atom) {
+    notify(atom_unchecked("nodeup"), node, None);
+}
+
+/// Tells subscribers that `node` disconnected for `reason`, such as `connection_closed` or
+/// `net_tick_timeout`
+pub fn node_down(node: Atom, reason: &str) {
+    notify(
+        atom_unchecked("nodedown"),
+        node,
+        Some(atom_unchecked(reason)),
+    );
+}
+
+// Private
+
+struct Subscription {
+    process: Weak<Process>,
+    options: Options,
+}
+
+fn notify(tag: Term, node: Atom, nodedown_reason: Option<Term>) {
+    let mut subscriptions = SUBSCRIPTIONS.lock().unwrap();
+
+    // the subscriptions of processes that have exited are dropped
+    subscriptions.retain(|subscription| subscription.process.upgrade().is_some());
+
+    for subscription in subscriptions
+        .iter()
+        .filter(|subscription| subscription.options.includes_visible())
+    {
+        if let Some(process) = subscription.process.upgrade() {
+            // the message is dropped if there isn't memory for it
+            let _ = send(&process, tag, node, nodedown_reason, subscription.options);
+        }
+    }
+}
+
+fn send(
+    process: &Process,
+    tag: Term,
+    node: Atom,
+    nodedown_reason: Option<Term>,
+    options: Options,
+) -> Result<(), Alloc> {
+    let cons_need_in_words = mem::size_of::<Cons>() / mem::size_of::<Term>();
+    let info_need_in_words = 2 * (cons_need_in_words + Tuple::need_in_words_from_len(2));
+    let need_in_words = Tuple::need_in_words_from_len(3) + info_need_in_words;
+    let mut non_null_heap_fragment = unsafe { HeapFragment::new_from_word_size(need_in_words)? };
+    let heap_fragment = unsafe { non_null_heap_fragment.as_mut() };
+
+    let node_term = atom_unchecked(node.name());
+
+    let data = if options == Default::default() {
+        heap_fragment.tuple_from_slice(&[tag, node_term])?
+    } else {
+        let mut info = Vec::new();
+
+        if let (true, Some(reason)) = (options.nodedown_reason, nodedown_reason) {
+            info.push(
+                heap_fragment.tuple_from_slice(&[atom_unchecked("nodedown_reason"), reason])?,
+            );
+        }
+
+        if options.node_type.is_some() {
+            info.push(
+                heap_fragment
+                    .tuple_from_slice(&[atom_unchecked("node_type"), atom_unchecked("visible")])?,
+            );
+        }
+
+        let info_list = heap_fragment.list_from_slice(&info)?;
+
+        heap_fragment.tuple_from_slice(&[tag, node_term, info_list])?
+    };
+
+    send_heap_message_and_wake(process, non_null_heap_fragment, data);
+
+    Ok(())
+}
+
+lazy_static! {
+    static ref SUBSCRIPTIONS: Mutex<Vec<Subscription>> = Default::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::scheduler::with_process_arc;
+    use crate::test::receive_message;
+
+    // Subscribers are sent about every node, so the options are checked in one test, as separate
+    // tests running in parallel would be sent about each other's nodes
+    #[test]
+    fn sends_messages_for_options() {
+        with_process_arc(|arc_process| {
+            let node = Atom::try_from_str("monitor_nodes@host").unwrap();
+            let node_term = atom_unchecked(node.name());
+
+            monitor_nodes(&arc_process, true, Default::default());
+            node_up(node);
+            node_down(node, "connection_closed");
+            monitor_nodes(&arc_process, false, Default::default());
+            node_up(node);
+
+            assert_eq!(
+                receive_message(&arc_process),
+                Some(
+                    arc_process
+                        .tuple_from_slice(&[atom_unchecked("nodeup"), node_term])
+                        .unwrap()
+                )
+            );
+            assert_eq!(
+                receive_message(&arc_process),
+                Some(
+                    arc_process
+                        .tuple_from_slice(&[atom_unchecked("nodedown"), node_term])
+                        .unwrap()
+                )
+            );
+            assert_eq!(receive_message(&arc_process), None);
+
+            let options_term = arc_process
+                .list_from_slice(&[atom_unchecked("nodedown_reason")])
+                .unwrap();
+            let options: Options = options_term.try_into().unwrap();
+
+            monitor_nodes(&arc_process, true, options);
+            node_down(node, "net_tick_timeout");
+            monitor_nodes(&arc_process, false, options);
+
+            let reason = arc_process
+                .tuple_from_slice(&[
+                    atom_unchecked("nodedown_reason"),
+                    atom_unchecked("net_tick_timeout"),
+                ])
+                .unwrap();
+
+            assert_eq!(
+                receive_message(&arc_process),
+                Some(
+                    arc_process
+                        .tuple_from_slice(&[
+                            atom_unchecked("nodedown"),
+                            node_term,
+                            arc_process.list_from_slice(&[reason]).unwrap()
+                        ])
+                        .unwrap()
+                )
+            );
+
+            let options = Options {
+                nodedown_reason: false,
+                node_type: Some(NodeType::Hidden),
+            };
+
+            monitor_nodes(&arc_process, true, options);
+            node_up(node);
+            monitor_nodes(&arc_process, false, options);
+
+            assert_eq!(receive_message(&arc_process), None);
+        });
+    }
+
+    #[test]
+    fn with_invalid_option_errors() {
+        with_process_arc(|arc_process| {
+            let options_term = arc_process
+                .list_from_slice(&[atom_unchecked("invalid")])
+                .unwrap();
+            let result: Result<Options, _> = options_term.try_into();
+
+            assert!(result.is_err());
+        });
+    }
+}
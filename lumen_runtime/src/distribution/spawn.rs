@@ -0,0 +1,55 @@
+//! Spawning processes on other nodes, as `spawn/4` and `spawn_link/4` do.
+//!
+//! This node doesn't tell other nodes it supports `SPAWN_REQUEST` in the handshake, so processes
+//! are spawned the way nodes did before `SPAWN_REQUEST`: the spawning process calls `net_kernel`
+//! on the other node, as `gen_server:call({net_kernel, Node}, {spawn, Module, Function, Arguments,
+//! GroupLeader})` does, and `net_kernel` replies with `{Reference, Pid}`.  The spawning process
+//! has to wait for the reply itself, such as in `receive`.
+//!
+//! Connecting takes as long as EPMD and the other node take to answer, so the request is sent from
+//! another thread once connected, and a failed connection is not returned.  The spawning process
+//! monitors the node with `monitor_node` to learn that the node is unreachable instead.
+//!
+//! Processes here have no group leader, so the spawning process is sent as the group leader of the
+//! spawned process.  For `spawn_link`, the spawned process links to the spawning process over the
+//! connection before `net_kernel` replies.
+
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Term};
+
+use super::{connect_in_background, control, Error};
+
+/// Asks `node` to spawn a process that calls `module:function` with `arguments`, linked to
+/// `process` if `link`.  `node` replies to `process` with `{reference, Pid}`, unless it can't be
+/// connected to.
+pub fn request(
+    process: &Process,
+    node: Atom,
+    module: Term,
+    function: Term,
+    arguments: Term,
+    link: bool,
+    reference: Term,
+) -> Result<(), Error> {
+    let self_term = process.pid_term();
+    let tag = if link { "spawn_link" } else { "spawn" };
+    let from = process.tuple_from_slice(&[self_term, reference])?;
+    let request =
+        process.tuple_from_slice(&[atom_unchecked(tag), module, function, arguments, self_term])?;
+    let message = process.tuple_from_slice(&[atom_unchecked("$gen_call"), from, request])?;
+
+    let packet = control::reg_send_packet(
+        self_term,
+        Atom::try_from_str("net_kernel").unwrap(),
+        message,
+    )?;
+
+    connect_in_background(node, move |result| {
+        if let Ok(connection) = result {
+            // a failed send closes the connection, which sends `{nodedown, Node}` to monitors
+            let _ = connection.send(&packet);
+        }
+    });
+
+    Ok(())
+}
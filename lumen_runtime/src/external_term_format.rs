@@ -0,0 +1,851 @@
+//! Encoding and decoding of terms in the external term format, which is how terms are sent
+//! between nodes.
+//!
+//! The bytes are read and written by `liblumen_beam`'s codec; this module converts between its
+//! `etf::Term`s and the terms on a process's heap.
+//!
+//! Pids, ports and references are encoded with the name and creation of their node, so that
+//! local identifiers can be told apart from those of other nodes, and other incarnations of this
+//! node, when they are decoded.  Decoded identifiers of other nodes become `ExternalPid`s,
+//! `ExternalPort`s and `ExternalReference`s.
+//!
+//! Closures are encoded as funs, but funs can't be decoded, as a fun's module, index and uniq
+//! don't lead to code this node can call.
+
+use core::convert::{TryFrom, TryInto};
+use core::ptr::NonNull;
+
+use miniz_oxide::deflate;
+
+use num_bigint::{BigInt, Sign};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception::runtime;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::alloc::heap_alloc::MakePidError;
+use liblumen_alloc::erts::process::HeapAlloc;
+use liblumen_alloc::erts::scheduler::ID;
+use liblumen_alloc::erts::term::{
+    make_pid, AsTerm, Atom, Closure, ExternalReference, Integer, Term, TypedTerm, MAX_EXTRA_IDS,
+};
+use liblumen_alloc::erts::{HeapFragment, Node, Process};
+use liblumen_alloc::CloneToProcess;
+
+use liblumen_beam::serialization::etf;
+
+use crate::digest::Md5;
+use crate::node;
+
+const VERSION: u8 = 131;
+const COMPRESSED: u8 = 80;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The term, such as a bitstring that is not a binary, can't be encoded, or the bytes encode a
+    /// term this node can't represent, such as a fun
+    Unsupported,
+    /// The bytes are not a term in the external term format
+    Invalid,
+    Alloc(Alloc),
+}
+
+impl From<Alloc> for Error {
+    fn from(alloc: Alloc) -> Self {
+        Error::Alloc(alloc)
+    }
+}
+
+impl From<MakePidError> for Error {
+    fn from(make_pid_error: MakePidError) -> Self {
+        match make_pid_error {
+            MakePidError::Number | MakePidError::Serial => Error::Invalid,
+            MakePidError::Alloc(alloc) => Error::Alloc(alloc),
+        }
+    }
+}
+
+/// The options of `term_to_binary/2`
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    /// The zlib level, `0..9`, to compress the encoding with.  `0` is no compression.
+    pub compressed: Option<u8>,
+    /// `0` encodes floats as text and `1` as their bits.  `2` also encodes atoms as UTF-8 when
+    /// they could be encoded as Latin-1.
+    pub minor_version: u8,
+}
+
+impl Options {
+    fn put_option_term(&mut self, option: Term) -> Result<&Options, runtime::Exception> {
+        match option.to_typed_term().unwrap() {
+            TypedTerm::Atom(atom) if atom.name() == "compressed" => {
+                self.compressed = Some(DEFAULT_COMPRESSION_LEVEL);
+
+                Ok(self)
+            }
+            TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+                TypedTerm::Tuple(tuple) if tuple.len() == 2 => {
+                    let name: Atom = tuple[0].try_into().map_err(|_| badarg!())?;
+                    let value: usize = tuple[1].try_into().map_err(|_| badarg!())?;
+
+                    match name.name() {
+                        "compressed" if value <= 9 => {
+                            self.compressed = Some(value as u8);
+
+                            Ok(self)
+                        }
+                        "minor_version" if value <= 2 => {
+                            self.minor_version = value as u8;
+
+                            Ok(self)
+                        }
+                        _ => Err(badarg!()),
+                    }
+                }
+                _ => Err(badarg!()),
+            },
+            _ => Err(badarg!()),
+        }
+    }
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            compressed: None,
+            minor_version: 1,
+        }
+    }
+}
+
+impl TryFrom<Term> for Options {
+    type Error = runtime::Exception;
+
+    fn try_from(term: Term) -> Result<Options, Self::Error> {
+        let mut options: Options = Default::default();
+        let mut options_term = term;
+
+        loop {
+            match options_term.to_typed_term().unwrap() {
+                TypedTerm::Nil => return Ok(options),
+                TypedTerm::List(cons) => {
+                    options.put_option_term(cons.head)?;
+                    options_term = cons.tail;
+
+                    continue;
+                }
+                _ => return Err(badarg!()),
+            }
+        }
+    }
+}
+
+/// Encodes `term` with the version prefix and UTF-8 atoms, as distribution does
+pub fn encode(term: Term) -> Result<Vec<u8>, Error> {
+    encode_etf(&to_etf(term)?, 2)
+}
+
+/// Encodes `term` with the version prefix, as `term_to_binary/2` does with `options`
+pub fn encode_with_options(term: Term, options: Options) -> Result<Vec<u8>, Error> {
+    let encoded = encode_etf(&to_etf(term)?, options.minor_version)?;
+
+    match options.compressed {
+        Some(level) if 0 < level => Ok(compress(encoded, level)),
+        _ => Ok(encoded),
+    }
+}
+
+/// Encodes a tuple of `elements` with the version prefix, without the tuple having to be on a
+/// heap, as distribution control messages are
+pub fn encode_tuple(elements: &[Term]) -> Result<Vec<u8>, Error> {
+    let etf_elements = elements
+        .iter()
+        .map(|element| to_etf(*element))
+        .collect::<Result<Vec<etf::Term>, Error>>()?;
+
+    encode_etf(&etf::Tuple::from(etf_elements).into(), 2)
+}
+
+/// Decodes the term encoded in `bytes`, which must start with the version prefix and contain
+/// nothing after the term
+pub fn decode(bytes: &[u8], process: &Process) -> Result<Term, Error> {
+    let (term, rest) = decode_prefix(bytes, &mut *process.acquire_heap())?;
+
+    if rest.is_empty() {
+        Ok(term)
+    } else {
+        Err(Error::Invalid)
+    }
+}
+
+/// Decodes the term encoded at the start of `bytes` into `heap`, returning it with the bytes
+/// after it, such as the message that follows the control message in a distribution packet
+pub fn decode_prefix<'a, H: HeapAlloc>(
+    bytes: &'a [u8],
+    heap: &mut H,
+) -> Result<(Term, &'a [u8]), Error> {
+    let (etf_term, rest) = etf::Term::decode_prefix(bytes).map_err(|_| Error::Invalid)?;
+    let term = from_etf(&etf_term, heap)?;
+
+    Ok((term, rest))
+}
+
+/// Decodes the term encoded at the start of `bytes` into a new `HeapFragment`, so that it can be
+/// sent to a process from another thread
+pub fn decode_prefix_to_heap_fragment(
+    bytes: &[u8],
+) -> Result<(Term, NonNull<HeapFragment>, &[u8]), Error> {
+    let (etf_term, rest) = etf::Term::decode_prefix(bytes).map_err(|_| Error::Invalid)?;
+    // Most terms take at most 2 words for every byte that encodes them, such as a string, which
+    // takes a cons cell for each byte, but compressed terms can take more, so the fragment grows
+    // until the term fits
+    let mut need_in_words = 2 * bytes.len();
+
+    loop {
+        let mut non_null_heap_fragment =
+            unsafe { HeapFragment::new_from_word_size(need_in_words)? };
+        let heap_fragment = unsafe { non_null_heap_fragment.as_mut() };
+
+        match from_etf(&etf_term, heap_fragment) {
+            Ok(term) => return Ok((term, non_null_heap_fragment, rest)),
+            Err(error) => {
+                unsafe { core::ptr::drop_in_place(non_null_heap_fragment.as_ptr()) };
+
+                match error {
+                    Error::Alloc(_) => need_in_words *= 2,
+                    _ => return Err(error),
+                }
+            }
+        }
+    }
+}
+
+// Private
+
+const DEFAULT_COMPRESSION_LEVEL: u8 = 6;
+
+/// The compressed form of `encoded`, unless compressing doesn't make it any smaller, in which case
+/// BEAM doesn't compress it either.
+fn compress(encoded: Vec<u8>, level: u8) -> Vec<u8> {
+    // without the version prefix
+    let uncompressed = &encoded[1..];
+    let mut compressed = vec![VERSION, COMPRESSED];
+    compressed.extend_from_slice(&(uncompressed.len() as u32).to_be_bytes());
+    compressed.extend_from_slice(&deflate::compress_to_vec_zlib(uncompressed, level));
+
+    if compressed.len() < encoded.len() {
+        compressed
+    } else {
+        encoded
+    }
+}
+
+fn encode_etf(etf_term: &etf::Term, minor_version: u8) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::new();
+
+    etf_term
+        .encode_with_options(&mut bytes, etf::EncodeOptions { minor_version })
+        .map_err(|_| Error::Unsupported)?;
+
+    Ok(bytes)
+}
+
+/// Converts `term` to `liblumen_beam`'s representation, which `encode` writes out
+pub fn to_etf(term: Term) -> Result<etf::Term, Error> {
+    match term.to_typed_term().unwrap() {
+        TypedTerm::Boxed(boxed) => typed_to_etf(term, boxed.to_typed_term().unwrap()),
+        typed_term => typed_to_etf(term, typed_term),
+    }
+}
+
+fn typed_to_etf(term: Term, typed_term: TypedTerm) -> Result<etf::Term, Error> {
+    let etf_term: etf::Term = match typed_term {
+        TypedTerm::Atom(atom) => atom_to_etf(atom).into(),
+        TypedTerm::SmallInteger(small_integer) => {
+            let i: isize = small_integer.into();
+
+            if (i32::min_value() as isize) <= i && i <= (i32::max_value() as isize) {
+                etf::FixInteger::from(i as i32).into()
+            } else {
+                etf::BigInteger::from(i).into()
+            }
+        }
+        TypedTerm::BigInteger(big_integer) => {
+            let big_int: &BigInt = big_integer.as_ref().into();
+            let (sign, digits) = big_int.to_bytes_le();
+
+            etf::BigInteger::from_bytes_le(sign == Sign::Minus, &digits).into()
+        }
+        TypedTerm::Float(float) => {
+            let f: f64 = float.into();
+
+            etf::Float::from(f).into()
+        }
+        TypedTerm::Nil => etf::List::nil().into(),
+        TypedTerm::List(cons) => {
+            let mut elements = vec![to_etf(cons.head)?];
+            let mut tail = cons.tail;
+
+            while let TypedTerm::List(cons) = tail.to_typed_term().unwrap() {
+                elements.push(to_etf(cons.head)?);
+                tail = cons.tail;
+            }
+
+            if tail == Term::NIL {
+                etf::List::from(elements).into()
+            } else {
+                etf::ImproperList::from((elements, to_etf(tail)?)).into()
+            }
+        }
+        TypedTerm::Tuple(tuple) => {
+            let elements = tuple
+                .iter()
+                .map(to_etf)
+                .collect::<Result<Vec<etf::Term>, Error>>()?;
+
+            etf::Tuple::from(elements).into()
+        }
+        TypedTerm::Map(_) => {
+            let mut entries = Vec::new();
+
+            for (key, value) in term.map_iter().unwrap() {
+                entries.push((to_etf(key)?, to_etf(value)?));
+            }
+
+            etf::Map::from(entries).into()
+        }
+        TypedTerm::HeapBinary(_) | TypedTerm::ProcBin(_) | TypedTerm::SubBinary(_) => {
+            let binary_bytes: Vec<u8> = term.try_into().map_err(|_| Error::Unsupported)?;
+
+            etf::Binary::from(binary_bytes).into()
+        }
+        TypedTerm::Closure(closure) => closure_to_etf(&closure)?,
+        TypedTerm::Pid(pid) => {
+            pid_to_etf(node::name(), node::creation(), pid.number(), pid.serial())
+        }
+        TypedTerm::ExternalPid(external_pid) => {
+            let node = external_pid.node();
+            let pid = external_pid.pid();
+
+            pid_to_etf(node.name(), node.creation(), pid.number(), pid.serial())
+        }
+        TypedTerm::Port(port) => port_to_etf(node::name(), node::creation(), port.number()),
+        TypedTerm::ExternalPort(external_port) => {
+            let node = external_port.node();
+
+            port_to_etf(node.name(), node.creation(), external_port.port().number())
+        }
+        TypedTerm::Reference(reference) => reference_to_etf(
+            node::name(),
+            node::creation(),
+            reference.scheduler_id(),
+            reference.number(),
+            &[],
+        ),
+        TypedTerm::ExternalReference(external_reference) => {
+            let node = external_reference.node();
+            let reference = external_reference.reference();
+
+            reference_to_etf(
+                node.name(),
+                node.creation(),
+                reference.scheduler_id(),
+                reference.number(),
+                external_reference.extra_ids(),
+            )
+        }
+        _ => return Err(Error::Unsupported),
+    };
+
+    Ok(etf_term)
+}
+
+fn atom_to_etf(atom: Atom) -> etf::Atom {
+    etf::Atom::from(atom.name())
+}
+
+/// A closure without an environment only needs its `module:function/arity` to be called, so it is
+/// encoded as an export fun.  Other closures are encoded as new funs whose free variables are their
+/// environment.  This node has no fun table, so the index is `0` and the uniq is the MD5 of the
+/// `module:function/arity`.
+fn closure_to_etf(closure: &Closure) -> Result<etf::Term, Error> {
+    let module_function_arity = closure.module_function_arity();
+    let module = atom_to_etf(module_function_arity.module);
+
+    if closure.env_len() == 0 {
+        return Ok(etf::ExternalFun {
+            module,
+            function: atom_to_etf(module_function_arity.function),
+            arity: module_function_arity.arity,
+        }
+        .into());
+    }
+
+    let pid = match to_etf(closure.creator())? {
+        etf::Term::Pid(pid) => pid,
+        _ => return Err(Error::Unsupported),
+    };
+    let free_vars = closure
+        .env_slice()
+        .iter()
+        .map(|term| to_etf(*term))
+        .collect::<Result<Vec<etf::Term>, Error>>()?;
+    let uniq = Md5::digest(
+        format!(
+            "{}:{}/{}",
+            module_function_arity.module.name(),
+            module_function_arity.function.name(),
+            module_function_arity.arity
+        )
+        .as_bytes(),
+    );
+    // BEAM's old uniq is a 27-bit hash
+    let old_uniq = (u32::from_be_bytes([uniq[0], uniq[1], uniq[2], uniq[3]]) & 0x07FF_FFFF) as i32;
+
+    Ok(etf::InternalFun::New {
+        module,
+        arity: closure.arity(),
+        pid,
+        free_vars,
+        index: 0,
+        uniq,
+        old_index: 0,
+        old_uniq,
+    }
+    .into())
+}
+
+fn pid_to_etf(node_name: Atom, creation: u32, number: usize, serial: usize) -> etf::Term {
+    etf::Pid::new(
+        atom_to_etf(node_name),
+        number as u32,
+        serial as u32,
+        creation,
+    )
+    .into()
+}
+
+fn port_to_etf(node_name: Atom, creation: u32, number: usize) -> etf::Term {
+    etf::Port {
+        node: atom_to_etf(node_name),
+        id: number as u64,
+        creation,
+    }
+    .into()
+}
+
+/// References are encoded as the low and high halves of their number followed by the scheduler
+/// that made them, and then any ids beyond those that a reference from another node had
+fn reference_to_etf(
+    node_name: Atom,
+    creation: u32,
+    scheduler_id: ID,
+    number: u64,
+    extra_ids: &[u32],
+) -> etf::Term {
+    let mut id = vec![
+        number as u32,
+        (number >> 32) as u32,
+        scheduler_id.as_usize() as u32,
+    ];
+    id.extend_from_slice(extra_ids);
+
+    etf::Reference {
+        node: atom_to_etf(node_name),
+        id,
+        creation,
+    }
+    .into()
+}
+
+fn from_etf<H: HeapAlloc>(etf_term: &etf::Term, heap: &mut H) -> Result<Term, Error> {
+    match etf_term {
+        etf::Term::Atom(etf_atom) => atom_from_etf(etf_atom).map(|atom| unsafe { atom.as_term() }),
+        etf::Term::FixInteger(fix_integer) => heap.integer(fix_integer.value).map_err(From::from),
+        etf::Term::BigInteger(big_integer) => {
+            let (negative, digits) = big_integer.to_bytes_le();
+            let sign = if negative { Sign::Minus } else { Sign::Plus };
+            let integer: Integer = BigInt::from_bytes_le(sign, &digits).into();
+
+            heap.integer(integer).map_err(From::from)
+        }
+        etf::Term::Float(float) => heap.float(float.value).map_err(From::from),
+        etf::Term::List(list) => {
+            let elements = terms_from_etf(&list.elements, heap)?;
+
+            heap.list_from_slice(&elements).map_err(From::from)
+        }
+        etf::Term::ImproperList(improper_list) => {
+            let elements = terms_from_etf(&improper_list.elements, heap)?;
+            let tail = from_etf(&improper_list.last, heap)?;
+
+            heap.improper_list_from_slice(&elements, tail)
+                .map_err(From::from)
+        }
+        etf::Term::Tuple(tuple) => {
+            let elements = terms_from_etf(&tuple.elements, heap)?;
+
+            heap.tuple_from_slice(&elements).map_err(From::from)
+        }
+        etf::Term::Map(map) => {
+            let mut entries = Vec::with_capacity(map.entries.len());
+
+            for (key, value) in &map.entries {
+                entries.push((from_etf(key, heap)?, from_etf(value, heap)?));
+            }
+
+            heap.map_from_slice(&entries).map_err(From::from)
+        }
+        // a heap fragment has no virtual binary heap to attach a reference-counted binary to, so
+        // binaries of any size are decoded as heap binaries
+        etf::Term::Binary(binary) => heap.heapbin_from_bytes(&binary.bytes).map_err(From::from),
+        etf::Term::Pid(pid) => {
+            let node_name = atom_from_etf(&pid.node)?;
+            let number = pid.id as usize;
+            let serial = pid.serial as usize;
+
+            if is_local(node_name, pid.creation) {
+                make_pid(number, serial).map_err(|_| Error::Invalid)
+            } else {
+                heap.external_pid(Node::new(node_name, pid.creation), number, serial)
+                    .map_err(From::from)
+            }
+        }
+        etf::Term::Port(port) => {
+            let node_name = atom_from_etf(&port.node)?;
+            let number = port.id as usize;
+
+            if is_local(node_name, port.creation) {
+                Ok(Term::make_port(number))
+            } else {
+                heap.external_port(Node::new(node_name, port.creation), number)
+                    .map_err(From::from)
+            }
+        }
+        etf::Term::Reference(reference) => {
+            let node_name = atom_from_etf(&reference.node)?;
+            let mut ids = [0; 3];
+
+            // BEAM references have up to 3 ids, or up to 5 from nodes that know this node accepts
+            // them (`V4_NC`), which only references of other nodes keep
+            if reference.id.is_empty() || (ids.len() + MAX_EXTRA_IDS) < reference.id.len() {
+                return Err(Error::Unsupported);
+            }
+
+            let (id, extra_ids) = if ids.len() < reference.id.len() {
+                reference.id.split_at(ids.len())
+            } else {
+                (&reference.id[..], &[][..])
+            };
+            ids[..id.len()].copy_from_slice(id);
+
+            let number = (ids[0] as u64) | ((ids[1] as u64) << 32);
+            let scheduler_id = ID::new(ids[2] as usize);
+
+            if is_local(node_name, reference.creation) {
+                if !extra_ids.is_empty() {
+                    return Err(Error::Invalid);
+                }
+
+                heap.reference(scheduler_id, number).map_err(From::from)
+            } else {
+                ExternalReference::with_extra_ids(
+                    Node::new(node_name, reference.creation),
+                    scheduler_id,
+                    number,
+                    extra_ids,
+                )
+                .clone_to_heap(heap)
+                .map_err(From::from)
+            }
+        }
+        etf::Term::BitBinary(_) | etf::Term::ExternalFun(_) | etf::Term::InternalFun(_) => {
+            Err(Error::Unsupported)
+        }
+    }
+}
+
+fn terms_from_etf<H: HeapAlloc>(etf_terms: &[etf::Term], heap: &mut H) -> Result<Vec<Term>, Error> {
+    etf_terms
+        .iter()
+        .map(|etf_term| from_etf(etf_term, heap))
+        .collect()
+}
+
+fn atom_from_etf(etf_atom: &etf::Atom) -> Result<Atom, Error> {
+    Atom::try_from_str(&etf_atom.name).map_err(|_| Error::Invalid)
+}
+
+fn is_local(node_name: Atom, creation: u32) -> bool {
+    (node_name == node::name()) && (creation == node::creation())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::sync::Arc;
+
+    use liblumen_alloc::erts::term::atom_unchecked;
+    use liblumen_alloc::erts::ModuleFunctionArity;
+
+    use crate::scheduler::with_process;
+
+    #[test]
+    fn round_trips_terms() {
+        with_process(|process| {
+            let terms = vec![
+                atom_unchecked("atom"),
+                Term::NIL,
+                process.integer(7).unwrap(),
+                process.integer(-300).unwrap(),
+                process.integer(1_i64 << 40).unwrap(),
+                process.integer(BigInt::from(1_u64) << 70).unwrap(),
+                process.float(1.5).unwrap(),
+                process.binary_from_bytes(&[1, 2, 3]).unwrap(),
+                process
+                    .improper_list_from_slice(&[atom_unchecked("head")], atom_unchecked("tail"))
+                    .unwrap(),
+                process
+                    .tuple_from_slice(&[atom_unchecked("ok"), process.integer(1).unwrap()])
+                    .unwrap(),
+                process
+                    .map_from_slice(&[(atom_unchecked("key"), atom_unchecked("value"))])
+                    .unwrap(),
+            ];
+
+            for term in terms {
+                assert_eq!(decode(&encode(term).unwrap(), process).unwrap(), term);
+            }
+        });
+    }
+
+    #[test]
+    fn round_trips_local_identifiers() {
+        with_process(|process| {
+            let terms = vec![
+                make_pid(1, 2).unwrap(),
+                Term::make_port(3),
+                process.reference_from_scheduler(ID::new(4), 5).unwrap(),
+            ];
+
+            for term in terms {
+                let decoded = decode(&encode(term).unwrap(), process).unwrap();
+
+                assert_eq!(decoded, term);
+                assert_eq!(
+                    decoded.to_typed_term().unwrap(),
+                    term.to_typed_term().unwrap()
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn round_trips_external_identifiers() {
+        with_process(|process| {
+            let node = Node::new(Atom::try_from_str("beam@host").unwrap(), 2);
+            let terms = vec![
+                (
+                    process.external_pid(node, 1, 2).unwrap(),
+                    make_pid(1, 2).unwrap(),
+                ),
+                (process.external_port(node, 3).unwrap(), Term::make_port(3)),
+                (
+                    process
+                        .external_reference(node, ID::new(4), (6 << 32) | 5)
+                        .unwrap(),
+                    process
+                        .reference_from_scheduler(ID::new(4), (6 << 32) | 5)
+                        .unwrap(),
+                ),
+            ];
+
+            for (external, local) in terms {
+                let decoded = decode(&encode(external).unwrap(), process).unwrap();
+
+                assert_eq!(decoded, external);
+                assert_ne!(decoded, local);
+            }
+        });
+    }
+
+    #[test]
+    fn round_trips_references_with_extra_ids() {
+        with_process(|process| {
+            let etf_reference = etf::Reference {
+                node: etf::Atom::from("beam@host"),
+                id: vec![1, 2, 3, 4, 5],
+                creation: 6,
+            };
+            let mut bytes = Vec::new();
+            etf::Term::from(etf_reference.clone())
+                .encode(&mut bytes)
+                .unwrap();
+
+            let decoded = decode(&bytes, process).unwrap();
+
+            assert_eq!(
+                etf::Term::decode(&encode(decoded).unwrap()[..]).unwrap(),
+                etf_reference.into()
+            );
+        });
+    }
+
+    #[test]
+    fn identifiers_from_another_creation_are_external() {
+        with_process(|process| {
+            let pid = make_pid(1, 2).unwrap();
+            let etf_pid = etf::Pid::new(node::name().name(), 1, 2, node::creation() + 1);
+            let mut bytes = Vec::new();
+            etf::Term::from(etf_pid).encode(&mut bytes).unwrap();
+
+            let decoded = decode(&bytes, process).unwrap();
+
+            assert_ne!(decoded, pid);
+
+            match decoded.to_typed_term().unwrap() {
+                TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+                    TypedTerm::ExternalPid(external_pid) => {
+                        assert_eq!(external_pid.node().name(), node::name());
+                        assert_eq!(external_pid.node().creation(), node::creation() + 1);
+                    }
+                    typed_term => panic!("{:?} is not an external pid", typed_term),
+                },
+                typed_term => panic!("{:?} is not an external pid", typed_term),
+            }
+        });
+    }
+
+    #[test]
+    fn encodes_closures_as_funs() {
+        with_process(|process| {
+            let module_function_arity = Arc::new(ModuleFunctionArity {
+                module: Atom::try_from_str("module").unwrap(),
+                function: Atom::try_from_str("function").unwrap(),
+                arity: 1,
+            });
+            let code = |arc_process: &Arc<Process>| {
+                arc_process.wait();
+
+                Ok(())
+            };
+
+            let without_env = process
+                .closure_with_env_from_slice(
+                    module_function_arity.clone(),
+                    code,
+                    process.pid_term(),
+                    &[],
+                )
+                .unwrap();
+
+            assert_eq!(
+                etf::Term::decode(&encode(without_env).unwrap()[..]).unwrap(),
+                etf::Term::from(etf::ExternalFun::from(("module", "function", 1)))
+            );
+
+            let with_env = process
+                .closure_with_env_from_slice(
+                    module_function_arity,
+                    code,
+                    process.pid_term(),
+                    &[process.integer(7).unwrap()],
+                )
+                .unwrap();
+            let bytes = encode(with_env).unwrap();
+
+            match etf::Term::decode(&bytes[..]).unwrap() {
+                etf::Term::InternalFun(etf::InternalFun::New {
+                    module,
+                    arity,
+                    free_vars,
+                    ..
+                }) => {
+                    assert_eq!(module, etf::Atom::from("module"));
+                    assert_eq!(arity, 1);
+                    assert_eq!(free_vars, vec![etf::Term::from(etf::FixInteger::from(7))]);
+                }
+                etf_term => panic!("{} is not a new fun", etf_term),
+            }
+
+            match decode(&bytes, process) {
+                Err(Error::Unsupported) => (),
+                result => panic!("{:?} is not unsupported", result),
+            }
+        });
+    }
+
+    #[test]
+    fn decodes_old_formats() {
+        with_process(|process| {
+            // :erlang.term_to_binary('abc') before UTF-8 atoms, as ATOM_EXT
+            assert_eq!(
+                decode(&[VERSION, 100, 0, 3, b'a', b'b', b'c'], process).unwrap(),
+                atom_unchecked("abc")
+            );
+            // :erlang.term_to_binary([1, 2]), as STRING_EXT
+            assert_eq!(
+                decode(&[VERSION, 107, 0, 2, 1, 2], process).unwrap(),
+                process
+                    .list_from_slice(&[process.integer(1).unwrap(), process.integer(2).unwrap()])
+                    .unwrap()
+            );
+        });
+    }
+
+    #[test]
+    fn round_trips_with_options() {
+        with_process(|process| {
+            let atoms: Vec<Term> = (0..100).map(|_| atom_unchecked("a")).collect();
+            let terms = vec![
+                process.float(0.1).unwrap(),
+                process.float(-1.0e300).unwrap(),
+                process.list_from_slice(&atoms).unwrap(),
+            ];
+
+            for term in terms {
+                for minor_version in 0..=2 {
+                    for compressed in &[None, Some(1), Some(9)] {
+                        let options = Options {
+                            compressed: *compressed,
+                            minor_version,
+                        };
+
+                        assert_eq!(
+                            decode(&encode_with_options(term, options).unwrap(), process).unwrap(),
+                            term
+                        );
+                    }
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn with_wrong_uncompressed_length_is_invalid() {
+        with_process(|process| {
+            let atoms: Vec<Term> = (0..100).map(|_| atom_unchecked("a")).collect();
+            let list = process.list_from_slice(&atoms).unwrap();
+            let options = Options {
+                compressed: Some(6),
+                minor_version: 1,
+            };
+            let mut bytes = encode_with_options(list, options).unwrap();
+            bytes[5] += 1;
+
+            match decode(&bytes, process) {
+                Err(Error::Invalid) => (),
+                result => panic!("{:?} is not invalid", result),
+            }
+        });
+    }
+
+    #[test]
+    fn with_trailing_bytes_is_invalid() {
+        // NIL_EXT followed by another NIL_EXT
+        with_process(|process| match decode(&[VERSION, 106, 106], process) {
+            Err(Error::Invalid) => (),
+            result => panic!("{:?} is not invalid", result),
+        });
+    }
+}
@@ -29,14 +29,24 @@ extern crate lazy_static;
 #[macro_use]
 mod macros;
 
+mod args;
 mod binary;
+mod digest;
 // `pub` or `examples/spawn-chain`
 pub mod code;
-mod config;
+// `pub` so that natives can read `config::runtime::RuntimeConfig`
+pub mod config;
+// `pub` so that binaries can start distribution and connect to other nodes
+#[cfg(not(target_arch = "wasm32"))]
+pub mod distribution;
+// `pub` so that the interpreter can convert terms to and from the external term format
+pub mod external_term_format;
 mod logging;
-mod node;
+pub mod node;
 mod number;
 pub mod otp;
+#[cfg(not(target_arch = "wasm32"))]
+mod port;
 pub mod process;
 // `pub` or `examples/spawn-chain`
 pub mod registry;
@@ -47,14 +57,16 @@ mod send;
 mod stacktrace;
 // `pub` for `examples/spawn-chain`
 pub mod system;
-// `pub` for `examples/spawn-chain`
-mod term;
+// `pub` so that debugging output can convert terms to JSON with `term::json`
+pub mod term;
 // `pub` to allow `time::monotonic::set_source(callback)`
 #[cfg(test)]
 mod test;
 pub mod time;
 // Public so that external code can all `timer::expire` to expire timers
 mod timer;
+// `pub` so that the interpreter can run trapping BIFs as natives
+pub mod trap;
 mod tuple;
 
 use self::config::Config;
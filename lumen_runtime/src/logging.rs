@@ -4,6 +4,25 @@ use log::{Level, Log, Metadata, Record, SetLoggerError};
 
 use crate::system;
 
+/// Whether the host application's logger takes messages at `level` for `target`.  When it doesn't,
+/// such as when the host hasn't installed a logger, messages that shouldn't be lost have to be
+/// written somewhere else instead.
+pub fn is_enabled(level: Level, target: &str) -> bool {
+    level <= log::max_level()
+        && log::logger().enabled(&Metadata::builder().level(level).target(target).build())
+}
+
+/// Logs `message` at the `error` level for `target` if the host application's logger takes it, or
+/// writes it to the standard error of the runtime otherwise, as the reports of the runtime itself,
+/// such as of processes that exit abnormally, are.
+pub fn report_error(target: &str, message: &str) {
+    if is_enabled(Level::Error, target) {
+        log::error!(target: target, "{}", message);
+    } else {
+        system::io::eputs(message);
+    }
+}
+
 pub struct Logger {
     level: Level,
     color: bool,
@@ -24,23 +43,23 @@ impl Logger {
             Level::Debug => record.level().to_string().purple(),
             Level::Trace => record.level().to_string().normal(),
         };
-        println!(
+        system::io::puts(&format!(
             "{} {:<5} [{}] {}",
             system::time::system_time().as_secs(),
             level,
             record.module_path().unwrap_or_default(),
             record.args(),
-        )
+        ))
     }
     #[cfg(not(target_arch = "wasm32"))]
     fn log_plain(record: &Record) {
-        println!(
+        system::io::puts(&format!(
             "{} {:<5} [{}] {}",
             system::time::system_time().as_secs(),
             record.level(),
             record.module_path().unwrap_or_default(),
             record.args(),
-        )
+        ))
     }
 
     #[cfg(target_arch = "wasm32")]
@@ -57,7 +76,7 @@ impl Logger {
             record.module_path().unwrap_or_default(),
             record.args()
         );
-        system::io::console_log(&msg);
+        system::io::puts(&msg);
     }
 }
 
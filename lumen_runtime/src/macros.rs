@@ -1,4 +1,6 @@
 #[macro_use]
+mod args;
+#[macro_use]
 mod atom;
 #[macro_use]
 mod binary;
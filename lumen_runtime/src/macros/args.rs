@@ -0,0 +1,26 @@
+/// Checks the arguments of a BIF against the specs in `crate::args`, returning the converted
+/// arguments as a tuple, or a `badarg` whose `ErrorInfo` names the first argument that does not
+/// match.
+///
+/// ```ignore
+/// let (bytes, radix) = args!(process, binary => binary(), base => integer(2..=36))?;
+/// ```
+macro_rules! args {
+    ($process:expr, $($term:expr => $spec:ident($($spec_argument:expr),*)),+ $(,)?) => {{
+        let process = $process;
+        let mut argument = 0;
+
+        (|| -> core::result::Result<_, liblumen_alloc::erts::exception::Exception> {
+            Ok(($({
+                argument += 1;
+
+                crate::args::check(
+                    crate::args::$spec($($spec_argument),*),
+                    argument,
+                    $term,
+                    process,
+                )?
+            },)+))
+        })()
+    }};
+}
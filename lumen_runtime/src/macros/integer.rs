@@ -58,83 +58,6 @@ macro_rules! bitwise_infix_operator {
     }};
 }
 
-macro_rules! bitshift_infix_operator {
-    ($integer:ident, $shift:ident, $process:ident, $positive:tt, $negative:tt) => {{
-        pub const MAX_SHIFT: usize = std::mem::size_of::<isize>() * 8 - 1;
-
-        let option_shifted = match $integer.to_typed_term().unwrap() {
-            TypedTerm::SmallInteger(integer_small_integer) => {
-                let integer_isize: isize = integer_small_integer.into();
-                let shift_isize: isize = $shift.try_into().map_err(|_| badarith!())?;
-
-                // Rust doesn't support negative shift, so negative left shifts need to be right shifts
-                if 0 <= shift_isize {
-                    let shift_usize = shift_isize as usize;
-
-                    if shift_usize <= MAX_SHIFT {
-                        let shifted = integer_isize $positive shift_usize;
-                        let shifted_term = $process.integer(shifted)?;
-
-                        Some(shifted_term)
-                    } else {
-                        let big_int: BigInt = integer_isize.into();
-                        let shifted = big_int $positive shift_usize;
-                        let shifted_term = $process.integer(shifted)?;
-
-                        Some(shifted_term)
-                    }
-                } else {
-                    let shift_usize = (-shift_isize) as usize;
-
-                    if shift_usize <= MAX_SHIFT {
-                        let shifted = integer_isize $negative shift_usize;
-                        let shifted_term = $process.integer(shifted)?;
-
-                        Some(shifted_term)
-                    } else {
-                        let big_int: BigInt = integer_isize.into();
-                        let shifted = big_int $negative shift_usize;
-                        let shifted_term = $process.integer(shifted)?;
-
-                        Some(shifted_term)
-                    }
-                }
-            }
-            TypedTerm::Boxed(integer_unboxed) => {
-                match integer_unboxed.to_typed_term().unwrap() {
-                    TypedTerm::BigInteger(integer_big_integer) => {
-                        let big_int: &BigInt = integer_big_integer.as_ref().into();
-                        let shift_isize: isize = $shift.try_into().map_err(|_| badarith!())?;
-
-                        // Rust doesn't support negative shift, so negative left shifts need to be right
-                        // shifts
-                        let shifted = if 0 <= shift_isize {
-                            let shift_usize = shift_isize as usize;
-
-                            big_int $positive shift_usize
-                        } else {
-                            let shift_usize = (-shift_isize) as usize;
-
-                            big_int $negative shift_usize
-                        };
-
-                        let shifted_term = $process.integer(shifted)?;
-
-                        Some(shifted_term)
-                    }
-                    _ => None,
-                }
-            }
-            _ => None,
-        };
-
-        match option_shifted {
-            Some(shifted) => Ok(shifted),
-            None => Err(badarith!().into())
-        }
-    }};
-}
-
 macro_rules! integer_infix_operator {
     ($left:ident, $right:ident, $process:ident, $infix:tt) => {{
         use liblumen_alloc::erts::term::TypedTerm;
@@ -1 +1,166 @@
+//! The identity of this node.
+//!
+//! A node is not alive, and is named `nonode@nohost`, until it is started with a name, as
+//! `-name` or `-sname` do for `erl`.  Local pids and references belong to whatever the node is
+//! named, so `node(Pid)` changes with `node()` when the node is started.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use liblumen_core::locks::RwLock;
+
+use liblumen_alloc::erts::term::Atom;
+
+use crate::system;
+
 pub const DEAD: &str = "nonode@nohost";
+
+/// Whether the host part of a node name is the fully qualified host name (`-name`) or only its
+/// first component (`-sname`)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NameKind {
+    Long,
+    Short,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum StartError {
+    AlreadyAlive,
+    InvalidName,
+}
+
+/// The name of this node, or `nonode@nohost` when it is not alive
+pub fn name() -> Atom {
+    RW_LOCK_NAME
+        .read()
+        .unwrap_or_else(|| Atom::try_from_str(DEAD).unwrap())
+}
+
+pub fn is_alive() -> bool {
+    RW_LOCK_NAME.read().is_some()
+}
+
+/// Which incarnation of the node with this name this is, as assigned by EPMD when the node
+/// registers.  `0` until the node is registered.
+pub fn creation() -> u32 {
+    CREATION.load(Ordering::SeqCst)
+}
+
+/// The full name `start` would give this node, adding the host if `name` has no `@`, without
+/// naming it.
+pub fn checked_name(name: &str, kind: NameKind) -> Result<Atom, StartError> {
+    if is_alive() {
+        return Err(StartError::AlreadyAlive);
+    }
+
+    let full_name = full_name(name, kind, &system::host::name::get())?;
+
+    Atom::try_from_str(full_name).map_err(|_| StartError::InvalidName)
+}
+
+/// Names this node `name`, adding the host if `name` has no `@`, and returns the full name.
+pub fn start(name: &str, kind: NameKind) -> Result<Atom, StartError> {
+    start_with_creation(name, kind, creation())
+}
+
+/// Like `start`, but also sets the `creation` EPMD assigned, under the same lock, so that no
+/// identifier is made with the new name and the old creation.
+pub fn start_with_creation(name: &str, kind: NameKind, creation: u32) -> Result<Atom, StartError> {
+    let mut writable_name = RW_LOCK_NAME.write();
+
+    if writable_name.is_some() {
+        return Err(StartError::AlreadyAlive);
+    }
+
+    let full_name = full_name(name, kind, &system::host::name::get())?;
+    let atom = Atom::try_from_str(full_name).map_err(|_| StartError::InvalidName)?;
+    CREATION.store(creation, Ordering::SeqCst);
+    *writable_name = Some(atom);
+
+    Ok(atom)
+}
+
+// Private
+
+fn full_name(name: &str, kind: NameKind, host_name: &str) -> Result<String, StartError> {
+    let (alive_name, host) = match name.find('@') {
+        Some(index) => (&name[..index], name[(index + 1)..].to_string()),
+        None => {
+            let host = match kind {
+                NameKind::Long => host_name,
+                NameKind::Short => host_name.split('.').next().unwrap(),
+            };
+
+            (name, host.to_string())
+        }
+    };
+
+    let is_valid_alive_name = !alive_name.is_empty()
+        && alive_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    let is_valid_host =
+        !host.is_empty() && !host.contains('@') && (kind == NameKind::Long || !host.contains('.'));
+
+    if is_valid_alive_name && is_valid_host {
+        Ok(format!("{}@{}", alive_name, host))
+    } else {
+        Err(StartError::InvalidName)
+    }
+}
+
+static CREATION: AtomicU32 = AtomicU32::new(0);
+
+lazy_static! {
+    static ref RW_LOCK_NAME: RwLock<Option<Atom>> = Default::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod full_name {
+        use super::*;
+
+        #[test]
+        fn without_host_adds_host() {
+            assert_eq!(
+                full_name("lumen", NameKind::Long, "box.example.com"),
+                Ok("lumen@box.example.com".to_string())
+            );
+            assert_eq!(
+                full_name("lumen", NameKind::Short, "box.example.com"),
+                Ok("lumen@box".to_string())
+            );
+        }
+
+        #[test]
+        fn with_host_keeps_host() {
+            assert_eq!(
+                full_name("lumen@other.example.com", NameKind::Long, "box"),
+                Ok("lumen@other.example.com".to_string())
+            );
+            assert_eq!(
+                full_name("lumen@other", NameKind::Short, "box"),
+                Ok("lumen@other".to_string())
+            );
+        }
+
+        #[test]
+        fn with_qualified_host_for_short_name_errors() {
+            assert_eq!(
+                full_name("lumen@other.example.com", NameKind::Short, "box"),
+                Err(StartError::InvalidName)
+            );
+        }
+
+        #[test]
+        fn with_invalid_name_errors() {
+            for name in &["", "@box", "lumen@", "lu men", "lumen@box@box"] {
+                assert_eq!(
+                    full_name(name, NameKind::Long, "box"),
+                    Err(StartError::InvalidName)
+                );
+            }
+        }
+    }
+}
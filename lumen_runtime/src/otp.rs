@@ -1,7 +1,17 @@
 //! All modules under the OTP namespace should mirror module shipped with C-BEAM OTP
 
 pub mod binary;
+pub mod crypto;
 pub mod erlang;
+pub mod error_logger;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod file;
+pub mod io_lib;
 pub mod lists;
+pub mod logger;
 pub mod maps;
+pub mod math;
+pub mod proplists;
+pub mod rand;
 pub mod timer;
+pub mod zlib;
@@ -0,0 +1,42 @@
+//! Mirrors the random bytes of [crypto](http://erlang.org/doc/man/crypto.html) module
+//!
+//! The bytes come from the operating system's cryptographically secure random number generator,
+//! or `crypto.getRandomValues` on wasm32, so they are suitable for tokens and ids, unlike those of
+//! `rand`.
+
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use core::convert::TryInto;
+
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+use liblumen_alloc::erts::exception::Result;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Term};
+use liblumen_alloc::{badarg, error};
+
+/// The most bytes `strong_rand_bytes/1` returns, as OTP's `crypto` takes `N` as a C `int`
+pub const MAX_STRONG_RAND_BYTES: usize = i32::max_value() as usize;
+
+/// `N` bytes as a binary.  Errors `badarg` if `N` is past `MAX_STRONG_RAND_BYTES`, before the bytes
+/// are allocated, and `low_entropy` if the operating system can't supply them.
+pub fn strong_rand_bytes_1(n: Term, process: &Process) -> Result {
+    let len: usize = n.try_into()?;
+
+    if MAX_STRONG_RAND_BYTES < len {
+        return Err(badarg!().into());
+    }
+
+    let mut bytes = vec![0; len];
+
+    match OsRng::new().and_then(|mut os_rng| os_rng.try_fill_bytes(&mut bytes)) {
+        Ok(()) => Ok(process.binary_from_bytes(&bytes)?),
+        Err(_) => Err(error!(atom_unchecked("low_entropy")).into()),
+    }
+}
@@ -0,0 +1,71 @@
+use proptest::prop_assert_eq;
+use proptest::test_runner::{Config, TestRunner};
+
+use liblumen_alloc::badarg;
+
+use crate::otp::crypto;
+use crate::otp::erlang;
+use crate::scheduler::{with_process, with_process_arc};
+use crate::test::strategy;
+
+#[test]
+fn without_non_negative_integer_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(&strategy::term::is_not_integer(arc_process.clone()), |n| {
+                prop_assert_eq!(
+                    crypto::strong_rand_bytes_1(n, &arc_process),
+                    Err(badarg!().into())
+                );
+
+                Ok(())
+            })
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_negative_integer_errors_badarg() {
+    with_process(|process| {
+        assert_eq!(
+            crypto::strong_rand_bytes_1(process.integer(-1).unwrap(), &process),
+            Err(badarg!().into())
+        );
+    });
+}
+
+#[test]
+fn with_integer_past_max_errors_badarg() {
+    with_process(|process| {
+        let n = process.integer(crypto::MAX_STRONG_RAND_BYTES + 1).unwrap();
+
+        assert_eq!(
+            crypto::strong_rand_bytes_1(n, &process),
+            Err(badarg!().into())
+        );
+    });
+}
+
+#[test]
+fn with_non_negative_integer_returns_binary_with_n_bytes() {
+    with_process(|process| {
+        for len in &[0, 1, 16, 256] {
+            let n = process.integer(*len).unwrap();
+            let binary = crypto::strong_rand_bytes_1(n, &process).unwrap();
+
+            assert_eq!(erlang::byte_size_1(binary, &process), Ok(n));
+        }
+    });
+}
+
+#[test]
+fn bytes_are_not_repeated() {
+    with_process(|process| {
+        let n = process.integer(32).unwrap();
+
+        assert_ne!(
+            crypto::strong_rand_bytes_1(n, &process),
+            crypto::strong_rand_bytes_1(n, &process)
+        );
+    });
+}
@@ -3,18 +3,25 @@
 pub mod add_2;
 pub mod apply_3;
 pub mod binary_to_integer_1;
+pub mod binary_to_list_1;
 pub mod convert_time_unit_3;
 pub mod demonitor_2;
 pub mod exit_1;
+pub mod garbage_collect_0;
+pub mod garbage_collect_1;
+pub mod garbage_collect_2;
 pub mod is_function_1;
 pub mod is_function_2;
 pub mod is_map_key_2;
+pub mod length_1;
 pub mod link_1;
 pub mod monitor_2;
+pub mod monitor_3;
 pub mod monotonic_time_0;
 pub mod number_or_badarith_1;
 pub mod process_flag_2;
 pub mod process_info_2;
+pub mod resume_process_1;
 pub mod self_0;
 pub mod send_2;
 pub mod spawn_3;
@@ -22,7 +29,11 @@ pub mod spawn_apply_3;
 pub mod spawn_link_3;
 pub mod spawn_opt_4;
 pub mod subtract_2;
+pub mod suspend_process_1;
+pub mod suspend_process_2;
+pub mod trace_3;
 pub mod unlink_1;
+pub mod yield_0;
 
 // wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
 // use proptest completely for wasm32
@@ -37,7 +48,7 @@ use core::num::FpCategory;
 
 use alloc::sync::Arc;
 
-use num_bigint::BigInt;
+use num_bigint::{BigInt, Sign};
 use num_traits::Zero;
 
 use liblumen_core::locks::MutexGuard;
@@ -49,18 +60,24 @@ use liblumen_alloc::erts::term::binary::aligned_binary::AlignedBinary;
 use liblumen_alloc::erts::term::binary::maybe_aligned_maybe_binary::MaybeAlignedMaybeBinary;
 use liblumen_alloc::erts::term::binary::{Bitstring, IterableBitstring, MaybePartialByte};
 use liblumen_alloc::erts::term::{
-    atom_unchecked, AsTerm, Atom, Boxed, Cons, Encoding, Float, ImproperList, Map, SmallInteger,
-    Term, Tuple, TypedTerm,
+    atom_unchecked, AsTerm, Atom, AtomErrorKind, Boxed, Cons, Encoding, Float, Map, Pid, Reference,
+    SmallInteger, Term, Tuple, TypedTerm,
 };
 use liblumen_alloc::{badarg, badarith, badkey, badmap, error, raise, throw};
 
+use crate::args;
 use crate::binary::{start_length_to_part_range, PartRange, ToTermOptions};
+use crate::digest::{self, Md5};
+use crate::external_term_format;
 use crate::node;
 use crate::otp;
-use crate::process::SchedulerDependentAlloc;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::port;
+use crate::process::{alias, SchedulerDependentAlloc};
 use crate::registry::{self, pid_to_self_or_process};
 use crate::send::{self, send, Sent};
 use crate::stacktrace;
+use crate::system;
 use crate::time::monotonic::{self, Milliseconds};
 use crate::timer::start::ReferenceFrame;
 use crate::timer::{self, Timeout};
@@ -121,6 +138,25 @@ pub fn abs_1(number: Term, process: &Process) -> Result {
     }
 }
 
+/// `adler32/1`
+///
+/// The Adler-32 checksum of `Data`, an iodata.
+pub fn adler32_1(data: Term, process: &Process) -> Result {
+    let bytes = iodata_to_bytes(data)?;
+
+    Ok(process.integer(digest::adler32(1, &bytes) as u64)?)
+}
+
+/// `adler32/2`
+///
+/// Continues `OldAdler`, the Adler-32 checksum of the previous data, with `Data`, an iodata.
+pub fn adler32_2(old_adler: Term, data: Term, process: &Process) -> Result {
+    let old_adler_u32: u32 = old_adler.try_into()?;
+    let bytes = iodata_to_bytes(data)?;
+
+    Ok(process.integer(digest::adler32(old_adler_u32, &bytes) as u64)?)
+}
+
 /// `and/2` infix operator.
 ///
 /// **NOTE: NOT SHORT-CIRCUITING!**  Use `andalso/2` for short-circuiting, but it doesn't enforce
@@ -145,7 +181,7 @@ pub fn andalso_2(boolean: Term, term: Term) -> Result {
 }
 
 pub fn append_element_2(tuple: Term, element: Term, process: &Process) -> Result {
-    let internal: Boxed<Tuple> = tuple.try_into()?;
+    let (internal,) = args!(process, tuple => tuple())?;
     let new_tuple = process.tuple_from_slices(&[&internal[..], &[element]])?;
 
     Ok(new_tuple)
@@ -171,27 +207,38 @@ pub fn are_not_equal_after_conversion_2(left: Term, right: Term) -> Term {
     left.ne(&right).into()
 }
 
+/// `atom_to_binary/1`, which is `atom_to_binary(Atom, utf8)`
+pub fn atom_to_binary_1(atom: Term, process: &Process) -> Result {
+    let (atom,) = args!(process, atom => atom())?;
+    let binary = process.binary_from_str(atom.name())?;
+
+    Ok(binary)
+}
+
+/// `latin1` can only encode atoms whose characters are all at most 255, one byte per character.
+/// `unicode` and `utf8` both encode the name as UTF-8.
 pub fn atom_to_binary_2(atom: Term, encoding: Term, process: &Process) -> Result {
-    match atom.to_typed_term().unwrap() {
-        TypedTerm::Atom(atom) => {
-            let _: Encoding = encoding.try_into()?;
-            let binary = process.binary_from_str(atom.name())?;
+    let (atom, encoding) = args!(process, atom => atom(), encoding => encoding())?;
+    let name = atom.name();
+
+    let binary = match encoding {
+        Encoding::Latin1 => {
+            let byte_vec = latin1_byte_vec(name)
+                .ok_or_else(|| badarg!(1, "contains characters that are not latin1"))?;
 
-            Ok(binary)
+            process.binary_from_bytes(&byte_vec)?
         }
-        _ => Err(badarg!().into()),
-    }
+        Encoding::Unicode | Encoding::Utf8 => process.binary_from_str(name)?,
+    };
+
+    Ok(binary)
 }
 
 pub fn atom_to_list_1(atom: Term, process: &Process) -> Result {
-    match atom.to_typed_term().unwrap() {
-        TypedTerm::Atom(atom) => {
-            let chars = atom.name().chars();
+    let (atom,) = args!(process, atom => atom())?;
+    let chars = atom.name().chars();
 
-            process.list_from_chars(chars).map_err(|error| error.into())
-        }
-        _ => Err(badarg!().into()),
-    }
+    process.list_from_chars(chars).map_err(|error| error.into())
 }
 
 // `band/2` infix operator.
@@ -292,74 +339,38 @@ pub fn binary_part_3(binary: Term, start: Term, length: Term, process: &Process)
     }
 }
 
-pub fn binary_to_atom_2(binary: Term, encoding: Term) -> Result {
-    let _: Encoding = encoding.try_into()?;
-
-    match binary.to_typed_term().unwrap() {
-        TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
-            TypedTerm::HeapBinary(heap_binary) => {
-                Atom::try_from_latin1_bytes(heap_binary.as_bytes()).map_err(|error| error.into())
-            }
-            TypedTerm::ProcBin(process_binary) => {
-                Atom::try_from_latin1_bytes(process_binary.as_bytes()).map_err(|error| error.into())
-            }
-            TypedTerm::SubBinary(subbinary) => {
-                if subbinary.is_binary() {
-                    if subbinary.is_aligned() {
-                        let bytes = unsafe { subbinary.as_bytes() };
-
-                        Atom::try_from_latin1_bytes(bytes)
-                    } else {
-                        let byte_vec: Vec<u8> = subbinary.full_byte_iter().collect();
+/// With `latin1`, each byte of `binary` is one character.  With `unicode` or `utf8`, `binary` has
+/// to be UTF-8.  Names longer than 255 characters fail with `system_limit`.
+pub fn binary_to_atom_2(binary: Term, encoding: Term, process: &Process) -> Result {
+    let (bytes, encoding) = args!(process, binary => binary(), encoding => encoding())?;
 
-                        Atom::try_from_latin1_bytes(&byte_vec)
-                    }
-                    .map_err(|error| error.into())
-                } else {
-                    Err(badarg!().into())
-                }
-            }
-            _ => Err(badarg!().into()),
-        },
-        _ => Err(badarg!().into()),
+    let atom = match encoding {
+        Encoding::Latin1 => Atom::try_from_latin1_bytes(bytes),
+        Encoding::Unicode | Encoding::Utf8 => Atom::try_from_utf8_bytes(bytes),
     }
-    .map(|atom| unsafe { atom.as_term() })
-}
-
-pub fn binary_to_existing_atom_2(binary: Term, encoding: Term) -> Result {
-    let _: Encoding = encoding.try_into()?;
+    .map_err(|error| match error.kind() {
+        AtomErrorKind::InvalidUtf8 => badarg!(1, "not valid UTF-8").into(),
+        _ => Exception::from(error),
+    })?;
 
-    match binary.to_typed_term().unwrap() {
-        TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
-            TypedTerm::HeapBinary(heap_binary) => {
-                Atom::try_from_latin1_bytes_existing(heap_binary.as_bytes())
-                    .map_err(|error| error.into())
-            }
-            TypedTerm::ProcBin(process_binary) => {
-                Atom::try_from_latin1_bytes_existing(process_binary.as_bytes())
-                    .map_err(|error| error.into())
-            }
-            TypedTerm::SubBinary(subbinary) => {
-                if subbinary.is_binary() {
-                    if subbinary.is_aligned() {
-                        let bytes = unsafe { subbinary.as_bytes() };
+    Ok(unsafe { atom.as_term() })
+}
 
-                        Atom::try_from_latin1_bytes_existing(bytes)
-                    } else {
-                        let byte_vec: Vec<u8> = subbinary.full_byte_iter().collect();
+/// Like `binary_to_atom_2`, but fails with `badarg` instead of creating the atom, so names longer
+/// than 255 characters are `badarg` too, as no atom can have them.
+pub fn binary_to_existing_atom_2(binary: Term, encoding: Term, process: &Process) -> Result {
+    let (bytes, encoding) = args!(process, binary => binary(), encoding => encoding())?;
 
-                        Atom::try_from_latin1_bytes_existing(&byte_vec)
-                    }
-                    .map_err(|error| error.into())
-                } else {
-                    Err(badarg!().into())
-                }
-            }
-            _ => Err(badarg!().into()),
-        },
-        _ => Err(badarg!().into()),
+    let atom = match encoding {
+        Encoding::Latin1 => Atom::try_from_latin1_bytes_existing(bytes),
+        Encoding::Unicode | Encoding::Utf8 => Atom::try_from_utf8_bytes_existing(bytes),
     }
-    .map(|atom| unsafe { atom.as_term() })
+    .map_err(|error| match error.kind() {
+        AtomErrorKind::InvalidUtf8 => badarg!(1, "not valid UTF-8"),
+        _ => badarg!(1, "not an already existing atom"),
+    })?;
+
+    Ok(unsafe { atom.as_term() })
 }
 
 pub fn binary_to_float_1<'process>(binary: Term, process: &'process Process) -> Result {
@@ -393,17 +404,15 @@ pub fn binary_to_float_1<'process>(binary: Term, process: &'process Process) ->
 }
 
 pub fn binary_to_integer_1<'process>(binary: Term, process: &'process Process) -> Result {
-    let mut heap = process.acquire_heap();
-    let s: &str = heap.str_from_binary(binary)?;
-    let bytes = s.as_bytes();
+    let (bytes,) = args!(process, binary => binary())?;
 
     match BigInt::parse_bytes(bytes, 10) {
         Some(big_int) => {
-            let term = heap.integer(big_int)?;
+            let term = process.integer(big_int)?;
 
             Ok(term)
         }
-        None => Err(badarg!().into()),
+        None => Err(badarg!(1, "not a textual representation of an integer").into()),
     }
 }
 
@@ -412,34 +421,16 @@ pub fn binary_to_integer_2<'process>(
     base: Term,
     process: &'process Process,
 ) -> Result {
-    let mut heap = process.acquire_heap();
-    let s: &str = heap.str_from_binary(binary)?;
-    let radix: usize = base.try_into()?;
+    let (bytes, radix) = args!(process, binary => binary(), base => integer(2..=36))?;
 
-    if 2 <= radix && radix <= 36 {
-        let bytes = s.as_bytes();
-
-        match BigInt::parse_bytes(bytes, radix as u32) {
-            Some(big_int) => {
-                let term = heap.integer(big_int)?;
+    match BigInt::parse_bytes(bytes, radix as u32) {
+        Some(big_int) => {
+            let term = process.integer(big_int)?;
 
-                Ok(term)
-            }
-            None => Err(badarg!()),
+            Ok(term)
         }
-    } else {
-        Err(badarg!())
+        None => Err(badarg!(1, "not a textual representation of an integer").into()),
     }
-    .map_err(|error| error.into())
-}
-
-pub fn binary_to_list_1(binary: Term, process: &Process) -> Result {
-    let bytes = process.bytes_from_binary(binary)?;
-    let byte_terms = bytes.iter().map(|byte| (*byte).into());
-
-    process
-        .list_from_iter(byte_terms)
-        .map_err(|error| error.into())
 }
 
 /// The one-based indexing for binaries used by this function is deprecated. New code is to use
@@ -586,14 +577,24 @@ pub fn bor_2(left_integer: Term, right_integer: Term, process: &Process) -> Resu
 
 pub const MAX_SHIFT: usize = std::mem::size_of::<isize>() * 8 - 1;
 
+/// The most bits an integer shifted left can have before `bsl/2` fails with `system_limit`, as
+/// BEAM's bignums have at most 2^19 64-bit digits
+pub const MAX_SHIFTED_BITS: usize = (1 << 19) * 64;
+
 /// `bsl/2` infix operator.
 pub fn bsl_2(integer: Term, shift: Term, process: &Process) -> Result {
-    bitshift_infix_operator!(integer, shift, process, <<, >>)
+    let shift_isize: isize = shift.try_into().map_err(|_| badarith!())?;
+
+    shift_left(integer, shift_isize, process)
 }
 
 /// `bsr/2` infix operator.
 pub fn bsr_2(integer: Term, shift: Term, process: &Process) -> Result {
-    bitshift_infix_operator!(integer, shift, process, >>, <<)
+    let shift_isize: isize = shift.try_into().map_err(|_| badarith!())?;
+    // `-isize::MIN` is past `isize::MAX`, which is already past `MAX_SHIFTED_BITS`
+    let left_shift = shift_isize.checked_neg().unwrap_or(isize::max_value());
+
+    shift_left(integer, left_shift, process)
 }
 
 /// `bxor/2` infix operator.
@@ -629,84 +630,65 @@ pub fn cancel_timer_2(timer_reference: Term, options: Term, process: &Process) -
 }
 
 pub fn ceil_1(number: Term, process: &Process) -> Result {
-    let option_ceil = match number.to_typed_term().unwrap() {
-        TypedTerm::SmallInteger(_) => Some(number),
-        TypedTerm::Boxed(boxed) => {
-            match boxed.to_typed_term().unwrap() {
-                TypedTerm::BigInteger(_) => Some(number),
-                TypedTerm::Float(float) => {
-                    let inner: f64 = float.into();
-                    let ceil_inner = inner.ceil();
+    round_number(number, f64::ceil, process)
+}
 
-                    // skip creating a BigInt if float can fit in small integer.
-                    let ceil_term = if (SmallInteger::MIN_VALUE as f64).max(Float::INTEGRAL_MIN)
-                        <= ceil_inner
-                        && ceil_inner <= (SmallInteger::MAX_VALUE as f64).min(Float::INTEGRAL_MAX)
-                    {
-                        process.integer(ceil_inner as isize)?
-                    } else {
-                        let ceil_string = ceil_inner.to_string();
-                        let ceil_bytes = ceil_string.as_bytes();
-                        let big_int = BigInt::parse_bytes(ceil_bytes, 10).unwrap();
+/// `++/2`
+pub fn concatenate_2(list: Term, term: Term, process: &Process) -> Result {
+    let vec: Vec<Term> = list.proper_list_iter()?.collect();
 
-                        process.integer(big_int)?
-                    };
+    process
+        .improper_list_from_slice(&vec, term)
+        .map_err(|error| error.into())
+}
 
-                    Some(ceil_term)
-                }
-                _ => None,
-            }
-        }
-        _ => None,
-    };
+/// `crc32/1`
+///
+/// The CRC-32 checksum of `Data`, an iodata.
+pub fn crc32_1(data: Term, process: &Process) -> Result {
+    let bytes = iodata_to_bytes(data)?;
 
-    match option_ceil {
-        Some(ceil) => Ok(ceil),
-        None => Err(badarg!().into()),
-    }
+    Ok(process.integer(digest::crc32(0, &bytes) as u64)?)
 }
 
-/// `++/2`
-pub fn concatenate_2(list: Term, term: Term, process: &Process) -> Result {
-    match list.to_typed_term().unwrap() {
-        TypedTerm::Nil => Ok(term),
-        TypedTerm::List(cons) => match cons
-            .into_iter()
-            .collect::<std::result::Result<Vec<Term>, _>>()
-        {
-            Ok(vec) => process
-                .improper_list_from_slice(&vec, term)
-                .map_err(|error| error.into()),
-            Err(ImproperList { .. }) => Err(badarg!().into()),
-        },
-        _ => Err(badarg!().into()),
-    }
+/// `crc32/2`
+///
+/// Continues `OldCrc`, the CRC-32 checksum of the previous data, with `Data`, an iodata.
+pub fn crc32_2(old_crc: Term, data: Term, process: &Process) -> Result {
+    let old_crc_u32: u32 = old_crc.try_into()?;
+    let bytes = iodata_to_bytes(data)?;
+
+    Ok(process.integer(digest::crc32(old_crc_u32, &bytes) as u64)?)
 }
 
 pub fn delete_element_2(index: Term, tuple: Term, process: &Process) -> Result {
-    let initial_inner_tuple: Boxed<Tuple> = tuple.try_into()?;
-    let ZeroBasedIndex(index_zero_based): ZeroBasedIndex = index.try_into()?;
-    let initial_len = initial_inner_tuple.len();
-
-    if index_zero_based < initial_len {
-        let smaller_len = initial_len - 1;
-        let smaller_element_iterator =
-            initial_inner_tuple
-                .iter()
-                .enumerate()
-                .filter_map(|(old_index, old_term)| {
-                    if old_index == index_zero_based {
-                        None
-                    } else {
-                        Some(old_term)
-                    }
-                });
-        let smaller_tuple = process.tuple_from_iter(smaller_element_iterator, smaller_len)?;
+    let tuple = args::check(args::tuple(), 2, tuple, process)?;
+    let len = tuple.len() as isize;
+    let index = args::check(args::integer(1..=len), 1, index, process)? as usize;
+    let smaller_tuple = process.tuple_from_slices(&[&tuple[..(index - 1)], &tuple[index..]])?;
 
-        Ok(smaller_tuple)
-    } else {
-        Err(badarg!().into())
-    }
+    Ok(smaller_tuple)
+}
+
+/// `display/1`
+///
+/// Writes `term` as `~w` does, and a newline, straight to the standard error of the runtime.  The
+/// io server and group leader aren't involved, so it works even while debugging them.
+pub fn display_1(term: Term) -> Result {
+    system::io::eputs(&otp::io_lib::write(term));
+
+    Ok(true.into())
+}
+
+/// `display_string/1`
+///
+/// Writes the characters of `string` straight to the standard error of the runtime, without a
+/// newline.
+pub fn display_string_1(string: Term) -> Result {
+    let string_string = list_to_string(string)?;
+    system::stdio::write_stderr(string_string.as_bytes());
+
+    Ok(true.into())
 }
 
 /// `div/2` infix operator.  Integer division.
@@ -761,6 +743,29 @@ pub fn error_2(reason: Term, arguments: Term) -> Result {
     Err(error!(reason, Some(arguments)).into())
 }
 
+pub fn floor_1(number: Term, process: &Process) -> Result {
+    round_number(number, f64::floor, process)
+}
+
+pub fn group_leader_0(process: &Process) -> Result {
+    Ok(unsafe { process.group_leader_pid().as_term() })
+}
+
+/// Makes `group_leader` the group leader of the local process `pid`.
+pub fn group_leader_2(group_leader: Term, pid: Term, arc_process: Arc<Process>) -> Result {
+    let group_leader_pid: Pid = group_leader.try_into()?;
+    let pid_pid: Pid = pid.try_into()?;
+
+    match pid_to_self_or_process(pid_pid, &arc_process) {
+        Some(pid_arc_process) => {
+            pid_arc_process.set_group_leader_pid(group_leader_pid);
+
+            Ok(true.into())
+        }
+        None => Err(badarg!().into()),
+    }
+}
+
 pub fn hd_1(list: Term) -> Result {
     let cons: Boxed<Cons> = list.try_into()?;
 
@@ -768,33 +773,19 @@ pub fn hd_1(list: Term) -> Result {
 }
 
 pub fn insert_element_3(index: Term, tuple: Term, element: Term, process: &Process) -> Result {
-    let initial_inner_tuple: Boxed<Tuple> = tuple.try_into()?;
-    let ZeroBasedIndex(index_zero_based): ZeroBasedIndex = index.try_into()?;
-
-    let length = initial_inner_tuple.len();
+    let tuple = args::check(args::tuple(), 2, tuple, process)?;
+    // can be one past the length when inserting at the end
+    let len = tuple.len() as isize;
+    let index = args::check(args::integer(1..=(len + 1)), 1, index, process)? as usize;
+    let larger_tuple =
+        process.tuple_from_slices(&[&tuple[..(index - 1)], &[element], &tuple[(index - 1)..]])?;
 
-    // can be equal to arity when insertion is at the end
-    if index_zero_based <= length {
-        if index_zero_based == 0 {
-            process.tuple_from_slices(&[&[element], &initial_inner_tuple[..]])
-        } else if index_zero_based < length {
-            process.tuple_from_slices(&[
-                &initial_inner_tuple[..index_zero_based],
-                &[element],
-                &initial_inner_tuple[index_zero_based..],
-            ])
-        } else {
-            process.tuple_from_slices(&[&initial_inner_tuple[..], &[element]])
-        }
-        .map_err(|error| error.into())
-    } else {
-        Err(badarg!().into())
-    }
+    Ok(larger_tuple)
 }
 
-/// Distribution is not supported at this time.  Always returns `false`.
+/// Whether the node was started with a name, such as with `-name` or `-sname`.
 pub fn is_alive_0() -> Term {
-    false.into()
+    node::is_alive().into()
 }
 
 pub fn is_atom_1(term: Term) -> Term {
@@ -884,21 +875,10 @@ pub fn is_tuple_1(term: Term) -> Term {
     term.is_tuple().into()
 }
 
-pub fn length_1(list: Term, process: &Process) -> Result {
-    match list.to_typed_term().unwrap() {
-        TypedTerm::Nil => Ok(0.into()),
-        TypedTerm::List(cons) => match cons.count() {
-            Some(count) => Ok(process.integer(count)?),
-            None => Err(badarg!().into()),
-        },
-        _ => Err(badarg!().into()),
-    }
-}
-
 pub fn list_to_atom_1(string: Term) -> Result {
     list_to_string(string).and_then(|s| match Atom::try_from_str(s) {
         Ok(atom) => unsafe { Ok(atom.as_term()) },
-        Err(_) => Err(badarg!().into()),
+        Err(error) => Err(error.into()),
     })
 }
 
@@ -910,59 +890,9 @@ pub fn list_to_existing_atom_1(string: Term) -> Result {
 }
 
 pub fn list_to_binary_1(iolist: Term, process: &Process) -> Result {
-    match iolist.to_typed_term().unwrap() {
-        TypedTerm::Nil | TypedTerm::List(_) => {
-            let mut byte_vec: Vec<u8> = Vec::new();
-            let mut stack: Vec<Term> = vec![iolist];
-
-            while let Some(top) = stack.pop() {
-                match top.to_typed_term().unwrap() {
-                    TypedTerm::SmallInteger(small_integer) => {
-                        let top_byte = small_integer.try_into()?;
-
-                        byte_vec.push(top_byte);
-                    }
-                    TypedTerm::Nil => (),
-                    TypedTerm::List(boxed_cons) => {
-                        // @type iolist :: maybe_improper_list(byte() | binary() | iolist(),
-                        // binary() | []) means that `byte()` isn't allowed
-                        // for `tail`s unlike `head`.
+    let byte_vec = iolist_to_bytes(iolist)?;
 
-                        let tail = boxed_cons.tail;
-
-                        if tail.is_smallint() {
-                            return Err(badarg!().into());
-                        } else {
-                            stack.push(tail);
-                        }
-
-                        stack.push(boxed_cons.head);
-                    }
-                    TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
-                        TypedTerm::HeapBinary(heap_binary) => {
-                            byte_vec.extend_from_slice(heap_binary.as_bytes());
-                        }
-                        TypedTerm::SubBinary(subbinary) => {
-                            if subbinary.is_binary() {
-                                if subbinary.is_aligned() {
-                                    byte_vec.extend(unsafe { subbinary.as_bytes() });
-                                } else {
-                                    byte_vec.extend(subbinary.full_byte_iter());
-                                }
-                            } else {
-                                return Err(badarg!().into());
-                            }
-                        }
-                        _ => return Err(badarg!().into()),
-                    },
-                    _ => return Err(badarg!().into()),
-                }
-            }
-
-            Ok(process.binary_from_bytes(byte_vec.as_slice()).unwrap())
-        }
-        _ => Err(badarg!().into()),
-    }
+    Ok(process.binary_from_bytes(byte_vec.as_slice()).unwrap())
 }
 
 pub fn list_to_bitstring_1(iolist: Term, process: &Process) -> Result {
@@ -1017,6 +947,18 @@ pub fn list_to_bitstring_1(iolist: Term, process: &Process) -> Result {
                                 }
                             }
                         }
+                        TypedTerm::ProcBin(process_binary) => {
+                            if partial_byte_bit_count == 0 {
+                                byte_vec.extend_from_slice(process_binary.as_bytes());
+                            } else {
+                                for byte in process_binary.as_bytes() {
+                                    partial_byte |= byte >> partial_byte_bit_count;
+                                    byte_vec.push(partial_byte);
+
+                                    partial_byte = byte << (8 - partial_byte_bit_count);
+                                }
+                            }
+                        }
                         TypedTerm::SubBinary(subbinary) => {
                             if partial_byte_bit_count == 0 {
                                 if subbinary.is_aligned() {
@@ -1102,19 +1044,76 @@ pub fn list_to_pid_1(string: Term, process: &Process) -> Result {
 }
 
 pub fn list_to_tuple_1(list: Term, process: &Process) -> Result {
-    match list.to_typed_term().unwrap() {
-        TypedTerm::Nil => process.tuple_from_slices(&[]).map_err(|error| error.into()),
-        TypedTerm::List(cons) => {
-            let vec: Vec<Term> = cons.into_iter().collect::<std::result::Result<_, _>>()?;
+    let proper_list_iter = list.proper_list_iter()?;
+    let len = proper_list_iter.len();
 
-            process.tuple_from_slice(&vec).map_err(|error| error.into())
-        }
-        _ => Err(badarg!().into()),
-    }
+    process
+        .tuple_from_iter(proper_list_iter, len)
+        .map_err(|error| error.into())
 }
 
 pub fn make_ref_0(process: &Process) -> Result {
-    process.next_reference().map_err(|error| error.into())
+    let reference = process.next_reference()?;
+    let reference_reference: Boxed<Reference> = reference.try_into().unwrap();
+    // a receive that only matches messages containing `reference` can skip those already queued
+    process
+        .mailbox
+        .lock()
+        .borrow_mut()
+        .recv_mark(*reference_reference);
+
+    Ok(reference)
+}
+
+/// The largest arity `make_tuple/2,3` allow, as on the BEAM, so that a tuple that can't be made
+/// fails with `badarg` before its elements are allocated
+pub const MAX_TUPLE_ARITY: isize = (1 << 24) - 1;
+
+pub fn make_tuple_2(arity: Term, initial_value: Term, process: &Process) -> Result {
+    let (arity,) = args!(process, arity => integer(0..=MAX_TUPLE_ARITY))?;
+    let tuple = process.tuple_from_iter(
+        core::iter::repeat(initial_value).take(arity as usize),
+        arity as usize,
+    )?;
+
+    Ok(tuple)
+}
+
+/// Like `make_tuple/2`, but with the elements at the positions in `init_list`, a list of
+/// `{Position, Term}`, set to their terms.  Later entries for the same position win.
+pub fn make_tuple_3(
+    arity: Term,
+    default_value: Term,
+    init_list: Term,
+    process: &Process,
+) -> Result {
+    let (arity,) = args!(process, arity => integer(0..=MAX_TUPLE_ARITY))?;
+    let mut elements = vec![default_value; arity as usize];
+
+    let iter = init_list
+        .list_iter()
+        .map_err(|_| badarg!(3, "not a list"))?;
+
+    for result in iter {
+        let entry = result.map_err(|_| badarg!(3, "not a proper list"))?;
+        let (position, term) = match entry.tuple_elements() {
+            Ok(mut entry_elements) if entry_elements.len() == 2 => (
+                entry_elements.next().unwrap(),
+                entry_elements.next().unwrap(),
+            ),
+            _ => return Err(badarg!(3, "not a list of {Position, Term}").into()),
+        };
+        let position: usize = match position.try_into() {
+            Ok(position) if 1 <= position && position <= elements.len() => position,
+            _ => return Err(badarg!(3, "position out of range").into()),
+        };
+
+        elements[position - 1] = term;
+    }
+
+    process
+        .tuple_from_slice(&elements)
+        .map_err(|error| error.into())
 }
 
 pub fn map_get_2(key: Term, map: Term, process: &Process) -> Result {
@@ -1152,6 +1151,43 @@ pub fn max_2(term1: Term, term2: Term) -> Term {
     term2.max(term1)
 }
 
+/// `md5/1`
+///
+/// The MD5 digest of `Data`, an iodata, as a 16-byte binary.
+pub fn md5_1(data: Term, process: &Process) -> Result {
+    let bytes = iodata_to_bytes(data)?;
+
+    Ok(process.binary_from_bytes(&Md5::digest(&bytes))?)
+}
+
+/// `md5_final/1`
+///
+/// The MD5 digest of all the data given to `md5_update/2` for `Context`.
+pub fn md5_final_1(context: Term, process: &Process) -> Result {
+    let md5 = md5_context(context)?;
+
+    Ok(process.binary_from_bytes(&md5.finalize())?)
+}
+
+/// `md5_init/0`
+///
+/// Starts an MD5 digest.  The context is a binary that is only meant to be passed to
+/// `md5_update/2` and `md5_final/1`.
+pub fn md5_init_0(process: &Process) -> Result {
+    Ok(process.binary_from_bytes(&Md5::new().to_bytes())?)
+}
+
+/// `md5_update/2`
+///
+/// Continues the MD5 digest of `Context` with `Data`, an iodata, returning the new context.
+pub fn md5_update_2(context: Term, data: Term, process: &Process) -> Result {
+    let mut md5 = md5_context(context)?;
+    let bytes = iodata_to_bytes(data)?;
+    md5.update(&bytes);
+
+    Ok(process.binary_from_bytes(&md5.to_bytes())?)
+}
+
 /// `min/2`
 ///
 /// Returns the smallest of `Term1` and `Term2`. If the terms are equal, `Term1` is returned.
@@ -1212,8 +1248,31 @@ pub fn negate_1(number: Term, process: &Process) -> Result {
     }
 }
 
+/// The name of this node, or `nonode@nohost` when it is not alive.
 pub fn node_0() -> Term {
-    atom_unchecked(node::DEAD)
+    unsafe { node::name().as_term() }
+}
+
+/// The node that the pid, port or reference `term` belongs to.
+pub fn node_1(term: Term) -> Result {
+    let option_node_name = match term.to_typed_term().unwrap() {
+        TypedTerm::Pid(_) | TypedTerm::Port(_) => Some(node::name()),
+        TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+            TypedTerm::Reference(_) | TypedTerm::ResourceReference(_) => Some(node::name()),
+            TypedTerm::ExternalPid(external_pid) => Some(external_pid.node().name()),
+            TypedTerm::ExternalPort(external_port) => Some(external_port.node().name()),
+            TypedTerm::ExternalReference(external_reference) => {
+                Some(external_reference.node().name())
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+
+    match option_node_name {
+        Some(node_name) => Ok(unsafe { node_name.as_term() }),
+        None => Err(badarg!().into()),
+    }
 }
 
 /// `not/1` prefix operator.
@@ -1224,6 +1283,23 @@ pub fn not_1(boolean: Term) -> Result {
     Ok(output.into())
 }
 
+/// Opens a port to the external program `Command` of `{spawn, Command}`, where `Command` is a
+/// string or binary run by the shell.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn open_port_2(port_name: Term, port_settings: Term, arc_process: Arc<Process>) -> Result {
+    let command = spawn_command(port_name)?;
+    let options: port::Options = port_settings.try_into()?;
+
+    match port::open(&arc_process, &command, options) {
+        Ok(port) => Ok(unsafe { port.as_term() }),
+        Err(error) => {
+            let reason = atom_unchecked(&format!("{:?}", error.kind()).to_lowercase());
+
+            Err(error!(reason).into())
+        }
+    }
+}
+
 /// `or/2` infix operator.
 ///
 /// **NOTE: NOT SHORT-CIRCUITING!**
@@ -1231,6 +1307,39 @@ pub fn or_2(left_boolean: Term, right_boolean: Term) -> Result {
     boolean_infix_operator!(left_boolean, right_boolean, |)
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+pub fn port_close_1(port: Term) -> Result {
+    match port.to_typed_term().unwrap() {
+        TypedTerm::Port(port) if port::close(port) => Ok(true.into()),
+        _ => Err(badarg!().into()),
+    }
+}
+
+/// `port_command/2`.  Returns `None` if the port is busy, in which case `process` now waits for it
+/// not to be busy and calls this again once it is woken.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn port_command_2(
+    port: Term,
+    data: Term,
+    process: &Process,
+) -> core::result::Result<Option<Term>, Exception> {
+    port_command(port, data, Default::default(), process)
+}
+
+/// `port_command/2` with the `force` and `nosuspend` `options`.  Returns `false` if the port is
+/// busy and the command had `nosuspend`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn port_command_3(
+    port: Term,
+    data: Term,
+    options: Term,
+    process: &Process,
+) -> core::result::Result<Option<Term>, Exception> {
+    let command_options: port::CommandOptions = options.try_into()?;
+
+    port_command(port, data, command_options, process)
+}
+
 pub fn raise_3(class: Term, reason: Term, stacktrace: Term) -> Result {
     let class_class: Class = class.try_into()?;
 
@@ -1243,6 +1352,16 @@ pub fn raise_3(class: Term, reason: Term, stacktrace: Term) -> Result {
     Err(runtime_exception.into())
 }
 
+/// The pids of the live processes.  The process table is read a chunk at a time by
+/// `registry::processes`, so this doesn't block spawning on other schedulers while it runs.
+pub fn processes_0(process: &Process) -> Result {
+    let pids: Vec<Term> = registry::processes()
+        .map(|arc_process| arc_process.pid_term())
+        .collect();
+
+    process.list_from_slice(&pids).map_err(|error| error.into())
+}
+
 pub fn read_timer_1(timer_reference: Term, process: &Process) -> Result {
     read_timer(timer_reference, Default::default(), process)
 }
@@ -1287,6 +1406,11 @@ pub fn rem_2(dividend: Term, divisor: Term, process: &Process) -> Result {
     integer_infix_operator!(dividend, divisor, process, %)
 }
 
+/// Rounds half-way floats away from zero, so `round(2.5)` is `3` and `round(-2.5)` is `-3`.
+pub fn round_1(number: Term, process: &Process) -> Result {
+    round_number(number, f64::round, process)
+}
+
 pub fn send_2(destination: Term, message: Term, process: &Process) -> Result {
     send(destination, message, Default::default(), process).map(|sent| match sent {
         Sent::Sent => message,
@@ -1545,49 +1669,40 @@ pub fn start_timer_4(
 }
 
 pub fn subtract_list_2(minuend: Term, subtrahend: Term, process: &Process) -> Result {
-    match (
-        minuend.to_typed_term().unwrap(),
-        subtrahend.to_typed_term().unwrap(),
-    ) {
-        (TypedTerm::Nil, TypedTerm::Nil) => Ok(minuend),
-        (TypedTerm::Nil, TypedTerm::List(subtrahend_cons)) => {
-            if subtrahend_cons.is_proper() {
-                Ok(minuend)
-            } else {
-                Err(badarg!().into())
-            }
-        }
-        (TypedTerm::List(minuend_cons), TypedTerm::Nil) => {
-            if minuend_cons.is_proper() {
-                Ok(minuend)
-            } else {
-                Err(badarg!().into())
-            }
-        }
-        (TypedTerm::List(minuend_cons), TypedTerm::List(subtrahend_cons)) => {
-            match minuend_cons
-                .into_iter()
-                .collect::<std::result::Result<Vec<Term>, _>>()
-            {
-                Ok(mut minuend_vec) => {
-                    for result in subtrahend_cons.into_iter() {
-                        match result {
-                            Ok(subtrahend_element) => minuend_vec.remove_item(&subtrahend_element),
-                            Err(ImproperList { .. }) => return Err(badarg!().into()),
-                        };
-                    }
+    let minuend_iter = minuend.proper_list_iter()?;
+    let subtrahend_iter = subtrahend.proper_list_iter()?;
 
-                    process
-                        .list_from_slice(&minuend_vec)
-                        .map_err(|error| error.into())
-                }
-                Err(ImproperList { .. }) => Err(badarg!().into()),
-            }
+    if minuend_iter.len() == 0 || subtrahend_iter.len() == 0 {
+        Ok(minuend)
+    } else {
+        let mut minuend_vec: Vec<Term> = minuend_iter.collect();
+
+        for subtrahend_element in subtrahend_iter {
+            minuend_vec.remove_item(&subtrahend_element);
         }
-        _ => Err(badarg!().into()),
+
+        process
+            .list_from_slice(&minuend_vec)
+            .map_err(|error| error.into())
     }
 }
 
+/// `term_to_binary/1`
+///
+/// The external term format encoding of `term`, as `term_to_binary/2` with no options
+pub fn term_to_binary_1(term: Term, process: &Process) -> Result {
+    term_to_binary(term, Default::default(), process)
+}
+
+/// `term_to_binary/2`
+///
+/// `options` are `compressed`, `{compressed, 0..9}` and `{minor_version, 0..2}`.
+pub fn term_to_binary_2(term: Term, options: Term, process: &Process) -> Result {
+    let options: external_term_format::Options = options.try_into()?;
+
+    term_to_binary(term, options, process)
+}
+
 pub fn throw_1(reason: Term) -> Result {
     Err(throw!(reason).into())
 }
@@ -1598,15 +1713,19 @@ pub fn tl_1(list: Term) -> Result {
     Ok(cons.tail)
 }
 
+pub fn trunc_1(number: Term, process: &Process) -> Result {
+    round_number(number, f64::trunc, process)
+}
+
 pub fn tuple_size_1(tuple: Term, process: &Process) -> Result {
-    let tuple: Boxed<Tuple> = tuple.try_into()?;
+    let (tuple,) = args!(process, tuple => tuple())?;
     let size = process.integer(tuple.len())?;
 
     Ok(size)
 }
 
 pub fn tuple_to_list_1(tuple: Term, process: &Process) -> Result {
-    let tuple: Boxed<Tuple> = tuple.try_into()?;
+    let (tuple,) = args!(process, tuple => tuple())?;
     let mut heap = process.acquire_heap();
     let mut acc = Term::NIL;
 
@@ -1617,6 +1736,13 @@ pub fn tuple_to_list_1(tuple: Term, process: &Process) -> Result {
     Ok(acc)
 }
 
+/// Deactivates `reference` if it is an alias of `process`, returning whether it was
+pub fn unalias_1(reference: Term, process: &Process) -> Result {
+    let reference_reference: Boxed<Reference> = reference.try_into()?;
+
+    Ok(alias::unalias(process, &reference_reference).into())
+}
+
 pub fn unregister_1(name: Term) -> Result {
     let atom: Atom = name.try_into()?;
 
@@ -1732,24 +1858,132 @@ fn is_record(term: Term, record_tag: Term, size: Option<Term>) -> Result {
     }
 }
 
-fn list_to_string(list: Term) -> std::result::Result<String, Exception> {
-    match list.to_typed_term().unwrap() {
-        TypedTerm::Nil => Ok("".to_owned()),
-        TypedTerm::List(cons) => cons
-            .into_iter()
-            .map(|result| match result {
-                Ok(term) => {
-                    let c: char = term.try_into()?;
-
-                    Ok(c)
+/// The command of `{spawn, Command}`
+#[cfg(not(target_arch = "wasm32"))]
+fn spawn_command(port_name: Term) -> std::result::Result<String, Exception> {
+    let tuple: Boxed<Tuple> = port_name.try_into()?;
+
+    if tuple.len() == 2 {
+        let name: Atom = tuple[0].try_into()?;
+
+        match name.name() {
+            "spawn" => match tuple[1].to_typed_term().unwrap() {
+                TypedTerm::List(_) => list_to_string(tuple[1]),
+                _ => {
+                    let command: String = tuple[1].try_into()?;
+
+                    Ok(command)
+                }
+            },
+            _ => Err(badarg!().into()),
+        }
+    } else {
+        Err(badarg!().into())
+    }
+}
+
+/// The bytes of `iodata`, which is a binary or an iolist
+pub fn iodata_to_bytes(iodata: Term) -> std::result::Result<Vec<u8>, Exception> {
+    match iodata.to_typed_term().unwrap() {
+        TypedTerm::Nil | TypedTerm::List(_) => iolist_to_bytes(iodata),
+        _ => {
+            let bytes: Vec<u8> = iodata.try_into()?;
+
+            Ok(bytes)
+        }
+    }
+}
+
+/// The bytes of `iolist`, which, unlike an iodata, must be a list
+fn iolist_to_bytes(iolist: Term) -> std::result::Result<Vec<u8>, Exception> {
+    match iolist.to_typed_term().unwrap() {
+        TypedTerm::Nil | TypedTerm::List(_) => {
+            let mut byte_vec: Vec<u8> = Vec::new();
+            let mut stack: Vec<Term> = vec![iolist];
+
+            while let Some(top) = stack.pop() {
+                match top.to_typed_term().unwrap() {
+                    TypedTerm::SmallInteger(small_integer) => {
+                        let top_byte = small_integer.try_into()?;
+
+                        byte_vec.push(top_byte);
+                    }
+                    TypedTerm::Nil => (),
+                    TypedTerm::List(boxed_cons) => {
+                        // @type iolist :: maybe_improper_list(byte() | binary() | iolist(),
+                        // binary() | []) means that `byte()` isn't allowed
+                        // for `tail`s unlike `head`.
+
+                        let tail = boxed_cons.tail;
+
+                        if tail.is_smallint() {
+                            return Err(badarg!().into());
+                        } else {
+                            stack.push(tail);
+                        }
+
+                        stack.push(boxed_cons.head);
+                    }
+                    TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+                        TypedTerm::HeapBinary(heap_binary) => {
+                            byte_vec.extend_from_slice(heap_binary.as_bytes());
+                        }
+                        TypedTerm::ProcBin(process_binary) => {
+                            byte_vec.extend_from_slice(process_binary.as_bytes());
+                        }
+                        TypedTerm::SubBinary(subbinary) => {
+                            if subbinary.is_binary() {
+                                if subbinary.is_aligned() {
+                                    byte_vec.extend(unsafe { subbinary.as_bytes() });
+                                } else {
+                                    byte_vec.extend(subbinary.full_byte_iter());
+                                }
+                            } else {
+                                return Err(badarg!().into());
+                            }
+                        }
+                        _ => return Err(badarg!().into()),
+                    },
+                    _ => return Err(badarg!().into()),
                 }
-                Err(ImproperList { .. }) => Err(badarg!().into()),
-            })
-            .collect::<std::result::Result<String, Exception>>(),
+            }
+
+            Ok(byte_vec)
+        }
         _ => Err(badarg!().into()),
     }
 }
 
+/// The bytes of `name` in latin1, or `None` if it has characters over 255
+fn latin1_byte_vec(name: &str) -> Option<Vec<u8>> {
+    name.chars()
+        .map(|c| {
+            if (c as u32) <= 0xFF {
+                Some(c as u8)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+fn list_to_string(list: Term) -> std::result::Result<String, Exception> {
+    list.proper_list_iter()?
+        .map(|element| -> std::result::Result<char, Exception> {
+            let c: char = element.try_into()?;
+
+            Ok(c)
+        })
+        .collect()
+}
+
+/// The MD5 digest saved in `context` by `md5_init/0` or `md5_update/2`
+fn md5_context(context: Term) -> std::result::Result<Md5, Exception> {
+    let bytes: Vec<u8> = context.try_into()?;
+
+    Md5::from_bytes(&bytes).ok_or_else(|| badarg!().into())
+}
+
 fn next_decimal(cons: Boxed<Cons>) -> std::result::Result<(usize, Term), Exception> {
     next_decimal_digit(cons)
         .and_then(|(first_digit, first_tail)| rest_decimal_digits(first_digit, first_tail))
@@ -1764,6 +1998,28 @@ fn next_decimal_digit(cons: Boxed<Cons>) -> std::result::Result<(u8, Term), Exce
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+fn port_command(
+    port: Term,
+    data: Term,
+    options: port::CommandOptions,
+    process: &Process,
+) -> core::result::Result<Option<Term>, Exception> {
+    match port.to_typed_term().unwrap() {
+        TypedTerm::Port(port) => {
+            let bytes = iodata_to_bytes(data)?;
+
+            match port::command(port, bytes, options, process) {
+                port::Commanded::Queued => Ok(Some(true.into())),
+                port::Commanded::Busy => Ok(Some(false.into())),
+                port::Commanded::Suspended => Ok(None),
+                port::Commanded::NotOpen => Err(badarg!().into()),
+            }
+        }
+        _ => Err(badarg!().into()),
+    }
+}
+
 fn read_timer(timer_reference: Term, options: timer::read::Options, process: &Process) -> Result {
     match timer_reference.to_typed_term().unwrap() {
         TypedTerm::Boxed(unboxed_timer_reference) => {
@@ -1827,6 +2083,115 @@ fn rest_decimal_digits(
     }
 }
 
+/// Rounds floats to an integer with `round`, while integers are already round.
+fn round_number<R>(number: Term, round: R, process: &Process) -> Result
+where
+    R: FnOnce(f64) -> f64,
+{
+    let option_rounded = match number.to_typed_term().unwrap() {
+        TypedTerm::SmallInteger(_) => Some(number),
+        TypedTerm::Boxed(boxed) => {
+            match boxed.to_typed_term().unwrap() {
+                TypedTerm::BigInteger(_) => Some(number),
+                TypedTerm::Float(float) => {
+                    let inner: f64 = float.into();
+                    let rounded_inner = round(inner);
+
+                    // skip creating a BigInt if float can fit in small integer.
+                    let rounded_term = if (SmallInteger::MIN_VALUE as f64).max(Float::INTEGRAL_MIN)
+                        <= rounded_inner
+                        && rounded_inner
+                            <= (SmallInteger::MAX_VALUE as f64).min(Float::INTEGRAL_MAX)
+                    {
+                        process.integer(rounded_inner as isize)?
+                    } else {
+                        let rounded_string = rounded_inner.to_string();
+                        let rounded_bytes = rounded_string.as_bytes();
+                        let big_int = BigInt::parse_bytes(rounded_bytes, 10).unwrap();
+
+                        process.integer(big_int)?
+                    };
+
+                    Some(rounded_term)
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+
+    match option_rounded {
+        Some(rounded) => Ok(rounded),
+        None => Err(badarg!().into()),
+    }
+}
+
+/// Shifts `integer` left by `shift` bits, or right by `-shift` bits when `shift` is negative.  Right
+/// shifts are arithmetic shifts of the two's complement, so negative integers round towards
+/// negative infinity, as they do in BEAM.
+fn shift_left(integer: Term, shift: isize, process: &Process) -> Result {
+    let big_int: BigInt = match integer.to_typed_term().unwrap() {
+        TypedTerm::SmallInteger(small_integer) => {
+            let integer_isize: isize = small_integer.into();
+
+            if shift <= 0 {
+                let shifted = integer_isize >> right_shift(shift).min(MAX_SHIFT);
+
+                return Ok(process.integer(shifted)?);
+            } else if (shift as usize) <= MAX_SHIFT {
+                let shifted = integer_isize << (shift as usize);
+
+                // no bits overflowed
+                if (shifted >> (shift as usize)) == integer_isize {
+                    return Ok(process.integer(shifted)?);
+                }
+            }
+
+            integer_isize.into()
+        }
+        TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+            TypedTerm::BigInteger(big_integer) => {
+                let big_int: &BigInt = big_integer.as_ref().into();
+
+                big_int.clone()
+            }
+            _ => return Err(badarith!().into()),
+        },
+        _ => return Err(badarith!().into()),
+    };
+
+    let shifted = if 0 <= shift {
+        if big_int.is_zero() {
+            big_int
+        } else if MAX_SHIFTED_BITS < big_int.bits().saturating_add(shift as usize) {
+            return Err(error!(atom_unchecked("system_limit")).into());
+        } else {
+            big_int << (shift as usize)
+        }
+    } else {
+        let shift_usize = right_shift(shift);
+
+        // `BigInt`'s `>>` rounds towards zero, so shift the one's complement, which isn't negative,
+        // instead
+        if big_int.sign() == Sign::Minus {
+            -((-big_int - 1) >> shift_usize) - 1
+        } else {
+            big_int >> shift_usize
+        }
+    };
+
+    Ok(process.integer(shifted)?)
+}
+
+/// The number of bits a negative `shift` shifts right by, which for `isize::MIN` is past
+/// `isize::MAX`
+fn right_shift(shift: isize) -> usize {
+    match shift.checked_neg() {
+        Some(negated) => negated as usize,
+        None => (isize::max_value() as usize) + 1,
+    }
+}
+
 fn skip_char(cons: Boxed<Cons>, skip: char) -> Result {
     let c: char = cons.head.try_into()?;
 
@@ -1886,3 +2251,12 @@ fn start_timer(
         Err(badarg!().into())
     }
 }
+
+/// Terms that can't be encoded, such as bitstrings that aren't binaries, are `badarg`
+fn term_to_binary(term: Term, options: external_term_format::Options, process: &Process) -> Result {
+    match external_term_format::encode_with_options(term, options) {
+        Ok(bytes) => Ok(process.binary_from_bytes(&bytes)?),
+        Err(external_term_format::Error::Alloc(alloc)) => Err(alloc.into()),
+        Err(_) => Err(badarg!().into()),
+    }
+}
@@ -0,0 +1,90 @@
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::exception::{self, Exception};
+use liblumen_alloc::erts::process::code;
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{Atom, Term};
+use liblumen_alloc::ModuleFunctionArity;
+
+use crate::trap::{self, Trap};
+
+/// Conses `trap::STEP` bytes of the binary per reduction, so that converting a large binary traps
+pub const TRAP: Trap = Trap {
+    state_len: 3,
+    start,
+    step,
+};
+
+pub fn place_frame_with_arguments(
+    process: &Process,
+    placement: Placement,
+    binary: Term,
+) -> Result<(), Alloc> {
+    TRAP.place_frame_with_arguments(process, placement, frame(), &[binary])
+}
+
+pub fn native(process: &Process, binary: Term) -> exception::Result {
+    TRAP.complete(process, &[binary])
+}
+
+// Private
+
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    TRAP.code(arc_process)
+}
+
+fn frame() -> Frame {
+    Frame::new(module_function_arity(), code)
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("binary_to_list").unwrap()
+}
+
+fn module_function_arity() -> Arc<ModuleFunctionArity> {
+    Arc::new(ModuleFunctionArity {
+        module: super::module(),
+        function: function(),
+        arity: 1,
+    })
+}
+
+/// The state is the binary, the number of bytes at its end that are in the list so far, and that
+/// list.  The list is consed from the last byte, so that it doesn't have to be reversed.
+fn start(arguments: &[Term]) -> Vec<Term> {
+    vec![arguments[0], 0_u8.into(), Term::NIL]
+}
+
+fn step(process: &Process, state: &mut [Term]) -> Result<Option<Term>, Exception> {
+    let (bytes,) = args!(process, state[0] => binary())?;
+    let mut consed: usize = state[1].try_into().unwrap();
+    let mut list = state[2];
+
+    for byte in bytes[..(bytes.len() - consed)]
+        .iter()
+        .rev()
+        .take(trap::STEP)
+    {
+        list = process.cons((*byte).into(), list)?;
+        consed += 1;
+    }
+
+    if consed == bytes.len() {
+        Ok(Some(list))
+    } else {
+        state[1] = process.integer(consed)?;
+        state[2] = list;
+
+        Ok(None)
+    }
+}
@@ -1,6 +1,13 @@
-use super::*;
+use proptest::strategy::{Just, Strategy};
+use proptest::test_runner::{Config, TestRunner};
+use proptest::{prop_assert, prop_assert_eq};
 
-use proptest::strategy::Strategy;
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::term::Term;
+
+use crate::otp::erlang::binary_to_list_1::native;
+use crate::scheduler::with_process_arc;
+use crate::test::strategy;
 
 #[test]
 fn without_binary_errors_badarg() {
@@ -9,10 +16,7 @@ fn without_binary_errors_badarg() {
             .run(
                 &strategy::term::is_not_binary(arc_process.clone()),
                 |binary| {
-                    prop_assert_eq!(
-                        erlang::binary_to_list_1(binary, &arc_process),
-                        Err(badarg!().into())
-                    );
+                    prop_assert_eq!(native(&arc_process, binary), Err(badarg!().into()));
 
                     Ok(())
                 },
@@ -67,7 +71,7 @@ fn with_binary_returns_list_of_bytes() {
                         len => unimplemented!("len = {:?}", len),
                     };
 
-                    prop_assert_eq!(erlang::binary_to_list_1(binary, &arc_process), Ok(list));
+                    prop_assert_eq!(native(&arc_process, binary), Ok(list));
 
                     Ok(())
                 },
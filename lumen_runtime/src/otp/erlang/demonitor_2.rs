@@ -20,8 +20,7 @@ use liblumen_alloc::erts::term::{Atom, Boxed, Reference, Term};
 use liblumen_alloc::ModuleFunctionArity;
 
 use crate::otp::erlang::demonitor_2::options::Options;
-use crate::process::monitor::is_down;
-use crate::registry::pid_to_process;
+use crate::process::monitor::{self, is_down};
 
 pub fn place_frame_with_arguments(
     process: &Process,
@@ -59,35 +58,31 @@ fn demonitor(
     reference: &Reference,
     Options { flush, info }: Options,
 ) -> exception::Result {
-    match monitoring_process.demonitor(reference) {
-        Some(monitored_pid) => {
-            match pid_to_process(&monitored_pid) {
-                Some(monitored_arc_proces) => match monitored_arc_proces.demonitored(reference) {
-                    Some(monitoring_pid) => assert_eq!(monitoring_process.pid(), monitoring_pid),
-                    None => (),
-                },
-                None => (),
-            }
-
-            if flush {
-                let flushed = self::flush(monitoring_process, reference);
-
-                if info && flushed {
-                    Ok(false.into())
-                } else {
-                    Ok(true.into())
-                }
-            } else {
-                Ok(true.into())
-            }
-        }
-        None => {
-            if info {
-                Ok(false.into())
-            } else {
-                Ok(true.into())
-            }
+    if monitor::demonitor(monitoring_process, reference) {
+        flush_demonitored(monitoring_process, reference, flush, info)
+    } else if info {
+        Ok(false.into())
+    } else {
+        Ok(true.into())
+    }
+}
+
+fn flush_demonitored(
+    monitoring_process: &Process,
+    reference: &Reference,
+    flush: bool,
+    info: bool,
+) -> exception::Result {
+    if flush {
+        let flushed = self::flush(monitoring_process, reference);
+
+        if info && flushed {
+            Ok(false.into())
+        } else {
+            Ok(true.into())
         }
+    } else {
+        Ok(true.into())
     }
 }
 
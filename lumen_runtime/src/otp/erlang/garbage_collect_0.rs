@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod test;
+
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception::Exception;
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::code::{self, result_from_exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{Atom, Term};
+use liblumen_alloc::ModuleFunctionArity;
+
+use crate::otp::erlang::garbage_collect_2;
+
+pub fn place_frame(process: &Process, placement: Placement) {
+    process.place_frame(frame(), placement);
+}
+
+// Private
+
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.reduce();
+
+    match native(arc_process) {
+        Ok(Some(true_term)) => {
+            arc_process.return_from_call(true_term)?;
+
+            Process::call_code(arc_process)
+        }
+        Ok(None) => {
+            arc_process.replace_frame(Frame::new(
+                module_function_arity(),
+                garbage_collect_2::wait_code,
+            ));
+
+            Ok(())
+        }
+        Err(exception) => result_from_exception(arc_process, exception),
+    }
+}
+
+fn frame() -> Frame {
+    Frame::new(module_function_arity(), code)
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("garbage_collect").unwrap()
+}
+
+fn module_function_arity() -> Arc<ModuleFunctionArity> {
+    Arc::new(ModuleFunctionArity {
+        module: super::module(),
+        function: function(),
+        arity: 0,
+    })
+}
+
+/// The process is collected with a full sweep once it stops running, as the terms that the code
+/// running it holds aren't roots, so `None` is returned and the process waits to be collected, as
+/// with `garbage_collect_2::native`.
+pub fn native(process: &Process) -> Result<Option<Term>, Exception> {
+    garbage_collect_2::native(process, process.pid_term(), Term::NIL)
+}
@@ -0,0 +1,20 @@
+use liblumen_alloc::erts::process::ProcessFlags;
+
+use crate::otp::erlang::garbage_collect_0::native;
+use crate::process::garbage_collect::{collect_requested, wait_for_reply};
+use crate::scheduler::with_process;
+
+#[test]
+fn returns_true_once_process_stops_running_and_is_collected_with_full_sweep() {
+    with_process(|process| {
+        assert_eq!(native(process), Ok(None));
+        assert!(process.are_flags_set(ProcessFlags::ForceGC | ProcessFlags::NeedFullSweep));
+        assert_eq!(wait_for_reply(process), None);
+
+        assert!(collect_requested(process).is_ok());
+
+        assert!(!process.are_flags_set(ProcessFlags::ForceGC));
+        assert!(!process.are_flags_set(ProcessFlags::NeedFullSweep));
+        assert_eq!(wait_for_reply(process), Some(true));
+    });
+}
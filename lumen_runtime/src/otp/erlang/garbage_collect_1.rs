@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::exception::Exception;
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::code::{self, result_from_exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{Atom, Term};
+use liblumen_alloc::ModuleFunctionArity;
+
+use crate::otp::erlang::garbage_collect_2;
+
+pub fn place_frame_with_arguments(
+    process: &Process,
+    placement: Placement,
+    pid: Term,
+) -> Result<(), Alloc> {
+    process.stack_push(pid)?;
+    process.place_frame(frame(), placement);
+
+    Ok(())
+}
+
+// Private
+
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.reduce();
+
+    let pid = arc_process.stack_pop().unwrap();
+
+    match native(arc_process, pid) {
+        Ok(Some(collected)) => {
+            arc_process.return_from_call(collected)?;
+
+            Process::call_code(arc_process)
+        }
+        Ok(None) => {
+            arc_process.replace_frame(Frame::new(
+                module_function_arity(),
+                garbage_collect_2::wait_code,
+            ));
+
+            Ok(())
+        }
+        Err(exception) => result_from_exception(arc_process, exception),
+    }
+}
+
+fn frame() -> Frame {
+    Frame::new(module_function_arity(), code)
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("garbage_collect").unwrap()
+}
+
+fn module_function_arity() -> Arc<ModuleFunctionArity> {
+    Arc::new(ModuleFunctionArity {
+        module: super::module(),
+        function: function(),
+        arity: 1,
+    })
+}
+
+/// See `garbage_collect_2::native`
+pub fn native(process: &Process, pid: Term) -> Result<Option<Term>, Exception> {
+    garbage_collect_2::native(process, pid, Term::NIL)
+}
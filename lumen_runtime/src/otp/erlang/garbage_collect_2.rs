@@ -0,0 +1,139 @@
+mod options;
+
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::exception::Exception;
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::code::{self, result_from_exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Pid, Term};
+use liblumen_alloc::ModuleFunctionArity;
+
+use crate::otp::erlang::garbage_collect_2::options::Options;
+use crate::process::garbage_collect::{request, request_own, wait_for_reply, Reply};
+use crate::registry::pid_to_process;
+
+pub fn place_frame_with_arguments(
+    process: &Process,
+    placement: Placement,
+    pid: Term,
+    options: Term,
+) -> Result<(), Alloc> {
+    process.stack_push(options)?;
+    process.stack_push(pid)?;
+    process.place_frame(frame(), placement);
+
+    Ok(())
+}
+
+/// Waits for the process that the calling process asked to be collected to be, and then returns
+/// whether it was.  The frame of `garbage_collect/0,1,2` once `native` returned `None`.
+pub(crate) fn wait_code(arc_process: &Arc<Process>) -> code::Result {
+    match wait_for_reply(arc_process) {
+        Some(collected) => {
+            arc_process.return_from_call(collected.into())?;
+
+            Process::call_code(arc_process)
+        }
+        None => Ok(()),
+    }
+}
+
+// Private
+
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.reduce();
+
+    let pid = arc_process.stack_pop().unwrap();
+    let options = arc_process.stack_pop().unwrap();
+
+    match native(arc_process, pid, options) {
+        Ok(Some(collected)) => {
+            arc_process.return_from_call(collected)?;
+
+            Process::call_code(arc_process)
+        }
+        Ok(None) => {
+            arc_process.replace_frame(Frame::new(module_function_arity(), wait_code));
+
+            Ok(())
+        }
+        Err(exception) => result_from_exception(arc_process, exception),
+    }
+}
+
+fn frame() -> Frame {
+    Frame::new(module_function_arity(), code)
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("garbage_collect").unwrap()
+}
+
+fn module_function_arity() -> Arc<ModuleFunctionArity> {
+    Arc::new(ModuleFunctionArity {
+        module: super::module(),
+        function: function(),
+        arity: 2,
+    })
+}
+
+/// Returns whether the process with `pid` was collected, which it isn't if it isn't alive, or,
+/// with the `{async, RequestId}` option, returns `async` and sends
+/// `{garbage_collect, RequestId, Collected}` to `process` instead.
+///
+/// A process that is running, such as `process` itself, is collected once it stops, so `None` is
+/// returned and `process` waits for `wait_for_reply` to return whether it was collected.
+pub fn native(process: &Process, pid: Term, options: Term) -> Result<Option<Term>, Exception> {
+    let pid_pid: Pid = pid.try_into()?;
+    let Options {
+        gc_type,
+        async_request_id,
+    } = options.try_into()?;
+
+    let reply = match async_request_id {
+        Some(request_id) => Reply::Message(request_id),
+        None => Reply::Wait,
+    };
+
+    let option_collected = if pid_pid == process.pid() {
+        request_own(process, gc_type, reply)?;
+
+        None
+    } else {
+        match pid_to_process(&pid_pid) {
+            Some(pid_arc_process) => request(process, &pid_arc_process, gc_type, reply)?,
+            None => Some(false),
+        }
+    };
+
+    match async_request_id {
+        Some(request_id) => {
+            // otherwise, it is sent once the process is collected
+            if let Some(collected) = option_collected {
+                let message = process.tuple_from_slice(&[
+                    atom_unchecked("garbage_collect"),
+                    request_id,
+                    collected.into(),
+                ])?;
+                process.send_from_self(message);
+            }
+
+            Ok(Some(atom_unchecked("async")))
+        }
+        None => {
+            let option_collected = option_collected.or_else(|| wait_for_reply(process));
+
+            Ok(option_collected.map(|collected| collected.into()))
+        }
+    }
+}
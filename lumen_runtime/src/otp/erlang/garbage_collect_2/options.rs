@@ -0,0 +1,83 @@
+use std::convert::{TryFrom, TryInto};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception::runtime;
+use liblumen_alloc::erts::term::{Atom, Boxed, Cons, Term, Tuple, TypedTerm};
+
+use crate::process::garbage_collect::Type;
+
+pub struct Options {
+    pub gc_type: Type,
+    pub async_request_id: Option<Term>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            gc_type: Default::default(),
+            async_request_id: None,
+        }
+    }
+}
+
+impl TryFrom<Boxed<Cons>> for Options {
+    type Error = runtime::Exception;
+
+    fn try_from(cons: Boxed<Cons>) -> Result<Self, Self::Error> {
+        let mut options: Options = Default::default();
+
+        for result in cons.into_iter() {
+            match result {
+                Ok(option) => {
+                    let option_tuple: Boxed<Tuple> = option.try_into()?;
+
+                    if option_tuple.len() != 2 {
+                        return Err(badarg!());
+                    }
+
+                    let name: Atom = option_tuple[0].try_into()?;
+                    let value = option_tuple[1];
+
+                    match name.name() {
+                        "async" => {
+                            options.async_request_id = Some(value);
+                        }
+                        "type" => {
+                            let value_atom: Atom = value.try_into()?;
+
+                            options.gc_type = match value_atom.name() {
+                                "major" => Type::Major,
+                                "minor" => Type::Minor,
+                                _ => return Err(badarg!()),
+                            };
+                        }
+                        _ => return Err(badarg!()),
+                    }
+                }
+                Err(_) => return Err(badarg!()),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+impl TryFrom<Term> for Options {
+    type Error = runtime::Exception;
+
+    fn try_from(term: Term) -> Result<Self, Self::Error> {
+        term.to_typed_term().unwrap().try_into()
+    }
+}
+
+impl TryFrom<TypedTerm> for Options {
+    type Error = runtime::Exception;
+
+    fn try_from(typed_term: TypedTerm) -> Result<Self, Self::Error> {
+        match typed_term {
+            TypedTerm::Nil => Ok(Default::default()),
+            TypedTerm::List(cons) => cons.try_into(),
+            _ => Err(badarg!()),
+        }
+    }
+}
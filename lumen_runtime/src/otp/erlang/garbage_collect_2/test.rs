@@ -0,0 +1,182 @@
+use proptest::prop_assert_eq;
+use proptest::test_runner::{Config, TestRunner};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::process::ProcessFlags;
+use liblumen_alloc::erts::term::{atom_unchecked, next_pid, Term};
+
+use crate::otp::erlang::garbage_collect_2::native;
+use crate::process;
+use crate::process::garbage_collect::{collect_requested, wait_for_reply};
+use crate::scheduler::{with_process, with_process_arc};
+use crate::test::{has_message, strategy};
+
+#[test]
+fn without_local_pid_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &strategy::term::is_not_local_pid(arc_process.clone()),
+                |pid| {
+                    prop_assert_eq!(native(&arc_process, pid, Term::NIL), Err(badarg!().into()));
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn without_list_options_errors_badarg() {
+    with_process(|process| {
+        let other_process = process::test(process);
+
+        assert_eq!(
+            native(process, other_process.pid_term(), atom_unchecked("minor")),
+            Err(badarg!().into())
+        );
+    });
+}
+
+#[test]
+fn with_unknown_type_errors_badarg() {
+    with_process(|process| {
+        let other_process = process::test(process);
+        let option = process
+            .tuple_from_slice(&[atom_unchecked("type"), atom_unchecked("full")])
+            .unwrap();
+        let options = process.list_from_slice(&[option]).unwrap();
+
+        assert_eq!(
+            native(process, other_process.pid_term(), options),
+            Err(badarg!().into())
+        );
+    });
+}
+
+#[test]
+fn with_non_existent_pid_returns_false() {
+    with_process(|process| {
+        assert_eq!(
+            native(process, next_pid(), Term::NIL),
+            Ok(Some(false.into()))
+        );
+    });
+}
+
+#[test]
+fn with_other_process_collects_it_now() {
+    with_process(|process| {
+        let other_process = process::test(process);
+        let option = process
+            .tuple_from_slice(&[atom_unchecked("type"), atom_unchecked("minor")])
+            .unwrap();
+        let options = process.list_from_slice(&[option]).unwrap();
+
+        assert_eq!(
+            native(process, other_process.pid_term(), options),
+            Ok(Some(true.into()))
+        );
+
+        assert!(!other_process.are_flags_set(ProcessFlags::ForceGC));
+    });
+}
+
+#[test]
+fn with_other_process_with_gc_disabled_returns_true_once_it_is_collected() {
+    with_process(|process| {
+        let other_process = process::test(process);
+        other_process.set_flags(ProcessFlags::DisableGC);
+
+        assert_eq!(
+            native(process, other_process.pid_term(), Term::NIL),
+            Ok(None)
+        );
+        assert!(other_process.are_flags_set(ProcessFlags::ForceGC));
+        assert_eq!(wait_for_reply(process), None);
+
+        other_process.clear_flags(ProcessFlags::DisableGC);
+        assert!(collect_requested(&other_process).is_ok());
+
+        assert!(!other_process.are_flags_set(ProcessFlags::ForceGC));
+        assert_eq!(wait_for_reply(process), Some(true));
+    });
+}
+
+#[test]
+fn with_other_process_with_gc_disabled_returns_false_if_it_exits_first() {
+    with_process(|process| {
+        let other_process = process::test(process);
+        other_process.set_flags(ProcessFlags::DisableGC);
+
+        assert_eq!(
+            native(process, other_process.pid_term(), Term::NIL),
+            Ok(None)
+        );
+
+        process::garbage_collect::propagate_exit(&other_process);
+
+        assert_eq!(wait_for_reply(process), Some(false));
+    });
+}
+
+#[test]
+fn with_self_returns_true_once_process_stops_running_and_is_collected() {
+    with_process(|process| {
+        assert_eq!(native(process, process.pid_term(), Term::NIL), Ok(None));
+        assert!(process.are_flags_set(ProcessFlags::ForceGC | ProcessFlags::NeedFullSweep));
+
+        assert!(collect_requested(process).is_ok());
+
+        assert!(!process.are_flags_set(ProcessFlags::ForceGC));
+        assert_eq!(wait_for_reply(process), Some(true));
+    });
+}
+
+#[test]
+fn with_self_and_async_sends_result_once_process_is_collected() {
+    with_process(|process| {
+        let request_id = process.integer(1).unwrap();
+        let option = process
+            .tuple_from_slice(&[atom_unchecked("async"), request_id])
+            .unwrap();
+        let options = process.list_from_slice(&[option]).unwrap();
+        let message = process
+            .tuple_from_slice(&[atom_unchecked("garbage_collect"), request_id, true.into()])
+            .unwrap();
+
+        assert_eq!(
+            native(process, process.pid_term(), options),
+            Ok(Some(atom_unchecked("async")))
+        );
+        assert!(!has_message(process, message));
+
+        assert!(collect_requested(process).is_ok());
+
+        assert!(has_message(process, message));
+    });
+}
+
+#[test]
+fn with_async_returns_async_and_sends_result() {
+    with_process(|process| {
+        let request_id = process.integer(1).unwrap();
+        let option = process
+            .tuple_from_slice(&[atom_unchecked("async"), request_id])
+            .unwrap();
+        let options = process.list_from_slice(&[option]).unwrap();
+
+        assert_eq!(
+            native(process, next_pid(), options),
+            Ok(Some(atom_unchecked("async")))
+        );
+
+        assert!(has_message(
+            process,
+            process
+                .tuple_from_slice(&[atom_unchecked("garbage_collect"), request_id, false.into()])
+                .unwrap()
+        ));
+    });
+}
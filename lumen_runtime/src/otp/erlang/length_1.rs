@@ -0,0 +1,86 @@
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::exception::{self, Exception};
+use liblumen_alloc::erts::process::code;
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{Atom, Term, TypedTerm};
+use liblumen_alloc::{badarg, ModuleFunctionArity};
+
+use crate::trap::{self, Trap};
+
+/// Counts `trap::STEP` elements of the list per reduction, so that counting a long list traps
+pub const TRAP: Trap = Trap {
+    state_len: 2,
+    start,
+    step,
+};
+
+pub fn place_frame_with_arguments(
+    process: &Process,
+    placement: Placement,
+    list: Term,
+) -> Result<(), Alloc> {
+    TRAP.place_frame_with_arguments(process, placement, frame(), &[list])
+}
+
+pub fn native(process: &Process, list: Term) -> exception::Result {
+    TRAP.complete(process, &[list])
+}
+
+// Private
+
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    TRAP.code(arc_process)
+}
+
+fn frame() -> Frame {
+    Frame::new(module_function_arity(), code)
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("length").unwrap()
+}
+
+fn module_function_arity() -> Arc<ModuleFunctionArity> {
+    Arc::new(ModuleFunctionArity {
+        module: super::module(),
+        function: function(),
+        arity: 1,
+    })
+}
+
+/// The state is the part of the list left to count and the number of elements counted so far
+fn start(arguments: &[Term]) -> Vec<Term> {
+    vec![arguments[0], 0_u8.into()]
+}
+
+fn step(process: &Process, state: &mut [Term]) -> Result<Option<Term>, Exception> {
+    let mut tail = state[0];
+    let mut len: usize = state[1].try_into().unwrap();
+
+    for _ in 0..trap::STEP {
+        match tail.to_typed_term().unwrap() {
+            TypedTerm::Nil => return Ok(Some(process.integer(len)?)),
+            TypedTerm::List(cons) => {
+                tail = cons.tail;
+                len += 1;
+            }
+            _ => return Err(badarg!().into()),
+        }
+    }
+
+    state[0] = tail;
+    state[1] = process.integer(len)?;
+
+    Ok(None)
+}
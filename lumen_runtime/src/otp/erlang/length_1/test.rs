@@ -1,14 +1,21 @@
-use super::*;
-
 use proptest::collection::SizeRange;
 use proptest::strategy::Strategy;
+use proptest::test_runner::{Config, TestRunner};
+use proptest::{prop_assert, prop_assert_eq};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::term::Term;
+
+use crate::otp::erlang::length_1::native;
+use crate::scheduler::{with_process, with_process_arc};
+use crate::test::strategy;
 
 #[test]
 fn without_list_errors_badarg() {
     with_process_arc(|arc_process| {
         TestRunner::new(Config::with_source_file(file!()))
             .run(&strategy::term::is_not_list(arc_process.clone()), |list| {
-                prop_assert_eq!(erlang::length_1(list, &arc_process), Err(badarg!().into()));
+                prop_assert_eq!(native(&arc_process, list), Err(badarg!().into()));
 
                 Ok(())
             })
@@ -22,7 +29,7 @@ fn with_empty_list_is_zero() {
         let list = Term::NIL;
         let zero_term = process.integer(0).unwrap();
 
-        assert_eq!(erlang::length_1(list, &process), Ok(zero_term));
+        assert_eq!(native(&process, list), Ok(zero_term));
     });
 }
 
@@ -33,7 +40,7 @@ fn with_improper_list_errors_badarg() {
             .run(
                 &strategy::term::list::improper(arc_process.clone()),
                 |list| {
-                    prop_assert_eq!(erlang::length_1(list, &arc_process), Err(badarg!().into()));
+                    prop_assert_eq!(native(&arc_process, list), Err(badarg!().into()));
 
                     Ok(())
                 },
@@ -58,7 +65,7 @@ fn with_non_empty_proper_list_is_number_of_elements() {
                     }),
                 |(list, element_count)| {
                     prop_assert_eq!(
-                        erlang::length_1(list, &arc_process),
+                        native(&arc_process, list),
                         Ok(arc_process.integer(element_count).unwrap())
                     );
 
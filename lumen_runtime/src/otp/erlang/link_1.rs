@@ -13,8 +13,10 @@ use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
 use liblumen_alloc::erts::process::code::{self, result_from_exception};
 use liblumen_alloc::erts::process::Process;
 use liblumen_alloc::erts::term::{atom_unchecked, Atom, Term, TypedTerm};
-use liblumen_alloc::{badarg, error, ModuleFunctionArity};
+use liblumen_alloc::{badarg, error, exit, ModuleFunctionArity};
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::distribution::{self, control::RemotePid};
 use crate::registry::pid_to_process;
 
 pub fn place_frame_with_arguments(
@@ -61,28 +63,58 @@ fn module_function_arity() -> Arc<ModuleFunctionArity> {
     })
 }
 
-fn native(process: &Process, pid_or_port: Term) -> exception::Result {
+pub fn native(process: &Process, pid_or_port: Term) -> exception::Result {
     match pid_or_port.to_typed_term().unwrap() {
         TypedTerm::Pid(pid) => {
             if pid == process.pid() {
                 Ok(true.into())
             } else {
                 match pid_to_process(&pid) {
-                    Some(pid_arc_process) => {
-                        process.link(&pid_arc_process);
-
-                        Ok(true.into())
-                    }
-                    None => Err(error!(atom_unchecked("noproc")).into()),
+                    // a process that is exiting may have already sent its exit signals
+                    Some(ref pid_arc_process) if process.link(pid_arc_process) => Ok(true.into()),
+                    _ => Err(error!(atom_unchecked("noproc")).into()),
                 }
             }
         }
         TypedTerm::Port(_) => unimplemented!(),
         TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
-            TypedTerm::ExternalPid(_) => unimplemented!(),
+            TypedTerm::ExternalPid(_) => link_external_pid(process, pid_or_port),
             TypedTerm::ExternalPort(_) => unimplemented!(),
             _ => Err(badarg!().into()),
         },
         _ => Err(badarg!().into()),
     }
 }
+
+#[cfg(not(target_arch = "wasm32"))]
+fn link_external_pid(process: &Process, pid_or_port: Term) -> exception::Result {
+    let remote = RemotePid::from_term(pid_or_port).unwrap();
+
+    match distribution::link::link(process, remote) {
+        Ok(()) => Ok(true.into()),
+        Err(_) => link_noconnection(process, pid_or_port),
+    }
+}
+
+/// Distribution is not supported on wasm32, so there is never a connection to the node of a remote
+/// process.
+#[cfg(target_arch = "wasm32")]
+fn link_external_pid(process: &Process, pid_or_port: Term) -> exception::Result {
+    link_noconnection(process, pid_or_port)
+}
+
+/// There is no connection to the node of the remote process, so the link is immediately broken
+/// with `noconnection`.
+fn link_noconnection(process: &Process, pid_or_port: Term) -> exception::Result {
+    let noconnection = atom_unchecked("noconnection");
+
+    if process.traps_exit() {
+        let tag = atom_unchecked("EXIT");
+        let exit_message = process.tuple_from_slice(&[tag, pid_or_port, noconnection])?;
+        process.send_from_self(exit_message);
+
+        Ok(true.into())
+    } else {
+        Err(exit!(noconnection).into())
+    }
+}
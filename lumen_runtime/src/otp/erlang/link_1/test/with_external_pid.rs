@@ -0,0 +1,45 @@
+use super::*;
+
+use liblumen_alloc::erts::term::atom_unchecked;
+use liblumen_alloc::exit;
+
+use crate::test::has_message;
+
+#[test]
+fn without_trapping_exits_errors_noconnection() {
+    with_process(|process| {
+        let external_pid = process.external_pid_with_node_id(1, 2, 3).unwrap();
+        let link_count_before = link_count(process);
+
+        assert_eq!(
+            native(process, external_pid),
+            Err(exit!(atom_unchecked("noconnection")).into())
+        );
+
+        assert_eq!(link_count(process), link_count_before);
+    });
+}
+
+#[test]
+fn with_trapping_exits_returns_true_and_sends_exit_message_with_noconnection() {
+    with_process(|process| {
+        process.trap_exit(true);
+
+        let external_pid = process.external_pid_with_node_id(1, 2, 3).unwrap();
+        let link_count_before = link_count(process);
+
+        assert_eq!(native(process, external_pid), Ok(true.into()));
+
+        assert_eq!(link_count(process), link_count_before);
+
+        let tag = atom_unchecked("EXIT");
+        let reason = atom_unchecked("noconnection");
+
+        assert!(has_message(
+            process,
+            process
+                .tuple_from_slice(&[tag, external_pid, reason])
+                .unwrap()
+        ));
+    });
+}
@@ -12,13 +12,17 @@ use liblumen_alloc::erts::exception;
 use liblumen_alloc::erts::exception::system::Alloc;
 use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
 use liblumen_alloc::erts::process::code::{self, result_from_exception};
-use liblumen_alloc::erts::process::{Monitor, Process};
+use liblumen_alloc::erts::process::{Monitor, Process, Tag};
 use liblumen_alloc::erts::term::{
     atom_unchecked, Atom, Boxed, Pid, Reference, Term, Tuple, TypedTerm,
 };
 use liblumen_alloc::{badarg, ModuleFunctionArity};
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::distribution::{self, control::RemotePid, monitor::Monitored};
+use crate::otp::erlang::monitor_3::options::Options;
 use crate::otp::erlang::node_0;
+use crate::process::alias::{self, Mode};
 use crate::process::SchedulerDependentAlloc;
 use crate::registry;
 
@@ -69,28 +73,149 @@ fn module_function_arity() -> Arc<ModuleFunctionArity> {
     })
 }
 
-fn monitor_process_identifier(process: &Process, process_identifier: Term) -> exception::Result {
+/// Makes the reference of a monitor an alias of `process`
+fn alias(process: &Process, reference: Term, mode: Mode) {
+    let reference_reference: Boxed<Reference> = reference.try_into().unwrap();
+
+    alias::alias(process, reference_reference.clone(), mode);
+}
+
+fn monitor_process_identifier(
+    process: &Process,
+    process_identifier: Term,
+    options: &Options,
+) -> exception::Result {
     match process_identifier.to_typed_term().unwrap() {
-        TypedTerm::Atom(atom) => monitor_process_registered_name(process, process_identifier, atom),
-        TypedTerm::Pid(pid) => monitor_process_pid(process, process_identifier, pid),
+        TypedTerm::Atom(atom) => {
+            monitor_process_registered_name(process, process_identifier, atom, options)
+        }
+        TypedTerm::Pid(pid) => monitor_process_pid(process, process_identifier, pid, options),
         TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
-            TypedTerm::ExternalPid(_) => unimplemented!(),
-            TypedTerm::Tuple(tuple) => monitor_process_tuple(process, process_identifier, &tuple),
+            TypedTerm::ExternalPid(_) => {
+                monitor_process_external_pid(process, process_identifier, options)
+            }
+            TypedTerm::Tuple(tuple) => {
+                monitor_process_tuple(process, process_identifier, &tuple, options)
+            }
             _ => Err(badarg!().into()),
         },
         _ => Err(badarg!().into()),
     }
 }
 
-fn monitor_process_identifier_noproc(process: &Process, identifier: Term) -> exception::Result {
+#[cfg(not(target_arch = "wasm32"))]
+fn monitor_process_external_pid(
+    process: &Process,
+    process_identifier: Term,
+    options: &Options,
+) -> exception::Result {
+    let remote = RemotePid::from_term(process_identifier).unwrap();
+
+    monitor_remote(process, process_identifier, Monitored::Pid(remote), options)
+}
+
+/// Distribution is not supported on wasm32, so there is never a connection to the node of a remote
+/// process.
+#[cfg(target_arch = "wasm32")]
+fn monitor_process_external_pid(
+    process: &Process,
+    process_identifier: Term,
+    options: &Options,
+) -> exception::Result {
+    monitor_process_identifier_noconnection(process, process_identifier, options)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn monitor_process_remote_name(
+    process: &Process,
+    process_identifier: Term,
+    name: Atom,
+    node: Atom,
+    options: &Options,
+) -> exception::Result {
+    monitor_remote(
+        process,
+        process_identifier,
+        Monitored::Name { name, node },
+        options,
+    )
+}
+
+#[cfg(target_arch = "wasm32")]
+fn monitor_process_remote_name(
+    process: &Process,
+    process_identifier: Term,
+    _name: Atom,
+    _node: Atom,
+    options: &Options,
+) -> exception::Result {
+    monitor_process_identifier_noconnection(process, process_identifier, options)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn monitor_remote(
+    process: &Process,
+    process_identifier: Term,
+    monitored: Monitored,
+    options: &Options,
+) -> exception::Result {
+    match distribution::monitor::monitor(process, monitored, options.tag()?) {
+        Ok(reference) => {
+            if let Some(mode) = options.alias {
+                alias(process, reference, mode);
+            }
+
+            Ok(reference)
+        }
+        Err(distribution::Error::Alloc(alloc)) => Err(alloc.into()),
+        Err(_) => monitor_process_identifier_noconnection(process, process_identifier, options),
+    }
+}
+
+/// There is no connection to the node of the remote process, so the `DOWN` message is sent
+/// immediately, as if the connection was lost.
+fn monitor_process_identifier_noconnection(
+    process: &Process,
+    identifier: Term,
+    options: &Options,
+) -> exception::Result {
+    monitor_process_identifier_down(process, identifier, atom_unchecked("noconnection"), options)
+}
+
+fn monitor_process_identifier_noproc(
+    process: &Process,
+    identifier: Term,
+    options: &Options,
+) -> exception::Result {
+    monitor_process_identifier_down(process, identifier, atom_unchecked("noproc"), options)
+}
+
+/// Sends the `DOWN` message with `info` immediately.  As the monitor is already gone, only an
+/// `explicit_unalias` alias is made.
+fn monitor_process_identifier_down(
+    process: &Process,
+    identifier: Term,
+    info: Term,
+    options: &Options,
+) -> exception::Result {
     let monitor_reference = process.next_reference()?;
-    let noproc_message = noproc_message(process, monitor_reference, identifier)?;
-    process.send_from_self(noproc_message);
+
+    if options.alias == Some(Mode::ExplicitUnalias) {
+        alias(process, monitor_reference, Mode::ExplicitUnalias);
+    }
+
+    let down_message = down_message(process, options, monitor_reference, identifier, info)?;
+    process.send_from_self(down_message);
 
     Ok(monitor_reference)
 }
 
-fn monitor_process_pid(process: &Process, process_identifier: Term, pid: Pid) -> exception::Result {
+fn monitor_process_pid(
+    process: &Process,
+    process_identifier: Term,
+    pid: Pid,
+    options: &Options,
+) -> exception::Result {
     match registry::pid_to_process(&pid) {
         Some(monitored_arc_process) => {
             let reference = process.next_reference()?;
@@ -98,13 +223,26 @@ fn monitor_process_pid(process: &Process, process_identifier: Term, pid: Pid) ->
             let reference_reference: Boxed<Reference> = reference.try_into().unwrap();
             let monitor = Monitor::Pid {
                 monitoring_pid: process.pid(),
+                tag: options.tag()?,
             };
-            process.monitor(reference_reference.clone(), monitored_arc_process.pid());
-            monitored_arc_process.monitored(reference_reference.clone(), monitor);
 
-            Ok(reference)
+            // the alias is active before the monitor, so a `DOWN` message can deactivate it
+            if let Some(mode) = options.alias {
+                alias(process, reference, mode);
+            }
+
+            // a process that is exiting may have already sent its `DOWN` messages
+            if monitored_arc_process.monitored(reference_reference.clone(), monitor) {
+                process.monitor(reference_reference.clone(), monitored_arc_process.pid());
+
+                Ok(reference)
+            } else {
+                alias::unalias(process, &reference_reference);
+
+                monitor_process_identifier_noproc(process, process_identifier, options)
+            }
         }
-        None => monitor_process_identifier_noproc(process, process_identifier),
+        None => monitor_process_identifier_noproc(process, process_identifier, options),
     }
 }
 
@@ -112,6 +250,7 @@ fn monitor_process_registered_name(
     process: &Process,
     process_identifier: Term,
     atom: Atom,
+    options: &Options,
 ) -> exception::Result {
     match registry::atom_to_process(&atom) {
         Some(monitored_arc_process) => {
@@ -121,24 +260,42 @@ fn monitor_process_registered_name(
             let monitor = Monitor::Name {
                 monitoring_pid: process.pid(),
                 monitored_name: atom,
+                tag: options.tag()?,
             };
-            process.monitor(reference_reference.clone(), monitored_arc_process.pid());
-            monitored_arc_process.monitored(reference_reference.clone(), monitor);
 
-            Ok(reference)
-        }
-        None => {
-            let identifier = process.tuple_from_slice(&[process_identifier, node_0()])?;
+            if let Some(mode) = options.alias {
+                alias(process, reference, mode);
+            }
+
+            if monitored_arc_process.monitored(reference_reference.clone(), monitor) {
+                process.monitor(reference_reference.clone(), monitored_arc_process.pid());
+
+                Ok(reference)
+            } else {
+                alias::unalias(process, &reference_reference);
 
-            monitor_process_identifier_noproc(process, identifier)
+                monitor_process_registered_name_noproc(process, process_identifier, options)
+            }
         }
+        None => monitor_process_registered_name_noproc(process, process_identifier, options),
     }
 }
 
+fn monitor_process_registered_name_noproc(
+    process: &Process,
+    process_identifier: Term,
+    options: &Options,
+) -> exception::Result {
+    let identifier = process.tuple_from_slice(&[process_identifier, node_0()])?;
+
+    monitor_process_identifier_noproc(process, identifier, options)
+}
+
 fn monitor_process_tuple(
     process: &Process,
-    _process_identifier: Term,
+    process_identifier: Term,
     tuple: &Tuple,
+    options: &Options,
 ) -> exception::Result {
     if tuple.len() == 2 {
         let registered_name = tuple[0];
@@ -147,11 +304,17 @@ fn monitor_process_tuple(
         let node = tuple[1];
 
         if node == node_0() {
-            monitor_process_registered_name(process, registered_name, registered_name_atom)
+            monitor_process_registered_name(process, registered_name, registered_name_atom, options)
         } else {
-            let _node_atom: Atom = node.try_into()?;
-
-            unimplemented!("node ({:?}) is not the local node ({:?})", node, node_0());
+            let node_atom: Atom = node.try_into()?;
+
+            monitor_process_remote_name(
+                process,
+                process_identifier,
+                registered_name_atom,
+                node_atom,
+                options,
+            )
         }
     } else {
         Err(badarg!().into())
@@ -159,30 +322,35 @@ fn monitor_process_tuple(
 }
 
 pub fn native(process: &Process, r#type: Term, item: Term) -> exception::Result {
+    monitor(process, r#type, item, &Default::default())
+}
+
+/// `monitor/2` and `monitor/3`, which also takes `options`
+pub(super) fn monitor(
+    process: &Process,
+    r#type: Term,
+    item: Term,
+    options: &Options,
+) -> exception::Result {
     let type_atom: Atom = r#type.try_into()?;
 
     match type_atom.name() {
         "port" => unimplemented!(),
-        "process" => monitor_process_identifier(process, item),
+        "process" => monitor_process_identifier(process, item, options),
         "time_offset" => unimplemented!(),
         _ => Err(badarg!().into()),
     }
 }
 
-fn noproc_message(process: &Process, reference: Term, identifier: Term) -> Result<Term, Alloc> {
-    let noproc = atom_unchecked("noproc");
-
-    down_message(process, reference, identifier, noproc)
-}
-
 fn down_message(
     process: &Process,
+    options: &Options,
     reference: Term,
     identifier: Term,
     info: Term,
 ) -> Result<Term, Alloc> {
-    let down = atom_unchecked("DOWN");
+    let tag = options.tag.unwrap_or_else(|| Tag::default().term());
     let r#type = atom_unchecked("process");
 
-    process.tuple_from_slice(&[down, reference, r#type, identifier, info])
+    process.tuple_from_slice(&[tag, reference, r#type, identifier, info])
 }
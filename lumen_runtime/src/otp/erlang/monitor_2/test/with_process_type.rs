@@ -1,4 +1,5 @@
 mod with_atom_process_identifier;
+mod with_external_pid_process_identifier;
 mod with_local_pid_process_identifier;
 mod with_tuple_process_identifier;
 
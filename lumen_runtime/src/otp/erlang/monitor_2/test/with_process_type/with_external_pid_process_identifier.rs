@@ -0,0 +1,33 @@
+use super::*;
+
+#[test]
+fn returns_reference_but_immediately_sends_noconnection_message() {
+    with_process_arc(|monitoring_arc_process| {
+        let monitored_pid = monitoring_arc_process
+            .external_pid_with_node_id(1, 2, 3)
+            .unwrap();
+        let monitored_count_before = monitored_count(&monitoring_arc_process);
+
+        let monitor_reference_result = native(&monitoring_arc_process, r#type(), monitored_pid);
+
+        assert!(monitor_reference_result.is_ok());
+
+        let monitor_reference = monitor_reference_result.unwrap();
+
+        assert!(monitor_reference.is_reference());
+        assert_eq!(
+            monitored_count(&monitoring_arc_process),
+            monitored_count_before
+        );
+
+        let tag = atom_unchecked("DOWN");
+        let reason = atom_unchecked("noconnection");
+
+        assert!(has_message(
+            &monitoring_arc_process,
+            monitoring_arc_process
+                .tuple_from_slice(&[tag, monitor_reference, r#type(), monitored_pid, reason])
+                .unwrap()
+        ));
+    });
+}
@@ -27,3 +27,32 @@ fn without_atom_node_errors_badarg() {
             .unwrap();
     });
 }
+
+#[test]
+fn with_remote_node_returns_reference_but_immediately_sends_noconnection_message() {
+    with_process_arc(|monitoring_arc_process| {
+        let registered_name = registered_name();
+        let node = atom_unchecked("remote@example.com");
+        let identifier = monitoring_arc_process
+            .tuple_from_slice(&[registered_name, node])
+            .unwrap();
+
+        let monitor_reference_result = native(&monitoring_arc_process, r#type(), identifier);
+
+        assert!(monitor_reference_result.is_ok());
+
+        let monitor_reference = monitor_reference_result.unwrap();
+
+        assert!(monitor_reference.is_reference());
+
+        let tag = atom_unchecked("DOWN");
+        let reason = atom_unchecked("noconnection");
+
+        assert!(has_message(
+            &monitoring_arc_process,
+            monitoring_arc_process
+                .tuple_from_slice(&[tag, monitor_reference, r#type(), identifier, reason])
+                .unwrap()
+        ));
+    });
+}
@@ -0,0 +1,81 @@
+pub mod options;
+
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::code::{self, result_from_exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{Atom, Term};
+use liblumen_alloc::ModuleFunctionArity;
+
+use crate::otp::erlang::monitor_2::monitor;
+use crate::otp::erlang::monitor_3::options::Options;
+
+pub fn place_frame_with_arguments(
+    process: &Process,
+    placement: Placement,
+    r#type: Term,
+    item: Term,
+    options: Term,
+) -> Result<(), Alloc> {
+    process.stack_push(options)?;
+    process.stack_push(item)?;
+    process.stack_push(r#type)?;
+    process.place_frame(frame(), placement);
+
+    Ok(())
+}
+
+// Private
+
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.reduce();
+
+    let r#type = arc_process.stack_pop().unwrap();
+    let item = arc_process.stack_pop().unwrap();
+    let options = arc_process.stack_pop().unwrap();
+
+    match native(arc_process, r#type, item, options) {
+        Ok(reference) => {
+            arc_process.return_from_call(reference)?;
+
+            Process::call_code(arc_process)
+        }
+        Err(exception) => result_from_exception(arc_process, exception),
+    }
+}
+
+fn frame() -> Frame {
+    Frame::new(module_function_arity(), code)
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("monitor").unwrap()
+}
+
+fn module_function_arity() -> Arc<ModuleFunctionArity> {
+    Arc::new(ModuleFunctionArity {
+        module: super::module(),
+        function: function(),
+        arity: 3,
+    })
+}
+
+/// `options` are `{tag, Tag}`, which makes `Tag` the first element of the `DOWN` message instead
+/// of `'DOWN'`, and `{alias, Mode}`, which makes the returned reference an alias of the process
+/// too (see `crate::process::alias`).
+pub fn native(process: &Process, r#type: Term, item: Term, options: Term) -> exception::Result {
+    let options_options: Options = options.try_into()?;
+
+    monitor(process, r#type, item, &options_options)
+}
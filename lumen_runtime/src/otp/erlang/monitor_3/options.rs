@@ -0,0 +1,98 @@
+use std::convert::{TryFrom, TryInto};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception::runtime;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::Tag;
+use liblumen_alloc::erts::term::{Atom, Boxed, Cons, Term, Tuple, TypedTerm};
+
+use crate::process::alias::Mode;
+
+pub struct Options {
+    /// The first element of the `DOWN` message instead of `'DOWN'`
+    pub tag: Option<Term>,
+    /// The reference of the monitor is also an alias deactivated as `Mode` says
+    pub alias: Option<Mode>,
+}
+
+impl Options {
+    /// The tag kept by the monitor until its `DOWN` message is sent
+    pub fn tag(&self) -> Result<Tag, Alloc> {
+        match self.tag {
+            Some(tag) => Tag::new(tag),
+            None => Ok(Default::default()),
+        }
+    }
+
+    fn put_option_term(&mut self, option: Term) -> Result<&Options, runtime::Exception> {
+        let tuple: Boxed<Tuple> = option.try_into().map_err(|_| badarg!())?;
+
+        if tuple.len() == 2 {
+            let name: Atom = tuple[0].try_into()?;
+
+            match name.name() {
+                "tag" => {
+                    self.tag = Some(tuple[1]);
+
+                    Ok(self)
+                }
+                "alias" => {
+                    self.alias = Some(Mode::try_from_term(tuple[1])?);
+
+                    Ok(self)
+                }
+                _ => Err(badarg!()),
+            }
+        } else {
+            Err(badarg!())
+        }
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            tag: None,
+            alias: None,
+        }
+    }
+}
+
+impl TryFrom<Boxed<Cons>> for Options {
+    type Error = runtime::Exception;
+
+    fn try_from(cons: Boxed<Cons>) -> Result<Self, Self::Error> {
+        let mut options: Options = Default::default();
+
+        for result in cons.into_iter() {
+            match result {
+                Ok(option) => {
+                    options.put_option_term(option)?;
+                }
+                Err(_) => return Err(badarg!()),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+impl TryFrom<Term> for Options {
+    type Error = runtime::Exception;
+
+    fn try_from(term: Term) -> Result<Self, Self::Error> {
+        term.to_typed_term().unwrap().try_into()
+    }
+}
+
+impl TryFrom<TypedTerm> for Options {
+    type Error = runtime::Exception;
+
+    fn try_from(typed_term: TypedTerm) -> Result<Self, Self::Error> {
+        match typed_term {
+            TypedTerm::Nil => Ok(Default::default()),
+            TypedTerm::List(cons) => cons.try_into(),
+            _ => Err(badarg!()),
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use std::sync::Arc;
+
+use proptest::strategy::Strategy;
+use proptest::test_runner::{Config, TestRunner};
+use proptest::{prop_assert, prop_assert_eq};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::process::code::stack::frame::Placement;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Term};
+
+use crate::otp::erlang;
+use crate::otp::erlang::monitor_3::native;
+use crate::otp::erlang::{demonitor_2, exit_1};
+use crate::process;
+use crate::scheduler::{with_process_arc, Scheduler};
+use crate::test::{has_message, monitor_count, strategy};
+
+#[test]
+fn without_proper_list_options_errors_badarg() {
+    with_process_arc(|monitoring_arc_process| {
+        let monitored_arc_process = process::test(&monitoring_arc_process);
+
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &strategy::term::is_not_list(monitoring_arc_process.clone()),
+                |options| {
+                    prop_assert_eq!(
+                        native(
+                            &monitoring_arc_process,
+                            r#type(),
+                            monitored_arc_process.pid_term(),
+                            options
+                        ),
+                        Err(badarg!().into())
+                    );
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_unknown_alias_mode_errors_badarg() {
+    with_process_arc(|monitoring_arc_process| {
+        let monitored_arc_process = process::test(&monitoring_arc_process);
+
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &strategy::term(monitoring_arc_process.clone()).prop_filter(
+                    "Mode cannot be explicit_unalias, demonitor, or reply_demonitor",
+                    |mode| {
+                        !(*mode == atom_unchecked("explicit_unalias")
+                            || *mode == atom_unchecked("demonitor")
+                            || *mode == atom_unchecked("reply_demonitor"))
+                    },
+                ),
+                |mode| {
+                    let options = monitoring_arc_process
+                        .list_from_slice(&[monitoring_arc_process
+                            .tuple_from_slice(&[atom_unchecked("alias"), mode])
+                            .unwrap()])
+                        .unwrap();
+
+                    prop_assert_eq!(
+                        native(
+                            &monitoring_arc_process,
+                            r#type(),
+                            monitored_arc_process.pid_term(),
+                            options
+                        ),
+                        Err(badarg!().into())
+                    );
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_tag_option_down_message_starts_with_tag() {
+    with_process_arc(|monitoring_arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(&strategy::term(monitoring_arc_process.clone()), |tag| {
+                let monitored_arc_process = process::test(&monitoring_arc_process);
+                let options = monitoring_arc_process
+                    .list_from_slice(&[monitoring_arc_process
+                        .tuple_from_slice(&[atom_unchecked("tag"), tag])
+                        .unwrap()])
+                    .unwrap();
+
+                let reference = native(
+                    &monitoring_arc_process,
+                    r#type(),
+                    monitored_arc_process.pid_term(),
+                    options,
+                )
+                .unwrap();
+
+                let reason = atom_unchecked("normal");
+                exit(&monitored_arc_process, reason);
+
+                prop_assert!(has_message(
+                    &monitoring_arc_process,
+                    monitoring_arc_process
+                        .tuple_from_slice(&[
+                            tag,
+                            reference,
+                            r#type(),
+                            monitored_arc_process.pid_term(),
+                            reason
+                        ])
+                        .unwrap()
+                ));
+
+                Ok(())
+            })
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_explicit_unalias_message_sent_to_reference_is_received_until_unalias() {
+    with_process_arc(|monitoring_arc_process| {
+        let monitored_arc_process = process::test(&monitoring_arc_process);
+        let reference = native(
+            &monitoring_arc_process,
+            r#type(),
+            monitored_arc_process.pid_term(),
+            alias_options(&monitoring_arc_process, "explicit_unalias"),
+        )
+        .unwrap();
+
+        let first_message = atom_unchecked("first");
+
+        assert_eq!(
+            erlang::send_2(reference, first_message, &monitored_arc_process),
+            Ok(first_message)
+        );
+        assert!(has_message(&monitoring_arc_process, first_message));
+
+        assert_eq!(
+            erlang::unalias_1(reference, &monitoring_arc_process),
+            Ok(true.into())
+        );
+        assert_eq!(
+            erlang::unalias_1(reference, &monitoring_arc_process),
+            Ok(false.into())
+        );
+
+        let second_message = atom_unchecked("second");
+
+        assert_eq!(
+            erlang::send_2(reference, second_message, &monitored_arc_process),
+            Ok(second_message)
+        );
+        assert!(!has_message(&monitoring_arc_process, second_message));
+    });
+}
+
+#[test]
+fn with_demonitor_alias_is_deactivated_by_demonitor() {
+    with_process_arc(|monitoring_arc_process| {
+        let monitored_arc_process = process::test(&monitoring_arc_process);
+        let reference = native(
+            &monitoring_arc_process,
+            r#type(),
+            monitored_arc_process.pid_term(),
+            alias_options(&monitoring_arc_process, "demonitor"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            demonitor_2::native(&monitoring_arc_process, reference, Term::NIL),
+            Ok(true.into())
+        );
+
+        let message = atom_unchecked("message");
+
+        assert_eq!(
+            erlang::send_2(reference, message, &monitored_arc_process),
+            Ok(message)
+        );
+        assert!(!has_message(&monitoring_arc_process, message));
+        assert_eq!(
+            erlang::unalias_1(reference, &monitoring_arc_process),
+            Ok(false.into())
+        );
+    });
+}
+
+#[test]
+fn with_reply_demonitor_first_message_deactivates_alias_and_removes_monitor() {
+    with_process_arc(|monitoring_arc_process| {
+        let monitored_arc_process = process::test(&monitoring_arc_process);
+        let monitor_count_before = monitor_count(&monitored_arc_process);
+        let reference = native(
+            &monitoring_arc_process,
+            r#type(),
+            monitored_arc_process.pid_term(),
+            alias_options(&monitoring_arc_process, "reply_demonitor"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            monitor_count(&monitored_arc_process),
+            monitor_count_before + 1
+        );
+
+        let reply = atom_unchecked("reply");
+
+        assert_eq!(
+            erlang::send_2(reference, reply, &monitored_arc_process),
+            Ok(reply)
+        );
+        assert!(has_message(&monitoring_arc_process, reply));
+        assert_eq!(monitor_count(&monitored_arc_process), monitor_count_before);
+
+        let late_reply = atom_unchecked("late_reply");
+
+        assert_eq!(
+            erlang::send_2(reference, late_reply, &monitored_arc_process),
+            Ok(late_reply)
+        );
+        assert!(!has_message(&monitoring_arc_process, late_reply));
+    });
+}
+
+fn alias_options(process: &Process, mode: &str) -> Term {
+    process
+        .list_from_slice(&[process
+            .tuple_from_slice(&[atom_unchecked("alias"), atom_unchecked(mode)])
+            .unwrap()])
+        .unwrap()
+}
+
+fn exit(arc_process: &Arc<Process>, reason: Term) {
+    exit_1::place_frame_with_arguments(arc_process, Placement::Replace, reason).unwrap();
+
+    assert!(Scheduler::current().run_through(arc_process));
+    assert!(arc_process.is_exiting());
+}
+
+fn r#type() -> Term {
+    atom_unchecked("process")
+}
@@ -12,10 +12,13 @@ use liblumen_alloc::erts::exception;
 use liblumen_alloc::erts::exception::system::Alloc;
 use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
 use liblumen_alloc::erts::process::code::{self, result_from_exception};
-use liblumen_alloc::erts::process::Process;
-use liblumen_alloc::erts::term::{Atom, Term};
+use liblumen_alloc::erts::process::flight_recorder;
+use liblumen_alloc::erts::process::{HeapSizing, MaxHeapSize, MessageQueueData, Process};
+use liblumen_alloc::erts::term::{atom_unchecked, AsTerm, Atom, Term};
 use liblumen_alloc::{badarg, ModuleFunctionArity};
 
+use crate::time::monotonic;
+
 pub fn place_frame_with_arguments(
     process: &Process,
     placement: Placement,
@@ -67,11 +70,65 @@ pub fn native(process: &Process, flag: Term, value: Term) -> exception::Result {
     let flag_atom: Atom = flag.try_into()?;
 
     match flag_atom.name() {
-        "error_handler" => unimplemented!(),
-        "max_heap_size" => unimplemented!(),
-        "message_queue_data" => unimplemented!(),
-        "min_bin_vheap_size" => unimplemented!(),
-        "min_heap_size" => unimplemented!(),
+        "error_handler" => {
+            let module: Atom = value.try_into()?;
+            let old_module = process.set_error_handler(module);
+
+            Ok(unsafe { old_module.as_term() })
+        }
+        "flight_recorder" => {
+            let capacity: usize = value.try_into()?;
+
+            if 0 < capacity {
+                flight_recorder::set_clock(monotonic::time_in_milliseconds);
+            }
+
+            let old_capacity = process.set_flight_recorder_capacity(capacity);
+
+            Ok(process.integer(old_capacity)?)
+        }
+        "heap_sizing" => {
+            let heap_sizing: HeapSizing = value.try_into()?;
+            let old_heap_sizing = process.set_heap_sizing(heap_sizing);
+
+            Ok(old_heap_sizing.to_map(process)?)
+        }
+        "max_heap_size" => {
+            let max_heap_size: MaxHeapSize = value.try_into()?;
+            let old_max_heap_size = process.set_max_heap_size(max_heap_size);
+
+            Ok(process.map_from_slice(&[
+                (
+                    atom_unchecked("error_logger"),
+                    old_max_heap_size.error_logger.into(),
+                ),
+                (atom_unchecked("kill"), old_max_heap_size.kill.into()),
+                (
+                    atom_unchecked("size"),
+                    process.integer(old_max_heap_size.size)?,
+                ),
+            ])?)
+        }
+        "message_queue_data" => {
+            let message_queue_data: MessageQueueData = value.try_into()?;
+            let old_message_queue_data = process.set_message_queue_data(message_queue_data);
+
+            Ok(old_message_queue_data.into())
+        }
+        "min_bin_vheap_size" => {
+            let min_bin_vheap_size: usize = value.try_into()?;
+            let old_min_bin_vheap_size = process.set_min_bin_vheap_size(min_bin_vheap_size);
+
+            Ok(process.integer(old_min_bin_vheap_size)?)
+        }
+        "min_heap_size" => {
+            let min_heap_size: usize = value.try_into()?;
+            // rounded up to a size the heap can have, as for `spawn_opt`
+            let old_min_heap_size =
+                process.set_min_heap_size(process.heap_sizing().next_heap_size(min_heap_size));
+
+            Ok(process.integer(old_min_heap_size)?)
+        }
         "priority" => unimplemented!(),
         "save_calls" => unimplemented!(),
         "sensitive" => unimplemented!(),
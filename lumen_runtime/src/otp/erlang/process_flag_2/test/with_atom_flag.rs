@@ -1,3 +1,10 @@
+mod with_error_handler_flag;
+mod with_flight_recorder_flag;
+mod with_heap_sizing_flag;
+mod with_max_heap_size_flag;
+mod with_message_queue_data_flag;
+mod with_min_bin_vheap_size_flag;
+mod with_min_heap_size_flag;
 mod with_trap_exit_flag;
 
 use super::*;
@@ -30,7 +37,10 @@ fn unsupported_flag_atom() -> BoxedStrategy<Term> {
             let atom_atom: Atom = (*atom).try_into().unwrap();
 
             match atom_atom.name() {
-                "trap_exit" => false,
+                "error_handler" | "flight_recorder" | "heap_sizing" | "max_heap_size"
+                | "message_queue_data" | "min_bin_vheap_size" | "min_heap_size" | "trap_exit" => {
+                    false
+                }
                 _ => true,
             }
         })
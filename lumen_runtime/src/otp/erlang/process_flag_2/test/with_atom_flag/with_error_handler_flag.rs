@@ -0,0 +1,57 @@
+use super::*;
+
+use liblumen_alloc::erts::term::atom_unchecked;
+
+use crate::process;
+
+#[test]
+fn without_atom_value_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(&strategy::term::is_not_atom(arc_process.clone()), |value| {
+                prop_assert_eq!(native(&arc_process, flag(), value), Err(badarg!().into()));
+
+                Ok(())
+            })
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_atom_value_returns_original_value_error_handler() {
+    TestRunner::new(Config::with_source_file(file!()))
+        .run(&strategy::term::atom(), |value| {
+            let arc_process = process::test(&process::test_init());
+
+            prop_assert_eq!(
+                native(&arc_process, flag(), value),
+                Ok(atom_unchecked("error_handler"))
+            );
+
+            Ok(())
+        })
+        .unwrap();
+}
+
+#[test]
+fn with_atom_value_then_atom_value_returns_old_value() {
+    TestRunner::new(Config::with_source_file(file!()))
+        .run(&strategy::term::atom(), |value| {
+            let arc_process = process::test(&process::test_init());
+
+            let old_value = atom_unchecked("autoloading_error_handler");
+            prop_assert_eq!(
+                native(&arc_process, flag(), old_value),
+                Ok(atom_unchecked("error_handler"))
+            );
+
+            prop_assert_eq!(native(&arc_process, flag(), value), Ok(old_value));
+
+            Ok(())
+        })
+        .unwrap();
+}
+
+fn flag() -> Term {
+    atom_unchecked("error_handler")
+}
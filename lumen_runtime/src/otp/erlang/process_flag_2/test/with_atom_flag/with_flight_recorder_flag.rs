@@ -0,0 +1,71 @@
+use super::*;
+
+use liblumen_alloc::erts::term::atom_unchecked;
+
+use crate::otp::erlang;
+use crate::process;
+
+#[test]
+fn without_non_negative_integer_value_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &strategy::term::is_not_non_negative_integer(arc_process.clone()),
+                |value| {
+                    prop_assert_eq!(native(&arc_process, flag(), value), Err(badarg!().into()));
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_non_negative_integer_value_returns_old_capacity() {
+    let arc_process = process::test(&process::test_init());
+
+    assert_eq!(
+        native(&arc_process, flag(), arc_process.integer(4).unwrap()),
+        Ok(arc_process.integer(0).unwrap())
+    );
+    assert_eq!(
+        native(&arc_process, flag(), arc_process.integer(0).unwrap()),
+        Ok(arc_process.integer(4).unwrap())
+    );
+    assert_eq!(arc_process.flight_recorder_dump(), None);
+}
+
+#[test]
+fn with_positive_integer_value_records_last_messages_sent_and_received() {
+    let arc_process = process::test(&process::test_init());
+
+    assert_eq!(
+        native(&arc_process, flag(), arc_process.integer(2).unwrap()),
+        Ok(arc_process.integer(0).unwrap())
+    );
+
+    for message in &["first", "second"] {
+        assert_eq!(
+            erlang::send_2(
+                arc_process.pid_term(),
+                atom_unchecked(message),
+                &arc_process
+            ),
+            Ok(atom_unchecked(message))
+        );
+    }
+
+    let dump = arc_process.flight_recorder_dump().unwrap();
+    let lines: Vec<&str> = dump.lines().collect();
+    let second = atom_unchecked("second");
+
+    // the send and receive of `first` fell out of the ring buffer
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].ends_with(&format!("sent {} to {}", second, arc_process.pid_term())));
+    assert!(lines[1].ends_with(&format!("received {}", second)));
+}
+
+fn flag() -> Term {
+    atom_unchecked("flight_recorder")
+}
@@ -0,0 +1,62 @@
+use super::*;
+
+use liblumen_alloc::erts::process::{heap_sizing, HeapSizing};
+use liblumen_alloc::erts::term::atom_unchecked;
+
+use crate::process;
+
+#[test]
+fn without_map_value_errors_badarg() {
+    let arc_process = process::test(&process::test_init());
+
+    assert_eq!(
+        native(&arc_process, flag(), atom_unchecked("default")),
+        Err(badarg!().into())
+    );
+    assert_eq!(
+        native(&arc_process, flag(), arc_process.integer(20).unwrap()),
+        Err(badarg!().into())
+    );
+}
+
+#[test]
+fn with_invalid_map_value_errors_badarg() {
+    let arc_process = process::test(&process::test_init());
+    let value = arc_process
+        .map_from_slice(&[(
+            atom_unchecked("shrink_threshold"),
+            arc_process.integer(90).unwrap(),
+        )])
+        .unwrap();
+
+    assert_eq!(native(&arc_process, flag(), value), Err(badarg!().into()));
+    assert_eq!(arc_process.heap_sizing(), heap_sizing());
+}
+
+#[test]
+fn with_map_value_sets_heap_sizing_and_returns_old_map() {
+    let arc_process = process::test(&process::test_init());
+    let old_heap_sizing = arc_process.heap_sizing();
+    let value = arc_process
+        .map_from_slice(&[(
+            atom_unchecked("growth_percent"),
+            arc_process.integer(50).unwrap(),
+        )])
+        .unwrap();
+
+    assert_eq!(
+        native(&arc_process, flag(), value),
+        Ok(old_heap_sizing.to_map(&arc_process).unwrap())
+    );
+    assert_eq!(
+        arc_process.heap_sizing(),
+        HeapSizing {
+            growth_percent: 50,
+            ..heap_sizing()
+        }
+    );
+}
+
+fn flag() -> Term {
+    atom_unchecked("heap_sizing")
+}
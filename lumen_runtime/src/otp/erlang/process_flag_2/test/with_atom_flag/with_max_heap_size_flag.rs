@@ -0,0 +1,97 @@
+use super::*;
+
+use liblumen_alloc::erts::process::{GcError, MaxHeapSize, Status};
+use liblumen_alloc::erts::term::atom_unchecked;
+
+use crate::process;
+use crate::process::max_heap_size::enforce;
+
+#[test]
+fn without_non_negative_integer_or_map_value_errors_badarg() {
+    let arc_process = process::test(&process::test_init());
+
+    assert_eq!(
+        native(&arc_process, flag(), atom_unchecked("infinity")),
+        Err(badarg!().into())
+    );
+    assert_eq!(
+        native(&arc_process, flag(), arc_process.integer(-1).unwrap()),
+        Err(badarg!().into())
+    );
+}
+
+#[test]
+fn with_non_negative_integer_value_returns_old_max_heap_size_map() {
+    let arc_process = process::test(&process::test_init());
+
+    assert_eq!(
+        native(&arc_process, flag(), arc_process.integer(1024).unwrap()),
+        Ok(max_heap_size_map(&arc_process, 0, true, true))
+    );
+    assert_eq!(
+        native(&arc_process, flag(), arc_process.integer(0).unwrap()),
+        Ok(max_heap_size_map(&arc_process, 1024, true, true))
+    );
+}
+
+#[test]
+fn with_map_value_sets_max_heap_size_and_returns_old() {
+    let arc_process = process::test(&process::test_init());
+    let value = max_heap_size_map(&arc_process, 2048, false, false);
+
+    assert_eq!(
+        native(&arc_process, flag(), value),
+        Ok(max_heap_size_map(&arc_process, 0, true, true))
+    );
+    assert_eq!(
+        arc_process.max_heap_size(),
+        MaxHeapSize {
+            size: 2048,
+            kill: false,
+            error_logger: false
+        }
+    );
+}
+
+#[test]
+fn with_kill_exceeding_max_heap_size_kills_process() {
+    let arc_process = process::test(&process::test_init());
+
+    assert!(native(&arc_process, flag(), arc_process.integer(1).unwrap()).is_ok());
+    assert_eq!(
+        enforce(&arc_process, Err(GcError::MaxHeapSizeExceeded)),
+        Err(GcError::MaxHeapSizeExceeded)
+    );
+
+    match *arc_process.status.read() {
+        Status::Exiting(ref runtime_exception) => {
+            assert_eq!(runtime_exception, &exit!(atom_unchecked("killed")))
+        }
+        ref status => panic!("Process status ({:?}) is not exiting", status),
+    };
+    assert_eq!(arc_process.max_heap_size(), Default::default());
+}
+
+#[test]
+fn without_kill_exceeding_max_heap_size_does_not_kill_process() {
+    let arc_process = process::test(&process::test_init());
+    let value = max_heap_size_map(&arc_process, 1, false, false);
+
+    assert!(native(&arc_process, flag(), value).is_ok());
+    assert_eq!(enforce(&arc_process, Ok(1)), Ok(1));
+    assert!(!arc_process.is_exiting());
+}
+
+fn flag() -> Term {
+    atom_unchecked("max_heap_size")
+}
+
+fn max_heap_size_map(process: &Process, size: usize, kill: bool, error_logger: bool) -> Term {
+    process
+        .map_from_slice(&[
+            (atom_unchecked("error_logger"), error_logger.into()),
+            (atom_unchecked("kill"), kill.into()),
+            (atom_unchecked("size"), process.integer(size).unwrap()),
+        ])
+        .unwrap()
+}
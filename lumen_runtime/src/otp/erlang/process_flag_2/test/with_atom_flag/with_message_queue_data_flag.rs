@@ -0,0 +1,85 @@
+use super::*;
+
+use liblumen_alloc::erts::process::ProcessFlags;
+use liblumen_alloc::erts::term::atom_unchecked;
+
+use crate::process;
+use crate::test::{has_heap_message, has_process_message, receive_message};
+
+#[test]
+fn without_on_heap_or_off_heap_value_errors_badarg() {
+    let arc_process = process::test(&process::test_init());
+
+    assert_eq!(
+        native(&arc_process, flag(), atom_unchecked("in_heap")),
+        Err(badarg!().into())
+    );
+    assert_eq!(
+        native(&arc_process, flag(), arc_process.integer(0).unwrap()),
+        Err(badarg!().into())
+    );
+}
+
+#[test]
+fn with_on_heap_or_off_heap_value_returns_old_value() {
+    let arc_process = process::test(&process::test_init());
+
+    assert_eq!(
+        native(&arc_process, flag(), atom_unchecked("off_heap")),
+        Ok(atom_unchecked("on_heap"))
+    );
+    assert_eq!(
+        native(&arc_process, flag(), atom_unchecked("on_heap")),
+        Ok(atom_unchecked("off_heap"))
+    );
+}
+
+#[test]
+fn with_on_heap_value_messages_from_other_processes_are_on_heap_after_collection() {
+    let init_arc_process = process::test_init();
+    let sender_arc_process = process::test(&init_arc_process);
+    let arc_process = process::test(&init_arc_process);
+
+    assert!(native(&arc_process, flag(), atom_unchecked("on_heap")).is_ok());
+
+    let message = sender_arc_process
+        .tuple_from_slice(&[atom_unchecked("message"), sender_arc_process.pid_term()])
+        .unwrap();
+
+    assert!(arc_process.send_from_other(message).is_ok());
+    assert!(has_heap_message(&arc_process, message));
+
+    // collecting merges the messages in heap fragments into the heap
+    arc_process.set_flags(ProcessFlags::NeedFullSweep);
+    assert!(arc_process.garbage_collect(0, &mut []).is_ok());
+
+    assert!(has_process_message(&arc_process, message));
+    assert_eq!(receive_message(&arc_process), Some(message));
+}
+
+#[test]
+fn with_off_heap_value_messages_from_other_processes_are_off_heap_until_received() {
+    let init_arc_process = process::test_init();
+    let sender_arc_process = process::test(&init_arc_process);
+    let arc_process = process::test(&init_arc_process);
+
+    assert!(native(&arc_process, flag(), atom_unchecked("off_heap")).is_ok());
+
+    let message = sender_arc_process
+        .tuple_from_slice(&[atom_unchecked("message"), sender_arc_process.pid_term()])
+        .unwrap();
+
+    assert!(arc_process.send_from_other(message).is_ok());
+    assert!(has_heap_message(&arc_process, message));
+
+    // collecting doesn't touch the messages that are still queued
+    arc_process.set_flags(ProcessFlags::NeedFullSweep);
+    assert!(arc_process.garbage_collect(0, &mut []).is_ok());
+
+    assert!(has_heap_message(&arc_process, message));
+    assert_eq!(receive_message(&arc_process), Some(message));
+}
+
+fn flag() -> Term {
+    atom_unchecked("message_queue_data")
+}
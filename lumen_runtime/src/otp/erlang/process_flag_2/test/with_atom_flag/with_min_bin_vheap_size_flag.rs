@@ -0,0 +1,40 @@
+use super::*;
+
+use liblumen_alloc::erts::term::atom_unchecked;
+
+use crate::process;
+
+#[test]
+fn without_non_negative_integer_value_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &strategy::term::is_not_non_negative_integer(arc_process.clone()),
+                |value| {
+                    prop_assert_eq!(native(&arc_process, flag(), value), Err(badarg!().into()));
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_non_negative_integer_value_returns_old_min_bin_vheap_size() {
+    let arc_process = process::test(&process::test_init());
+
+    assert_eq!(
+        native(&arc_process, flag(), arc_process.integer(46_422).unwrap()),
+        Ok(arc_process.integer(0).unwrap())
+    );
+    assert_eq!(arc_process.min_bin_vheap_size(), 46_422);
+    assert_eq!(
+        native(&arc_process, flag(), arc_process.integer(0).unwrap()),
+        Ok(arc_process.integer(46_422).unwrap())
+    );
+}
+
+fn flag() -> Term {
+    atom_unchecked("min_bin_vheap_size")
+}
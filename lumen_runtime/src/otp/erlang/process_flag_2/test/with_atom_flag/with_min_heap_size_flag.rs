@@ -0,0 +1,56 @@
+use super::*;
+
+use liblumen_alloc::erts::process::alloc::next_heap_size;
+use liblumen_alloc::erts::term::atom_unchecked;
+
+use crate::process;
+
+#[test]
+fn without_non_negative_integer_value_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &strategy::term::is_not_non_negative_integer(arc_process.clone()),
+                |value| {
+                    prop_assert_eq!(native(&arc_process, flag(), value), Err(badarg!().into()));
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_non_negative_integer_value_returns_old_min_heap_size() {
+    let arc_process = process::test(&process::test_init());
+    let spawned_min_heap_size = arc_process.min_heap_size();
+
+    assert_eq!(
+        native(&arc_process, flag(), arc_process.integer(50_000).unwrap()),
+        Ok(arc_process.integer(spawned_min_heap_size).unwrap())
+    );
+
+    let min_heap_size = next_heap_size(50_000);
+
+    assert_eq!(arc_process.min_heap_size(), min_heap_size);
+    assert_eq!(
+        native(&arc_process, flag(), arc_process.integer(0).unwrap()),
+        Ok(arc_process.integer(min_heap_size).unwrap())
+    );
+}
+
+#[test]
+fn with_non_negative_integer_value_keeps_heap_from_shrinking_below_it() {
+    let arc_process = process::test(&process::test_init());
+    let min_heap_size = next_heap_size(50_000);
+
+    assert!(native(&arc_process, flag(), arc_process.integer(50_000).unwrap()).is_ok());
+    assert!(arc_process.garbage_collect(0, &mut []).is_ok());
+
+    assert!(min_heap_size <= arc_process.heap_size());
+}
+
+fn flag() -> Term {
+    atom_unchecked("min_heap_size")
+}
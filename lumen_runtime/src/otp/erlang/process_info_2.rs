@@ -90,7 +90,7 @@ fn process_info(process: &Process, item: Atom) -> exception::Result {
         "error_handler" => unimplemented!(),
         "garbage_collection" => unimplemented!(),
         "garbage_collection_info" => unimplemented!(),
-        "group_leader" => unimplemented!(),
+        "group_leader" => group_leader(process),
         "heap_size" => unimplemented!(),
         "initial_call" => unimplemented!(),
         "links" => unimplemented!(),
@@ -98,8 +98,8 @@ fn process_info(process: &Process, item: Atom) -> exception::Result {
         "memory" => unimplemented!(),
         "message_queue_len" => unimplemented!(),
         "messages" => unimplemented!(),
-        "min_heap_size" => unimplemented!(),
-        "min_bin_vheap_size" => unimplemented!(),
+        "min_heap_size" => min_heap_size(process),
+        "min_bin_vheap_size" => min_bin_vheap_size(process),
         "monitored_by" => unimplemented!(),
         "monitors" => unimplemented!(),
         "message_queue_data" => unimplemented!(),
@@ -117,6 +117,33 @@ fn process_info(process: &Process, item: Atom) -> exception::Result {
     }
 }
 
+fn group_leader(process: &Process) -> exception::Result {
+    let tag = atom_unchecked("group_leader");
+    let value = unsafe { process.group_leader_pid().as_term() };
+
+    process
+        .tuple_from_slice(&[tag, value])
+        .map_err(|error| error.into())
+}
+
+fn min_bin_vheap_size(process: &Process) -> exception::Result {
+    let tag = atom_unchecked("min_bin_vheap_size");
+    let value = process.integer(process.min_bin_vheap_size())?;
+
+    process
+        .tuple_from_slice(&[tag, value])
+        .map_err(|error| error.into())
+}
+
+fn min_heap_size(process: &Process) -> exception::Result {
+    let tag = atom_unchecked("min_heap_size");
+    let value = process.integer(process.min_heap_size())?;
+
+    process
+        .tuple_from_slice(&[tag, value])
+        .map_err(|error| error.into())
+}
+
 fn registered_name(process: &Process) -> exception::Result {
     match *process.registered_name.read() {
         Some(registered_name) => {
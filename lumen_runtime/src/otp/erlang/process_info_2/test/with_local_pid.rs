@@ -1,3 +1,6 @@
+mod with_group_leader;
+mod with_min_bin_vheap_size;
+mod with_min_heap_size;
 mod with_registered_name;
 
 use super::*;
@@ -28,7 +31,9 @@ fn unsupported_item(arc_process: Arc<Process>) -> BoxedStrategy<Term> {
         .prop_filter("Item cannot be supported", |item| {
             match item.to_typed_term().unwrap() {
                 TypedTerm::Atom(atom) => match atom.name() {
-                    "registered_name" => false,
+                    "group_leader" | "min_bin_vheap_size" | "min_heap_size" | "registered_name" => {
+                        false
+                    }
                     _ => true,
                 },
                 _ => true,
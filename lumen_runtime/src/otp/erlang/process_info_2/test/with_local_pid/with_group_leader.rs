@@ -0,0 +1,17 @@
+use super::*;
+
+#[test]
+fn with_self_returns_group_leader() {
+    with_process_arc(|arc_process| {
+        assert_eq!(
+            native(&arc_process, arc_process.pid_term(), item()),
+            Ok(arc_process
+                .tuple_from_slice(&[item(), arc_process.pid_term()])
+                .unwrap())
+        );
+    });
+}
+
+fn item() -> Term {
+    atom_unchecked("group_leader")
+}
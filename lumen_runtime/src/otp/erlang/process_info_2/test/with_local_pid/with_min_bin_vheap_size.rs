@@ -0,0 +1,19 @@
+use super::*;
+
+#[test]
+fn with_self_returns_min_bin_vheap_size() {
+    with_process_arc(|arc_process| {
+        arc_process.set_min_bin_vheap_size(46_422);
+
+        assert_eq!(
+            native(&arc_process, arc_process.pid_term(), item()),
+            Ok(arc_process
+                .tuple_from_slice(&[item(), arc_process.integer(46_422).unwrap()])
+                .unwrap())
+        );
+    });
+}
+
+fn item() -> Term {
+    atom_unchecked("min_bin_vheap_size")
+}
@@ -0,0 +1,20 @@
+use super::*;
+
+#[test]
+fn with_self_returns_min_heap_size() {
+    with_process_arc(|arc_process| {
+        assert_eq!(
+            native(&arc_process, arc_process.pid_term(), item()),
+            Ok(arc_process
+                .tuple_from_slice(&[
+                    item(),
+                    arc_process.integer(arc_process.min_heap_size()).unwrap()
+                ])
+                .unwrap())
+        );
+    });
+}
+
+fn item() -> Term {
+    atom_unchecked("min_heap_size")
+}
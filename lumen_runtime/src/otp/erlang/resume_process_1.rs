@@ -0,0 +1,86 @@
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::code::{self, result_from_exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{Atom, Pid, Term};
+use liblumen_alloc::{badarg, ModuleFunctionArity};
+
+use crate::registry::pid_to_process;
+use crate::scheduler::Scheduled;
+
+pub fn place_frame_with_arguments(
+    process: &Process,
+    placement: Placement,
+    suspendee: Term,
+) -> Result<(), Alloc> {
+    process.stack_push(suspendee)?;
+    process.place_frame(frame(), placement);
+
+    Ok(())
+}
+
+// Private
+
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.reduce();
+
+    let suspendee = arc_process.stack_pop().unwrap();
+
+    match native(arc_process, suspendee) {
+        Ok(resumed) => {
+            arc_process.return_from_call(resumed)?;
+
+            Process::call_code(arc_process)
+        }
+        Err(exception) => result_from_exception(arc_process, exception),
+    }
+}
+
+fn frame() -> Frame {
+    Frame::new(module_function_arity(), code)
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("resume_process").unwrap()
+}
+
+fn module_function_arity() -> Arc<ModuleFunctionArity> {
+    Arc::new(ModuleFunctionArity {
+        module: super::module(),
+        function: function(),
+        arity: 1,
+    })
+}
+
+/// Undoes one `erlang:suspend_process/1,2` of the process with `suspendee` pid by `process`.  Once
+/// no process has it suspended, it is run again.
+pub fn native(process: &Process, suspendee: Term) -> exception::Result {
+    let suspendee_pid: Pid = suspendee.try_into()?;
+
+    match pid_to_process(&suspendee_pid) {
+        Some(suspendee_arc_process) => match suspendee_arc_process.resume(process.pid()) {
+            Some(still_suspended) => {
+                if !still_suspended {
+                    if let Some(arc_scheduler) = suspendee_arc_process.scheduler() {
+                        arc_scheduler.resume(&suspendee_arc_process);
+                    }
+                }
+
+                Ok(true.into())
+            }
+            None => Err(badarg!().into()),
+        },
+        None => Err(badarg!().into()),
+    }
+}
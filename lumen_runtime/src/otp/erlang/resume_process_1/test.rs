@@ -0,0 +1,75 @@
+use proptest::prop_assert_eq;
+use proptest::test_runner::{Config, TestRunner};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::term::{next_pid, Term};
+
+use crate::otp::erlang::resume_process_1::native;
+use crate::otp::erlang::suspend_process_1;
+use crate::process;
+use crate::scheduler::{with_process, with_process_arc};
+use crate::test::strategy;
+
+#[test]
+fn without_local_pid_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &strategy::term::is_not_local_pid(arc_process.clone()),
+                |suspendee| {
+                    prop_assert_eq!(native(&arc_process, suspendee), Err(badarg!().into()));
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_non_existent_pid_errors_badarg() {
+    with_process(|process| {
+        assert_eq!(native(process, next_pid()), Err(badarg!().into()));
+    });
+}
+
+#[test]
+fn without_suspending_process_errors_badarg() {
+    with_process(|process| {
+        let suspendee = process::test(process);
+
+        assert_eq!(native(process, suspendee.pid_term()), Err(badarg!().into()));
+    });
+}
+
+#[test]
+fn with_suspending_process_resumes_once_per_suspend() {
+    with_process(|process| {
+        let suspendee = process::test(process);
+
+        assert!(suspend_process_1::native(process, suspendee.pid_term()).is_ok());
+        assert!(suspend_process_1::native(process, suspendee.pid_term()).is_ok());
+
+        assert_eq!(native(process, suspendee.pid_term()), Ok(true.into()));
+        assert!(suspendee.is_suspended());
+
+        assert_eq!(native(process, suspendee.pid_term()), Ok(true.into()));
+        assert!(!suspendee.is_suspended());
+
+        assert_eq!(native(process, suspendee.pid_term()), Err(badarg!().into()));
+    });
+}
+
+#[test]
+fn with_other_suspending_process_stays_suspended() {
+    with_process(|process| {
+        let other_suspender = process::test(process);
+        let suspendee = process::test(process);
+
+        assert!(suspend_process_1::native(process, suspendee.pid_term()).is_ok());
+        assert!(suspend_process_1::native(&other_suspender, suspendee.pid_term()).is_ok());
+
+        assert_eq!(native(process, suspendee.pid_term()), Ok(true.into()));
+        assert!(suspendee.is_suspended());
+    });
+}
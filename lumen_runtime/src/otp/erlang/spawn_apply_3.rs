@@ -1,10 +1,11 @@
 use std::convert::TryInto;
 
-use liblumen_alloc::badarg;
 use liblumen_alloc::erts::exception;
 use liblumen_alloc::erts::process::Process;
-use liblumen_alloc::erts::term::{Atom, Term};
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Term};
+use liblumen_alloc::{badarg, error};
 
+use crate::process::limit;
 use crate::process::spawn::options::Options;
 use crate::scheduler::Scheduler;
 
@@ -18,12 +19,16 @@ pub(in crate::otp::erlang) fn native(
     let module_atom: Atom = module.try_into()?;
     let function_atom: Atom = function.try_into()?;
 
-    if arguments.is_proper_list() {
-        let arc_process =
-            Scheduler::spawn_apply_3(process, options, module_atom, function_atom, arguments)?;
-
-        Ok(arc_process.pid_term())
-    } else {
+    if !arguments.is_proper_list() {
         Err(badarg!().into())
+    } else if !limit::reserve() {
+        Err(error!(atom_unchecked("system_limit")).into())
+    } else {
+        let result =
+            Scheduler::spawn_apply_3(process, options, module_atom, function_atom, arguments);
+        // the spawned process counts itself
+        limit::unreserve();
+
+        Ok(result?.pid_term())
     }
 }
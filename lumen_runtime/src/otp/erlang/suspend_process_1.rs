@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::code::{self, result_from_exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{Atom, Term};
+use liblumen_alloc::ModuleFunctionArity;
+
+use crate::otp::erlang::suspend_process_2;
+
+pub fn place_frame_with_arguments(
+    process: &Process,
+    placement: Placement,
+    suspendee: Term,
+) -> Result<(), Alloc> {
+    process.stack_push(suspendee)?;
+    process.place_frame(frame(), placement);
+
+    Ok(())
+}
+
+// Private
+
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.reduce();
+
+    let suspendee = arc_process.stack_pop().unwrap();
+
+    match native(arc_process, suspendee) {
+        Ok(suspended) => {
+            arc_process.return_from_call(suspended)?;
+
+            Process::call_code(arc_process)
+        }
+        Err(exception) => result_from_exception(arc_process, exception),
+    }
+}
+
+fn frame() -> Frame {
+    Frame::new(module_function_arity(), code)
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("suspend_process").unwrap()
+}
+
+fn module_function_arity() -> Arc<ModuleFunctionArity> {
+    Arc::new(ModuleFunctionArity {
+        module: super::module(),
+        function: function(),
+        arity: 1,
+    })
+}
+
+pub fn native(process: &Process, suspendee: Term) -> exception::Result {
+    suspend_process_2::native(process, suspendee, Term::NIL)
+}
@@ -0,0 +1,109 @@
+mod options;
+
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::code::{self, result_from_exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Pid, Term};
+use liblumen_alloc::{badarg, ModuleFunctionArity};
+
+use crate::otp::erlang::suspend_process_2::options::Options;
+use crate::registry::pid_to_process;
+
+pub fn place_frame_with_arguments(
+    process: &Process,
+    placement: Placement,
+    suspendee: Term,
+    options: Term,
+) -> Result<(), Alloc> {
+    process.stack_push(options)?;
+    process.stack_push(suspendee)?;
+    process.place_frame(frame(), placement);
+
+    Ok(())
+}
+
+// Private
+
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.reduce();
+
+    let suspendee = arc_process.stack_pop().unwrap();
+    let options = arc_process.stack_pop().unwrap();
+
+    match native(arc_process, suspendee, options) {
+        Ok(suspended) => {
+            arc_process.return_from_call(suspended)?;
+
+            Process::call_code(arc_process)
+        }
+        Err(exception) => result_from_exception(arc_process, exception),
+    }
+}
+
+fn frame() -> Frame {
+    Frame::new(module_function_arity(), code)
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("suspend_process").unwrap()
+}
+
+fn module_function_arity() -> Arc<ModuleFunctionArity> {
+    Arc::new(ModuleFunctionArity {
+        module: super::module(),
+        function: function(),
+        arity: 2,
+    })
+}
+
+/// Suspends the process with `suspendee` pid on behalf of `process` until `process` resumes it with
+/// `erlang:resume_process/1` as many times as it suspended it.  Returns `false` without suspending
+/// it again if `options` has `unless_suspending` and `process` already suspended it.
+///
+/// A suspendee that is running on another scheduler finishes its current run before it is held
+/// back, so the `asynchronous` options only change whether `{ReplyTag, suspended}` is sent.
+pub fn native(process: &Process, suspendee: Term, options: Term) -> exception::Result {
+    let suspendee_pid: Pid = suspendee.try_into()?;
+
+    if suspendee_pid == process.pid() {
+        return Err(badarg!().into());
+    }
+
+    let Options {
+        unless_suspending,
+        reply_tag,
+    } = options.try_into()?;
+
+    match pid_to_process(&suspendee_pid) {
+        Some(suspendee_arc_process) if !suspendee_arc_process.is_exiting() => {
+            let suspended =
+                if unless_suspending && 0 < suspendee_arc_process.suspend_count(process.pid()) {
+                    false
+                } else {
+                    suspendee_arc_process.suspend(process.pid());
+
+                    true
+                };
+
+            if let Some(reply_tag) = reply_tag {
+                let reply = process.tuple_from_slice(&[reply_tag, atom_unchecked("suspended")])?;
+                process.send_from_self(reply);
+            }
+
+            Ok(suspended.into())
+        }
+        _ => Err(badarg!().into()),
+    }
+}
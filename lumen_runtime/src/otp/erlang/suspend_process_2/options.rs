@@ -0,0 +1,93 @@
+use std::convert::{TryFrom, TryInto};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception::runtime;
+use liblumen_alloc::erts::term::{Atom, Boxed, Cons, Term, Tuple, TypedTerm};
+
+pub struct Options {
+    pub unless_suspending: bool,
+    /// Set by `{asynchronous, ReplyTag}`
+    pub reply_tag: Option<Term>,
+}
+
+impl Options {
+    fn put_option(&mut self, option: Term) -> Result<(), runtime::Exception> {
+        match option.to_typed_term().unwrap() {
+            TypedTerm::Atom(atom) => match atom.name() {
+                // there is nothing to wait for, so only the reply differs
+                "asynchronous" => Ok(()),
+                "unless_suspending" => {
+                    self.unless_suspending = true;
+
+                    Ok(())
+                }
+                _ => Err(badarg!()),
+            },
+            TypedTerm::Boxed(_) => {
+                let option_tuple: Boxed<Tuple> = option.try_into()?;
+
+                if option_tuple.len() != 2 {
+                    return Err(badarg!());
+                }
+
+                let name: Atom = option_tuple[0].try_into()?;
+
+                match name.name() {
+                    "asynchronous" => {
+                        self.reply_tag = Some(option_tuple[1]);
+
+                        Ok(())
+                    }
+                    _ => Err(badarg!()),
+                }
+            }
+            _ => Err(badarg!()),
+        }
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            unless_suspending: false,
+            reply_tag: None,
+        }
+    }
+}
+
+impl TryFrom<Boxed<Cons>> for Options {
+    type Error = runtime::Exception;
+
+    fn try_from(cons: Boxed<Cons>) -> Result<Self, Self::Error> {
+        let mut options: Options = Default::default();
+
+        for result in cons.into_iter() {
+            match result {
+                Ok(option) => options.put_option(option)?,
+                Err(_) => return Err(badarg!()),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+impl TryFrom<Term> for Options {
+    type Error = runtime::Exception;
+
+    fn try_from(term: Term) -> Result<Self, Self::Error> {
+        term.to_typed_term().unwrap().try_into()
+    }
+}
+
+impl TryFrom<TypedTerm> for Options {
+    type Error = runtime::Exception;
+
+    fn try_from(typed_term: TypedTerm) -> Result<Self, Self::Error> {
+        match typed_term {
+            TypedTerm::Nil => Ok(Default::default()),
+            TypedTerm::List(cons) => cons.try_into(),
+            _ => Err(badarg!()),
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use proptest::prop_assert_eq;
+use proptest::test_runner::{Config, TestRunner};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::term::{atom_unchecked, next_pid, Term};
+
+use crate::otp::erlang::suspend_process_2::native;
+use crate::process;
+use crate::scheduler::{with_process, with_process_arc};
+use crate::test::{has_message, strategy};
+
+#[test]
+fn without_local_pid_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &strategy::term::is_not_local_pid(arc_process.clone()),
+                |suspendee| {
+                    prop_assert_eq!(
+                        native(&arc_process, suspendee, Term::NIL),
+                        Err(badarg!().into())
+                    );
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_self_errors_badarg() {
+    with_process(|process| {
+        assert_eq!(
+            native(process, process.pid_term(), Term::NIL),
+            Err(badarg!().into())
+        );
+    });
+}
+
+#[test]
+fn with_non_existent_pid_errors_badarg() {
+    with_process(|process| {
+        assert_eq!(
+            native(process, next_pid(), Term::NIL),
+            Err(badarg!().into())
+        );
+    });
+}
+
+#[test]
+fn with_unknown_option_errors_badarg() {
+    with_process(|process| {
+        let suspendee = process::test(process);
+        let options = process.list_from_slice(&[atom_unchecked("sync")]).unwrap();
+
+        assert_eq!(
+            native(process, suspendee.pid_term(), options),
+            Err(badarg!().into())
+        );
+    });
+}
+
+#[test]
+fn with_other_process_suspends_it_once_per_call() {
+    with_process(|process| {
+        let suspendee = process::test(process);
+
+        assert_eq!(
+            native(process, suspendee.pid_term(), Term::NIL),
+            Ok(true.into())
+        );
+        assert_eq!(
+            native(process, suspendee.pid_term(), Term::NIL),
+            Ok(true.into())
+        );
+
+        assert!(suspendee.is_suspended());
+        assert_eq!(suspendee.suspend_count(process.pid()), 2);
+    });
+}
+
+#[test]
+fn with_unless_suspending_does_not_suspend_again() {
+    with_process(|process| {
+        let suspendee = process::test(process);
+        let options = process
+            .list_from_slice(&[atom_unchecked("unless_suspending")])
+            .unwrap();
+
+        assert_eq!(
+            native(process, suspendee.pid_term(), options),
+            Ok(true.into())
+        );
+        assert_eq!(
+            native(process, suspendee.pid_term(), options),
+            Ok(false.into())
+        );
+
+        assert_eq!(suspendee.suspend_count(process.pid()), 1);
+    });
+}
+
+#[test]
+fn with_asynchronous_reply_tag_sends_suspended() {
+    with_process(|process| {
+        let suspendee = process::test(process);
+        let reply_tag = atom_unchecked("reply_tag");
+        let option = process
+            .tuple_from_slice(&[atom_unchecked("asynchronous"), reply_tag])
+            .unwrap();
+        let options = process.list_from_slice(&[option]).unwrap();
+
+        assert_eq!(
+            native(process, suspendee.pid_term(), options),
+            Ok(true.into())
+        );
+
+        assert!(has_message(
+            process,
+            process
+                .tuple_from_slice(&[reply_tag, atom_unchecked("suspended")])
+                .unwrap()
+        ));
+    });
+}
@@ -18,6 +18,7 @@ use crate::test::{
 };
 
 mod abs_1;
+mod adler32_2;
 mod and_2;
 mod andalso_2;
 mod append_element_2;
@@ -25,6 +26,7 @@ mod are_equal_after_conversion_2;
 mod are_exactly_equal_2;
 mod are_exactly_not_equal_2;
 mod are_not_equal_after_conversion_2;
+mod atom_to_binary_1;
 mod atom_to_binary_2;
 mod atom_to_list_1;
 mod band_2;
@@ -34,7 +36,6 @@ mod binary_to_atom_2;
 mod binary_to_existing_atom_2;
 mod binary_to_float_1;
 mod binary_to_integer_2;
-mod binary_to_list_1;
 mod binary_to_list_3;
 mod binary_to_term_1;
 mod binary_to_term_2;
@@ -50,12 +51,17 @@ mod cancel_timer_1;
 mod cancel_timer_2;
 mod ceil_1;
 mod concatenate_2;
+mod crc32_2;
 mod delete_element_2;
+mod display_1;
 mod div_2;
 mod divide_2;
 mod element_2;
 mod error_1;
 mod error_2;
+mod floor_1;
+mod group_leader_0;
+mod group_leader_2;
 mod hd_1;
 mod insert_element_3;
 mod is_alive_0;
@@ -77,7 +83,6 @@ mod is_record_2;
 mod is_record_3;
 mod is_reference_1;
 mod is_tuple_1;
-mod length_1;
 mod list_to_atom_1;
 mod list_to_binary_1;
 mod list_to_bitstring_1;
@@ -85,23 +90,30 @@ mod list_to_existing_atom_1;
 mod list_to_pid_1;
 mod list_to_tuple_1;
 mod make_ref_0;
+mod make_tuple_2;
+mod make_tuple_3;
 mod map_get_2;
 mod map_size_1;
 mod max_2;
+mod md5_1;
+mod md5_update_2;
 mod min_2;
 mod monotonic_time_1;
 mod multiply_2;
 mod negate_1;
 mod node_0;
+mod node_1;
 mod not_1;
 mod or_2;
 mod orelse_2;
+mod processes_0;
 mod raise_3;
 mod read_timer_1;
 mod read_timer_2;
 mod register_2;
 mod registered_0;
 mod rem_2;
+mod round_1;
 mod send_2;
 mod send_3;
 mod send_after_3;
@@ -112,8 +124,10 @@ mod split_binary_2;
 mod start_timer_3;
 mod start_timer_4;
 mod subtract_list_2;
+mod term_to_binary_2;
 mod throw_1;
 mod tl_1;
+mod trunc_1;
 mod tuple_size_1;
 mod tuple_to_list_1;
 mod unregister_1;
@@ -154,6 +168,15 @@ fn count_ones_in_big_integer(big_integer: Boxed<BigInteger>) -> u32 {
         .sum()
 }
 
+fn error_info_argument(result: Result) -> Option<usize> {
+    match result {
+        Err(Exception::Runtime(runtime_exception)) => runtime_exception
+            .error_info
+            .map(|error_info| error_info.argument),
+        _ => None,
+    }
+}
+
 fn errors_badarg<F>(actual: F)
 where
     F: FnOnce(&Process) -> Result,
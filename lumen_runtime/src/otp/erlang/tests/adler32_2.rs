@@ -0,0 +1,94 @@
+use super::*;
+
+#[test]
+fn without_iodata_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &strategy::term::is_not_list(arc_process.clone())
+                    .prop_filter("Data cannot be a binary", |data| !data.is_binary()),
+                |data| {
+                    prop_assert_eq!(erlang::adler32_1(data, &arc_process), Err(badarg!().into()));
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn without_32_bit_old_checksum_errors_badarg() {
+    with_process(|process| {
+        let data = process.binary_from_bytes(b"data").unwrap();
+
+        for old in &[
+            process.integer(-1).unwrap(),
+            process.integer(1_u64 << 32).unwrap(),
+            atom_unchecked("checksum"),
+        ] {
+            assert_eq!(
+                erlang::adler32_2(*old, data, &process),
+                Err(badarg!().into())
+            );
+        }
+    });
+}
+
+#[test]
+fn with_empty_binary_returns_initial_checksum() {
+    with_process(|process| {
+        let data = process.binary_from_bytes(&[]).unwrap();
+
+        assert_eq!(
+            erlang::adler32_1(data, &process),
+            Ok(process.integer(1).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_iolist_returns_checksum_of_bytes() {
+    with_process(|process| {
+        let iolist = process
+            .list_from_slice(&[
+                process.binary_from_bytes(b"The quick brown fox").unwrap(),
+                process
+                    .charlist_from_str(" jumps over the lazy dog")
+                    .unwrap(),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            erlang::adler32_1(iolist, &process),
+            Ok(process.integer(0x5bdc0fda_u64).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_old_checksum_continues_checksum() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &(strategy::byte_vec(), strategy::byte_vec()),
+                |(first_bytes, second_bytes)| {
+                    let first = arc_process.binary_from_bytes(&first_bytes).unwrap();
+                    let second = arc_process.binary_from_bytes(&second_bytes).unwrap();
+                    let old = erlang::adler32_1(first, &arc_process).unwrap();
+
+                    let mut all_bytes = first_bytes.clone();
+                    all_bytes.extend_from_slice(&second_bytes);
+                    let all = arc_process.binary_from_bytes(&all_bytes).unwrap();
+
+                    prop_assert_eq!(
+                        erlang::adler32_2(old, second, &arc_process),
+                        erlang::adler32_1(all, &arc_process)
+                    );
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
@@ -97,3 +97,17 @@ fn with_different_list_right_returns_false() {
         )
         .unwrap();
 }
+
+#[test]
+fn with_list_right_with_equal_after_conversion_element_returns_false() {
+    with_process(|process| {
+        let left = process
+            .list_from_slice(&[process.integer(1).unwrap()])
+            .unwrap();
+        let right = process
+            .list_from_slice(&[process.float(1.0).unwrap()])
+            .unwrap();
+
+        assert_eq!(erlang::are_exactly_equal_2(left, right), false.into());
+    });
+}
@@ -88,3 +88,33 @@ fn with_different_map_right_returns_false() {
         )
         .unwrap();
 }
+
+#[test]
+fn with_map_right_with_equal_after_conversion_key_returns_false() {
+    with_process(|process| {
+        let value = atom_unchecked("value");
+        let left = process
+            .map_from_slice(&[(process.integer(1).unwrap(), value)])
+            .unwrap();
+        let right = process
+            .map_from_slice(&[(process.float(1.0).unwrap(), value)])
+            .unwrap();
+
+        assert_eq!(erlang::are_exactly_equal_2(left, right), false.into());
+    });
+}
+
+#[test]
+fn with_map_right_with_equal_after_conversion_value_returns_false() {
+    with_process(|process| {
+        let key = atom_unchecked("key");
+        let left = process
+            .map_from_slice(&[(key, process.integer(1).unwrap())])
+            .unwrap();
+        let right = process
+            .map_from_slice(&[(key, process.float(1.0).unwrap())])
+            .unwrap();
+
+        assert_eq!(erlang::are_exactly_equal_2(left, right), false.into());
+    });
+}
@@ -0,0 +1,38 @@
+use super::*;
+
+use proptest::strategy::Strategy;
+
+#[test]
+fn without_atom_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(&strategy::term::is_not_atom(arc_process.clone()), |atom| {
+                prop_assert_eq!(
+                    erlang::atom_to_binary_1(atom, &arc_process),
+                    Err(badarg!().into())
+                );
+
+                Ok(())
+            })
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_atom_returns_name_in_utf8_binary() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &any::<String>().prop_map(|string| (atom_unchecked(&string), string)),
+                |(atom, string)| {
+                    prop_assert_eq!(
+                        erlang::atom_to_binary_1(atom, &arc_process),
+                        Ok(arc_process.binary_from_bytes(string.as_bytes()).unwrap())
+                    );
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
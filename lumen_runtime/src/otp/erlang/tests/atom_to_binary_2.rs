@@ -47,11 +47,11 @@ fn with_atom_without_encoding_errors_badarg() {
 }
 
 #[test]
-fn with_atom_with_encoding_atom_returns_name_in_binary() {
+fn with_atom_with_utf8_encoding_returns_name_in_binary() {
     with_process_arc(|arc_process| {
         TestRunner::new(Config::with_source_file(file!()))
             .run(
-                &(any::<String>(), strategy::term::is_encoding())
+                &(any::<String>(), strategy::term::is_utf8_encoding())
                     .prop_map(|(string, encoding)| (atom_unchecked(&string), encoding, string)),
                 |(atom, encoding, string)| {
                     prop_assert_eq!(
@@ -65,3 +65,35 @@ fn with_atom_with_encoding_atom_returns_name_in_binary() {
             .unwrap();
     });
 }
+
+#[test]
+fn with_latin1_atom_with_latin1_encoding_returns_one_byte_per_character() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(&any::<Vec<u8>>(), |byte_vec| {
+                let name: String = byte_vec.iter().map(|&byte| byte as char).collect();
+
+                prop_assert_eq!(
+                    erlang::atom_to_binary_2(
+                        atom_unchecked(&name),
+                        atom_unchecked("latin1"),
+                        &arc_process
+                    ),
+                    Ok(arc_process.binary_from_bytes(&byte_vec).unwrap())
+                );
+
+                Ok(())
+            })
+            .unwrap();
+    });
+}
+
+#[test]
+fn without_latin1_atom_with_latin1_encoding_errors_badarg() {
+    with_process(|process| {
+        assert_eq!(
+            erlang::atom_to_binary_2(atom_unchecked("\u{3bb}"), atom_unchecked("latin1"), process),
+            Err(badarg!().into())
+        );
+    });
+}
@@ -1,5 +1,7 @@
 use super::*;
 
+use num_traits::Num;
+
 #[test]
 fn with_integer_right_returns_bitwise_and() {
     with_process_arc(|arc_process| {
@@ -26,3 +28,25 @@ fn with_integer_right_returns_bitwise_and() {
             .unwrap();
     });
 }
+
+#[test]
+fn with_negative_big_integer_left_uses_twos_complement() {
+    with_process(|process| {
+        // -(2^64) is all ones above the lowest 64 bits
+        let left = process
+            .integer(<BigInt as Num>::from_str_radix("-18446744073709551616", 10).unwrap())
+            .unwrap();
+        // 2^65 + 2^64 + 1
+        let right = process
+            .integer(<BigInt as Num>::from_str_radix("55340232221128654849", 10).unwrap())
+            .unwrap();
+
+        // 2^65 + 2^64
+        assert_eq!(
+            erlang::band_2(left, right, &process),
+            Ok(process
+                .integer(<BigInt as Num>::from_str_radix("55340232221128654848", 10).unwrap())
+                .unwrap())
+        );
+    });
+}
@@ -11,7 +11,7 @@ fn without_binary_errors_badarg() {
                 ),
                 |(binary, encoding)| {
                     prop_assert_eq!(
-                        erlang::binary_to_atom_2(binary, encoding),
+                        erlang::binary_to_atom_2(binary, encoding, &arc_process),
                         Err(badarg!().into())
                     );
 
@@ -33,7 +33,7 @@ fn with_binary_without_encoding_errors_badarg() {
                 ),
                 |(binary, encoding)| {
                     prop_assert_eq!(
-                        erlang::binary_to_atom_2(binary, encoding),
+                        erlang::binary_to_atom_2(binary, encoding, &arc_process),
                         Err(badarg!().into())
                     );
 
@@ -45,13 +45,13 @@ fn with_binary_without_encoding_errors_badarg() {
 }
 
 #[test]
-fn with_utf8_binary_with_encoding_returns_atom_with_binary_name() {
+fn with_utf8_binary_with_utf8_encoding_returns_atom_with_binary_name() {
     with_process_arc(|arc_process| {
         TestRunner::new(Config::with_source_file(file!()))
             .run(
                 &(
                     strategy::term::binary::is_utf8(arc_process.clone()),
-                    strategy::term::is_encoding(),
+                    strategy::term::is_utf8_encoding(),
                 ),
                 |(binary, encoding)| {
                     let byte_vec: Vec<u8> = match binary.to_typed_term().unwrap() {
@@ -71,7 +71,7 @@ fn with_utf8_binary_with_encoding_returns_atom_with_binary_name() {
                     let s = std::str::from_utf8(&byte_vec).unwrap();
 
                     prop_assert_eq!(
-                        erlang::binary_to_atom_2(binary, encoding),
+                        erlang::binary_to_atom_2(binary, encoding, &arc_process),
                         Ok(atom_unchecked(s))
                     );
 
@@ -81,3 +81,66 @@ fn with_utf8_binary_with_encoding_returns_atom_with_binary_name() {
             .unwrap();
     });
 }
+
+#[test]
+fn without_utf8_binary_with_utf8_encoding_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &(
+                    strategy::term::binary::containing_bytes(vec![0xFF, 0xFE], arc_process.clone()),
+                    strategy::term::is_utf8_encoding(),
+                ),
+                |(binary, encoding)| {
+                    prop_assert_eq!(
+                        erlang::binary_to_atom_2(binary, encoding, &arc_process),
+                        Err(badarg!().into())
+                    );
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_binary_with_latin1_encoding_returns_atom_with_byte_characters() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(&any::<Vec<u8>>(), |byte_vec| {
+                let binary = arc_process.binary_from_bytes(&byte_vec).unwrap();
+                let name: String = byte_vec.iter().map(|&byte| byte as char).collect();
+
+                prop_assert_eq!(
+                    erlang::binary_to_atom_2(binary, atom_unchecked("latin1"), &arc_process),
+                    Ok(atom_unchecked(&name))
+                );
+
+                Ok(())
+            })
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_name_longer_than_255_characters_errors_system_limit() {
+    with_process(|process| {
+        // 2 bytes per character, so that it is the characters that are counted, not the bytes
+        let name: String = std::iter::repeat('\u{e9}').take(256).collect();
+        let binary = process.binary_from_str(&name).unwrap();
+
+        assert_eq!(
+            erlang::binary_to_atom_2(binary, atom_unchecked("utf8"), process),
+            Err(error!(atom_unchecked("system_limit")).into())
+        );
+
+        let name: String = std::iter::repeat('\u{e9}').take(255).collect();
+        let binary = process.binary_from_str(&name).unwrap();
+
+        assert_eq!(
+            erlang::binary_to_atom_2(binary, atom_unchecked("utf8"), process),
+            Ok(atom_unchecked(&name))
+        );
+    });
+}
@@ -11,7 +11,7 @@ fn without_binary_errors_badarg() {
                 ),
                 |(binary, encoding)| {
                     prop_assert_eq!(
-                        erlang::binary_to_existing_atom_2(binary, encoding),
+                        erlang::binary_to_existing_atom_2(binary, encoding, &arc_process),
                         Err(badarg!().into())
                     );
 
@@ -33,7 +33,7 @@ fn with_binary_without_encoding_errors_badarg() {
                 ),
                 |(binary, encoding)| {
                     prop_assert_eq!(
-                        erlang::binary_to_existing_atom_2(binary, encoding),
+                        erlang::binary_to_existing_atom_2(binary, encoding, &arc_process),
                         Err(badarg!().into())
                     );
 
@@ -60,7 +60,7 @@ fn with_utf8_binary_with_valid_encoding_without_existing_atom_errors_badarg() {
                 ),
                 |(binary, encoding)| {
                     prop_assert_eq!(
-                        erlang::binary_to_existing_atom_2(binary, encoding),
+                        erlang::binary_to_existing_atom_2(binary, encoding, &arc_process),
                         Err(badarg!().into())
                     );
 
@@ -72,14 +72,36 @@ fn with_utf8_binary_with_valid_encoding_without_existing_atom_errors_badarg() {
 }
 
 #[test]
-fn with_utf8_binary_with_valid_encoding_with_existing_atom_returns_atom() {
+fn without_utf8_binary_with_valid_encoding_errors_badarg() {
     with_process_arc(|arc_process| {
         TestRunner::new(Config::with_source_file(file!()))
             .run(
                 &(
-                    strategy::term::binary::is_utf8(arc_process.clone()),
+                    strategy::term::binary::containing_bytes(vec![0xFF, 0xFE], arc_process.clone()),
                     strategy::term::is_encoding(),
                 ),
+                |(binary, encoding)| {
+                    prop_assert_eq!(
+                        erlang::binary_to_existing_atom_2(binary, encoding, &arc_process),
+                        Err(badarg!().into())
+                    );
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_utf8_binary_with_utf8_encoding_with_existing_atom_returns_atom() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &(
+                    strategy::term::binary::is_utf8(arc_process.clone()),
+                    strategy::term::is_utf8_encoding(),
+                ),
                 |(binary, encoding)| {
                     let byte_vec: Vec<u8> = match binary.to_typed_term().unwrap() {
                         TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
@@ -99,7 +121,7 @@ fn with_utf8_binary_with_valid_encoding_with_existing_atom_returns_atom() {
                     let existing_atom = atom_unchecked(s);
 
                     prop_assert_eq!(
-                        erlang::binary_to_existing_atom_2(binary, encoding),
+                        erlang::binary_to_existing_atom_2(binary, encoding, &arc_process),
                         Ok(existing_atom)
                     );
 
@@ -109,3 +131,18 @@ fn with_utf8_binary_with_valid_encoding_with_existing_atom_returns_atom() {
             .unwrap();
     });
 }
+
+#[test]
+fn with_latin1_binary_with_latin1_encoding_with_existing_atom_returns_atom() {
+    with_process(|process| {
+        let existing_atom = atom_unchecked("binary_to_existing_atom_latin1_\u{e9}");
+        let mut byte_vec = b"binary_to_existing_atom_latin1_".to_vec();
+        byte_vec.push(0xE9);
+        let binary = process.binary_from_bytes(&byte_vec).unwrap();
+
+        assert_eq!(
+            erlang::binary_to_existing_atom_2(binary, atom_unchecked("latin1"), process),
+            Ok(existing_atom)
+        );
+    });
+}
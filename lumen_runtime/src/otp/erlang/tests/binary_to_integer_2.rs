@@ -3,6 +3,8 @@ use super::*;
 use proptest::strategy::Strategy;
 use radix_fmt::radix;
 
+use crate::test::beam::Differential;
+
 #[test]
 fn without_binary_errors_badarg() {
     with_process_arc(|arc_process| {
@@ -17,6 +19,14 @@ fn without_binary_errors_badarg() {
                         erlang::binary_to_integer_2(binary, base, &arc_process),
                         Err(badarg!().into())
                     );
+                    prop_assert_eq!(
+                        error_info_argument(erlang::binary_to_integer_2(
+                            binary,
+                            base,
+                            &arc_process
+                        )),
+                        Some(1)
+                    );
 
                     Ok(())
                 },
@@ -39,6 +49,14 @@ fn with_binary_without_base_errors_badarg() {
                         erlang::binary_to_integer_2(binary, base, &arc_process),
                         Err(badarg!().into())
                     );
+                    prop_assert_eq!(
+                        error_info_argument(erlang::binary_to_integer_2(
+                            binary,
+                            base,
+                            &arc_process
+                        )),
+                        Some(2)
+                    );
 
                     Ok(())
                 },
@@ -113,6 +131,36 @@ fn with_binary_without_integer_in_base_errors_badarg() {
     });
 }
 
+#[test]
+fn is_same_as_beam() {
+    with_process(|process| {
+        let mut differential = Differential::new("erlang", "binary_to_integer");
+
+        for (string, base) in &[
+            ("0", 2),
+            ("-101", 2),
+            ("+777", 8),
+            ("zz", 36),
+            ("ZZ", 36),
+            ("123456789012345678901234567890", 10),
+            ("12", 2),
+            ("", 10),
+            ("1", 1),
+            ("1", 37),
+        ] {
+            let binary = process.binary_from_str(string).unwrap();
+            let base = process.integer(*base).unwrap();
+
+            differential.call(
+                &[binary, base],
+                erlang::binary_to_integer_2(binary, base, process),
+            );
+        }
+
+        differential.assert();
+    });
+}
+
 fn base() -> BoxedStrategy<u8> {
     (2_u8..=36_u8).boxed()
 }
@@ -96,6 +96,49 @@ fn with_integer_integer_with_integer_shift_is_the_same_as_bsr_with_negated_shift
     });
 }
 
+#[test]
+fn with_non_zero_integer_with_shift_past_max_shifted_bits_errors_system_limit() {
+    with_process(|process| {
+        let integer = process.integer(1).unwrap();
+
+        for shift in &[
+            process.integer(erlang::MAX_SHIFTED_BITS).unwrap(),
+            process.integer(isize::max_value()).unwrap(),
+        ] {
+            assert_eq!(
+                erlang::bsl_2(integer, *shift, process),
+                Err(error!(atom_unchecked("system_limit")).into())
+            );
+        }
+    });
+}
+
+#[test]
+fn with_zero_integer_with_shift_past_max_shifted_bits_returns_zero() {
+    with_process(|process| {
+        let zero = process.integer(0).unwrap();
+        let shift = process.integer(isize::max_value()).unwrap();
+
+        assert_eq!(erlang::bsl_2(zero, shift, process), Ok(zero));
+    });
+}
+
+#[test]
+fn with_isize_min_shift_shifts_all_bits_out() {
+    with_process(|process| {
+        let shift = process.integer(isize::min_value()).unwrap();
+
+        assert_eq!(
+            erlang::bsl_2(process.integer(1).unwrap(), shift, process),
+            Ok(process.integer(0).unwrap())
+        );
+        assert_eq!(
+            erlang::bsl_2(process.integer(-1).unwrap(), shift, process),
+            Ok(process.integer(-1).unwrap())
+        );
+    });
+}
+
 fn shift() -> BoxedStrategy<i8> {
     // any::<i8> is not symmetric because i8::MIN is -128 while i8::MAX is 127, so make symmetric
     // range
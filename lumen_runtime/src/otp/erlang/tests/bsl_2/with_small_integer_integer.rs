@@ -67,3 +67,26 @@ fn with_positive_with_overflow_returns_big_integer() {
         );
     });
 }
+
+#[test]
+fn with_positive_with_overflow_within_word_returns_big_integer() {
+    with_process(|process| {
+        let integer = process.integer(0b11).unwrap();
+        let shift = process.integer(62).unwrap();
+
+        let result = erlang::bsl_2(integer, shift, &process);
+
+        assert!(result.is_ok());
+
+        let shifted = result.unwrap();
+
+        assert!(shifted.is_bigint());
+
+        assert_eq!(
+            shifted,
+            process
+                .integer(<BigInt as Num>::from_str_radix("13835058055282163712", 10).unwrap())
+                .unwrap()
+        );
+    });
+}
@@ -96,6 +96,22 @@ fn with_integer_integer_with_integer_shift_is_the_same_as_bsl_with_negated_shift
     });
 }
 
+#[test]
+fn with_isize_min_shift_errors_system_limit() {
+    with_process(|process| {
+        let shift = process.integer(isize::min_value()).unwrap();
+
+        assert_eq!(
+            erlang::bsr_2(process.integer(1).unwrap(), shift, process),
+            Err(error!(atom_unchecked("system_limit")).into())
+        );
+        assert_eq!(
+            erlang::bsr_2(process.integer(0).unwrap(), shift, process),
+            Ok(process.integer(0).unwrap())
+        );
+    });
+}
+
 fn shift() -> BoxedStrategy<i8> {
     // any::<i8> is not symmetric because i8::MIN is -128 while i8::MAX is 127, so make symmetric
     // range
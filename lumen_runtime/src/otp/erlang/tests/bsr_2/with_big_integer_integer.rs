@@ -51,6 +51,25 @@ fn with_positive_with_underflow_returns_zero() {
     });
 }
 
+#[test]
+fn with_negative_big_integer_with_positive_shift_rounds_down() {
+    with_process(|process| {
+        // -(2^64 + 1)
+        let integer = process
+            .integer(<BigInt as Num>::from_str_radix("-18446744073709551617", 10).unwrap())
+            .unwrap();
+        let shift = process.integer(1).unwrap();
+
+        // -(2^63) - 1
+        assert_eq!(
+            erlang::bsr_2(integer, shift, &process),
+            Ok(process
+                .integer(<BigInt as Num>::from_str_radix("-9223372036854775809", 10).unwrap())
+                .unwrap())
+        );
+    });
+}
+
 fn with<F>(f: F)
 where
     F: FnOnce(Term, &Process) -> (),
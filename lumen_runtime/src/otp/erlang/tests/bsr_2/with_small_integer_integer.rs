@@ -62,6 +62,32 @@ fn with_positive_with_underflow_returns_zero() {
     });
 }
 
+#[test]
+fn with_negative_integer_with_positive_shift_rounds_down() {
+    with_process(|process| {
+        let integer = process.integer(-5).unwrap();
+        let shift = process.integer(1).unwrap();
+
+        assert_eq!(
+            erlang::bsr_2(integer, shift, &process),
+            Ok(process.integer(-3).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_negative_integer_with_positive_shift_past_word_returns_negative_one() {
+    with_process(|process| {
+        let integer = process.integer(-5).unwrap();
+        let shift = process.integer(100).unwrap();
+
+        assert_eq!(
+            erlang::bsr_2(integer, shift, &process),
+            Ok(process.integer(-1).unwrap())
+        );
+    });
+}
+
 fn with<F>(f: F)
 where
     F: FnOnce(Term, &Process) -> (),
@@ -16,6 +16,10 @@ fn without_tuple_errors_badarg() {
                     erlang::delete_element_2(index, tuple, &arc_process),
                     Err(badarg!().into())
                 );
+                prop_assert_eq!(
+                    error_info_argument(erlang::delete_element_2(index, tuple, &arc_process)),
+                    Some(2)
+                );
 
                 Ok(())
             },
@@ -34,6 +38,10 @@ fn with_tuple_without_integer_between_1_and_the_length_inclusive_errors_badarg()
                         erlang::delete_element_2(index, tuple, &arc_process),
                         Err(badarg!().into())
                     );
+                    prop_assert_eq!(
+                        error_info_argument(erlang::delete_element_2(index, tuple, &arc_process)),
+                        Some(1)
+                    );
 
                     Ok(())
                 },
@@ -0,0 +1,71 @@
+use super::*;
+
+use std::sync::Mutex;
+
+use crate::system::stdio::{self, Stdio};
+
+#[derive(Default)]
+struct Capturing {
+    stderr: Mutex<Vec<u8>>,
+}
+
+impl Stdio for Capturing {
+    fn write_stdout(&self, _bytes: &[u8]) {}
+
+    fn write_stderr(&self, bytes: &[u8]) {
+        self.stderr.lock().unwrap().extend_from_slice(bytes);
+    }
+}
+
+#[test]
+fn writes_term_and_newline_to_stderr() {
+    with_process(|process| {
+        let term = process
+            .tuple_from_slice(&[
+                atom_unchecked("display_1"),
+                process.charlist_from_str("hi").unwrap(),
+                process.binary_from_bytes(&[1, 2]).unwrap(),
+            ])
+            .unwrap();
+        let capturing: Arc<Capturing> = Default::default();
+
+        stdio::set(capturing.clone());
+        let result = erlang::display_1(term);
+        stdio::reset();
+
+        assert_eq!(result, Ok(true.into()));
+
+        // other tests may write while the `Stdio` is set, so only check for this test's output
+        let stderr = String::from_utf8(capturing.stderr.lock().unwrap().clone()).unwrap();
+
+        assert!(stderr.contains("{display_1,[104,105],<<1,2>>}\n"));
+    });
+}
+
+#[test]
+fn with_string_display_string_writes_string_to_stderr() {
+    with_process(|process| {
+        let string = process.charlist_from_str("display_string_1").unwrap();
+        let capturing: Arc<Capturing> = Default::default();
+
+        stdio::set(capturing.clone());
+        let result = erlang::display_string_1(string);
+        stdio::reset();
+
+        assert_eq!(result, Ok(true.into()));
+
+        let stderr = String::from_utf8(capturing.stderr.lock().unwrap().clone()).unwrap();
+
+        assert!(stderr.contains("display_string_1"));
+    });
+}
+
+#[test]
+fn without_string_display_string_errors_badarg() {
+    with_process(|process| {
+        assert_eq!(
+            erlang::display_string_1(process.binary_from_bytes(b"string").unwrap()),
+            Err(badarg!().into())
+        );
+    });
+}
@@ -0,0 +1,75 @@
+use super::*;
+
+#[test]
+fn without_number_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &strategy::term::is_not_number(arc_process.clone()),
+                |number| {
+                    prop_assert_eq!(erlang::floor_1(number, &arc_process), Err(badarg!().into()));
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_integer_returns_integer() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(&strategy::term::is_integer(arc_process.clone()), |number| {
+                prop_assert_eq!(erlang::floor_1(number, &arc_process), Ok(number));
+
+                Ok(())
+            })
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_negative_float_rounds_down_to_the_previous_integer() {
+    with_process(|process| {
+        let number = process.float(-1.5).unwrap();
+
+        assert_eq!(
+            erlang::floor_1(number, &process),
+            Ok(process.integer(-2).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_positive_float_rounds_down_to_the_previous_integer() {
+    with_process(|process| {
+        let number = process.float(1.5).unwrap();
+
+        assert_eq!(
+            erlang::floor_1(number, &process),
+            Ok(process.integer(1).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_float_past_small_integer_returns_big_integer() {
+    with_process(|process| {
+        let number = process.float(1.0e20).unwrap();
+
+        let result = erlang::floor_1(number, &process);
+
+        assert!(result.is_ok());
+
+        let result_term = result.unwrap();
+
+        assert!(result_term.is_bigint());
+        assert_eq!(
+            result_term,
+            process
+                .integer(BigInt::parse_bytes(b"100000000000000000000", 10).unwrap())
+                .unwrap()
+        );
+    });
+}
@@ -0,0 +1,32 @@
+use super::*;
+
+#[test]
+fn without_parent_returns_self() {
+    let init_arc_process = process::test_init();
+
+    assert_eq!(
+        erlang::group_leader_0(&init_arc_process),
+        Ok(init_arc_process.pid_term())
+    );
+}
+
+#[test]
+fn with_parent_returns_group_leader_of_parent() {
+    let init_arc_process = process::test_init();
+    let parent_arc_process = process::test(&init_arc_process);
+
+    assert_eq!(
+        erlang::group_leader_0(&parent_arc_process),
+        Ok(init_arc_process.pid_term())
+    );
+
+    let group_leader_arc_process = process::test(&init_arc_process);
+    parent_arc_process.set_group_leader_pid(group_leader_arc_process.pid());
+
+    let child_arc_process = process::test(&parent_arc_process);
+
+    assert_eq!(
+        erlang::group_leader_0(&child_arc_process),
+        Ok(group_leader_arc_process.pid_term())
+    );
+}
@@ -0,0 +1,55 @@
+use super::*;
+
+#[test]
+fn without_group_leader_pid_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &strategy::term::is_not_local_pid(arc_process.clone()),
+                |group_leader| {
+                    prop_assert_eq!(
+                        erlang::group_leader_2(
+                            group_leader,
+                            arc_process.pid_term(),
+                            arc_process.clone()
+                        ),
+                        Err(badarg!().into())
+                    );
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn without_process_errors_badarg() {
+    with_process_arc(|arc_process| {
+        assert_eq!(
+            erlang::group_leader_2(arc_process.pid_term(), next_pid(), arc_process.clone()),
+            Err(badarg!().into())
+        );
+    });
+}
+
+#[test]
+fn with_process_sets_group_leader() {
+    with_process_arc(|arc_process| {
+        let group_leader_arc_process = process::test(&arc_process);
+        let other_arc_process = process::test(&arc_process);
+
+        assert_eq!(
+            erlang::group_leader_2(
+                group_leader_arc_process.pid_term(),
+                other_arc_process.pid_term(),
+                arc_process.clone()
+            ),
+            Ok(true.into())
+        );
+        assert_eq!(
+            other_arc_process.group_leader_pid(),
+            group_leader_arc_process.pid()
+        );
+    });
+}
@@ -19,6 +19,15 @@ fn without_tuple_errors_badarg() {
                     erlang::insert_element_3(index, tuple, element, &arc_process),
                     Err(badarg!().into())
                 );
+                prop_assert_eq!(
+                    error_info_argument(erlang::insert_element_3(
+                        index,
+                        tuple,
+                        element,
+                        &arc_process
+                    )),
+                    Some(2)
+                );
 
                 Ok(())
             },
@@ -57,6 +66,10 @@ fn with_tuple_without_integer_between_1_and_the_length_plus_1_inclusive_errors_b
                         erlang::insert_element_3(index, tuple, element, &arc_process),
                         Err(badarg!().into())
                     );
+                    prop_assert_eq!(
+                        error_info_argument(erlang::insert_element_3(index, tuple, element, &arc_process)),
+                        Some(1)
+                    );
 
                     Ok(())
                 },
@@ -1,5 +1,7 @@
 use super::*;
 
+use num_bigint::BigInt;
+
 #[test]
 fn with_lesser_small_integer_right_returns_false() {
     is_less_than(|_, process| process.integer(-1).unwrap(), false)
@@ -55,6 +57,38 @@ fn without_number_returns_true() {
     });
 }
 
+#[test]
+fn with_integer_right_one_greater_than_integral_float_past_float_precision_returns_true() {
+    // 2^53 + 1 rounds to 2^53 as a float, so the integer can't be converted for the comparison
+    super::is_less_than(
+        |process| process.float(9_007_199_254_740_992.0).unwrap(),
+        |_, process| process.integer(9_007_199_254_740_993_i64).unwrap(),
+        true,
+    );
+}
+
+#[test]
+fn with_integer_right_equal_to_integral_part_of_negative_float_returns_true() {
+    super::is_less_than(
+        |process| process.float(-1.5).unwrap(),
+        |_, process| process.integer(-1).unwrap(),
+        true,
+    );
+}
+
+#[test]
+fn with_big_integer_right_greater_than_float_past_isize_returns_true() {
+    super::is_less_than(
+        |process| process.float(1.0e300).unwrap(),
+        |_, process| {
+            let big_int: BigInt = BigInt::from(1) << 1000;
+
+            process.integer(big_int).unwrap()
+        },
+        true,
+    );
+}
+
 fn is_less_than<R>(right: R, expected: bool)
 where
     R: FnOnce(Term, &Process) -> Term,
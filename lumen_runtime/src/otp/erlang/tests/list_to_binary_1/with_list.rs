@@ -58,6 +58,26 @@ fn with_subbinary_with_bit_count_errors_badarg() {
     });
 }
 
+#[test]
+fn with_process_binary_returns_binary_with_its_bytes() {
+    with_process(|process| {
+        // longer than 64 bytes, so that it is a process binary instead of a heap binary
+        let bytes: Vec<u8> = (0..=100).collect();
+        let binary = process.binary_from_bytes(&bytes).unwrap();
+        let iolist = process
+            .list_from_slice(&[process.integer(101).unwrap(), binary])
+            .unwrap();
+
+        let mut expected_bytes = vec![101];
+        expected_bytes.extend_from_slice(&bytes);
+
+        assert_eq!(
+            erlang::list_to_binary_1(iolist, &process),
+            Ok(process.binary_from_bytes(&expected_bytes).unwrap())
+        );
+    })
+}
+
 fn is_not_byte_binary_nor_list(arc_process: Arc<Process>) -> BoxedStrategy<Term> {
     strategy::term(arc_process.clone())
         .prop_filter("Element must not be a binary or byte", move |element| {
@@ -11,3 +11,20 @@ fn returns_a_unique_reference() {
         assert_eq!(second_reference, second_reference);
     })
 }
+
+#[test]
+fn marks_mailbox_so_receive_can_skip_messages_queued_before_reference() {
+    with_process(|process| {
+        process.send_from_self(atom_unchecked("before"));
+
+        let reference = erlang::make_ref_0(&process).unwrap();
+        let reference_reference: Boxed<Reference> = reference.try_into().unwrap();
+
+        let mailbox_lock = process.mailbox.lock();
+        let mut mailbox = mailbox_lock.borrow_mut();
+        mailbox.recv_start(false);
+
+        assert!(mailbox.recv_set(&reference_reference));
+        assert_eq!(mailbox.recv_peek(), None);
+    })
+}
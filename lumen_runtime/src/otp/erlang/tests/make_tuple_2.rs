@@ -0,0 +1,71 @@
+use super::*;
+
+#[test]
+fn without_non_negative_integer_arity_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &(
+                    strategy::term::is_not_non_negative_integer(arc_process.clone()),
+                    strategy::term(arc_process.clone()),
+                ),
+                |(arity, initial_value)| {
+                    prop_assert_eq!(
+                        erlang::make_tuple_2(arity, initial_value, &arc_process),
+                        Err(badarg!().into())
+                    );
+                    prop_assert_eq!(
+                        error_info_argument(erlang::make_tuple_2(
+                            arity,
+                            initial_value,
+                            &arc_process
+                        )),
+                        Some(1)
+                    );
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_arity_above_max_tuple_arity_errors_badarg() {
+    with_process_arc(|arc_process| {
+        let arity = arc_process.integer(erlang::MAX_TUPLE_ARITY + 1).unwrap();
+        let initial_value = atom_unchecked("initial_value");
+
+        assert_eq!(
+            erlang::make_tuple_2(arity, initial_value, &arc_process),
+            Err(badarg!().into())
+        );
+        assert_eq!(
+            error_info_argument(erlang::make_tuple_2(arity, initial_value, &arc_process)),
+            Some(1)
+        );
+    });
+}
+
+#[test]
+fn with_non_negative_integer_arity_returns_tuple_of_initial_value() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &(0_usize..=4_usize, strategy::term(arc_process.clone())),
+                |(len, initial_value)| {
+                    let arity = arc_process.integer(len).unwrap();
+
+                    prop_assert_eq!(
+                        erlang::make_tuple_2(arity, initial_value, &arc_process),
+                        Ok(arc_process
+                            .tuple_from_slice(&vec![initial_value; len])
+                            .unwrap())
+                    );
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
@@ -0,0 +1,125 @@
+use super::*;
+
+#[test]
+fn without_non_negative_integer_arity_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &(
+                    strategy::term::is_not_non_negative_integer(arc_process.clone()),
+                    strategy::term(arc_process.clone()),
+                ),
+                |(arity, default_value)| {
+                    prop_assert_eq!(
+                        error_info_argument(erlang::make_tuple_3(
+                            arity,
+                            default_value,
+                            Term::NIL,
+                            &arc_process
+                        )),
+                        Some(1)
+                    );
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_arity_above_max_tuple_arity_errors_badarg() {
+    with_process_arc(|arc_process| {
+        let arity = arc_process.integer(erlang::MAX_TUPLE_ARITY + 1).unwrap();
+        let default_value = atom_unchecked("default_value");
+
+        assert_eq!(
+            erlang::make_tuple_3(arity, default_value, Term::NIL, &arc_process),
+            Err(badarg!().into())
+        );
+        assert_eq!(
+            error_info_argument(erlang::make_tuple_3(
+                arity,
+                default_value,
+                Term::NIL,
+                &arc_process
+            )),
+            Some(1)
+        );
+    });
+}
+
+#[test]
+fn without_proper_list_init_list_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &strategy::term::is_not_proper_list(arc_process.clone()),
+                |init_list| {
+                    let arity = arc_process.integer(2).unwrap();
+
+                    prop_assert_eq!(
+                        error_info_argument(erlang::make_tuple_3(
+                            arity,
+                            atom_unchecked("default"),
+                            init_list,
+                            &arc_process
+                        )),
+                        Some(3)
+                    );
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_position_out_of_range_errors_badarg() {
+    with_process(|process| {
+        let arity = process.integer(2).unwrap();
+
+        for position in &[0_usize, 3] {
+            let entry = process
+                .tuple_from_slice(&[process.integer(*position).unwrap(), atom_unchecked("value")])
+                .unwrap();
+            let init_list = process.list_from_slice(&[entry]).unwrap();
+
+            assert_eq!(
+                error_info_argument(erlang::make_tuple_3(
+                    arity,
+                    atom_unchecked("default"),
+                    init_list,
+                    &process
+                )),
+                Some(3)
+            );
+        }
+    });
+}
+
+#[test]
+fn with_init_list_sets_positions_with_later_entries_winning() {
+    with_process(|process| {
+        let arity = process.integer(3).unwrap();
+        let default = atom_unchecked("default");
+        let first = process
+            .tuple_from_slice(&[process.integer(1).unwrap(), atom_unchecked("first")])
+            .unwrap();
+        let replaced = process
+            .tuple_from_slice(&[process.integer(3).unwrap(), atom_unchecked("replaced")])
+            .unwrap();
+        let last = process
+            .tuple_from_slice(&[process.integer(3).unwrap(), atom_unchecked("last")])
+            .unwrap();
+        let init_list = process.list_from_slice(&[first, replaced, last]).unwrap();
+
+        assert_eq!(
+            erlang::make_tuple_3(arity, default, init_list, &process),
+            Ok(process
+                .tuple_from_slice(&[atom_unchecked("first"), default, atom_unchecked("last")])
+                .unwrap())
+        );
+    });
+}
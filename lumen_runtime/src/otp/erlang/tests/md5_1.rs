@@ -0,0 +1,60 @@
+use super::*;
+
+#[test]
+fn without_iodata_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &strategy::term::is_not_list(arc_process.clone())
+                    .prop_filter("Data cannot be a binary", |data| !data.is_binary()),
+                |data| {
+                    prop_assert_eq!(erlang::md5_1(data, &arc_process), Err(badarg!().into()));
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_empty_binary_returns_digest_of_nothing() {
+    with_process(|process| {
+        let data = process.binary_from_bytes(&[]).unwrap();
+
+        assert_eq!(
+            erlang::md5_1(data, &process),
+            Ok(process
+                .binary_from_bytes(&[
+                    0xd4, 0x1d, 0x8c, 0xd9, 0x8f, 0x00, 0xb2, 0x04, 0xe9, 0x80, 0x09, 0x98, 0xec,
+                    0xf8, 0x42, 0x7e
+                ])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_iolist_returns_digest_of_bytes() {
+    with_process(|process| {
+        let binary = process.binary_from_bytes(b"The quick brown fox").unwrap();
+        let iolist = process
+            .list_from_slice(&[
+                binary,
+                process
+                    .charlist_from_str(" jumps over the lazy dog")
+                    .unwrap(),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            erlang::md5_1(iolist, &process),
+            Ok(process
+                .binary_from_bytes(&[
+                    0x9e, 0x10, 0x7d, 0x9d, 0x37, 0x2b, 0xb6, 0x82, 0x6b, 0xd8, 0x1d, 0x35, 0x42,
+                    0xa4, 0x19, 0xd6
+                ])
+                .unwrap())
+        );
+    });
+}
@@ -0,0 +1,48 @@
+use super::*;
+
+#[test]
+fn without_context_errors_badarg() {
+    with_process(|process| {
+        let context = process.binary_from_bytes(&[0; 8]).unwrap();
+        let data = process.binary_from_bytes(b"data").unwrap();
+
+        assert_eq!(
+            erlang::md5_update_2(context, data, &process),
+            Err(badarg!().into())
+        );
+        assert_eq!(
+            erlang::md5_final_1(context, &process),
+            Err(badarg!().into())
+        );
+    });
+}
+
+#[test]
+fn with_updates_final_returns_digest_of_all_data() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &(strategy::byte_vec(), strategy::byte_vec()),
+                |(first_bytes, second_bytes)| {
+                    let mut context = erlang::md5_init_0(&arc_process).unwrap();
+
+                    for bytes in &[&first_bytes, &second_bytes] {
+                        let data = arc_process.binary_from_bytes(bytes).unwrap();
+                        context = erlang::md5_update_2(context, data, &arc_process).unwrap();
+                    }
+
+                    let mut all_bytes = first_bytes.clone();
+                    all_bytes.extend_from_slice(&second_bytes);
+                    let all_data = arc_process.binary_from_bytes(&all_bytes).unwrap();
+
+                    prop_assert_eq!(
+                        erlang::md5_final_1(context, &arc_process),
+                        erlang::md5_1(all_data, &arc_process)
+                    );
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
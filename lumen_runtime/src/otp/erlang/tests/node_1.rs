@@ -0,0 +1,43 @@
+use super::*;
+
+#[test]
+fn without_pid_or_reference_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &strategy::term(arc_process.clone())
+                    .prop_filter("Cannot be a pid or reference", |term| {
+                        !(term.is_pid() || term.is_reference())
+                    }),
+                |term| {
+                    prop_assert_eq!(erlang::node_1(term), Err(badarg!().into()));
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_local_pid_or_reference_returns_node() {
+    with_process(|process| {
+        assert_eq!(erlang::node_1(process.pid_term()), Ok(erlang::node_0()));
+        assert_eq!(
+            erlang::node_1(process.next_reference().unwrap()),
+            Ok(erlang::node_0())
+        );
+    });
+}
+
+#[test]
+fn with_external_pid_returns_its_node() {
+    with_process(|process| {
+        let external_pid = process.external_pid_with_node_id(1, 2, 3).unwrap();
+
+        assert_eq!(
+            erlang::node_1(external_pid),
+            Ok(atom_unchecked("node1@nohost"))
+        );
+    });
+}
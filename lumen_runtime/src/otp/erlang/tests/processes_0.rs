@@ -0,0 +1,21 @@
+use super::*;
+
+// because processes are global and tests are concurrent, there is no way to test for an exact list
+
+#[test]
+fn includes_live_processes_and_excludes_exiting_processes() {
+    with_process_arc(|arc_process| {
+        let exiting_arc_process = process::test(&arc_process);
+        exiting_arc_process.exit();
+
+        let processes = erlang::processes_0(&arc_process).unwrap();
+
+        match processes.to_typed_term().unwrap() {
+            TypedTerm::List(cons) => {
+                assert!(cons.contains(arc_process.pid_term()));
+                assert!(!cons.contains(exiting_arc_process.pid_term()));
+            }
+            typed_term => panic!("Wrong TypedTerm ({:?})", typed_term),
+        }
+    });
+}
@@ -3,7 +3,7 @@ use super::*;
 use proptest::strategy::Strategy;
 
 #[test]
-fn without_locked_adds_process_message_to_mailbox_and_returns_message() {
+fn without_locked_adds_heap_message_to_mailbox_and_returns_message() {
     TestRunner::new(Config::with_source_file(file!()))
         .run(
             &strategy::process().prop_flat_map(|arc_process| {
@@ -27,7 +27,7 @@ fn without_locked_adds_process_message_to_mailbox_and_returns_message() {
                     Ok(message)
                 );
 
-                prop_assert!(has_process_message(&different_arc_process, message));
+                prop_assert!(has_heap_message(&different_arc_process, message));
 
                 Ok(())
             },
@@ -21,7 +21,7 @@ fn with_locked_adds_heap_message_to_mailbox_and_returns_message() {
 }
 
 #[test]
-fn without_locked_adds_process_message_to_mailbox_and_returns_message() {
+fn without_locked_adds_heap_message_to_mailbox_and_returns_message() {
     with_process_arc(|arc_process| {
         TestRunner::new(Config::with_source_file(file!()))
             .run(&strategy::term(arc_process.clone()), |message| {
@@ -33,7 +33,7 @@ fn without_locked_adds_process_message_to_mailbox_and_returns_message() {
                     Ok(message)
                 );
 
-                prop_assert!(has_process_message(&different_arc_process, message));
+                prop_assert!(has_heap_message(&different_arc_process, message));
 
                 Ok(())
             })
@@ -38,7 +38,7 @@ fn with_locked_adds_heap_message_to_mailbox_and_returns_message() {
 }
 
 #[test]
-fn without_locked_adds_process_message_to_mailbox_and_returns_message() {
+fn without_locked_adds_heap_message_to_mailbox_and_returns_message() {
     TestRunner::new(Config::with_source_file(file!()))
         .run(
             &strategy::process().prop_flat_map(|arc_process| {
@@ -62,7 +62,7 @@ fn without_locked_adds_process_message_to_mailbox_and_returns_message() {
                     Ok(message)
                 );
 
-                prop_assert!(has_process_message(&different_process, message));
+                prop_assert!(has_heap_message(&different_process, message));
 
                 Ok(())
             },
@@ -27,7 +27,7 @@ fn without_locked_adds_heap_message_to_mailbox_and_returns_ok() {
                         Ok(atom_unchecked("ok"))
                     );
 
-                    prop_assert!(has_process_message(&different_arc_process, message));
+                    prop_assert!(has_heap_message(&different_arc_process, message));
 
                     Ok(())
                 },
@@ -1,7 +1,7 @@
 use super::*;
 
 #[test]
-fn without_locked_adds_process_message_to_mailbox_and_returns_ok() {
+fn without_locked_adds_heap_message_to_mailbox_and_returns_ok() {
     with_process_arc(|arc_process| {
         TestRunner::new(Config::with_source_file(file!()))
             .run(
@@ -31,7 +31,7 @@ fn without_locked_adds_process_message_to_mailbox_and_returns_ok() {
                         Ok(atom_unchecked("ok"))
                     );
 
-                    prop_assert!(has_process_message(&different_arc_process, message));
+                    prop_assert!(has_heap_message(&different_arc_process, message));
 
                     Ok(())
                 },
@@ -0,0 +1,134 @@
+use super::*;
+
+#[test]
+fn without_options_encodes_latin1_atoms_and_new_floats() {
+    with_process(|process| {
+        assert_eq!(
+            erlang::term_to_binary_1(atom_unchecked("abc"), &process),
+            Ok(process
+                .binary_from_bytes(&[131, 100, 0, 3, b'a', b'b', b'c'])
+                .unwrap())
+        );
+        assert_eq!(
+            erlang::term_to_binary_1(process.float(1.5).unwrap(), &process),
+            Ok(process
+                .binary_from_bytes(&[131, 70, 63, 248, 0, 0, 0, 0, 0, 0])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_list_of_bytes_encodes_string() {
+    with_process(|process| {
+        let list = process
+            .list_from_slice(&[process.integer(1).unwrap(), process.integer(2).unwrap()])
+            .unwrap();
+
+        assert_eq!(
+            erlang::term_to_binary_1(list, &process),
+            Ok(process.binary_from_bytes(&[131, 107, 0, 2, 1, 2]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_minor_version_0_encodes_float_as_text() {
+    with_process(|process| {
+        let options = options(&process, "minor_version", 0);
+        let mut expected = vec![131, 99];
+        expected.extend_from_slice(b"1.50000000000000000000e+00");
+        expected.extend_from_slice(&[0; 5]);
+
+        assert_eq!(
+            erlang::term_to_binary_2(process.float(1.5).unwrap(), options, &process),
+            Ok(process.binary_from_bytes(&expected).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_minor_version_2_encodes_utf8_atoms() {
+    with_process(|process| {
+        let options = options(&process, "minor_version", 2);
+
+        assert_eq!(
+            erlang::term_to_binary_2(atom_unchecked("abc"), options, &process),
+            Ok(process
+                .binary_from_bytes(&[131, 119, 3, b'a', b'b', b'c'])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_compressed_encodes_uncompressed_length_and_zlib() {
+    with_process(|process| {
+        let atoms: Vec<Term> = (0..100).map(|_| atom_unchecked("a")).collect();
+        let list = process.list_from_slice(&atoms).unwrap();
+        let options = process
+            .list_from_slice(&[atom_unchecked("compressed")])
+            .unwrap();
+
+        let compressed = erlang::term_to_binary_2(list, options, &process).unwrap();
+        let compressed_bytes: Vec<u8> = compressed.try_into().unwrap();
+        let uncompressed_len: u32 = 1 + 4 + 100 * 4 + 1;
+
+        assert_eq!(&compressed_bytes[0..2], &[131, 80]);
+        assert_eq!(&compressed_bytes[2..6], &uncompressed_len.to_be_bytes());
+        // zlib header
+        assert_eq!(compressed_bytes[6], 0x78);
+    });
+}
+
+#[test]
+fn with_compressed_when_compressing_is_not_smaller_does_not_compress() {
+    with_process(|process| {
+        let term = atom_unchecked("abc");
+
+        assert_eq!(
+            erlang::term_to_binary_2(term, options(&process, "compressed", 9), &process),
+            erlang::term_to_binary_1(term, &process)
+        );
+    });
+}
+
+#[test]
+fn with_compressed_level_0_does_not_compress() {
+    with_process(|process| {
+        let atoms: Vec<Term> = (0..100).map(|_| atom_unchecked("a")).collect();
+        let list = process.list_from_slice(&atoms).unwrap();
+
+        assert_eq!(
+            erlang::term_to_binary_2(list, options(&process, "compressed", 0), &process),
+            erlang::term_to_binary_1(list, &process)
+        );
+    });
+}
+
+#[test]
+fn with_invalid_option_errors_badarg() {
+    with_process(|process| {
+        let term = atom_unchecked("abc");
+
+        for options in &[
+            options(&process, "compressed", 10),
+            options(&process, "minor_version", 3),
+            process.list_from_slice(&[atom_unchecked("safe")]).unwrap(),
+            atom_unchecked("compressed"),
+        ] {
+            assert_eq!(
+                erlang::term_to_binary_2(term, *options, &process),
+                Err(badarg!().into())
+            );
+        }
+    });
+}
+
+fn options(process: &Process, name: &str, value: usize) -> Term {
+    let option = process
+        .tuple_from_slice(&[atom_unchecked(name), process.integer(value).unwrap()])
+        .unwrap();
+
+    process.list_from_slice(&[option]).unwrap()
+}
@@ -0,0 +1,145 @@
+mod options;
+
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::code::{self, result_from_exception};
+use liblumen_alloc::erts::process::trace::{self, Trace, Tracer};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{Atom, Term, TypedTerm};
+use liblumen_alloc::{badarg, ModuleFunctionArity};
+
+use crate::otp::erlang::trace_3::options::Options;
+use crate::process;
+use crate::registry::{self, pid_to_process};
+
+pub fn place_frame_with_arguments(
+    process: &Process,
+    placement: Placement,
+    pid_spec: Term,
+    how: Term,
+    flag_list: Term,
+) -> Result<(), Alloc> {
+    process.stack_push(flag_list)?;
+    process.stack_push(how)?;
+    process.stack_push(pid_spec)?;
+    process.place_frame(frame(), placement);
+
+    Ok(())
+}
+
+// Private
+
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.reduce();
+
+    let pid_spec = arc_process.stack_pop().unwrap();
+    let how = arc_process.stack_pop().unwrap();
+    let flag_list = arc_process.stack_pop().unwrap();
+
+    match native(arc_process, pid_spec, how, flag_list) {
+        Ok(count) => {
+            arc_process.return_from_call(count)?;
+
+            Process::call_code(arc_process)
+        }
+        Err(exception) => result_from_exception(arc_process, exception),
+    }
+}
+
+fn frame() -> Frame {
+    Frame::new(module_function_arity(), code)
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("trace").unwrap()
+}
+
+fn module_function_arity() -> Arc<ModuleFunctionArity> {
+    Arc::new(ModuleFunctionArity {
+        module: super::module(),
+        function: function(),
+        arity: 3,
+    })
+}
+
+/// Sets (`how` is `true`) or clears (`how` is `false`) the trace flags in `flag_list` for the
+/// process with `pid_spec` pid, the `existing` processes, the `new` processes spawned from now on,
+/// or `all` of them.  Returns the number of processes whose flags changed, not counting `new` ones.
+///
+/// Trace messages go to the `{tracer, Tracer}` in `flag_list`, or to `process` without one.
+/// `Tracer` is either a pid or the name of a Rust backend registered with
+/// `trace::register_backend`, such as `log`.
+pub fn native(process: &Process, pid_spec: Term, how: Term, flag_list: Term) -> exception::Result {
+    // before the flags are read, so that the built-in backends can be named
+    process::trace::init();
+
+    let how: bool = how.try_into()?;
+    let Options { flags, tracer } = flag_list.try_into()?;
+    let tracer = tracer.unwrap_or_else(|| Tracer::Process(process.pid()));
+
+    if let Tracer::Process(tracer_pid) = tracer {
+        if how && tracer_pid != process.pid() && pid_to_process(&tracer_pid).is_none() {
+            return Err(badarg!().into());
+        }
+    }
+
+    let apply = |tracee: &Process| {
+        tracee.set_trace(Trace::apply(tracee.trace(), how, tracer.clone(), flags));
+    };
+
+    let count = match pid_spec.to_typed_term().unwrap() {
+        TypedTerm::Pid(pid) => match pid_to_process(&pid) {
+            Some(tracee_arc_process) => {
+                apply(&tracee_arc_process);
+
+                1
+            }
+            None => return Err(badarg!().into()),
+        },
+        TypedTerm::Atom(atom) => {
+            let (existing, new) = match atom.name() {
+                "existing" => (true, false),
+                "new" => (false, true),
+                "all" => (true, true),
+                _ => return Err(badarg!().into()),
+            };
+
+            if new {
+                let new_process_trace = trace::new_process_trace();
+                trace::set_new_process_trace(Trace::apply(
+                    new_process_trace,
+                    how,
+                    tracer.clone(),
+                    flags,
+                ));
+            }
+
+            if existing {
+                let mut count = 0;
+
+                for tracee_arc_process in registry::processes() {
+                    apply(&tracee_arc_process);
+                    count += 1;
+                }
+
+                count
+            } else {
+                0
+            }
+        }
+        _ => return Err(badarg!().into()),
+    };
+
+    Ok(process.integer(count)?)
+}
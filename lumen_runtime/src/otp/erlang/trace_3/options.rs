@@ -0,0 +1,111 @@
+use std::convert::{TryFrom, TryInto};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception::runtime;
+use liblumen_alloc::erts::process::trace::{self, Flags, Tracer};
+use liblumen_alloc::erts::term::{Atom, Boxed, Cons, Term, Tuple, TypedTerm};
+
+pub struct Options {
+    pub flags: Flags,
+    /// Set by `{tracer, Tracer}`
+    pub tracer: Option<Tracer>,
+}
+
+impl Options {
+    fn put_option(&mut self, option: Term) -> Result<(), runtime::Exception> {
+        match option.to_typed_term().unwrap() {
+            TypedTerm::Atom(atom) => {
+                match atom.name() {
+                    "all" => {
+                        self.flags.call = true;
+                        self.flags.send = true;
+                        self.flags.receive = true;
+                        self.flags.procs = true;
+                        self.flags.set_on_spawn = true;
+                    }
+                    "call" => self.flags.call = true,
+                    "procs" => self.flags.procs = true,
+                    "receive" => self.flags.receive = true,
+                    "send" => self.flags.send = true,
+                    "set_on_spawn" => self.flags.set_on_spawn = true,
+                    _ => return Err(badarg!()),
+                }
+
+                Ok(())
+            }
+            TypedTerm::Boxed(_) => {
+                let option_tuple: Boxed<Tuple> = option.try_into()?;
+
+                if option_tuple.len() != 2 {
+                    return Err(badarg!());
+                }
+
+                let name: Atom = option_tuple[0].try_into()?;
+
+                match name.name() {
+                    "tracer" => {
+                        let tracer = match option_tuple[1].to_typed_term().unwrap() {
+                            TypedTerm::Pid(pid) => Tracer::Process(pid),
+                            TypedTerm::Atom(name) => match trace::backend(name) {
+                                Some(backend) => Tracer::Backend(backend),
+                                None => return Err(badarg!()),
+                            },
+                            _ => return Err(badarg!()),
+                        };
+                        self.tracer = Some(tracer);
+
+                        Ok(())
+                    }
+                    _ => Err(badarg!()),
+                }
+            }
+            _ => Err(badarg!()),
+        }
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            flags: Default::default(),
+            tracer: None,
+        }
+    }
+}
+
+impl TryFrom<Boxed<Cons>> for Options {
+    type Error = runtime::Exception;
+
+    fn try_from(cons: Boxed<Cons>) -> Result<Self, Self::Error> {
+        let mut options: Options = Default::default();
+
+        for result in cons.into_iter() {
+            match result {
+                Ok(option) => options.put_option(option)?,
+                Err(_) => return Err(badarg!()),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+impl TryFrom<Term> for Options {
+    type Error = runtime::Exception;
+
+    fn try_from(term: Term) -> Result<Self, Self::Error> {
+        term.to_typed_term().unwrap().try_into()
+    }
+}
+
+impl TryFrom<TypedTerm> for Options {
+    type Error = runtime::Exception;
+
+    fn try_from(typed_term: TypedTerm) -> Result<Self, Self::Error> {
+        match typed_term {
+            TypedTerm::Nil => Ok(Default::default()),
+            TypedTerm::List(cons) => cons.try_into(),
+            _ => Err(badarg!()),
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use std::sync::{Arc, Mutex};
+
+use proptest::prop_assert_eq;
+use proptest::test_runner::{Config, TestRunner};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::process::trace::{self, Backend, Event, Tracer};
+use liblumen_alloc::erts::term::{atom_unchecked, next_pid, Atom, Term};
+
+use crate::otp::erlang;
+use crate::otp::erlang::trace_3::native;
+use crate::process;
+use crate::scheduler::{with_process, with_process_arc};
+use crate::test::{has_message, strategy};
+
+#[test]
+fn without_boolean_how_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &strategy::term::is_not_boolean(arc_process.clone()),
+                |how| {
+                    prop_assert_eq!(
+                        native(&arc_process, arc_process.pid_term(), how, Term::NIL),
+                        Err(badarg!().into())
+                    );
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_non_existent_pid_errors_badarg() {
+    with_process(|process| {
+        let flag_list = process.list_from_slice(&[atom_unchecked("send")]).unwrap();
+
+        assert_eq!(
+            native(process, next_pid(), true.into(), flag_list),
+            Err(badarg!().into())
+        );
+    });
+}
+
+#[test]
+fn with_unknown_flag_errors_badarg() {
+    with_process(|process| {
+        let tracee = process::test(process);
+        let flag_list = process
+            .list_from_slice(&[atom_unchecked("unknown")])
+            .unwrap();
+
+        assert_eq!(
+            native(process, tracee.pid_term(), true.into(), flag_list),
+            Err(badarg!().into())
+        );
+    });
+}
+
+#[test]
+fn with_send_flag_sends_trace_message_to_tracer() {
+    with_process(|process| {
+        let tracee = process::test(process);
+        let flag_list = process.list_from_slice(&[atom_unchecked("send")]).unwrap();
+
+        assert_eq!(
+            native(process, tracee.pid_term(), true.into(), flag_list),
+            Ok(process.integer(1).unwrap())
+        );
+
+        let message = atom_unchecked("message");
+        assert!(erlang::send_2(tracee.pid_term(), message, &tracee).is_ok());
+
+        assert!(has_message(
+            process,
+            process
+                .tuple_from_slice(&[
+                    atom_unchecked("trace"),
+                    tracee.pid_term(),
+                    atom_unchecked("send"),
+                    message,
+                    tracee.pid_term()
+                ])
+                .unwrap()
+        ));
+    });
+}
+
+#[test]
+fn with_receive_flag_sends_trace_message_to_tracer() {
+    with_process(|process| {
+        let tracee = process::test(process);
+        let flag_list = process
+            .list_from_slice(&[atom_unchecked("receive")])
+            .unwrap();
+
+        assert!(native(process, tracee.pid_term(), true.into(), flag_list).is_ok());
+
+        let message = atom_unchecked("message");
+        assert!(erlang::send_2(tracee.pid_term(), message, process).is_ok());
+
+        assert!(has_message(
+            process,
+            process
+                .tuple_from_slice(&[
+                    atom_unchecked("trace"),
+                    tracee.pid_term(),
+                    atom_unchecked("receive"),
+                    message
+                ])
+                .unwrap()
+        ));
+    });
+}
+
+#[test]
+fn with_procs_flag_sends_link_trace_message_to_tracer() {
+    with_process(|process| {
+        let tracee = process::test(process);
+        let linked = process::test(process);
+        let flag_list = process.list_from_slice(&[atom_unchecked("procs")]).unwrap();
+
+        assert!(native(process, tracee.pid_term(), true.into(), flag_list).is_ok());
+
+        assert!(erlang::link_1::native(&tracee, linked.pid_term()).is_ok());
+
+        assert!(has_message(
+            process,
+            process
+                .tuple_from_slice(&[
+                    atom_unchecked("trace"),
+                    tracee.pid_term(),
+                    atom_unchecked("link"),
+                    linked.pid_term()
+                ])
+                .unwrap()
+        ));
+    });
+}
+
+#[test]
+fn with_tracer_sends_trace_messages_to_tracer() {
+    with_process(|process| {
+        let tracer = process::test(process);
+        let tracee = process::test(process);
+        let tracer_option = process
+            .tuple_from_slice(&[atom_unchecked("tracer"), tracer.pid_term()])
+            .unwrap();
+        let flag_list = process
+            .list_from_slice(&[atom_unchecked("send"), tracer_option])
+            .unwrap();
+
+        assert!(native(process, tracee.pid_term(), true.into(), flag_list).is_ok());
+
+        assert_eq!(
+            tracee.trace().unwrap().tracer,
+            Tracer::Process(tracer.pid())
+        );
+    });
+}
+
+#[test]
+fn with_unregistered_backend_tracer_errors_badarg() {
+    with_process(|process| {
+        let tracee = process::test(process);
+        let tracer_option = process
+            .tuple_from_slice(&[atom_unchecked("tracer"), atom_unchecked("unregistered")])
+            .unwrap();
+        let flag_list = process
+            .list_from_slice(&[atom_unchecked("send"), tracer_option])
+            .unwrap();
+
+        assert_eq!(
+            native(process, tracee.pid_term(), true.into(), flag_list),
+            Err(badarg!().into())
+        );
+    });
+}
+
+#[test]
+fn with_backend_tracer_gives_trace_messages_to_backend() {
+    with_process(|process| {
+        let backend = Arc::new(Collect::default());
+        trace::register_backend(
+            Atom::try_from_str("with_backend_tracer_gives_trace_messages_to_backend").unwrap(),
+            backend.clone(),
+        );
+
+        let tracee = process::test(process);
+        let tracer_option = process
+            .tuple_from_slice(&[
+                atom_unchecked("tracer"),
+                atom_unchecked("with_backend_tracer_gives_trace_messages_to_backend"),
+            ])
+            .unwrap();
+        let flag_list = process
+            .list_from_slice(&[atom_unchecked("send"), tracer_option])
+            .unwrap();
+
+        assert!(native(process, tracee.pid_term(), true.into(), flag_list).is_ok());
+
+        let message = atom_unchecked("message");
+        assert!(erlang::send_2(tracee.pid_term(), message, &tracee).is_ok());
+
+        let expected = process
+            .tuple_from_slice(&[
+                atom_unchecked("trace"),
+                tracee.pid_term(),
+                atom_unchecked("send"),
+                message,
+                tracee.pid_term(),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            *backend.traced.lock().unwrap(),
+            vec![(Event::Send, expected.to_string())]
+        );
+    });
+}
+
+#[derive(Default)]
+struct Collect {
+    traced: Mutex<Vec<(Event, String)>>,
+}
+
+impl Backend for Collect {
+    fn trace(&self, event: Event, message: Term) {
+        self.traced
+            .lock()
+            .unwrap()
+            .push((event, message.to_string()));
+    }
+}
+
+#[test]
+fn with_false_how_clears_flags() {
+    with_process(|process| {
+        let tracee = process::test(process);
+        let flag_list = process
+            .list_from_slice(&[atom_unchecked("send"), atom_unchecked("receive")])
+            .unwrap();
+
+        assert!(native(process, tracee.pid_term(), true.into(), flag_list).is_ok());
+        assert!(tracee.trace().is_some());
+
+        let send_flag_list = process.list_from_slice(&[atom_unchecked("send")]).unwrap();
+
+        assert!(native(process, tracee.pid_term(), false.into(), send_flag_list).is_ok());
+        assert!(!tracee.trace().unwrap().flags.send);
+        assert!(tracee.trace().unwrap().flags.receive);
+
+        assert!(native(process, tracee.pid_term(), false.into(), flag_list).is_ok());
+        assert!(tracee.trace().is_none());
+    });
+}
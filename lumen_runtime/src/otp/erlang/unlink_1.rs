@@ -15,6 +15,8 @@ use liblumen_alloc::erts::process::Process;
 use liblumen_alloc::erts::term::{Atom, Term, TypedTerm};
 use liblumen_alloc::{badarg, ModuleFunctionArity};
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::distribution::{self, control::RemotePid};
 use crate::registry::pid_to_process;
 
 pub fn place_frame_with_arguments(
@@ -79,7 +81,14 @@ fn native(process: &Process, pid_or_port: Term) -> exception::Result {
         }
         TypedTerm::Port(_) => unimplemented!(),
         TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
-            TypedTerm::ExternalPid(_) => unimplemented!(),
+            TypedTerm::ExternalPid(_) => {
+                // Distribution is not supported on wasm32, so links to remote processes are never
+                // established there.
+                #[cfg(not(target_arch = "wasm32"))]
+                distribution::link::unlink(process, RemotePid::from_term(pid_or_port).unwrap());
+
+                Ok(true.into())
+            }
             TypedTerm::ExternalPort(_) => unimplemented!(),
             _ => Err(badarg!().into()),
         },
@@ -1,3 +1,4 @@
+mod with_external_pid;
 mod with_local_pid;
 
 use proptest::prop_assert_eq;
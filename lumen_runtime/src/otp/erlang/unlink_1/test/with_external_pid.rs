@@ -0,0 +1,13 @@
+use super::*;
+
+#[test]
+fn returns_true() {
+    with_process(|process| {
+        let external_pid = process.external_pid_with_node_id(1, 2, 3).unwrap();
+        let link_count_before = link_count(process);
+
+        assert_eq!(native(process, external_pid), Ok(true.into()));
+
+        assert_eq!(link_count(process), link_count_before);
+    });
+}
@@ -0,0 +1,13 @@
+use crate::otp::erlang::yield_0::native;
+use crate::scheduler::with_process;
+
+#[test]
+fn returns_true_and_uses_up_run() {
+    with_process(|process| {
+        assert!(!process.is_reduced());
+
+        assert_eq!(native(process), true.into());
+
+        assert!(process.is_reduced());
+    });
+}
@@ -0,0 +1,45 @@
+//! Mirrors [error_logger](http://erlang.org/doc/man/error_logger.html) module
+//!
+//! Like OTP, which keeps `error_logger` for backwards compatibility, every function forwards to
+//! `logger`, so messages and reports go through the Rust `log` facade too.
+
+use liblumen_alloc::erts::exception::Result;
+use liblumen_alloc::erts::term::{atom_unchecked, Term};
+
+use crate::otp::logger;
+
+pub fn error_msg_1(format: Term) -> Result {
+    error_msg_2(format, Term::NIL)
+}
+
+pub fn error_msg_2(format: Term, args: Term) -> Result {
+    logger::log_3(atom_unchecked("error"), format, args)
+}
+
+pub fn error_report_1(report: Term) -> Result {
+    logger::log_2(atom_unchecked("error"), report)
+}
+
+pub fn info_msg_1(format: Term) -> Result {
+    info_msg_2(format, Term::NIL)
+}
+
+pub fn info_msg_2(format: Term, args: Term) -> Result {
+    logger::log_3(atom_unchecked("info"), format, args)
+}
+
+pub fn info_report_1(report: Term) -> Result {
+    logger::log_2(atom_unchecked("info"), report)
+}
+
+pub fn warning_msg_1(format: Term) -> Result {
+    warning_msg_2(format, Term::NIL)
+}
+
+pub fn warning_msg_2(format: Term, args: Term) -> Result {
+    logger::log_3(atom_unchecked("warning"), format, args)
+}
+
+pub fn warning_report_1(report: Term) -> Result {
+    logger::log_2(atom_unchecked("warning"), report)
+}
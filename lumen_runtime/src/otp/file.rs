@@ -0,0 +1,164 @@
+//! Mirrors [file](http://erlang.org/doc/man/file.html) module
+//!
+//! Like the BEAM, bad arguments are returned as `{error, badarg}` instead of being raised, and
+//! errors from the host are returned as `{error, Posix}`, where `Posix` is the name of the `errno`,
+//! such as `enoent`.
+
+pub mod read_file_1;
+pub mod write_file_2;
+pub mod write_file_3;
+
+use std::convert::TryInto;
+use std::io;
+use std::path::PathBuf;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, BinaryView, Term, TypedTerm};
+
+// Private
+
+fn module() -> Atom {
+    Atom::try_from_str("file").unwrap()
+}
+
+/// The path named by `filename`, which is a string, which may be deep, a binary or an atom
+fn filename_to_path(filename: Term) -> Option<PathBuf> {
+    let mut string = String::new();
+
+    if push_filename(&mut string, filename) && !string.is_empty() {
+        Some(PathBuf::from(string))
+    } else {
+        None
+    }
+}
+
+fn push_filename(string: &mut String, filename: Term) -> bool {
+    match filename.to_typed_term().unwrap() {
+        TypedTerm::Atom(atom) => {
+            string.push_str(atom.name());
+
+            true
+        }
+        TypedTerm::Nil => true,
+        TypedTerm::List(cons) => cons.into_iter().all(|result| match result {
+            Ok(element) if element.is_smallint() => {
+                let result_c: Result<char, _> = element.try_into();
+
+                match result_c {
+                    Ok(c) => {
+                        string.push(c);
+
+                        true
+                    }
+                    Err(_) => false,
+                }
+            }
+            Ok(element) => push_filename(string, element),
+            Err(_) => false,
+        }),
+        _ => {
+            let result_name: Result<String, _> = filename.try_into();
+
+            match result_name {
+                Ok(name) => {
+                    string.push_str(&name);
+
+                    true
+                }
+                Err(_) => false,
+            }
+        }
+    }
+}
+
+/// Writes `iodata` to `file`, borrowing the bytes of a binary instead of copying them
+fn write_iodata<W: io::Write>(
+    process: &Process,
+    file: &mut W,
+    iodata: Term,
+) -> Result<(), Option<io::Error>> {
+    match BinaryView::new(process, iodata) {
+        Ok(view) => {
+            if !view.is_binary() {
+                return Err(None);
+            }
+
+            file.write_all(&view.full_bytes()).map_err(Some)
+        }
+        Err(_) => match crate::otp::erlang::iodata_to_bytes(iodata) {
+            Ok(bytes) => file.write_all(&bytes).map_err(Some),
+            Err(_) => Err(None),
+        },
+    }
+}
+
+fn badarg(process: &Process) -> exception::Result {
+    error(process, atom_unchecked("badarg"))
+}
+
+fn error(process: &Process, reason: Term) -> exception::Result {
+    process
+        .tuple_from_slice(&[atom_unchecked("error"), reason])
+        .map_err(|error| error.into())
+}
+
+fn io_error(process: &Process, io_error: &io::Error) -> exception::Result {
+    error(process, atom_unchecked(posix(io_error)))
+}
+
+/// The name of the `errno` of `io_error`, or the closest to its kind if it doesn't have one
+fn posix(io_error: &io::Error) -> &'static str {
+    if let Some(name) = io_error.raw_os_error().and_then(errno_name) {
+        return name;
+    }
+
+    match io_error.kind() {
+        io::ErrorKind::NotFound => "enoent",
+        io::ErrorKind::PermissionDenied => "eacces",
+        io::ErrorKind::AlreadyExists => "eexist",
+        io::ErrorKind::InvalidInput => "einval",
+        io::ErrorKind::Interrupted => "eintr",
+        io::ErrorKind::WouldBlock => "eagain",
+        _ => "eio",
+    }
+}
+
+#[cfg(unix)]
+fn errno_name(errno: i32) -> Option<&'static str> {
+    let name = match errno {
+        libc::EACCES => "eacces",
+        libc::EAGAIN => "eagain",
+        libc::EBADF => "ebadf",
+        libc::EBUSY => "ebusy",
+        libc::EDQUOT => "edquot",
+        libc::EEXIST => "eexist",
+        libc::EFBIG => "efbig",
+        libc::EINTR => "eintr",
+        libc::EINVAL => "einval",
+        libc::EIO => "eio",
+        libc::EISDIR => "eisdir",
+        libc::ELOOP => "eloop",
+        libc::EMFILE => "emfile",
+        libc::ENAMETOOLONG => "enametoolong",
+        libc::ENFILE => "enfile",
+        libc::ENODEV => "enodev",
+        libc::ENOENT => "enoent",
+        libc::ENOMEM => "enomem",
+        libc::ENOSPC => "enospc",
+        libc::ENOTDIR => "enotdir",
+        libc::EPERM => "eperm",
+        libc::EROFS => "erofs",
+        libc::ESPIPE => "espipe",
+        libc::ETXTBSY => "etxtbsy",
+        libc::EXDEV => "exdev",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+#[cfg(not(unix))]
+fn errno_name(_errno: i32) -> Option<&'static str> {
+    None
+}
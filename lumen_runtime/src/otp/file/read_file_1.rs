@@ -0,0 +1,79 @@
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::fs;
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::code::{self, result_from_exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Term};
+use liblumen_alloc::ModuleFunctionArity;
+
+pub fn place_frame_with_arguments(
+    process: &Process,
+    placement: Placement,
+    filename: Term,
+) -> Result<(), Alloc> {
+    process.stack_push(filename)?;
+    process.place_frame(frame(), placement);
+
+    Ok(())
+}
+
+// Private
+
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.reduce();
+
+    let filename = arc_process.stack_pop().unwrap();
+
+    match native(arc_process, filename) {
+        Ok(result) => {
+            arc_process.return_from_call(result)?;
+
+            Process::call_code(arc_process)
+        }
+        Err(exception) => result_from_exception(arc_process, exception),
+    }
+}
+
+fn frame() -> Frame {
+    Frame::new(module_function_arity(), code)
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("read_file").unwrap()
+}
+
+fn module_function_arity() -> Arc<ModuleFunctionArity> {
+    Arc::new(ModuleFunctionArity {
+        module: super::module(),
+        function: function(),
+        arity: 1,
+    })
+}
+
+/// `{ok, Binary}`, where `Binary` is a reference-counted binary unless the file is small enough to
+/// be a heap binary
+pub fn native(process: &Process, filename: Term) -> exception::Result {
+    match super::filename_to_path(filename) {
+        Some(path) => match fs::read(&path) {
+            Ok(bytes) => {
+                let binary = process.binary_from_bytes(&bytes)?;
+
+                process
+                    .tuple_from_slice(&[atom_unchecked("ok"), binary])
+                    .map_err(|error| error.into())
+            }
+            Err(io_error) => super::io_error(process, &io_error),
+        },
+        None => super::badarg(process),
+    }
+}
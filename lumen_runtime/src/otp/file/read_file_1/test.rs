@@ -0,0 +1,111 @@
+use std::fs;
+use std::path::PathBuf;
+
+use liblumen_alloc::erts::term::atom_unchecked;
+
+use crate::otp::file::read_file_1::native;
+use crate::scheduler::with_process;
+
+#[test]
+fn with_missing_file_returns_enoent() {
+    with_process(|process| {
+        let path = path("missing");
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(
+            native(
+                process,
+                process.charlist_from_str(path.to_str().unwrap()).unwrap()
+            ),
+            Ok(process
+                .tuple_from_slice(&[atom_unchecked("error"), atom_unchecked("enoent")])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_directory_returns_eisdir() {
+    with_process(|process| {
+        let path = std::env::temp_dir();
+
+        assert_eq!(
+            native(
+                process,
+                process.binary_from_str(path.to_str().unwrap()).unwrap()
+            ),
+            Ok(process
+                .tuple_from_slice(&[atom_unchecked("error"), atom_unchecked("eisdir")])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_non_filename_returns_badarg() {
+    with_process(|process| {
+        assert_eq!(
+            native(process, process.integer(1).unwrap()),
+            Ok(process
+                .tuple_from_slice(&[atom_unchecked("error"), atom_unchecked("badarg")])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_small_file_returns_heap_binary() {
+    with_process(|process| {
+        let path = path("small");
+        fs::write(&path, b"small").unwrap();
+
+        assert_eq!(
+            native(
+                process,
+                process.charlist_from_str(path.to_str().unwrap()).unwrap()
+            ),
+            Ok(process
+                .tuple_from_slice(&[
+                    atom_unchecked("ok"),
+                    process.binary_from_bytes(b"small").unwrap()
+                ])
+                .unwrap())
+        );
+
+        fs::remove_file(&path).unwrap();
+    });
+}
+
+#[test]
+fn with_large_file_returns_reference_counted_binary() {
+    with_process(|process| {
+        let path = path("large");
+        let bytes: Vec<u8> = (0..4096).map(|i| i as u8).collect();
+        fs::write(&path, &bytes).unwrap();
+
+        let result = native(
+            process,
+            process.charlist_from_str(path.to_str().unwrap()).unwrap(),
+        );
+
+        assert_eq!(
+            result,
+            Ok(process
+                .tuple_from_slice(&[
+                    atom_unchecked("ok"),
+                    process.binary_from_bytes(&bytes).unwrap()
+                ])
+                .unwrap())
+        );
+
+        fs::remove_file(&path).unwrap();
+    });
+}
+
+fn path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "lumen_file_read_file_1_{}_{}",
+        std::process::id(),
+        name
+    ))
+}
@@ -0,0 +1,69 @@
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::code::{self, result_from_exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{Atom, Term};
+use liblumen_alloc::ModuleFunctionArity;
+
+use crate::otp::file::write_file_3;
+
+pub fn place_frame_with_arguments(
+    process: &Process,
+    placement: Placement,
+    filename: Term,
+    bytes: Term,
+) -> Result<(), Alloc> {
+    process.stack_push(bytes)?;
+    process.stack_push(filename)?;
+    process.place_frame(frame(), placement);
+
+    Ok(())
+}
+
+// Private
+
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.reduce();
+
+    let filename = arc_process.stack_pop().unwrap();
+    let bytes = arc_process.stack_pop().unwrap();
+
+    match native(arc_process, filename, bytes) {
+        Ok(result) => {
+            arc_process.return_from_call(result)?;
+
+            Process::call_code(arc_process)
+        }
+        Err(exception) => result_from_exception(arc_process, exception),
+    }
+}
+
+fn frame() -> Frame {
+    Frame::new(module_function_arity(), code)
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("write_file").unwrap()
+}
+
+fn module_function_arity() -> Arc<ModuleFunctionArity> {
+    Arc::new(ModuleFunctionArity {
+        module: super::module(),
+        function: function(),
+        arity: 2,
+    })
+}
+
+pub fn native(process: &Process, filename: Term, bytes: Term) -> exception::Result {
+    write_file_3::native(process, filename, bytes, Term::NIL)
+}
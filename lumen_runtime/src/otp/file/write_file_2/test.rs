@@ -0,0 +1,100 @@
+use std::fs;
+use std::path::PathBuf;
+
+use liblumen_alloc::erts::term::atom_unchecked;
+
+use crate::otp::file::write_file_2::native;
+use crate::scheduler::with_process;
+
+#[test]
+fn with_binary_writes_binary() {
+    with_process(|process| {
+        let path = path("binary");
+
+        assert_eq!(
+            native(
+                process,
+                process.charlist_from_str(path.to_str().unwrap()).unwrap(),
+                process.binary_from_str("binary").unwrap()
+            ),
+            Ok(atom_unchecked("ok"))
+        );
+        assert_eq!(fs::read(&path).unwrap(), b"binary");
+
+        fs::remove_file(&path).unwrap();
+    });
+}
+
+#[test]
+fn with_iolist_writes_flattened_bytes() {
+    with_process(|process| {
+        let path = path("iolist");
+        let iolist = process
+            .list_from_slice(&[
+                process.integer(b'i').unwrap(),
+                process.binary_from_str("o").unwrap(),
+                process.charlist_from_str("list").unwrap(),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            native(
+                process,
+                process.binary_from_str(path.to_str().unwrap()).unwrap(),
+                iolist
+            ),
+            Ok(atom_unchecked("ok"))
+        );
+        assert_eq!(fs::read(&path).unwrap(), b"iolist");
+
+        fs::remove_file(&path).unwrap();
+    });
+}
+
+#[test]
+fn truncates_existing_file() {
+    with_process(|process| {
+        let path = path("truncates");
+        fs::write(&path, b"longer than new").unwrap();
+
+        assert_eq!(
+            native(
+                process,
+                process.charlist_from_str(path.to_str().unwrap()).unwrap(),
+                process.binary_from_str("new").unwrap()
+            ),
+            Ok(atom_unchecked("ok"))
+        );
+        assert_eq!(fs::read(&path).unwrap(), b"new");
+
+        fs::remove_file(&path).unwrap();
+    });
+}
+
+#[test]
+fn without_iodata_returns_badarg() {
+    with_process(|process| {
+        let path = path("without_iodata");
+
+        assert_eq!(
+            native(
+                process,
+                process.charlist_from_str(path.to_str().unwrap()).unwrap(),
+                atom_unchecked("data")
+            ),
+            Ok(process
+                .tuple_from_slice(&[atom_unchecked("error"), atom_unchecked("badarg")])
+                .unwrap())
+        );
+
+        let _ = fs::remove_file(&path);
+    });
+}
+
+fn path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "lumen_file_write_file_2_{}_{}",
+        std::process::id(),
+        name
+    ))
+}
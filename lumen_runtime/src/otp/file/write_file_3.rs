@@ -0,0 +1,147 @@
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::fs::OpenOptions;
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::code::{self, result_from_exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Term, TypedTerm};
+use liblumen_alloc::ModuleFunctionArity;
+
+pub fn place_frame_with_arguments(
+    process: &Process,
+    placement: Placement,
+    filename: Term,
+    bytes: Term,
+    modes: Term,
+) -> Result<(), Alloc> {
+    process.stack_push(modes)?;
+    process.stack_push(bytes)?;
+    process.stack_push(filename)?;
+    process.place_frame(frame(), placement);
+
+    Ok(())
+}
+
+// Private
+
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.reduce();
+
+    let filename = arc_process.stack_pop().unwrap();
+    let bytes = arc_process.stack_pop().unwrap();
+    let modes = arc_process.stack_pop().unwrap();
+
+    match native(arc_process, filename, bytes, modes) {
+        Ok(result) => {
+            arc_process.return_from_call(result)?;
+
+            Process::call_code(arc_process)
+        }
+        Err(exception) => result_from_exception(arc_process, exception),
+    }
+}
+
+fn frame() -> Frame {
+    Frame::new(module_function_arity(), code)
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("write_file").unwrap()
+}
+
+fn module_function_arity() -> Arc<ModuleFunctionArity> {
+    Arc::new(ModuleFunctionArity {
+        module: super::module(),
+        function: function(),
+        arity: 3,
+    })
+}
+
+/// `ok` after writing `bytes`, which is iodata, to `filename` opened with `modes`.  The
+/// `append`, `exclusive` and `sync` modes change how the file is written; `binary`, `raw`,
+/// `read` and `write` are accepted, but don't change anything.
+pub fn native(process: &Process, filename: Term, bytes: Term, modes: Term) -> exception::Result {
+    let (path, modes) = match (super::filename_to_path(filename), Modes::from_term(modes)) {
+        (Some(path), Some(modes)) => (path, modes),
+        _ => return super::badarg(process),
+    };
+
+    let mut open_options = OpenOptions::new();
+    open_options.write(true);
+
+    if modes.append {
+        open_options.append(true);
+    } else {
+        open_options.truncate(true);
+    }
+
+    if modes.exclusive {
+        open_options.create_new(true);
+    } else {
+        open_options.create(true);
+    }
+
+    let mut file = match open_options.open(&path) {
+        Ok(file) => file,
+        Err(io_error) => return super::io_error(process, &io_error),
+    };
+
+    let result = super::write_iodata(process, &mut file, bytes).and_then(|()| {
+        if modes.sync {
+            file.sync_all().map_err(Some)
+        } else {
+            Ok(())
+        }
+    });
+
+    match result {
+        Ok(()) => Ok(atom_unchecked("ok")),
+        Err(Some(io_error)) => super::io_error(process, &io_error),
+        Err(None) => super::badarg(process),
+    }
+}
+
+#[derive(Default)]
+struct Modes {
+    append: bool,
+    exclusive: bool,
+    sync: bool,
+}
+
+impl Modes {
+    fn from_term(term: Term) -> Option<Self> {
+        let mut modes: Self = Default::default();
+
+        match term.to_typed_term().unwrap() {
+            TypedTerm::Nil => (),
+            TypedTerm::List(cons) => {
+                for result in cons.into_iter() {
+                    let name = match result.ok()?.to_typed_term().unwrap() {
+                        TypedTerm::Atom(atom) => atom.name(),
+                        _ => return None,
+                    };
+
+                    match name {
+                        "append" => modes.append = true,
+                        "exclusive" => modes.exclusive = true,
+                        "sync" => modes.sync = true,
+                        "binary" | "raw" | "read" | "write" => (),
+                        _ => return None,
+                    }
+                }
+            }
+            _ => return None,
+        }
+
+        Some(modes)
+    }
+}
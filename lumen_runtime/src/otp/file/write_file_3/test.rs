@@ -0,0 +1,105 @@
+use std::fs;
+use std::path::PathBuf;
+
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Term};
+
+use crate::otp::file::write_file_3::native;
+use crate::scheduler::with_process;
+
+#[test]
+fn with_append_appends() {
+    with_process(|process| {
+        let path = path("append");
+        fs::write(&path, b"first ").unwrap();
+
+        assert_eq!(
+            native(
+                process,
+                process.charlist_from_str(path.to_str().unwrap()).unwrap(),
+                process.binary_from_str("second").unwrap(),
+                modes(process, &["append", "binary"])
+            ),
+            Ok(atom_unchecked("ok"))
+        );
+        assert_eq!(fs::read(&path).unwrap(), b"first second");
+
+        fs::remove_file(&path).unwrap();
+    });
+}
+
+#[test]
+fn with_exclusive_and_existing_file_returns_eexist() {
+    with_process(|process| {
+        let path = path("exclusive");
+        fs::write(&path, b"existing").unwrap();
+
+        assert_eq!(
+            native(
+                process,
+                process.charlist_from_str(path.to_str().unwrap()).unwrap(),
+                process.binary_from_str("new").unwrap(),
+                modes(process, &["exclusive"])
+            ),
+            Ok(process
+                .tuple_from_slice(&[atom_unchecked("error"), atom_unchecked("eexist")])
+                .unwrap())
+        );
+        assert_eq!(fs::read(&path).unwrap(), b"existing");
+
+        fs::remove_file(&path).unwrap();
+    });
+}
+
+#[test]
+fn with_unknown_mode_returns_badarg() {
+    with_process(|process| {
+        let path = path("unknown_mode");
+
+        assert_eq!(
+            native(
+                process,
+                process.charlist_from_str(path.to_str().unwrap()).unwrap(),
+                process.binary_from_str("new").unwrap(),
+                modes(process, &["unknown"])
+            ),
+            Ok(process
+                .tuple_from_slice(&[atom_unchecked("error"), atom_unchecked("badarg")])
+                .unwrap())
+        );
+        assert!(!path.exists());
+    });
+}
+
+#[test]
+fn with_missing_directory_returns_enoent() {
+    with_process(|process| {
+        let path = path("missing").join("file");
+
+        assert_eq!(
+            native(
+                process,
+                process.charlist_from_str(path.to_str().unwrap()).unwrap(),
+                process.binary_from_str("new").unwrap(),
+                Term::NIL
+            ),
+            Ok(process
+                .tuple_from_slice(&[atom_unchecked("error"), atom_unchecked("enoent")])
+                .unwrap())
+        );
+    });
+}
+
+fn modes(process: &Process, names: &[&str]) -> Term {
+    let atoms: Vec<Term> = names.iter().map(|name| atom_unchecked(name)).collect();
+
+    process.list_from_slice(&atoms).unwrap()
+}
+
+fn path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "lumen_file_write_file_3_{}_{}",
+        std::process::id(),
+        name
+    ))
+}
@@ -0,0 +1,925 @@
+//! Mirrors [io_lib](http://erlang.org/doc/man/io_lib.html) module
+//!
+//! `format` parses control sequences with field widths, precisions, padding characters and the `t`
+//! and `l` modifiers, and supports the `~c`, `~f`, `~e`, `~g`, `~s`, `~w`, `~p`, `~W`, `~P`, `~B`,
+//! `~X`, `~#`, `~b`, `~x`, `~+`, `~i`, `~n` and `~~` controls.  `~p` breaks terms that don't fit
+//! in the line length across lines, indenting elements under their opening bracket, but doesn't
+//! break strings.
+
+pub mod format_2;
+
+use std::convert::TryInto;
+use std::fmt::Write;
+
+use num_bigint::BigInt;
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception::Exception;
+use liblumen_alloc::erts::term::{Atom, ImproperList, Term, TypedTerm};
+
+/// The text of an atom, binary or possibly deep list of characters and binaries.  Without
+/// `unicode`, characters must be Latin-1 and binaries are bytes of Latin-1 characters; with it,
+/// binaries are UTF-8.
+pub fn chardata_to_string(term: Term, unicode: bool) -> Result<String, Exception> {
+    let mut string = String::new();
+    push_chardata(&mut string, term, unicode)?;
+
+    Ok(string)
+}
+
+/// The text of `format` with its control sequences replaced by the formatted `arguments`
+pub fn format(format: Term, arguments: Term) -> Result<String, Exception> {
+    let format_chars: Vec<char> = chardata_to_string(format, true)?.chars().collect();
+    let mut arguments_vec = Vec::new();
+
+    for result in arguments.list_iter()? {
+        arguments_vec.push(result?);
+    }
+
+    let mut formatter = Formatter {
+        format: &format_chars,
+        index: 0,
+        arguments: arguments_vec.into_iter(),
+        formatted: String::new(),
+    };
+
+    formatter.format()?;
+
+    if formatter.arguments.next().is_some() {
+        return Err(badarg!().into());
+    }
+
+    Ok(formatter.formatted)
+}
+
+/// The text of `term` in Erlang syntax, as `~w` writes it
+pub fn write(term: Term) -> String {
+    let mut written = String::new();
+    Writer::write(false).term(&mut written, term, -1);
+
+    written
+}
+
+// Private
+
+fn module() -> Atom {
+    Atom::try_from_str("io_lib").unwrap()
+}
+
+const DEFAULT_LINE_LENGTH: usize = 80;
+
+struct Formatter<'a> {
+    format: &'a [char],
+    index: usize,
+    arguments: std::vec::IntoIter<Term>,
+    formatted: String,
+}
+
+impl<'a> Formatter<'a> {
+    fn format(&mut self) -> Result<(), Exception> {
+        while let Some(c) = self.next_char() {
+            if c == '~' {
+                let control = self.control()?;
+                self.format_control(control)?;
+            } else {
+                self.formatted.push(c);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// `~F.P.PadModC`, after the `~`
+    fn control(&mut self) -> Result<Control, Exception> {
+        let field_width = self.field()?;
+        let mut precision = None;
+        let mut pad = ' ';
+
+        if self.peek_char() == Some('.') {
+            self.next_char();
+            precision = self.field()?;
+
+            if self.peek_char() == Some('.') {
+                self.next_char();
+                pad = self.next_char().ok_or_else(|| badarg!())?;
+            }
+        }
+
+        let mut unicode = false;
+        let mut strings = true;
+
+        loop {
+            match self.next_char() {
+                Some('t') => unicode = true,
+                Some('l') => strings = false,
+                Some(c) => {
+                    return Ok(Control {
+                        field_width,
+                        precision,
+                        pad,
+                        unicode,
+                        strings,
+                        c,
+                    })
+                }
+                None => return Err(badarg!().into()),
+            }
+        }
+    }
+
+    /// A field width or precision, which is `*` when it is the next argument
+    fn field(&mut self) -> Result<Option<isize>, Exception> {
+        if self.peek_char() == Some('*') {
+            self.next_char();
+            let field: isize = self.next_argument()?.try_into()?;
+
+            return Ok(Some(field));
+        }
+
+        let negative = self.peek_char() == Some('-');
+
+        if negative {
+            self.next_char();
+        }
+
+        let mut digits = String::new();
+
+        while let Some(c) = self.peek_char().filter(|c| c.is_ascii_digit()) {
+            self.next_char();
+            digits.push(c);
+        }
+
+        match (digits.parse::<isize>(), negative) {
+            (Ok(field), true) => Ok(Some(-field)),
+            (Ok(field), false) => Ok(Some(field)),
+            (Err(_), true) => Err(badarg!().into()),
+            (Err(_), false) => Ok(None),
+        }
+    }
+
+    fn format_control(&mut self, control: Control) -> Result<(), Exception> {
+        match control.c {
+            '~' => self.formatted.push('~'),
+            'n' => self.formatted.push('\n'),
+            'i' => {
+                self.next_argument()?;
+            }
+            'c' => {
+                let c: char = self.next_argument()?.try_into()?;
+
+                if !control.unicode && 255 < (c as u32) {
+                    return Err(badarg!().into());
+                }
+
+                let times = control
+                    .precision
+                    .or(control.field_width.map(isize::abs))
+                    .unwrap_or(1);
+                let text: String = (0..times).map(|_| c).collect();
+                self.adjust(&control, text);
+            }
+            'f' | 'e' | 'g' => {
+                let float = float(self.next_argument()?)?;
+                let text = match control.c {
+                    'f' => fixed(float, precision(&control, 6, 1)?),
+                    'e' => exponential(float, precision(&control, 6, 2)?),
+                    _ => general(float, precision(&control, 6, 2)?),
+                };
+                self.fit(&control, text);
+            }
+            's' => {
+                let mut text = chardata_to_string(self.next_argument()?, control.unicode)?;
+
+                if let Some(precision) = control.precision.or(control.field_width.map(isize::abs)) {
+                    if precision < 0 {
+                        return Err(badarg!().into());
+                    }
+
+                    text = text.chars().take(precision as usize).collect();
+                }
+
+                self.adjust(&control, text);
+            }
+            'w' | 'W' => {
+                let term = self.next_argument()?;
+                let depth = self.depth(&control)?;
+                let mut text = String::new();
+                Writer::write(control.unicode).term(&mut text, term, depth);
+                self.fit(&control, text);
+            }
+            'p' | 'P' => {
+                let term = self.next_argument()?;
+                let depth = self.depth(&control)?;
+                let line_length = match control.field_width {
+                    Some(field_width) if 0 < field_width => field_width as usize,
+                    Some(_) => return Err(badarg!().into()),
+                    None => DEFAULT_LINE_LENGTH,
+                };
+                let column = self.column();
+                let writer = Writer::print(control.strings, control.unicode);
+                let text = writer.pretty(term, depth, column, line_length);
+                self.formatted.push_str(&text);
+            }
+            'b' | 'B' | 'x' | 'X' | '#' | '+' => {
+                let integer: BigInt = self.next_argument()?.try_into()?;
+                let base = precision(&control, 10, 2)?;
+
+                if 36 < base {
+                    return Err(badarg!().into());
+                }
+
+                let prefix = match control.c {
+                    'x' | 'X' => chardata_to_string(self.next_argument()?, control.unicode)?,
+                    '#' | '+' => format!("{}#", base),
+                    _ => String::new(),
+                };
+                let digits = integer.to_str_radix(base as u32);
+                let digits = match control.c {
+                    'B' | 'X' | '#' => digits.to_uppercase(),
+                    _ => digits,
+                };
+                let text = if digits.starts_with('-') {
+                    format!("-{}{}", prefix, &digits[1..])
+                } else {
+                    format!("{}{}", prefix, digits)
+                };
+                self.fit(&control, text);
+            }
+            _ => return Err(badarg!().into()),
+        }
+
+        Ok(())
+    }
+
+    /// The depth argument of `~W` and `~P`, where `-1` is unlimited
+    fn depth(&mut self, control: &Control) -> Result<isize, Exception> {
+        match control.c {
+            'W' | 'P' => Ok(self.next_argument()?.try_into()?),
+            _ => Ok(-1),
+        }
+    }
+
+    /// Pads `text` to the field width
+    fn adjust(&mut self, control: &Control, text: String) {
+        let len = text.chars().count();
+
+        match control.field_width {
+            Some(field_width) if len < (field_width.abs() as usize) => {
+                let padding: String = (len..(field_width.abs() as usize))
+                    .map(|_| control.pad)
+                    .collect();
+
+                if field_width < 0 {
+                    self.formatted.push_str(&text);
+                    self.formatted.push_str(&padding);
+                } else {
+                    self.formatted.push_str(&padding);
+                    self.formatted.push_str(&text);
+                }
+            }
+            _ => self.formatted.push_str(&text),
+        }
+    }
+
+    /// Pads `text` to the field width, or fills the field with `*` if `text` doesn't fit
+    fn fit(&mut self, control: &Control, text: String) {
+        match control.field_width {
+            Some(field_width) if (field_width.abs() as usize) < text.chars().count() => {
+                for _ in 0..field_width.abs() {
+                    self.formatted.push('*');
+                }
+            }
+            _ => self.adjust(control, text),
+        }
+    }
+
+    /// The characters formatted on the current line so far
+    fn column(&self) -> usize {
+        match self.formatted.rfind('\n') {
+            Some(index) => self.formatted[(index + 1)..].chars().count(),
+            None => self.formatted.chars().count(),
+        }
+    }
+
+    fn next_argument(&mut self) -> Result<Term, Exception> {
+        self.arguments.next().ok_or_else(|| badarg!().into())
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        let option_c = self.peek_char();
+
+        if option_c.is_some() {
+            self.index += 1;
+        }
+
+        option_c
+    }
+
+    fn peek_char(&self) -> Option<char> {
+        self.format.get(self.index).cloned()
+    }
+}
+
+struct Control {
+    /// Negative when left-justified
+    field_width: Option<isize>,
+    precision: Option<isize>,
+    pad: char,
+    /// The `t` modifier
+    unicode: bool,
+    /// Lists and binaries are printed as strings when they are printable, unless the `l` modifier
+    /// is given
+    strings: bool,
+    c: char,
+}
+
+/// The precision of `control`, or `default` if there isn't one
+fn precision(control: &Control, default: usize, minimum: usize) -> Result<usize, Exception> {
+    match control.precision {
+        Some(precision) if (minimum as isize) <= precision => Ok(precision as usize),
+        Some(_) => Err(badarg!().into()),
+        None => Ok(default),
+    }
+}
+
+/// Writes terms in Erlang syntax, as `~w` does, or with printable lists and binaries as strings,
+/// as `~p` does
+#[derive(Clone, Copy)]
+struct Writer {
+    strings: bool,
+    unicode: bool,
+}
+
+impl Writer {
+    fn write(unicode: bool) -> Writer {
+        Writer {
+            strings: false,
+            unicode,
+        }
+    }
+
+    fn print(strings: bool, unicode: bool) -> Writer {
+        Writer { strings, unicode }
+    }
+
+    fn term(&self, out: &mut String, term: Term, depth: isize) {
+        if depth == 0 {
+            out.push_str("...");
+
+            return;
+        }
+
+        match term.to_typed_term().unwrap() {
+            TypedTerm::Atom(atom) => write_atom(out, atom),
+            TypedTerm::SmallInteger(small_integer) => write!(out, "{}", small_integer).unwrap(),
+            TypedTerm::Float(float) => write_float(out, float.into()),
+            TypedTerm::Nil => out.push_str("[]"),
+            TypedTerm::List(_) => match self.string(term) {
+                Some(string) => write_string(out, '"', &string),
+                None => self.items(out, "[", &self.list_items(term, depth), "]"),
+            },
+            TypedTerm::Pid(pid) => out.push_str(&pid.to_string().replacen("#PID", "", 1)),
+            TypedTerm::Port(port) => write!(out, "{}", port).unwrap(),
+            TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+                TypedTerm::BigInteger(big_integer) => write!(out, "{}", big_integer).unwrap(),
+                TypedTerm::Float(float) => write_float(out, float.into()),
+                TypedTerm::Tuple(_) => match self.container_items(term, depth) {
+                    Some((open, items)) => self.items(out, open, &items, close(open)),
+                    None => out.push_str("{}"),
+                },
+                TypedTerm::Map(_) => match self.container_items(term, depth) {
+                    Some((open, items)) => self.items(out, open, &items, close(open)),
+                    None => out.push_str("#{}"),
+                },
+                TypedTerm::ExternalPid(external_pid) => {
+                    out.push_str(&external_pid.to_string().replacen("#PID", "", 1))
+                }
+                TypedTerm::Reference(reference) => {
+                    out.push_str(&reference.to_string().replacen("#Reference", "#Ref", 1))
+                }
+                TypedTerm::ExternalReference(external_reference) => out.push_str(
+                    &external_reference
+                        .to_string()
+                        .replacen("#Reference", "#Ref", 1),
+                ),
+                TypedTerm::Closure(closure) => {
+                    let module_function_arity = closure.module_function_arity();
+
+                    write!(
+                        out,
+                        "#Fun<{}.{}.{}>",
+                        module_function_arity.module.name(),
+                        module_function_arity.function.name(),
+                        module_function_arity.arity
+                    )
+                    .unwrap()
+                }
+                _ => self.binary(out, term),
+            },
+            _ => self.binary(out, term),
+        }
+    }
+
+    /// Writes `term` broken across lines that are at most `line_length` long, if it doesn't fit on
+    /// the rest of the line after `column`
+    fn pretty(&self, term: Term, depth: isize, column: usize, line_length: usize) -> String {
+        let mut one_line = String::new();
+        self.term(&mut one_line, term, depth);
+
+        if column + one_line.chars().count() <= line_length {
+            return one_line;
+        }
+
+        let (open, items) = match self.container_items(term, depth) {
+            Some(open_items) => open_items,
+            None => return one_line,
+        };
+
+        let mut out = String::from(open);
+        let indent = column + open.chars().count();
+        let fill = items.iter().all(|item| match item {
+            Item::Element(term, _) | Item::Tail(term, _) => {
+                self.container_items(*term, -1).is_none()
+            }
+            _ => true,
+        });
+        let mut line_column = indent;
+
+        for (index, item) in items.iter().enumerate() {
+            let separator = item.separator();
+            let item_column = line_column + if index == 0 { 0 } else { separator.len() };
+            let text = self.pretty_item(item, item_column, line_length);
+            let text_len = text.lines().next().unwrap_or("").chars().count();
+
+            if index == 0 {
+                out.push_str(&text);
+                line_column = indent + text_len;
+            } else if fill && item_column + text_len < line_length {
+                out.push_str(separator);
+                out.push_str(&text);
+                line_column = item_column + text_len;
+            } else {
+                let item_column = indent + separator.len() - 1;
+                let text = self.pretty_item(item, item_column, line_length);
+
+                // `|` stays on the line of the last element, but `,` ends its line
+                out.push_str(&separator[..1]);
+                out.push('\n');
+                out.extend((0..indent).map(|_| ' '));
+                out.push_str(&separator[1..]);
+                out.push_str(&text);
+                line_column = item_column + text.lines().last().unwrap_or("").chars().count();
+            }
+        }
+
+        out.push_str(close(open));
+
+        out
+    }
+
+    fn pretty_item(&self, item: &Item, column: usize, line_length: usize) -> String {
+        match item {
+            Item::Element(term, depth) | Item::Tail(term, depth) => {
+                self.pretty(*term, *depth, column, line_length)
+            }
+            Item::Association(key, value, depth) => {
+                let mut key_text = String::new();
+                self.term(&mut key_text, *key, *depth);
+                key_text.push_str(" => ");
+                let value_column = column + key_text.chars().count();
+                key_text.push_str(&self.pretty(*value, *depth, value_column, line_length));
+
+                key_text
+            }
+            Item::Ellipsis(_) => "...".to_string(),
+        }
+    }
+
+    fn items(&self, out: &mut String, open: &str, items: &[Item], close: &str) {
+        out.push_str(open);
+
+        for (index, item) in items.iter().enumerate() {
+            if 0 < index {
+                out.push_str(item.separator());
+            }
+
+            match item {
+                Item::Element(term, depth) | Item::Tail(term, depth) => {
+                    self.term(out, *term, *depth)
+                }
+                Item::Association(key, value, depth) => {
+                    self.term(out, *key, *depth);
+                    out.push_str(" => ");
+                    self.term(out, *value, *depth);
+                }
+                Item::Ellipsis(_) => out.push_str("..."),
+            }
+        }
+
+        out.push_str(close);
+    }
+
+    /// The opening bracket and items of lists that aren't printed as strings, tuples and maps
+    fn container_items(&self, term: Term, depth: isize) -> Option<(&'static str, Vec<Item>)> {
+        if depth == 0 {
+            return None;
+        }
+
+        match term.to_typed_term().unwrap() {
+            TypedTerm::List(_) if self.string(term).is_none() => {
+                Some(("[", self.list_items(term, depth)))
+            }
+            TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+                TypedTerm::Tuple(tuple) => {
+                    let elements: Vec<Term> = tuple.iter().collect();
+
+                    if elements.is_empty() {
+                        None
+                    } else {
+                        Some(("{", tail_items(elements, None, depth, false)))
+                    }
+                }
+                TypedTerm::Map(map) => {
+                    let mut keys = map.keys();
+
+                    if keys.is_empty() {
+                        return None;
+                    }
+
+                    keys.sort();
+
+                    let items = if depth == 1 {
+                        vec![Item::Ellipsis(",")]
+                    } else {
+                        let mut items = Vec::new();
+                        let mut item_depth = depth - 1;
+
+                        for key in keys {
+                            if item_depth == 0 {
+                                items.push(Item::Ellipsis(","));
+                                break;
+                            }
+
+                            items.push(Item::Association(key, map.get(key).unwrap(), item_depth));
+                            item_depth -= 1;
+                        }
+
+                        items
+                    };
+
+                    Some(("#{", items))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn list_items(&self, list: Term, depth: isize) -> Vec<Item> {
+        let mut elements = Vec::new();
+        let mut option_tail = None;
+
+        for result in list.list_iter().unwrap() {
+            match result {
+                Ok(element) => elements.push(element),
+                Err(ImproperList { tail }) => option_tail = Some(tail),
+            }
+        }
+
+        tail_items(elements, option_tail, depth, true)
+    }
+
+    /// The text of `term` if it is a printable list and strings are printed
+    fn string(&self, term: Term) -> Option<String> {
+        if !self.strings {
+            return None;
+        }
+
+        let mut string = String::new();
+
+        for result in term.list_iter().ok()? {
+            let c: char = result.ok()?.try_into().ok()?;
+
+            if !is_printable(c, self.unicode) {
+                return None;
+            }
+
+            string.push(c);
+        }
+
+        Some(string)
+    }
+
+    fn binary(&self, out: &mut String, term: Term) {
+        let bytes: Vec<u8> = match term.try_into() {
+            Ok(bytes) => bytes,
+            // bitstrings and any other terms are written as the runtime displays them
+            Err(_) => {
+                write!(out, "{}", term).unwrap();
+
+                return;
+            }
+        };
+
+        if self.strings && !bytes.is_empty() {
+            let option_string = if self.unicode {
+                String::from_utf8(bytes.clone()).ok()
+            } else {
+                Some(bytes.iter().map(|byte| *byte as char).collect())
+            };
+
+            if let Some(string) = option_string {
+                if string.chars().all(|c| is_printable(c, self.unicode)) {
+                    out.push_str("<<");
+                    write_string(out, '"', &string);
+                    if self.unicode && string.chars().any(|c| 255 < (c as u32)) {
+                        out.push_str("/utf8");
+                    }
+                    out.push_str(">>");
+
+                    return;
+                }
+            }
+        }
+
+        out.push_str("<<");
+
+        for (index, byte) in bytes.iter().enumerate() {
+            if 0 < index {
+                out.push(',');
+            }
+
+            write!(out, "{}", byte).unwrap();
+        }
+
+        out.push_str(">>");
+    }
+}
+
+/// A piece of a list, tuple or map, written with the depth
+enum Item {
+    Element(Term, isize),
+    /// The tail of an improper list
+    Tail(Term, isize),
+    Association(Term, Term, isize),
+    /// Elements past the depth, after the separator
+    Ellipsis(&'static str),
+}
+
+impl Item {
+    fn separator(&self) -> &'static str {
+        match self {
+            Item::Tail(..) => "|",
+            Item::Ellipsis(separator) => separator,
+            _ => ",",
+        }
+    }
+}
+
+/// The items of a list or tuple, with each element written with one less depth than the one before
+/// it and the rest elided once the depth runs out, as `io_lib:write/2` does
+fn tail_items(
+    elements: Vec<Term>,
+    option_tail: Option<Term>,
+    depth: isize,
+    list: bool,
+) -> Vec<Item> {
+    let ellipsis_separator = if list { "|" } else { "," };
+
+    if depth == 1 {
+        return vec![Item::Ellipsis(ellipsis_separator)];
+    }
+
+    let mut items = Vec::new();
+    let mut item_depth = depth - 1;
+
+    for element in elements {
+        if item_depth == 0 {
+            items.push(Item::Ellipsis(ellipsis_separator));
+
+            return items;
+        }
+
+        items.push(Item::Element(element, item_depth));
+        item_depth -= 1;
+    }
+
+    if let Some(tail) = option_tail {
+        if item_depth == 0 {
+            items.push(Item::Ellipsis(ellipsis_separator));
+        } else {
+            items.push(Item::Tail(tail, item_depth));
+        }
+    }
+
+    items
+}
+
+fn close(open: &str) -> &'static str {
+    match open {
+        "[" => "]",
+        "{" => "}",
+        _ => "}",
+    }
+}
+
+fn push_chardata(string: &mut String, term: Term, unicode: bool) -> Result<(), Exception> {
+    match term.to_typed_term().unwrap() {
+        TypedTerm::Atom(atom) => string.push_str(atom.name()),
+        TypedTerm::Nil => (),
+        TypedTerm::List(_) => {
+            for result in term.list_iter()? {
+                let element = result?;
+
+                if element.is_smallint() {
+                    let c: char = element.try_into()?;
+
+                    if !unicode && 255 < (c as u32) {
+                        return Err(badarg!().into());
+                    }
+
+                    string.push(c);
+                } else {
+                    match element.to_typed_term().unwrap() {
+                        TypedTerm::Atom(_) => return Err(badarg!().into()),
+                        _ => push_chardata(string, element, unicode)?,
+                    }
+                }
+            }
+        }
+        _ => {
+            let bytes: Vec<u8> = term.try_into()?;
+
+            if unicode {
+                match String::from_utf8(bytes) {
+                    Ok(utf8) => string.push_str(&utf8),
+                    Err(_) => return Err(badarg!().into()),
+                }
+            } else {
+                string.extend(bytes.iter().map(|byte| *byte as char));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn float(term: Term) -> Result<f64, Exception> {
+    match term.to_typed_term().unwrap() {
+        TypedTerm::Float(float) => Ok(float.into()),
+        TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+            TypedTerm::Float(float) => Ok(float.into()),
+            _ => Err(badarg!().into()),
+        },
+        _ => Err(badarg!().into()),
+    }
+}
+
+/// `~f`: `precision` digits after the decimal point
+fn fixed(float: f64, precision: usize) -> String {
+    format!("{:.*}", precision, float)
+}
+
+/// `~e`: `precision` significant digits, with an exponent that always has a sign
+fn exponential(float: f64, precision: usize) -> String {
+    let text = format!("{:.*e}", precision - 1, float);
+    let (mantissa, exponent) = text.split_at(text.find('e').unwrap());
+    let exponent = &exponent[1..];
+
+    if exponent.starts_with('-') {
+        format!("{}e{}", mantissa, exponent)
+    } else {
+        format!("{}e+{}", mantissa, exponent)
+    }
+}
+
+/// `~g`: `~f` when `0.1 <= abs(float) < 10000.0`, otherwise `~e`, with `precision` significant
+/// digits either way
+fn general(float: f64, precision: usize) -> String {
+    let abs = float.abs();
+
+    if 0.1 <= abs && abs < 10000.0 {
+        let integer_digits = abs.log10().floor() as isize + 1;
+        let decimals = (precision as isize - integer_digits).max(0) as usize;
+
+        fixed(float, decimals)
+    } else {
+        exponential(float, precision)
+    }
+}
+
+/// The shortest text that reads back as `float`, as fixed point or with an exponent, whichever is
+/// shorter
+fn write_float(out: &mut String, float: f64) {
+    // the shortest digits that round trip, as `d.ddde[-]x`
+    let scientific = format!("{:e}", float.abs());
+    let (mantissa, exponent) = scientific.split_at(scientific.find('e').unwrap());
+    let exponent: isize = exponent[1..].parse().unwrap();
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+
+    let fixed = if exponent < 0 {
+        let zeros: String = (0..(-exponent - 1)).map(|_| '0').collect();
+
+        format!("0.{}{}", zeros, digits)
+    } else {
+        let integer_len = (exponent + 1) as usize;
+
+        if digits.len() <= integer_len {
+            let zeros: String = (digits.len()..integer_len).map(|_| '0').collect();
+
+            format!("{}{}.0", digits, zeros)
+        } else {
+            format!("{}.{}", &digits[..integer_len], &digits[integer_len..])
+        }
+    };
+    let exponential = if digits.len() == 1 {
+        format!("{}.0e{}", digits, exponent)
+    } else {
+        format!("{}.{}e{}", &digits[..1], &digits[1..], exponent)
+    };
+
+    if float.is_sign_negative() {
+        out.push('-');
+    }
+
+    if fixed.len() <= exponential.len() {
+        out.push_str(&fixed);
+    } else {
+        out.push_str(&exponential);
+    }
+}
+
+fn write_atom(out: &mut String, atom: Atom) {
+    let name = atom.name();
+
+    if is_unquoted_atom(name) {
+        out.push_str(name);
+    } else {
+        write_string(out, '\'', name);
+    }
+}
+
+fn is_unquoted_atom(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    match chars.next() {
+        Some(first) if is_latin1_lowercase(first) => {
+            chars.all(|c| c.is_alphanumeric() || c == '_' || c == '@') && !is_reserved_word(name)
+        }
+        _ => false,
+    }
+}
+
+fn is_latin1_lowercase(c: char) -> bool {
+    match c {
+        'a'..='z' => true,
+        '\u{df}'..='\u{ff}' => c != '\u{f7}',
+        _ => false,
+    }
+}
+
+fn is_reserved_word(name: &str) -> bool {
+    match name {
+        "after" | "and" | "andalso" | "band" | "begin" | "bnot" | "bor" | "bsl" | "bsr"
+        | "bxor" | "case" | "catch" | "cond" | "div" | "end" | "fun" | "if" | "let" | "not"
+        | "of" | "or" | "orelse" | "receive" | "rem" | "try" | "when" | "xor" => true,
+        _ => false,
+    }
+}
+
+/// Whether `c` can be in a string printed by `~p`: Latin-1 characters that aren't control
+/// characters, or any character that isn't a control character with `unicode`, and the control
+/// characters that have escapes
+fn is_printable(c: char, unicode: bool) -> bool {
+    match c as u32 {
+        8..=13 | 27 => true,
+        32..=126 | 160..=255 => true,
+        code if unicode => 255 < code,
+        _ => false,
+    }
+}
+
+fn write_string(out: &mut String, quote: char, string: &str) {
+    out.push(quote);
+
+    for c in string.chars() {
+        match c {
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{b}' => out.push_str("\\v"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            '\u{1b}' => out.push_str("\\e"),
+            '\\' => out.push_str("\\\\"),
+            c if c == quote => {
+                out.push('\\');
+                out.push(c);
+            }
+            c if (c as u32) < 32 || c as u32 == 127 => write!(out, "\\{:o}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+
+    out.push(quote);
+}
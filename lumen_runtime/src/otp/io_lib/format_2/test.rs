@@ -0,0 +1,291 @@
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Term};
+
+use crate::otp::io_lib::format_2::native;
+use crate::scheduler::with_process;
+
+#[test]
+fn without_controls_returns_format() {
+    with_process(|process| {
+        assert_formats(process, "hello", &[], "hello");
+    });
+}
+
+#[test]
+fn with_too_few_arguments_errors_badarg() {
+    with_process(|process| {
+        assert_badarg(process, "~w ~w", &[atom_unchecked("a")]);
+    });
+}
+
+#[test]
+fn with_too_many_arguments_errors_badarg() {
+    with_process(|process| {
+        assert_badarg(process, "~w", &[atom_unchecked("a"), atom_unchecked("b")]);
+    });
+}
+
+#[test]
+fn with_unknown_control_errors_badarg() {
+    with_process(|process| {
+        assert_badarg(process, "~a", &[atom_unchecked("a")]);
+    });
+}
+
+#[test]
+fn with_w_writes_atoms_quoted_when_needed() {
+    with_process(|process| {
+        assert_formats(
+            process,
+            "~w ~w ~w ~w",
+            &[
+                atom_unchecked("ok"),
+                atom_unchecked("Ok"),
+                atom_unchecked("receive"),
+                atom_unchecked("it's"),
+            ],
+            "ok 'Ok' 'receive' 'it\\'s'",
+        );
+    });
+}
+
+#[test]
+fn with_w_writes_lists_and_binaries_without_strings() {
+    with_process(|process| {
+        assert_formats(
+            process,
+            "~w ~w",
+            &[
+                process.charlist_from_str("hi").unwrap(),
+                process.binary_from_str("hi").unwrap(),
+            ],
+            "[104,105] <<104,105>>",
+        );
+    });
+}
+
+#[test]
+fn with_w_writes_improper_lists_tuples_and_maps() {
+    with_process(|process| {
+        assert_formats(
+            process,
+            "~w ~w ~w",
+            &[
+                process
+                    .improper_list_from_slice(&[atom_unchecked("a")], atom_unchecked("b"))
+                    .unwrap(),
+                process
+                    .tuple_from_slice(&[process.integer(1).unwrap(), Term::NIL])
+                    .unwrap(),
+                process
+                    .map_from_slice(&[
+                        (atom_unchecked("b"), process.integer(2).unwrap()),
+                        (atom_unchecked("a"), process.integer(1).unwrap()),
+                    ])
+                    .unwrap(),
+            ],
+            "[a|b] {1,[]} #{a => 1,b => 2}",
+        );
+    });
+}
+
+#[test]
+fn with_w_writes_shortest_floats() {
+    with_process(|process| {
+        assert_formats(
+            process,
+            "~w ~w ~w ~w",
+            &[
+                process.float(1.5).unwrap(),
+                process.float(100.0).unwrap(),
+                process.float(1.0e10).unwrap(),
+                process.float(-0.001).unwrap(),
+            ],
+            "1.5 100.0 1.0e10 -0.001",
+        );
+    });
+}
+
+#[test]
+fn with_big_w_limits_depth() {
+    with_process(|process| {
+        let list = process
+            .list_from_slice(&[
+                process.integer(1).unwrap(),
+                process.integer(2).unwrap(),
+                process.integer(3).unwrap(),
+            ])
+            .unwrap();
+
+        assert_formats(
+            process,
+            "~W ~W ~W",
+            &[
+                list,
+                process.integer(2).unwrap(),
+                list,
+                process.integer(3).unwrap(),
+                list,
+                process.integer(1).unwrap(),
+            ],
+            "[1|...] [1,2|...] [...]",
+        );
+    });
+}
+
+#[test]
+fn with_p_prints_strings() {
+    with_process(|process| {
+        assert_formats(
+            process,
+            "~p ~p ~lp",
+            &[
+                process.charlist_from_str("hi\n").unwrap(),
+                process.binary_from_str("hi").unwrap(),
+                process.charlist_from_str("hi").unwrap(),
+            ],
+            "\"hi\\n\" <<\"hi\">> [104,105]",
+        );
+    });
+}
+
+#[test]
+fn with_p_breaks_terms_longer_than_line_length() {
+    with_process(|process| {
+        let element = process
+            .tuple_from_slice(&[atom_unchecked("key"), atom_unchecked("value")])
+            .unwrap();
+        let list = process.list_from_slice(&[element, element]).unwrap();
+
+        assert_formats(process, "~20p", &[list], "[{key,value},\n {key,value}]");
+    });
+}
+
+#[test]
+fn with_p_fills_lines_with_atomic_elements() {
+    with_process(|process| {
+        let elements: Vec<Term> = (0..6).map(|_| atom_unchecked("abc")).collect();
+        let list = process.list_from_slice(&elements).unwrap();
+
+        assert_formats(process, "~12p", &[list], "[abc,abc,\n abc,abc,\n abc,abc]");
+    });
+}
+
+#[test]
+fn with_s_pads_and_truncates() {
+    with_process(|process| {
+        assert_formats(
+            process,
+            "~5s|~-5s|~3s|~5.2s|~5..*s",
+            &[
+                process.charlist_from_str("ab").unwrap(),
+                process.binary_from_str("ab").unwrap(),
+                atom_unchecked("abcdef"),
+                process.charlist_from_str("abc").unwrap(),
+                process.charlist_from_str("ab").unwrap(),
+            ],
+            "   ab|ab   |abc|   ab|***ab",
+        );
+    });
+}
+
+#[test]
+fn with_s_without_t_errors_badarg_for_unicode() {
+    with_process(|process| {
+        assert_badarg(process, "~s", &[process.charlist_from_str("λ").unwrap()]);
+        assert_formats(
+            process,
+            "~ts",
+            &[process.charlist_from_str("λ").unwrap()],
+            "λ",
+        );
+    });
+}
+
+#[test]
+fn with_c_repeats() {
+    with_process(|process| {
+        let c = process.integer('a' as isize).unwrap();
+
+        assert_formats(process, "~c~3c~3.1c", &[c, c, c], "aaaa  a");
+    });
+}
+
+#[test]
+fn with_floats_formats_precision() {
+    with_process(|process| {
+        let float = process.float(3.14).unwrap();
+
+        assert_formats(
+            process,
+            "~f ~.2f ~e ~.3e ~g ~4f",
+            &[float, float, float, float, float, float],
+            "3.140000 3.14 3.14000e+0 3.14e+0 3.14000 ****",
+        );
+    });
+}
+
+#[test]
+fn with_integers_formats_base() {
+    with_process(|process| {
+        let integer = process.integer(-255).unwrap();
+
+        assert_formats(
+            process,
+            "~b ~.16b ~.16B ~.16x ~.16# ~.16+ ~6b",
+            &[
+                integer,
+                integer,
+                integer,
+                integer,
+                process.charlist_from_str("0x").unwrap(),
+                integer,
+                integer,
+                integer,
+            ],
+            "-255 -ff -FF -0xff -16#FF -16#ff   -255",
+        );
+    });
+}
+
+#[test]
+fn with_float_for_integer_control_errors_badarg() {
+    with_process(|process| {
+        assert_badarg(process, "~b", &[process.float(1.0).unwrap()]);
+    });
+}
+
+#[test]
+fn with_i_n_and_tilde() {
+    with_process(|process| {
+        assert_formats(
+            process,
+            "~i~w~n~~",
+            &[atom_unchecked("ignored"), atom_unchecked("a")],
+            "a\n~",
+        );
+    });
+}
+
+fn assert_badarg(process: &Process, format: &str, arguments: &[Term]) {
+    assert_eq!(
+        native(
+            process,
+            process.charlist_from_str(format).unwrap(),
+            process.list_from_slice(arguments).unwrap()
+        ),
+        Err(badarg!().into())
+    );
+}
+
+fn assert_formats(process: &Process, format: &str, arguments: &[Term], expected: &str) {
+    assert_eq!(
+        native(
+            process,
+            process.charlist_from_str(format).unwrap(),
+            process.list_from_slice(arguments).unwrap()
+        ),
+        Ok(process.charlist_from_str(expected).unwrap())
+    );
+}
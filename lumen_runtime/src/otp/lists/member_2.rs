@@ -69,7 +69,7 @@ pub fn native(element: Term, list: Term) -> exception::Result {
             for result in cons.into_iter() {
                 match result {
                     Ok(term) => {
-                        if term == element {
+                        if term.exactly_eq(&element) {
                             return Ok(true.into());
                         }
                     }
@@ -21,3 +21,14 @@ fn with_found_returns_true() {
         assert_eq!(native(element, list), Ok(true.into()));
     });
 }
+
+#[test]
+fn with_equal_after_conversion_returns_false() {
+    with_process_arc(|arc_process| {
+        let element = arc_process.float(1.0).unwrap();
+        let slice = &[arc_process.integer(1).unwrap()];
+        let list = arc_process.list_from_slice(slice).unwrap();
+
+        assert_eq!(native(element, list), Ok(false.into()));
+    });
+}
@@ -12,8 +12,8 @@ use liblumen_alloc::erts::exception::system::Alloc;
 use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
 use liblumen_alloc::erts::process::code::{self, result_from_exception};
 use liblumen_alloc::erts::process::Process;
-use liblumen_alloc::erts::term::{Atom, Term, TypedTerm};
-use liblumen_alloc::{badarg, ModuleFunctionArity};
+use liblumen_alloc::erts::term::{Atom, Term};
+use liblumen_alloc::ModuleFunctionArity;
 
 pub fn place_frame_with_arguments(
     process: &Process,
@@ -63,22 +63,11 @@ fn module_function_arity() -> Arc<ModuleFunctionArity> {
 }
 
 pub(super) fn native(process: &Process, list: Term, tail: Term) -> exception::Result {
-    match list.to_typed_term().unwrap() {
-        TypedTerm::Nil => Ok(tail),
-        TypedTerm::List(cons) => {
-            let mut reversed = tail;
+    let mut reversed = tail;
 
-            for result in cons.into_iter() {
-                match result {
-                    Ok(element) => {
-                        reversed = process.cons(element, reversed)?;
-                    }
-                    Err(_) => return Err(badarg!().into()),
-                }
-            }
-
-            Ok(reversed)
-        }
-        _ => Err(badarg!().into()),
+    for element in list.proper_list_iter()? {
+        reversed = process.cons(element, reversed)?;
     }
+
+    Ok(reversed)
 }
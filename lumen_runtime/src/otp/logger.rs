@@ -0,0 +1,230 @@
+//! Mirrors [logger](http://erlang.org/doc/man/logger.html) module
+//!
+//! Instead of going to logger's handlers, events are logged through the Rust `log` facade with the
+//! `logger` target, so that they go wherever the host application's logger sends them.  Erlang's
+//! levels map to the nearest `log` level: `emergency`, `alert`, `critical` and `error` to `Error`,
+//! `warning` to `Warn`, `notice` and `info` to `Info`, and `debug` to `Debug`.  The `mfa`, `file`
+//! and `line` of the location or metadata of an event become the module path, file and line of its
+//! record.
+//!
+//! Events that the host application's logger doesn't take, such as when it hasn't installed one,
+//! are written to the standard output of the runtime, as logger's default handler does, if they are
+//! at least as severe as `notice`, logger's default primary level.
+
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use core::convert::TryInto;
+
+use log::{Level, Record};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception::{Exception, Result};
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Boxed, Map, Term, Tuple, TypedTerm};
+
+use crate::logging;
+use crate::otp::io_lib;
+use crate::system;
+
+/// Whether events at `level` from `module` would be logged
+pub fn allow_2(level: Term, module: Term) -> Result {
+    let (level_atom, level) = self::level(level)?;
+    let _: Atom = module.try_into()?;
+
+    Ok((logging::is_enabled(level, TARGET) || is_default_allowed(level_atom)).into())
+}
+
+pub fn log_2(level: Term, string_or_report: Term) -> Result {
+    log(level, string_or_report_text(string_or_report), &[])
+}
+
+/// `log(Level, StringOrReport, Metadata)` when the last argument is a map, otherwise
+/// `log(Level, Format, Args)`
+pub fn log_3(level: Term, format_or_string_or_report: Term, args_or_metadata: Term) -> Result {
+    if args_or_metadata.is_map() {
+        log(
+            level,
+            string_or_report_text(format_or_string_or_report),
+            &[args_or_metadata],
+        )
+    } else {
+        log(
+            level,
+            io_lib::format(format_or_string_or_report, args_or_metadata),
+            &[],
+        )
+    }
+}
+
+pub fn log_4(level: Term, format: Term, args: Term, metadata: Term) -> Result {
+    log(level, io_lib::format(format, args), &[metadata])
+}
+
+/// The `?LOG_*` macros call `macro_log/3,4,5` with the `location` of the call, whose `mfa`, `file`
+/// and `line` take precedence over those in the metadata.
+pub fn macro_log_3(location: Term, level: Term, string_or_report: Term) -> Result {
+    log(level, string_or_report_text(string_or_report), &[location])
+}
+
+pub fn macro_log_4(
+    location: Term,
+    level: Term,
+    format_or_string_or_report: Term,
+    args_or_metadata: Term,
+) -> Result {
+    if args_or_metadata.is_map() {
+        log(
+            level,
+            string_or_report_text(format_or_string_or_report),
+            &[location, args_or_metadata],
+        )
+    } else {
+        log(
+            level,
+            io_lib::format(format_or_string_or_report, args_or_metadata),
+            &[location],
+        )
+    }
+}
+
+pub fn macro_log_5(
+    location: Term,
+    level: Term,
+    format: Term,
+    args: Term,
+    metadata: Term,
+) -> Result {
+    log(level, io_lib::format(format, args), &[location, metadata])
+}
+
+// Private
+
+const TARGET: &str = "logger";
+
+/// The levels at least as severe as `notice`
+const DEFAULT_ALLOWED_LEVEL_NAMES: &[&str] = &[
+    "emergency",
+    "alert",
+    "critical",
+    "error",
+    "warning",
+    "notice",
+];
+
+/// The `mfa`, `file` and `line` of the location or metadata of an event
+#[derive(Default)]
+struct Location {
+    module_path: Option<String>,
+    file: Option<String>,
+    line: Option<u32>,
+}
+
+impl Location {
+    /// The first of each key found in `maps`
+    fn try_from_maps(maps: &[Term]) -> core::result::Result<Self, Exception> {
+        let mut location: Location = Default::default();
+
+        for &map in maps {
+            let map: Boxed<Map> = map.try_into().map_err(|_| badarg!())?;
+
+            if location.module_path.is_none() {
+                location.module_path = map.get(atom_unchecked("mfa")).and_then(mfa_text);
+            }
+
+            if location.file.is_none() {
+                location.file = map
+                    .get(atom_unchecked("file"))
+                    .and_then(|file| io_lib::chardata_to_string(file, true).ok());
+            }
+
+            if location.line.is_none() {
+                location.line = map
+                    .get(atom_unchecked("line"))
+                    .and_then(|line| line.try_into().ok());
+            }
+        }
+
+        Ok(location)
+    }
+}
+
+fn is_default_allowed(level_atom: Atom) -> bool {
+    DEFAULT_ALLOWED_LEVEL_NAMES.contains(&level_atom.name())
+}
+
+/// The name and nearest `log` level of the Erlang `level`
+fn level(level: Term) -> core::result::Result<(Atom, Level), Exception> {
+    let level_atom: Atom = level.try_into()?;
+    let level = match level_atom.name() {
+        "emergency" | "alert" | "critical" | "error" => Level::Error,
+        "warning" => Level::Warn,
+        "notice" | "info" => Level::Info,
+        "debug" => Level::Debug,
+        _ => return Err(badarg!().into()),
+    };
+
+    Ok((level_atom, level))
+}
+
+fn log(level: Term, text: core::result::Result<String, Exception>, maps: &[Term]) -> Result {
+    let (level_atom, level) = self::level(level)?;
+    let text = text?;
+    let location = Location::try_from_maps(maps)?;
+
+    log_text(level_atom, level, &text, &location);
+
+    Ok(atom_unchecked("ok"))
+}
+
+/// Logs `text` at the Erlang level `level_atom`, whose nearest `log` level is `level`
+fn log_text(level_atom: Atom, level: Level, text: &str, location: &Location) {
+    if logging::is_enabled(level, TARGET) {
+        log::logger().log(
+            &Record::builder()
+                .level(level)
+                .target(TARGET)
+                .module_path(location.module_path.as_ref().map(String::as_str))
+                .file(location.file.as_ref().map(String::as_str))
+                .line(location.line)
+                .args(format_args!("{}", text))
+                .build(),
+        );
+    } else if is_default_allowed(level_atom) {
+        system::io::puts(&format!("** (logger {}) {}", level_atom.name(), text));
+    }
+}
+
+/// `{Module, Function, Arity}` as `Module:Function/Arity`
+fn mfa_text(mfa: Term) -> Option<String> {
+    let tuple: Boxed<Tuple> = mfa.try_into().ok()?;
+
+    if tuple.len() == 3 {
+        let module: Atom = tuple[0].try_into().ok()?;
+        let function: Atom = tuple[1].try_into().ok()?;
+        let arity: usize = tuple[2].try_into().ok()?;
+
+        Some(format!("{}:{}/{}", module.name(), function.name(), arity))
+    } else {
+        None
+    }
+}
+
+/// A string is logged as it is, but a report, which is a map or a list of key-value tuples, is
+/// written in Erlang syntax.
+fn string_or_report_text(string_or_report: Term) -> core::result::Result<String, Exception> {
+    match io_lib::chardata_to_string(string_or_report, true) {
+        Ok(string) => Ok(string),
+        Err(_) => match string_or_report.to_typed_term().unwrap() {
+            TypedTerm::List(_) => Ok(io_lib::write(string_or_report)),
+            TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+                TypedTerm::Map(_) => Ok(io_lib::write(string_or_report)),
+                _ => Err(badarg!().into()),
+            },
+            _ => Err(badarg!().into()),
+        },
+    }
+}
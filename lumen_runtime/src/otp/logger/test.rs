@@ -0,0 +1,176 @@
+use std::sync::{Mutex, Once};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::term::atom_unchecked;
+
+use crate::otp::logger;
+use crate::scheduler::with_process;
+
+#[test]
+fn with_format_and_args_logs_formatted_text_at_nearest_level() {
+    with_process(|process| {
+        let capturing = capturing();
+        let format = process.charlist_from_str("~w is ~w").unwrap();
+        let args = process
+            .list_from_slice(&[atom_unchecked("log_3"), atom_unchecked("formatted")])
+            .unwrap();
+
+        assert_eq!(
+            logger::log_3(atom_unchecked("warning"), format, args),
+            Ok(atom_unchecked("ok"))
+        );
+
+        let captured = capturing.find("log_3 is formatted").unwrap();
+
+        assert_eq!(captured.level, Level::Warn);
+    });
+}
+
+#[test]
+fn with_report_logs_report_in_erlang_syntax() {
+    with_process(|process| {
+        let capturing = capturing();
+        let report = process
+            .map_from_slice(&[(atom_unchecked("log_2"), atom_unchecked("report"))])
+            .unwrap();
+
+        assert_eq!(
+            logger::log_2(atom_unchecked("notice"), report),
+            Ok(atom_unchecked("ok"))
+        );
+
+        let captured = capturing.find("log_2 => report").unwrap();
+
+        assert_eq!(captured.level, Level::Info);
+    });
+}
+
+#[test]
+fn with_location_logs_module_path_and_line() {
+    with_process(|process| {
+        let capturing = capturing();
+        let mfa = process
+            .tuple_from_slice(&[
+                atom_unchecked("module"),
+                atom_unchecked("function"),
+                process.integer(1).unwrap(),
+            ])
+            .unwrap();
+        let location = process
+            .map_from_slice(&[
+                (atom_unchecked("mfa"), mfa),
+                (atom_unchecked("line"), process.integer(7).unwrap()),
+            ])
+            .unwrap();
+        let string = process.charlist_from_str("macro_log_3").unwrap();
+
+        assert_eq!(
+            logger::macro_log_3(location, atom_unchecked("critical"), string),
+            Ok(atom_unchecked("ok"))
+        );
+
+        let captured = capturing.find("macro_log_3").unwrap();
+
+        assert_eq!(captured.level, Level::Error);
+        assert_eq!(captured.module_path, Some("module:function/1".to_string()));
+        assert_eq!(captured.line, Some(7));
+    });
+}
+
+#[test]
+fn with_enabled_level_allow_returns_true() {
+    capturing();
+
+    assert_eq!(
+        logger::allow_2(atom_unchecked("debug"), atom_unchecked("module")),
+        Ok(true.into())
+    );
+}
+
+#[test]
+fn without_level_errors_badarg() {
+    with_process(|process| {
+        let string = process.charlist_from_str("string").unwrap();
+
+        assert_eq!(
+            logger::log_2(atom_unchecked("verbose"), string),
+            Err(badarg!().into())
+        );
+        assert_eq!(
+            logger::allow_2(atom_unchecked("verbose"), atom_unchecked("module")),
+            Err(badarg!().into())
+        );
+    });
+}
+
+#[test]
+fn without_string_or_report_errors_badarg() {
+    with_process(|process| {
+        assert_eq!(
+            logger::log_2(atom_unchecked("error"), process.integer(1).unwrap()),
+            Err(badarg!().into())
+        );
+    });
+}
+
+struct Captured {
+    level: Level,
+    text: String,
+    module_path: Option<String>,
+    line: Option<u32>,
+}
+
+/// Captures the records of `logger`, so that tests can find their own
+#[derive(Default)]
+struct Capturing {
+    captured: Mutex<Vec<Captured>>,
+}
+
+impl Capturing {
+    fn find(&self, text: &str) -> Option<Captured> {
+        let mut captured = self.captured.lock().unwrap();
+        let index = captured
+            .iter()
+            .position(|captured| captured.text.contains(text))?;
+
+        Some(captured.remove(index))
+    }
+}
+
+impl Log for Capturing {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.target() == "logger"
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            self.captured.lock().unwrap().push(Captured {
+                level: record.level(),
+                text: record.args().to_string(),
+                module_path: record.module_path().map(String::from),
+                line: record.line(),
+            });
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+lazy_static! {
+    static ref CAPTURING: Capturing = Default::default();
+}
+
+/// Installs the capturing logger, which only takes the `logger` target, for all tests, as a logger
+/// can only be installed once
+fn capturing() -> &'static Capturing {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        log::set_logger(&*CAPTURING).unwrap();
+        log::set_max_level(LevelFilter::Debug);
+    });
+
+    &CAPTURING
+}
@@ -0,0 +1,147 @@
+//! Mirrors [math](http://erlang.org/doc/man/math.html) module
+//!
+//! Arguments can be any number, but results are always floats.  Like BEAM, non-number arguments
+//! are a `badarg`, while arguments outside the domain of the function, such as `math:sqrt(-1)`, or
+//! results that aren't finite, such as `math:pow(10, 1000)`, are a `badarith`.
+
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use core::convert::TryInto;
+
+use liblumen_alloc::erts::exception::{Exception, Result};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::Term;
+use liblumen_alloc::{badarg, badarith};
+
+pub fn acos_1(x: Term, process: &Process) -> Result {
+    unary(x, f64::acos, process)
+}
+
+pub fn acosh_1(x: Term, process: &Process) -> Result {
+    unary(x, f64::acosh, process)
+}
+
+pub fn asin_1(x: Term, process: &Process) -> Result {
+    unary(x, f64::asin, process)
+}
+
+pub fn asinh_1(x: Term, process: &Process) -> Result {
+    unary(x, f64::asinh, process)
+}
+
+pub fn atan_1(x: Term, process: &Process) -> Result {
+    unary(x, f64::atan, process)
+}
+
+pub fn atan2_2(y: Term, x: Term, process: &Process) -> Result {
+    binary(y, x, f64::atan2, process)
+}
+
+pub fn atanh_1(x: Term, process: &Process) -> Result {
+    unary(x, f64::atanh, process)
+}
+
+pub fn ceil_1(x: Term, process: &Process) -> Result {
+    unary(x, f64::ceil, process)
+}
+
+pub fn cos_1(x: Term, process: &Process) -> Result {
+    unary(x, f64::cos, process)
+}
+
+pub fn cosh_1(x: Term, process: &Process) -> Result {
+    unary(x, f64::cosh, process)
+}
+
+pub fn exp_1(x: Term, process: &Process) -> Result {
+    unary(x, f64::exp, process)
+}
+
+pub fn floor_1(x: Term, process: &Process) -> Result {
+    unary(x, f64::floor, process)
+}
+
+/// The remainder of `x / y` with the same sign as `x`
+pub fn fmod_2(x: Term, y: Term, process: &Process) -> Result {
+    binary(x, y, |x_f64, y_f64| x_f64 % y_f64, process)
+}
+
+pub fn log_1(x: Term, process: &Process) -> Result {
+    unary(x, f64::ln, process)
+}
+
+pub fn log10_1(x: Term, process: &Process) -> Result {
+    unary(x, f64::log10, process)
+}
+
+pub fn log2_1(x: Term, process: &Process) -> Result {
+    unary(x, f64::log2, process)
+}
+
+pub fn pi_0(process: &Process) -> Result {
+    float(core::f64::consts::PI, process)
+}
+
+pub fn pow_2(x: Term, y: Term, process: &Process) -> Result {
+    binary(x, y, f64::powf, process)
+}
+
+pub fn sin_1(x: Term, process: &Process) -> Result {
+    unary(x, f64::sin, process)
+}
+
+pub fn sinh_1(x: Term, process: &Process) -> Result {
+    unary(x, f64::sinh, process)
+}
+
+pub fn sqrt_1(x: Term, process: &Process) -> Result {
+    unary(x, f64::sqrt, process)
+}
+
+pub fn tan_1(x: Term, process: &Process) -> Result {
+    unary(x, f64::tan, process)
+}
+
+pub fn tanh_1(x: Term, process: &Process) -> Result {
+    unary(x, f64::tanh, process)
+}
+
+// Private
+
+fn binary<F>(x: Term, y: Term, f: F, process: &Process) -> Result
+where
+    F: FnOnce(f64, f64) -> f64,
+{
+    let x_f64 = number_to_f64(x)?;
+    let y_f64 = number_to_f64(y)?;
+
+    float(f(x_f64, y_f64), process)
+}
+
+/// NaN and infinities can't be Erlang floats, so they mean the arguments were out of the domain of
+/// the function or the result overflowed.
+fn float(f: f64, process: &Process) -> Result {
+    if f.is_finite() {
+        Ok(process.float(f)?)
+    } else {
+        Err(badarith!().into())
+    }
+}
+
+fn number_to_f64(number: Term) -> core::result::Result<f64, Exception> {
+    number.try_into().map_err(|_| badarg!().into())
+}
+
+fn unary<F>(x: Term, f: F, process: &Process) -> Result
+where
+    F: FnOnce(f64) -> f64,
+{
+    let x_f64 = number_to_f64(x)?;
+
+    float(f(x_f64), process)
+}
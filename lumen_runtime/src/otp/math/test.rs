@@ -0,0 +1,99 @@
+use proptest::prop_assert_eq;
+use proptest::test_runner::{Config, TestRunner};
+
+use liblumen_alloc::{badarg, badarith};
+
+use crate::otp::math;
+use crate::scheduler::{with_process, with_process_arc};
+use crate::test::strategy;
+
+#[test]
+fn without_number_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(&strategy::term::is_not_number(arc_process.clone()), |x| {
+                prop_assert_eq!(math::sin_1(x, &arc_process), Err(badarg!().into()));
+
+                Ok(())
+            })
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_integer_returns_float() {
+    with_process(|process| {
+        let x = process.integer(4).unwrap();
+
+        assert_eq!(math::sqrt_1(x, &process), Ok(process.float(2.0).unwrap()));
+    });
+}
+
+#[test]
+fn with_float_returns_float() {
+    with_process(|process| {
+        let x = process.float(0.0).unwrap();
+
+        assert_eq!(math::cos_1(x, &process), Ok(process.float(1.0).unwrap()));
+    });
+}
+
+#[test]
+fn with_argument_outside_domain_errors_badarith() {
+    with_process(|process| {
+        let negative = process.integer(-1).unwrap();
+
+        assert_eq!(math::sqrt_1(negative, &process), Err(badarith!().into()));
+        assert_eq!(
+            math::acos_1(process.integer(2).unwrap(), &process),
+            Err(badarith!().into())
+        );
+        assert_eq!(
+            math::log_1(process.integer(0).unwrap(), &process),
+            Err(badarith!().into())
+        );
+    });
+}
+
+#[test]
+fn with_overflowing_result_errors_badarith() {
+    with_process(|process| {
+        let x = process.integer(10).unwrap();
+        let y = process.integer(1000).unwrap();
+
+        assert_eq!(math::pow_2(x, y, &process), Err(badarith!().into()));
+    });
+}
+
+#[test]
+fn with_zero_divisor_fmod_errors_badarith() {
+    with_process(|process| {
+        let x = process.integer(1).unwrap();
+        let y = process.integer(0).unwrap();
+
+        assert_eq!(math::fmod_2(x, y, &process), Err(badarith!().into()));
+    });
+}
+
+#[test]
+fn fmod_has_sign_of_dividend() {
+    with_process(|process| {
+        let x = process.float(-5.5).unwrap();
+        let y = process.integer(2).unwrap();
+
+        assert_eq!(
+            math::fmod_2(x, y, &process),
+            Ok(process.float(-1.5).unwrap())
+        );
+    });
+}
+
+#[test]
+fn pi_returns_pi() {
+    with_process(|process| {
+        assert_eq!(
+            math::pi_0(&process),
+            Ok(process.float(std::f64::consts::PI).unwrap())
+        );
+    });
+}
@@ -0,0 +1,59 @@
+//! Mirrors [proplists](http://erlang.org/doc/man/proplists.html) module
+//!
+//! Options are passed as property lists throughout OTP, so the lookups are native instead of
+//! interpreted.  As in `proplists`, a property is either an atom, which is short for `{Atom,
+//! true}`, or a tuple whose first element is its key.  Only 2-tuples have a value, so other tuples
+//! with the key are found by `lookup/2` and `is_defined/2`, but have no value for `get_value/2,3`
+//! and `get_all_values/2`.  Elements that are neither are skipped.  An improper list is `badarg`
+//! if its tail is reached.
+
+pub mod get_all_values_2;
+pub mod get_value_2;
+pub mod get_value_3;
+pub mod is_defined_2;
+pub mod lookup_2;
+
+use liblumen_alloc::erts::exception::Exception;
+use liblumen_alloc::erts::term::{Atom, Term, TypedTerm};
+
+fn module() -> Atom {
+    Atom::try_from_str("proplists").unwrap()
+}
+
+/// Whether `property` is `key` itself, as an atom, or a tuple whose first element is `key`
+fn has_key(property: Term, key: Term) -> bool {
+    match property.to_typed_term().unwrap() {
+        TypedTerm::Atom(_) => property.exactly_eq(&key),
+        TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+            TypedTerm::Tuple(tuple) => tuple.len() >= 1 && tuple[0].exactly_eq(&key),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// The first property in `list` with `key`
+fn lookup(key: Term, list: Term) -> Result<Option<Term>, Exception> {
+    for result in list.list_iter()? {
+        let property = result?;
+
+        if has_key(property, key) {
+            return Ok(Some(property));
+        }
+    }
+
+    Ok(None)
+}
+
+/// The value of `property`, which has a key: `true` for an atom and the second element of a
+/// 2-tuple.  Other tuples have no value.
+fn value(property: Term) -> Option<Term> {
+    match property.to_typed_term().unwrap() {
+        TypedTerm::Atom(_) => Some(true.into()),
+        TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+            TypedTerm::Tuple(tuple) if tuple.len() == 2 => Some(tuple[1]),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,82 @@
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::code::{self, result_from_exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{Atom, Term};
+use liblumen_alloc::ModuleFunctionArity;
+
+pub fn place_frame_with_arguments(
+    process: &Process,
+    placement: Placement,
+    key: Term,
+    list: Term,
+) -> Result<(), Alloc> {
+    process.stack_push(list)?;
+    process.stack_push(key)?;
+    process.place_frame(frame(), placement);
+
+    Ok(())
+}
+
+// Private
+
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.reduce();
+
+    let key = arc_process.stack_pop().unwrap();
+    let list = arc_process.stack_pop().unwrap();
+
+    match native(arc_process, key, list) {
+        Ok(values) => {
+            arc_process.return_from_call(values)?;
+
+            Process::call_code(arc_process)
+        }
+        Err(exception) => result_from_exception(arc_process, exception),
+    }
+}
+
+fn frame() -> Frame {
+    Frame::new(module_function_arity(), code)
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("get_all_values").unwrap()
+}
+
+fn module_function_arity() -> Arc<ModuleFunctionArity> {
+    Arc::new(ModuleFunctionArity {
+        module: super::module(),
+        function: function(),
+        arity: 2,
+    })
+}
+
+/// The values of all the properties in `list` with `key`, in order
+pub fn native(process: &Process, key: Term, list: Term) -> exception::Result {
+    let mut value_vec: Vec<Term> = Vec::new();
+
+    for result in list.list_iter()? {
+        let property = result?;
+
+        if super::has_key(property, key) {
+            if let Some(value) = super::value(property) {
+                value_vec.push(value);
+            }
+        }
+    }
+
+    process
+        .list_from_slice(&value_vec)
+        .map_err(|error| error.into())
+}
@@ -0,0 +1,62 @@
+use proptest::prop_assert_eq;
+use proptest::test_runner::{Config, TestRunner};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::term::{atom_unchecked, Term};
+
+use crate::otp::proplists::get_all_values_2::native;
+use crate::scheduler::with_process_arc;
+use crate::test::strategy;
+
+#[test]
+fn without_list_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(&strategy::term::is_not_list(arc_process.clone()), |list| {
+                prop_assert_eq!(
+                    native(&arc_process, atom_unchecked("key"), list),
+                    Err(badarg!().into())
+                );
+
+                Ok(())
+            })
+            .unwrap();
+    });
+}
+
+#[test]
+fn without_key_returns_empty_list() {
+    with_process_arc(|arc_process| {
+        let list = arc_process
+            .list_from_slice(&[atom_unchecked("other_key")])
+            .unwrap();
+
+        assert_eq!(
+            native(&arc_process, atom_unchecked("key"), list),
+            Ok(Term::NIL)
+        );
+    });
+}
+
+#[test]
+fn with_key_returns_values_in_order() {
+    with_process_arc(|arc_process| {
+        let key = atom_unchecked("key");
+        let first_value = arc_process.integer(1).unwrap();
+        let first = arc_process.tuple_from_slice(&[key, first_value]).unwrap();
+        let without_value = arc_process.tuple_from_slice(&[key]).unwrap();
+        let other = arc_process
+            .tuple_from_slice(&[atom_unchecked("other_key"), first_value])
+            .unwrap();
+        let list = arc_process
+            .list_from_slice(&[first, without_value, other, key])
+            .unwrap();
+
+        assert_eq!(
+            native(&arc_process, key, list),
+            Ok(arc_process
+                .list_from_slice(&[first_value, true.into()])
+                .unwrap())
+        );
+    });
+}
@@ -0,0 +1,94 @@
+use proptest::prop_assert_eq;
+use proptest::test_runner::{Config, TestRunner};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::term::atom_unchecked;
+
+use crate::otp::proplists::get_value_2::native;
+use crate::scheduler::with_process_arc;
+use crate::test::strategy;
+
+#[test]
+fn without_list_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(&strategy::term::is_not_list(arc_process.clone()), |list| {
+                prop_assert_eq!(native(atom_unchecked("key"), list), Err(badarg!().into()));
+
+                Ok(())
+            })
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_atom_property_returns_true() {
+    with_process_arc(|arc_process| {
+        let key = atom_unchecked("key");
+        let list = arc_process.list_from_slice(&[key]).unwrap();
+
+        assert_eq!(native(key, list), Ok(true.into()));
+    });
+}
+
+#[test]
+fn with_2_tuple_property_returns_second_element() {
+    with_process_arc(|arc_process| {
+        let key = atom_unchecked("key");
+        let value = arc_process.integer(1).unwrap();
+        let property = arc_process.tuple_from_slice(&[key, value]).unwrap();
+        let list = arc_process.list_from_slice(&[property]).unwrap();
+
+        assert_eq!(native(key, list), Ok(value));
+    });
+}
+
+#[test]
+fn with_first_property_without_value_returns_undefined() {
+    with_process_arc(|arc_process| {
+        let key = atom_unchecked("key");
+        let three_tuple = arc_process
+            .tuple_from_slice(&[key, atom_unchecked("one"), atom_unchecked("two")])
+            .unwrap();
+        let list = arc_process.list_from_slice(&[three_tuple, key]).unwrap();
+
+        assert_eq!(native(key, list), Ok(atom_unchecked("undefined")));
+    });
+}
+
+#[test]
+fn without_key_returns_undefined() {
+    with_process_arc(|arc_process| {
+        let list = arc_process
+            .list_from_slice(&[atom_unchecked("other_key"), arc_process.integer(0).unwrap()])
+            .unwrap();
+
+        assert_eq!(
+            native(atom_unchecked("key"), list),
+            Ok(atom_unchecked("undefined"))
+        );
+    });
+}
+
+#[test]
+fn with_improper_list_with_key_before_tail_returns_value() {
+    with_process_arc(|arc_process| {
+        let key = atom_unchecked("key");
+        let list = arc_process
+            .improper_list_from_slice(&[key], atom_unchecked("tail"))
+            .unwrap();
+
+        assert_eq!(native(key, list), Ok(true.into()));
+    });
+}
+
+#[test]
+fn with_improper_list_without_key_errors_badarg() {
+    with_process_arc(|arc_process| {
+        let list = arc_process
+            .improper_list_from_slice(&[atom_unchecked("other_key")], atom_unchecked("tail"))
+            .unwrap();
+
+        assert_eq!(native(atom_unchecked("key"), list), Err(badarg!().into()));
+    });
+}
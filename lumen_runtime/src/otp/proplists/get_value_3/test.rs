@@ -0,0 +1,56 @@
+use proptest::prop_assert_eq;
+use proptest::test_runner::{Config, TestRunner};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::term::atom_unchecked;
+
+use crate::otp::proplists::get_value_3::native;
+use crate::scheduler::with_process_arc;
+use crate::test::strategy;
+
+#[test]
+fn without_list_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(&strategy::term::is_not_list(arc_process.clone()), |list| {
+                prop_assert_eq!(
+                    native(atom_unchecked("key"), list, atom_unchecked("default")),
+                    Err(badarg!().into())
+                );
+
+                Ok(())
+            })
+            .unwrap();
+    });
+}
+
+#[test]
+fn without_key_returns_default() {
+    with_process_arc(|arc_process| {
+        let default = arc_process.integer(0).unwrap();
+        let list = arc_process
+            .list_from_slice(&[atom_unchecked("other_key")])
+            .unwrap();
+
+        assert_eq!(native(atom_unchecked("key"), list, default), Ok(default));
+    });
+}
+
+#[test]
+fn with_key_returns_value_of_first_property() {
+    with_process_arc(|arc_process| {
+        let key = atom_unchecked("key");
+        let first = arc_process
+            .tuple_from_slice(&[key, arc_process.integer(1).unwrap()])
+            .unwrap();
+        let second = arc_process
+            .tuple_from_slice(&[key, arc_process.integer(2).unwrap()])
+            .unwrap();
+        let list = arc_process.list_from_slice(&[first, second]).unwrap();
+
+        assert_eq!(
+            native(key, list, atom_unchecked("default")),
+            Ok(arc_process.integer(1).unwrap())
+        );
+    });
+}
@@ -0,0 +1,47 @@
+use proptest::prop_assert_eq;
+use proptest::test_runner::{Config, TestRunner};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::term::atom_unchecked;
+
+use crate::otp::proplists::is_defined_2::native;
+use crate::scheduler::with_process_arc;
+use crate::test::strategy;
+
+#[test]
+fn without_list_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(&strategy::term::is_not_list(arc_process.clone()), |list| {
+                prop_assert_eq!(native(atom_unchecked("key"), list), Err(badarg!().into()));
+
+                Ok(())
+            })
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_tuple_with_key_returns_true() {
+    with_process_arc(|arc_process| {
+        let key = atom_unchecked("key");
+        let three_tuple = arc_process
+            .tuple_from_slice(&[key, atom_unchecked("one"), atom_unchecked("two")])
+            .unwrap();
+        let list = arc_process.list_from_slice(&[three_tuple]).unwrap();
+
+        assert_eq!(native(key, list), Ok(true.into()));
+    });
+}
+
+#[test]
+fn without_key_returns_false() {
+    with_process_arc(|arc_process| {
+        let key = atom_unchecked("key");
+        let list = arc_process
+            .list_from_slice(&[atom_unchecked("other_key"), arc_process.integer(0).unwrap()])
+            .unwrap();
+
+        assert_eq!(native(key, list), Ok(false.into()));
+    });
+}
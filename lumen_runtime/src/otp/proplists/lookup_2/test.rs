@@ -0,0 +1,65 @@
+use proptest::prop_assert_eq;
+use proptest::test_runner::{Config, TestRunner};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::term::atom_unchecked;
+
+use crate::otp::proplists::lookup_2::native;
+use crate::scheduler::with_process_arc;
+use crate::test::strategy;
+
+#[test]
+fn without_list_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(&strategy::term::is_not_list(arc_process.clone()), |list| {
+                prop_assert_eq!(
+                    native(&arc_process, atom_unchecked("key"), list),
+                    Err(badarg!().into())
+                );
+
+                Ok(())
+            })
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_atom_property_returns_tuple_with_true() {
+    with_process_arc(|arc_process| {
+        let key = atom_unchecked("key");
+        let list = arc_process.list_from_slice(&[key]).unwrap();
+
+        assert_eq!(
+            native(&arc_process, key, list),
+            Ok(arc_process.tuple_from_slice(&[key, true.into()]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_tuple_property_returns_tuple() {
+    with_process_arc(|arc_process| {
+        let key = atom_unchecked("key");
+        let property = arc_process
+            .tuple_from_slice(&[key, atom_unchecked("one"), atom_unchecked("two")])
+            .unwrap();
+        let list = arc_process.list_from_slice(&[property]).unwrap();
+
+        assert_eq!(native(&arc_process, key, list), Ok(property));
+    });
+}
+
+#[test]
+fn without_key_returns_none() {
+    with_process_arc(|arc_process| {
+        let list = arc_process
+            .list_from_slice(&[atom_unchecked("other_key")])
+            .unwrap();
+
+        assert_eq!(
+            native(&arc_process, atom_unchecked("key"), list),
+            Ok(atom_unchecked("none"))
+        );
+    });
+}
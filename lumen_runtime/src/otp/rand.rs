@@ -0,0 +1,428 @@
+//! Mirrors [rand](http://erlang.org/doc/man/rand.html) module
+//!
+//! Like OTP, the state of a process's generator is kept in its process dictionary under
+//! `rand_seed`, so once a process is seeded the rest of its sequence is reproducible, and is the
+//! same as OTP's for the same seed.  The state, as returned by `seed/1,2`, is
+//! `{#{type => Algorithm, bits => 58}, [S0 | S1]}`, where `Algorithm` is `exsss` (the default) or
+//! `exrop` and `S0` and `S1` are the 58-bit words of the algorithm's state.  OTP's handler map
+//! also holds the funs of the algorithm, which are native here, so only its `type`, `bits` and
+//! `weak_low_bits` are kept.  `export_seed/0` returns `{Algorithm, [S0 | S1]}`, and `seed/1` takes
+//! either back.
+
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use core::convert::TryInto;
+use core::f64::consts::PI;
+
+use num_bigint::{BigInt, BigUint, Sign, ToBigInt};
+use num_traits::{One, ToPrimitive, Zero};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception::{Exception, Result};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Boxed, Cons, Map, Term, Tuple, TypedTerm};
+
+/// `{Algorithm, [S0 | S1]}` of the state in the process dictionary, or `undefined` if the process
+/// has not been seeded
+pub fn export_seed_0(process: &Process) -> Result {
+    let term = process.get(key());
+
+    if term == Term::NIL {
+        Ok(atom_unchecked("undefined"))
+    } else {
+        State::try_from_term(term)?.to_exported_term(process)
+    }
+}
+
+/// A standard normal deviate
+pub fn normal_0(process: &Process) -> Result {
+    let mut state = state(process)?;
+    let normal = state.normal();
+
+    put(state, process)?;
+
+    Ok(process.float(normal)?)
+}
+
+pub fn normal_2(mean: Term, variance: Term, process: &Process) -> Result {
+    let mean_f64: f64 = mean.try_into()?;
+    let variance_f64: f64 = variance.try_into()?;
+
+    if 0.0 <= variance_f64 {
+        let mut state = state(process)?;
+        let normal = mean_f64 + variance_f64.sqrt() * state.normal();
+
+        put(state, process)?;
+
+        Ok(process.float(normal)?)
+    } else {
+        Err(badarg!().into())
+    }
+}
+
+/// Seeds `algorithm` from entropy or restores a state, either as `seed/1,2` or as `export_seed/0`
+/// returned it
+pub fn seed_1(algorithm_or_state: Term, process: &Process) -> Result {
+    let state = match algorithm_or_state.to_typed_term().unwrap() {
+        TypedTerm::Atom(atom) => State::from_entropy(algorithm(atom)?),
+        _ => State::try_from_term(algorithm_or_state)?,
+    };
+
+    put(state, process)
+}
+
+/// Seeds `algorithm` from `{A1, A2, A3}`, so that the same seed always gives the same sequence
+pub fn seed_2(algorithm: Term, seed: Term, process: &Process) -> Result {
+    let algorithm_atom: Atom = algorithm.try_into()?;
+    let algorithm = self::algorithm(algorithm_atom)?;
+    let seed_tuple: Boxed<Tuple> = seed.try_into()?;
+
+    if seed_tuple.len() == 3 {
+        let a1 = integer_to_u64_bits(seed_tuple[0])?;
+        let a2 = integer_to_u64_bits(seed_tuple[1])?;
+        let a3 = integer_to_u64_bits(seed_tuple[2])?;
+
+        put(State::from_seed(algorithm, a1, a2, a3), process)
+    } else {
+        Err(badarg!().into())
+    }
+}
+
+/// A float uniformly distributed in `0.0 =< X < 1.0`
+pub fn uniform_0(process: &Process) -> Result {
+    let mut state = state(process)?;
+    let uniform = state.uniform();
+
+    put(state, process)?;
+
+    Ok(process.float(uniform)?)
+}
+
+/// An integer uniformly distributed in `1 =< X =< N`, where `N` may be a bignum
+pub fn uniform_1(n: Term, process: &Process) -> Result {
+    let range: BigInt = n.try_into()?;
+
+    match range.to_biguint() {
+        Some(ref range) if !range.is_zero() => {
+            let mut state = state(process)?;
+            let uniform = state.uniform_range(range);
+
+            put(state, process)?;
+
+            Ok(process.integer((uniform + 1u32).to_bigint().unwrap())?)
+        }
+        _ => Err(badarg!().into()),
+    }
+}
+
+// Private
+
+const BITS: u32 = 58;
+const MASK: u64 = (1 << BITS) - 1;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Algorithm {
+    /// Xorshift116\*\*: xorshift116+ with a StarStar scrambler
+    Exsss,
+    /// Xoroshiro116+
+    Exrop,
+}
+
+impl Algorithm {
+    fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Exsss => "exsss",
+            Algorithm::Exrop => "exrop",
+        }
+    }
+
+    /// The low bits of the output that aren't as random as the rest, which are dropped when
+    /// outputs are put together for a range wider than one
+    fn weak_low_bits(&self) -> u32 {
+        match self {
+            Algorithm::Exsss => 0,
+            Algorithm::Exrop => 1,
+        }
+    }
+
+    /// The handler map of OTP's state, without its funs
+    fn to_term(&self, process: &Process) -> Result {
+        let mut entries = vec![
+            (atom_unchecked("type"), atom_unchecked(self.name())),
+            (atom_unchecked("bits"), process.integer(BITS as u8)?),
+        ];
+
+        if 0 < self.weak_low_bits() {
+            entries.push((
+                atom_unchecked("weak_low_bits"),
+                process.integer(self.weak_low_bits() as u8)?,
+            ));
+        }
+
+        Ok(process.map_from_slice(&entries)?)
+    }
+}
+
+/// `[S0 | S1]` of the algorithm, as in OTP's `rand`
+struct State {
+    algorithm: Algorithm,
+    s0: u64,
+    s1: u64,
+}
+
+impl State {
+    fn from_entropy(algorithm: Algorithm) -> Self {
+        Self::from_seed(
+            algorithm,
+            ::rand::random(),
+            ::rand::random(),
+            ::rand::random(),
+        )
+    }
+
+    fn from_seed(algorithm: Algorithm, a1: u64, a2: u64, a3: u64) -> Self {
+        let w1 = a1.wrapping_mul(4294967197).wrapping_add(1) & MASK;
+        let w2 = a2.wrapping_mul(4294967231).wrapping_add(1) & MASK;
+        let w3 = a3.wrapping_mul(4294967279).wrapping_add(1) & MASK;
+
+        let (s0, s1) = match algorithm {
+            Algorithm::Exsss => {
+                let (_, r1) = exsp_next(w1, w2);
+
+                exsp_next(w3, r1)
+            }
+            Algorithm::Exrop => {
+                let (_, r1) = exrop_next_state(w1, w2);
+
+                exrop_next_state(w3, r1)
+            }
+        };
+
+        State { algorithm, s0, s1 }
+    }
+
+    /// From `{#{type := Algorithm}, [S0 | S1]}` or `{Algorithm, [S0 | S1]}`
+    fn try_from_term(term: Term) -> core::result::Result<Self, Exception> {
+        let tuple: Boxed<Tuple> = term.try_into()?;
+
+        if tuple.len() == 2 {
+            let result_handler: core::result::Result<Boxed<Map>, _> = tuple[0].try_into();
+            let algorithm_atom: Atom = match result_handler {
+                Ok(handler) => handler
+                    .get(atom_unchecked("type"))
+                    .ok_or_else(|| badarg!())?
+                    .try_into()?,
+                Err(_) => tuple[0].try_into()?,
+            };
+            let algorithm = algorithm(algorithm_atom)?;
+            let words: Boxed<Cons> = tuple[1].try_into()?;
+            let s0: u64 = words.head.try_into()?;
+            let s1: u64 = words.tail.try_into()?;
+
+            if s0 <= MASK && s1 <= MASK {
+                Ok(State { algorithm, s0, s1 })
+            } else {
+                Err(badarg!().into())
+            }
+        } else {
+            Err(badarg!().into())
+        }
+    }
+
+    /// The next 58-bit output
+    fn next(&mut self) -> u64 {
+        match self.algorithm {
+            Algorithm::Exsss => {
+                let output = scramble_star_star(self.s1);
+                let (s0, s1) = exsp_next(self.s0, self.s1);
+                self.s0 = s0;
+                self.s1 = s1;
+
+                output
+            }
+            Algorithm::Exrop => {
+                let output = (self.s0 + self.s1) & MASK;
+                let (s0, s1) = exrop_next_state(self.s0, self.s1);
+                self.s0 = s0;
+                self.s1 = s1;
+
+                output
+            }
+        }
+    }
+
+    /// Box-Muller transform of two uniforms, the first shifted to `0.0 < X =< 1.0` so that its
+    /// logarithm is finite.
+    fn normal(&mut self) -> f64 {
+        let u1 = 1.0 - self.uniform();
+        let u2 = self.uniform();
+
+        (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+    }
+
+    /// The top 53 bits of the output fill the mantissa of a float in `0.0 =< X < 1.0`
+    fn uniform(&mut self) -> f64 {
+        ((self.next() >> (BITS - 53)) as f64) * (2.0_f64).powi(-53)
+    }
+
+    /// An integer in `0 =< X < range`.  Outputs are rejected and redrawn when keeping them would
+    /// bias the result towards lower values.
+    fn uniform_range(&mut self, range: &BigUint) -> BigUint {
+        let v = self.next();
+
+        match range.to_u64() {
+            Some(range) if range <= (1 << BITS) => {
+                let limit = (1 << BITS) - range;
+                let mut v = v;
+
+                loop {
+                    let i = v % range;
+
+                    if v - i <= limit {
+                        break BigUint::from(i);
+                    }
+
+                    v = self.next();
+                }
+            }
+            _ => self.uniform_wide_range(range, v),
+        }
+    }
+
+    /// An integer in `0 =< X < range` for a range wider than one output, starting with the output
+    /// `v`.  Outputs, without their weak low bits, are shifted in until there are as many bits as
+    /// the range has, or two more if it isn't a power of two, so that fewer values are rejected.
+    fn uniform_wide_range(&mut self, range: &BigUint, mut v: u64) -> BigUint {
+        let weak_low_bits = self.algorithm.weak_low_bits();
+        let shift = (BITS - weak_low_bits) as usize;
+        let range_minus_one = range.clone() - 1u32;
+        let is_power_of_two = (range & &range_minus_one).is_zero();
+        let value_bits = if is_power_of_two {
+            range_minus_one.bits()
+        } else {
+            range.bits() + 2
+        };
+
+        loop {
+            let mut value = BigUint::from(v);
+            let mut bits = BITS as usize;
+
+            while bits < value_bits {
+                value = (value << shift) | BigUint::from(self.next() >> weak_low_bits);
+                bits += shift;
+            }
+
+            if is_power_of_two {
+                break value & range_minus_one;
+            }
+
+            let i = &value % range;
+
+            if &value - &i <= (BigUint::one() << bits) - range {
+                break i;
+            }
+
+            v = self.next();
+        }
+    }
+
+    /// `{#{type => Algorithm, bits => 58}, [S0 | S1]}`
+    fn to_term(&self, process: &Process) -> Result {
+        let handler = self.algorithm.to_term(process)?;
+
+        Ok(process.tuple_from_slice(&[handler, self.words(process)?])?)
+    }
+
+    /// `{Algorithm, [S0 | S1]}`
+    fn to_exported_term(&self, process: &Process) -> Result {
+        Ok(process
+            .tuple_from_slice(&[atom_unchecked(self.algorithm.name()), self.words(process)?])?)
+    }
+
+    fn words(&self, process: &Process) -> Result {
+        Ok(process.cons(process.integer(self.s0)?, process.integer(self.s1)?)?)
+    }
+}
+
+fn algorithm(atom: Atom) -> core::result::Result<Algorithm, Exception> {
+    match atom.name() {
+        "exsss" => Ok(Algorithm::Exsss),
+        "exrop" => Ok(Algorithm::Exrop),
+        _ => Err(badarg!().into()),
+    }
+}
+
+fn bsl(x: u64, n: u32) -> u64 {
+    (x << n) & MASK
+}
+
+fn exrop_next_state(s0: u64, s1: u64) -> (u64, u64) {
+    let s1_a = s1 ^ s0;
+
+    (rotl(s0, 24) ^ s1_a ^ bsl(s1_a, 2), rotl(s1_a, 35))
+}
+
+/// The xorshift116+ state transition shared by `exsss`
+fn exsp_next(s1: u64, s0: u64) -> (u64, u64) {
+    let s0 = s0 & MASK;
+    let s1_temp = s1 ^ bsl(s1, 24);
+
+    (s0, s0 ^ s1_temp ^ (s0 >> 11) ^ (s1_temp >> 41))
+}
+
+/// Low 64 bits, in two's complement, of any integer
+fn integer_to_u64_bits(integer: Term) -> core::result::Result<u64, Exception> {
+    let big_int: BigInt = integer.try_into()?;
+    let fill = if big_int.sign() == Sign::Minus {
+        0xFF
+    } else {
+        0x00
+    };
+    let mut bytes = [fill; 8];
+
+    for (byte, big_int_byte) in bytes.iter_mut().zip(big_int.to_signed_bytes_le()) {
+        *byte = big_int_byte;
+    }
+
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn key() -> Term {
+    atom_unchecked("rand_seed")
+}
+
+/// Stores `state` in the process dictionary, returning it as a term
+fn put(state: State, process: &Process) -> Result {
+    let term = state.to_term(process)?;
+    process.put(key(), term)?;
+
+    Ok(term)
+}
+
+fn rotl(x: u64, n: u32) -> u64 {
+    bsl(x, n) | (x >> (BITS - n))
+}
+
+fn scramble_star_star(s: u64) -> u64 {
+    let v_b = s.wrapping_mul(5) & MASK;
+    let v_a = rotl(v_b, 7);
+
+    v_a.wrapping_mul(9) & MASK
+}
+
+/// The state in the process dictionary, seeding the default algorithm from entropy if the process
+/// has not been seeded yet.
+fn state(process: &Process) -> core::result::Result<State, Exception> {
+    let term = process.get(key());
+
+    if term == Term::NIL {
+        Ok(State::from_entropy(Algorithm::Exsss))
+    } else {
+        State::try_from_term(term)
+    }
+}
@@ -0,0 +1,292 @@
+use core::convert::TryInto;
+
+use num_bigint::BigInt;
+
+use proptest::prop_assert_eq;
+use proptest::test_runner::{Config, TestRunner};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Boxed, Map, Term, Tuple};
+
+use crate::otp::rand;
+use crate::scheduler::{with_process, with_process_arc};
+use crate::test::strategy;
+
+#[test]
+fn without_seed_export_seed_returns_undefined() {
+    with_process(|process| {
+        assert_eq!(
+            rand::export_seed_0(&process),
+            Ok(atom_unchecked("undefined"))
+        );
+    });
+}
+
+#[test]
+fn without_seed_uniform_seeds_exsss() {
+    with_process(|process| {
+        assert!(rand::uniform_0(&process).is_ok());
+
+        let state: Boxed<Tuple> = rand::export_seed_0(&process).unwrap().try_into().unwrap();
+
+        assert_eq!(state[0], atom_unchecked("exsss"));
+    });
+}
+
+#[test]
+fn with_same_seed_returns_same_sequence() {
+    with_process(|process| {
+        let seed = process
+            .tuple_from_slice(&[
+                process.integer(1).unwrap(),
+                process.integer(2).unwrap(),
+                process.integer(3).unwrap(),
+            ])
+            .unwrap();
+
+        for algorithm in &["exsss", "exrop"] {
+            rand::seed_2(atom_unchecked(algorithm), seed, &process).unwrap();
+
+            let first: Vec<_> = (0..4).map(|_| rand::uniform_0(&process).unwrap()).collect();
+
+            rand::seed_2(atom_unchecked(algorithm), seed, &process).unwrap();
+
+            let second: Vec<_> = (0..4).map(|_| rand::uniform_0(&process).unwrap()).collect();
+
+            assert_eq!(first, second);
+        }
+    });
+}
+
+#[test]
+fn seed_returns_state_with_handler_map_and_export_seed_returns_algorithm_and_words() {
+    with_process(|process| {
+        let state: Boxed<Tuple> = rand::seed_2(atom_unchecked("exrop"), seed(&process), &process)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let handler: Boxed<Map> = state[0].try_into().unwrap();
+
+        assert_eq!(
+            handler.get(atom_unchecked("type")),
+            Some(atom_unchecked("exrop"))
+        );
+        assert_eq!(
+            handler.get(atom_unchecked("bits")),
+            Some(process.integer(58).unwrap())
+        );
+
+        let words = process
+            .cons(
+                process.integer(287619632154817547_u64).unwrap(),
+                process.integer(285170057355761663_u64).unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(state[1], words);
+        assert_eq!(
+            rand::export_seed_0(&process),
+            Ok(process
+                .tuple_from_slice(&[atom_unchecked("exrop"), words])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_exported_seed_restores_sequence() {
+    with_process(|process| {
+        let state = rand::seed_1(atom_unchecked("exrop"), &process).unwrap();
+        let first = rand::uniform_1(process.integer(1_000_000).unwrap(), &process).unwrap();
+
+        assert_eq!(rand::seed_1(state, &process), Ok(state));
+        assert_eq!(
+            rand::uniform_1(process.integer(1_000_000).unwrap(), &process),
+            Ok(first)
+        );
+    });
+}
+
+#[test]
+fn with_export_seed_result_restores_sequence() {
+    with_process(|process| {
+        rand::seed_1(atom_unchecked("exsss"), &process).unwrap();
+        let exported = rand::export_seed_0(&process).unwrap();
+        let first = rand::uniform_0(&process).unwrap();
+
+        rand::seed_1(exported, &process).unwrap();
+
+        assert_eq!(rand::uniform_0(&process), Ok(first));
+    });
+}
+
+// The known answers follow OTP's `rand`, whose `uniform(1 bsl 58)` is an output of the algorithm
+// plus one
+#[test]
+fn with_seed_exsss_uniform_returns_known_answers() {
+    with_process(|process| {
+        rand::seed_2(atom_unchecked("exsss"), seed(&process), &process).unwrap();
+
+        assert_eq!(
+            uniform_outputs(&process),
+            vec![
+                223234820387491073,
+                12563311869737768,
+                243129429190329693,
+                114744659691699070,
+                93226356733660674
+            ]
+        );
+
+        rand::seed_2(atom_unchecked("exsss"), seed(&process), &process).unwrap();
+
+        assert_eq!(
+            uniform_floats(&process),
+            vec![0.7745013671632934, 0.04358774407290433, 0.8435246570346113]
+        );
+    });
+}
+
+#[test]
+fn with_seed_exrop_uniform_returns_known_answers() {
+    with_process(|process| {
+        rand::seed_2(atom_unchecked("exrop"), seed(&process), &process).unwrap();
+
+        assert_eq!(
+            uniform_outputs(&process),
+            vec![
+                284559313358867467,
+                163044605219412271,
+                90714316089417330,
+                24856131281372987,
+                178688953786847508
+            ]
+        );
+
+        rand::seed_2(atom_unchecked("exrop"), seed(&process), &process).unwrap();
+
+        assert_eq!(
+            uniform_floats(&process),
+            vec![0.9872634423829361, 0.5656746086109702, 0.3147285074549856]
+        );
+    });
+}
+
+#[test]
+fn with_bignum_uniform_returns_integer_between_one_and_n() {
+    with_process(|process| {
+        for n in &[
+            (BigInt::from(1) << 100),
+            (BigInt::from(1) << 100) + 1,
+            (BigInt::from(3) << 64) + 7,
+        ] {
+            let n_term = process.integer(n.clone()).unwrap();
+
+            for _ in 0..100 {
+                let uniform: BigInt = rand::uniform_1(n_term, &process)
+                    .unwrap()
+                    .try_into()
+                    .unwrap();
+
+                assert!(BigInt::from(1) <= uniform && &uniform <= n);
+            }
+        }
+    });
+}
+
+#[test]
+fn with_unknown_algorithm_seed_errors_badarg() {
+    with_process(|process| {
+        assert_eq!(
+            rand::seed_1(atom_unchecked("exs1024s"), &process),
+            Err(badarg!().into())
+        );
+    });
+}
+
+#[test]
+fn uniform_returns_float_at_least_zero_and_less_than_one() {
+    with_process(|process| {
+        for _ in 0..100 {
+            let uniform: f64 = rand::uniform_0(&process).unwrap().try_into().unwrap();
+
+            assert!(0.0 <= uniform && uniform < 1.0);
+        }
+    });
+}
+
+#[test]
+fn with_positive_integer_uniform_returns_integer_between_one_and_n() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &strategy::term::integer::small::positive(arc_process.clone()),
+                |n| {
+                    let uniform = rand::uniform_1(n, &arc_process).unwrap();
+
+                    prop_assert_eq!(
+                        arc_process.integer(1).unwrap() <= uniform && uniform <= n,
+                        true
+                    );
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn without_positive_integer_uniform_errors_badarg() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &strategy::term::integer::non_positive(arc_process.clone()),
+                |n| {
+                    prop_assert_eq!(rand::uniform_1(n, &arc_process), Err(badarg!().into()));
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_negative_variance_normal_errors_badarg() {
+    with_process(|process| {
+        assert_eq!(
+            rand::normal_2(
+                process.integer(0).unwrap(),
+                process.integer(-1).unwrap(),
+                &process
+            ),
+            Err(badarg!().into())
+        );
+    });
+}
+
+fn seed(process: &Process) -> Term {
+    process
+        .tuple_from_slice(&[
+            process.integer(100).unwrap(),
+            process.integer(200).unwrap(),
+            process.integer(300).unwrap(),
+        ])
+        .unwrap()
+}
+
+fn uniform_floats(process: &Process) -> Vec<f64> {
+    (0..3)
+        .map(|_| rand::uniform_0(process).unwrap().try_into().unwrap())
+        .collect()
+}
+
+fn uniform_outputs(process: &Process) -> Vec<u64> {
+    let n = process.integer(1_u64 << 58).unwrap();
+
+    (0..5)
+        .map(|_| rand::uniform_1(n, process).unwrap().try_into().unwrap())
+        .collect()
+}
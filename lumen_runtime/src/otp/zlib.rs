@@ -0,0 +1,356 @@
+//! Mirrors [zlib](http://erlang.org/doc/man/zlib.html) module
+//!
+//! The one-shot functions compress and uncompress whole iodata in the zlib (`compress/1`), raw
+//! deflate (`zip/1`) and gzip (`gzip/1`) formats.  For data that arrives in pieces, a stream from
+//! `open/0` is initialized with `deflateInit/1,2` or `inflateInit/1` for the zlib format and then
+//! fed with `deflate/2,3` or `inflate/2`, which return the output so far as an iolist.
+//!
+//! Like BEAM, data that can't be uncompressed is a `data_error` error.
+
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use core::convert::TryInto;
+
+use miniz_oxide::deflate::core::CompressorOxide;
+use miniz_oxide::deflate::{self, stream::deflate};
+use miniz_oxide::inflate::{self, stream::inflate, stream::InflateState};
+use miniz_oxide::{DataFormat, MZError, MZFlush, MZStatus, StreamResult};
+
+use liblumen_core::locks::Mutex;
+
+use liblumen_alloc::erts::exception::{Exception, Result};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, resource, Atom, Term, TypedTerm};
+use liblumen_alloc::{badarg, error};
+
+use crate::digest;
+use crate::otp::erlang::iodata_to_bytes;
+
+pub fn close_1(z: Term) -> Result {
+    with_state(z, |state| match state {
+        State::Closed => Err(badarg!().into()),
+        _ => {
+            *state = State::Closed;
+
+            Ok(atom_unchecked("ok"))
+        }
+    })
+}
+
+/// Compresses `data` in the zlib format
+pub fn compress_1(data: Term, process: &Process) -> Result {
+    let bytes = iodata_to_bytes(data)?;
+
+    Ok(process.binary_from_bytes(&deflate::compress_to_vec_zlib(&bytes, DEFAULT_LEVEL))?)
+}
+
+pub fn deflate_2(z: Term, data: Term, process: &Process) -> Result {
+    deflate_with_flush(z, data, MZFlush::None, process)
+}
+
+/// `flush` is `none`, `sync`, `full` or `finish`, after which the stream has to be initialized
+/// again with `deflateEnd/1` and `deflateInit/1,2`.
+pub fn deflate_3(z: Term, data: Term, flush: Term, process: &Process) -> Result {
+    let flush_atom: Atom = flush.try_into()?;
+    let flush = match flush_atom.name() {
+        "none" => MZFlush::None,
+        "sync" => MZFlush::Sync,
+        "full" => MZFlush::Full,
+        "finish" => MZFlush::Finish,
+        _ => return Err(badarg!().into()),
+    };
+
+    deflate_with_flush(z, data, flush, process)
+}
+
+pub fn deflate_end_1(z: Term) -> Result {
+    end(z, |state| match state {
+        State::Deflate(_) => true,
+        _ => false,
+    })
+}
+
+pub fn deflate_init_1(z: Term) -> Result {
+    deflate_init(z, DEFAULT_LEVEL)
+}
+
+/// `level` is `none`, `default`, `best_speed`, `best_compression` or `0..9`
+pub fn deflate_init_2(z: Term, level: Term) -> Result {
+    let level = match level.to_typed_term().unwrap() {
+        TypedTerm::Atom(atom) => match atom.name() {
+            "none" => 0,
+            "default" => DEFAULT_LEVEL,
+            "best_speed" => 1,
+            "best_compression" => 9,
+            _ => return Err(badarg!().into()),
+        },
+        _ => {
+            let level_usize: usize = level.try_into().map_err(|_| badarg!())?;
+
+            if level_usize <= 9 {
+                level_usize as u8
+            } else {
+                return Err(badarg!().into());
+            }
+        }
+    };
+
+    deflate_init(z, level)
+}
+
+/// Uncompresses `data` in the gzip format
+pub fn gunzip_1(data: Term, process: &Process) -> Result {
+    let bytes = iodata_to_bytes(data)?;
+    let uncompressed = gunzip(&bytes).ok_or_else(data_error)?;
+
+    Ok(process.binary_from_bytes(&uncompressed)?)
+}
+
+/// Compresses `data` in the gzip format
+pub fn gzip_1(data: Term, process: &Process) -> Result {
+    let bytes = iodata_to_bytes(data)?;
+
+    let mut gzip = GZIP_HEADER.to_vec();
+    gzip.extend_from_slice(&deflate::compress_to_vec(&bytes, DEFAULT_LEVEL));
+    gzip.extend_from_slice(&digest::crc32(0, &bytes).to_le_bytes());
+    gzip.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+
+    Ok(process.binary_from_bytes(&gzip)?)
+}
+
+pub fn inflate_2(z: Term, data: Term, process: &Process) -> Result {
+    let bytes = iodata_to_bytes(data)?;
+
+    with_state(z, |state| match state {
+        State::Inflate(inflate_state) => {
+            let inflated = stream(&bytes, |input, output| {
+                inflate(inflate_state, input, output, MZFlush::None)
+            })
+            .map_err(|_| data_error())?;
+
+            iolist(&inflated, process)
+        }
+        _ => Err(badarg!().into()),
+    })
+}
+
+pub fn inflate_end_1(z: Term) -> Result {
+    end(z, |state| match state {
+        State::Inflate(_) => true,
+        _ => false,
+    })
+}
+
+pub fn inflate_init_1(z: Term) -> Result {
+    init(z, || {
+        State::Inflate(InflateState::new_boxed(DataFormat::Zlib))
+    })
+}
+
+pub fn open_0(process: &Process) -> Result {
+    let stream = Stream(Mutex::new(State::Uninitialized));
+
+    Ok(process.resource(Box::new(stream))?)
+}
+
+/// Uncompresses `data` in the zlib format
+pub fn uncompress_1(data: Term, process: &Process) -> Result {
+    let bytes = iodata_to_bytes(data)?;
+    let uncompressed = inflate::decompress_to_vec_zlib(&bytes).map_err(|_| data_error())?;
+
+    Ok(process.binary_from_bytes(&uncompressed)?)
+}
+
+/// Uncompresses `data` in the raw deflate format, without a zlib header or checksum
+pub fn unzip_1(data: Term, process: &Process) -> Result {
+    let bytes = iodata_to_bytes(data)?;
+    let uncompressed = inflate::decompress_to_vec(&bytes).map_err(|_| data_error())?;
+
+    Ok(process.binary_from_bytes(&uncompressed)?)
+}
+
+/// Compresses `data` in the raw deflate format, without a zlib header or checksum
+pub fn zip_1(data: Term, process: &Process) -> Result {
+    let bytes = iodata_to_bytes(data)?;
+
+    Ok(process.binary_from_bytes(&deflate::compress_to_vec(&bytes, DEFAULT_LEVEL))?)
+}
+
+// Private
+
+const DEFAULT_LEVEL: u8 = 6;
+
+/// Magic, deflate, no flags, no modification time, no extra flags and Unix as the OS, as zlib
+/// writes by default.
+const GZIP_HEADER: [u8; 10] = [0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 3];
+
+const GZIP_FLAG_HCRC: u8 = 0b0000_0010;
+const GZIP_FLAG_EXTRA: u8 = 0b0000_0100;
+const GZIP_FLAG_NAME: u8 = 0b0000_1000;
+const GZIP_FLAG_COMMENT: u8 = 0b0001_0000;
+
+/// The `z` of the stream functions.  It is a resource, so every copy of the reference refers to the
+/// same stream.
+struct Stream(Mutex<State>);
+
+enum State {
+    Uninitialized,
+    Deflate(Box<CompressorOxide>),
+    Inflate(Box<InflateState>),
+    Closed,
+}
+
+fn data_error() -> Exception {
+    error!(atom_unchecked("data_error")).into()
+}
+
+fn deflate_init(z: Term, level: u8) -> Result {
+    init(z, || {
+        let mut compressor = Box::new(CompressorOxide::default());
+        compressor.set_format_and_level(DataFormat::Zlib, level);
+
+        State::Deflate(compressor)
+    })
+}
+
+fn deflate_with_flush(z: Term, data: Term, flush: MZFlush, process: &Process) -> Result {
+    let bytes = iodata_to_bytes(data)?;
+
+    with_state(z, |state| match state {
+        State::Deflate(compressor) => {
+            let deflated = stream(&bytes, |input, output| {
+                deflate(compressor, input, output, flush)
+            })
+            .map_err(|_| badarg!())?;
+
+            iolist(&deflated, process)
+        }
+        _ => Err(badarg!().into()),
+    })
+}
+
+/// Returns a stream in the state selected by `is_state` to uninitialized
+fn end<F>(z: Term, is_state: F) -> Result
+where
+    F: FnOnce(&State) -> bool,
+{
+    with_state(z, |state| {
+        if is_state(state) {
+            *state = State::Uninitialized;
+
+            Ok(atom_unchecked("ok"))
+        } else {
+            Err(badarg!().into())
+        }
+    })
+}
+
+/// The uncompressed bytes of a single gzip member.  `None` if `gzip` isn't a valid gzip member.
+fn gunzip(gzip: &[u8]) -> Option<Vec<u8>> {
+    if gzip.len() < GZIP_HEADER.len() + 8 || gzip[0..3] != GZIP_HEADER[0..3] {
+        return None;
+    }
+
+    let flags = gzip[3];
+    let mut position = GZIP_HEADER.len();
+
+    if flags & GZIP_FLAG_EXTRA != 0 {
+        let extra_len = u16::from_le_bytes(gzip.get(position..position + 2)?.try_into().unwrap());
+        position += 2 + extra_len as usize;
+    }
+
+    for flag in &[GZIP_FLAG_NAME, GZIP_FLAG_COMMENT] {
+        if flags & flag != 0 {
+            // zero-terminated
+            position += gzip.get(position..)?.iter().position(|byte| *byte == 0)? + 1;
+        }
+    }
+
+    if flags & GZIP_FLAG_HCRC != 0 {
+        position += 2;
+    }
+
+    let trailer_position = gzip.len() - 8;
+    let deflated = gzip.get(position..trailer_position)?;
+    let trailer = &gzip[trailer_position..];
+    let crc = u32::from_le_bytes(trailer[0..4].try_into().unwrap());
+    let len = u32::from_le_bytes(trailer[4..8].try_into().unwrap());
+
+    let inflated = inflate::decompress_to_vec(deflated).ok()?;
+
+    if digest::crc32(0, &inflated) == crc && (inflated.len() as u32) == len {
+        Some(inflated)
+    } else {
+        None
+    }
+}
+
+/// Initializes an uninitialized stream
+fn init<F>(z: Term, initialized: F) -> Result
+where
+    F: FnOnce() -> State,
+{
+    with_state(z, |state| match state {
+        State::Uninitialized => {
+            *state = initialized();
+
+            Ok(atom_unchecked("ok"))
+        }
+        _ => Err(badarg!().into()),
+    })
+}
+
+/// `bytes` as an iolist, so that no output is `[]`
+fn iolist(bytes: &[u8], process: &Process) -> Result {
+    if bytes.is_empty() {
+        Ok(Term::NIL)
+    } else {
+        let binary = process.binary_from_bytes(bytes)?;
+
+        Ok(process.list_from_slice(&[binary])?)
+    }
+}
+
+/// Runs `step` over all of `input` until it stops making progress, collecting the output
+fn stream<F>(mut input: &[u8], mut step: F) -> core::result::Result<Vec<u8>, MZError>
+where
+    F: FnMut(&[u8], &mut [u8]) -> StreamResult,
+{
+    let mut output = Vec::new();
+    let mut buffer = [0; 8 * 1024];
+
+    loop {
+        let result = step(input, &mut buffer);
+        input = &input[result.bytes_consumed..];
+        output.extend_from_slice(&buffer[..result.bytes_written]);
+
+        match result.status {
+            Ok(MZStatus::StreamEnd) => break,
+            // a full buffer may have more output waiting
+            Ok(_) if input.is_empty() && result.bytes_written < buffer.len() => break,
+            Ok(_) => continue,
+            // no progress can be made without more input
+            Err(MZError::Buf) => break,
+            Err(error) => return Err(error),
+        }
+    }
+
+    Ok(output)
+}
+
+/// Runs `f` with the locked state of the stream `z`
+fn with_state<F>(z: Term, f: F) -> Result
+where
+    F: FnOnce(&mut State) -> Result,
+{
+    let reference: resource::Reference = z.try_into().map_err(|_| badarg!())?;
+    let stream: &Stream = reference.downcast_ref().ok_or_else(|| badarg!())?;
+    let mut state = stream.0.lock();
+
+    f(&mut state)
+}
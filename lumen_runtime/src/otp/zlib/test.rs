@@ -0,0 +1,160 @@
+use proptest::prop_assert_eq;
+use proptest::test_runner::{Config, TestRunner};
+
+use liblumen_alloc::erts::exception::Result;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Term};
+use liblumen_alloc::{badarg, error};
+
+use crate::otp::{erlang, zlib};
+use crate::scheduler::{with_process, with_process_arc};
+use crate::test::strategy;
+
+type Convert = fn(Term, &Process) -> Result;
+
+#[test]
+fn uncompress_returns_compressed_data() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(&strategy::byte_vec(), |bytes| {
+                let data = arc_process.binary_from_bytes(&bytes).unwrap();
+
+                let conversions: [(Convert, Convert); 3] = [
+                    (zlib::compress_1, zlib::uncompress_1),
+                    (zlib::zip_1, zlib::unzip_1),
+                    (zlib::gzip_1, zlib::gunzip_1),
+                ];
+
+                for (compress, uncompress) in &conversions {
+                    let compressed = compress(data, &arc_process).unwrap();
+
+                    prop_assert_eq!(uncompress(compressed, &arc_process), Ok(data));
+                }
+
+                Ok(())
+            })
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_invalid_data_uncompress_errors_data_error() {
+    with_process(|process| {
+        let data = process.binary_from_bytes(b"not compressed").unwrap();
+
+        let uncompresses: [Convert; 3] = [zlib::uncompress_1, zlib::unzip_1, zlib::gunzip_1];
+
+        for uncompress in &uncompresses {
+            assert_eq!(
+                uncompress(data, &process),
+                Err(error!(atom_unchecked("data_error")).into())
+            );
+        }
+    });
+}
+
+#[test]
+fn with_corrupted_gzip_trailer_gunzip_errors_data_error() {
+    with_process(|process| {
+        let data = process.binary_from_bytes(b"data").unwrap();
+        let gzip = zlib::gzip_1(data, &process).unwrap();
+        let mut bytes = erlang::iodata_to_bytes(gzip).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+        let corrupted = process.binary_from_bytes(&bytes).unwrap();
+
+        assert_eq!(
+            zlib::gunzip_1(corrupted, &process),
+            Err(error!(atom_unchecked("data_error")).into())
+        );
+    });
+}
+
+#[test]
+fn inflate_returns_data_deflated_in_pieces() {
+    with_process_arc(|arc_process| {
+        TestRunner::new(Config::with_source_file(file!()))
+            .run(
+                &(strategy::byte_vec(), strategy::byte_vec()),
+                |(first_bytes, second_bytes)| {
+                    let z = zlib::open_0(&arc_process).unwrap();
+
+                    prop_assert_eq!(zlib::deflate_init_1(z), Ok(atom_unchecked("ok")));
+
+                    let first = arc_process.binary_from_bytes(&first_bytes).unwrap();
+                    let second = arc_process.binary_from_bytes(&second_bytes).unwrap();
+                    let first_deflated = zlib::deflate_2(z, first, &arc_process).unwrap();
+                    let second_deflated =
+                        zlib::deflate_3(z, second, atom_unchecked("finish"), &arc_process).unwrap();
+
+                    prop_assert_eq!(zlib::deflate_end_1(z), Ok(atom_unchecked("ok")));
+                    prop_assert_eq!(zlib::inflate_init_1(z), Ok(atom_unchecked("ok")));
+
+                    let mut inflated_bytes = Vec::new();
+
+                    for deflated in &[first_deflated, second_deflated] {
+                        let inflated = zlib::inflate_2(z, *deflated, &arc_process).unwrap();
+                        inflated_bytes.extend(erlang::iodata_to_bytes(inflated).unwrap());
+                    }
+
+                    let mut all_bytes = first_bytes.clone();
+                    all_bytes.extend_from_slice(&second_bytes);
+
+                    prop_assert_eq!(inflated_bytes, all_bytes);
+                    prop_assert_eq!(zlib::inflate_end_1(z), Ok(atom_unchecked("ok")));
+                    prop_assert_eq!(zlib::close_1(z), Ok(atom_unchecked("ok")));
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+    });
+}
+
+#[test]
+fn with_deflate_stream_uncompress_returns_data() {
+    with_process(|process| {
+        let z = zlib::open_0(&process).unwrap();
+        zlib::deflate_init_2(z, atom_unchecked("best_compression")).unwrap();
+
+        let data = process.binary_from_bytes(b"some data").unwrap();
+        let deflated = zlib::deflate_3(z, data, atom_unchecked("finish"), &process).unwrap();
+        let deflated_binary = erlang::list_to_binary_1(deflated, &process).unwrap();
+
+        assert_eq!(zlib::uncompress_1(deflated_binary, &process), Ok(data));
+    });
+}
+
+#[test]
+fn without_initialized_stream_errors_badarg() {
+    with_process(|process| {
+        let z = zlib::open_0(&process).unwrap();
+        let data = process.binary_from_bytes(b"data").unwrap();
+
+        assert_eq!(zlib::deflate_2(z, data, &process), Err(badarg!().into()));
+        assert_eq!(zlib::inflate_2(z, data, &process), Err(badarg!().into()));
+        assert_eq!(zlib::deflate_end_1(z), Err(badarg!().into()));
+
+        zlib::inflate_init_1(z).unwrap();
+
+        assert_eq!(zlib::deflate_init_1(z), Err(badarg!().into()));
+        assert_eq!(zlib::close_1(z), Ok(atom_unchecked("ok")));
+        assert_eq!(zlib::close_1(z), Err(badarg!().into()));
+    });
+}
+
+#[test]
+fn with_invalid_level_deflate_init_errors_badarg() {
+    with_process(|process| {
+        let z = zlib::open_0(&process).unwrap();
+
+        assert_eq!(
+            zlib::deflate_init_2(z, process.integer(10).unwrap()),
+            Err(badarg!().into())
+        );
+        assert_eq!(
+            zlib::deflate_init_2(z, atom_unchecked("fastest")),
+            Err(badarg!().into())
+        );
+    });
+}
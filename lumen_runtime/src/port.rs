@@ -0,0 +1,1014 @@
+//! Ports to external programs, as opened with `open_port({spawn, Command}, Options)`.
+//!
+//! `Command` is run by `sh -c` with its standard input and output piped to the port, and its
+//! standard error inherited, unless `stderr_to_stdout` is given.  The process that opens a port owns
+//! it: its standard output is read on a thread of its own, like standard input in
+//! `system::io::stdin`, and sent to the owner as `{Port, {data, Data}}`, where `Data` is a string,
+//! or a binary with the `binary` option.  With `{line, L}`, `Data` is `{eol, Line}` for each line
+//! without its newline, or `{noeol, Part}` for the parts of lines longer than `L` and of a last line
+//! without a newline.  Once the program closes its standard output, the owner is sent
+//! `{Port, {exit_status, Status}}` with the `exit_status` option, and the port closes.
+//!
+//! `port_command/2,3` and `{Owner, {command, Data}}` queue `Data` to be written to the standard
+//! input of the program by a thread of the port's own, so that a program that is slow to read
+//! doesn't block the scheduler.  Once more than `HIGH_WATERMARK` bytes are queued the port is busy
+//! until the queue drains to `LOW_WATERMARK`, as `busy_limits_port` defaults to on BEAM.  A
+//! `port_command` to a busy port suspends the calling process, which waits until the port isn't
+//! busy and then commands it again, unless the command is `force`d or, with `nosuspend`, fails.
+//! Commands sent as `{Owner, {command, Data}}` are queued even to a busy port, as sending a message
+//! doesn't suspend the sender, unless they are sent with `nosuspend`, which drops them.
+//!
+//! `port_close/1` closes the port at once, killing the program and dropping the queued commands.
+//! `{Owner, close}` closes the port once the queued commands are written and is replied to with
+//! `{Port, closed}`.  `{Owner, {connect, Pid}}` makes `Pid` the owner and is replied to with
+//! `{Port, connected}`.  Messages that aren't from the owner are
+//! ignored, and a port closes when its owner exits, but, unlike BEAM, ports aren't linked to their
+//! owner, so owners aren't sent exit signals by their ports.
+
+use core::convert::{TryFrom, TryInto};
+use core::mem;
+use core::ptr;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, ExitStatus, Stdio};
+use std::sync::{Arc, Condvar, Mutex, Weak};
+use std::thread;
+
+use hashbrown::HashMap;
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::exception::{runtime, Exception};
+use liblumen_alloc::erts::process::{HeapAlloc, Process};
+use liblumen_alloc::erts::term::{atom_unchecked, AsTerm, Atom, Cons, HeapBin, Pid, Port, Term};
+use liblumen_alloc::erts::term::{Tuple, TypedTerm};
+use liblumen_alloc::erts::HeapFragment;
+
+use crate::otp::erlang;
+use crate::process::{send_heap_message_and_wake, wake};
+use crate::registry::pid_to_process;
+use crate::send::send as send_to_process;
+
+/// The options of `open_port/2` that ports to external programs support
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Options {
+    /// Send `{Port, {exit_status, Status}}` when the program exits
+    pub exit_status: bool,
+    /// Send data as binaries instead of strings
+    pub binary: bool,
+    /// Send data line by line, splitting lines longer than this many bytes
+    pub line: Option<usize>,
+    /// Send standard error of the program to the port too
+    pub stderr_to_stdout: bool,
+}
+
+impl Options {
+    fn put_option_term(&mut self, option: Term) -> Result<&Options, runtime::Exception> {
+        match option.to_typed_term().unwrap() {
+            TypedTerm::Atom(atom) => match atom.name() {
+                "binary" => {
+                    self.binary = true;
+
+                    Ok(self)
+                }
+                "exit_status" => {
+                    self.exit_status = true;
+
+                    Ok(self)
+                }
+                "stderr_to_stdout" => {
+                    self.stderr_to_stdout = true;
+
+                    Ok(self)
+                }
+                // the defaults
+                "stream" | "use_stdio" => Ok(self),
+                _ => Err(badarg!()),
+            },
+            TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+                TypedTerm::Tuple(tuple) if tuple.len() == 2 => {
+                    let name: Atom = tuple[0].try_into().map_err(|_| badarg!())?;
+
+                    match name.name() {
+                        "line" => {
+                            let len: usize = tuple[1].try_into().map_err(|_| badarg!())?;
+
+                            if 0 < len {
+                                self.line = Some(len);
+
+                                Ok(self)
+                            } else {
+                                Err(badarg!())
+                            }
+                        }
+                        _ => Err(badarg!()),
+                    }
+                }
+                _ => Err(badarg!()),
+            },
+            _ => Err(badarg!()),
+        }
+    }
+}
+
+impl TryFrom<Term> for Options {
+    type Error = runtime::Exception;
+
+    fn try_from(term: Term) -> Result<Options, Self::Error> {
+        let mut options: Options = Default::default();
+        let mut options_term = term;
+
+        loop {
+            match options_term.to_typed_term().unwrap() {
+                TypedTerm::Nil => return Ok(options),
+                TypedTerm::List(cons) => {
+                    options.put_option_term(cons.head)?;
+                    options_term = cons.tail;
+
+                    continue;
+                }
+                _ => return Err(badarg!()),
+            }
+        }
+    }
+}
+
+/// The options of `port_command/3`
+#[derive(Clone, Copy, Debug)]
+pub struct CommandOptions {
+    /// Queue the command even if the port is busy
+    pub force: bool,
+    /// Wait for a busy port not to be busy instead of failing the command
+    pub suspend: bool,
+}
+
+impl CommandOptions {
+    fn put_option_term(&mut self, option: Term) -> Result<&CommandOptions, runtime::Exception> {
+        let atom: Atom = option.try_into().map_err(|_| badarg!())?;
+
+        match atom.name() {
+            "force" => {
+                self.force = true;
+
+                Ok(self)
+            }
+            "nosuspend" => {
+                self.suspend = false;
+
+                Ok(self)
+            }
+            _ => Err(badarg!()),
+        }
+    }
+}
+
+impl Default for CommandOptions {
+    fn default() -> CommandOptions {
+        CommandOptions {
+            force: false,
+            suspend: true,
+        }
+    }
+}
+
+impl TryFrom<Term> for CommandOptions {
+    type Error = runtime::Exception;
+
+    fn try_from(term: Term) -> Result<CommandOptions, Self::Error> {
+        let mut options: CommandOptions = Default::default();
+        let mut options_term = term;
+
+        loop {
+            match options_term.to_typed_term().unwrap() {
+                TypedTerm::Nil => return Ok(options),
+                TypedTerm::List(cons) => {
+                    options.put_option_term(cons.head)?;
+                    options_term = cons.tail;
+
+                    continue;
+                }
+                _ => return Err(badarg!()),
+            }
+        }
+    }
+}
+
+/// What became of a command to a port
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Commanded {
+    /// The command will be written to the program
+    Queued,
+    /// The port is busy and the command had `nosuspend`, so it was dropped
+    Busy,
+    /// The port is busy, so the command was dropped and the process commanding it now waits to be
+    /// woken once the port isn't busy, to command it again
+    Suspended,
+    /// The port isn't open, or closed while the command waited for it not to be busy
+    NotOpen,
+}
+
+/// Runs `command` and opens a port to it owned by `owner`
+pub fn open(owner: &Arc<Process>, command: &str, options: Options) -> io::Result<Port> {
+    let shell_command = if options.stderr_to_stdout {
+        format!("exec 2>&1; {}", command)
+    } else {
+        command.to_string()
+    };
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(shell_command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+
+    let stdin = child.stdin.take().unwrap();
+    let stdout = child.stdout.take().unwrap();
+    let port = unsafe { Port::from_raw(NEXT_NUMBER.fetch_add(1, Ordering::SeqCst)) };
+    let open = Arc::new(Open {
+        owner: Mutex::new(Owner::new(owner)),
+        output: Default::default(),
+        output_condvar: Condvar::new(),
+        child: Mutex::new(Some(child)),
+        closed: AtomicBool::new(false),
+    });
+
+    PORTS.lock().unwrap().insert(port, open.clone());
+
+    let writer_open = open.clone();
+    thread::spawn(move || write(port, writer_open, stdin, options.binary));
+    thread::spawn(move || read(port, open, stdout, options));
+
+    Ok(port)
+}
+
+/// Queues `bytes` to be written to the program of `port` for `process`
+pub fn command(
+    port: Port,
+    bytes: Vec<u8>,
+    options: CommandOptions,
+    process: &Process,
+) -> Commanded {
+    match get(port) {
+        Some(open) => open.command(bytes, options, process),
+        None => Commanded::NotOpen,
+    }
+}
+
+/// Closes `port`, killing its program, returning `false` if `port` isn't open
+pub fn close(port: Port) -> bool {
+    match remove(port) {
+        Some(open) => {
+            open.close();
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Handles `message` sent to `port` by `process`.  Messages to ports that aren't open are dropped,
+/// as they are for processes that have exited.  Returns `false` if `message` is a command that
+/// wasn't queued because the port is busy and `suspend` is `false`.
+pub fn send(
+    port: Port,
+    message: Term,
+    suspend: bool,
+    process: &Process,
+) -> Result<bool, Exception> {
+    let open = match get(port) {
+        Some(open) => open,
+        None => return Ok(true),
+    };
+    let (from, request) = match from_request(message) {
+        Some(from_request) => from_request,
+        None => return Ok(true),
+    };
+
+    if open.owner.lock().unwrap().pid != from {
+        return Ok(true);
+    }
+
+    match request.to_typed_term().unwrap() {
+        TypedTerm::Atom(atom) if atom.name() == "close" => {
+            // `{Port, closed}` is sent once the queued commands are written
+            if remove(port).is_some() {
+                open.close_after_output(from);
+            }
+
+            Ok(true)
+        }
+        TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+            TypedTerm::Tuple(tuple) if tuple.len() == 2 => {
+                let name: Atom = match tuple[0].try_into() {
+                    Ok(name) => name,
+                    Err(_) => return Ok(true),
+                };
+
+                match name.name() {
+                    "command" => match erlang::iodata_to_bytes(tuple[1]) {
+                        Ok(bytes) => {
+                            // sending doesn't suspend the sender, so the command is queued even
+                            // to a busy port, unless it is sent with `nosuspend`
+                            let options = CommandOptions {
+                                force: suspend,
+                                suspend: false,
+                            };
+
+                            Ok(open.command(bytes, options, process) != Commanded::Busy)
+                        }
+                        Err(_) => Ok(true),
+                    },
+                    "connect" => match tuple[1].to_typed_term().unwrap() {
+                        TypedTerm::Pid(pid) => match pid_to_process(&pid) {
+                            Some(new_owner) => {
+                                *open.owner.lock().unwrap() = Owner::new(&new_owner);
+
+                                reply(process, from, port, atom_unchecked("connected"))?;
+
+                                Ok(true)
+                            }
+                            None => Ok(true),
+                        },
+                        _ => Ok(true),
+                    },
+                    _ => Ok(true),
+                }
+            }
+            _ => Ok(true),
+        },
+        _ => Ok(true),
+    }
+}
+
+/// Closes the ports owned by `process`, which has exited
+pub fn propagate_exit(process: &Process) {
+    let pid = process.pid();
+    let mut owned = Vec::new();
+
+    PORTS.lock().unwrap().retain(|_, open| {
+        if open.owner.lock().unwrap().pid == pid {
+            owned.push(open.clone());
+
+            false
+        } else {
+            true
+        }
+    });
+
+    for open in owned {
+        open.close();
+    }
+}
+
+// Private
+
+/// A port that is open
+struct Open {
+    owner: Mutex<Owner>,
+    output: Mutex<Output>,
+    /// Notified when commands are queued and when the port closes, for the writer
+    output_condvar: Condvar,
+    /// `None` once the program closed its standard output and is being waited for
+    child: Mutex<Option<Child>>,
+    closed: AtomicBool,
+}
+
+impl Open {
+    fn command(&self, bytes: Vec<u8>, options: CommandOptions, process: &Process) -> Commanded {
+        let mut output = self.output.lock().unwrap();
+
+        if self.is_closed() || output.close_requested_by.is_some() {
+            return Commanded::NotOpen;
+        }
+
+        if output.busy && !options.force {
+            if !options.suspend {
+                return Commanded::Busy;
+            }
+
+            // the process waits while the output is still locked, so that it is already waiting
+            // when the writer drains the queue and wakes it
+            process.wait();
+
+            let pid = process.pid();
+
+            if !output.suspended.contains(&pid) {
+                output.suspended.push(pid);
+            }
+
+            return Commanded::Suspended;
+        }
+
+        output.queued_len += bytes.len();
+        output.commands.push_back(bytes);
+
+        if HIGH_WATERMARK < output.queued_len {
+            output.busy = true;
+        }
+
+        self.output_condvar.notify_all();
+
+        Commanded::Queued
+    }
+
+    /// Closes the port once the queued commands are written, replying `{Port, closed}` to `from`
+    fn close_after_output(&self, from: Pid) {
+        self.output.lock().unwrap().close_requested_by = Some(from);
+        self.output_condvar.notify_all();
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+
+        let suspended = {
+            let mut output = self.output.lock().unwrap();
+            output.commands.clear();
+            output.queued_len = 0;
+            output.busy = false;
+
+            mem::replace(&mut output.suspended, Vec::new())
+        };
+
+        // wakes the writer, so that it drops standard input
+        self.output_condvar.notify_all();
+        // and the processes suspended on the busy port, so that their commands fail
+        wake_suspended(suspended);
+
+        if let Some(child) = self.child.lock().unwrap().as_mut() {
+            let _ = child.kill();
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+}
+
+/// The commands queued for the program of a port
+#[derive(Default)]
+struct Output {
+    commands: VecDeque<Vec<u8>>,
+    /// The bytes in `commands`, and in the command being written
+    queued_len: usize,
+    busy: bool,
+    /// The processes waiting for the port not to be busy
+    suspended: Vec<Pid>,
+    close_requested_by: Option<Pid>,
+}
+
+struct Owner {
+    pid: Pid,
+    process: Weak<Process>,
+}
+
+impl Owner {
+    fn new(process: &Arc<Process>) -> Self {
+        Self {
+            pid: process.pid(),
+            process: Arc::downgrade(process),
+        }
+    }
+}
+
+/// What is sent to the owner of a port
+enum Data<'a> {
+    Stream(&'a [u8]),
+    Line { eol: bool, bytes: &'a [u8] },
+    ExitStatus(i32),
+    Closed,
+}
+
+fn get(port: Port) -> Option<Arc<Open>> {
+    PORTS.lock().unwrap().get(&port).cloned()
+}
+
+fn remove(port: Port) -> Option<Arc<Open>> {
+    PORTS.lock().unwrap().remove(&port)
+}
+
+/// `{From, Request}`, which is how messages to ports are sent
+fn from_request(message: Term) -> Option<(Pid, Term)> {
+    match message.to_typed_term().unwrap() {
+        TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+            TypedTerm::Tuple(tuple) if tuple.len() == 2 => {
+                match tuple[0].to_typed_term().unwrap() {
+                    TypedTerm::Pid(pid) => Some((pid, tuple[1])),
+                    _ => None,
+                }
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Sends `{port, reply}` to `to` from `process`
+fn reply(process: &Process, to: Pid, port: Port, reply: Term) -> Result<(), Exception> {
+    let message = process.tuple_from_slice(&[unsafe { port.as_term() }, reply])?;
+
+    send_to_process(
+        unsafe { to.as_term() },
+        message,
+        Default::default(),
+        process,
+    )
+    .map(|_| ())
+}
+
+/// Writes the commands queued for the program of `port` to its standard input until `port` closes
+fn write(port: Port, open: Arc<Open>, mut stdin: ChildStdin, binary: bool) {
+    loop {
+        let bytes = {
+            let mut output = open.output.lock().unwrap();
+
+            loop {
+                if open.is_closed() {
+                    return;
+                }
+
+                if let Some(bytes) = output.commands.pop_front() {
+                    break bytes;
+                }
+
+                if let Some(from) = output.close_requested_by {
+                    drop(output);
+                    drop(stdin);
+                    open.close();
+
+                    if let Some(from_process) = pid_to_process(&from) {
+                        // the reply is dropped if there isn't memory for it, as a message would be
+                        let _ = send_data_to_owner(&from_process, port, binary, Data::Closed);
+                    }
+
+                    return;
+                }
+
+                output = open.output_condvar.wait(output).unwrap();
+            }
+        };
+
+        // the program may have exited, which closes the port once its output is read
+        let _ = stdin.write_all(&bytes).and_then(|_| stdin.flush());
+
+        let mut output = open.output.lock().unwrap();
+        output.queued_len = output.queued_len.saturating_sub(bytes.len());
+
+        if output.busy && output.queued_len <= LOW_WATERMARK {
+            output.busy = false;
+            let suspended = mem::replace(&mut output.suspended, Vec::new());
+            drop(output);
+
+            wake_suspended(suspended);
+        }
+    }
+}
+
+/// Wakes the processes that were suspended on a port that is no longer busy or closed
+fn wake_suspended(suspended: Vec<Pid>) {
+    for pid in suspended {
+        if let Some(arc_process) = pid_to_process(&pid) {
+            wake(&arc_process);
+        }
+    }
+}
+
+fn read(port: Port, open: Arc<Open>, stdout: ChildStdout, options: Options) {
+    let mut reader = BufReader::new(stdout);
+
+    match options.line {
+        Some(len) => read_lines(port, &open, &mut reader, options.binary, len),
+        None => read_stream(port, &open, &mut reader, options.binary),
+    }
+
+    // `close` kills the program if it is still running
+    let option_child = open.child.lock().unwrap().take();
+
+    if let Some(mut child) = option_child {
+        if let Ok(status) = child.wait() {
+            if options.exit_status {
+                send_data(
+                    port,
+                    &open,
+                    options.binary,
+                    Data::ExitStatus(exit_status_code(status)),
+                );
+            }
+        }
+    }
+
+    if !open.is_closed() {
+        remove(port);
+        open.close();
+    }
+}
+
+fn read_stream(port: Port, open: &Open, reader: &mut impl Read, binary: bool) {
+    let mut buffer = [0; 4096];
+
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                if !send_data(port, open, binary, Data::Stream(&buffer[..n])) {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+fn read_lines(port: Port, open: &Open, reader: &mut impl BufRead, binary: bool, len: usize) {
+    let mut line = Vec::new();
+
+    loop {
+        line.clear();
+
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {
+                let eol = line.last() == Some(&b'\n');
+
+                if eol {
+                    line.pop();
+                }
+
+                let mut parts: Vec<&[u8]> = line.chunks(len).collect();
+
+                if parts.is_empty() {
+                    parts.push(&[]);
+                }
+
+                let last_index = parts.len() - 1;
+
+                for (index, bytes) in parts.into_iter().enumerate() {
+                    let data = Data::Line {
+                        eol: eol && index == last_index,
+                        bytes,
+                    };
+
+                    if !send_data(port, open, binary, data) {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Sends `{port, data}` to the owner of `port`, returning `false` if `port` closed
+fn send_data(port: Port, open: &Open, binary: bool, data: Data) -> bool {
+    if open.is_closed() {
+        return false;
+    }
+
+    let option_owner = open.owner.lock().unwrap().process.upgrade();
+
+    match option_owner {
+        Some(owner) => {
+            // the data is dropped if there isn't memory for it, as a message would be
+            let _ = send_data_to_owner(&owner, port, binary, data);
+
+            true
+        }
+        // the owner exited before its exit closed the port
+        None => false,
+    }
+}
+
+fn send_data_to_owner(owner: &Process, port: Port, binary: bool, data: Data) -> Result<(), Alloc> {
+    let bytes_need_in_words = match &data {
+        Data::Stream(bytes) | Data::Line { bytes, .. } => bytes_need_in_words(bytes, binary),
+        Data::ExitStatus(_) | Data::Closed => 0,
+    };
+    let need_in_words = 3 * Tuple::need_in_words_from_len(2) + bytes_need_in_words;
+    let mut non_null_heap_fragment = unsafe { HeapFragment::new_from_word_size(need_in_words)? };
+    let heap_fragment = unsafe { non_null_heap_fragment.as_mut() };
+
+    let result = data_to_term(heap_fragment, binary, data).and_then(|data_term| {
+        heap_fragment.tuple_from_slice(&[unsafe { port.as_term() }, data_term])
+    });
+
+    match result {
+        Ok(message) => {
+            send_heap_message_and_wake(owner, non_null_heap_fragment, message);
+
+            Ok(())
+        }
+        Err(alloc) => {
+            unsafe { ptr::drop_in_place(non_null_heap_fragment.as_ptr()) };
+
+            Err(alloc)
+        }
+    }
+}
+
+fn data_to_term<H: HeapAlloc>(heap: &mut H, binary: bool, data: Data) -> Result<Term, Alloc> {
+    match data {
+        Data::Stream(bytes) => {
+            let bytes_term = bytes_to_term(heap, bytes, binary)?;
+
+            heap.tuple_from_slice(&[atom_unchecked("data"), bytes_term])
+        }
+        Data::Line { eol, bytes } => {
+            let tag = atom_unchecked(if eol { "eol" } else { "noeol" });
+            let bytes_term = bytes_to_term(heap, bytes, binary)?;
+            let line = heap.tuple_from_slice(&[tag, bytes_term])?;
+
+            heap.tuple_from_slice(&[atom_unchecked("data"), line])
+        }
+        Data::ExitStatus(code) => {
+            let code_term = heap.integer(code)?;
+
+            heap.tuple_from_slice(&[atom_unchecked("exit_status"), code_term])
+        }
+        Data::Closed => Ok(atom_unchecked("closed")),
+    }
+}
+
+/// `bytes` as a heap binary, so that a heap fragment can hold it, or as a string
+fn bytes_to_term<H: HeapAlloc>(heap: &mut H, bytes: &[u8], binary: bool) -> Result<Term, Alloc> {
+    if binary {
+        heap.heapbin_from_bytes(bytes)
+    } else {
+        heap.list_from_iter(bytes.iter().map(|byte| Term::make_smallint(*byte as isize)))
+    }
+}
+
+fn bytes_need_in_words(bytes: &[u8], binary: bool) -> usize {
+    let word_size = mem::size_of::<Term>();
+
+    if binary {
+        (HeapBin::layout_bytes(bytes).size() + word_size - 1) / word_size
+    } else {
+        bytes.len() * (mem::size_of::<Cons>() / word_size)
+    }
+}
+
+/// The exit status as BEAM reports it, which is `128` plus the signal for programs killed by one
+fn exit_status_code(status: ExitStatus) -> i32 {
+    match status.code() {
+        Some(code) => code,
+        None => {
+            #[cfg(unix)]
+            {
+                use std::os::unix::process::ExitStatusExt;
+
+                128 + status.signal().unwrap_or(0)
+            }
+            #[cfg(not(unix))]
+            {
+                255
+            }
+        }
+    }
+}
+
+/// More than this many bytes queued for a program makes its port busy
+const HIGH_WATERMARK: usize = 8 * 1024;
+
+/// A busy port stops being busy once this many bytes or fewer are queued
+const LOW_WATERMARK: usize = 4 * 1024;
+
+/// Port 0 is left for standard input and output, as BEAM does
+static NEXT_NUMBER: AtomicUsize = AtomicUsize::new(1);
+
+lazy_static! {
+    static ref PORTS: Mutex<HashMap<Port, Arc<Open>>> = Default::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::{Duration, Instant};
+
+    use liblumen_alloc::erts::process::Status;
+
+    use crate::scheduler::with_process_arc;
+
+    #[test]
+    fn without_binary_or_line_sends_output_as_string_then_exit_status() {
+        with_process_arc(|arc_process| {
+            let options = Options {
+                exit_status: true,
+                ..Default::default()
+            };
+            let port = open(&arc_process, "printf hi; exit 3", options).unwrap();
+
+            let received = receive(&arc_process, 2);
+
+            assert_eq!(
+                received[0],
+                message(&arc_process, port, |process| {
+                    let hi = process.charlist_from_str("hi")?;
+
+                    process.tuple_from_slice(&[atom_unchecked("data"), hi])
+                })
+            );
+            assert_eq!(
+                received[1],
+                message(&arc_process, port, |process| {
+                    process.tuple_from_slice(&[atom_unchecked("exit_status"), process.integer(3)?])
+                })
+            );
+            assert!(!close(port));
+        });
+    }
+
+    #[test]
+    fn with_binary_and_line_sends_lines_split_at_length() {
+        with_process_arc(|arc_process| {
+            let options = Options {
+                binary: true,
+                line: Some(4),
+                ..Default::default()
+            };
+            let port = open(&arc_process, "printf 'abcdef\\ngh'", options).unwrap();
+
+            let received = receive(&arc_process, 3);
+            let expected: Vec<Term> = [("noeol", "abcd"), ("eol", "ef"), ("noeol", "gh")]
+                .iter()
+                .map(|(tag, bytes)| {
+                    message(&arc_process, port, |process| {
+                        let bytes_term = process.binary_from_str(bytes)?;
+                        let line = process.tuple_from_slice(&[atom_unchecked(tag), bytes_term])?;
+
+                        process.tuple_from_slice(&[atom_unchecked("data"), line])
+                    })
+                })
+                .collect();
+
+            assert_eq!(received, expected);
+        });
+    }
+
+    #[test]
+    fn command_writes_to_standard_input() {
+        with_process_arc(|arc_process| {
+            let port = open(&arc_process, "head -c 5", Default::default()).unwrap();
+
+            assert_eq!(
+                command(port, b"hello".to_vec(), Default::default(), &arc_process),
+                Commanded::Queued
+            );
+
+            let received = receive(&arc_process, 1);
+
+            assert_eq!(
+                received[0],
+                message(&arc_process, port, |process| {
+                    let hello = process.charlist_from_str("hello")?;
+
+                    process.tuple_from_slice(&[atom_unchecked("data"), hello])
+                })
+            );
+        });
+    }
+
+    #[test]
+    fn close_by_message_from_owner_replies_closed() {
+        with_process_arc(|arc_process| {
+            let port = open(&arc_process, "cat", Default::default()).unwrap();
+            let close_message = arc_process
+                .tuple_from_slice(&[arc_process.pid_term(), atom_unchecked("close")])
+                .unwrap();
+
+            assert_eq!(send(port, close_message, true, &arc_process), Ok(true));
+            assert_eq!(
+                command(
+                    port,
+                    b"after close".to_vec(),
+                    Default::default(),
+                    &arc_process
+                ),
+                Commanded::NotOpen
+            );
+
+            let received = receive(&arc_process, 1);
+
+            assert_eq!(
+                received[0],
+                message(&arc_process, port, |_| Ok(atom_unchecked("closed")))
+            );
+        });
+    }
+
+    #[test]
+    fn command_to_program_that_does_not_read_makes_port_busy() {
+        with_process_arc(|arc_process| {
+            let port = open(&arc_process, "exec sleep 10", Default::default()).unwrap();
+            let nosuspend = CommandOptions {
+                suspend: false,
+                ..Default::default()
+            };
+
+            // the pipe to the program fills before the queue does
+            let busy = (0..1024)
+                .map(|_| command(port, vec![0; 1024], nosuspend, &arc_process))
+                .position(|commanded| commanded == Commanded::Busy);
+
+            assert!(busy.is_some());
+            assert_eq!(
+                command(
+                    port,
+                    vec![0; 1024],
+                    CommandOptions {
+                        force: true,
+                        ..nosuspend
+                    },
+                    &arc_process
+                ),
+                Commanded::Queued
+            );
+            assert!(close(port));
+            assert_eq!(
+                command(port, vec![0; 1024], nosuspend, &arc_process),
+                Commanded::NotOpen
+            );
+        });
+    }
+
+    #[test]
+    fn command_to_busy_port_suspends_process_until_program_reads() {
+        with_process_arc(|arc_process| {
+            let port = open(
+                &arc_process,
+                "sleep 0.2; exec cat > /dev/null",
+                Default::default(),
+            )
+            .unwrap();
+            let nosuspend = CommandOptions {
+                suspend: false,
+                ..Default::default()
+            };
+
+            let busy = (0..1024)
+                .map(|_| command(port, vec![0; 1024], nosuspend, &arc_process))
+                .position(|commanded| commanded == Commanded::Busy);
+
+            assert!(busy.is_some());
+
+            assert_eq!(
+                command(port, vec![0; 1024], Default::default(), &arc_process),
+                Commanded::Suspended
+            );
+            assert_eq!(*arc_process.status.read(), Status::Waiting);
+
+            let start = Instant::now();
+
+            while *arc_process.status.read() == Status::Waiting {
+                assert!(
+                    start.elapsed() < Duration::from_secs(5),
+                    "process wasn't woken once the program read"
+                );
+
+                thread::sleep(Duration::from_millis(10));
+            }
+
+            assert_eq!(
+                command(port, vec![0; 1024], Default::default(), &arc_process),
+                Commanded::Queued
+            );
+            assert!(close(port));
+        });
+    }
+
+    /// Waits for `count` messages to be sent to `arc_process`
+    fn receive(arc_process: &Arc<Process>, count: usize) -> Vec<Term> {
+        let start = Instant::now();
+
+        loop {
+            let received: Vec<Term> = {
+                let mailbox = arc_process.mailbox.lock();
+                let mailbox = mailbox.borrow();
+
+                mailbox.iter().map(|message| *message.data()).collect()
+            };
+
+            if count <= received.len() {
+                return received;
+            }
+
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "only received {:?} while waiting for {} messages",
+                received,
+                count
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    fn message<F>(process: &Process, port: Port, data: F) -> Term
+    where
+        F: FnOnce(&Process) -> Result<Term, Alloc>,
+    {
+        let data_term = data(process).unwrap();
+
+        process
+            .tuple_from_slice(&[unsafe { port.as_term() }, data_term])
+            .unwrap()
+    }
+}
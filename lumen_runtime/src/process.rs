@@ -1,5 +1,16 @@
+pub mod alias;
+pub mod env;
+pub mod garbage_collect;
+pub mod limit;
+pub mod max_heap_size;
 pub mod monitor;
 pub mod spawn;
+pub mod trace;
+// wasm32 cannot spawn the threads that race exits
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod tests;
+
+use core::ptr::NonNull;
 
 use alloc::sync::Arc;
 
@@ -9,21 +20,22 @@ use liblumen_core::locks::RwLockWriteGuard;
 
 use liblumen_alloc::erts::exception::runtime;
 use liblumen_alloc::erts::exception::system::Alloc;
-use liblumen_alloc::erts::process::alloc::heap_alloc::HeapAlloc;
 use liblumen_alloc::erts::process::code::stack::frame::Frame;
-use liblumen_alloc::erts::process::{self, Process};
-use liblumen_alloc::erts::term::{atom_unchecked, Atom, Term, Tuple, TypedTerm};
+use liblumen_alloc::erts::process::signal::Exit;
+use liblumen_alloc::erts::process::{self, Process, Status};
+use liblumen_alloc::erts::term::{Atom, Term, TypedTerm};
 use liblumen_alloc::erts::ModuleFunctionArity;
 use liblumen_alloc::HeapFragment;
 
 use crate::code;
+use crate::logging;
 #[cfg(test)]
 use crate::process::spawn::options::Options;
 use crate::registry::*;
-use crate::scheduler::Scheduler;
-use crate::system;
+use crate::scheduler::{Scheduled, Scheduler};
 #[cfg(test)]
 use crate::test;
+use crate::time::monotonic;
 
 fn is_expected_exception(exception: &runtime::Exception) -> bool {
     match exception.class {
@@ -59,72 +71,122 @@ pub fn log_exit(process: &Process, exception: &runtime::Exception) {
             let reason = exception.reason;
 
             if !is_expected_exit_reason(reason) {
-                system::io::puts(&format!(
-                    "** (EXIT from {}) exited with reason: {}",
-                    process, reason
-                ));
+                logging::report_error(
+                    module_path!(),
+                    &format!("** (EXIT from {}) exited with reason: {}", process, reason),
+                );
             }
         }
-        runtime::Class::Error { .. } => system::io::puts(&format!(
-            "** (EXIT from {}) exited with reason: an exception was raised: {}\n{}",
-            process,
-            exception.reason,
-            process.stacktrace()
-        )),
+        runtime::Class::Error { .. } => logging::report_error(
+            module_path!(),
+            &format!(
+                "** (EXIT from {}) exited with reason: an exception was raised: {}\n{}",
+                process,
+                exception.reason,
+                process.stacktrace()
+            ),
+        ),
         _ => unimplemented!("{:?}", exception),
     }
+
+    if !is_expected_exception(exception) {
+        if let Some(dump) = process.flight_recorder_dump() {
+            logging::report_error(
+                module_path!(),
+                &format!("** (flight recorder of {})\n{}", process, dump),
+            );
+        }
+    }
 }
 
 pub fn propagate_exit(process: &Process, exception: &runtime::Exception) {
+    remove_pid_to_process(&process.pid());
+    process.trace_exit(exception.reason);
+    process.clear_code_versions();
     monitor::propagate_exit(process, exception);
     propagate_exit_to_links(process, exception);
+    alias::propagate_exit(process);
+    garbage_collect::propagate_exit(process);
+    #[cfg(not(target_arch = "wasm32"))]
+    crate::distribution::propagate_exit(process, exception);
+    #[cfg(not(target_arch = "wasm32"))]
+    crate::port::propagate_exit(process);
 }
 
 pub fn propagate_exit_to_links(process: &Process, exception: &runtime::Exception) {
     if !is_expected_exception(exception) {
-        let tag = atom_unchecked("EXIT");
-        let from = process.pid_term();
-        let reason = exception.reason;
-        let exit_message_elements: &[Term] = &[tag, from, reason];
-        let exit_message_word_size = Tuple::need_in_words_from_elements(exit_message_elements);
+        let exit = Exit::linked(process.pid_term(), exception.reason);
 
         for linked_pid in process.linked_pid_set.lock().iter() {
             if let Some(linked_pid_arc_process) = pid_to_process(linked_pid) {
-                if linked_pid_arc_process.traps_exit() {
-                    match linked_pid_arc_process.try_acquire_heap() {
-                        Some(ref mut linked_pid_heap) => {
-                            if exit_message_word_size <= linked_pid_heap.heap_available() {
-                                let linked_pid_data = linked_pid_heap
-                                    .tuple_from_slice(exit_message_elements)
-                                    .unwrap();
-
-                                linked_pid_arc_process.send_from_self(linked_pid_data);
-                            } else {
-                                let (heap_fragment_data, heap_fragment) =
-                                    HeapFragment::tuple_from_slice(exit_message_elements).unwrap();
-
-                                linked_pid_arc_process
-                                    .send_heap_message(heap_fragment, heap_fragment_data);
-                            }
-                        }
-                        None => {
-                            let (heap_fragment_data, heap_fragment) =
-                                HeapFragment::tuple_from_slice(exit_message_elements).unwrap();
-
-                            linked_pid_arc_process
-                                .send_heap_message(heap_fragment, heap_fragment_data);
-                        }
-                    }
-                } else {
-                    // only tell the linked process to exit.  When it is run by its scheduler, it
-                    // will go through propagating its own exit.
-                    linked_pid_arc_process.exit();
-                }
+                // a linked process that doesn't trap exits is only told to exit.  When it is run
+                // by its scheduler, it will go through propagating its own exit.
+                send_exit_and_wake(&linked_pid_arc_process, exit).unwrap();
             }
         }
     }
 }
 
+/// Sends `data`, which is in `heap_fragment`, to `process` from a thread that isn't running a
+/// process, such as one reading standard input or a socket, and wakes `process` if it is waiting.
+pub fn send_heap_message_and_wake(
+    process: &Process,
+    heap_fragment: NonNull<HeapFragment>,
+    data: Term,
+) {
+    process.send_heap_message(heap_fragment, data);
+    wake(process);
+}
+
+/// Makes `process` runnable again if it is waiting, so that it sees a message or signal that was
+/// put in its mailbox without going through `send`.
+pub fn wake(process: &Process) {
+    let stop_waiting = {
+        let mut writable_status = process.status.write();
+
+        if *writable_status == Status::Waiting {
+            *writable_status = Status::Runnable;
+
+            true
+        } else {
+            false
+        }
+    };
+
+    if stop_waiting {
+        if let Some(arc_scheduler) = process.scheduler() {
+            arc_scheduler.stop_waiting(process);
+        }
+    }
+}
+
+/// Makes `process` exit with `reason` from a thread that isn't running a process, such as one
+/// reading a distribution connection, and wakes `process` if it is waiting so that its scheduler
+/// propagates the exit.  Like `kill`, the exit can't be trapped, but a process that is already
+/// exiting keeps its reason.
+pub fn exit_and_wake(process: &Process, reason: Term) -> Result<(), Alloc> {
+    send_exit_and_wake(
+        process,
+        Exit {
+            from: process.pid_term(),
+            reason,
+            trappable: false,
+        },
+    )
+}
+
+/// Delivers `exit` to `process`, in order with the messages sent to it, and wakes `process` if it
+/// is waiting, so that it receives `{'EXIT', From, Reason}` or its scheduler propagates its exit.
+pub fn send_exit_and_wake(process: &Process, exit: Exit) -> Result<(), Alloc> {
+    if process.send_exit(exit)? {
+        if let Some(arc_scheduler) = process.scheduler() {
+            arc_scheduler.stop_waiting(process);
+        }
+    }
+
+    Ok(())
+}
+
 pub fn register_in(
     arc_process: Arc<Process>,
     mut writable_registry: RwLockWriteGuard<HashMap<Atom, Registered>>,
@@ -161,6 +223,7 @@ pub fn init(minimum_heap_size: usize) -> Result<Process, Alloc> {
         heap,
         heap_size,
     );
+    process.set_creation_monotonic_time_milliseconds(monotonic::time_in_milliseconds());
 
     let frame = Frame::new(module_function_arity, code::init);
     process.push_frame(frame);
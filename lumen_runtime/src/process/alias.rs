@@ -0,0 +1,164 @@
+//! Aliases are references that messages can be sent to, as to a pid.  A message sent to an active
+//! alias is delivered to the process that made it, and one sent to an inactive alias is dropped.
+//!
+//! `monitor/3` with `{alias, Mode}` makes the reference of the monitor an alias, so a reply can
+//! only reach the process while it still waits for it.  How the alias is deactivated depends on
+//! `Mode`:
+//!
+//! * `explicit_unalias` - only by `unalias/1`.
+//! * `demonitor` - also when the monitor is removed, by `demonitor/1,2` or the `DOWN` message.
+//! * `reply_demonitor` - also when the first message is sent to the alias, which removes the
+//!   monitor too.
+//!
+//! All aliases of a process are deactivated when it exits.
+
+use std::convert::TryInto;
+use std::sync::Mutex;
+
+use hashbrown::{HashMap, HashSet};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception::runtime;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{Atom, Pid, Reference, Term};
+
+use crate::process::monitor;
+use crate::registry::pid_to_process;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+    ExplicitUnalias,
+    Demonitor,
+    ReplyDemonitor,
+}
+
+impl Mode {
+    pub fn try_from_term(term: Term) -> Result<Self, runtime::Exception> {
+        let atom: Atom = term.try_into()?;
+
+        match atom.name() {
+            "explicit_unalias" => Ok(Mode::ExplicitUnalias),
+            "demonitor" => Ok(Mode::Demonitor),
+            "reply_demonitor" => Ok(Mode::ReplyDemonitor),
+            _ => Err(badarg!()),
+        }
+    }
+}
+
+/// Makes `reference` an alias of `process`
+pub fn alias(process: &Process, reference: Reference, mode: Mode) {
+    let pid = process.pid();
+    let mut aliases = ALIASES.lock().unwrap();
+
+    aliases
+        .alias_by_reference
+        .insert(reference, Alias { pid, mode });
+    aliases
+        .references_by_pid
+        .entry(pid)
+        .or_insert_with(Default::default)
+        .insert(reference);
+}
+
+/// The monitor of `pid` with `reference` was removed, which deactivates its alias unless it is
+/// `explicit_unalias`
+pub fn demonitored(pid: Pid, reference: &Reference) {
+    ALIASES.lock().unwrap().remove_if(reference, |alias| {
+        alias.pid == pid && alias.mode != Mode::ExplicitUnalias
+    });
+}
+
+/// Deactivates the aliases of `process`, which has exited
+pub fn propagate_exit(process: &Process) {
+    let mut aliases = ALIASES.lock().unwrap();
+
+    if let Some(references) = aliases.references_by_pid.remove(&process.pid()) {
+        for reference in references {
+            aliases.alias_by_reference.remove(&reference);
+        }
+    }
+}
+
+/// The pid of the process that a message sent to `reference` is delivered to, or `None` if
+/// `reference` isn't an active alias.  A `reply_demonitor` alias is deactivated, and its monitor
+/// removed, by the message.
+pub fn sent_to(reference: &Reference) -> Option<Pid> {
+    let alias = ALIASES
+        .lock()
+        .unwrap()
+        .remove_if(reference, |alias| alias.mode == Mode::ReplyDemonitor);
+
+    match alias {
+        Some(Alias { pid, .. }) => {
+            if let Some(arc_process) = pid_to_process(&pid) {
+                monitor::demonitor(&arc_process, reference);
+            }
+
+            Some(pid)
+        }
+        None => ALIASES
+            .lock()
+            .unwrap()
+            .alias_by_reference
+            .get(reference)
+            .map(|alias| alias.pid),
+    }
+}
+
+/// Deactivates `reference` if it is an alias of `process`.  Returns whether it was.
+pub fn unalias(process: &Process, reference: &Reference) -> bool {
+    let pid = process.pid();
+
+    ALIASES
+        .lock()
+        .unwrap()
+        .remove_if(reference, |alias| alias.pid == pid)
+        .is_some()
+}
+
+// Private
+
+#[derive(Clone, Copy)]
+struct Alias {
+    pid: Pid,
+    mode: Mode,
+}
+
+#[derive(Default)]
+struct Aliases {
+    alias_by_reference: HashMap<Reference, Alias>,
+    /// So that the aliases of a process can be deactivated when it exits
+    references_by_pid: HashMap<Pid, HashSet<Reference>>,
+}
+
+impl Aliases {
+    fn remove_if<P>(&mut self, reference: &Reference, predicate: P) -> Option<Alias>
+    where
+        P: FnOnce(&Alias) -> bool,
+    {
+        let matches = match self.alias_by_reference.get(reference) {
+            Some(alias) => predicate(alias),
+            None => false,
+        };
+
+        if matches {
+            let alias = self.alias_by_reference.remove(reference).unwrap();
+
+            if let Some(references) = self.references_by_pid.get_mut(&alias.pid) {
+                references.remove(reference);
+
+                if references.is_empty() {
+                    self.references_by_pid.remove(&alias.pid);
+                }
+            }
+
+            Some(alias)
+        } else {
+            None
+        }
+    }
+}
+
+lazy_static! {
+    static ref ALIASES: Mutex<Aliases> = Default::default();
+}
@@ -0,0 +1,167 @@
+//! Sending messages to processes from Rust threads that aren't running a process, such as threads
+//! an embedder spawns for io, like `enif_send` with an environment from `enif_alloc_env` on the BEAM.
+//!
+//! Terms are built on the heap of an `Env`, which grows as needed, since such a thread has no
+//! process heap to build them on.  `Env::send` copies the message to the mailbox of the process, so
+//! the `Env` can be cleared and reused for the next message, and wakes the process if it is
+//! waiting, such as in `receive`.
+
+use core::cmp;
+use core::mem;
+use core::ptr::{self, NonNull};
+
+use alloc::vec::Vec;
+
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::HeapAlloc;
+use liblumen_alloc::erts::term::{Pid, Term};
+use liblumen_alloc::erts::HeapFragment;
+
+use crate::registry::pid_to_process;
+use crate::scheduler::Scheduled;
+
+/// The size, in words, of the first heap fragment of an `Env`
+const MINIMUM_FRAGMENT_WORD_SIZE: usize = 64;
+
+/// A heap, owned by a Rust thread, to build messages on.  Terms built on the `Env` are only valid
+/// until it is cleared or dropped.
+#[derive(Default)]
+pub struct Env {
+    heap_fragments: Vec<NonNull<HeapFragment>>,
+}
+
+impl Env {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Frees the terms built on this `Env`, so it can be reused for the next message
+    pub fn clear(&mut self) {
+        for non_null_heap_fragment in self.heap_fragments.drain(..) {
+            unsafe { ptr::drop_in_place(non_null_heap_fragment.as_ptr()) };
+        }
+    }
+
+    /// Sends `message` to the process with `pid`, waking it if it is waiting.
+    ///
+    /// Returns `Ok(false)` if there is no process with `pid`, as it has already exited, and
+    /// `Ok(true)` otherwise, even if the process is exiting and will never receive the message, the
+    /// same as `erlang:send/2`.
+    pub fn send(&self, pid: Pid, message: Term) -> Result<bool, Alloc> {
+        match pid_to_process(&pid) {
+            Some(ref destination_arc_process) if destination_arc_process.is_exiting() => Ok(true),
+            Some(destination_arc_process) => {
+                if destination_arc_process.send_from_other(message)? {
+                    if let Some(arc_scheduler) = destination_arc_process.scheduler() {
+                        arc_scheduler.stop_waiting(&destination_arc_process);
+                    }
+                }
+
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    unsafe fn push_heap_fragment(&mut self, need: usize) -> Result<&mut HeapFragment, Alloc> {
+        // double the size of each fragment, so that building a large term takes few fragments
+        let word_size = cmp::max(
+            need,
+            match self.heap_fragments.last() {
+                Some(last_non_null_heap_fragment) => {
+                    2 * last_non_null_heap_fragment.as_ref().size() / mem::size_of::<Term>()
+                }
+                None => MINIMUM_FRAGMENT_WORD_SIZE,
+            },
+        );
+        let non_null_heap_fragment = HeapFragment::new_from_word_size(word_size)?;
+        self.heap_fragments.push(non_null_heap_fragment);
+
+        Ok(&mut *non_null_heap_fragment.as_ptr())
+    }
+}
+
+impl Drop for Env {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl HeapAlloc for Env {
+    unsafe fn alloc(&mut self, need: usize) -> Result<NonNull<Term>, Alloc> {
+        if let Some(last_non_null_heap_fragment) = self.heap_fragments.last_mut() {
+            if let Ok(non_null_term) = last_non_null_heap_fragment.as_mut().alloc(need) {
+                return Ok(non_null_term);
+            }
+        }
+
+        self.push_heap_fragment(need)?.alloc(need)
+    }
+
+    fn is_owner<T>(&mut self, ptr: *const T) -> bool {
+        self.heap_fragments
+            .iter_mut()
+            .any(|non_null_heap_fragment| unsafe { non_null_heap_fragment.as_mut() }.is_owner(ptr))
+    }
+}
+
+// The heap fragments are only reachable through the `Env`, so it can move to another thread
+unsafe impl Send for Env {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::thread;
+
+    use liblumen_alloc::erts::process::Status;
+    use liblumen_alloc::erts::term::atom_unchecked;
+
+    use crate::scheduler::with_process_arc;
+    use crate::test::has_message;
+
+    #[test]
+    fn send_from_other_thread_wakes_waiting_process() {
+        with_process_arc(|arc_process| {
+            arc_process.wait();
+
+            let pid = arc_process.pid();
+            let sent = thread::spawn(move || {
+                let mut env = Env::new();
+                let list = env
+                    .list_from_slice(&[atom_unchecked("from"), atom_unchecked("thread")])
+                    .unwrap();
+                let message = env
+                    .tuple_from_slice(&[atom_unchecked("env"), list])
+                    .unwrap();
+
+                env.send(pid, message).unwrap()
+            })
+            .join()
+            .unwrap();
+
+            assert!(sent);
+            assert_eq!(*arc_process.status.read(), Status::Runnable);
+
+            let list = arc_process
+                .list_from_slice(&[atom_unchecked("from"), atom_unchecked("thread")])
+                .unwrap();
+            let message = arc_process
+                .tuple_from_slice(&[atom_unchecked("env"), list])
+                .unwrap();
+
+            assert!(has_message(&arc_process, message));
+        });
+    }
+
+    #[test]
+    fn terms_larger_than_a_heap_fragment_are_built_across_fragments() {
+        let mut env = Env::new();
+        let elements: Vec<Term> = (0..(4 * MINIMUM_FRAGMENT_WORD_SIZE))
+            .map(|_| atom_unchecked("element"))
+            .collect();
+        let list = env.list_from_slice(&elements).unwrap();
+
+        assert_eq!(list.list_iter().unwrap().count(), elements.len());
+    }
+}
@@ -0,0 +1,352 @@
+//! Collecting a process when `erlang:garbage_collect/0,1,2` asks for it, instead of when its heap
+//! is full.
+//!
+//! A process can only be collected when it isn't running, as the code running it holds terms that
+//! aren't roots, so a process that is running, including one collecting itself, is collected by
+//! the scheduler running it once it stops.  The same goes for a process with collections disabled,
+//! such as one waiting for a dirty native function, which is collected once it stops running after
+//! they are enabled again.
+//!
+//! The process that asked for a collection that had to wait is only replied to once it is done,
+//! or with `false` if the process exits first: a synchronous request waits for `wait_for_reply`
+//! to return whether the process was collected, and an `{async, RequestId}` request is sent
+//! `{garbage_collect, RequestId, Collected}`.
+
+use core::ptr::{self, NonNull};
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use hashbrown::HashMap;
+
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::alloc::heap_alloc::HeapAlloc;
+use liblumen_alloc::erts::process::{GcError, Process, ProcessFlags, Status};
+use liblumen_alloc::erts::term::{atom_unchecked, Pid, Term, Tuple};
+use liblumen_alloc::{CloneToProcess, HeapFragment};
+
+use crate::process::{max_heap_size, send_heap_message_and_wake, wake};
+use crate::registry::pid_to_process;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Type {
+    /// A full sweep, which also collects the old heap
+    Major,
+    /// A collection of the young heap, which becomes a full sweep if the old heap can't hold what
+    /// is tenured
+    Minor,
+}
+
+impl Default for Type {
+    fn default() -> Self {
+        Type::Major
+    }
+}
+
+/// How the process that asked for a collection is replied to once it is done
+#[derive(Clone, Copy)]
+pub enum Reply {
+    /// The process waits for `wait_for_reply` to return whether the process was collected
+    Wait,
+    /// `{garbage_collect, RequestId, Collected}` is sent to the process with the request id
+    Message(Term),
+}
+
+/// Collects `process`, which isn't `requester`, now if it isn't running, and returns whether it
+/// was, which it isn't if it is exiting.  If it is running, it is collected once it stops, and
+/// `None` is returned, as `requester` is then replied to with `reply`.
+pub fn request(
+    requester: &Process,
+    process: &Process,
+    gc_type: Type,
+    reply: Reply,
+) -> Result<Option<bool>, Alloc> {
+    // the request is pending before the status is checked, so that it is replied to by the
+    // scheduler if `process` stops running and is collected as soon as the status is unlocked
+    let id = pend(requester, process, reply)?;
+
+    // holding the status keeps `process` from starting to run until the collection is done.  The
+    // status is locked before the heap and mailbox that the collection locks (see
+    // `Process::status`).
+    let status = process.status.write();
+
+    let option_gc_result = match *status {
+        Status::Exiting(_) => None,
+        Status::Running => {
+            defer(process, gc_type);
+
+            return Ok(None);
+        }
+        _ if process.are_flags_set(ProcessFlags::DisableGC) => {
+            defer(process, gc_type);
+
+            return Ok(None);
+        }
+        _ => Some(sweep(process, gc_type)),
+    };
+
+    // enforcing the max heap size may kill `process`, which locks the status again, and the
+    // pending requests are never locked while a status is (see `wait_for_reply`)
+    drop(status);
+
+    // `process` may have exited, or been collected by its scheduler for another request, since
+    // the request was pending, in which case `requester` was already replied to
+    let is_pending = unpend(id);
+
+    let collected = match option_gc_result {
+        Some(gc_result) => {
+            enforce(process, gc_result)?;
+
+            true
+        }
+        None => false,
+    };
+
+    if is_pending {
+        Ok(Some(collected))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Collects the calling `process` once it stops running, as the terms it holds now may not be
+/// roots, and replies to it with `reply` once it is collected.
+pub fn request_own(process: &Process, gc_type: Type, reply: Reply) -> Result<(), Alloc> {
+    pend(process, process, reply)?;
+    defer(process, gc_type);
+
+    Ok(())
+}
+
+/// Whether the process that `process` asked to be collected with `Reply::Wait` was collected,
+/// once it is replied to.  Until then, `None` is returned and `process` waits to be woken by the
+/// reply.
+pub fn wait_for_reply(process: &Process) -> Option<bool> {
+    let mut requests = REQUESTS.lock().unwrap();
+
+    match requests.collected_by_requester.remove(&process.pid()) {
+        Some(collected) => Some(collected),
+        None => {
+            // the process waits while the requests are still locked, so that it is already waiting
+            // when it is replied to and woken
+            process.wait();
+
+            None
+        }
+    }
+}
+
+/// Collects `process` if `request` or `request_own` was called while it was running or had
+/// collections disabled, and replies to the requests.  Called by the scheduler once `process`
+/// stops running.
+pub fn collect_requested(process: &Process) -> Result<(), Alloc> {
+    if process.are_flags_set(ProcessFlags::ForceGC)
+        && !process.are_flags_set(ProcessFlags::DisableGC)
+    {
+        process.clear_flags(ProcessFlags::ForceGC);
+
+        // `NeedFullSweep` is still set if a major collection was requested
+        collect(process, Type::Minor)?;
+
+        reply(process.pid(), true)
+    } else {
+        Ok(())
+    }
+}
+
+/// Replies `false` to the requests for the exiting `process` that weren't replied to, as it
+/// won't be collected, and drops the requests that `process` is waiting for.
+pub fn propagate_exit(process: &Process) {
+    let pid = process.pid();
+
+    {
+        let mut requests = REQUESTS.lock().unwrap();
+        requests.pending.retain(|pending| pending.requester != pid);
+        requests.collected_by_requester.remove(&pid);
+    }
+
+    reply(pid, false).unwrap();
+}
+
+// Private
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Default)]
+struct Requests {
+    pending: Vec<Pending>,
+    /// The replies to the processes that waited with `Reply::Wait`, until `wait_for_reply` takes
+    /// them
+    collected_by_requester: HashMap<Pid, bool>,
+}
+
+/// A request for a process to be collected once it stops running
+struct Pending {
+    id: u64,
+    requester: Pid,
+    /// The process to be collected
+    pid: Pid,
+    reply: PendingReply,
+}
+
+enum PendingReply {
+    Wait,
+    Message(RequestId),
+}
+
+/// The request id of an `{async, RequestId}` request, copied to a heap fragment, as the process
+/// that asked may be collected before it is replied to
+struct RequestId {
+    term: Term,
+    heap_fragment: NonNull<HeapFragment>,
+}
+
+impl RequestId {
+    fn new(request_id: Term) -> Result<Self, Alloc> {
+        let (term, heap_fragment) = request_id.clone_to_fragment()?;
+
+        Ok(Self {
+            term,
+            heap_fragment,
+        })
+    }
+
+    /// `{garbage_collect, RequestId, Collected}` in a heap fragment of its own, so that it can be
+    /// sent from any thread
+    fn message(&self, collected: bool) -> Result<(Term, NonNull<HeapFragment>), Alloc> {
+        let need_in_words = Tuple::need_in_words_from_len(3) + self.term.size_in_words();
+        let mut non_null_heap_fragment =
+            unsafe { HeapFragment::new_from_word_size(need_in_words)? };
+        let heap_fragment = unsafe { non_null_heap_fragment.as_mut() };
+
+        let request_id = self.term.clone_to_heap(heap_fragment)?;
+        let message = heap_fragment.tuple_from_slice(&[
+            atom_unchecked("garbage_collect"),
+            request_id,
+            collected.into(),
+        ])?;
+
+        Ok((message, non_null_heap_fragment))
+    }
+}
+
+impl Drop for RequestId {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.heap_fragment.as_ptr()) }
+    }
+}
+
+// The heap fragment is only read by the pending request that owns it
+unsafe impl Send for RequestId {}
+
+fn pend(requester: &Process, process: &Process, reply: Reply) -> Result<u64, Alloc> {
+    let reply = match reply {
+        Reply::Wait => PendingReply::Wait,
+        Reply::Message(request_id) => PendingReply::Message(RequestId::new(request_id)?),
+    };
+    let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+
+    REQUESTS.lock().unwrap().pending.push(Pending {
+        id,
+        requester: requester.pid(),
+        pid: process.pid(),
+        reply,
+    });
+
+    Ok(id)
+}
+
+/// Removes the request with `id` unless it was replied to.  Returns whether it was pending.
+fn unpend(id: u64) -> bool {
+    let mut requests = REQUESTS.lock().unwrap();
+
+    match requests.pending.iter().position(|pending| pending.id == id) {
+        Some(index) => {
+            requests.pending.remove(index);
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Replies to the pending requests for the process with `pid` to be collected
+fn reply(pid: Pid, collected: bool) -> Result<(), Alloc> {
+    let replied: Vec<Pending> = {
+        let mut requests = REQUESTS.lock().unwrap();
+        let (replied, pending): (Vec<Pending>, Vec<Pending>) = requests
+            .pending
+            .drain(..)
+            .partition(|pending| pending.pid == pid);
+        requests.pending = pending;
+
+        for pending in &replied {
+            if let PendingReply::Wait = pending.reply {
+                requests
+                    .collected_by_requester
+                    .insert(pending.requester, collected);
+            }
+        }
+
+        replied
+    };
+
+    // the requesters are woken once the requests are unlocked, as waking locks their status
+    for pending in replied {
+        if let Some(requester_arc_process) = pid_to_process(&pending.requester) {
+            match pending.reply {
+                PendingReply::Wait => wake(&requester_arc_process),
+                PendingReply::Message(ref request_id) => {
+                    let (message, heap_fragment) = request_id.message(collected)?;
+
+                    send_heap_message_and_wake(&requester_arc_process, heap_fragment, message);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn defer(process: &Process, gc_type: Type) {
+    let flags = match gc_type {
+        Type::Major => ProcessFlags::ForceGC | ProcessFlags::NeedFullSweep,
+        Type::Minor => ProcessFlags::ForceGC,
+    };
+
+    process.set_flags(flags);
+}
+
+fn collect(process: &Process, gc_type: Type) -> Result<(), Alloc> {
+    let gc_result = sweep(process, gc_type);
+
+    enforce(process, gc_result)
+}
+
+fn sweep(process: &Process, gc_type: Type) -> Result<usize, GcError> {
+    if gc_type == Type::Major {
+        process.set_flags(ProcessFlags::NeedFullSweep);
+    }
+
+    match process.garbage_collect(0, &mut []) {
+        Err(GcError::FullsweepRequired) => {
+            process.set_flags(ProcessFlags::NeedFullSweep);
+
+            process.garbage_collect(0, &mut [])
+        }
+        result => result,
+    }
+}
+
+fn enforce(process: &Process, gc_result: Result<usize, GcError>) -> Result<(), Alloc> {
+    match max_heap_size::enforce(process, gc_result) {
+        Ok(_) => Ok(()),
+        Err(GcError::Alloc(alloc)) => Err(alloc),
+        // the process was killed for exceeding its max heap size, or the heap is left as it was
+        Err(GcError::MaxHeapSizeExceeded) | Err(GcError::FullsweepRequired) => Ok(()),
+    }
+}
+
+lazy_static! {
+    static ref REQUESTS: Mutex<Requests> = Default::default();
+}
@@ -0,0 +1,214 @@
+//! The maximum number of processes that can be alive at once, as set with `+P` on the BEAM.
+//!
+//! `spawn/3`, `spawn_link/3` and `spawn_opt/4` fail with `system_limit` instead of spawning past the
+//! maximum, so that code that spawns without bound can't use up the memory of the host.  An
+//! embedder can also `set_approaching` a callback that is called when the number of processes
+//! reaches a percentage of the maximum, so it can shed load before spawns start failing.  The
+//! callback is called again only after the number of processes has dropped below that percentage.
+//!
+//! Live processes are counted as they are entered into and removed from the pid table, so checking
+//! the maximum doesn't go through every process.  Processes spawned by the runtime
+//! itself, such as `init` and io servers, count towards the maximum, but are always spawned.
+
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use liblumen_core::locks::RwLock;
+
+/// The maximum number of processes when it isn't set, the same as the BEAM
+pub const DEFAULT_MAX: usize = 262_144;
+
+/// Called with the number of processes and the maximum when the number of processes reaches the
+/// approaching percentage of the maximum
+pub type Approaching = fn(count: usize, max: usize);
+
+pub fn max() -> usize {
+    MAX.load(Ordering::SeqCst)
+}
+
+/// Sets the maximum number of processes.  Processes that are already alive past a lower maximum
+/// keep running, but no more can be spawned until enough have exited.
+pub fn set_max(max: usize) {
+    assert!(0 < max, "maximum number of processes must be positive");
+
+    MAX.store(max, Ordering::SeqCst);
+}
+
+/// Calls `approaching` when the number of processes reaches `percent` of the maximum, or stops
+/// calling any callback if `approaching` is `None`.
+pub fn set_approaching(approaching: Option<Approaching>, percent: usize) {
+    assert!(
+        0 < percent && percent <= 100,
+        "approaching percentage must be between 1 and 100"
+    );
+
+    *RW_LOCK_APPROACHING.write() = approaching.map(|approaching| (approaching, percent));
+    IS_APPROACHING.store(false, Ordering::SeqCst);
+}
+
+/// The number of processes that are alive, counting the places reserved for processes being
+/// spawned
+pub fn count() -> usize {
+    COUNT.load(Ordering::SeqCst)
+}
+
+/// Reserves a place for a process that is about to be spawned, unless that would go past the
+/// maximum.  The place counts as a process until `unreserve`, so processes spawned at the same
+/// time on different schedulers can't go past the maximum together.
+pub fn reserve() -> bool {
+    let max = max();
+    let option_approaching = *RW_LOCK_APPROACHING.read();
+    let approaching_at = match option_approaching {
+        Some((_, percent)) => approaching_at(max, percent),
+        None => max,
+    };
+    let mut count = count();
+
+    loop {
+        if !is_allowed(
+            count,
+            max,
+            approaching_at,
+            &IS_APPROACHING,
+            option_approaching.map(|(approaching, _)| approaching),
+        ) {
+            break false;
+        }
+
+        match COUNT.compare_exchange(count, count + 1, Ordering::SeqCst, Ordering::SeqCst) {
+            Ok(_) => break true,
+            Err(current) => count = current,
+        }
+    }
+}
+
+/// Releases a place from `reserve` once the process has been spawned, and so counts itself, or
+/// has failed to spawn
+pub fn unreserve() {
+    COUNT.fetch_sub(1, Ordering::SeqCst);
+}
+
+/// Counts a process entered into the pid table
+pub(crate) fn spawned() {
+    COUNT.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Stops counting a process removed from the pid table when it exited
+pub(crate) fn exited() {
+    COUNT.fetch_sub(1, Ordering::SeqCst);
+}
+
+// Private
+
+fn approaching_at(max: usize, percent: usize) -> usize {
+    // at least 1, so that the callback isn't called without any processes
+    ((max * percent) / 100).max(1)
+}
+
+fn is_allowed(
+    count: usize,
+    max: usize,
+    approaching_at: usize,
+    is_approaching: &AtomicBool,
+    option_approaching: Option<Approaching>,
+) -> bool {
+    if count < approaching_at {
+        is_approaching.store(false, Ordering::SeqCst);
+    } else if let Some(approaching) = option_approaching {
+        if !is_approaching.swap(true, Ordering::SeqCst) {
+            approaching(count, max);
+        }
+    }
+
+    count < max
+}
+
+static MAX: AtomicUsize = AtomicUsize::new(DEFAULT_MAX);
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+static IS_APPROACHING: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    static ref RW_LOCK_APPROACHING: RwLock<Option<(Approaching, usize)>> = RwLock::new(None);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn below_max_is_allowed() {
+        let is_approaching = AtomicBool::new(false);
+
+        assert!(is_allowed(9, 10, 10, &is_approaching, None));
+    }
+
+    #[test]
+    fn at_max_is_not_allowed() {
+        let is_approaching = AtomicBool::new(false);
+
+        assert!(!is_allowed(10, 10, 10, &is_approaching, None));
+        assert!(!is_allowed(11, 10, 10, &is_approaching, None));
+    }
+
+    #[test]
+    fn approaching_is_called_once_until_count_drops_below_approaching_at() {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        fn approaching(count: usize, max: usize) {
+            assert_eq!(max, 10);
+            assert!(8 <= count);
+
+            CALLS.fetch_add(1, Ordering::SeqCst);
+        }
+
+        let is_approaching = AtomicBool::new(false);
+        let approaching_at = approaching_at(10, 80);
+
+        assert!(is_allowed(
+            7,
+            10,
+            approaching_at,
+            &is_approaching,
+            Some(approaching)
+        ));
+        assert_eq!(CALLS.load(Ordering::SeqCst), 0);
+
+        assert!(is_allowed(
+            8,
+            10,
+            approaching_at,
+            &is_approaching,
+            Some(approaching)
+        ));
+        assert!(is_allowed(
+            9,
+            10,
+            approaching_at,
+            &is_approaching,
+            Some(approaching)
+        ));
+        assert!(!is_allowed(
+            10,
+            10,
+            approaching_at,
+            &is_approaching,
+            Some(approaching)
+        ));
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+
+        assert!(is_allowed(
+            7,
+            10,
+            approaching_at,
+            &is_approaching,
+            Some(approaching)
+        ));
+        assert!(is_allowed(
+            8,
+            10,
+            approaching_at,
+            &is_approaching,
+            Some(approaching)
+        ));
+        assert_eq!(CALLS.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,61 @@
+//! Enforcing the `max_heap_size` process flag, which is checked when a process is collected, so
+//! that a runaway process is killed before it takes the memory of every other process.
+
+use liblumen_alloc::erts::process::{GcError, MaxHeapSize, Process};
+use liblumen_alloc::erts::term::atom_unchecked;
+use liblumen_alloc::exit;
+
+use crate::logging;
+
+/// Applies the max heap size of `process` to `gc_result`, the result of collecting `process`.
+///
+/// When the collection would exceed the max heap size of a process that is killed for it, the
+/// collector refuses to collect, and `process` exits with `killed`.  Its max heap size is then
+/// cleared, so that the collection can be retried for the code running `process` to get back to
+/// the scheduler, which doesn't run exiting processes again.  A process that isn't killed is
+/// collected anyway.  Either way, the error logger is told if the max heap size says to tell it.
+pub fn enforce(process: &Process, gc_result: Result<usize, GcError>) -> Result<usize, GcError> {
+    let max_heap_size = process.max_heap_size();
+
+    match gc_result {
+        Ok(reductions) => {
+            let heap_size = process.heap_size();
+
+            if max_heap_size.error_logger && max_heap_size.is_exceeded_by(heap_size) {
+                report(process, heap_size, max_heap_size);
+            }
+
+            Ok(reductions)
+        }
+        Err(GcError::MaxHeapSizeExceeded) => {
+            if max_heap_size.error_logger {
+                report(process, process.heap_size(), max_heap_size);
+            }
+
+            process.set_max_heap_size(Default::default());
+            process.exception(exit!(atom_unchecked("killed")));
+
+            Err(GcError::MaxHeapSizeExceeded)
+        }
+        Err(gc_error) => Err(gc_error),
+    }
+}
+
+// Private
+
+fn report(process: &Process, heap_size: usize, max_heap_size: MaxHeapSize) {
+    logging::report_error(
+        module_path!(),
+        &format!(
+            "** (max_heap_size of {}) heap of {} words exceeds the maximum of {} words{}",
+            process,
+            heap_size,
+            max_heap_size.size,
+            if max_heap_size.kill {
+                ", so it is killed"
+            } else {
+                ""
+            }
+        ),
+    );
+}
@@ -9,7 +9,10 @@ use liblumen_alloc::erts::term::{
 use liblumen_alloc::erts::Message;
 use liblumen_alloc::{CloneToProcess, HeapFragment};
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::distribution;
 use crate::otp::erlang::node_0;
+use crate::process::{alias, wake};
 use crate::registry::pid_to_process;
 
 pub fn is_down(message: &Message, reference: &Reference) -> bool {
@@ -32,11 +35,40 @@ pub fn is_down(message: &Message, reference: &Reference) -> bool {
     }
 }
 
+/// Removes the monitor by `monitoring_process` with `reference`, of a local or remote process,
+/// returning whether there was one
+pub fn demonitor(monitoring_process: &Process, reference: &Reference) -> bool {
+    let demonitored = match monitoring_process.demonitor(reference) {
+        Some(monitored_pid) => {
+            if let Some(monitored_arc_process) = pid_to_process(&monitored_pid) {
+                if let Some(monitoring_pid) = monitored_arc_process.demonitored(reference) {
+                    assert_eq!(monitoring_process.pid(), monitoring_pid);
+                }
+            }
+
+            true
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        None => distribution::monitor::demonitor(monitoring_process, reference),
+        #[cfg(target_arch = "wasm32")]
+        None => false,
+    };
+
+    if demonitored {
+        alias::demonitored(monitoring_process.pid(), reference);
+    }
+
+    demonitored
+}
+
 pub fn propagate_exit(process: &Process, exception: &runtime::Exception) {
     let info = exception.reason;
 
     for (reference, monitor) in process.monitor_by_reference.lock().iter() {
-        if let Some(monitoring_pid_arc_process) = pid_to_process(&monitor.monitoring_pid()) {
+        let monitoring_pid = *monitor.monitoring_pid();
+        alias::demonitored(monitoring_pid, reference);
+
+        if let Some(monitoring_pid_arc_process) = pid_to_process(&monitoring_pid) {
             let down_message_need_in_words = down_need_in_words(monitor, info);
 
             match monitoring_pid_arc_process.try_acquire_heap() {
@@ -68,6 +100,8 @@ pub fn propagate_exit(process: &Process, exception: &runtime::Exception) {
                     );
                 }
             }
+
+            wake(&monitoring_pid_arc_process);
         }
     }
 }
@@ -83,7 +117,7 @@ fn down<A: HeapAlloc>(
     monitor: &Monitor,
     info: Term,
 ) -> Term {
-    let tag = down_tag();
+    let tag = monitor.tag().term().clone_to_heap(heap).unwrap();
     let reference_term = reference.clone_to_heap(heap).unwrap();
     let r#type = atom_unchecked("process");
     let identifier = identifier(process, monitor, heap);
@@ -97,17 +131,13 @@ fn down_need_in_words(monitor: &Monitor, info: Term) -> usize {
     let identifier_need_in_words = identifier_need_in_words(monitor);
 
     Tuple::need_in_words_from_len(DOWN_LEN)
-        + Atom::SIZE_IN_WORDS
+        + monitor.tag().term().size_in_words()
         + Reference::need_in_words()
         + Atom::SIZE_IN_WORDS
         + identifier_need_in_words
         + info.size_in_words()
 }
 
-fn down_tag() -> Term {
-    atom_unchecked("DOWN")
-}
-
 fn identifier<A: HeapAlloc>(process: &Process, monitor: &Monitor, heap: &mut A) -> Term {
     match monitor {
         Monitor::Pid { .. } => process.pid_term(),
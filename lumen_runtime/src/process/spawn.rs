@@ -39,6 +39,16 @@ pub fn apply_3(
         heap_arguments,
     )?;
 
+    // the argument list is only walked when it is traced
+    if parent_process.trace().is_some() {
+        let argument_vec: Vec<Term> = match heap_arguments.to_typed_term().unwrap() {
+            TypedTerm::List(cons) => cons.into_iter().map(|result| result.unwrap()).collect(),
+            _ => Vec::new(),
+        };
+
+        parent_process.trace_spawn(&child_process, module, function, &argument_vec);
+    }
+
     // Connect after placing frame, so that any logging can show the `Frame`s when connections occur
     options.connect(Some(&parent_process), &child_process);
 
@@ -67,6 +77,10 @@ pub fn code(
     let frame = Frame::new(child_process.initial_module_function_arity.clone(), code);
     child_process.push_frame(frame);
 
+    if let Some(parent_process) = parent_process {
+        parent_process.trace_spawn(&child_process, module, function, &arguments);
+    }
+
     // Connect after placing frame, so that any logging can show the `Frame`s when connections occur
     options.connect(parent_process, &child_process);
 
@@ -3,44 +3,14 @@ use std::sync::Arc;
 
 use liblumen_alloc::erts::exception::system::Alloc;
 use liblumen_alloc::erts::exception::Exception;
-use liblumen_alloc::erts::process::alloc::{default_heap_size, heap, next_heap_size};
-use liblumen_alloc::erts::process::{Priority, Process};
+use liblumen_alloc::erts::process::alloc::{default_heap_size, heap};
+use liblumen_alloc::erts::process::{
+    heap_sizing, HeapSizing, MaxHeapSize, MessageQueueData, Priority, Process,
+};
 use liblumen_alloc::erts::term::{Atom, Boxed, Cons, Term, Tuple, TypedTerm};
 use liblumen_alloc::{badarg, ModuleFunctionArity};
 
-#[allow(dead_code)]
-#[derive(Clone, Copy)]
-pub struct MaxHeapSize {
-    size: Option<usize>,
-    kill: Option<bool>,
-    error_logger: Option<bool>,
-}
-
-#[derive(Clone, Copy)]
-pub enum MessageQueueData {
-    OnHeap,
-    OffHeap,
-}
-
-impl Default for MessageQueueData {
-    fn default() -> Self {
-        MessageQueueData::OnHeap
-    }
-}
-
-impl TryFrom<Term> for MessageQueueData {
-    type Error = Exception;
-
-    fn try_from(term: Term) -> Result<Self, Self::Error> {
-        let atom: Atom = term.try_into()?;
-
-        match atom.name() {
-            "off_heap" => Ok(Self::OffHeap),
-            "on_heap" => Ok(Self::OnHeap),
-            _ => Err(badarg!().into()),
-        }
-    }
-}
+use crate::time::monotonic;
 
 #[derive(Clone, Copy)]
 pub struct Options {
@@ -53,13 +23,15 @@ pub struct Options {
     pub min_heap_size: Option<usize>,
     pub min_bin_vheap_size: Option<usize>,
     pub max_heap_size: Option<MaxHeapSize>,
+    /// When heap sizing is not set, the process uses the VM's heap sizing
+    pub heap_sizing: Option<HeapSizing>,
     pub message_queue_data: MessageQueueData,
 }
 
 impl Options {
     pub fn connect(&self, parent_process: Option<&Process>, child_process: &Process) {
         if self.link {
-            parent_process.unwrap().link(child_process)
+            parent_process.unwrap().link(child_process);
         }
 
         if self.monitor {
@@ -93,6 +65,25 @@ impl Options {
             heap,
             heap_size,
         );
+        process.set_creation_monotonic_time_milliseconds(monotonic::time_in_milliseconds());
+
+        if let Some(min_bin_vheap_size) = self.min_bin_vheap_size {
+            process.set_min_bin_vheap_size(min_bin_vheap_size);
+        }
+
+        if let Some(max_heap_size) = self.max_heap_size {
+            process.set_max_heap_size(max_heap_size);
+        }
+
+        if let Some(heap_sizing) = self.heap_sizing {
+            process.set_heap_sizing(heap_sizing);
+        }
+
+        process.set_message_queue_data(self.message_queue_data);
+
+        if let Some(parent_process) = parent_process {
+            process.set_group_leader_pid(parent_process.group_leader_pid());
+        }
 
         Ok(process)
     }
@@ -109,10 +100,17 @@ impl Options {
         }
     }
 
+    fn cascaded_heap_sizing(&self) -> HeapSizing {
+        match self.heap_sizing {
+            Some(heap_sizing) => heap_sizing,
+            None => heap_sizing(),
+        }
+    }
+
     /// `heap` size in words.
     fn heap_size(&self) -> usize {
         match self.min_heap_size {
-            Some(min_heap_size) => next_heap_size(min_heap_size),
+            Some(min_heap_size) => self.cascaded_heap_sizing().next_heap_size(min_heap_size),
             None => default_heap_size(),
         }
     }
@@ -163,7 +161,22 @@ impl Options {
                         }
                         Err(_) => false,
                     },
-                    "max_heap_size" => unimplemented!(),
+                    "heap_sizing" => match tuple[1].try_into() {
+                        Ok(heap_sizing) => {
+                            self.heap_sizing = Some(heap_sizing);
+
+                            true
+                        }
+                        Err(_) => false,
+                    },
+                    "max_heap_size" => match tuple[1].try_into() {
+                        Ok(max_heap_size) => {
+                            self.max_heap_size = Some(max_heap_size);
+
+                            true
+                        }
+                        Err(_) => false,
+                    },
                     "message_queue_data" => match tuple[1].try_into() {
                         Ok(message_queue_data) => {
                             self.message_queue_data = message_queue_data;
@@ -214,6 +227,7 @@ impl Default for Options {
             min_heap_size: None,
             min_bin_vheap_size: None,
             max_heap_size: None,
+            heap_sizing: None,
             message_queue_data: Default::default(),
         }
     }
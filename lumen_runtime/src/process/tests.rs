@@ -0,0 +1,384 @@
+//! Messages sent by a process before it exits are received before the `DOWN` and `EXIT` signals
+//! for its exit, and a monitor or link that races the exit either gets its signal or fails with
+//! `noproc`, but is never left without one.  Whether an exit signal is trapped depends on the
+//! receiver when it is delivered, and one to a process that is already exiting doesn't change its
+//! reason.
+
+use super::*;
+
+use std::convert::TryInto;
+use std::sync::mpsc::channel;
+use std::sync::Arc;
+use std::thread;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::code;
+use liblumen_alloc::erts::process::signal::Exit;
+use liblumen_alloc::erts::term::{atom_unchecked, AsTerm, Boxed, Pid, Tuple};
+use liblumen_alloc::exit;
+
+use crate::otp::erlang::{link_1, monitor_2};
+use crate::send::send;
+use crate::test::{has_no_message, receive_message};
+
+#[test]
+fn messages_sent_before_exit_are_received_before_down_and_exit() {
+    let init_arc_process = test_init();
+    let monitoring_arc_process = test(&init_arc_process);
+    let linked_arc_process = test(&init_arc_process);
+    linked_arc_process.trap_exit(true);
+    let exiting_arc_process = test(&init_arc_process);
+
+    let reference = monitor(&monitoring_arc_process, exiting_arc_process.pid_term()).unwrap();
+    assert!(linked_arc_process.link(&exiting_arc_process));
+
+    let messages: Vec<Term> = (0..MESSAGES)
+        .map(|i| exiting_arc_process.integer(i).unwrap())
+        .collect();
+
+    for message in &messages {
+        for destination_arc_process in &[&monitoring_arc_process, &linked_arc_process] {
+            send(
+                destination_arc_process.pid_term(),
+                *message,
+                Default::default(),
+                &exiting_arc_process,
+            )
+            .unwrap();
+        }
+    }
+
+    let reason = atom_unchecked("stress");
+    exit(&exiting_arc_process, reason);
+
+    let exiting_pid_term = exiting_arc_process.pid_term();
+
+    for message in &messages {
+        assert_eq!(receive_message(&monitoring_arc_process), Some(*message));
+    }
+
+    assert_eq!(
+        receive_message(&monitoring_arc_process),
+        Some(
+            monitoring_arc_process
+                .tuple_from_slice(&[
+                    atom_unchecked("DOWN"),
+                    reference,
+                    atom_unchecked("process"),
+                    exiting_pid_term,
+                    reason
+                ])
+                .unwrap()
+        )
+    );
+    assert!(has_no_message(&monitoring_arc_process));
+
+    for message in &messages {
+        assert_eq!(receive_message(&linked_arc_process), Some(*message));
+    }
+
+    assert_eq!(
+        receive_message(&linked_arc_process),
+        Some(
+            linked_arc_process
+                .tuple_from_slice(&[atom_unchecked("EXIT"), exiting_pid_term, reason])
+                .unwrap()
+        )
+    );
+    assert!(has_no_message(&linked_arc_process));
+}
+
+#[test]
+fn exit_propagated_by_scheduler_wakes_waiting_monitoring_process() {
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = test_init();
+    let waiting_arc_process = Scheduler::spawn_code(
+        &init_arc_process,
+        Default::default(),
+        test::r#loop::module(),
+        Atom::try_from_str("wait").unwrap(),
+        vec![],
+        wait,
+    )
+    .unwrap();
+
+    assert!(arc_scheduler.run_through(&waiting_arc_process));
+    assert_eq!(*waiting_arc_process.status.read(), Status::Waiting);
+
+    let exiting_arc_process = test(&init_arc_process);
+    monitor(&waiting_arc_process, exiting_arc_process.pid_term()).unwrap();
+
+    exit_and_wake(&exiting_arc_process, atom_unchecked("stress")).unwrap();
+
+    // the exit is propagated when the scheduler next dequeues `exiting_arc_process`
+    let woken = (0..RUNS).any(|_| {
+        let _ = arc_scheduler.run_once();
+
+        arc_scheduler.is_run_queued(&waiting_arc_process)
+    });
+
+    assert!(woken);
+    assert!(!has_no_message(&waiting_arc_process));
+}
+
+#[test]
+fn send_to_exiting_process_is_dropped() {
+    let init_arc_process = test_init();
+    let sending_arc_process = test(&init_arc_process);
+    let exiting_arc_process = test(&init_arc_process);
+
+    exiting_arc_process.exception(exit!(atom_unchecked("stress")));
+
+    send(
+        exiting_arc_process.pid_term(),
+        atom_unchecked("dropped"),
+        Default::default(),
+        &sending_arc_process,
+    )
+    .unwrap();
+
+    assert!(has_no_message(&exiting_arc_process));
+}
+
+#[test]
+fn exit_signal_to_exiting_process_keeps_first_reason() {
+    let init_arc_process = test_init();
+    let exiting_arc_process = test(&init_arc_process);
+
+    let first_reason = atom_unchecked("first");
+    exiting_arc_process.exception(exit!(first_reason));
+
+    exit_and_wake(&exiting_arc_process, atom_unchecked("second")).unwrap();
+
+    match *exiting_arc_process.status.read() {
+        Status::Exiting(ref exception) => assert_eq!(exception.reason, first_reason),
+        ref status => panic!("{:?} is not exiting", status),
+    };
+}
+
+#[test]
+fn exit_signal_is_trapped_if_trapping_when_delivered() {
+    let init_arc_process = test_init();
+    let sending_arc_process = test(&init_arc_process);
+    let trapping_arc_process = test(&init_arc_process);
+    let reason = atom_unchecked("stress");
+
+    send_exit_and_wake(
+        &trapping_arc_process,
+        Exit::linked(sending_arc_process.pid_term(), atom_unchecked("normal")),
+    )
+    .unwrap();
+
+    assert!(!trapping_arc_process.is_exiting());
+    assert!(has_no_message(&trapping_arc_process));
+
+    trapping_arc_process.trap_exit(true);
+
+    send_exit_and_wake(
+        &trapping_arc_process,
+        Exit::linked(sending_arc_process.pid_term(), reason),
+    )
+    .unwrap();
+
+    assert!(!trapping_arc_process.is_exiting());
+    assert_eq!(
+        receive_message(&trapping_arc_process),
+        Some(
+            trapping_arc_process
+                .tuple_from_slice(&[
+                    atom_unchecked("EXIT"),
+                    sending_arc_process.pid_term(),
+                    reason
+                ])
+                .unwrap()
+        )
+    );
+}
+
+#[test]
+fn kill_is_not_trapped() {
+    let init_arc_process = test_init();
+    let sending_arc_process = test(&init_arc_process);
+    let trapping_arc_process = test(&init_arc_process);
+    trapping_arc_process.trap_exit(true);
+
+    send_exit_and_wake(
+        &trapping_arc_process,
+        Exit::exit2(sending_arc_process.pid_term(), atom_unchecked("kill")),
+    )
+    .unwrap();
+
+    assert!(has_no_message(&trapping_arc_process));
+
+    match *trapping_arc_process.status.read() {
+        Status::Exiting(ref exception) => {
+            assert_eq!(exception.reason, atom_unchecked("killed"))
+        }
+        ref status => panic!("{:?} is not exiting", status),
+    };
+}
+
+#[test]
+fn monitor_racing_exit_gets_one_down_after_messages() {
+    race_exits(|process, pid_term| {
+        let reference = monitor(process, pid_term).unwrap();
+
+        Some(reference)
+    });
+}
+
+#[test]
+fn link_racing_exit_gets_exit_after_messages_or_noproc() {
+    race_exits(|process, pid_term| {
+        process.trap_exit(true);
+
+        match link_1::native(process, pid_term) {
+            Ok(_) => Some(Term::NIL),
+            Err(exception::Exception::Runtime(runtime_exception)) => {
+                assert_eq!(runtime_exception.reason, atom_unchecked("noproc"));
+
+                None
+            }
+            Err(exception) => panic!("{:?}", exception),
+        }
+    })
+}
+
+const MESSAGES: isize = 8;
+const RUNS: usize = 1_000;
+const RACES: usize = 256;
+
+/// Races `signal`, on another thread and scheduler, against processes exiting right after each
+/// sends a message.  `signal` returns the reference of a monitor, `NIL` for a link, or `None` if
+/// the process had already exited and no signal will be received for it.
+fn race_exits<S>(signal: S)
+where
+    S: Fn(&Process, Term) -> Option<Term> + Send + 'static,
+{
+    let (pid_sender, pid_receiver) = channel::<Pid>();
+    let (receiving_pid_sender, receiving_pid_receiver) = channel::<Pid>();
+    let (done_sender, done_receiver) = channel::<()>();
+
+    let receiving = thread::spawn(move || {
+        let init_arc_process = test_init();
+        let receiving_arc_process = test(&init_arc_process);
+        receiving_pid_sender
+            .send(receiving_arc_process.pid())
+            .unwrap();
+
+        let signalled: Vec<(Pid, Option<Term>)> = pid_receiver
+            .iter()
+            .map(|pid| {
+                let pid_term = unsafe { pid.as_term() };
+
+                (pid, signal(&receiving_arc_process, pid_term))
+            })
+            .collect();
+
+        // the exiting thread has propagated all of its exits
+        done_receiver.recv().unwrap();
+
+        check_order(&receiving_arc_process, &signalled);
+    });
+
+    let init_arc_process = test_init();
+    let receiving_pid_term = unsafe { receiving_pid_receiver.recv().unwrap().as_term() };
+
+    for i in 0..RACES {
+        let exiting_arc_process = test(&init_arc_process);
+        pid_sender.send(exiting_arc_process.pid()).unwrap();
+
+        let message = exiting_arc_process.integer(i).unwrap();
+        send(
+            receiving_pid_term,
+            message,
+            Default::default(),
+            &exiting_arc_process,
+        )
+        .unwrap();
+
+        exit(&exiting_arc_process, atom_unchecked("stress"));
+    }
+
+    drop(pid_sender);
+    done_sender.send(()).unwrap();
+    receiving.join().unwrap();
+}
+
+/// Checks that `receiving_process` received exactly one signal for each process in `signalled`
+/// that it expects one from, and that each signal came after the message from that process.
+fn check_order(receiving_process: &Process, signalled: &[(Pid, Option<Term>)]) {
+    let mut message_count = 0;
+    let mut signal_count = 0;
+    let mut messaged_pids: Vec<Pid> = Vec::new();
+    let mut signalled_pids: Vec<Pid> = Vec::new();
+
+    while let Some(message) = receive_message(receiving_process) {
+        if message.is_integer() {
+            let index: usize = message.try_into().unwrap();
+            messaged_pids.push(signalled[index].0);
+            message_count += 1;
+
+            continue;
+        }
+
+        let tuple: Boxed<Tuple> = message.try_into().unwrap();
+        let (from, reason) = match tuple.len() {
+            // {'DOWN', Reference, process, Pid, Reason}
+            5 => (tuple[3], tuple[4]),
+            // {'EXIT', Pid, Reason}
+            3 => (tuple[1], tuple[2]),
+            len => panic!("{} is not a DOWN or EXIT message ({})", message, len),
+        };
+        let from_pid: Pid = from.try_into().unwrap();
+
+        assert!(
+            messaged_pids.contains(&from_pid),
+            "{} was received before the message from {}",
+            message,
+            from
+        );
+        assert!(!signalled_pids.contains(&from_pid));
+        assert!(reason == atom_unchecked("stress") || reason == atom_unchecked("noproc"));
+
+        let (_, expected) = signalled.iter().find(|(pid, _)| pid == &from_pid).unwrap();
+
+        match expected {
+            Some(reference) if reference.is_reference() => assert_eq!(tuple[1], *reference),
+            Some(_) => (),
+            None => panic!("{} was received after linking failed with noproc", message),
+        }
+
+        signalled_pids.push(from_pid);
+        signal_count += 1;
+    }
+
+    assert_eq!(message_count, signalled.len());
+    assert_eq!(
+        signal_count,
+        signalled
+            .iter()
+            .filter(|(_, expected)| expected.is_some())
+            .count()
+    );
+}
+
+/// Exits `process` the way its scheduler does, but on the calling thread
+fn exit(process: &Process, reason: Term) {
+    process.exception(exit!(reason));
+
+    match *process.status.read() {
+        Status::Exiting(ref exception) => propagate_exit(process, exception),
+        _ => unreachable!(),
+    }
+}
+
+fn monitor(process: &Process, pid_term: Term) -> exception::Result {
+    monitor_2::native(process, atom_unchecked("process"), pid_term)
+}
+
+fn wait(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.wait();
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+//! Delivering the trace messages of processes traced with `erlang:trace/3` to their tracers.
+
+use core::ptr::{self, NonNull};
+
+use alloc::sync::Arc;
+
+use liblumen_alloc::erts::process::trace::{self, Backend, Event};
+use liblumen_alloc::erts::term::{Atom, Pid, Term};
+use liblumen_alloc::HeapFragment;
+
+use crate::process::send_heap_message_and_wake;
+use crate::registry::pid_to_process;
+
+/// Sets up delivery to tracer processes and registers the built-in backends, so that
+/// `erlang:trace/3` can select them with `{tracer, Name}`:
+///
+/// * `log` - `Log`
+pub fn init() {
+    trace::set_deliver(deliver);
+
+    let log = Atom::try_from_str("log").unwrap();
+
+    if trace::backend(log).is_none() {
+        trace::register_backend(log, Arc::new(Log));
+    }
+}
+
+/// Delivers `message`, which is in `heap_fragment`, to `tracer`.  A trace message for a tracer
+/// that has exited or is exiting is dropped, as a message sent to it would be.
+pub fn deliver(tracer: Pid, heap_fragment: NonNull<HeapFragment>, message: Term) {
+    match pid_to_process(&tracer) {
+        Some(ref tracer_arc_process) if !tracer_arc_process.is_exiting() => {
+            send_heap_message_and_wake(tracer_arc_process, heap_fragment, message)
+        }
+        _ => unsafe { ptr::drop_in_place(heap_fragment.as_ptr()) },
+    }
+}
+
+/// Logs trace messages at the `info` level with the `trace` target of the `log` facade, so they go
+/// wherever the embedder's logger sends them.
+pub struct Log;
+
+impl Backend for Log {
+    fn trace(&self, _event: Event, message: Term) {
+        log::info!(target: "trace", "{}", message);
+    }
+}
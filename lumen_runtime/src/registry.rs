@@ -1,5 +1,7 @@
 /// Maps registered names (`Atom`) to `LocalPid` or `Port`
+use alloc::collections::{BTreeMap, VecDeque};
 use alloc::sync::{Arc, Weak};
+use core::ops::Bound;
 
 use hashbrown::HashMap;
 
@@ -10,6 +12,7 @@ use liblumen_alloc::erts::term::{AsTerm, Atom, Pid, Term};
 use liblumen_alloc::{HeapAlloc, Process};
 
 use crate::process;
+use crate::process::limit;
 
 pub fn atom_to_process(name: &Atom) -> Option<Arc<Process>> {
     let readable_registry = RW_LOCK_REGISTERED_BY_NAME.read();
@@ -21,11 +24,16 @@ pub fn atom_to_process(name: &Atom) -> Option<Arc<Process>> {
         })
 }
 
+/// The registered names, in the order the atoms were created, so that the order is the same each
+/// time the same names are registered
 pub fn names(process: &Process) -> exception::Result {
     let mut acc = Term::NIL;
     let mut heap = process.acquire_heap();
+    let mut names: Vec<Atom> = RW_LOCK_REGISTERED_BY_NAME.read().keys().cloned().collect();
+    names.sort_by_key(|name| name.id());
 
-    for name in RW_LOCK_REGISTERED_BY_NAME.read().keys() {
+    // consed from the last, so that the list is in order
+    for name in names.iter().rev() {
         let name_term = unsafe { name.as_term() };
 
         acc = heap.cons(name_term, acc)?
@@ -49,6 +57,19 @@ pub fn pid_to_self_or_process(pid: Pid, process_arc: &Arc<Process>) -> Option<Ar
     }
 }
 
+/// The live processes, in pid order.
+///
+/// The pid table is only locked while each chunk of pids is read, so iterating over every process
+/// on a node with millions of them doesn't stop other schedulers from spawning in the meantime.
+/// Processes spawned or exiting during the iteration may or may not be included.
+pub fn processes() -> Processes {
+    Processes {
+        after: None,
+        chunk: VecDeque::new(),
+        done: false,
+    }
+}
+
 pub fn put_atom_to_process(name: Atom, arc_process: Arc<Process>) -> bool {
     let writable_registry = RW_LOCK_REGISTERED_BY_NAME.write();
 
@@ -70,6 +91,20 @@ pub fn put_pid_to_process(arc_process: &Arc<Process>) {
     {
         panic!("Process already registered with pid");
     }
+
+    limit::spawned();
+}
+
+/// Removes `pid` of a process that has exited from the pid table, so that it is no longer found or
+/// counted as alive
+pub fn remove_pid_to_process(pid: &Pid) {
+    if RW_LOCK_WEAK_PROCESS_CONTROL_BLOCK_BY_PID
+        .write()
+        .remove(pid)
+        .is_some()
+    {
+        limit::exited();
+    }
 }
 
 pub fn unregister(name: &Atom) -> bool {
@@ -87,6 +122,56 @@ pub fn unregister(name: &Atom) -> bool {
     }
 }
 
+pub struct Processes {
+    /// The last pid read from the table
+    after: Option<Pid>,
+    chunk: VecDeque<Arc<Process>>,
+    done: bool,
+}
+
+impl Processes {
+    /// Pids read from the table while it is locked
+    const CHUNK_LEN: usize = 1024;
+
+    /// Reads the next chunk of pids, keeping the processes that are still alive
+    fn read_chunk(&mut self) {
+        let lower = match self.after {
+            Some(pid) => Bound::Excluded(pid),
+            None => Bound::Unbounded,
+        };
+        let readable_table = RW_LOCK_WEAK_PROCESS_CONTROL_BLOCK_BY_PID.read();
+        let mut len = 0;
+
+        for (pid, weak_process) in readable_table
+            .range((lower, Bound::Unbounded))
+            .take(Self::CHUNK_LEN)
+        {
+            self.after = Some(*pid);
+            len += 1;
+
+            if let Some(arc_process) = weak_process.upgrade() {
+                if !arc_process.is_exiting() {
+                    self.chunk.push_back(arc_process);
+                }
+            }
+        }
+
+        self.done = len < Self::CHUNK_LEN;
+    }
+}
+
+impl Iterator for Processes {
+    type Item = Arc<Process>;
+
+    fn next(&mut self) -> Option<Arc<Process>> {
+        while self.chunk.is_empty() && !self.done {
+            self.read_chunk();
+        }
+
+        self.chunk.pop_front()
+    }
+}
+
 #[cfg_attr(test, derive(Debug))]
 pub enum Registered {
     Process(Weak<Process>),
@@ -105,5 +190,6 @@ impl PartialEq for Registered {
 lazy_static! {
     static ref RW_LOCK_REGISTERED_BY_NAME: RwLock<HashMap<Atom, Registered>> = Default::default();
     // Strong references are owned by the scheduler run queues
-    static ref RW_LOCK_WEAK_PROCESS_CONTROL_BLOCK_BY_PID: RwLock<HashMap<Pid, Weak<Process>>> = Default::default();
+    // Ordered, so that `Processes` can resume after the last pid it read
+    static ref RW_LOCK_WEAK_PROCESS_CONTROL_BLOCK_BY_PID: RwLock<BTreeMap<Pid, Weak<Process>>> = Default::default();
 }
@@ -7,27 +7,42 @@ use std::sync::Arc;
 
 use liblumen_alloc::erts::process::{Priority, Process, Status};
 
-use crate::run::queues::delayed::Delayed;
 use crate::run::queues::immediate::Immediate;
 use crate::run::queues::Next::*;
 use crate::run::Run;
 
-mod delayed;
 mod immediate;
 
+/// How many `Priority::Normal` processes are run for each `Priority::Low` process while both are
+/// runnable, the same as the BEAM's `RESCHEDULE_LOW`.
+/// -- https://github.com/erlang/otp/blob/fe2b1323a3866ed0a9712e9d12e1f8f84793ec47/erts/emulator/beam/erl_process.h#L317
+const NORMAL_PER_LOW: u8 = 8;
+
+/// A run queue for each priority.  As on the BEAM, `Priority::Max` processes always run before
+/// `Priority::High` processes, which always run before `Priority::Normal` and `Priority::Low`
+/// processes, so that system processes aren't starved by batch work, while `Priority::Low`
+/// processes run once for every `NORMAL_PER_LOW` `Priority::Normal` processes.
 #[derive(Debug, Default)]
 pub struct Queues {
     waiting: Waiting,
-    normal_low: Delayed,
+    /// Processes that are runnable, but held back because they are suspended
+    suspended: Waiting,
+    low: Immediate,
+    normal: Immediate,
     high: Immediate,
     max: Immediate,
+    /// The number of `Priority::Normal` processes that have run since the last `Priority::Low`
+    /// process
+    normal_since_low: u8,
 }
 
 impl Queues {
     #[cfg(test)]
     pub fn contains(&self, value: &Arc<Process>) -> bool {
         self.waiting.contains(value)
-            || self.normal_low.contains(value)
+            || self.suspended.contains(value)
+            || self.low.contains(value)
+            || self.normal.contains(value)
             || self.high.contains(value)
             || self.max.contains(value)
     }
@@ -35,34 +50,48 @@ impl Queues {
     #[cfg(test)]
     pub fn run_queue_len(&self, priority: Priority) -> usize {
         match priority {
-            Priority::Low | Priority::Normal => self.normal_low.len(),
+            Priority::Low => self.low.len(),
+            Priority::Normal => self.normal.len(),
             Priority::High => self.high.len(),
             Priority::Max => self.max.len(),
         }
     }
 
+    /// Processes that were suspended after they were put in a run queue are held back when they
+    /// reach the front of it.
     pub fn dequeue(&mut self) -> Run {
-        if 0 < self.max.len() {
-            self.max.dequeue()
-        } else if 0 < self.high.len() {
-            self.high.dequeue()
-        } else if 0 < self.normal_low.len() {
-            self.normal_low.dequeue()
-        } else {
-            Run::None
+        loop {
+            match self.dequeue_by_priority() {
+                Run::Now(arc_process) if is_held_back(&arc_process) => {
+                    self.suspended.insert(arc_process);
+                }
+                run => break run,
+            }
         }
     }
 
     pub fn enqueue(&mut self, arc_process: Arc<Process>) {
+        if is_held_back(&arc_process) {
+            self.suspended.insert(arc_process);
+
+            return;
+        }
+
         match arc_process.priority {
-            Priority::Low | Priority::Normal => self.normal_low.enqueue(arc_process),
+            Priority::Low => self.low.enqueue(arc_process),
+            Priority::Normal => self.normal.enqueue(arc_process),
             Priority::High => self.high.enqueue(arc_process),
             Priority::Max => self.max.enqueue(arc_process),
         }
     }
 
     pub fn len(&self) -> usize {
-        self.waiting.len() + self.normal_low.len() + self.high.len() + self.max.len()
+        self.waiting.len()
+            + self.suspended.len()
+            + self.low.len()
+            + self.normal.len()
+            + self.high.len()
+            + self.max.len()
     }
 
     /// Returns the process is not pushed back because it is exiting
@@ -100,10 +129,59 @@ impl Queues {
             None => (),
         }
     }
+
+    /// Puts `process` back in its run queue if it was held back because it was suspended and no
+    /// longer is.
+    pub fn resume(&mut self, process: &Process) {
+        if process.is_suspended() {
+            return;
+        }
+
+        match self.suspended.get(process) {
+            Some(arc_process) => {
+                let arc_process = Arc::clone(arc_process);
+                self.suspended.remove(&arc_process);
+
+                self.enqueue(arc_process);
+            }
+            None => (),
+        }
+    }
+
+    fn dequeue_by_priority(&mut self) -> Run {
+        if 0 < self.max.len() {
+            self.max.dequeue()
+        } else if 0 < self.high.len() {
+            self.high.dequeue()
+        } else {
+            self.dequeue_normal_low()
+        }
+    }
+
+    fn dequeue_normal_low(&mut self) -> Run {
+        let low_turn = self.normal.len() == 0 || NORMAL_PER_LOW <= self.normal_since_low;
+
+        if 0 < self.low.len() && low_turn {
+            self.normal_since_low = 0;
+
+            self.low.dequeue()
+        } else if 0 < self.normal.len() {
+            self.normal_since_low = self.normal_since_low.saturating_add(1);
+
+            self.normal.dequeue()
+        } else {
+            Run::None
+        }
+    }
 }
 
 // Private
 
+/// An exiting process still runs, so that its exit is propagated.
+fn is_held_back(process: &Process) -> bool {
+    process.is_suspended() && !process.is_exiting()
+}
+
 enum Next {
     Wait,
     PushBack,
@@ -165,3 +243,104 @@ impl Debug for Waiting {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use liblumen_alloc::erts::term::Atom;
+
+    use crate::process::spawn::options::Options;
+
+    #[test]
+    fn higher_priorities_run_first() {
+        let mut queues: Queues = Default::default();
+        let low = process(Priority::Low);
+        let normal = process(Priority::Normal);
+        let high = process(Priority::High);
+        let max = process(Priority::Max);
+
+        queues.enqueue(low.clone());
+        queues.enqueue(normal.clone());
+        queues.enqueue(high.clone());
+        queues.enqueue(max.clone());
+
+        assert_run_now(queues.dequeue(), &max);
+        assert_run_now(queues.dequeue(), &high);
+        assert_run_now(queues.dequeue(), &normal);
+        assert_run_now(queues.dequeue(), &low);
+        assert!(match queues.dequeue() {
+            Run::None => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn low_runs_once_per_normal_per_low_normal() {
+        let mut queues: Queues = Default::default();
+        let low = process(Priority::Low);
+        let normals: Vec<Arc<Process>> = (0..=NORMAL_PER_LOW)
+            .map(|_| process(Priority::Normal))
+            .collect();
+
+        queues.enqueue(low.clone());
+
+        for normal in &normals {
+            queues.enqueue(normal.clone());
+        }
+
+        for normal in &normals[..(NORMAL_PER_LOW as usize)] {
+            assert_run_now(queues.dequeue(), normal);
+        }
+
+        assert_run_now(queues.dequeue(), &low);
+        assert_run_now(queues.dequeue(), normals.last().unwrap());
+    }
+
+    #[test]
+    fn suspended_is_held_back_until_resumed() {
+        let mut queues: Queues = Default::default();
+        let suspended = process(Priority::Normal);
+        let normal = process(Priority::Normal);
+
+        queues.enqueue(suspended.clone());
+        queues.enqueue(normal.clone());
+
+        suspended.suspend(normal.pid());
+
+        assert_run_now(queues.dequeue(), &normal);
+        assert!(match queues.dequeue() {
+            Run::None => true,
+            _ => false,
+        });
+        assert!(queues.contains(&suspended));
+
+        assert_eq!(suspended.resume(normal.pid()), Some(false));
+        queues.resume(&suspended);
+
+        assert_run_now(queues.dequeue(), &suspended);
+    }
+
+    fn assert_run_now(run: Run, expected: &Arc<Process>) {
+        match run {
+            Run::Now(arc_process) => assert_eq!(&arc_process, expected),
+            _ => panic!("Expected {:?} to run now", expected),
+        }
+    }
+
+    fn process(priority: Priority) -> Arc<Process> {
+        let mut options: Options = Default::default();
+        options.priority = Some(priority);
+
+        Arc::new(
+            options
+                .spawn(
+                    None,
+                    Atom::try_from_str("module").unwrap(),
+                    Atom::try_from_str("function").unwrap(),
+                    0,
+                )
+                .unwrap(),
+        )
+    }
+}
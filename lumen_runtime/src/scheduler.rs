@@ -1,11 +1,19 @@
+#[cfg(not(target_arch = "wasm32"))]
+pub mod dirty;
+pub mod low_power;
 #[cfg(test)]
 pub mod test;
 
 use core::fmt::{self, Debug};
 use core::sync::atomic::{AtomicU64, Ordering};
+#[cfg(not(target_arch = "wasm32"))]
+use core::time::Duration;
 
 use alloc::sync::{Arc, Weak};
 
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread::{self, Thread};
+
 use hashbrown::HashMap;
 
 use liblumen_core::locks::{Mutex, RwLock};
@@ -14,14 +22,15 @@ use liblumen_alloc::erts::exception::system::{Alloc, Exception};
 use liblumen_alloc::erts::process::code::Code;
 #[cfg(test)]
 use liblumen_alloc::erts::process::Priority;
-use liblumen_alloc::erts::process::{Process, Status};
+use liblumen_alloc::erts::process::{GcError, Process, Status};
 pub use liblumen_alloc::erts::scheduler::{id, ID};
-use liblumen_alloc::erts::term::{reference, Atom, Reference, Term};
+use liblumen_alloc::erts::term::{reference, Atom, Pid, Reference, Term};
 
 use crate::process;
 use crate::process::spawn::options::Options;
-use crate::registry::put_pid_to_process;
+use crate::registry::{pid_to_process, put_pid_to_process};
 use crate::run::{self, Run};
+use crate::time::monotonic::{self, Milliseconds};
 use crate::timer::Hierarchy;
 
 pub trait Scheduled {
@@ -47,6 +56,10 @@ pub struct Scheduler {
     // References are always 64-bits even on 32-bit platforms
     reference_count: AtomicU64,
     run_queues: RwLock<run::queues::Queues>,
+    /// The thread the scheduler runs on, so that it can be unparked when a process is made
+    /// runnable while it is parked in low-power mode
+    #[cfg(not(target_arch = "wasm32"))]
+    thread: Thread,
 }
 
 impl Scheduler {
@@ -54,6 +67,11 @@ impl Scheduler {
         SCHEDULER.with(|thread_local_scheduler| thread_local_scheduler.clone())
     }
 
+    /// The number of schedulers, one for each thread that has run processes
+    pub fn count() -> usize {
+        SCHEDULER_BY_ID.lock().len()
+    }
+
     pub fn from_id(id: &ID) -> Option<Arc<Scheduler>> {
         Self::current_from_id(id).or_else(|| {
             SCHEDULER_BY_ID
@@ -86,13 +104,30 @@ impl Scheduler {
     /// > 7. While needed pick a port task to execute
     /// > 8. Pick a process to execute
     /// > -- [The Scheduler Loop](https://blog.stenmans.org/theBeamBook/#_the_scheduler_loop)
+    ///
+    /// In low-power mode, the thread parks when there is no process to run until a timer times out
+    /// or a process is made runnable (see `low_power`).
     pub fn run(&self) {
         loop {
-            // TODO sleep or steal if nothing run
-            let _ = self.run_once();
+            // TODO steal if nothing run
+            if !self.run_once() && low_power::is_enabled() {
+                self.park();
+            }
         }
     }
 
+    /// The milliseconds until the next timer of this scheduler times out, or `None` if it has no
+    /// timers.  An embedder that runs the scheduler with `run_once`, such as in a browser, can wait
+    /// this long before running it again when it has no process to run.
+    pub fn milliseconds_until_next_timeout(&self) -> Option<Milliseconds> {
+        self.hierarchy
+            .read()
+            .next_timeout_monotonic_time_milliseconds()
+            .map(|monotonic_time_milliseconds| {
+                monotonic_time_milliseconds.saturating_sub(monotonic::time_in_milliseconds())
+            })
+    }
+
     /// > 1. Update reduction counters
     /// > 2. Check timers
     /// > 3. If needed check balance
@@ -109,50 +144,59 @@ impl Scheduler {
     pub fn run_once(&self) -> bool {
         self.hierarchy.write().timeout();
 
-        loop {
-            // separate from `match` below so that WriteGuard temporary is not held while process
-            // runs.
-            let run = self.run_queues.write().dequeue();
-
-            match run {
-                Run::Now(arc_process) => {
-                    // Don't allow exiting processes to run again.
-                    //
-                    // Without this check, a process.exit() from outside the process during WAITING
-                    // will return to the Frame that called `process.wait()`
-                    if !arc_process.is_exiting() {
-                        match Process::run(&arc_process) {
-                            Ok(()) => (),
-                            Err(exception) => match exception {
-                                Exception::Alloc(_inner) => {
-                                    match arc_process.garbage_collect(0, &mut []) {
-                                        Ok(_freed) => (),
-                                        Err(gc_err) => panic!("Gc error: {:?}", gc_err),
-                                    }
+        // separate from `match` below so that WriteGuard temporary is not held while process
+        // runs.
+        let run = self.run_queues.write().dequeue();
+
+        match run {
+            Run::Now(arc_process) => {
+                // Don't allow exiting processes to run again.
+                //
+                // Without this check, a process.exit() from outside the process during WAITING
+                // will return to the Frame that called `process.wait()`
+                if !arc_process.is_exiting() {
+                    match Process::run(&arc_process) {
+                        Ok(()) => (),
+                        Err(exception) => match exception {
+                            Exception::Alloc(_inner) => {
+                                let gc_result = arc_process.garbage_collect(0, &mut []);
+
+                                match process::max_heap_size::enforce(&arc_process, gc_result) {
+                                    // killed, so it won't run again
+                                    Ok(_freed) | Err(GcError::MaxHeapSizeExceeded) => (),
+                                    Err(gc_err) => panic!("Gc error: {:?}", gc_err),
                                 }
-                            },
-                        }
-                    } else {
-                        arc_process.reduce()
-                    }
-
-                    match self.run_queues.write().requeue(arc_process) {
-                        Some(exiting_arc_process) => match *exiting_arc_process.status.read() {
-                            Status::Exiting(ref exception) => {
-                                process::log_exit(&exiting_arc_process, exception);
-                                process::propagate_exit(&exiting_arc_process, exception);
                             }
-                            _ => unreachable!(),
                         },
-                        None => (),
-                    };
+                    }
 
-                    break true;
+                    // collections requested with `erlang:garbage_collect/0,1,2` while it ran
+                    if let Err(alloc) = process::garbage_collect::collect_requested(&arc_process) {
+                        panic!("Gc error: {:?}", alloc);
+                    }
+                } else {
+                    arc_process.reduce()
                 }
-                Run::Delayed => continue,
-                // TODO steal processes or sleep if nothing to steal
-                Run::None => break false,
+
+                // separate from `match` below so that the run queues aren't locked while the
+                // exit is propagated, which wakes the processes it sends signals to
+                let option_exiting_arc_process = self.run_queues.write().requeue(arc_process);
+
+                match option_exiting_arc_process {
+                    Some(exiting_arc_process) => match *exiting_arc_process.status.read() {
+                        Status::Exiting(ref exception) => {
+                            process::log_exit(&exiting_arc_process, exception);
+                            process::propagate_exit(&exiting_arc_process, exception);
+                        }
+                        _ => unreachable!(),
+                    },
+                    None => (),
+                };
+
+                true
             }
+            // TODO steal processes or sleep if nothing to steal
+            Run::None => false,
         }
     }
 
@@ -190,6 +234,42 @@ impl Scheduler {
         }
     }
 
+    /// Runs processes from the calling thread, which must be the thread of this scheduler, until
+    /// the process with `pid` exits, so that embedders without threads of their own, such as on
+    /// wasm32, can wait for a process.
+    ///
+    /// Returns `true` once the process has exited, or `false` if it can never exit because no
+    /// process is runnable, no timer will time out, and there is no other thread to wake a process,
+    /// which can only happen on wasm32.
+    pub fn block_on_process(&self, pid: Pid) -> bool {
+        loop {
+            match pid_to_process(&pid) {
+                Some(ref arc_process) if !arc_process.is_exiting() => {
+                    if !self.run_once() && !self.wait_for_work() {
+                        break false;
+                    }
+                }
+                _ => break true,
+            }
+        }
+    }
+
+    /// Runs processes from the calling thread, which must be the thread of this scheduler, until
+    /// no process is runnable.  Timers that haven't timed out yet are left running, so embedders
+    /// that drive the scheduler themselves can call this again after
+    /// `milliseconds_until_next_timeout`.
+    ///
+    /// Returns `true` if any process was run.
+    pub fn run_until_idle(&self) -> bool {
+        let mut ran = false;
+
+        while self.run_once() {
+            ran = true;
+        }
+
+        ran
+    }
+
     pub fn schedule(self: Arc<Scheduler>, process: Process) -> Arc<Process> {
         let mut writable_run_queues = self.run_queues.write();
 
@@ -198,6 +278,7 @@ impl Scheduler {
         let arc_process = Arc::new(process);
 
         writable_run_queues.enqueue(Arc::clone(&arc_process));
+        self.unpark();
 
         arc_process
     }
@@ -271,16 +352,62 @@ impl Scheduler {
 
     pub fn stop_waiting(&self, process: &Process) {
         self.run_queues.write().stop_waiting(process);
+        self.unpark();
+    }
+
+    /// Puts `process` back in its run queue once it is no longer suspended by any process, if it
+    /// was held back while runnable.
+    pub fn resume(&self, process: &Process) {
+        self.run_queues.write().resume(process);
+        self.unpark();
     }
 
     // Private
 
+    #[cfg(not(target_arch = "wasm32"))]
+    fn park(&self) {
+        match self.milliseconds_until_next_timeout() {
+            Some(0) => (),
+            Some(milliseconds) => thread::park_timeout(Duration::from_millis(milliseconds)),
+            None => thread::park(),
+        }
+    }
+
+    // there are no threads to park, so the scheduler spins as it does outside of low-power mode
+    #[cfg(target_arch = "wasm32")]
+    fn park(&self) {}
+
+    /// Waits until there may be a process to run again.  Returns `false` if there never will be.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn wait_for_work(&self) -> bool {
+        // other threads, such as dirty schedulers, can make processes runnable
+        self.park();
+
+        true
+    }
+
+    // there are no other threads to make processes runnable, only timers
+    #[cfg(target_arch = "wasm32")]
+    fn wait_for_work(&self) -> bool {
+        self.milliseconds_until_next_timeout().is_some()
+    }
+
+    /// Wakes the scheduler if it is parked, so that it runs a process that was made runnable from
+    /// another thread.  If it isn't parked, it won't park the next time it would.
+    fn unpark(&self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        self.thread.unpark();
+    }
+
     fn new() -> Scheduler {
         Scheduler {
             id: id::next(),
             hierarchy: Default::default(),
             reference_count: AtomicU64::new(0),
             run_queues: Default::default(),
+            // schedulers are made by the thread local of the thread they run on
+            #[cfg(not(target_arch = "wasm32"))]
+            thread: thread::current(),
         }
     }
 
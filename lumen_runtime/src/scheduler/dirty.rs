@@ -0,0 +1,83 @@
+//! Dirty CPU schedulers: a pool of threads, one for each logical processor, that run native
+//! functions that take too long to run on a normal scheduler without keeping the other processes
+//! in its run queues from running.
+//!
+//! The process calling a dirty native function waits, as it would in `receive`, while the function
+//! runs as a job on a dirty scheduler, and the job wakes the process once the function returns.
+//! Jobs run in the order they are spawned, as dirty schedulers become free.
+
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::system::host::cpus;
+
+pub type Job = Box<dyn FnOnce() + Send>;
+
+/// The number of dirty CPU schedulers
+pub fn count() -> usize {
+    cpus::num_logical().max(1)
+}
+
+/// Runs `job` on the next dirty scheduler that is free.  The dirty schedulers are started the first
+/// time a job is spawned.
+pub fn spawn(job: Job) {
+    JOBS.lock().unwrap().send(job).unwrap();
+}
+
+// Private
+
+fn run(jobs: Arc<Mutex<Receiver<Job>>>) {
+    loop {
+        // the lock is only held while waiting for a job, so other dirty schedulers can take the
+        // next job while this one runs
+        let job = match jobs.lock().unwrap().recv() {
+            Ok(job) => job,
+            Err(_) => break,
+        };
+
+        job();
+    }
+}
+
+lazy_static! {
+    static ref JOBS: Mutex<Sender<Job>> = {
+        let (sender, receiver) = channel();
+        let shared_receiver = Arc::new(Mutex::new(receiver));
+
+        for index in 0..count() {
+            let jobs = Arc::clone(&shared_receiver);
+
+            thread::Builder::new()
+                .name(format!("dirty_cpu_scheduler_{}", index + 1))
+                .spawn(move || run(jobs))
+                .unwrap();
+        }
+
+        Mutex::new(sender)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jobs_run_on_dirty_schedulers() {
+        let (sender, receiver) = channel();
+
+        for _ in 0..(count() * 2) {
+            let sender = sender.clone();
+
+            spawn(Box::new(move || {
+                let name = thread::current().name().unwrap().to_string();
+
+                sender.send(name).unwrap();
+            }));
+        }
+
+        for _ in 0..(count() * 2) {
+            assert!(receiver.recv().unwrap().starts_with("dirty_cpu_scheduler_"));
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! A low-power mode for nodes that are mostly idle, such as those on battery-powered devices, where
+//! waking the CPU costs more than the work done once awake.
+//!
+//! In low-power mode, timers are coalesced: a timer times out at the end of the window it falls in
+//! instead of at its own millisecond, so timers started close together time out on the same
+//! wakeup.  Timers never time out early, only up to a window late, which Erlang allows, as
+//! timeouts are only guaranteed to not happen before the time given.
+//!
+//! A scheduler whose run queues are empty also parks its thread in `Scheduler::run` until its next
+//! timer times out or a process is made runnable, instead of spinning.  On wasm32, where there are
+//! no threads to park, embedders can instead wait `Scheduler::milliseconds_until_next_timeout`
+//! before running the scheduler again.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::time::monotonic::Milliseconds;
+
+/// The window that timers are coalesced into when low-power mode is enabled without one
+pub const DEFAULT_WINDOW_MILLISECONDS: Milliseconds = 50;
+
+pub fn is_enabled() -> bool {
+    window_milliseconds().is_some()
+}
+
+/// The window that timers are coalesced into, or `None` if low-power mode is disabled
+pub fn window_milliseconds() -> Option<Milliseconds> {
+    match WINDOW_MILLISECONDS.load(Ordering::SeqCst) {
+        0 => None,
+        window_milliseconds => Some(window_milliseconds),
+    }
+}
+
+/// Enables low-power mode with timers coalesced into windows of `window_milliseconds`, or disables
+/// it if `None`.  Timers that were started before low-power mode changed keep their time.
+pub fn set_window_milliseconds(option_window_milliseconds: Option<Milliseconds>) {
+    let window_milliseconds = match option_window_milliseconds {
+        Some(window_milliseconds) => {
+            assert!(
+                0 < window_milliseconds,
+                "coalescing window must be positive"
+            );
+
+            window_milliseconds
+        }
+        None => 0,
+    };
+
+    WINDOW_MILLISECONDS.store(window_milliseconds, Ordering::SeqCst);
+}
+
+/// The time that a timer for `monotonic_time_milliseconds` times out at
+pub fn coalesce(monotonic_time_milliseconds: Milliseconds) -> Milliseconds {
+    match window_milliseconds() {
+        Some(window_milliseconds) => {
+            coalesce_into(monotonic_time_milliseconds, window_milliseconds)
+        }
+        None => monotonic_time_milliseconds,
+    }
+}
+
+// Private
+
+fn coalesce_into(
+    monotonic_time_milliseconds: Milliseconds,
+    window_milliseconds: Milliseconds,
+) -> Milliseconds {
+    // round up to the end of the window, so that the timer doesn't time out early
+    let remainder = monotonic_time_milliseconds % window_milliseconds;
+
+    if remainder == 0 {
+        monotonic_time_milliseconds
+    } else {
+        monotonic_time_milliseconds + (window_milliseconds - remainder)
+    }
+}
+
+static WINDOW_MILLISECONDS: AtomicU64 = AtomicU64::new(0);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::sync::Arc;
+
+    use liblumen_alloc::erts::term::atom_unchecked;
+
+    use crate::scheduler::{with_process_arc, Scheduler};
+    use crate::time::monotonic;
+    use crate::timer::{self, Destination, Timeout};
+
+    #[test]
+    fn coalesce_into_rounds_up_to_end_of_window() {
+        assert_eq!(coalesce_into(100, 50), 100);
+        assert_eq!(coalesce_into(101, 50), 150);
+        assert_eq!(coalesce_into(149, 50), 150);
+        assert_eq!(coalesce_into(150, 50), 150);
+    }
+
+    #[test]
+    fn milliseconds_until_next_timeout_is_until_soonest_timer() {
+        with_process_arc(|arc_process| {
+            let scheduler = Scheduler::current();
+            let now = monotonic::time_in_milliseconds();
+            let later = now + 60_000;
+            let sooner = now + 30_000;
+
+            for monotonic_time_milliseconds in &[later, sooner] {
+                timer::start(
+                    *monotonic_time_milliseconds,
+                    Destination::Process(Arc::downgrade(&arc_process)),
+                    Timeout::Message,
+                    atom_unchecked("message"),
+                    &arc_process,
+                )
+                .unwrap();
+            }
+
+            let milliseconds = scheduler.milliseconds_until_next_timeout().unwrap();
+
+            assert!(milliseconds <= 30_000);
+            assert!(25_000 < milliseconds);
+        });
+    }
+}
@@ -26,6 +26,21 @@ fn scheduler_does_not_requeue_exiting_process() {
     })
 }
 
+#[test]
+fn block_on_process_runs_until_process_exits() {
+    with_process_arc(|arc_process| {
+        exit_1::place_frame_with_arguments(
+            &arc_process,
+            Placement::Replace,
+            atom_unchecked("normal"),
+        )
+        .unwrap();
+
+        assert!(Scheduler::current().block_on_process(arc_process.pid()));
+        assert!(arc_process.is_exiting());
+    })
+}
+
 #[test]
 fn scheduler_does_run_exiting_process() {
     with_process_arc(|arc_process| {
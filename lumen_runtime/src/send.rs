@@ -2,10 +2,13 @@ use core::convert::{TryFrom, TryInto};
 use core::result::Result;
 
 use liblumen_alloc::erts::exception::{runtime, Exception};
-use liblumen_alloc::term::{Atom, Term, TypedTerm};
+use liblumen_alloc::term::{Atom, Pid, Term, TypedTerm};
 use liblumen_alloc::{badarg, Process};
 
 use crate::node;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::port;
+use crate::process::alias;
 use crate::registry::{self, pid_to_process};
 use crate::scheduler::Scheduler;
 
@@ -15,6 +18,10 @@ pub fn send(
     options: Options,
     process: &Process,
 ) -> Result<Sent, Exception> {
+    // before delivery, so a send to self is recorded before it is received
+    process.record_sent(destination, message);
+    process.trace_send(message, destination);
+
     match destination.to_typed_term().unwrap() {
         TypedTerm::Atom(destination_atom) => {
             send_to_name(destination_atom, message, options, process)
@@ -30,20 +37,17 @@ pub fn send(
                                 let node = tuple[1];
 
                                 match node.to_typed_term().unwrap() {
-                                    TypedTerm::Atom(node_atom) => match node_atom.name() {
-                                        node::DEAD => {
+                                    TypedTerm::Atom(node_atom) => {
+                                        if node_atom == node::name() {
                                             send_to_name(name_atom, message, options, process)
+                                        } else if !options.connect {
+                                            Ok(Sent::ConnectRequired)
+                                        } else if !options.suspend {
+                                            Ok(Sent::SuspendRequired)
+                                        } else {
+                                            unimplemented!("distribution")
                                         }
-                                        _ => {
-                                            if !options.connect {
-                                                Ok(Sent::ConnectRequired)
-                                            } else if !options.suspend {
-                                                Ok(Sent::SuspendRequired)
-                                            } else {
-                                                unimplemented!("distribution")
-                                            }
-                                        }
-                                    },
+                                    }
                                     _ => Err(badarg!().into()),
                                 }
                             }
@@ -53,27 +57,24 @@ pub fn send(
                         Err(badarg!().into())
                     }
                 }
+                // a message sent to a reference that isn't an active alias is dropped
+                TypedTerm::Reference(reference) => match alias::sent_to(&reference) {
+                    Some(destination_pid) => send_to_pid(destination_pid, message, process),
+                    None => Ok(Sent::Sent),
+                },
+                // aliases of processes on other nodes aren't known to this node
+                TypedTerm::ExternalReference(_) => Ok(Sent::Sent),
                 _ => Err(badarg!().into()),
             }
         }
-        TypedTerm::Pid(destination_pid) => {
-            if destination_pid == process.pid() {
-                process.send_from_self(message);
-
+        TypedTerm::Pid(destination_pid) => send_to_pid(destination_pid, message, process),
+        // ports are only opened off the web
+        #[cfg(not(target_arch = "wasm32"))]
+        TypedTerm::Port(destination_port) => {
+            if port::send(destination_port, message, options.suspend, process)? {
                 Ok(Sent::Sent)
             } else {
-                match pid_to_process(&destination_pid) {
-                    Some(destination_arc_process) => {
-                        if destination_arc_process.send_from_other(message)? {
-                            let scheduler_id = destination_arc_process.scheduler_id().unwrap();
-                            let arc_scheduler = Scheduler::from_id(&scheduler_id).unwrap();
-                            arc_scheduler.stop_waiting(&destination_arc_process);
-                        }
-
-                        Ok(Sent::Sent)
-                    }
-                    None => Ok(Sent::Sent),
-                }
+                Ok(Sent::SuspendRequired)
             }
         }
         _ => Err(badarg!().into()),
@@ -81,8 +82,8 @@ pub fn send(
 }
 
 pub struct Options {
-    // Send only suspends for some sends to ports and for remote (`ExternalPid` or
-    // `{name, remote_node}`) sends, so it does not apply at this time.
+    // Send only suspends for sends to busy ports and for remote (`ExternalPid` or
+    // `{name, remote_node}`) sends.
     suspend: bool,
     // Connect only applies when there is distribution, which isn't implemented yet.
     connect: bool,
@@ -166,6 +167,9 @@ fn send_to_name(
         Ok(Sent::Sent)
     } else {
         match registry::atom_to_process(&destination) {
+            Some(ref destination_arc_process) if destination_arc_process.is_exiting() => {
+                Ok(Sent::Sent)
+            }
             Some(destination_arc_process) => {
                 if destination_arc_process.send_from_other(message)? {
                     let scheduler_id = destination_arc_process.scheduler_id().unwrap();
@@ -179,3 +183,29 @@ fn send_to_name(
         }
     }
 }
+
+fn send_to_pid(destination_pid: Pid, message: Term, process: &Process) -> Result<Sent, Exception> {
+    if destination_pid == process.pid() {
+        process.send_from_self(message);
+
+        Ok(Sent::Sent)
+    } else {
+        match pid_to_process(&destination_pid) {
+            // an exiting process never receives again, so the message is dropped as if the process
+            // had already exited
+            Some(ref destination_arc_process) if destination_arc_process.is_exiting() => {
+                Ok(Sent::Sent)
+            }
+            Some(destination_arc_process) => {
+                if destination_arc_process.send_from_other(message)? {
+                    let scheduler_id = destination_arc_process.scheduler_id().unwrap();
+                    let arc_scheduler = Scheduler::from_id(&scheduler_id).unwrap();
+                    arc_scheduler.stop_waiting(&destination_arc_process);
+                }
+
+                Ok(Sent::Sent)
+            }
+            None => Ok(Sent::Sent),
+        }
+    }
+}
@@ -1,5 +1,7 @@
 pub mod break_handler;
+pub mod crash_dump;
 pub mod host;
 pub mod io;
 pub mod random;
+pub mod stdio;
 pub mod time;
@@ -0,0 +1,120 @@
+//! A dump of the state of the node, in the sections of a BEAM `erl_crash.dump`, for when the node
+//! crashes or to inspect a node that is still running.
+//!
+//! Processes are in the order they were spawned, which is pid order, and atoms are in the order
+//! they were created, so that dumps of the same workload can be diffed.  Each process has the
+//! monotonic time it was spawned at, as times, unlike pids, differ between runs.
+
+use core::fmt::{self, Write};
+use core::sync::atomic::Ordering;
+
+use liblumen_alloc::erts::process::{Process, Status};
+use liblumen_alloc::erts::term::Atom;
+
+use crate::registry;
+
+pub fn dump(slogan: &str) -> String {
+    let mut dump = String::new();
+    // writing to a `String` can't fail
+    write(&mut dump, slogan).unwrap();
+
+    dump
+}
+
+pub fn write<W: Write>(writer: &mut W, slogan: &str) -> fmt::Result {
+    let atoms = Atom::all();
+    let processes: Vec<_> = registry::processes().collect();
+
+    writeln!(writer, "=erl_crash_dump:0.5")?;
+    writeln!(writer, "Slogan: {}", slogan)?;
+    writeln!(writer, "Atoms: {}", atoms.len())?;
+    writeln!(writer, "Processes: {}", processes.len())?;
+
+    for process in &processes {
+        write_process(writer, process)?;
+    }
+
+    writeln!(writer, "=atoms")?;
+
+    for atom in atoms {
+        writeln!(writer, "{}", atom.name())?;
+    }
+
+    writeln!(writer, "=end")
+}
+
+// Private
+
+fn write_process<W: Write>(writer: &mut W, process: &Process) -> fmt::Result {
+    let state = match *process.status.read() {
+        Status::Runnable => "Runnable",
+        Status::Running => "Running",
+        Status::Waiting => "Waiting",
+        Status::Exiting(_) => "Exiting",
+    };
+
+    writeln!(writer, "=proc:{}", process.pid())?;
+    writeln!(writer, "State: {}", state)?;
+
+    if let Some(registered_name) = *process.registered_name.read() {
+        writeln!(writer, "Name: {}", registered_name.name())?;
+    }
+
+    writeln!(
+        writer,
+        "Spawned as: {}",
+        process.initial_module_function_arity
+    )?;
+    writeln!(
+        writer,
+        "Created: {}",
+        process.creation_monotonic_time_milliseconds()
+    )?;
+    writeln!(
+        writer,
+        "Reductions: {}",
+        process.total_reductions.load(Ordering::SeqCst)
+    )?;
+    writeln!(
+        writer,
+        "Message queue length: {}",
+        process.mailbox.lock().borrow().len()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::scheduler::with_process_arc;
+
+    #[test]
+    fn processes_have_creation_time() {
+        with_process_arc(|arc_process| {
+            let dump = dump("test");
+            let process_header = format!("=proc:{}", arc_process.pid());
+            let process_index = dump.find(&process_header).unwrap();
+            let process_section = &dump[process_index..];
+
+            assert!(process_section.contains(&format!(
+                "Created: {}",
+                arc_process.creation_monotonic_time_milliseconds()
+            )));
+        });
+    }
+
+    #[test]
+    fn atoms_are_in_creation_order() {
+        let first = Atom::try_from_str("crash_dump_test_first").unwrap();
+        let second = Atom::try_from_str("crash_dump_test_second").unwrap();
+
+        let dump = dump("test");
+        let atoms = &dump[dump.find("=atoms\n").unwrap()..];
+
+        assert!(first.id() < second.id());
+        assert!(
+            atoms.find("\ncrash_dump_test_first\n").unwrap()
+                < atoms.find("\ncrash_dump_test_second\n").unwrap()
+        );
+    }
+}
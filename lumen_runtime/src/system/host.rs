@@ -1 +1,2 @@
 pub mod cpus;
+pub mod name;
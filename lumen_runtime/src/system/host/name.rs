@@ -0,0 +1,24 @@
+//! The name of the current host, used for node names that don't include one.
+
+#[cfg(unix)]
+pub fn get() -> String {
+    let mut buffer = [0_u8; 256];
+    let result =
+        unsafe { libc::gethostname(buffer.as_mut_ptr() as *mut libc::c_char, buffer.len()) };
+
+    if result == 0 {
+        let len = buffer
+            .iter()
+            .position(|byte| *byte == 0)
+            .unwrap_or(buffer.len());
+
+        String::from_utf8_lossy(&buffer[..len]).into_owned()
+    } else {
+        "localhost".to_string()
+    }
+}
+
+#[cfg(not(unix))]
+pub fn get() -> String {
+    "localhost".to_string()
+}
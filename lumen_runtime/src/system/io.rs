@@ -1,3 +1,7 @@
+pub mod server;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod stdin;
+
 #[cfg(not(target_arch = "wasm32"))]
 use std::ffi::CStr;
 
@@ -11,6 +15,9 @@ use wasm_bindgen::prelude::*;
 extern "C" {
     #[wasm_bindgen(js_namespace = console, js_name = log)]
     pub fn console_log(s: &str);
+
+    #[wasm_bindgen(js_namespace = console, js_name = error)]
+    pub fn console_error(s: &str);
 }
 
 #[allow(dead_code)]
@@ -22,13 +29,12 @@ pub extern "C" fn lumen_system_io_puts(s: *const libc::c_char) {
     puts(&sref);
 }
 
-#[cfg(not(target_arch = "wasm32"))]
+/// Writes `s` and a newline to the standard output of the runtime (see `system::stdio`)
 pub fn puts(s: &str) {
-    println!("{}", s);
+    super::stdio::write_stdout(format!("{}\n", s).as_bytes());
 }
 
-#[cfg(target_arch = "wasm32")]
-#[allow(dead_code)]
-pub fn puts(s: &str) {
-    console_log(s);
+/// Writes `s` and a newline to the standard error of the runtime (see `system::stdio`)
+pub fn eputs(s: &str) {
+    super::stdio::write_stderr(format!("{}\n", s).as_bytes());
 }
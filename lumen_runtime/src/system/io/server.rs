@@ -0,0 +1,493 @@
+//! The io server that `init` runs as the group leader of the processes it spawns, so that output
+//! from any process goes through the io protocol as it does on BEAM.
+//!
+//! An io request is `{io_request, From, ReplyAs, Request}`, and is answered by sending
+//! `{io_reply, ReplyAs, Reply}` to `From`.  The requests served are:
+//!
+//! - `{put_chars, Encoding, Chars}` and `{put_chars, Chars}`, which write the characters of the
+//!   chardata `Chars` and reply `ok`, or `{error, put_chars}` if `Chars` isn't chardata.
+//! - `{get_line, Encoding, Prompt}` and `{get_line, Prompt}`, which write `Prompt` and reply with
+//!   the next line of standard input, as `stdin` reads it.  There is no standard input on the web
+//!   or for capturing servers, so there `get_line` is replied to with `eof`.
+//! - `{requests, Requests}`, which serves each of the `put_chars` `Requests` in turn, replying
+//!   with the reply to the last one, or the first error.
+//!
+//! Anything else, including `put_chars` with a `Module:Function(Args)` to format, is replied to
+//! with `{error, request}`.  Messages that aren't io requests are dropped.
+//!
+//! Output goes to the standard output of the runtime (see `system::stdio`), except for servers
+//! started with `spawn_capturing`, whose output is kept in a `Captured` buffer for tests to read.
+
+use core::convert::TryInto;
+
+use alloc::sync::Arc;
+
+use std::sync::Mutex;
+
+use hashbrown::HashMap;
+
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::code;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, AsTerm, Atom, Pid, Term, TypedTerm};
+
+use crate::scheduler::Scheduler;
+use crate::send;
+use crate::system::stdio;
+
+/// The output of a server started with `spawn_capturing`
+#[derive(Clone, Default)]
+pub struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Captured {
+    /// Takes the output written since the last call
+    pub fn take(&self) -> String {
+        let bytes = std::mem::replace(&mut *self.0.lock().unwrap(), Vec::new());
+
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+/// Serves the io requests in the mailbox of `arc_process`, writing to standard output
+pub fn serve(arc_process: &Arc<Process>) -> code::Result {
+    serve_to(arc_process, Sink::Stdout)
+}
+
+/// Spawns an io server whose output is captured instead of written to standard output, so that
+/// tests can make it the group leader of the processes whose output they check
+pub fn spawn_capturing(parent_process: &Process) -> Result<(Arc<Process>, Captured), Alloc> {
+    // locked until the server is inserted, so that the server can't run without its `Captured`
+    let mut captured_by_pid = CAPTURED_BY_PID.lock().unwrap();
+    let arc_process = Scheduler::spawn_code(
+        parent_process,
+        Default::default(),
+        Atom::try_from_str("lumen_io_server").unwrap(),
+        Atom::try_from_str("capture").unwrap(),
+        vec![],
+        capture,
+    )?;
+    let captured: Captured = Default::default();
+
+    captured_by_pid.insert(arc_process.pid(), captured.clone());
+
+    Ok((arc_process, captured))
+}
+
+// Private
+
+enum Sink {
+    Stdout,
+    Captured(Captured),
+}
+
+impl Sink {
+    fn write(&self, bytes: &[u8]) {
+        match self {
+            Sink::Stdout => stdio::write_stdout(bytes),
+            Sink::Captured(captured) => captured.0.lock().unwrap().extend_from_slice(bytes),
+        }
+    }
+}
+
+fn capture(arc_process: &Arc<Process>) -> code::Result {
+    let captured = CAPTURED_BY_PID
+        .lock()
+        .unwrap()
+        .get(&arc_process.pid())
+        .cloned()
+        .unwrap();
+
+    serve_to(arc_process, Sink::Captured(captured))
+}
+
+fn serve_to(arc_process: &Arc<Process>, sink: Sink) -> code::Result {
+    loop {
+        let option_result = arc_process.mailbox.lock().borrow_mut().receive(arc_process);
+
+        match option_result {
+            Some(result) => {
+                let message = result?;
+                arc_process.reduce();
+
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    if get_line::forward_reply(arc_process, message)? {
+                        continue;
+                    }
+                }
+
+                if let Some((from, reply_as, request)) = io_request(message) {
+                    if let Some(reply) = serve_request(arc_process, &sink, from, reply_as, request)?
+                    {
+                        send_reply(arc_process, from, reply_as, reply)?;
+                    }
+                }
+            }
+            None => break,
+        }
+    }
+
+    Arc::clone(arc_process).wait();
+
+    Ok(())
+}
+
+/// `From`, `ReplyAs` and `Request` of `{io_request, From, ReplyAs, Request}`
+fn io_request(message: Term) -> Option<(Pid, Term, Term)> {
+    let elements = tuple_elements(message)?;
+
+    match elements.as_slice() {
+        [tag, from, reply_as, request] if *tag == atom_unchecked("io_request") => {
+            match from.to_typed_term().unwrap() {
+                TypedTerm::Pid(from_pid) => Some((from_pid, *reply_as, *request)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The reply to `request`, or `None` if the reply is sent later, as it is for `get_line`
+fn serve_request(
+    arc_process: &Arc<Process>,
+    sink: &Sink,
+    from: Pid,
+    reply_as: Term,
+    request: Term,
+) -> Result<Option<Term>, Alloc> {
+    let elements = tuple_elements(request).unwrap_or_default();
+    let name = elements
+        .first()
+        .and_then(|first| first.to_typed_term().ok())
+        .and_then(|typed_term| match typed_term {
+            TypedTerm::Atom(atom) => Some(atom),
+            _ => None,
+        });
+
+    match (name.map(|atom| atom.name()), elements.len()) {
+        (Some("put_chars"), 2) => put_chars(arc_process, sink, elements[1]).map(Some),
+        (Some("put_chars"), 3) => put_chars(arc_process, sink, elements[2]).map(Some),
+        (Some("get_line"), 2) => get_line(arc_process, sink, from, reply_as, elements[1]),
+        (Some("get_line"), 3) => get_line(arc_process, sink, from, reply_as, elements[2]),
+        (Some("requests"), 2) => requests(arc_process, sink, elements[1]).map(Some),
+        _ => error(arc_process, "request").map(Some),
+    }
+}
+
+fn put_chars(process: &Process, sink: &Sink, chars: Term) -> Result<Term, Alloc> {
+    match chardata_to_string(chars) {
+        Some(string) => {
+            sink.write(string.as_bytes());
+
+            Ok(atom_unchecked("ok"))
+        }
+        None => error(process, "put_chars"),
+    }
+}
+
+fn get_line(
+    arc_process: &Arc<Process>,
+    sink: &Sink,
+    from: Pid,
+    reply_as: Term,
+    prompt: Term,
+) -> Result<Option<Term>, Alloc> {
+    // prompts are often atoms, such as `''`
+    let prompt_string = match prompt.to_typed_term().unwrap() {
+        TypedTerm::Atom(atom) => Some(atom.name().to_string()),
+        _ => chardata_to_string(prompt),
+    };
+
+    match prompt_string {
+        Some(prompt_string) => sink.write(prompt_string.as_bytes()),
+        None => return error(arc_process, "get_line").map(Some),
+    }
+
+    match sink {
+        #[cfg(not(target_arch = "wasm32"))]
+        Sink::Stdout => {
+            get_line::request(arc_process, from, reply_as);
+
+            Ok(None)
+        }
+        _ => {
+            let _ = (from, reply_as);
+
+            Ok(Some(atom_unchecked("eof")))
+        }
+    }
+}
+
+fn requests(process: &Process, sink: &Sink, requests: Term) -> Result<Term, Alloc> {
+    let mut reply = atom_unchecked("ok");
+
+    let iter = match requests.list_iter() {
+        Ok(iter) => iter,
+        Err(_) => return error(process, "request"),
+    };
+
+    for result in iter {
+        let elements = match result.ok().and_then(tuple_elements) {
+            Some(elements) => elements,
+            None => return error(process, "request"),
+        };
+
+        reply = match elements.as_slice() {
+            [name, chars] if *name == atom_unchecked("put_chars") => {
+                put_chars(process, sink, *chars)?
+            }
+            [name, _, chars] if *name == atom_unchecked("put_chars") => {
+                put_chars(process, sink, *chars)?
+            }
+            _ => return error(process, "request"),
+        };
+
+        if reply != atom_unchecked("ok") {
+            break;
+        }
+    }
+
+    Ok(reply)
+}
+
+fn error(process: &Process, reason: &str) -> Result<Term, Alloc> {
+    process.tuple_from_slice(&[atom_unchecked("error"), atom_unchecked(reason)])
+}
+
+/// Sends `{io_reply, reply_as, reply}` to `to` from `process`
+fn send_reply(process: &Process, to: Pid, reply_as: Term, reply: Term) -> Result<(), Alloc> {
+    let message = process.tuple_from_slice(&[atom_unchecked("io_reply"), reply_as, reply])?;
+
+    // `to` may have exited, which drops the reply as it would any message
+    let _ = send::send(
+        unsafe { to.as_term() },
+        message,
+        Default::default(),
+        process,
+    );
+
+    Ok(())
+}
+
+/// The characters of the chardata `term`: a binary of UTF-8, or a possibly deep list of
+/// characters and chardata
+fn chardata_to_string(term: Term) -> Option<String> {
+    let mut string = String::new();
+
+    if push_chardata(&mut string, term) {
+        Some(string)
+    } else {
+        None
+    }
+}
+
+fn push_chardata(string: &mut String, term: Term) -> bool {
+    match term.to_typed_term().unwrap() {
+        TypedTerm::Nil => true,
+        TypedTerm::List(cons) => cons.into_iter().all(|result| match result {
+            Ok(element) => match element.to_typed_term().unwrap() {
+                TypedTerm::SmallInteger(_) => match element.try_into() {
+                    Ok(c) => {
+                        string.push(c);
+
+                        true
+                    }
+                    Err(_) => false,
+                },
+                _ => push_chardata(string, element),
+            },
+            Err(_) => false,
+        }),
+        _ => {
+            let result: Result<Vec<u8>, _> = term.try_into();
+
+            match result.ok().and_then(|bytes| String::from_utf8(bytes).ok()) {
+                Some(utf8) => {
+                    string.push_str(&utf8);
+
+                    true
+                }
+                None => false,
+            }
+        }
+    }
+}
+
+fn tuple_elements(term: Term) -> Option<Vec<Term>> {
+    match term.to_typed_term().unwrap() {
+        TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+            TypedTerm::Tuple(tuple) => Some(tuple.iter().collect()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// `get_line` requests are answered by `stdin`, which sends `{io_reply, Id, Line}` back to the
+/// server, so that the server can forward the line to the process that asked for it as
+/// `{io_reply, ReplyAs, Line}`.  `ReplyAs` is kept in the external term format until then, so
+/// that it doesn't need to stay on the server's heap.
+#[cfg(not(target_arch = "wasm32"))]
+mod get_line {
+    use core::convert::TryInto;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use alloc::sync::Arc;
+
+    use std::sync::Mutex;
+
+    use hashbrown::HashMap;
+
+    use liblumen_alloc::erts::exception::system::Alloc;
+    use liblumen_alloc::erts::process::Process;
+    use liblumen_alloc::erts::term::{atom_unchecked, Pid, Term};
+
+    use crate::external_term_format::{decode_prefix, encode};
+    use crate::system::io::stdin;
+
+    /// Asks `stdin` for a line for `from`, who is replied to as `reply_as`
+    pub fn request(arc_process: &Arc<Process>, from: Pid, reply_as: Term) {
+        let encoded_reply_as = match encode(reply_as) {
+            Ok(encoded_reply_as) => encoded_reply_as,
+            // `ReplyAs` that can't be encoded, such as a closure, can't be replied to
+            Err(_) => return,
+        };
+        let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+
+        PENDING.lock().unwrap().insert(
+            id,
+            Pending {
+                from,
+                encoded_reply_as,
+            },
+        );
+
+        stdin::request_line(arc_process, id);
+    }
+
+    /// Forwards `message` if it is the reply to a line requested with `request`, returning whether
+    /// it was
+    pub fn forward_reply(process: &Process, message: Term) -> Result<bool, Alloc> {
+        let elements = match super::tuple_elements(message) {
+            Some(elements) => elements,
+            None => return Ok(false),
+        };
+
+        if elements.len() != 3 || elements[0] != atom_unchecked("io_reply") {
+            return Ok(false);
+        }
+
+        let id: usize = match elements[1].try_into() {
+            Ok(id) => id,
+            Err(_) => return Ok(false),
+        };
+        let option_pending = PENDING.lock().unwrap().remove(&id);
+
+        match option_pending {
+            Some(pending) => {
+                // the term was encoded by `request`
+                let (reply_as, _) =
+                    decode_prefix(&pending.encoded_reply_as, &mut *process.acquire_heap()).unwrap();
+
+                super::send_reply(process, pending.from, reply_as, elements[2])?;
+
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    struct Pending {
+        from: Pid,
+        encoded_reply_as: Vec<u8>,
+    }
+
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+    lazy_static! {
+        static ref PENDING: Mutex<HashMap<usize, Pending>> = Default::default();
+    }
+}
+
+lazy_static! {
+    static ref CAPTURED_BY_PID: Mutex<HashMap<Pid, Captured>> = Default::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::process;
+    use crate::test::receive_message;
+
+    #[test]
+    fn put_chars_writes_chardata_and_replies_ok() {
+        let init_arc_process = process::test_init();
+        let (server_arc_process, captured) = spawn_capturing(&init_arc_process).unwrap();
+        let client_arc_process = process::test(&init_arc_process);
+
+        let reply = request(&client_arc_process, &server_arc_process, |process| {
+            let chars = process.charlist_from_str("hello ")?;
+            let binary = process.binary_from_str("world")?;
+            let chardata = process.list_from_slice(&[chars, binary])?;
+
+            process.tuple_from_slice(&[
+                atom_unchecked("put_chars"),
+                atom_unchecked("unicode"),
+                chardata,
+            ])
+        });
+
+        assert_eq!(captured.take(), "hello world");
+        assert_eq!(reply, atom_unchecked("ok"));
+    }
+
+    #[test]
+    fn unknown_request_replies_error_request() {
+        let init_arc_process = process::test_init();
+        let (server_arc_process, captured) = spawn_capturing(&init_arc_process).unwrap();
+        let client_arc_process = process::test(&init_arc_process);
+
+        let reply = request(&client_arc_process, &server_arc_process, |_| {
+            Ok(atom_unchecked("getopts"))
+        });
+
+        assert_eq!(captured.take(), "");
+        assert_eq!(
+            reply,
+            client_arc_process
+                .tuple_from_slice(&[atom_unchecked("error"), atom_unchecked("request")])
+                .unwrap()
+        );
+    }
+
+    /// Sends the request made by `request` from `client` to `server`, runs `server` and returns
+    /// the reply
+    fn request<F>(client: &Arc<Process>, server: &Arc<Process>, request: F) -> Term
+    where
+        F: FnOnce(&Process) -> Result<Term, Alloc>,
+    {
+        let reply_as = atom_unchecked("reply_as");
+        let request_term = request(client).unwrap();
+        let message = client
+            .tuple_from_slice(&[
+                atom_unchecked("io_request"),
+                client.pid_term(),
+                reply_as,
+                request_term,
+            ])
+            .unwrap();
+
+        send::send(server.pid_term(), message, Default::default(), client).unwrap();
+
+        assert!(Scheduler::current().run_through(server));
+
+        let reply_message = receive_message(client).unwrap();
+        let elements = tuple_elements(reply_message).unwrap();
+
+        assert_eq!(elements[0], atom_unchecked("io_reply"));
+        assert_eq!(elements[1], reply_as);
+
+        elements[2]
+    }
+}
@@ -0,0 +1,105 @@
+//! Standard input, read on a thread of its own so that a process waiting for a line waits as it
+//! would in `receive` instead of blocking its scheduler.
+//!
+//! A process asks for a line with `request_line`, and is sent `{io_reply, Id, Reply}` once the
+//! line is read, where `Reply` is the line as a string including its newline, `eof` or
+//! `{error, Reason}`, as a group leader replies to `get_line` requests.  Requests are answered in
+//! the order they are made.
+
+use core::mem;
+
+use std::io::{self, BufRead};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
+
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::{HeapAlloc, Process};
+use liblumen_alloc::erts::term::{atom_unchecked, Cons, Term, Tuple};
+use liblumen_alloc::erts::HeapFragment;
+
+use crate::process::send_heap_message_and_wake;
+
+/// Asks for the next line of standard input to be sent to `process` tagged with `id`.
+pub fn request_line(process: &Arc<Process>, id: usize) {
+    REQUESTS
+        .lock()
+        .unwrap()
+        .send(Request {
+            process: Arc::downgrade(process),
+            id,
+        })
+        .unwrap();
+}
+
+// Private
+
+struct Request {
+    process: Weak<Process>,
+    id: usize,
+}
+
+enum Reply {
+    Line(String),
+    Eof,
+    Error(io::Error),
+}
+
+fn read(requests: Receiver<Request>) {
+    let stdin = io::stdin();
+
+    for request in requests {
+        let mut line = String::new();
+        let reply = match stdin.lock().read_line(&mut line) {
+            Ok(0) => Reply::Eof,
+            Ok(_) => Reply::Line(line),
+            Err(error) => Reply::Error(error),
+        };
+
+        // the process may have exited while waiting
+        if let Some(process) = request.process.upgrade() {
+            // the line is dropped if there isn't memory for it, as a message would be
+            let _ = send_reply(&process, request.id, reply);
+        }
+    }
+}
+
+fn send_reply(process: &Process, id: usize, reply: Reply) -> Result<(), Alloc> {
+    let cons_need_in_words = mem::size_of::<Cons>() / mem::size_of::<Term>();
+    let reply_need_in_words = match &reply {
+        Reply::Line(line) => line.chars().count() * cons_need_in_words,
+        Reply::Eof => 0,
+        Reply::Error(_) => Tuple::need_in_words_from_len(2),
+    };
+    let need_in_words = Tuple::need_in_words_from_len(3) + reply_need_in_words;
+    let mut non_null_heap_fragment = unsafe { HeapFragment::new_from_word_size(need_in_words)? };
+    let heap_fragment = unsafe { non_null_heap_fragment.as_mut() };
+
+    let reply_term = match reply {
+        Reply::Line(line) => heap_fragment.charlist_from_str(&line)?,
+        Reply::Eof => atom_unchecked("eof"),
+        Reply::Error(error) => {
+            let reason = atom_unchecked(&format!("{:?}", error.kind()).to_lowercase());
+
+            heap_fragment.tuple_from_slice(&[atom_unchecked("error"), reason])?
+        }
+    };
+    // ids are handed out by the interpreter, so they are small integers that need no space
+    let id_term = heap_fragment.integer(id)?;
+    let data =
+        heap_fragment.tuple_from_slice(&[atom_unchecked("io_reply"), id_term, reply_term])?;
+
+    send_heap_message_and_wake(process, non_null_heap_fragment, data);
+
+    Ok(())
+}
+
+lazy_static! {
+    static ref REQUESTS: Mutex<Sender<Request>> = {
+        let (sender, receiver) = channel();
+
+        thread::spawn(move || read(receiver));
+
+        Mutex::new(sender)
+    };
+}
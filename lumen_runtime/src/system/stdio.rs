@@ -0,0 +1,138 @@
+//! Where the output of the runtime goes: io from processes through the io server, `system::io::puts`
+//! (which natives such as `lumen_intrinsics:println/1` use), exit reports and flight recorder
+//! dumps of processes that exit abnormally, and log messages.
+//!
+//! Output goes to the standard output and standard error of the host process (the console on the
+//! web) unless an embedder sets a `Stdio` with `set`, such as to show the output in a GUI or to send
+//! it to the logs of a server.
+
+use alloc::sync::Arc;
+
+use liblumen_core::locks::RwLock;
+
+pub trait Stdio: Send + Sync {
+    /// Writes output, such as from `io:format` or log messages
+    fn write_stdout(&self, bytes: &[u8]);
+
+    /// Writes errors, such as the exit reports of processes that exit abnormally
+    fn write_stderr(&self, bytes: &[u8]);
+}
+
+/// The standard output and standard error of the host process, or the console on the web
+pub struct HostStdio;
+
+impl Stdio for HostStdio {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn write_stdout(&self, bytes: &[u8]) {
+        use std::io::Write;
+
+        let stdout = std::io::stdout();
+        let mut stdout = stdout.lock();
+        let _ = stdout.write_all(bytes);
+        let _ = stdout.flush();
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn write_stdout(&self, bytes: &[u8]) {
+        super::io::console_log(console_line(&String::from_utf8_lossy(bytes)));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn write_stderr(&self, bytes: &[u8]) {
+        use std::io::Write;
+
+        let stderr = std::io::stderr();
+        let mut stderr = stderr.lock();
+        let _ = stderr.write_all(bytes);
+        let _ = stderr.flush();
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn write_stderr(&self, bytes: &[u8]) {
+        super::io::console_error(console_line(&String::from_utf8_lossy(bytes)));
+    }
+}
+
+/// Sends all output of the runtime to `stdio` from now on
+pub fn set(stdio: Arc<dyn Stdio>) {
+    *RW_LOCK_STDIO.write() = stdio;
+}
+
+/// Sends output back to the host process, as before any `set`
+pub fn reset() {
+    set(Arc::new(HostStdio));
+}
+
+pub fn write_stdout(bytes: &[u8]) {
+    stdio().write_stdout(bytes);
+}
+
+pub fn write_stderr(bytes: &[u8]) {
+    stdio().write_stderr(bytes);
+}
+
+// Private
+
+/// Each call to the console is already its own line
+#[cfg(target_arch = "wasm32")]
+fn console_line(text: &str) -> &str {
+    if text.ends_with('\n') {
+        &text[..(text.len() - 1)]
+    } else {
+        text
+    }
+}
+
+fn stdio() -> Arc<dyn Stdio> {
+    // cloned so that the lock isn't held while writing, in case the `Stdio` writes to the runtime
+    RW_LOCK_STDIO.read().clone()
+}
+
+lazy_static! {
+    static ref RW_LOCK_STDIO: RwLock<Arc<dyn Stdio>> = RwLock::new(Arc::new(HostStdio));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Mutex;
+
+    use crate::system::io::{eputs, puts};
+
+    #[derive(Default)]
+    struct Capturing {
+        stdout: Mutex<Vec<u8>>,
+        stderr: Mutex<Vec<u8>>,
+    }
+
+    impl Stdio for Capturing {
+        fn write_stdout(&self, bytes: &[u8]) {
+            self.stdout.lock().unwrap().extend_from_slice(bytes);
+        }
+
+        fn write_stderr(&self, bytes: &[u8]) {
+            self.stderr.lock().unwrap().extend_from_slice(bytes);
+        }
+    }
+
+    #[test]
+    fn output_goes_to_set_stdio_until_reset() {
+        let capturing: Arc<Capturing> = Default::default();
+
+        set(capturing.clone());
+        puts("to stdout");
+        eputs("to stderr");
+        reset();
+        puts("to host");
+
+        // other tests may write while the `Stdio` is set, so only check for this test's output
+        let stdout = String::from_utf8(capturing.stdout.lock().unwrap().clone()).unwrap();
+        let stderr = String::from_utf8(capturing.stderr.lock().unwrap().clone()).unwrap();
+
+        assert!(stdout.contains("to stdout\n"));
+        assert!(!stdout.contains("to stderr"));
+        assert!(!stdout.contains("to host"));
+        assert!(stderr.contains("to stderr\n"));
+    }
+}
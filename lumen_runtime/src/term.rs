@@ -1 +1,2 @@
 pub mod external_format;
+pub mod json;
@@ -0,0 +1,196 @@
+//! A lossy, but readable, conversion of terms to JSON for debugging output.
+//!
+//! Tuples and proper lists become arrays, maps become objects, integers and floats become numbers,
+//! and `true` and `false` become booleans.  Every other term becomes a string: atoms are their
+//! names, binaries are their text when they are UTF-8, and pids, ports, references, closures and
+//! resources are how they are displayed, such as `"#PID<0.1.0>"`.  Improper lists become
+//! `{"list": [...], "tail": Tail}`.
+//!
+//! Different terms can convert to the same JSON, so the JSON can't be converted back.
+
+use std::fmt::Write;
+
+use liblumen_alloc::erts::term::{Atom, ImproperList, Term, TypedTerm};
+
+/// Converts `term` to JSON text
+pub fn to_json(term: Term) -> String {
+    let mut json = String::new();
+    write_term(&mut json, term);
+
+    json
+}
+
+// Private
+
+fn write_term(json: &mut String, term: Term) {
+    match term.to_typed_term().unwrap() {
+        TypedTerm::Atom(atom) => write_atom(json, atom),
+        TypedTerm::SmallInteger(small_integer) => write!(json, "{}", small_integer).unwrap(),
+        TypedTerm::Float(float) => write!(json, "{}", float).unwrap(),
+        TypedTerm::Nil => json.push_str("[]"),
+        TypedTerm::List(cons) => {
+            let mut elements = Vec::new();
+            let mut option_tail = None;
+
+            for result in cons.into_iter() {
+                match result {
+                    Ok(element) => elements.push(element),
+                    Err(ImproperList { tail }) => option_tail = Some(tail),
+                }
+            }
+
+            match option_tail {
+                Some(tail) => {
+                    json.push_str("{\"list\":");
+                    write_array(json, elements);
+                    json.push_str(",\"tail\":");
+                    write_term(json, tail);
+                    json.push('}');
+                }
+                None => write_array(json, elements),
+            }
+        }
+        TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+            TypedTerm::BigInteger(big_integer) => write!(json, "{}", big_integer).unwrap(),
+            TypedTerm::Float(float) => write!(json, "{}", float).unwrap(),
+            TypedTerm::Tuple(tuple) => write_array(json, tuple.iter()),
+            TypedTerm::Map(map) => {
+                let mut keys = map.keys();
+                keys.sort();
+
+                json.push('{');
+
+                for (index, key) in keys.into_iter().enumerate() {
+                    if 0 < index {
+                        json.push(',');
+                    }
+
+                    write_key(json, key);
+                    json.push(':');
+                    write_term(json, map.get(key).unwrap());
+                }
+
+                json.push('}');
+            }
+            typed_term => write_string(json, &typed_term.to_string()),
+        },
+        typed_term => write_string(json, &typed_term.to_string()),
+    }
+}
+
+fn write_array<I: IntoIterator<Item = Term>>(json: &mut String, elements: I) {
+    json.push('[');
+
+    for (index, element) in elements.into_iter().enumerate() {
+        if 0 < index {
+            json.push(',');
+        }
+
+        write_term(json, element);
+    }
+
+    json.push(']');
+}
+
+fn write_atom(json: &mut String, atom: Atom) {
+    match atom.name() {
+        "false" => json.push_str("false"),
+        "true" => json.push_str("true"),
+        name => write_string(json, name),
+    }
+}
+
+/// Object keys must be strings, so keys that aren't atoms are their JSON as a string
+fn write_key(json: &mut String, key: Term) {
+    match key.to_typed_term().unwrap() {
+        TypedTerm::Atom(atom) => write_string(json, atom.name()),
+        _ => write_string(json, &to_json(key)),
+    }
+}
+
+fn write_string(json: &mut String, s: &str) {
+    json.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c < ' ' => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use liblumen_alloc::erts::term::atom_unchecked;
+
+    use crate::scheduler::with_process;
+
+    #[test]
+    fn tuple_of_atoms_and_numbers_is_array() {
+        with_process(|process| {
+            let term = process
+                .tuple_from_slice(&[
+                    atom_unchecked("ok"),
+                    true.into(),
+                    process.integer(-1).unwrap(),
+                    process.float(1.5).unwrap(),
+                ])
+                .unwrap();
+
+            assert_eq!(to_json(term), r#"["ok",true,-1,1.5]"#);
+        });
+    }
+
+    #[test]
+    fn pid_is_string() {
+        with_process(|process| {
+            assert_eq!(
+                to_json(process.pid_term()),
+                format!("\"{}\"", process.pid())
+            );
+        });
+    }
+
+    #[test]
+    fn improper_list_has_tail() {
+        with_process(|process| {
+            let term = process
+                .cons(atom_unchecked("head"), atom_unchecked("tail"))
+                .unwrap();
+
+            assert_eq!(to_json(term), r#"{"list":["head"],"tail":"tail"}"#);
+        });
+    }
+
+    #[test]
+    fn map_has_string_keys() {
+        with_process(|process| {
+            let term = process
+                .map_from_slice(&[
+                    (atom_unchecked("a"), Term::NIL),
+                    (process.integer(1).unwrap(), atom_unchecked("one")),
+                ])
+                .unwrap();
+
+            assert_eq!(to_json(term), r#"{"1":"one","a":[]}"#);
+        });
+    }
+
+    #[test]
+    fn binary_with_quotes_is_escaped_string() {
+        with_process(|process| {
+            let term = process.binary_from_str("say \"hi\"\n").unwrap();
+
+            assert_eq!(to_json(term), r#""say \"hi\"\n""#);
+        });
+    }
+}
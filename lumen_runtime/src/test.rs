@@ -1,3 +1,5 @@
+#[cfg(not(target_arch = "wasm32"))]
+pub mod beam;
 pub mod r#loop;
 
 // wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
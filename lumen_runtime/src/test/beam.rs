@@ -0,0 +1,253 @@
+//! Differential testing of BIFs against a real BEAM node.
+//!
+//! When `LUMEN_DIFFERENTIAL_ERL` is set to the path of an `erl` executable, `Differential::assert`
+//! starts it, writes each recorded call and the result Lumen returned to its standard input, and
+//! fails if BEAM returns anything else for the same call.  When it is not set, the calls are
+//! recorded but not checked, so the tests still pass without an OTP install.
+//!
+//! Arguments and results are sent as Erlang source, so only terms with a literal syntax (numbers,
+//! atoms, lists, tuples, maps and bitstrings) can be compared.
+
+use std::env;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::process::{Command, Stdio};
+
+use liblumen_alloc::erts::exception::runtime::Class;
+use liblumen_alloc::erts::exception::{Exception, Result};
+use liblumen_alloc::erts::term::binary::aligned_binary::AlignedBinary;
+use liblumen_alloc::erts::term::binary::maybe_aligned_maybe_binary::MaybeAlignedMaybeBinary;
+use liblumen_alloc::erts::term::binary::IterableBitstring;
+use liblumen_alloc::erts::term::{ImproperList, Term, TypedTerm};
+
+const ERL: &str = "LUMEN_DIFFERENTIAL_ERL";
+
+/// Reads `{Module, Function, Arguments, LumenResult}.` terms until `eof`, printing a line for each
+/// call where BEAM's result differs and then the number of differences.
+const SCRIPT: &str = "\
+Loop = fun Loop(Differences) ->
+    case io:read('') of
+        {ok, {M, F, A, Lumen}} ->
+            Beam = try {ok, apply(M, F, A)} catch Class:Reason -> {Class, Reason} end,
+            case Beam =:= Lumen of
+                true -> Loop(Differences);
+                false ->
+                    io:format(\"~w:~w~w returned ~w on BEAM, but ~w on Lumen~n\", [M, F, A, Beam, Lumen]),
+                    Loop(Differences + 1)
+            end;
+        eof ->
+            io:format(\"differences ~w~n\", [Differences])
+    end
+end,
+Loop(0),
+halt().";
+
+pub struct Differential {
+    module: &'static str,
+    function: &'static str,
+    calls: Vec<String>,
+}
+
+impl Differential {
+    pub fn new(module: &'static str, function: &'static str) -> Self {
+        Self {
+            module,
+            function,
+            calls: Vec::new(),
+        }
+    }
+
+    /// Records that calling the function with `arguments` returned `result` on Lumen
+    pub fn call(&mut self, arguments: &[Term], result: Result) {
+        let mut call = String::new();
+        write!(call, "{{'{}', '{}', [", self.module, self.function).unwrap();
+
+        for (index, argument) in arguments.iter().enumerate() {
+            if 0 < index {
+                call.push_str(", ");
+            }
+
+            write_term(&mut call, *argument);
+        }
+
+        call.push_str("], ");
+        write_result(&mut call, result);
+        call.push_str("}.\n");
+
+        self.calls.push(call);
+    }
+
+    /// Checks the recorded calls against BEAM, if `LUMEN_DIFFERENTIAL_ERL` is set
+    pub fn assert(self) {
+        let erl = match env::var_os(ERL) {
+            Some(erl) => erl,
+            None => return,
+        };
+
+        let mut child = Command::new(erl)
+            .args(&["-noshell", "-eval", SCRIPT])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("could not start erl");
+
+        {
+            let stdin = child.stdin.as_mut().unwrap();
+
+            for call in &self.calls {
+                stdin.write_all(call.as_bytes()).unwrap();
+            }
+        }
+
+        let output = child.wait_with_output().unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(
+            stdout.ends_with("differences 0\n"),
+            "Lumen differs from BEAM:\n{}",
+            stdout
+        );
+    }
+}
+
+fn write_result(s: &mut String, result: Result) {
+    match result {
+        Ok(term) => {
+            s.push_str("{ok, ");
+            write_term(s, term);
+            s.push('}');
+        }
+        Err(Exception::Runtime(runtime_exception)) => {
+            let class = match runtime_exception.class {
+                Class::Error { .. } => "error",
+                Class::Exit => "exit",
+                Class::Throw => "throw",
+            };
+
+            write!(s, "{{{}, ", class).unwrap();
+            write_term(s, runtime_exception.reason);
+            s.push('}');
+        }
+        Err(Exception::System(system_exception)) => {
+            panic!("{:?} can't be compared with BEAM", system_exception)
+        }
+    }
+}
+
+fn write_term(s: &mut String, term: Term) {
+    write_typed_term(s, term, term.to_typed_term().unwrap())
+}
+
+fn write_typed_term(s: &mut String, term: Term, typed_term: TypedTerm) {
+    match typed_term {
+        TypedTerm::Atom(atom) => {
+            s.push('\'');
+
+            for c in atom.name().chars() {
+                match c {
+                    '\'' | '\\' => {
+                        s.push('\\');
+                        s.push(c);
+                    }
+                    ' '..='~' => s.push(c),
+                    _ => write!(s, "\\x{{{:x}}}", c as u32).unwrap(),
+                }
+            }
+
+            s.push('\'');
+        }
+        TypedTerm::SmallInteger(small_integer) => write!(s, "{}", small_integer).unwrap(),
+        TypedTerm::BigInteger(big_integer) => write!(s, "{}", big_integer).unwrap(),
+        TypedTerm::Float(float) => {
+            let f: f64 = float.into();
+            assert!(f.is_finite(), "{} can't be compared with BEAM", f);
+            let debug = format!("{:?}", f);
+
+            // Erlang requires a fraction before any exponent
+            match debug.find('e') {
+                Some(index) if !debug[..index].contains('.') => {
+                    write!(s, "{}.0{}", &debug[..index], &debug[index..]).unwrap()
+                }
+                _ => s.push_str(&debug),
+            }
+        }
+        TypedTerm::Nil => s.push_str("[]"),
+        TypedTerm::List(cons) => {
+            s.push('[');
+
+            for (index, result) in cons.into_iter().enumerate() {
+                match result {
+                    Ok(element) => {
+                        if 0 < index {
+                            s.push_str(", ");
+                        }
+
+                        write_term(s, element);
+                    }
+                    Err(ImproperList { tail }) => {
+                        s.push_str(" | ");
+                        write_term(s, tail);
+                    }
+                }
+            }
+
+            s.push(']');
+        }
+        TypedTerm::Tuple(tuple) => {
+            s.push('{');
+
+            for (index, element) in tuple.iter().enumerate() {
+                if 0 < index {
+                    s.push_str(", ");
+                }
+
+                write_term(s, element);
+            }
+
+            s.push('}');
+        }
+        TypedTerm::Map(_) => {
+            s.push_str("#{");
+
+            for (index, (key, value)) in term.map_iter().unwrap().enumerate() {
+                if 0 < index {
+                    s.push_str(", ");
+                }
+
+                write_term(s, key);
+                s.push_str(" => ");
+                write_term(s, value);
+            }
+
+            s.push('}');
+        }
+        TypedTerm::HeapBinary(heap_binary) => write_bytes(s, heap_binary.as_bytes(), &[]),
+        TypedTerm::ProcBin(process_binary) => write_bytes(s, process_binary.as_bytes(), &[]),
+        TypedTerm::SubBinary(subbinary) => {
+            let bytes: Vec<u8> = subbinary.full_byte_iter().collect();
+            let bits: Vec<u8> = subbinary.partial_byte_bit_iter().collect();
+
+            write_bytes(s, &bytes, &bits)
+        }
+        TypedTerm::Boxed(boxed) => write_typed_term(s, term, boxed.to_typed_term().unwrap()),
+        _ => panic!("{} can't be compared with BEAM", term),
+    }
+}
+
+/// Writes a bitstring of the full `bytes` followed by the `bits` of a partial byte
+fn write_bytes(s: &mut String, bytes: &[u8], bits: &[u8]) {
+    s.push_str("<<");
+
+    let bytes = bytes.iter().map(|byte| byte.to_string());
+    let bits = bits.iter().map(|bit| format!("{}:1", bit));
+
+    for (index, segment) in bytes.chain(bits).enumerate() {
+        if 0 < index {
+            s.push_str(", ");
+        }
+
+        s.push_str(&segment);
+    }
+
+    s.push_str(">>");
+}
@@ -244,9 +244,12 @@ pub fn is_not_send_after_destination(arc_process: Arc<Process>) -> BoxedStrategy
 pub fn is_not_destination(arc_process: Arc<Process>) -> BoxedStrategy<Term> {
     super::term(arc_process.clone())
         .prop_filter(
-            "Destination must not be an atom, pid, or tuple",
+            "Destination must not be an atom, pid, reference, or tuple",
             |destination| {
-                !(destination.is_atom() || destination.is_pid() || destination.is_tuple())
+                !(destination.is_atom()
+                    || destination.is_pid()
+                    || destination.is_reference()
+                    || destination.is_tuple())
             },
         )
         .boxed()
@@ -416,6 +419,15 @@ pub fn is_reference(arc_process: Arc<Process>) -> BoxedStrategy<Term> {
     .boxed()
 }
 
+/// The encodings that are UTF-8, unlike `latin1`
+pub fn is_utf8_encoding() -> BoxedStrategy<Term> {
+    prop_oneof![
+        Just(atom_unchecked("unicode")),
+        Just(atom_unchecked("utf8"))
+    ]
+    .boxed()
+}
+
 pub fn leaf(
     range_inclusive: RangeInclusive<usize>,
     arc_process: Arc<Process>,
@@ -20,7 +20,7 @@ use liblumen_alloc::CloneToProcess;
 use liblumen_alloc::Process;
 
 use crate::registry;
-use crate::scheduler::{Scheduled, Scheduler};
+use crate::scheduler::{low_power, Scheduled, Scheduler};
 use crate::time::monotonic::{self, Milliseconds};
 
 pub fn cancel(timer_reference: &Reference) -> Option<Milliseconds> {
@@ -140,6 +140,7 @@ impl Hierarchy {
         process: &Process,
         scheduler: &Scheduler,
     ) -> Result<Term, Alloc> {
+        let monotonic_time_milliseconds = low_power::coalesce(monotonic_time_milliseconds);
         let reference_number = scheduler.next_reference_number();
         let process_reference = process.reference_from_scheduler(scheduler.id, reference_number)?;
         let (heap_fragment_message, heap_fragment) = match timeout {
@@ -182,6 +183,20 @@ impl Hierarchy {
         Ok(process_reference)
     }
 
+    /// The time of the timer that will time out next, or `None` if there are no timers
+    pub fn next_timeout_monotonic_time_milliseconds(&self) -> Option<Milliseconds> {
+        if !self.at_once.is_empty() {
+            return Some(0);
+        }
+
+        // timers are sorted by time in each slot, and slots are in order from the current slot
+        self.soon
+            .first_timer()
+            .or_else(|| self.later.first_timer())
+            .or_else(|| self.long_term.first_timer())
+            .map(|arc_timer| arc_timer.monotonic_time_milliseconds)
+    }
+
     pub fn timeout(&mut self) {
         self.timeout_at_once();
 
@@ -468,6 +483,10 @@ impl Slot {
         self.0.drain(0..exclusive_end_bound)
     }
 
+    fn first_timer(&self) -> Option<&Arc<Timer>> {
+        self.0.first()
+    }
+
     fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
@@ -548,6 +567,13 @@ impl Wheel {
         self.slots[self.slot_index as usize].drain_before_or_at(max_monotonic_time_milliseconds)
     }
 
+    /// The first timer in the first slot with a timer, starting from the current slot
+    fn first_timer(&self) -> Option<&Arc<Timer>> {
+        (0..Self::LENGTH)
+            .map(|offset| &self[(self.slot_index + offset) % Self::LENGTH])
+            .find_map(|slot| slot.first_timer())
+    }
+
     fn is_empty(&self) -> bool {
         self.slots[self.slot_index as usize].is_empty()
     }
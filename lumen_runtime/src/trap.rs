@@ -0,0 +1,255 @@
+//! Trapping lets BIFs whose work grows with their arguments, such as `length/1` of a long list or
+//! `binary_to_list/1` of a large binary, do that work in steps that each cost a reduction.  When
+//! the reductions of the run are used up, the BIF traps: its state is saved and the process returns
+//! to the scheduler, so other processes get to run before the BIF continues, instead of waiting
+//! until it returns.
+//!
+//! Between runs, the state is kept on the stack of the process under the frame of the BIF, so it
+//! is a root when the process is collected, and the BIF continues when the frame is run again.  If
+//! a step runs out of heap, the steps of the run are redone from the saved state once the process
+//! has been collected, so steps only have to leave a state that the next step can continue from.
+
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception::system::{self, Alloc};
+use liblumen_alloc::erts::exception::{self, Exception};
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::code::{self, result_from_exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::Term;
+
+/// The most units of work, such as list elements or bytes, that a step does for its reduction
+pub const STEP: usize = 64;
+
+#[derive(Clone, Copy)]
+pub struct Trap {
+    /// The number of terms in the state
+    pub state_len: usize,
+    /// The state before the first step, from the arguments.  The arguments are only checked by
+    /// the steps, so that they are checked on the process the BIF runs on.
+    pub start: fn(&[Term]) -> Vec<Term>,
+    /// Does at most `STEP` units of work, updating the state, and returns the result once there is
+    /// no work left
+    pub step: fn(&Process, &mut [Term]) -> Result<Option<Term>, Exception>,
+}
+
+impl Trap {
+    /// Runs the BIF without trapping, for callers that can't resume it, such as other BIFs
+    pub fn complete(&self, process: &Process, arguments: &[Term]) -> exception::Result {
+        let mut state = (self.start)(arguments);
+
+        loop {
+            if let Some(result) = (self.step)(process, &mut state)? {
+                break Ok(result);
+            }
+        }
+    }
+
+    /// Steps until the BIF returns or raises, or the run of `process` is reduced, in which case
+    /// `None` is returned and `state` is where the next run continues from.
+    pub fn run(&self, process: &Process, state: &mut [Term]) -> Option<exception::Result> {
+        loop {
+            process.reduce();
+
+            match (self.step)(process, state) {
+                Ok(Some(result)) => break Some(Ok(result)),
+                Ok(None) => {
+                    if process.is_reduced() {
+                        break None;
+                    }
+                }
+                Err(exception) => break Some(Err(exception)),
+            }
+        }
+    }
+
+    /// Places `frame`, whose code calls `code` with this trap, with the state of `arguments`
+    pub fn place_frame_with_arguments(
+        &self,
+        process: &Process,
+        placement: Placement,
+        frame: Frame,
+        arguments: &[Term],
+    ) -> Result<(), Alloc> {
+        push_state(process, &(self.start)(arguments))?;
+        process.place_frame(frame, placement);
+
+        Ok(())
+    }
+
+    /// The code of a frame placed by `place_frame_with_arguments`.  If the BIF traps, the frame
+    /// stays in place, so that this is called again on the next run.
+    pub fn code(&self, arc_process: &Arc<Process>) -> code::Result {
+        match self.resume(arc_process)? {
+            None => Ok(()),
+            Some(Ok(result)) => {
+                arc_process.return_from_call(result)?;
+
+                Process::call_code(arc_process)
+            }
+            Some(Err(exception)) => result_from_exception(arc_process, exception),
+        }
+    }
+
+    /// Continues the BIF from the state at the top of the stack of `process`.  If the BIF traps
+    /// again, the state is replaced by where the next run continues from, and `None` is returned;
+    /// otherwise, the state is popped.  If a step runs out of heap, the state is left as it was,
+    /// so that the run is redone once the process has been collected.
+    pub fn resume(
+        &self,
+        process: &Process,
+    ) -> Result<Option<exception::Result>, system::Exception> {
+        let mut state = peek_state(process, self.state_len)?;
+        let option_result = self.run(process, &mut state);
+
+        // the state is only popped once the steps can no longer run out of heap, so the stack
+        // space it frees is still free when the next state is pushed
+        match option_result {
+            None => {
+                pop_state(process, self.state_len);
+                push_state(process, &state)?;
+
+                Ok(None)
+            }
+            Some(Err(Exception::System(system_exception))) => Err(system_exception),
+            Some(result) => {
+                pop_state(process, self.state_len);
+
+                Ok(Some(result))
+            }
+        }
+    }
+}
+
+/// Pushes `state` so that `Trap::resume` continues from it, with its first term at the top of the
+/// stack
+pub fn push_state(process: &Process, state: &[Term]) -> Result<(), Alloc> {
+    for term in state.iter().rev() {
+        process.stack_push(*term)?;
+    }
+
+    Ok(())
+}
+
+// Private
+
+fn pop_state(process: &Process, len: usize) -> Vec<Term> {
+    (0..len).map(|_| process.stack_pop().unwrap()).collect()
+}
+
+/// The state that `push_state` pushed, leaving it on the stack
+fn peek_state(process: &Process, len: usize) -> Result<Vec<Term>, Alloc> {
+    let state = pop_state(process, len);
+    push_state(process, &state)?;
+
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::TryInto;
+
+    use liblumen_alloc::erts::process::MAX_REDUCTIONS_PER_RUN;
+    use liblumen_alloc::erts::term::{atom_unchecked, Atom};
+    use liblumen_alloc::ModuleFunctionArity;
+
+    use crate::scheduler::{with_process, with_process_arc, Scheduler};
+
+    #[test]
+    fn complete_returns_without_reducing() {
+        with_process(|process| {
+            let count = process
+                .integer(2 * MAX_REDUCTIONS_PER_RUN as usize)
+                .unwrap();
+
+            assert_eq!(
+                COUNT_DOWN.complete(process, &[count]),
+                Ok(atom_unchecked("done"))
+            );
+            assert!(!process.is_reduced());
+        });
+    }
+
+    #[test]
+    fn run_returns_none_once_reduced() {
+        with_process(|process| {
+            let count = 2 * MAX_REDUCTIONS_PER_RUN as usize;
+            let mut state = [process.integer(count).unwrap()];
+
+            assert_eq!(COUNT_DOWN.run(process, &mut state), None);
+            assert!(process.is_reduced());
+
+            let left: usize = state[0].try_into().unwrap();
+
+            assert!(0 < left && left < count);
+        });
+    }
+
+    #[test]
+    fn code_keeps_frame_and_state_until_returning() {
+        with_process_arc(|arc_process| {
+            let count = 2 * MAX_REDUCTIONS_PER_RUN as usize;
+
+            COUNT_DOWN
+                .place_frame_with_arguments(
+                    &arc_process,
+                    Placement::Replace,
+                    count_down_frame(),
+                    &[arc_process.integer(count).unwrap()],
+                )
+                .unwrap();
+
+            let scheduler = Scheduler::current();
+
+            assert!(scheduler.run_through(&arc_process));
+            assert!(!arc_process.is_exiting());
+
+            let left: usize = arc_process.stack_top().unwrap().try_into().unwrap();
+
+            assert!(0 < left && left < count);
+
+            while !arc_process.is_exiting() {
+                assert!(scheduler.run_through(&arc_process));
+            }
+        });
+    }
+
+    const COUNT_DOWN: Trap = Trap {
+        state_len: 1,
+        start: count_down_start,
+        step: count_down_step,
+    };
+
+    fn count_down_code(arc_process: &Arc<Process>) -> code::Result {
+        COUNT_DOWN.code(arc_process)
+    }
+
+    fn count_down_frame() -> Frame {
+        Frame::new(
+            Arc::new(ModuleFunctionArity {
+                module: Atom::try_from_str("trap_test").unwrap(),
+                function: Atom::try_from_str("count_down").unwrap(),
+                arity: 1,
+            }),
+            count_down_code,
+        )
+    }
+
+    fn count_down_start(arguments: &[Term]) -> Vec<Term> {
+        arguments.to_vec()
+    }
+
+    fn count_down_step(process: &Process, state: &mut [Term]) -> Result<Option<Term>, Exception> {
+        let count: usize = state[0].try_into().unwrap();
+
+        if count == 0 {
+            Ok(Some(atom_unchecked("done")))
+        } else {
+            state[0] = process.integer(count - 1)?;
+
+            Ok(None)
+        }
+    }
+}
@@ -4,6 +4,7 @@ pub mod create_element_2;
 pub mod create_text_node_2;
 pub mod get_element_by_id_2;
 pub mod new_0;
+pub mod query_selector_2;
 
 use std::convert::TryInto;
 use std::mem;
@@ -0,0 +1,79 @@
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::code::{self, result_from_exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{Atom, Term};
+use liblumen_alloc::erts::ModuleFunctionArity;
+
+use crate::document::document_from_term;
+use crate::option_to_ok_tuple_or_error;
+
+/// ```elixir
+/// case Lumen.Web.Document.query_selector(document, "#element-id .class") do
+///   {:ok, element} -> ...
+///   :error -> ...
+/// end
+/// ```
+pub fn place_frame_with_arguments(
+    process: &Process,
+    placement: Placement,
+    document: Term,
+    selector: Term,
+) -> Result<(), Alloc> {
+    process.stack_push(selector)?;
+    process.stack_push(document)?;
+    process.place_frame(frame(), placement);
+
+    Ok(())
+}
+
+// Private
+
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.reduce();
+
+    let document = arc_process.stack_pop().unwrap();
+    let selector = arc_process.stack_pop().unwrap();
+
+    match native(arc_process, document, selector) {
+        Ok(ok_tuple_or_error) => {
+            arc_process.return_from_call(ok_tuple_or_error)?;
+
+            Process::call_code(arc_process)
+        }
+        Err(exception) => result_from_exception(arc_process, exception),
+    }
+}
+
+fn frame() -> Frame {
+    Frame::new(module_function_arity(), code)
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("query_selector").unwrap()
+}
+
+fn module_function_arity() -> Arc<ModuleFunctionArity> {
+    Arc::new(ModuleFunctionArity {
+        module: super::module(),
+        function: function(),
+        arity: 2,
+    })
+}
+
+pub fn native(process: &Process, document: Term, selector: Term) -> exception::Result {
+    let document_document = document_from_term(document)?;
+    let selector_string: String = selector.try_into()?;
+
+    // an invalid selector is a `SyntaxError` `DOMException`
+    let option_element = document_document
+        .query_selector(&selector_string)
+        .map_err(|_| badarg!())?;
+
+    option_to_ok_tuple_or_error(process, option_element).map_err(|error| error.into())
+}
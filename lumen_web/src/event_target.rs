@@ -0,0 +1,86 @@
+//! EventTarget is the interface implemented by objects that can receive events and may have
+//! listeners for them, such as the window, the document, and elements.
+pub mod add_event_listener_3;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+
+use web_sys::{Event, EventTarget};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::{HeapAlloc, Process};
+use liblumen_alloc::erts::term::{atom_unchecked, resource, Atom, Pid, Term, Tuple};
+use liblumen_alloc::{CloneToProcess, HeapFragment};
+
+use lumen_runtime::process::send_heap_message_and_wake;
+use lumen_runtime::registry::pid_to_process;
+
+use crate::js_value;
+
+/// Sends `{:event, event_type, event}` to the process with `pid` for each `event_type` event on
+/// `event_target`, until the process exits, when the listener is removed.
+pub fn add_event_listener(event_target: &EventTarget, event_type: Atom, pid: Pid) {
+    let f: Rc<RefCell<Option<Closure<dyn FnMut(Event)>>>> = Rc::new(RefCell::new(None));
+    let g = f.clone();
+    let listened_event_target = event_target.clone();
+
+    let event_listener = move |event: Event| match pid_to_process(&pid) {
+        Some(arc_process) => send_event(&arc_process, event_type, event).unwrap(),
+        None => {
+            // `f` holds the closure that is running, so it is never dropped, only removed
+            if let Some(event_listener_closure) = f.borrow().as_ref() {
+                listened_event_target
+                    .remove_event_listener_with_callback(
+                        event_type.name(),
+                        event_listener_closure.as_ref().unchecked_ref(),
+                    )
+                    .unwrap();
+            }
+        }
+    };
+
+    let event_listener_box: Box<dyn FnMut(Event)> = Box::new(event_listener);
+    *g.borrow_mut() = Some(Closure::wrap(event_listener_box));
+
+    event_target
+        .add_event_listener_with_callback(
+            event_type.name(),
+            g.borrow().as_ref().unwrap().as_ref().unchecked_ref(),
+        )
+        .unwrap();
+}
+
+// Private
+
+fn event_target_from_term(term: Term) -> Result<EventTarget, exception::Exception> {
+    js_value::from_term(term)?
+        .dyn_into()
+        .map_err(|_| badarg!().into())
+}
+
+fn module() -> Atom {
+    Atom::try_from_str("Elixir.Lumen.Web.EventTarget").unwrap()
+}
+
+fn send_event(process: &Process, event_type: Atom, event: Event) -> Result<(), Alloc> {
+    let event_reference = resource::Reference::new(Box::new(event))?;
+    let need_in_words = Tuple::need_in_words_from_len(3) + event_reference.size_in_words();
+    let mut non_null_heap_fragment = unsafe { HeapFragment::new_from_word_size(need_in_words)? };
+    let heap_fragment = unsafe { non_null_heap_fragment.as_mut() };
+
+    let event_term = event_reference.clone_to_heap(heap_fragment)?;
+    let message = heap_fragment.tuple_from_slice(&[
+        atom_unchecked("event"),
+        atom_unchecked(event_type.name()),
+        event_term,
+    ])?;
+
+    send_heap_message_and_wake(process, non_null_heap_fragment, message);
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::code::{self, result_from_exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Pid, Term};
+use liblumen_alloc::erts::ModuleFunctionArity;
+
+use crate::event_target::{add_event_listener, event_target_from_term};
+
+/// ```elixir
+/// :ok = Lumen.Web.EventTarget.add_event_listener(element, :click, self())
+///
+/// receive do
+///   {:event, :click, event} -> ...
+/// end
+/// ```
+pub fn place_frame_with_arguments(
+    process: &Process,
+    placement: Placement,
+    event_target: Term,
+    event_type: Term,
+    pid: Term,
+) -> Result<(), Alloc> {
+    process.stack_push(pid)?;
+    process.stack_push(event_type)?;
+    process.stack_push(event_target)?;
+    process.place_frame(frame(), placement);
+
+    Ok(())
+}
+
+// Private
+
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.reduce();
+
+    let event_target = arc_process.stack_pop().unwrap();
+    let event_type = arc_process.stack_pop().unwrap();
+    let pid = arc_process.stack_pop().unwrap();
+
+    match native(event_target, event_type, pid) {
+        Ok(ok) => {
+            arc_process.return_from_call(ok)?;
+
+            Process::call_code(arc_process)
+        }
+        Err(exception) => result_from_exception(arc_process, exception),
+    }
+}
+
+fn frame() -> Frame {
+    Frame::new(module_function_arity(), code)
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("add_event_listener").unwrap()
+}
+
+fn module_function_arity() -> Arc<ModuleFunctionArity> {
+    Arc::new(ModuleFunctionArity {
+        module: super::module(),
+        function: function(),
+        arity: 3,
+    })
+}
+
+pub fn native(event_target: Term, event_type: Term, pid: Term) -> exception::Result {
+    let event_target_event_target = event_target_from_term(event_target)?;
+    let event_type_atom: Atom = event_type.try_into()?;
+    let pid_pid: Pid = pid.try_into()?;
+
+    add_event_listener(&event_target_event_target, event_type_atom, pid_pid);
+
+    Ok(atom_unchecked("ok"))
+}
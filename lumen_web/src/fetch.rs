@@ -0,0 +1,190 @@
+//! The [Fetch API](https://developer.mozilla.org/en-US/docs/Web/API/Fetch_API), which makes HTTP
+//! requests asynchronously.  The response is sent to the requesting process as a message, so the
+//! process can `receive` it while the scheduler keeps running other processes.
+pub mod request_4;
+
+use std::mem;
+use std::ptr;
+
+use js_sys::{Array, Promise, Uint8Array};
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+
+use web_sys::{Headers, Request, Response};
+
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::{HeapAlloc, Process};
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Cons, HeapBin, Pid, Term, Tuple};
+use liblumen_alloc::erts::HeapFragment;
+
+use lumen_runtime::process::send_heap_message_and_wake;
+use lumen_runtime::registry::pid_to_process;
+
+/// Sends `{:ok, status, headers, body}` to the process with `pid` once the response to `request`,
+/// including its body, has been received, or `{:error, reason}` if the request fails, such as
+/// when the network is down or CORS forbids it.  `headers` is a list of `{name, value}` binaries
+/// and `body` is a binary.
+pub fn request(request: &Request, pid: Pid) {
+    let window = web_sys::window().unwrap();
+    let response_promise = window.fetch_with_request(request);
+
+    // Each closure is forgotten, as JavaScript holds it until the promise settles.  The closures
+    // are `once`, so the Rust state of the one that is called is freed when it is called.
+    let on_response = Closure::once(move |response_js_value: JsValue| {
+        let response: Response = response_js_value.dyn_into().unwrap();
+
+        match response.array_buffer() {
+            Ok(body_promise) => read_body(response, body_promise, pid),
+            Err(error) => send(pid, Err(reason(error))),
+        }
+    });
+    let on_error = Closure::once(move |error: JsValue| send(pid, Err(reason(error))));
+
+    response_promise.then2(&on_response, &on_error);
+
+    on_response.forget();
+    on_error.forget();
+}
+
+// Private
+
+struct Fetched {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+fn bytes_need_in_words(bytes: &[u8]) -> usize {
+    let word_size = mem::size_of::<Term>();
+
+    (HeapBin::layout_bytes(bytes).size() + word_size - 1) / word_size
+}
+
+fn fetched_need_in_words(fetched: &Fetched) -> usize {
+    let cons_need_in_words = mem::size_of::<Cons>() / mem::size_of::<Term>();
+    let headers_need_in_words: usize = fetched
+        .headers
+        .iter()
+        .map(|(name, value)| {
+            cons_need_in_words
+                + Tuple::need_in_words_from_len(2)
+                + bytes_need_in_words(name.as_bytes())
+                + bytes_need_in_words(value.as_bytes())
+        })
+        .sum();
+
+    Tuple::need_in_words_from_len(4) + headers_need_in_words + bytes_need_in_words(&fetched.body)
+}
+
+fn fetched_to_term<H: HeapAlloc>(heap: &mut H, fetched: Fetched) -> Result<Term, Alloc> {
+    let status = heap.integer(fetched.status as usize)?;
+
+    let mut header_terms = Vec::with_capacity(fetched.headers.len());
+
+    for (name, value) in &fetched.headers {
+        let name_term = heap.heapbin_from_bytes(name.as_bytes())?;
+        let value_term = heap.heapbin_from_bytes(value.as_bytes())?;
+
+        header_terms.push(heap.tuple_from_slice(&[name_term, value_term])?);
+    }
+
+    let headers = heap.list_from_slice(&header_terms)?;
+    let body = heap.heapbin_from_bytes(&fetched.body)?;
+
+    heap.tuple_from_slice(&[atom_unchecked("ok"), status, headers, body])
+}
+
+/// The response `headers`, with names lowercase, as the Fetch API normalizes them
+fn headers_to_vec(headers: &Headers) -> Vec<(String, String)> {
+    match js_sys::try_iter(headers) {
+        Ok(Some(entries)) => entries
+            .filter_map(|result| result.ok())
+            .filter_map(|entry| {
+                let entry: Array = entry.dyn_into().ok()?;
+
+                Some((entry.get(0).as_string()?, entry.get(1).as_string()?))
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn module() -> Atom {
+    Atom::try_from_str("Elixir.Lumen.Web.Fetch").unwrap()
+}
+
+fn read_body(response: Response, body_promise: Promise, pid: Pid) {
+    let status = response.status();
+    let headers = headers_to_vec(&response.headers());
+
+    let on_body = Closure::once(move |array_buffer: JsValue| {
+        let uint8_array = Uint8Array::new(&array_buffer);
+        let mut body = vec![0; uint8_array.length() as usize];
+        uint8_array.copy_to(&mut body);
+
+        send(
+            pid,
+            Ok(Fetched {
+                status,
+                headers,
+                body,
+            }),
+        )
+    });
+    let on_error = Closure::once(move |error: JsValue| send(pid, Err(reason(error))));
+
+    body_promise.then2(&on_body, &on_error);
+
+    on_body.forget();
+    on_error.forget();
+}
+
+/// The message of the JavaScript `error`, such as the `TypeError` that `fetch` rejects with
+fn reason(error: JsValue) -> String {
+    match error.dyn_ref::<js_sys::Error>() {
+        Some(error) => error.message().into(),
+        None => error
+            .as_string()
+            .unwrap_or_else(|| "unknown error".to_string()),
+    }
+}
+
+/// Sends the `result` of a request to the process with `pid`, unless the process has exited
+fn send(pid: Pid, result: Result<Fetched, String>) {
+    if let Some(arc_process) = pid_to_process(&pid) {
+        // the response is dropped if there isn't memory for it, as a message would be
+        let _ = send_result(&arc_process, result);
+    }
+}
+
+fn send_result(process: &Process, result: Result<Fetched, String>) -> Result<(), Alloc> {
+    let need_in_words = match &result {
+        Ok(fetched) => fetched_need_in_words(fetched),
+        Err(reason) => Tuple::need_in_words_from_len(2) + bytes_need_in_words(reason.as_bytes()),
+    };
+    let mut non_null_heap_fragment = unsafe { HeapFragment::new_from_word_size(need_in_words)? };
+    let heap_fragment = unsafe { non_null_heap_fragment.as_mut() };
+
+    let message_result = match result {
+        Ok(fetched) => fetched_to_term(heap_fragment, fetched),
+        Err(reason) => heap_fragment
+            .heapbin_from_bytes(reason.as_bytes())
+            .and_then(|reason_term| {
+                heap_fragment.tuple_from_slice(&[atom_unchecked("error"), reason_term])
+            }),
+    };
+
+    match message_result {
+        Ok(message) => {
+            send_heap_message_and_wake(process, non_null_heap_fragment, message);
+
+            Ok(())
+        }
+        Err(alloc) => {
+            unsafe { ptr::drop_in_place(non_null_heap_fragment.as_ptr()) };
+
+            Err(alloc)
+        }
+    }
+}
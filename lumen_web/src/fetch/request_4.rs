@@ -0,0 +1,130 @@
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use js_sys::Uint8Array;
+
+use wasm_bindgen::JsValue;
+
+use web_sys::{Headers, Request, RequestInit};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::code::{self, result_from_exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Boxed, Term, Tuple};
+use liblumen_alloc::erts::ModuleFunctionArity;
+
+use crate::fetch::request;
+
+/// ```elixir
+/// :ok = Lumen.Web.Fetch.request("GET", "/users", [{"accept", "application/json"}], "")
+///
+/// receive do
+///   {:ok, status, headers, body} -> ...
+///   {:error, reason} -> ...
+/// end
+/// ```
+pub fn place_frame_with_arguments(
+    process: &Process,
+    placement: Placement,
+    method: Term,
+    url: Term,
+    headers: Term,
+    body: Term,
+) -> Result<(), Alloc> {
+    process.stack_push(body)?;
+    process.stack_push(headers)?;
+    process.stack_push(url)?;
+    process.stack_push(method)?;
+    process.place_frame(frame(), placement);
+
+    Ok(())
+}
+
+// Private
+
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.reduce();
+
+    let method = arc_process.stack_pop().unwrap();
+    let url = arc_process.stack_pop().unwrap();
+    let headers = arc_process.stack_pop().unwrap();
+    let body = arc_process.stack_pop().unwrap();
+
+    match native(arc_process, method, url, headers, body) {
+        Ok(ok) => {
+            arc_process.return_from_call(ok)?;
+
+            Process::call_code(arc_process)
+        }
+        Err(exception) => result_from_exception(arc_process, exception),
+    }
+}
+
+fn frame() -> Frame {
+    Frame::new(module_function_arity(), code)
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("request").unwrap()
+}
+
+fn headers_from_term(term: Term) -> Result<Headers, exception::Exception> {
+    let headers = Headers::new().map_err(|_| badarg!())?;
+
+    for result in term.list_iter()? {
+        let header: Boxed<Tuple> = result?.try_into()?;
+
+        if header.len() != 2 {
+            return Err(badarg!().into());
+        }
+
+        let name: String = header[0].try_into()?;
+        let value: String = header[1].try_into()?;
+
+        // invalid header names and values are a `TypeError`
+        headers.append(&name, &value).map_err(|_| badarg!())?;
+    }
+
+    Ok(headers)
+}
+
+fn module_function_arity() -> Arc<ModuleFunctionArity> {
+    Arc::new(ModuleFunctionArity {
+        module: super::module(),
+        function: function(),
+        arity: 4,
+    })
+}
+
+pub fn native(
+    process: &Process,
+    method: Term,
+    url: Term,
+    headers: Term,
+    body: Term,
+) -> exception::Result {
+    let method_string: String = method.try_into()?;
+    let url_string: String = url.try_into()?;
+    let headers_headers = headers_from_term(headers)?;
+    let body_bytes: Vec<u8> = body.try_into()?;
+
+    let mut request_init = RequestInit::new();
+    request_init.method(&method_string);
+    request_init.headers(&headers_headers);
+
+    // `GET` and `HEAD` requests can't have a body, even an empty one
+    if !body_bytes.is_empty() {
+        let body_js_value: JsValue = Uint8Array::from(&body_bytes[..]).into();
+        request_init.body(Some(&body_js_value));
+    }
+
+    let request_request =
+        Request::new_with_str_and_init(&url_string, &request_init).map_err(|_| badarg!())?;
+
+    request(&request_request, process.pid());
+
+    Ok(atom_unchecked("ok"))
+}
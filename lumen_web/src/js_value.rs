@@ -0,0 +1,269 @@
+//! JavaScript values, such as DOM nodes, callbacks, and plain objects, held as terms.
+//!
+//! A `JsValue` is held in a resource, the same as the DOM types that the `Lumen.Web` modules
+//! return, so the term can be sent to other processes, which all refer to the same JavaScript
+//! value.  The `JsValue` is only dropped, releasing the JavaScript value to the JavaScript garbage
+//! collector, once no process refers to it anymore.
+//!
+//! Values that have a term equivalent, such as numbers, strings and arrays, can instead be
+//! converted with `js_value_to_term` and `term_to_js_value`, for passing arguments from JavaScript
+//! and returning results to it.
+
+use std::convert::TryInto;
+use std::str;
+
+use wasm_bindgen::{JsCast, JsValue};
+
+use js_sys::{Array, Object, Reflect, Symbol, Uint8Array};
+
+use web_sys::{
+    Document, Element, Event, EventTarget, HtmlBodyElement, HtmlElement, HtmlFormElement,
+    HtmlInputElement, HtmlTableElement, Node, Text, WebSocket, Window,
+};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::binary::aligned_binary::AlignedBinary;
+use liblumen_alloc::erts::term::{
+    atom_unchecked, resource, Atom, MapIterator, Pid, SmallInteger, Term, Tuple, TypedTerm,
+};
+
+/// Holds `js_value` as a term on `process`
+pub fn to_term(process: &Process, js_value: JsValue) -> Result<Term, Alloc> {
+    process.resource(Box::new(js_value))
+}
+
+/// The `JsValue` held by `term`, from `to_term` or from a `Lumen.Web` function that returns a DOM
+/// type, such as `Lumen.Web.Document.create_element/2`
+pub fn from_term(term: Term) -> Result<JsValue, exception::Exception> {
+    let resource_reference: resource::Reference = term.try_into()?;
+
+    match from_resource_reference(&resource_reference) {
+        Some(js_value) => Ok(js_value),
+        None => Err(badarg!().into()),
+    }
+}
+
+/// The `JsValue` held by `resource_reference`, or `None` if it holds a Rust value instead
+pub fn from_resource_reference(resource_reference: &resource::Reference) -> Option<JsValue> {
+    let value = resource_reference.value();
+
+    if let Some(js_value) = value.downcast_ref::<JsValue>() {
+        Some(js_value.clone())
+    } else if let Some(document) = value.downcast_ref::<Document>() {
+        Some(document.into())
+    } else if let Some(element) = value.downcast_ref::<Element>() {
+        Some(element.into())
+    } else if let Some(event) = value.downcast_ref::<Event>() {
+        Some(event.into())
+    } else if let Some(event_target) = value.downcast_ref::<EventTarget>() {
+        Some(event_target.into())
+    } else if let Some(html_body_element) = value.downcast_ref::<HtmlBodyElement>() {
+        Some(html_body_element.into())
+    } else if let Some(html_element) = value.downcast_ref::<HtmlElement>() {
+        Some(html_element.into())
+    } else if let Some(html_form_element) = value.downcast_ref::<HtmlFormElement>() {
+        Some(html_form_element.into())
+    } else if let Some(html_input_element) = value.downcast_ref::<HtmlInputElement>() {
+        Some(html_input_element.into())
+    } else if let Some(html_table_element) = value.downcast_ref::<HtmlTableElement>() {
+        Some(html_table_element.into())
+    } else if let Some(node) = value.downcast_ref::<Node>() {
+        Some(node.into())
+    } else if let Some(text) = value.downcast_ref::<Text>() {
+        Some(text.into())
+    } else if let Some(web_socket) = value.downcast_ref::<WebSocket>() {
+        Some(web_socket.into())
+    } else if let Some(window) = value.downcast_ref::<Window>() {
+        Some(window.into())
+    } else {
+        None
+    }
+}
+
+/// Converts `js_value` to the equivalent term on `process`:
+///
+/// * `true` and `false` are the atoms `true` and `false`.
+/// * `null` and `undefined` are the atoms `null` and `undefined`.
+/// * Numbers are integers if they are whole numbers that are safe integers, and floats otherwise.
+/// * Strings are binaries.
+/// * Symbols from `Symbol.for(name)` are atoms, the same as `term_to_js_value` returns for atoms.
+/// * Arrays are lists of their converted elements.
+/// * `Uint8Array`s are binaries.
+///
+/// Any other value, such as an object or function, is held as with `to_term`.
+pub fn js_value_to_term(process: &Process, js_value: JsValue) -> Result<Term, Alloc> {
+    if let Some(boolean) = js_value.as_bool() {
+        Ok(atom_unchecked(if boolean { "true" } else { "false" }))
+    } else if js_value.is_null() {
+        Ok(atom_unchecked("null"))
+    } else if js_value.is_undefined() {
+        Ok(atom_unchecked("undefined"))
+    } else if let Some(number) = js_value.as_f64() {
+        number_to_term(process, number)
+    } else if let Some(string) = js_value.as_string() {
+        process.binary_from_str(&string)
+    } else if js_value.is_symbol() {
+        match Symbol::key_for(js_value.unchecked_ref()).as_string() {
+            Some(name) => match Atom::try_from_str(name) {
+                Ok(atom) => Ok(atom_unchecked(atom.name())),
+                Err(_) => to_term(process, js_value),
+            },
+            // symbols from `Symbol()` instead of `Symbol.for()` have no name to be an atom
+            None => to_term(process, js_value),
+        }
+    } else if Array::is_array(&js_value) {
+        let array: Array = js_value.unchecked_into();
+        let mut element_terms = Vec::with_capacity(array.length() as usize);
+
+        for index in 0..array.length() {
+            element_terms.push(js_value_to_term(process, array.get(index))?);
+        }
+
+        process.list_from_slice(&element_terms)
+    } else if let Some(uint8_array) = js_value.dyn_ref::<Uint8Array>() {
+        let mut bytes = vec![0; uint8_array.length() as usize];
+        uint8_array.copy_to(&mut bytes);
+
+        process.binary_from_bytes(&bytes)
+    } else {
+        to_term(process, js_value)
+    }
+}
+
+/// Converts `term` to the equivalent `JsValue`, the inverse of `js_value_to_term`:
+///
+/// * Atoms are symbols from `Symbol.for(name)`.
+/// * Integers are numbers.
+/// * Floats are numbers.
+/// * Binaries are strings if they are UTF-8 and `Uint8Array`s otherwise.
+/// * Lists and tuples are arrays of their converted elements.
+/// * Maps are objects with their converted keys as property keys, so atom keys are symbol
+///   properties and binary keys are string properties.
+/// * Pids are `[number, serial]` arrays.
+/// * Resources are the `JsValue` they hold.
+///
+/// Returns `badarg` if `term` is or contains a term that has no JavaScript equivalent, such as a
+/// closure, reference or improper list.
+pub fn term_to_js_value(term: Term) -> Result<JsValue, exception::Exception> {
+    match term.to_typed_term().unwrap() {
+        TypedTerm::Atom(atom) => Ok(atom_to_js_value(atom)),
+        TypedTerm::Boxed(boxed) => match boxed.to_typed_term().unwrap() {
+            TypedTerm::Float(float) => {
+                let f: f64 = float.into();
+
+                Ok(f.into())
+            }
+            TypedTerm::HeapBinary(heap_binary) => Ok(aligned_binary_to_js_value(heap_binary)),
+            TypedTerm::Map(_) => map_to_js_value(term),
+            TypedTerm::ProcBin(process_binary) => Ok(aligned_binary_to_js_value(process_binary)),
+            TypedTerm::ResourceReference(resource_reference) => {
+                resource_reference_to_js_value(resource_reference)
+            }
+            TypedTerm::Tuple(tuple) => tuple_to_js_value(&tuple),
+            _ => Err(badarg!().into()),
+        },
+        TypedTerm::List(cons) => {
+            let array = Array::new();
+
+            for result in cons.into_iter() {
+                match result {
+                    Ok(element_term) => {
+                        array.push(&term_to_js_value(element_term)?);
+                    }
+                    Err(_) => return Err(badarg!().into()),
+                }
+            }
+
+            Ok(array.into())
+        }
+        TypedTerm::Nil => Ok(Array::new().into()),
+        TypedTerm::Pid(pid) => Ok(pid_to_js_value(pid)),
+        TypedTerm::SmallInteger(small_integer) => Ok(small_integer_to_js_value(small_integer)),
+        _ => Err(badarg!().into()),
+    }
+}
+
+// Private
+
+/// `Number.MAX_SAFE_INTEGER`, above which not all whole numbers can be represented
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+fn aligned_binary_to_js_value<A: AlignedBinary>(aligned_binary: A) -> JsValue {
+    bytes_to_js_value(aligned_binary.as_bytes())
+}
+
+fn atom_to_js_value(atom: Atom) -> JsValue {
+    Symbol::for_(atom.name()).into()
+}
+
+fn bytes_to_js_value(bytes: &[u8]) -> JsValue {
+    match str::from_utf8(bytes) {
+        Ok(s) => s.into(),
+        // copied, so that the `JsValue` doesn't depend on the binary not being garbage collected
+        Err(_) => Uint8Array::from(bytes).into(),
+    }
+}
+
+fn map_to_js_value(map: Term) -> Result<JsValue, exception::Exception> {
+    let object = Object::new();
+
+    for (key, value) in MapIterator::new(map)? {
+        let key_js_value = term_to_js_value(key)?;
+        let value_js_value = term_to_js_value(value)?;
+
+        // can only fail for proxies and frozen objects, which a new `Object` is not
+        Reflect::set(&object, &key_js_value, &value_js_value).unwrap();
+    }
+
+    Ok(object.into())
+}
+
+fn number_to_term(process: &Process, number: f64) -> Result<Term, Alloc> {
+    if number.fract() == 0.0 && number.abs() <= MAX_SAFE_INTEGER {
+        process.integer(number as i64)
+    } else {
+        process.float(number)
+    }
+}
+
+fn pid_to_js_value(pid: Pid) -> JsValue {
+    let array = Array::new();
+
+    array.push(&(pid.number() as i32).into());
+    array.push(&(pid.serial() as i32).into());
+
+    array.into()
+}
+
+fn resource_reference_to_js_value(
+    resource_reference: resource::Reference,
+) -> Result<JsValue, exception::Exception> {
+    match from_resource_reference(&resource_reference) {
+        Some(js_value) => Ok(js_value),
+        None => Err(badarg!().into()),
+    }
+}
+
+fn small_integer_to_js_value(small_integer: SmallInteger) -> JsValue {
+    let i: isize = small_integer.into();
+
+    if (std::i32::MIN as isize) <= i && i <= (std::i32::MAX as isize) {
+        (i as i32).into()
+    } else {
+        (i as f64).into()
+    }
+}
+
+fn tuple_to_js_value(tuple: &Tuple) -> Result<JsValue, exception::Exception> {
+    let array = Array::new();
+
+    for element_term in tuple.iter() {
+        let element_js_value = term_to_js_value(element_term)?;
+        array.push(&element_js_value);
+    }
+
+    Ok(array.into())
+}
@@ -3,11 +3,15 @@
 pub mod document;
 pub mod element;
 pub mod event;
+pub mod event_target;
+pub mod fetch;
 pub mod html_form_element;
 pub mod html_input_element;
+pub mod js_value;
 pub mod math;
 pub mod node;
 pub mod wait;
+pub mod websocket;
 pub mod window;
 
 use std::any::Any;
@@ -29,11 +33,16 @@ use lumen_runtime::time::monotonic::{time_in_milliseconds, Milliseconds};
 
 use crate::window::add_event_listener;
 
-/// Starts the scheduler loop.  It yield and reschedule itself using
-/// [requestAnimationFrame](https://developer.mozilla.org/en-US/docs/Web/API/window/requestAnimationFrame).
+/// Starts the scheduler loop.  Processes run in slices of at most half a frame, so that the page
+/// stays responsive, and the loop yields to the browser between slices with
+/// [requestAnimationFrame](https://developer.mozilla.org/en-US/docs/Web/API/window/requestAnimationFrame)
+/// while the page is visible, or with
+/// [setTimeout](https://developer.mozilla.org/en-US/docs/Web/API/WindowOrWorkerGlobalScope/setTimeout)
+/// while it is hidden and animation frames are paused.  When no process is runnable, the next slice
+/// waits until the next timer times out, up to a frame, instead of running every frame.
 pub fn start() {
     add_event_listeners();
-    request_animation_frames();
+    run_slices();
 }
 
 // Private
@@ -41,6 +50,8 @@ pub fn start() {
 const MILLISECONDS_PER_SECOND: u64 = 1000;
 const FRAMES_PER_SECOND: u64 = 60;
 const MILLISECONDS_PER_FRAME: Milliseconds = MILLISECONDS_PER_SECOND / FRAMES_PER_SECOND;
+// the rest of the frame is left for the browser to handle events and render
+const MILLISECONDS_PER_SLICE: Milliseconds = MILLISECONDS_PER_FRAME / 2;
 
 fn add_event_listeners() {
     let window = web_sys::window().unwrap();
@@ -87,31 +98,74 @@ fn option_to_ok_tuple_or_error<T: 'static>(
     }
 }
 
-fn request_animation_frame(f: &Closure<dyn FnMut()>) {
-    web_sys::window()
-        .unwrap()
-        .request_animation_frame(f.as_ref().unchecked_ref())
-        .unwrap();
+fn page_is_hidden(window: &Window) -> bool {
+    window
+        .document()
+        .map(|document| document.hidden())
+        .unwrap_or(false)
 }
 
-fn request_animation_frames() {
+/// Runs `slice` once the scheduler should run again: on the next animation frame if a process ran
+/// in the last slice and the page is visible, or after `delay_milliseconds` otherwise.
+fn request_slice(slice: &Closure<dyn FnMut()>, delay_milliseconds: Milliseconds) {
+    let window = web_sys::window().unwrap();
+    let function = slice.as_ref().unchecked_ref();
+
+    if delay_milliseconds == 0 && !page_is_hidden(&window) {
+        window.request_animation_frame(function).unwrap();
+    } else {
+        window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                function,
+                delay_milliseconds as i32,
+            )
+            .unwrap();
+    }
+}
+
+fn run_slices() {
     // Based on https://github.com/rustwasm/wasm-bindgen/blob/603d5742eeca2a7a978f13614de9282229d1835e/examples/request-animation-frame/src/lib.rs
     let f = Rc::new(RefCell::new(None));
     let g = f.clone();
 
     *g.borrow_mut() = Some(Closure::wrap(Box::new(move || {
-        run_for_milliseconds(MILLISECONDS_PER_FRAME);
+        let delay_milliseconds = run_slice();
 
-        // Schedule ourselves for another requestAnimationFrame callback.
-        request_animation_frame(f.borrow().as_ref().unwrap());
+        // Schedule ourselves for the next slice.
+        request_slice(f.borrow().as_ref().unwrap(), delay_milliseconds);
     }) as Box<dyn FnMut()>));
 
-    request_animation_frame(g.borrow().as_ref().unwrap());
+    request_slice(g.borrow().as_ref().unwrap(), 0);
 }
 
-fn run_for_milliseconds(duration: Milliseconds) {
+/// Runs processes for up to `MILLISECONDS_PER_SLICE` and returns how long to wait before the next
+/// slice.
+fn run_slice() -> Milliseconds {
     let scheduler = Scheduler::current();
+
+    if run_for_milliseconds(&scheduler, MILLISECONDS_PER_SLICE) {
+        0
+    } else {
+        // processes can also be made runnable by events and calls from JavaScript, which don't
+        // wake the scheduler, so check again at least every frame
+        scheduler
+            .milliseconds_until_next_timeout()
+            .map_or(MILLISECONDS_PER_FRAME, |milliseconds| {
+                milliseconds.min(MILLISECONDS_PER_FRAME)
+            })
+    }
+}
+
+/// Returns `true` if processes ran for all of `duration`, so there may be more to run, or `false`
+/// if the scheduler ran out of processes to run first.
+fn run_for_milliseconds(scheduler: &Scheduler, duration: Milliseconds) -> bool {
     let timeout = time_in_milliseconds() + duration;
 
-    while (time_in_milliseconds() < timeout) && scheduler.run_once() {}
+    while time_in_milliseconds() < timeout {
+        if !scheduler.run_once() {
+            return false;
+        }
+    }
+
+    true
 }
@@ -0,0 +1,228 @@
+//! [WebSocket](https://developer.mozilla.org/en-US/docs/Web/API/WebSocket) connections, whose
+//! events are sent as messages to the process that connected, which owns the connection.
+//!
+//! The owner receives `{:websocket, websocket, event}`, where `websocket` is the handle returned by
+//! `:websocket.connect/1` and `event` is one of
+//!
+//! * `:open` once the connection is open, and sends can be made.
+//! * `{:text, binary}` for each text frame.
+//! * `{:binary, binary}` for each binary frame.
+//! * `:error` if the connection fails, which is followed by `{:closed, code, reason}`.
+//! * `{:closed, code, reason}` once the connection is closed, by either side.
+//!
+//! The connection is closed if the owner exits.
+pub mod close_1;
+pub mod connect_1;
+pub mod send_2;
+
+use std::mem;
+use std::ptr;
+
+use js_sys::{ArrayBuffer, Uint8Array};
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::convert::FromWasmAbi;
+use wasm_bindgen::{JsCast, JsValue};
+
+use web_sys::{BinaryType, CloseEvent, Event, MessageEvent, WebSocket};
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::{HeapAlloc, Process};
+use liblumen_alloc::erts::term::{atom_unchecked, resource, Atom, HeapBin, Pid, Term, Tuple};
+use liblumen_alloc::{CloneToProcess, HeapFragment};
+
+use lumen_runtime::process::send_heap_message_and_wake;
+use lumen_runtime::registry::pid_to_process;
+
+use crate::js_value;
+
+/// Connects to `url`, sending the events of the connection to the process with `owner`.  The
+/// returned `resource::Reference` is the handle that is in each message.
+pub fn connect(url: &str, owner: Pid) -> Result<resource::Reference, ConnectError> {
+    let web_socket = WebSocket::new(url).map_err(ConnectError::Url)?;
+    // frames are received as `ArrayBuffer`s instead of `Blob`s, so they can be read synchronously
+    web_socket.set_binary_type(BinaryType::Arraybuffer);
+
+    let handle =
+        resource::Reference::new(Box::new(web_socket.clone())).map_err(ConnectError::Alloc)?;
+
+    // The handlers live as long as the connection, so they are forgotten instead of being held
+    let on_open = listener(&web_socket, &handle, owner, |_: Event| {
+        Some(WebSocketEvent::Open)
+    });
+    web_socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+    on_open.forget();
+
+    let on_message = listener(
+        &web_socket,
+        &handle,
+        owner,
+        |message_event: MessageEvent| {
+            let data = message_event.data();
+
+            match data.as_string() {
+                Some(text) => Some(WebSocketEvent::Text(text)),
+                None => {
+                    let array_buffer: ArrayBuffer = data.dyn_into().ok()?;
+                    let uint8_array = Uint8Array::new(&array_buffer);
+                    let mut bytes = vec![0; uint8_array.length() as usize];
+                    uint8_array.copy_to(&mut bytes);
+
+                    Some(WebSocketEvent::Binary(bytes))
+                }
+            }
+        },
+    );
+    web_socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    on_message.forget();
+
+    let on_error = listener(&web_socket, &handle, owner, |_: Event| {
+        Some(WebSocketEvent::Error)
+    });
+    web_socket.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+    on_error.forget();
+
+    let on_close = listener(&web_socket, &handle, owner, |close_event: CloseEvent| {
+        Some(WebSocketEvent::Closed {
+            code: close_event.code(),
+            reason: close_event.reason(),
+        })
+    });
+    web_socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+    on_close.forget();
+
+    Ok(handle)
+}
+
+pub enum ConnectError {
+    Alloc(Alloc),
+    /// The URL isn't a `ws:` or `wss:` URL, or its port is blocked
+    Url(JsValue),
+}
+
+// Private
+
+enum WebSocketEvent {
+    Open,
+    Text(String),
+    Binary(Vec<u8>),
+    Error,
+    Closed { code: u16, reason: String },
+}
+
+fn bytes_need_in_words(bytes: &[u8]) -> usize {
+    let word_size = mem::size_of::<Term>();
+
+    (HeapBin::layout_bytes(bytes).size() + word_size - 1) / word_size
+}
+
+fn event_need_in_words(event: &WebSocketEvent) -> usize {
+    match event {
+        WebSocketEvent::Open | WebSocketEvent::Error => 0,
+        WebSocketEvent::Text(text) => {
+            Tuple::need_in_words_from_len(2) + bytes_need_in_words(text.as_bytes())
+        }
+        WebSocketEvent::Binary(bytes) => {
+            Tuple::need_in_words_from_len(2) + bytes_need_in_words(bytes)
+        }
+        WebSocketEvent::Closed { reason, .. } => {
+            Tuple::need_in_words_from_len(3) + bytes_need_in_words(reason.as_bytes())
+        }
+    }
+}
+
+fn event_to_term<H: HeapAlloc>(heap: &mut H, event: WebSocketEvent) -> Result<Term, Alloc> {
+    match event {
+        WebSocketEvent::Open => Ok(atom_unchecked("open")),
+        WebSocketEvent::Text(text) => {
+            let text_term = heap.heapbin_from_bytes(text.as_bytes())?;
+
+            heap.tuple_from_slice(&[atom_unchecked("text"), text_term])
+        }
+        WebSocketEvent::Binary(bytes) => {
+            let bytes_term = heap.heapbin_from_bytes(&bytes)?;
+
+            heap.tuple_from_slice(&[atom_unchecked("binary"), bytes_term])
+        }
+        WebSocketEvent::Error => Ok(atom_unchecked("error")),
+        WebSocketEvent::Closed { code, reason } => {
+            let code_term = heap.integer(code as usize)?;
+            let reason_term = heap.heapbin_from_bytes(reason.as_bytes())?;
+
+            heap.tuple_from_slice(&[atom_unchecked("closed"), code_term, reason_term])
+        }
+    }
+}
+
+/// A handler that sends the `WebSocketEvent` that `event_to_web_socket_event` returns to `owner`,
+/// or closes `web_socket` if `owner` has exited
+fn listener<E, F>(
+    web_socket: &WebSocket,
+    handle: &resource::Reference,
+    owner: Pid,
+    event_to_web_socket_event: F,
+) -> Closure<dyn FnMut(E)>
+where
+    E: FromWasmAbi + 'static,
+    F: Fn(E) -> Option<WebSocketEvent> + 'static,
+{
+    let web_socket = web_socket.clone();
+    let handle = handle.clone();
+
+    let listener_box: Box<dyn FnMut(E)> = Box::new(move |event: E| match pid_to_process(&owner) {
+        Some(arc_process) => {
+            if let Some(web_socket_event) = event_to_web_socket_event(event) {
+                // the event is dropped if there isn't memory for it, as a message would be
+                let _ = send_event(&arc_process, &handle, web_socket_event);
+            }
+        }
+        None => {
+            // there is no one to receive the events anymore
+            let _ = web_socket.close();
+        }
+    });
+
+    Closure::wrap(listener_box)
+}
+
+fn module() -> Atom {
+    Atom::try_from_str("websocket").unwrap()
+}
+
+fn send_event(
+    process: &Process,
+    handle: &resource::Reference,
+    event: WebSocketEvent,
+) -> Result<(), Alloc> {
+    let need_in_words =
+        Tuple::need_in_words_from_len(3) + handle.size_in_words() + event_need_in_words(&event);
+    let mut non_null_heap_fragment = unsafe { HeapFragment::new_from_word_size(need_in_words)? };
+    let heap_fragment = unsafe { non_null_heap_fragment.as_mut() };
+
+    let message_result = handle.clone_to_heap(heap_fragment).and_then(|handle_term| {
+        let event_term = event_to_term(heap_fragment, event)?;
+
+        heap_fragment.tuple_from_slice(&[atom_unchecked("websocket"), handle_term, event_term])
+    });
+
+    match message_result {
+        Ok(message) => {
+            send_heap_message_and_wake(process, non_null_heap_fragment, message);
+
+            Ok(())
+        }
+        Err(alloc) => {
+            unsafe { ptr::drop_in_place(non_null_heap_fragment.as_ptr()) };
+
+            Err(alloc)
+        }
+    }
+}
+
+fn web_socket_from_term(term: Term) -> Result<WebSocket, exception::Exception> {
+    js_value::from_term(term)?
+        .dyn_into()
+        .map_err(|_| badarg!().into())
+}
@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::code::{self, result_from_exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Term};
+use liblumen_alloc::erts::ModuleFunctionArity;
+
+use crate::websocket::web_socket_from_term;
+
+/// Closes the connection.  The owner receives `{:websocket, websocket, {:closed, code, reason}}`
+/// once it is closed.
+///
+/// ```elixir
+/// :ok = :websocket.close(websocket)
+/// ```
+pub fn place_frame_with_arguments(
+    process: &Process,
+    placement: Placement,
+    websocket: Term,
+) -> Result<(), Alloc> {
+    process.stack_push(websocket)?;
+    process.place_frame(frame(), placement);
+
+    Ok(())
+}
+
+// Private
+
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.reduce();
+
+    let websocket = arc_process.stack_pop().unwrap();
+
+    match native(websocket) {
+        Ok(ok) => {
+            arc_process.return_from_call(ok)?;
+
+            Process::call_code(arc_process)
+        }
+        Err(exception) => result_from_exception(arc_process, exception),
+    }
+}
+
+fn frame() -> Frame {
+    Frame::new(module_function_arity(), code)
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("close").unwrap()
+}
+
+fn module_function_arity() -> Arc<ModuleFunctionArity> {
+    Arc::new(ModuleFunctionArity {
+        module: super::module(),
+        function: function(),
+        arity: 1,
+    })
+}
+
+pub fn native(websocket: Term) -> exception::Result {
+    let web_socket = web_socket_from_term(websocket)?;
+
+    // closing a connection that is already closing or closed does nothing
+    let _ = web_socket.close();
+
+    Ok(atom_unchecked("ok"))
+}
@@ -0,0 +1,79 @@
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use liblumen_alloc::badarg;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::code::{self, result_from_exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{Atom, Term};
+use liblumen_alloc::erts::ModuleFunctionArity;
+use liblumen_alloc::CloneToProcess;
+
+use crate::websocket::{connect, ConnectError};
+
+/// Connects to `url`, with the calling process as the owner of the connection.
+///
+/// ```elixir
+/// websocket = :websocket.connect("wss://example.com/socket")
+///
+/// receive do
+///   {:websocket, ^websocket, :open} -> :websocket.send(websocket, "hello")
+/// end
+/// ```
+pub fn place_frame_with_arguments(
+    process: &Process,
+    placement: Placement,
+    url: Term,
+) -> Result<(), Alloc> {
+    process.stack_push(url)?;
+    process.place_frame(frame(), placement);
+
+    Ok(())
+}
+
+// Private
+
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.reduce();
+
+    let url = arc_process.stack_pop().unwrap();
+
+    match native(arc_process, url) {
+        Ok(websocket) => {
+            arc_process.return_from_call(websocket)?;
+
+            Process::call_code(arc_process)
+        }
+        Err(exception) => result_from_exception(arc_process, exception),
+    }
+}
+
+fn frame() -> Frame {
+    Frame::new(module_function_arity(), code)
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("connect").unwrap()
+}
+
+fn module_function_arity() -> Arc<ModuleFunctionArity> {
+    Arc::new(ModuleFunctionArity {
+        module: super::module(),
+        function: function(),
+        arity: 1,
+    })
+}
+
+pub fn native(process: &Process, url: Term) -> exception::Result {
+    let url_string: String = url.try_into()?;
+
+    match connect(&url_string, process.pid()) {
+        Ok(handle) => handle
+            .clone_to_heap(&mut *process.acquire_heap())
+            .map_err(|alloc| alloc.into()),
+        Err(ConnectError::Alloc(alloc)) => Err(alloc.into()),
+        Err(ConnectError::Url(_)) => Err(badarg!().into()),
+    }
+}
@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use js_sys::Uint8Array;
+
+use web_sys::WebSocket;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::exception::system::Alloc;
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::code::{self, result_from_exception};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::{atom_unchecked, Atom, Term};
+use liblumen_alloc::erts::ModuleFunctionArity;
+
+use lumen_runtime::otp::erlang::iodata_to_bytes;
+
+use crate::error;
+use crate::websocket::web_socket_from_term;
+
+/// Sends `iodata` as a binary frame.
+///
+/// ```elixir
+/// case :websocket.send(websocket, ["hello", ?\s, "world"]) do
+///   :ok -> ...
+///   {:error, :not_open} -> ...
+/// end
+/// ```
+pub fn place_frame_with_arguments(
+    process: &Process,
+    placement: Placement,
+    websocket: Term,
+    iodata: Term,
+) -> Result<(), Alloc> {
+    process.stack_push(iodata)?;
+    process.stack_push(websocket)?;
+    process.place_frame(frame(), placement);
+
+    Ok(())
+}
+
+// Private
+
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.reduce();
+
+    let websocket = arc_process.stack_pop().unwrap();
+    let iodata = arc_process.stack_pop().unwrap();
+
+    match native(arc_process, websocket, iodata) {
+        Ok(ok_or_error) => {
+            arc_process.return_from_call(ok_or_error)?;
+
+            Process::call_code(arc_process)
+        }
+        Err(exception) => result_from_exception(arc_process, exception),
+    }
+}
+
+fn frame() -> Frame {
+    Frame::new(module_function_arity(), code)
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("send").unwrap()
+}
+
+fn module_function_arity() -> Arc<ModuleFunctionArity> {
+    Arc::new(ModuleFunctionArity {
+        module: super::module(),
+        function: function(),
+        arity: 2,
+    })
+}
+
+pub fn native(process: &Process, websocket: Term, iodata: Term) -> exception::Result {
+    let web_socket = web_socket_from_term(websocket)?;
+    let bytes = iodata_to_bytes(iodata)?;
+
+    // sending while connecting throws, while sending while closing silently drops the frame
+    if web_socket.ready_state() == WebSocket::OPEN {
+        let uint8_array = Uint8Array::from(&bytes[..]);
+
+        if web_socket.send_with_array_buffer_view(&uint8_array).is_ok() {
+            return Ok(atom_unchecked("ok"));
+        }
+    }
+
+    process
+        .tuple_from_slice(&[error(), atom_unchecked("not_open")])
+        .map_err(|alloc| alloc.into())
+}
@@ -7,6 +7,8 @@
 mod document;
 #[path = "./web/element.rs"]
 mod element;
+#[path = "./web/js_value.rs"]
+mod js_value;
 #[path = "./web/math.rs"]
 mod math;
 #[path = "./web/node.rs"]
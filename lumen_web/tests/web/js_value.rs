@@ -0,0 +1,93 @@
+use super::*;
+
+#[wasm_bindgen_test(async)]
+fn to_term_resolves_to_same_js_value() -> impl Future<Item = (), Error = JsValue> {
+    start_once();
+
+    let options: Options = Default::default();
+    let object: JsValue = js_sys::Object::new().into();
+    let held_object = object.clone();
+
+    let promise = wait::with_return_0::spawn(options, move |child_process| {
+        let object_term = lumen_web::js_value::to_term(child_process, held_object.clone())?;
+
+        // returned to `with_return/0` directly
+        child_process.stack_push(object_term)
+    })
+    .unwrap();
+
+    JsFuture::from(promise)
+        .map(move |resolved| {
+            assert_eq!(resolved, object);
+        })
+        .map_err(|_| unreachable!())
+}
+
+#[wasm_bindgen_test(async)]
+fn js_value_to_term_converts_back_with_term_to_js_value() -> impl Future<Item = (), Error = JsValue>
+{
+    start_once();
+
+    let options: Options = Default::default();
+
+    let promise = wait::with_return_0::spawn(options, |child_process| {
+        let string_term = lumen_web::js_value::js_value_to_term(child_process, "converted".into())?;
+
+        // returned to `with_return/0` directly
+        child_process.stack_push(string_term)
+    })
+    .unwrap();
+
+    JsFuture::from(promise)
+        .map(|resolved| {
+            assert_eq!(resolved, JsValue::from("converted"));
+        })
+        .map_err(|_| unreachable!())
+}
+
+#[wasm_bindgen_test(async)]
+fn term_to_js_value_converts_map_to_object() -> impl Future<Item = (), Error = JsValue> {
+    start_once();
+
+    let options: Options = Default::default();
+
+    let promise = wait::with_return_0::spawn(options, |child_process| {
+        let key_term = child_process.binary_from_str("key")?;
+        let value_term = child_process.integer(1)?;
+        let map_term = child_process.map_from_slice(&[(key_term, value_term)])?;
+
+        // returned to `with_return/0` directly
+        child_process.stack_push(map_term)
+    })
+    .unwrap();
+
+    JsFuture::from(promise)
+        .map(|resolved| {
+            let value = js_sys::Reflect::get(&resolved, &"key".into()).unwrap();
+
+            assert_eq!(value, JsValue::from(1));
+        })
+        .map_err(|_| unreachable!())
+}
+
+#[wasm_bindgen_test(async)]
+fn term_to_js_value_rejects_improper_list() -> impl Future<Item = (), Error = JsValue> {
+    start_once();
+
+    let options: Options = Default::default();
+
+    let promise = wait::with_return_0::spawn(options, |child_process| {
+        let improper_list_term =
+            child_process.cons(child_process.integer(0)?, child_process.integer(1)?)?;
+
+        // returned to `with_return/0` directly
+        child_process.stack_push(improper_list_term)
+    })
+    .unwrap();
+
+    JsFuture::from(promise).then(|result| {
+        assert!(result.is_err());
+
+        Ok(())
+    })
+}